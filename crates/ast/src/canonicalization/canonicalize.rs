@@ -187,6 +187,10 @@ fn canonicalize_field<'a>(
             canonicalize_field(env, scope, sub_field)
         }
 
+        Nested(_, _) => {
+            panic!("TODO canonicalize nested record update field");
+        }
+
         Malformed(_string) => {
             panic!("TODO canonicalize malformed record field");
         }
@@ -3,7 +3,7 @@ use bumpalo::{collections::Vec as BumpVec, Bump};
 use roc_can::expected::{Expected, PExpected};
 use roc_collections::all::{BumpMap, BumpMapDefault, HumanIndex, SendMap};
 use roc_module::{
-    ident::{Lowercase, TagName},
+    ident::{IndexOrField, Lowercase, TagName},
     symbol::Symbol,
 };
 use roc_region::all::Region;
@@ -321,6 +321,7 @@ pub fn constrain_expr<'a>(
                 let reason = Reason::FnArg {
                     name: opt_symbol,
                     arg_index: HumanIndex::zero_based(index),
+                    called_via: *called_via,
                 };
 
                 let expected_arg = Expected::ForReason(reason, arg_type.shallow_clone(), region);
@@ -375,7 +376,7 @@ pub fn constrain_expr<'a>(
                 env.pool.add(ext_type),
             );
 
-            let category = Category::Accessor(field.as_str(env.pool).into());
+            let category = Category::Accessor(IndexOrField::Field(field.as_str(env.pool).into()));
 
             let record_expected = Expected::NoExpectation(record_type.shallow_clone());
             let record_con = Eq(
@@ -140,6 +140,7 @@ pub enum AstDef<'a> {
 fn to_pending_def<'a>(
     env: &mut Env<'a>,
     def: &'a AstDef<'a>,
+    region: Region,
     scope: &mut Scope,
     pattern_type: PatternType,
 ) -> Option<(Output, PendingDef<'a>)> {
@@ -280,9 +281,16 @@ fn to_pending_def<'a>(
 
         Value(AstValueDef::Expect { .. }) => todo!(),
         Value(AstValueDef::ExpectFx { .. }) => todo!(),
+        Value(AstValueDef::Malformed(_)) => {
+            // The parser has already reported the syntax error; remember where the def was,
+            // and skip it like the compiler's canonicalization does.
+            env.problem(Problem::RuntimeError(RuntimeError::MalformedExpr(region)));
+
+            None
+        }
 
         SpaceBefore(sub_def, _) | SpaceAfter(sub_def, _) => {
-            to_pending_def(env, sub_def, scope, pattern_type)
+            to_pending_def(env, sub_def, region, scope, pattern_type)
         }
 
         NotYetImplemented(s) => todo!("{}", s),
@@ -842,13 +850,13 @@ pub fn canonicalize_defs<'a>(
     // Canonicalize all the patterns, record shadowing problems, and store
     // the ast::Expr values in pending_exprs for further canonicalization
     // once we've finished assembling the entire scope.
-    for loc_def in loc_defs.defs() {
+    for (loc_def, region) in loc_defs.defs().zip(loc_defs.regions.iter()) {
         let def = match loc_def {
             Ok(type_def) => AstDef::Type(type_def.clone()),
             Err(value_def) => AstDef::Value(value_def.clone()),
         };
 
-        match to_pending_def(env, env.arena.alloc(def), &mut scope, pattern_type) {
+        match to_pending_def(env, env.arena.alloc(def), *region, &mut scope, pattern_type) {
             None => (),
             Some((new_output, pending_def)) => {
                 // store the top-level defs, used to ensure that closures won't capture them
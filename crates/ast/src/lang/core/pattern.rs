@@ -12,6 +12,7 @@ use roc_error_macros::internal_error;
 use roc_module::symbol::{Interns, Symbol};
 use roc_parse::ast::{StrLiteral, StrSegment};
 use roc_parse::pattern::PatternType;
+use roc_parse::string_literal::block_string_segments;
 use roc_problem::can::{MalformedPatternProblem, Problem, RuntimeError, ShadowKind};
 use roc_region::all::Region;
 use roc_types::subs::Variable;
@@ -440,6 +441,8 @@ pub fn to_pattern2<'a>(
             })
         }
 
+        Tuple(_) | List(_) | ListRest(_) => Pattern2::UnsupportedPattern(region),
+
         RequiredField(_name, _loc_pattern) => {
             unreachable!("should have been handled in RecordDestructure");
         }
@@ -594,7 +597,7 @@ pub(crate) fn flatten_str_literal(pool: &mut Pool, literal: &StrLiteral<'_>) ->
     match literal {
         PlainLine(str_slice) => Pattern2::StrLiteral(PoolStr::new(str_slice, pool)),
         Line(segments) => flatten_str_lines(pool, &[segments]),
-        Block(lines) => flatten_str_lines(pool, lines),
+        Block(lines) => flatten_str_lines(pool, &[&block_string_segments(lines)]),
     }
 }
 
@@ -1,6 +1,7 @@
 use roc_error_macros::internal_error;
 use roc_module::{called_via::CalledVia, symbol::Symbol};
 use roc_parse::ast::StrLiteral;
+use roc_parse::string_literal::block_string_segments;
 
 use crate::{
     ast_error::{ASTResult, UnexpectedASTNodeSnafu},
@@ -35,7 +36,11 @@ pub(crate) fn flatten_str_literal<'a>(
             (expr, Output::default())
         }
         Line(segments) => flatten_str_lines(env, scope, &[segments]),
-        Block(lines) => flatten_str_lines(env, scope, lines),
+        Block(lines) => {
+            let segments = block_string_segments(lines);
+
+            flatten_str_lines(env, scope, &[&segments])
+        }
     }
 }
 
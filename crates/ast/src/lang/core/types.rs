@@ -442,6 +442,12 @@ pub fn to_type2<'a>(
 
             Type2::Record(field_types, ext_type)
         }
+        Tuple { .. } => {
+            // Type2 has no tuples yet, so let the type of a tuple annotation be inferred
+            let var = env.var_store.fresh();
+
+            Type2::Variable(var)
+        }
         TagUnion { tags, ext, .. } => {
             let tag_types_vec = can_tags(env, scope, references, tags.items, region);
 
@@ -650,6 +656,9 @@ fn can_assigned_fields<'a>(
                     field = nested;
                     continue 'inner;
                 }
+                Nested(_, _) => {
+                    unreachable!("only record updates have nested fields")
+                }
                 Malformed(_) => {
                     // TODO report this?
                     // completely skip this element, advance to the next tag
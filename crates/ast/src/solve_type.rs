@@ -10,7 +10,7 @@ use roc_region::all::{Loc, Region};
 use roc_solve::module::Solved;
 use roc_types::subs::{
     self, AliasVariables, Content, Descriptor, FlatType, Mark, OptVariable, Rank, RecordFields,
-    Subs, SubsSlice, UnionLambdas, UnionTags, Variable, VariableSubsSlice,
+    Subs, SubsSlice, TupleElems, UnionLambdas, UnionTags, Variable, VariableSubsSlice,
 };
 use roc_types::types::{
     gather_fields_unsorted_iter, Alias, AliasKind, Category, ErrorType, PatternCategory,
@@ -1371,6 +1371,8 @@ fn adjust_rank_content(
 
                 EmptyTagUnion => Rank::toplevel(),
 
+                EmptyTuple => group_rank,
+
                 Record(fields, ext_var) => {
                     let mut rank = adjust_rank(subs, young_mark, visit_mark, group_rank, *ext_var);
 
@@ -1382,6 +1384,17 @@ fn adjust_rank_content(
                     rank
                 }
 
+                Tuple(elems, ext_var) => {
+                    let mut rank = adjust_rank(subs, young_mark, visit_mark, group_rank, *ext_var);
+
+                    for index in elems.iter_variables() {
+                        let var = subs[index];
+                        rank = rank.max(adjust_rank(subs, young_mark, visit_mark, group_rank, var));
+                    }
+
+                    rank
+                }
+
                 TagUnion(tags, ext_var) => {
                     let mut rank = adjust_rank(subs, young_mark, visit_mark, group_rank, *ext_var);
 
@@ -1562,7 +1575,7 @@ fn instantiate_rigids_help(
                     }
                 }
 
-                EmptyRecord | EmptyTagUnion | Erroneous(_) => {}
+                EmptyRecord | EmptyTuple | EmptyTagUnion | Erroneous(_) => {}
 
                 Record(fields, ext_var) => {
                     for index in fields.iter_variables() {
@@ -1573,6 +1586,15 @@ fn instantiate_rigids_help(
                     instantiate_rigids_help(subs, max_rank, pools, ext_var);
                 }
 
+                Tuple(elems, ext_var) => {
+                    for index in elems.iter_variables() {
+                        let var = subs[index];
+                        instantiate_rigids_help(subs, max_rank, pools, var);
+                    }
+
+                    instantiate_rigids_help(subs, max_rank, pools, ext_var);
+                }
+
                 TagUnion(tags, ext_var) => {
                     for (_, index) in tags.iter_all() {
                         let slice = subs[index];
@@ -1742,7 +1764,10 @@ fn deep_copy_var_help(
                     Func(arg_vars, new_closure_var, new_ret_var)
                 }
 
-                same @ EmptyRecord | same @ EmptyTagUnion | same @ Erroneous(_) => same,
+                same @ EmptyRecord
+                | same @ EmptyTuple
+                | same @ EmptyTagUnion
+                | same @ Erroneous(_) => same,
 
                 Record(fields, ext_var) => {
                     let record_fields = {
@@ -1785,6 +1810,22 @@ fn deep_copy_var_help(
                     )
                 }
 
+                Tuple(elems, ext_var) => {
+                    let mut new_elems = Vec::with_capacity(elems.len());
+
+                    for (i1, i2) in elems.iter_all() {
+                        let var = subs[i2];
+                        let copy_var = deep_copy_var_help(subs, max_rank, pools, var);
+
+                        new_elems.push((subs[i1], copy_var));
+                    }
+
+                    Tuple(
+                        TupleElems::insert_into_subs(subs, new_elems),
+                        deep_copy_var_help(subs, max_rank, pools, ext_var),
+                    )
+                }
+
                 TagUnion(tags, ext_var) => {
                     let mut new_variable_slices = Vec::with_capacity(tags.len());
 
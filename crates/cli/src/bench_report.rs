@@ -0,0 +1,243 @@
+//! The report of `roc bench`: the mean, median and standard deviation of each benchmark, and how
+//! its mean compares to a baseline that an earlier run saved as JSON.
+
+use roc_load::MonomorphizedModule;
+use roc_module::symbol::Symbol;
+use roc_repl_expect::bench::BenchSamples;
+use roc_repl_expect::run::expect_module_name;
+use serde_json::json;
+use std::io::{self, Write};
+
+/// A change of the mean smaller than this is reported as no change, even if it is significant.
+const MIN_CHANGE: f64 = 0.02;
+
+/// How large Welch's t statistic must be for a change of the mean to be significant. Benchmarks
+/// take enough samples that this is about a 95% confidence level.
+const MIN_T: f64 = 2.0;
+
+/// The name `roc bench` shows for a benchmark: its module name followed by its own name,
+/// e.g. `Parser.benchParseInt`.
+pub fn bench_name(loaded: &MonomorphizedModule, bench: Symbol) -> String {
+    let module_id = bench.module_id();
+    let (path, _) = &loaded.sources[&module_id];
+
+    format!(
+        "{}.{}",
+        expect_module_name(&loaded.interns, path, module_id),
+        bench.as_str(&loaded.interns)
+    )
+}
+
+/// The statistics of the time one run of a benchmark takes, in nanoseconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchStats {
+    pub name: String,
+    pub mean: f64,
+    pub median: f64,
+    pub stddev: f64,
+    pub samples: usize,
+    pub iterations_per_sample: u64,
+}
+
+impl BenchStats {
+    pub fn of(name: String, samples: &BenchSamples) -> Self {
+        let times = &samples.nanos_per_iteration;
+        let n = times.len();
+
+        let mean = times.iter().sum::<f64>() / n.max(1) as f64;
+
+        let mut sorted = times.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        let median = match n {
+            0 => 0.0,
+            _ if n % 2 == 0 => (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0,
+            _ => sorted[n / 2],
+        };
+
+        // the sample standard deviation, since the samples are all we know of the benchmark
+        let stddev = if n > 1 {
+            let squares: f64 = times.iter().map(|time| (time - mean).powi(2)).sum();
+
+            (squares / (n - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+
+        BenchStats {
+            name,
+            mean,
+            median,
+            stddev,
+            samples: n,
+            iterations_per_sample: samples.iterations_per_sample,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    Improved,
+    Regressed,
+    NoChange,
+}
+
+/// How the mean of a benchmark changed since the baseline, as a fraction of the baseline's mean.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Comparison {
+    pub change: f64,
+    pub verdict: Verdict,
+}
+
+/// Compare with Welch's t-test, which does not assume that both runs vary as much.
+pub fn compare(current: &BenchStats, baseline: &BenchStats) -> Comparison {
+    let change = if baseline.mean > 0.0 {
+        (current.mean - baseline.mean) / baseline.mean
+    } else {
+        0.0
+    };
+
+    let variance_of_means = current.stddev.powi(2) / current.samples.max(1) as f64
+        + baseline.stddev.powi(2) / baseline.samples.max(1) as f64;
+
+    let significant = if variance_of_means > 0.0 {
+        ((current.mean - baseline.mean) / variance_of_means.sqrt()).abs() > MIN_T
+    } else {
+        current.mean != baseline.mean
+    };
+
+    let verdict = if !significant || change.abs() < MIN_CHANGE {
+        Verdict::NoChange
+    } else if change < 0.0 {
+        Verdict::Improved
+    } else {
+        Verdict::Regressed
+    };
+
+    Comparison { change, verdict }
+}
+
+/// A line per benchmark, followed by how it compares to the baseline if it is in there.
+///
+/// ```text
+/// Main.benchSort    mean 1.21 µs    median 1.20 µs    stddev 35.2 ns    (50 × 2048 runs)
+///     +4.1% compared to the baseline: regressed
+/// ```
+pub fn write_human(
+    writer: &mut impl Write,
+    stats: &BenchStats,
+    width: usize,
+    baseline: Option<&BenchStats>,
+) -> io::Result<()> {
+    writeln!(
+        writer,
+        "{:width$}    mean {}    median {}    stddev {}    ({} × {} runs)",
+        stats.name,
+        format_nanos(stats.mean),
+        format_nanos(stats.median),
+        format_nanos(stats.stddev),
+        stats.samples,
+        stats.iterations_per_sample,
+        width = width,
+    )?;
+
+    if let Some(baseline) = baseline {
+        let comparison = compare(stats, baseline);
+
+        let (color, verdict) = match comparison.verdict {
+            Verdict::Improved => (32, "improved"),   // green
+            Verdict::Regressed => (31, "regressed"), // red
+            Verdict::NoChange => (39, "no change"),  // default
+        };
+
+        writeln!(
+            writer,
+            "    {:+.1}% compared to the baseline: \x1B[{}m{}\x1B[39m",
+            comparison.change * 100.0,
+            color,
+            verdict
+        )?;
+    }
+
+    Ok(())
+}
+
+/// A baseline that later runs can compare with, like
+///
+/// ```json
+/// { "benchmarks": [ { "name": "Main.benchSort", "mean_ns": 1210.5, ... } ] }
+/// ```
+pub fn write_baseline(writer: &mut impl Write, all_stats: &[BenchStats]) -> io::Result<()> {
+    let benchmarks: Vec<_> = all_stats
+        .iter()
+        .map(|stats| {
+            json!({
+                "name": stats.name,
+                "mean_ns": stats.mean,
+                "median_ns": stats.median,
+                "stddev_ns": stats.stddev,
+                "samples": stats.samples,
+                "iterations_per_sample": stats.iterations_per_sample,
+            })
+        })
+        .collect();
+
+    serde_json::to_writer_pretty(&mut *writer, &json!({ "benchmarks": benchmarks }))?;
+    writeln!(writer)
+}
+
+/// The benchmarks of a baseline written by [`write_baseline`].
+pub fn parse_baseline(json: &str) -> Result<Vec<BenchStats>, String> {
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|error| error.to_string())?;
+
+    let benchmarks = value["benchmarks"]
+        .as_array()
+        .ok_or("it has no list of `benchmarks`")?;
+
+    benchmarks
+        .iter()
+        .map(|benchmark| {
+            let name = benchmark["name"]
+                .as_str()
+                .ok_or("a benchmark has no `name`")?;
+
+            let number = |field: &str| {
+                benchmark[field]
+                    .as_f64()
+                    .ok_or_else(|| format!("the benchmark `{}` has no `{}`", name, field))
+            };
+
+            Ok(BenchStats {
+                name: name.to_string(),
+                mean: number("mean_ns")?,
+                median: number("median_ns")?,
+                stddev: number("stddev_ns")?,
+                samples: number("samples")? as usize,
+                iterations_per_sample: number("iterations_per_sample")? as u64,
+            })
+        })
+        .collect()
+}
+
+/// A time in the largest unit that keeps it at least 1, with 3 significant digits.
+fn format_nanos(nanos: f64) -> String {
+    let (value, unit) = if nanos >= 1e9 {
+        (nanos / 1e9, "s")
+    } else if nanos >= 1e6 {
+        (nanos / 1e6, "ms")
+    } else if nanos >= 1e3 {
+        (nanos / 1e3, "µs")
+    } else {
+        (nanos, "ns")
+    };
+
+    let decimals = if value >= 100.0 {
+        0
+    } else if value >= 10.0 {
+        1
+    } else {
+        2
+    };
+
+    format!("{:.*} {}", decimals, value, unit)
+}
@@ -1,13 +1,14 @@
 use bumpalo::Bump;
 use roc_build::{
+    emit::Emit,
     link::{link, preprocess_host_wasm32, rebuild_host, LinkType, LinkingStrategy},
     program::{self, Problems},
 };
 use roc_builtins::bitcode;
 use roc_collections::VecMap;
 use roc_load::{
-    EntryPoint, ExecutionMode, Expectations, LoadConfig, LoadMonomorphizedError, LoadedModule,
-    LoadingProblem, Threading,
+    EmitKind, EntryPoint, ExecutionMode, Expectations, LoadConfig, LoadMonomorphizedError,
+    LoadedModule, LoadingProblem, Threading,
 };
 use roc_module::symbol::{Interns, ModuleId};
 use roc_mono::ir::OptLevel;
@@ -69,6 +70,9 @@ pub fn build_file<'a>(
     threading: Threading,
     wasm_dev_stack_bytes: Option<u32>,
     order: BuildOrdering,
+    runs_under_roc_dev: bool,
+    render: RenderTarget,
+    emit_kinds: Vec<EmitKind>,
 ) -> Result<BuiltFile, BuildFileError<'a>> {
     let compilation_start = Instant::now();
     let target_info = TargetInfo::from(target);
@@ -83,10 +87,11 @@ pub fn build_file<'a>(
 
     let load_config = LoadConfig {
         target_info,
-        // TODO: expose this from CLI?
-        render: RenderTarget::ColorTerminal,
+        render,
         threading,
         exec_mode,
+        emit: emit_kinds.clone(),
+        ..Default::default()
     };
     let load_result = roc_load::load_and_monomorphize(
         arena,
@@ -139,6 +144,26 @@ pub fn build_file<'a>(
         binary_path.set_extension(extension);
     }
 
+    let emit = Emit {
+        kinds: emit_kinds,
+        output_path: binary_path.clone(),
+    };
+
+    if matches!(opt_level, OptLevel::Development) {
+        for kind in [EmitKind::LlvmIr, EmitKind::LlvmBc] {
+            if emit.contains(kind) {
+                eprintln!(
+                    "Skipping --emit={}, because the dev backend does not use LLVM.",
+                    kind.name()
+                );
+            }
+        }
+
+        if emit_wasm && emit.contains(EmitKind::Asm) {
+            eprintln!("Skipping --emit=asm, because the wasm dev backend emits no assembly.");
+        }
+    }
+
     let host_input_path = if let EntryPoint::Executable { platform_path, .. } = &loaded.entry_point
     {
         cwd.join(platform_path)
@@ -246,10 +271,12 @@ pub fn build_file<'a>(
     // This only needs to be mutable for report_problems. This can't be done
     // inside a nested scope without causing a borrow error!
     let mut loaded = loaded;
-    let problems = program::report_problems_monomorphized(&mut loaded);
+    let problems = program::report_problems_monomorphized(&mut loaded, render);
     let expectations = std::mem::take(&mut loaded.expectations);
     let loaded = loaded;
 
+    emit.write_ir(&loaded.emitted_ir, &loaded.interns);
+
     let interns = loaded.interns.clone();
 
     enum HostRebuildTiming {
@@ -280,6 +307,8 @@ pub fn build_file<'a>(
         emit_debug_info,
         &preprocessed_host_path,
         wasm_dev_stack_bytes,
+        runs_under_roc_dev,
+        &emit,
     );
 
     buf.push('\n');
@@ -376,6 +405,14 @@ pub fn build_file<'a>(
 
     let linking_time = link_start.elapsed();
 
+    if emit.contains(EmitKind::Wat) {
+        if emit_wasm && binary_path.extension() == Some("wasm".as_ref()) {
+            emit.write_wat(&binary_path);
+        } else {
+            eprintln!("Skipping --emit=wat, because no WebAssembly binary was built.");
+        }
+    }
+
     if emit_timings {
         println!("Finished linking in {} ms\n", linking_time.as_millis());
     }
@@ -462,6 +499,8 @@ pub fn check_file(
     roc_file_path: PathBuf,
     emit_timings: bool,
     threading: Threading,
+    render: RenderTarget,
+    cache_dir: Option<PathBuf>,
 ) -> Result<(program::Problems, Duration), LoadingProblem> {
     let compilation_start = Instant::now();
 
@@ -474,10 +513,11 @@ pub fn check_file(
 
     let load_config = LoadConfig {
         target_info,
-        // TODO: expose this from CLI?
-        render: RenderTarget::ColorTerminal,
+        render,
         threading,
         exec_mode: ExecutionMode::Check,
+        cache_dir,
+        ..Default::default()
     };
     let mut loaded =
         roc_load::load_and_typecheck(arena, roc_file_path, subs_by_module, load_config)?;
@@ -497,6 +537,12 @@ pub fn check_file(
             buf.push_str(module_name);
         }
 
+        if loaded.cached_modules.contains(module_id) {
+            buf.push_str(" (cached)");
+        } else if loaded.cached_declarations.contains(module_id) {
+            buf.push_str(" (declarations cached)");
+        }
+
         buf.push('\n');
 
         report_timing(buf, "Read .roc file from disk", module_timing.read_roc_file);
@@ -526,7 +572,7 @@ pub fn check_file(
     }
 
     Ok((
-        program::report_problems_typechecked(&mut loaded),
+        program::report_problems_typechecked(&mut loaded, render),
         compilation_end,
     ))
 }
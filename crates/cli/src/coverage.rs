@@ -0,0 +1,289 @@
+//! The reports of `roc test --coverage`: an lcov file that CI services and editors can read, and
+//! a summary per module in the terminal.
+
+use roc_can::coverage::CoveragePoint;
+use roc_collections::{MutSet, VecMap};
+use roc_load::MonomorphizedModule;
+use roc_module::symbol::ModuleId;
+use roc_region::all::{LineColumn, LineInfo};
+use roc_repl_expect::run::expect_module_name;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// The coverage points of a module, located in its source.
+#[derive(Debug)]
+pub struct ModuleCoverage {
+    pub module_id: ModuleId,
+    pub module_name: String,
+    pub path: PathBuf,
+    pub points: Vec<PointInfo>,
+}
+
+#[derive(Debug)]
+pub struct PointInfo {
+    pub kind: PointKind,
+    pub start: LineColumn,
+}
+
+#[derive(Debug)]
+pub enum PointKind {
+    /// A function, with a name that is unique within its module
+    Function(String),
+    Branch {
+        block: u32,
+        branch: u32,
+    },
+}
+
+/// Where the coverage points of each counted module are, in the order they are counted in.
+pub fn describe_coverage(loaded: &MonomorphizedModule) -> Vec<ModuleCoverage> {
+    let mut modules: Vec<_> = loaded
+        .coverage
+        .iter()
+        .map(|(module_id, points)| {
+            let (path, source) = &loaded.sources[module_id];
+            let line_info = LineInfo::new(source);
+            let mut taken_names = MutSet::default();
+
+            let points = points
+                .iter()
+                .map(|point| {
+                    let start = line_info.convert_pos(point.region().start());
+
+                    let kind = match point {
+                        CoveragePoint::Function { symbol, .. } => {
+                            let name = symbol.as_str(&loaded.interns);
+
+                            // anonymous functions get a generated, numeric name
+                            let name = if name.starts_with(|c: char| c.is_ascii_digit()) {
+                                "lambda"
+                            } else {
+                                name
+                            };
+
+                            // functions of the same name can be nested in different defs
+                            let name = if taken_names.contains(name) {
+                                format!("{}@{}:{}", name, start.line + 1, start.column + 1)
+                            } else {
+                                name.to_string()
+                            };
+
+                            taken_names.insert(name.clone());
+
+                            PointKind::Function(name)
+                        }
+                        CoveragePoint::Branch { block, branch, .. } => PointKind::Branch {
+                            block: *block,
+                            branch: *branch,
+                        },
+                    };
+
+                    PointInfo { kind, start }
+                })
+                .collect();
+
+            ModuleCoverage {
+                module_id: *module_id,
+                module_name: expect_module_name(&loaded.interns, path, *module_id),
+                path: path.clone(),
+                points,
+            }
+        })
+        .collect();
+
+    modules.sort_by(|a, b| a.path.cmp(&b.path));
+
+    modules
+}
+
+/// An lcov tracefile, with a record per module. Lines and functions are 1-based, and the
+/// executable lines are the ones that a function or branch starts on.
+pub fn write_lcov(
+    writer: &mut impl Write,
+    modules: &[ModuleCoverage],
+    counts: &VecMap<ModuleId, Vec<u64>>,
+) -> io::Result<()> {
+    for module in modules {
+        let counts = counts_of(module, counts);
+
+        writeln!(writer, "TN:")?;
+        writeln!(writer, "SF:{}", module.path.display())?;
+
+        let functions = || {
+            module
+                .points
+                .iter()
+                .zip(counts)
+                .filter_map(|(point, count)| match &point.kind {
+                    PointKind::Function(name) => Some((point.start.line + 1, name, *count)),
+                    PointKind::Branch { .. } => None,
+                })
+        };
+
+        for (line, name, _) in functions() {
+            writeln!(writer, "FN:{},{}", line, name)?;
+        }
+
+        for (_, name, count) in functions() {
+            writeln!(writer, "FNDA:{},{}", count, name)?;
+        }
+
+        let stats = CoverageStats::of(module, counts);
+        writeln!(writer, "FNF:{}", stats.functions)?;
+        writeln!(writer, "FNH:{}", stats.functions_hit)?;
+
+        let blocks_run: MutSet<u32> = module
+            .points
+            .iter()
+            .zip(counts)
+            .filter_map(|(point, count)| match point.kind {
+                PointKind::Branch { block, .. } if *count > 0 => Some(block),
+                _ => None,
+            })
+            .collect();
+
+        for (point, count) in module.points.iter().zip(counts) {
+            if let PointKind::Branch { block, branch } = point.kind {
+                // lcov writes `-` for the branches of an `if` or `when` that never ran at all
+                let taken = if blocks_run.contains(&block) {
+                    count.to_string()
+                } else {
+                    "-".to_string()
+                };
+
+                writeln!(
+                    writer,
+                    "BRDA:{},{},{},{}",
+                    point.start.line + 1,
+                    block,
+                    branch,
+                    taken
+                )?;
+            }
+        }
+
+        writeln!(writer, "BRF:{}", stats.branches)?;
+        writeln!(writer, "BRH:{}", stats.branches_hit)?;
+
+        let mut lines: VecMap<u32, u64> = VecMap::default();
+
+        for (point, count) in module.points.iter().zip(counts) {
+            let line = point.start.line + 1;
+
+            match lines.get_mut(&line) {
+                Some(line_count) => *line_count = (*line_count).max(*count),
+                None => {
+                    lines.insert(line, *count);
+                }
+            }
+        }
+
+        let mut lines: Vec<_> = lines.into_iter().collect();
+        lines.sort_unstable();
+
+        for (line, count) in lines.iter() {
+            writeln!(writer, "DA:{},{}", line, count)?;
+        }
+
+        writeln!(writer, "LF:{}", lines.len())?;
+        writeln!(
+            writer,
+            "LH:{}",
+            lines.iter().filter(|(_, count)| *count > 0).count()
+        )?;
+        writeln!(writer, "end_of_record")?;
+    }
+
+    Ok(())
+}
+
+/// A line per module with how many of its functions and branches ran, and a total.
+///
+/// ```text
+/// Coverage:
+///     Main     functions 2/3 (66.7%)    branches 5/6 (83.3%)
+///     total    functions 2/3 (66.7%)    branches 5/6 (83.3%)
+/// ```
+pub fn coverage_summary(modules: &[ModuleCoverage], counts: &VecMap<ModuleId, Vec<u64>>) -> String {
+    let mut total = CoverageStats::default();
+    let mut rows = Vec::with_capacity(modules.len() + 1);
+
+    for module in modules {
+        let stats = CoverageStats::of(module, counts_of(module, counts));
+
+        total.functions += stats.functions;
+        total.functions_hit += stats.functions_hit;
+        total.branches += stats.branches;
+        total.branches_hit += stats.branches_hit;
+
+        rows.push((module.module_name.as_str(), stats));
+    }
+
+    rows.push(("total", total));
+
+    let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    let mut summary = String::from("Coverage:\n");
+
+    for (name, stats) in rows {
+        summary.push_str(&format!(
+            "    {:width$}    functions {}    branches {}\n",
+            name,
+            ratio(stats.functions_hit, stats.functions),
+            ratio(stats.branches_hit, stats.branches),
+            width = width,
+        ));
+    }
+
+    summary
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct CoverageStats {
+    functions: usize,
+    functions_hit: usize,
+    branches: usize,
+    branches_hit: usize,
+}
+
+impl CoverageStats {
+    fn of(module: &ModuleCoverage, counts: &[u64]) -> Self {
+        let mut stats = CoverageStats::default();
+
+        for (point, count) in module.points.iter().zip(counts) {
+            let hit = (*count > 0) as usize;
+
+            match point.kind {
+                PointKind::Function(_) => {
+                    stats.functions += 1;
+                    stats.functions_hit += hit;
+                }
+                PointKind::Branch { .. } => {
+                    stats.branches += 1;
+                    stats.branches_hit += hit;
+                }
+            }
+        }
+
+        stats
+    }
+}
+
+fn counts_of<'c>(module: &ModuleCoverage, counts: &'c VecMap<ModuleId, Vec<u64>>) -> &'c [u64] {
+    counts
+        .get(&module.module_id)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn ratio(hit: usize, total: usize) -> String {
+    if total == 0 {
+        format!("{}/{}", hit, total)
+    } else {
+        format!(
+            "{}/{} ({:.1}%)",
+            hit,
+            total,
+            hit as f64 * 100.0 / total as f64
+        )
+    }
+}
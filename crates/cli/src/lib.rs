@@ -7,9 +7,10 @@ use clap::{Arg, ArgMatches, Command, ValueSource};
 use roc_build::link::{LinkType, LinkingStrategy};
 use roc_collections::VecMap;
 use roc_error_macros::{internal_error, user_error};
-use roc_load::{Expectations, LoadingProblem, Threading};
+use roc_load::{EmitKind, Expectations, LoadingProblem, Threading};
 use roc_module::symbol::{Interns, ModuleId};
 use roc_mono::ir::OptLevel;
+use roc_reporting::report::RenderTarget;
 use std::env;
 use std::ffi::{CString, OsStr};
 use std::io;
@@ -24,8 +25,14 @@ use target_lexicon::{
 #[cfg(not(target_os = "linux"))]
 use tempfile::TempDir;
 
+#[cfg(not(windows))]
+mod bench_report;
 pub mod build;
+#[cfg(not(windows))]
+mod coverage;
 mod format;
+#[cfg(not(windows))]
+mod test_report;
 pub use format::format;
 
 use crate::build::{BuildFileError, BuildOrdering};
@@ -42,6 +49,7 @@ pub const CMD_CHECK: &str = "check";
 pub const CMD_VERSION: &str = "version";
 pub const CMD_FORMAT: &str = "format";
 pub const CMD_TEST: &str = "test";
+pub const CMD_BENCH: &str = "bench";
 pub const CMD_GLUE: &str = "glue";
 
 pub const FLAG_DEBUG: &str = "debug";
@@ -57,6 +65,22 @@ pub const FLAG_LINKER: &str = "linker";
 pub const FLAG_PREBUILT: &str = "prebuilt-platform";
 pub const FLAG_CHECK: &str = "check";
 pub const FLAG_WASM_STACK_SIZE_KB: &str = "wasm-stack-size-kb";
+pub const FLAG_FORMAT: &str = "format";
+pub const FLAG_NO_CACHE: &str = "no-cache";
+pub const FLAG_EMIT: &str = "emit";
+pub const FLAG_FILTER: &str = "filter";
+pub const FLAG_LIST: &str = "list";
+pub const FLAG_MOCK_EFFECTS: &str = "mock-effects";
+pub const FLAG_TIMEOUT: &str = "timeout";
+pub const FLAG_JOBS: &str = "jobs";
+pub const FLAG_COVERAGE: &str = "coverage";
+pub const FLAG_DOC: &str = "doc";
+pub const FLAG_BUILTINS: &str = "builtins";
+pub const FLAG_WARM_UP: &str = "warm-up";
+pub const FLAG_MEASURE: &str = "measure";
+pub const FLAG_SAMPLES: &str = "samples";
+pub const FLAG_BASELINE: &str = "baseline";
+pub const FLAG_SAVE_BASELINE: &str = "save-baseline";
 pub const ROC_FILE: &str = "ROC_FILE";
 pub const ROC_DIR: &str = "ROC_DIR";
 pub const GLUE_FILE: &str = "GLUE_FILE";
@@ -90,7 +114,7 @@ pub fn build_app<'a>() -> Command<'a> {
 
     let flag_debug = Arg::new(FLAG_DEBUG)
         .long(FLAG_DEBUG)
-        .help("Store LLVM debug information in the generated program")
+        .help("Store debug information in the generated program, so debuggers can show where in the .roc source code it is")
         .required(false);
 
     let flag_time = Arg::new(FLAG_TIME)
@@ -117,6 +141,37 @@ pub fn build_app<'a>() -> Command<'a> {
         .validator(|s| s.parse::<u32>())
         .required(false);
 
+    let flag_format = Arg::new(FLAG_FORMAT)
+        .long(FLAG_FORMAT)
+        .help("How to print problems\n(`json` prints one JSON object per problem, each on its own line, for use by other tools.)")
+        .takes_value(true)
+        .possible_values(["human", "json"])
+        .default_value("human")
+        .required(false);
+
+    let flag_test_format = Arg::new(FLAG_FORMAT)
+        .long(FLAG_FORMAT)
+        .help("How to print problems and test results\n(`json` prints one JSON object per problem and then per expect, each on its own line. `junit` prints a JUnit XML report.)")
+        .takes_value(true)
+        .possible_values(["human", "json", "junit"])
+        .default_value("human")
+        .required(false);
+
+    let flag_no_cache = Arg::new(FLAG_NO_CACHE)
+        .long(FLAG_NO_CACHE)
+        .help("Check every module from scratch, without reading or writing the module cache\n(The cache lets `roc check` skip canonicalizing and solving modules that have not changed; they are still parsed.)")
+        .required(false);
+
+    let flag_emit = Arg::new(FLAG_EMIT)
+        .long(FLAG_EMIT)
+        .help("Write intermediate representations next to the output, e.g. `--emit=mono,llvm-ir`\n(`llvm-ir` and `llvm-bc` need the LLVM backend, and `wat` a wasm32 target.)")
+        .takes_value(true)
+        .multiple_values(true)
+        .use_value_delimiter(true)
+        .require_value_delimiter(true)
+        .possible_values(["can", "mono", "llvm-ir", "llvm-bc", "asm", "wat"])
+        .required(false);
+
     let roc_file_to_run = Arg::new(ROC_FILE)
         .help("The .roc file of an app to run")
         .allow_invalid_utf8(true)
@@ -145,6 +200,8 @@ pub fn build_app<'a>() -> Command<'a> {
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
             .arg(flag_wasm_stack_size_kb.clone())
+            .arg(flag_format.clone())
+            .arg(flag_emit)
             .arg(
                 Arg::new(FLAG_TARGET)
                     .long(FLAG_TARGET)
@@ -183,6 +240,71 @@ pub fn build_app<'a>() -> Command<'a> {
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
+            .arg(flag_test_format)
+            .arg(
+                Arg::new(FLAG_FILTER)
+                    .long(FLAG_FILTER)
+                    .help("Only run the expects whose name contains this\n(An expect is named after its module and the def it is written beneath, e.g. `Parser.parseInt`.)")
+                    .takes_value(true)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_LIST)
+                    .long(FLAG_LIST)
+                    .help("List the expects and where they are, without running them")
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_MOCK_EFFECTS)
+                    .long(FLAG_MOCK_EFFECTS)
+                    .help("Run `expect-fx`s against this script of effect calls and their responses, instead of the platform\n(Each line is a call like `stdinLine -> \"Alice\"` or `stdoutLine \"Hi\"`, with the arguments it expects; a `[Main.greet]` line starts the calls of the expects under `greet`.)")
+                    .takes_value(true)
+                    .allow_invalid_utf8(true)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_TIMEOUT)
+                    .long(FLAG_TIMEOUT)
+                    .help("Fail an expect that runs for longer than this many seconds\n(Each expect runs in a process of its own, which is stopped when it times out. By default, or with 0, there is no timeout.)")
+                    .takes_value(true)
+                    .validator(|s| s.parse::<f64>())
+                    .default_value("0")
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_JOBS)
+                    .long(FLAG_JOBS)
+                    .short('j')
+                    .help("Run the expects of this many modules at the same time")
+                    .takes_value(true)
+                    .validator(|s| s.parse::<usize>())
+                    .default_value("1")
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_COVERAGE)
+                    .long(FLAG_COVERAGE)
+                    .help("Count how often each function and branch of the main module's package runs, and write an lcov report\n(The report goes to `lcov.info` unless given a path, like `--coverage=coverage/lcov.info`.)")
+                    .takes_value(true)
+                    .min_values(0)
+                    .require_equals(true)
+                    .default_missing_value("lcov.info")
+                    .allow_invalid_utf8(true)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_DOC)
+                    .long(FLAG_DOC)
+                    .help("Run the code examples in doc comments instead of the expects\n(An example runs if it has `expect`s, or else if it compares with `==`.)")
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_BUILTINS)
+                    .long(FLAG_BUILTINS)
+                    .help("Also run the code examples in the doc comments of the builtins")
+                    .requires(FLAG_DOC)
+                    .required(false),
+            )
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file for the main module")
@@ -192,6 +314,67 @@ pub fn build_app<'a>() -> Command<'a> {
             )
             .arg(args_for_app.clone())
         )
+        .subcommand(Command::new(CMD_BENCH)
+            .about("Time the benchmarks in a main module and any modules of its package\n(A benchmark is a top-level function named like `benchSort` that takes a `{}`. It is built with `--optimize`.)")
+            .arg(flag_max_threads.clone())
+            .arg(
+                Arg::new(FLAG_FILTER)
+                    .long(FLAG_FILTER)
+                    .help("Only run the benchmarks whose name contains this\n(A benchmark is named after its module and itself, e.g. `Parser.benchParseInt`.)")
+                    .takes_value(true)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_WARM_UP)
+                    .long(FLAG_WARM_UP)
+                    .help("Run each benchmark for this many seconds before timing it")
+                    .takes_value(true)
+                    .validator(|s| s.parse::<f64>())
+                    .default_value("1")
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_MEASURE)
+                    .long(FLAG_MEASURE)
+                    .help("Spend about this many seconds timing each benchmark")
+                    .takes_value(true)
+                    .validator(|s| s.parse::<f64>())
+                    .default_value("3")
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_SAMPLES)
+                    .long(FLAG_SAMPLES)
+                    .help("Time this many batches of runs of each benchmark")
+                    .takes_value(true)
+                    .validator(|s| s.parse::<usize>())
+                    .default_value("50")
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_BASELINE)
+                    .long(FLAG_BASELINE)
+                    .help("Compare with the timings saved in this file by `--save-baseline`")
+                    .takes_value(true)
+                    .allow_invalid_utf8(true)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_SAVE_BASELINE)
+                    .long(FLAG_SAVE_BASELINE)
+                    .help("Save the timings to this file as JSON, for a later `--baseline` to compare with")
+                    .takes_value(true)
+                    .allow_invalid_utf8(true)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file for the main module")
+                    .allow_invalid_utf8(true)
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME)
+            )
+        )
         .subcommand(Command::new(CMD_REPL)
             .about("Launch the interactive Read Eval Print Loop (REPL)")
         )
@@ -242,6 +425,8 @@ pub fn build_app<'a>() -> Command<'a> {
             .about("Check the code for problems, but don’t build or run it")
             .arg(flag_time.clone())
             .arg(flag_max_threads.clone())
+            .arg(flag_format)
+            .arg(flag_no_cache)
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file of an app to check")
@@ -315,6 +500,43 @@ pub enum FormatMode {
     CheckOnly,
 }
 
+/// Which intermediate representations to write, based on the `--emit` flag.
+pub fn emit_kinds(matches: &ArgMatches) -> Vec<EmitKind> {
+    // Only `roc build` has this flag, so this must not panic.
+    let names = matches.try_get_many::<String>(FLAG_EMIT).ok().flatten();
+
+    names
+        .into_iter()
+        .flatten()
+        .map(|name| match EmitKind::from_name(name) {
+            Some(kind) => kind,
+            None => internal_error!("clap should have rejected `--emit={}`", name),
+        })
+        .collect()
+}
+
+/// How to print problems, based on the `--format` flag.
+pub fn render_target(matches: &ArgMatches) -> RenderTarget {
+    // Not every command that builds has this flag (e.g. `roc run`), so this must not panic.
+    let format = matches.try_get_one::<String>(FLAG_FORMAT).ok().flatten();
+
+    match format.map(String::as_str) {
+        Some("json") => RenderTarget::Json,
+        // the report is all that goes on stdout, so problems are printed plainly on stderr
+        Some("junit") => RenderTarget::Generic,
+        _ => RenderTarget::ColorTerminal,
+    }
+}
+
+/// With `--format=json`, stdout only has problems on it, so tools can parse it.
+/// Everything else we'd normally print there goes to stderr instead.
+pub fn print_summary(render: RenderTarget, summary: &str) {
+    match render {
+        RenderTarget::Json => eprintln!("{}", summary),
+        RenderTarget::ColorTerminal | RenderTarget::Generic => println!("{}", summary),
+    }
+}
+
 #[cfg(windows)]
 pub fn test(_matches: &ArgMatches, _triple: Triple) -> io::Result<i32> {
     todo!("running tests does not work on windows right now")
@@ -322,14 +544,28 @@ pub fn test(_matches: &ArgMatches, _triple: Triple) -> io::Result<i32> {
 
 #[cfg(not(windows))]
 pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
+    use roc_can::mock_effects::MockEffects;
     use roc_gen_llvm::llvm::build::LlvmBackendMode;
     use roc_load::{ExecutionMode, LoadConfig};
+    use roc_repl_expect::run::WorkerConfig;
     use roc_target::TargetInfo;
-    use std::time::Instant;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+    use test_report::TestFormat;
 
     let start_time = Instant::now();
     let arena = Bump::new();
     let filename = matches.value_of_os(ROC_FILE).unwrap();
+    let render = render_target(matches);
+    let format = TestFormat::from_flag(matches.value_of(FLAG_FORMAT));
+    let filter = matches.value_of(FLAG_FILTER);
+
+    // With a machine-readable format, stdout only has problems and the report on it.
+    let print_test_summary = |summary: &str| match format {
+        TestFormat::Human => println!("{}", summary),
+        TestFormat::Json | TestFormat::Junit => eprintln!("{}", summary),
+    };
+
     let opt_level = match (
         matches.is_present(FLAG_OPTIMIZE),
         matches.is_present(FLAG_OPT_SIZE),
@@ -372,6 +608,50 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
         process::exit(1);
     }
 
+    let timeout = match matches.value_of(FLAG_TIMEOUT).unwrap().parse::<f64>() {
+        Ok(seconds) if seconds == 0.0 => None,
+        Ok(seconds) if seconds > 0.0 && seconds.is_finite() => {
+            Some(Duration::from_secs_f64(seconds))
+        }
+        _ => user_error!("`--{}` must be a number of seconds", FLAG_TIMEOUT),
+    };
+
+    let jobs = match matches
+        .value_of(FLAG_JOBS)
+        .and_then(|s| s.parse::<usize>().ok())
+    {
+        None => 1,
+        Some(0) => user_error!("cannot run the expects of 0 modules at a time"),
+        Some(n) => n,
+    };
+
+    let worker_config = WorkerConfig { timeout, jobs };
+
+    let coverage_path = matches.value_of_os(FLAG_COVERAGE).map(PathBuf::from);
+    let doc_tests = matches.is_present(FLAG_DOC);
+    let builtin_doc_tests = matches.is_present(FLAG_BUILTINS);
+
+    let mock_effects = matches.value_of_os(FLAG_MOCK_EFFECTS).map(|script_path| {
+        let script_path = Path::new(script_path);
+
+        let parsed = std::fs::read_to_string(script_path)
+            .map_err(|error| error.to_string())
+            .and_then(|script| MockEffects::parse(&script).map_err(|error| error.to_string()));
+
+        match parsed {
+            Ok(mock_effects) => Arc::new(mock_effects),
+            Err(error) => {
+                eprintln!(
+                    "\nThe mock effects script {} could not be used: {}\n",
+                    script_path.display(),
+                    error
+                );
+
+                process::exit(1);
+            }
+        }
+    });
+
     let arena = &arena;
     let target = &triple;
     let opt_level = opt_level;
@@ -382,22 +662,75 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
 
     let load_config = LoadConfig {
         target_info,
-        // TODO: expose this from CLI?
-        render: roc_reporting::report::RenderTarget::ColorTerminal,
+        render,
         threading,
         exec_mode: ExecutionMode::Test,
+        mock_effects: mock_effects.clone(),
+        coverage: coverage_path.is_some(),
+        doc_tests,
+        builtin_doc_tests,
+        ..Default::default()
     };
-    let loaded =
-        roc_load::load_and_monomorphize(arena, path.to_path_buf(), subs_by_module, load_config)
-            .unwrap();
+    let loaded = match roc_load::load_and_monomorphize(
+        arena,
+        path.to_path_buf(),
+        subs_by_module,
+        load_config,
+    ) {
+        Ok(loaded) => loaded,
+        Err(roc_load::LoadMonomorphizedError::LoadingProblem(LoadingProblem::FormattedReport(
+            report,
+        ))) => {
+            match format {
+                TestFormat::Junit => eprint!("{}", report),
+                TestFormat::Human | TestFormat::Json => print!("{}", report),
+            }
+
+            return Ok(1);
+        }
+        Err(other) => {
+            panic!("loading the module failed with error:\n{:?}", other);
+        }
+    };
+
+    let selected: Vec<_> = test_report::describe_expects(&loaded)
+        .into_iter()
+        .filter(|info| doc_tests == loaded.toplevel_expects.doc_tests.contains(&info.symbol))
+        .filter(|info| filter.map_or(true, |filter| info.matches(filter)))
+        .collect();
+
+    if matches.is_present(FLAG_LIST) {
+        test_report::write_list(&mut io::stdout(), format, &selected)?;
+
+        return Ok(0);
+    }
+
+    // examples that do not parse fail without running, and are reported where they are
+    let unparsed_examples = if doc_tests {
+        let reports: Vec<_> = loaded.doc_test_problems.values().flatten().collect();
+
+        for report in reports.iter() {
+            match format {
+                TestFormat::Human => println!("{}", report),
+                TestFormat::Json | TestFormat::Junit => eprintln!("{}", report),
+            }
+        }
+
+        reports.len()
+    } else {
+        0
+    };
+
+    let coverage_modules = coverage::describe_coverage(&loaded);
 
     let mut loaded = loaded;
     let mut expectations = std::mem::take(&mut loaded.expectations);
+    let coverage_points = std::mem::take(&mut loaded.coverage);
     let loaded = loaded;
 
     let interns = loaded.interns.clone();
 
-    let (lib, expects, layout_interner) = roc_repl_expect::run::expect_mono_module_to_dylib(
+    let (lib, mut expects, layout_interner) = roc_repl_expect::run::expect_mono_module_to_dylib(
         arena,
         target.clone(),
         loaded,
@@ -406,28 +739,82 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
     )
     .unwrap();
 
+    let is_selected =
+        |symbol: roc_module::symbol::Symbol| selected.iter().any(|info| info.symbol == symbol);
+    expects.pure.retain(|expect| is_selected(expect.symbol));
+    expects.fx.retain(|expect| is_selected(expect.symbol));
+
     let arena = &bumpalo::Bump::new();
     let interns = arena.alloc(interns);
 
-    let mut writer = std::io::stdout();
+    // Machine-readable formats include what each expect reported in the report instead.
+    let (mut writer, expect_render): (Box<dyn io::Write>, _) = match format {
+        TestFormat::Human => (Box::new(io::stdout()), render),
+        TestFormat::Json | TestFormat::Junit => (Box::new(io::sink()), RenderTarget::Generic),
+    };
+
+    // installed before the workers are forked, so they all count into the same counters
+    let coverage_counts = match coverage_path {
+        Some(_) => Some(roc_repl_expect::coverage::install(&lib, &coverage_points)?),
+        None => None,
+    };
 
-    let (failed, passed) = roc_repl_expect::run::run_expects(
+    let results = roc_repl_expect::run::run_expects(
         &mut writer,
-        roc_reporting::report::RenderTarget::ColorTerminal,
+        expect_render,
         arena,
         interns,
         &layout_interner.into_global(),
         &lib,
         &mut expectations,
         expects,
+        mock_effects.as_deref(),
+        worker_config,
     )
     .unwrap();
 
     let total_time = start_time.elapsed();
 
+    match format {
+        TestFormat::Human => {}
+        TestFormat::Json => test_report::write_json(&mut io::stdout(), &selected, &results)?,
+        TestFormat::Junit => {
+            test_report::write_junit(&mut io::stdout(), &selected, &results, total_time)?
+        }
+    }
+
+    if let (Some(coverage_path), Some(coverage_counts)) = (&coverage_path, &coverage_counts) {
+        use std::io::Write;
+
+        let counts = coverage_counts.counts();
+
+        let mut lcov = io::BufWriter::new(std::fs::File::create(coverage_path)?);
+        coverage::write_lcov(&mut lcov, &coverage_modules, &counts)?;
+        lcov.flush()?;
+
+        print_test_summary(&coverage::coverage_summary(&coverage_modules, &counts));
+        print_test_summary(&format!(
+            "Wrote the coverage report to {}",
+            coverage_path.display()
+        ));
+    }
+
+    let failed = results.iter().filter(|result| !result.passed).count();
+    let passed = results.len() - failed;
+    let failed = failed + unparsed_examples;
+
     if failed == 0 && passed == 0 {
         // TODO print this in a more nicely formatted way!
-        println!("No expectations were found.");
+        match filter {
+            Some(filter) => print_test_summary(&format!(
+                "No expectations matched `--{} {}`.",
+                FLAG_FILTER, filter
+            )),
+            None if doc_tests => {
+                print_test_summary("No code examples with expectations were found in doc comments.")
+            }
+            None => print_test_summary("No expectations were found."),
+        }
 
         // If no tests ran, treat that as an error. This is perhaps
         // briefly annoying at the very beginning of a project when
@@ -442,15 +829,227 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
             31 // red
         };
 
-        println!(
+        print_test_summary(&format!(
             "\n\x1B[{failed_color}m{failed}\x1B[39m failed and \x1B[32m{passed}\x1B[39m passed in {} ms.\n",
             total_time.as_millis(),
-        );
+        ));
 
         Ok((failed > 0) as i32)
     }
 }
 
+#[cfg(windows)]
+pub fn bench(_matches: &ArgMatches, _triple: Triple) -> io::Result<i32> {
+    todo!("running benchmarks does not work on windows right now")
+}
+
+#[cfg(not(windows))]
+pub fn bench(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
+    use bench_report::BenchStats;
+    use roc_collections::MutMap;
+    use roc_gen_llvm::llvm::build::LlvmBackendMode;
+    use roc_load::{ExecutionMode, LoadConfig};
+    use roc_repl_expect::bench::BenchConfig;
+    use roc_target::TargetInfo;
+    use std::time::Duration;
+
+    let arena = Bump::new();
+    let filename = matches.value_of_os(ROC_FILE).unwrap();
+    let render = render_target(matches);
+    let filter = matches.value_of(FLAG_FILTER);
+
+    let threading = match matches
+        .value_of(FLAG_MAX_THREADS)
+        .and_then(|s| s.parse::<usize>().ok())
+    {
+        None => Threading::AllAvailable,
+        Some(0) => user_error!("cannot build with at most 0 threads"),
+        Some(1) => Threading::Single,
+        Some(n) => Threading::AtMost(n),
+    };
+
+    let seconds = |flag: &str| match matches.value_of(flag).unwrap().parse::<f64>() {
+        Ok(seconds) if seconds >= 0.0 && seconds.is_finite() => Duration::from_secs_f64(seconds),
+        _ => user_error!("`--{}` must be a number of seconds", flag),
+    };
+
+    let config = BenchConfig {
+        warm_up: seconds(FLAG_WARM_UP),
+        measure: seconds(FLAG_MEASURE),
+        samples: match matches
+            .value_of(FLAG_SAMPLES)
+            .and_then(|s| s.parse::<usize>().ok())
+        {
+            Some(0) | None => user_error!("`--{}` must be at least 1", FLAG_SAMPLES),
+            Some(n) => n,
+        },
+    };
+
+    let path = Path::new(filename);
+
+    if !path.exists() {
+        match matches.value_source(ROC_FILE) {
+            Some(ValueSource::DefaultValue) => {
+                eprintln!(
+                    "\nNo `.roc` file was specified, and the current directory does not contain a {} file to use as a default.\n\nYou can run `roc help` for more information on how to provide a .roc file.\n",
+                    DEFAULT_ROC_FILENAME
+                )
+            }
+            _ => eprintln!("\nThis file was not found: {}\n\nYou can run `roc help` for more information on how to provide a .roc file.\n", path.to_string_lossy()),
+        }
+
+        process::exit(1);
+    }
+
+    // read before anything is timed, so that a bad baseline does not waste a run
+    let baseline = match matches.value_of_os(FLAG_BASELINE).map(Path::new) {
+        Some(baseline_path) => {
+            let parsed = std::fs::read_to_string(baseline_path)
+                .map_err(|error| error.to_string())
+                .and_then(|json| bench_report::parse_baseline(&json));
+
+            match parsed {
+                Ok(baseline) => baseline,
+                Err(error) => {
+                    eprintln!(
+                        "\nThe baseline {} could not be used: {}\n",
+                        baseline_path.display(),
+                        error
+                    );
+
+                    return Ok(1);
+                }
+            }
+        }
+        None => Vec::new(),
+    };
+
+    let arena = &arena;
+    let target = &triple;
+    let target_info = TargetInfo::from(target);
+
+    let load_config = LoadConfig {
+        target_info,
+        render,
+        threading,
+        exec_mode: ExecutionMode::Test,
+        benches: true,
+        ..Default::default()
+    };
+    let loaded = match roc_load::load_and_monomorphize(
+        arena,
+        path.to_path_buf(),
+        Default::default(),
+        load_config,
+    ) {
+        Ok(loaded) => loaded,
+        Err(roc_load::LoadMonomorphizedError::LoadingProblem(LoadingProblem::FormattedReport(
+            report,
+        ))) => {
+            print!("{}", report);
+
+            return Ok(1);
+        }
+        Err(other) => {
+            panic!("loading the module failed with error:\n{:?}", other);
+        }
+    };
+
+    // named before the module is handed to code gen, which takes its interns
+    let names: MutMap<_, _> = loaded
+        .toplevel_benches
+        .thunks
+        .values()
+        .map(|bench| {
+            let (path, _) = &loaded.sources[&bench.value.module_id()];
+
+            (
+                bench.value,
+                (path.clone(), bench_report::bench_name(&loaded, bench.value)),
+            )
+        })
+        .collect();
+
+    // benchmarks are always timed as they would run in an optimized build
+    let (lib, expects, _layout_interner) = roc_repl_expect::run::expect_mono_module_to_dylib(
+        arena,
+        target.clone(),
+        loaded,
+        OptLevel::Optimize,
+        LlvmBackendMode::CliTest,
+    )
+    .unwrap();
+
+    let mut benches: Vec<_> = expects
+        .benches
+        .iter()
+        .map(|bench| (&names[&bench.symbol], bench))
+        .filter(|((_, name), _)| filter.map_or(true, |filter| name.contains(filter)))
+        .collect();
+
+    // in source order, since modules are loaded in no particular order
+    benches.sort_by_key(|((path, _), bench)| (path.clone(), bench.region.start()));
+
+    if benches.is_empty() {
+        match filter {
+            Some(filter) => print_summary(
+                render,
+                &format!("No benchmarks matched `--{} {}`.", FLAG_FILTER, filter),
+            ),
+            None => print_summary(
+                render,
+                "No benchmarks were found.\n\nA benchmark is a top-level function named like `benchSort` that takes a `{}`.",
+            ),
+        }
+
+        return Ok(2);
+    }
+
+    let width = benches
+        .iter()
+        .map(|((_, name), _)| name.len())
+        .max()
+        .unwrap_or(0);
+    let mut all_stats = Vec::with_capacity(benches.len());
+    let mut crashed = 0;
+    let stdout = &mut io::stdout();
+
+    for ((_, name), bench) in benches {
+        match roc_repl_expect::bench::run_bench(&lib, bench, &config) {
+            Ok(samples) => {
+                let stats = BenchStats::of(name.clone(), &samples);
+                let baseline = baseline.iter().find(|saved| saved.name == stats.name);
+
+                bench_report::write_human(stdout, &stats, width, baseline)?;
+
+                all_stats.push(stats);
+            }
+            Err(message) => {
+                println!(
+                    "{:width$}    \x1B[31mcrashed\x1B[39m: {}",
+                    name,
+                    message,
+                    width = width
+                );
+
+                crashed += 1;
+            }
+        }
+    }
+
+    if let Some(save_path) = matches.value_of_os(FLAG_SAVE_BASELINE).map(Path::new) {
+        use std::io::Write;
+
+        let mut file = io::BufWriter::new(std::fs::File::create(save_path)?);
+        bench_report::write_baseline(&mut file, &all_stats)?;
+        file.flush()?;
+
+        println!("\nSaved the baseline to {}", save_path.display());
+    }
+
+    Ok((crashed > 0) as i32)
+}
+
 pub fn build(
     matches: &ArgMatches,
     config: BuildConfig,
@@ -475,6 +1074,8 @@ pub fn build(
     };
     let emit_debug_info = matches.is_present(FLAG_DEBUG);
     let emit_timings = matches.is_present(FLAG_TIME);
+    let render = render_target(matches);
+    let emit_kinds = emit_kinds(matches);
 
     let threading = match matches
         .value_of(FLAG_MAX_THREADS)
@@ -538,6 +1139,11 @@ pub fn build(
         BuildAndRunIfNoErrors => BuildOrdering::BuildIfChecks,
         _ => BuildOrdering::AlwaysBuild,
     };
+
+    // `roc dev` and `roc run` report the `dbg`s and failing `expect`s of the program as it runs;
+    // a program that `roc build` writes runs on its own, so its `dbg`s only say where they are
+    let runs_under_roc_dev = !matches!(config, BuildOnly);
+
     let res_binary_path = build_file(
         &arena,
         &triple,
@@ -551,6 +1157,9 @@ pub fn build(
         threading,
         wasm_dev_stack_bytes,
         build_ordering,
+        runs_under_roc_dev,
+        render,
+        emit_kinds,
     );
 
     match res_binary_path {
@@ -572,7 +1181,7 @@ pub fn build(
                     // since the process is about to exit anyway.
                     std::mem::forget(arena);
 
-                    println!(
+                    let summary = format!(
                         "\x1B[{}m{}\x1B[39m {} and \x1B[{}m{}\x1B[39m {} found in {} ms while successfully building:\n\n    {}",
                         if problems.errors == 0 {
                             32 // green
@@ -600,12 +1209,14 @@ pub fn build(
                         generated_filename.to_str().unwrap()
                     );
 
+                    print_summary(render, &summary);
+
                     // Return a nonzero exit code if there were problems
                     Ok(problems.exit_code())
                 }
                 BuildAndRun => {
                     if problems.errors > 0 || problems.warnings > 0 {
-                        println!(
+                        let summary = format!(
                             "\x1B[{}m{}\x1B[39m {} and \x1B[{}m{}\x1B[39m {} found in {} ms.\n\nRunning program anyway…\n\n\x1B[36m{}\x1B[39m",
                             if problems.errors == 0 {
                                 32 // green
@@ -632,6 +1243,8 @@ pub fn build(
                             total_time.as_millis(),
                             "─".repeat(80)
                         );
+
+                        print_summary(render, &summary);
                     }
 
                     let args = matches.values_of_os(ARGS_FOR_APP).unwrap_or_default();
@@ -656,7 +1269,7 @@ pub fn build(
                         "if there are errors, they should have been returned as an error variant"
                     );
                     if problems.warnings > 0 {
-                        println!(
+                        let summary = format!(
                             "\x1B[32m0\x1B[39m errors and \x1B[33m{}\x1B[39m {} found in {} ms.\n\nRunning program…\n\n\x1B[36m{}\x1B[39m",
                             problems.warnings,
                             if problems.warnings == 1 {
@@ -667,6 +1280,8 @@ pub fn build(
                             total_time.as_millis(),
                             "─".repeat(80)
                         );
+
+                        print_summary(render, &summary);
                     }
 
                     let args = matches.values_of_os(ARGS_FOR_APP).unwrap_or_default();
@@ -684,7 +1299,7 @@ pub fn build(
         }) => {
             debug_assert!(module.total_problems() > 0);
 
-            let problems = roc_build::program::report_problems_typechecked(&mut module);
+            let problems = roc_build::program::report_problems_typechecked(&mut module, render);
 
             let mut output = format!(
                 "\x1B[{}m{}\x1B[39m {} and \x1B[{}m{}\x1B[39m {} found in {} ms.\n\nYou can run the program anyway with \x1B[32mroc run",
@@ -719,7 +1334,8 @@ pub fn build(
                 output.push_str(&filename.to_string_lossy());
             }
 
-            println!("{}\x1B[39m", output);
+            output.push_str("\x1B[39m");
+            print_summary(render, &output);
 
             Ok(problems.exit_code())
         }
@@ -855,16 +1471,16 @@ fn roc_run_native<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
             .collect_in(&arena);
 
         match opt_level {
-            OptLevel::Development => {
-                roc_run_native_debug(executable, &argv, &envp, expectations, interns)
+            OptLevel::Development | OptLevel::Normal => {
+                roc_run_native_debug(&arena, executable, &argv, &envp, expectations, interns)
             }
-            OptLevel::Normal | OptLevel::Size | OptLevel::Optimize => {
+            OptLevel::Size | OptLevel::Optimize => {
                 roc_run_native_fast(executable, &argv, &envp);
+
+                Ok(1)
             }
         }
     }
-
-    Ok(1)
 }
 
 unsafe fn roc_run_native_fast(
@@ -932,16 +1548,70 @@ impl ExecutableFile {
     }
 }
 
-// with Expect
+/// Run the program in a process of its own, and report its `dbg`s, failed `expect`s and
+/// `crash`es as it goes. Returns the exit code of the program.
 #[cfg(target_family = "unix")]
 unsafe fn roc_run_native_debug(
-    _executable: ExecutableFile,
-    _argv: &[*const c_char],
-    _envp: &[*const c_char],
-    _expectations: VecMap<ModuleId, Expectations>,
-    _interns: Interns,
-) {
-    todo!()
+    arena: &Bump,
+    executable: ExecutableFile,
+    argv: &[*const c_char],
+    envp: &[*const c_char],
+    mut expectations: VecMap<ModuleId, Expectations>,
+    interns: Interns,
+) -> std::io::Result<i32> {
+    use roc_repl_expect::run::DevExpectMemory;
+    use signal_hook::consts::signal::{SIGCHLD, SIGUSR1};
+    use signal_hook::iterator::Signals;
+
+    // the program signals us as soon as it has written a frame, so listen before it starts
+    let mut signals = Signals::new(&[SIGCHLD, SIGUSR1])?;
+    let memory = DevExpectMemory::create()?;
+
+    let pid = match libc::fork() {
+        0 => {
+            // we are the program
+            executable.execve(argv, envp);
+
+            eprintln!(
+                "libc::{}({:?}, ..., ...) failed: {:?}",
+                ExecutableFile::SYSCALL,
+                executable.as_path(),
+                errno::errno()
+            );
+
+            // skip the destructors of what we copied from `roc dev`, which would remove its memory
+            libc::_exit(1)
+        }
+        -1 => return Err(std::io::Error::last_os_error()),
+        pid => pid,
+    };
+
+    let mut stdout = std::io::stdout();
+
+    loop {
+        for signal in signals.wait() {
+            // the program can write a frame and exit before we get to its first signal
+            memory.report_frame(&mut stdout, arena, &mut expectations, &interns)?;
+
+            if signal != SIGCHLD {
+                continue;
+            }
+
+            let mut status = 0;
+
+            if libc::waitpid(pid, &mut status, libc::WNOHANG) != pid {
+                // the program was stopped or continued, but is still running
+                continue;
+            }
+
+            if libc::WIFSIGNALED(status) {
+                // the exit code a shell reports for a program that was killed by a signal
+                return Ok(128 + libc::WTERMSIG(status));
+            }
+
+            return Ok(libc::WEXITSTATUS(status));
+        }
+    }
 }
 
 #[cfg(target_os = "linux")]
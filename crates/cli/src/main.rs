@@ -1,10 +1,10 @@
 use roc_build::link::LinkType;
 use roc_cli::build::check_file;
 use roc_cli::{
-    build_app, format, test, BuildConfig, FormatMode, Target, CMD_BUILD, CMD_CHECK, CMD_DEV,
-    CMD_DOCS, CMD_EDIT, CMD_FORMAT, CMD_GLUE, CMD_REPL, CMD_RUN, CMD_TEST, CMD_VERSION,
-    DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_LIB, FLAG_NO_LINK, FLAG_TARGET, FLAG_TIME, GLUE_FILE,
-    ROC_FILE,
+    bench, build_app, format, print_summary, render_target, test, BuildConfig, FormatMode, Target,
+    CMD_BENCH, CMD_BUILD, CMD_CHECK, CMD_DEV, CMD_DOCS, CMD_EDIT, CMD_FORMAT, CMD_GLUE, CMD_REPL,
+    CMD_RUN, CMD_TEST, CMD_VERSION, DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_LIB, FLAG_NO_LINK,
+    FLAG_TARGET, FLAG_TIME, GLUE_FILE, ROC_FILE,
 };
 use roc_docs::generate_docs_html;
 use roc_error_macros::user_error;
@@ -67,6 +67,15 @@ fn main() -> io::Result<()> {
                 Ok(1)
             }
         }
+        Some((CMD_BENCH, matches)) => {
+            if matches.is_present(ROC_FILE) {
+                bench(matches, Triple::host())
+            } else {
+                eprintln!("What .roc file do you want to benchmark? Specify it at the end of the `roc bench` command.");
+
+                Ok(1)
+            }
+        }
         Some((CMD_DEV, matches)) => {
             if matches.is_present(ROC_FILE) {
                 build(
@@ -129,9 +138,24 @@ fn main() -> io::Result<()> {
                 Some(n) => Threading::AtMost(n),
             };
 
-            match check_file(&arena, roc_file_path, emit_timings, threading) {
+            let render = render_target(matches);
+
+            let cache_dir = if matches.is_present(roc_cli::FLAG_NO_CACHE) {
+                None
+            } else {
+                roc_load::default_cache_dir()
+            };
+
+            match check_file(
+                &arena,
+                roc_file_path,
+                emit_timings,
+                threading,
+                render,
+                cache_dir,
+            ) {
                 Ok((problems, total_time)) => {
-                    println!(
+                    let summary = format!(
                         "\x1B[{}m{}\x1B[39m {} and \x1B[{}m{}\x1B[39m {} found in {} ms.",
                         if problems.errors == 0 {
                             32 // green
@@ -158,6 +182,8 @@ fn main() -> io::Result<()> {
                         total_time.as_millis(),
                     );
 
+                    print_summary(render, &summary);
+
                     Ok(problems.exit_code())
                 }
 
@@ -0,0 +1,273 @@
+//! Naming, selecting and reporting the expects that `roc test` runs, for its `--filter`,
+//! `--list` and `--format` flags.
+
+use roc_load::MonomorphizedModule;
+use roc_module::symbol::Symbol;
+use roc_region::all::{LineColumn, LineColumnRegion, LineInfo};
+use roc_repl_expect::run::{expect_module_name, toplevel_expect_name, ExpectResult};
+use serde_json::json;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TestFormat {
+    Human,
+    Json,
+    Junit,
+}
+
+impl TestFormat {
+    pub fn from_flag(format: Option<&str>) -> Self {
+        match format {
+            Some("json") => TestFormat::Json,
+            Some("junit") => TestFormat::Junit,
+            _ => TestFormat::Human,
+        }
+    }
+}
+
+/// Where a top-level expect is, and what it is called.
+#[derive(Debug)]
+pub struct ExpectInfo {
+    pub symbol: Symbol,
+    /// The module name followed by the name of the def the expect is written beneath,
+    /// e.g. `Parser.parseInt`, or just the module name if it comes before every def.
+    pub name: String,
+    pub module_name: String,
+    pub path: PathBuf,
+    pub region: LineColumnRegion,
+}
+
+impl ExpectInfo {
+    /// Whether `--filter` selects this expect. The filter can be any part of its name,
+    /// so a module name selects all the expects in that module.
+    pub fn matches(&self, filter: &str) -> bool {
+        self.name.contains(filter)
+    }
+
+    fn location(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.path.display(),
+            self.region.start.line + 1,
+            self.region.start.column + 1
+        )
+    }
+}
+
+/// All the top-level expects in the loaded modules, in source order.
+pub fn describe_expects(loaded: &MonomorphizedModule) -> Vec<ExpectInfo> {
+    let expects = &loaded.toplevel_expects;
+
+    let mut infos: Vec<_> = expects
+        .pure
+        .iter()
+        .chain(expects.fx.iter())
+        .map(|(symbol, region)| {
+            let (path, source) = &loaded.sources[&symbol.module_id()];
+            let enclosing_def = expects.enclosing_defs.get(symbol).copied();
+
+            ExpectInfo {
+                symbol: *symbol,
+                name: toplevel_expect_name(&loaded.interns, path, *symbol, enclosing_def),
+                module_name: expect_module_name(&loaded.interns, path, symbol.module_id()),
+                path: path.clone(),
+                region: LineInfo::new(source).convert_region(*region),
+            }
+        })
+        .collect();
+
+    infos.sort_by(|a, b| (&a.path, a.region.start).cmp(&(&b.path, b.region.start)));
+
+    infos
+}
+
+/// `--list` prints where each expect is, without running any of them.
+pub fn write_list(
+    writer: &mut impl Write,
+    format: TestFormat,
+    infos: &[ExpectInfo],
+) -> io::Result<()> {
+    for info in infos {
+        match format {
+            TestFormat::Json => writeln!(
+                writer,
+                "{}",
+                json!({
+                    "name": info.name,
+                    "path": info.path.to_string_lossy(),
+                    "region": region_to_json(info.region),
+                })
+            )?,
+            TestFormat::Human | TestFormat::Junit => {
+                writeln!(writer, "{}  {}", info.name, info.location())?
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// One JSON object per expect, each on its own line. Lines and columns are 1-based, e.g.
+///
+/// ```json
+/// {"name":"Parser.parseInt","path":"Parser.roc",
+///  "region":{"start":{"line":12,"column":1},"end":{"line":12,"column":25}},
+///  "outcome":"failed","duration_ms":0.042,"message":"── EXPECT FAILED ..."}
+/// ```
+pub fn write_json(
+    writer: &mut impl Write,
+    infos: &[ExpectInfo],
+    results: &[ExpectResult],
+) -> io::Result<()> {
+    for (info, result) in in_source_order(infos, results) {
+        let outcome = if result.passed { "passed" } else { "failed" };
+
+        writeln!(
+            writer,
+            "{}",
+            json!({
+                "name": info.name,
+                "path": info.path.to_string_lossy(),
+                "region": region_to_json(info.region),
+                "outcome": outcome,
+                "duration_ms": result.duration.as_secs_f64() * 1000.0,
+                "message": result.output.trim(),
+            })
+        )?;
+    }
+
+    Ok(())
+}
+
+fn region_to_json(region: LineColumnRegion) -> serde_json::Value {
+    fn position(line_column: LineColumn) -> serde_json::Value {
+        json!({ "line": line_column.line + 1, "column": line_column.column + 1 })
+    }
+
+    json!({ "start": position(region.start), "end": position(region.end) })
+}
+
+/// A JUnit XML report with a test suite per module, which most CI services can display.
+pub fn write_junit(
+    writer: &mut impl Write,
+    infos: &[ExpectInfo],
+    results: &[ExpectResult],
+    total_time: Duration,
+) -> io::Result<()> {
+    let ordered = in_source_order(infos, results);
+
+    // consecutive expects in the same module make up a suite, because they are in source order
+    let mut suites: Vec<(&str, Vec<(&ExpectInfo, &ExpectResult)>)> = Vec::new();
+
+    for (info, result) in ordered {
+        match suites.last_mut() {
+            Some((module_name, cases)) if *module_name == info.module_name => {
+                cases.push((info, result))
+            }
+            _ => suites.push((&info.module_name, vec![(info, result)])),
+        }
+    }
+
+    let failures = |cases: &[(&ExpectInfo, &ExpectResult)]| {
+        cases.iter().filter(|(_, result)| !result.passed).count()
+    };
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<testsuites name="roc test" tests="{}" failures="{}" time="{}">"#,
+        results.len(),
+        results.iter().filter(|result| !result.passed).count(),
+        seconds(total_time),
+    )?;
+
+    for (module_name, cases) in suites {
+        let suite_time = cases.iter().map(|(_, result)| result.duration).sum();
+
+        writeln!(
+            writer,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" time="{}">"#,
+            escape_xml(module_name),
+            cases.len(),
+            failures(&cases),
+            seconds(suite_time),
+        )?;
+
+        for (info, result) in cases {
+            write!(
+                writer,
+                r#"    <testcase name="{}" classname="{}" file="{}" line="{}" time="{}">"#,
+                escape_xml(&info.name),
+                escape_xml(module_name),
+                escape_xml(&info.path.to_string_lossy()),
+                info.region.start.line + 1,
+                seconds(result.duration),
+            )?;
+
+            let output = result.output.trim();
+
+            if !result.passed {
+                write!(
+                    writer,
+                    "\n      <failure message=\"expect failed at {}\">{}</failure>\n    ",
+                    escape_xml(&info.location()),
+                    escape_xml(output),
+                )?;
+            } else if !output.is_empty() {
+                // e.g. the values of `dbg`s
+                write!(
+                    writer,
+                    "\n      <system-out>{}</system-out>\n    ",
+                    escape_xml(output)
+                )?;
+            }
+
+            writeln!(writer, "</testcase>")?;
+        }
+
+        writeln!(writer, "  </testsuite>")?;
+    }
+
+    writeln!(writer, "</testsuites>")
+}
+
+/// Expects run grouped by whether they perform effects; reports list them as they are in the source.
+fn in_source_order<'r, 'a>(
+    infos: &'r [ExpectInfo],
+    results: &'r [ExpectResult<'a>],
+) -> Vec<(&'r ExpectInfo, &'r ExpectResult<'a>)> {
+    infos
+        .iter()
+        .filter_map(|info| {
+            results
+                .iter()
+                .find(|result| result.expect.symbol == info.symbol)
+                .map(|result| (info, result))
+        })
+        .collect()
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64())
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // XML 1.0 cannot represent most control characters, not even escaped
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
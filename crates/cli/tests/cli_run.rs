@@ -16,11 +16,12 @@ mod cli_run {
     };
     use const_format::concatcp;
     use indoc::indoc;
-    use roc_cli::{CMD_BUILD, CMD_CHECK, CMD_FORMAT, CMD_RUN};
+    use roc_cli::{CMD_BENCH, CMD_BUILD, CMD_CHECK, CMD_DEV, CMD_FORMAT, CMD_RUN, CMD_TEST};
     use roc_test_utils::assert_multiline_str_eq;
     use serial_test::serial;
     use std::iter;
     use std::path::Path;
+    use std::time::{Duration, Instant};
     use strum::IntoEnumIterator;
     use strum_macros::EnumIter;
 
@@ -28,6 +29,23 @@ mod cli_run {
     const LINKER_FLAG: &str = concatcp!("--", roc_cli::FLAG_LINKER);
     const CHECK_FLAG: &str = concatcp!("--", roc_cli::FLAG_CHECK);
     const PREBUILT_PLATFORM: &str = concatcp!("--", roc_cli::FLAG_PREBUILT, "=true");
+    const EMIT_FLAG: &str = concatcp!("--", roc_cli::FLAG_EMIT);
+    const FILTER_FLAG: &str = concatcp!("--", roc_cli::FLAG_FILTER);
+    const LIST_FLAG: &str = concatcp!("--", roc_cli::FLAG_LIST);
+    const FORMAT_FLAG: &str = concatcp!("--", roc_cli::FLAG_FORMAT);
+    const MOCK_EFFECTS_FLAG: &str = concatcp!("--", roc_cli::FLAG_MOCK_EFFECTS);
+    const TIMEOUT_FLAG: &str = concatcp!("--", roc_cli::FLAG_TIMEOUT);
+    const JOBS_FLAG: &str = concatcp!("--", roc_cli::FLAG_JOBS);
+    const COVERAGE_FLAG: &str = concatcp!("--", roc_cli::FLAG_COVERAGE);
+    const DOC_FLAG: &str = concatcp!("--", roc_cli::FLAG_DOC);
+    const BUILTINS_FLAG: &str = concatcp!("--", roc_cli::FLAG_BUILTINS);
+    const WARM_UP_FLAG: &str = concatcp!("--", roc_cli::FLAG_WARM_UP);
+    const MEASURE_FLAG: &str = concatcp!("--", roc_cli::FLAG_MEASURE);
+    const SAMPLES_FLAG: &str = concatcp!("--", roc_cli::FLAG_SAMPLES);
+    const BASELINE_FLAG: &str = concatcp!("--", roc_cli::FLAG_BASELINE);
+    const SAVE_BASELINE_FLAG: &str = concatcp!("--", roc_cli::FLAG_SAVE_BASELINE);
+    const DEV_FLAG: &str = concatcp!("--", roc_cli::FLAG_DEV);
+    const DEBUG_FLAG: &str = concatcp!("--", roc_cli::FLAG_DEBUG);
     #[allow(dead_code)]
     const TARGET_FLAG: &str = concatcp!("--", roc_cli::FLAG_TARGET);
 
@@ -960,6 +978,658 @@ mod cli_run {
         );
     }
 
+    #[test]
+    #[serial(multi_dep_str)]
+    fn dev_prints_dbg_values_as_the_program_runs() {
+        let file = fixture_file("dbg", "Main.roc");
+        let out = run_roc([CMD_DEV, file.to_str().unwrap()], &[]);
+
+        assert!(out.status.success(), "bad status {:?}", out);
+        assert_multiline_str_eq!(out.stdout.as_str(), "The answer is 42\n");
+
+        // `roc dev` renders the value, and the host prints it
+        let stderr = out.stderr.replacen("🔨 Rebuilding platform...\n", "", 1);
+        assert_multiline_str_eq!(stderr.as_str(), "[Main.roc:8:18] 42\n");
+    }
+
+    #[test]
+    #[serial(multi_dep_str)]
+    fn build_prints_where_dbg_is() {
+        let file = fixture_file("dbg", "Main.roc");
+        let out = run_roc([CMD_BUILD, file.to_str().unwrap()], &[]);
+
+        assert!(out.status.success(), "bad status {:?}", out);
+
+        // the program runs on its own, so there is nothing to render the value of its `dbg`
+        let binary_path = file.with_file_name("dbg");
+        let out = run_cmd(binary_path.to_str().unwrap(), [], &[]);
+
+        assert!(out.status.success(), "bad status {:?}", out);
+        assert_multiline_str_eq!(out.stdout.as_str(), "The answer is 42\n");
+        assert!(out.stderr.starts_with("[module "), "{}", out.stderr);
+        assert!(
+            out.stderr
+                .ends_with("] (only `roc dev` and `roc run` can show this value)\n"),
+            "{}",
+            out.stderr
+        );
+    }
+
+    #[test]
+    #[serial(multi_dep_str)]
+    fn optimized_build_strips_dbg() {
+        let file = fixture_file("dbg", "Main.roc");
+        let out = run_roc([CMD_BUILD, OPTIMIZE_FLAG, file.to_str().unwrap()], &[]);
+
+        assert!(out.status.success(), "bad status {:?}", out);
+
+        let binary_path = file.with_file_name("dbg");
+        let out = run_cmd(binary_path.to_str().unwrap(), [], &[]);
+
+        assert!(out.status.success(), "bad status {:?}", out);
+        assert_multiline_str_eq!(out.stdout.as_str(), "The answer is 42\n");
+        assert_multiline_str_eq!(out.stderr.as_str(), "");
+    }
+
+    #[test]
+    #[serial(multi_dep_str)]
+    fn dev_reports_where_the_program_crashed() {
+        let file = fixture_file("crash", "Main.roc");
+        let out = run_roc([CMD_DEV, file.to_str().unwrap()], &[]);
+
+        let stdout = strip_colors(&out.stdout);
+        assert!(stdout.contains("CRASHED"), "{}", stdout);
+        assert!(
+            stdout.contains("The program crashed at this crash:"),
+            "{}",
+            stdout
+        );
+        assert!(stdout.contains("the answer is not known yet"), "{}", stdout);
+    }
+
+    #[test]
+    #[serial(multi_dep_thunk)]
+    fn emit_intermediate_representations() {
+        let file = fixture_file("multi-dep-thunk", "Main.roc");
+        let emit = format!("{}=can,mono,llvm-ir,llvm-bc,asm", EMIT_FLAG);
+        let out = run_roc([CMD_BUILD, emit.as_str(), file.to_str().unwrap()], &[]);
+
+        assert!(out.status.success(), "bad status {:?}", out);
+
+        let binary_path = file.with_file_name("multi-dep-thunk");
+        let read = |extension: &str| {
+            std::fs::read_to_string(binary_path.with_extension(extension))
+                .unwrap_or_else(|e| panic!("--emit did not write a .{} file: {}", extension, e))
+        };
+
+        let can = read("can");
+        assert!(can.contains("# Dep1"), "{}", can);
+        assert!(can.contains("main ="), "{}", can);
+
+        let monos = ["specialization", "reset-reuse", "refcount"]
+            .map(|pass| read(&format!("{}.mono", pass)));
+
+        for mono in monos.iter() {
+            assert!(mono.contains("procedure "), "{}", mono);
+        }
+
+        assert!(read("ll").contains("define "));
+
+        let bitcode = std::fs::read(binary_path.with_extension("bc")).unwrap();
+        assert!(bitcode.starts_with(b"BC\xC0\xDE"), "not LLVM bitcode");
+
+        let asm = read("s");
+        assert!(asm.contains("roc__mainForHost_1_exposed"), "{}", asm);
+
+        // the mono IR lists the procedures in the same order every time
+        let out = run_roc([CMD_BUILD, emit.as_str(), file.to_str().unwrap()], &[]);
+        assert!(out.status.success(), "bad status {:?}", out);
+
+        let again = ["specialization", "reset-reuse", "refcount"]
+            .map(|pass| read(&format!("{}.mono", pass)));
+        assert_eq!(monos, again);
+    }
+
+    #[test]
+    fn emit_dev_backend_assembly() {
+        let file = fixture_file("multi-dep-thunk", "Main.roc");
+        let emit = format!("{}=asm", EMIT_FLAG);
+        let out = run_roc(
+            [CMD_BUILD, DEV_FLAG, emit.as_str(), file.to_str().unwrap()],
+            &[],
+        );
+
+        assert!(out.status.success(), "bad status {:?}", out);
+
+        let path = file.with_file_name("multi-dep-thunk").with_extension("s");
+        let asm = std::fs::read_to_string(&path).expect("--emit did not write a .s file");
+
+        // a function per procedure, disassembled from the object file
+        assert!(asm.contains("roc__mainForHost_1_exposed:\n"), "{}", asm);
+        assert!(asm.lines().any(|line| line.contains("ret")), "{}", asm);
+    }
+
+    #[test]
+    #[serial(multi_dep_thunk)]
+    fn debug_info_points_at_the_roc_source() {
+        /// The value of a field of a metadata node, like `7` for `line` in `!DILocation(line: 7)`
+        fn field<'a>(node: &'a str, name: &str) -> Option<&'a str> {
+            let (_, fields) = node.split_once('(')?;
+
+            fields
+                .trim_end_matches(')')
+                .split(", ")
+                .find_map(|field| field.strip_prefix(name)?.strip_prefix(": "))
+        }
+
+        let file = fixture_file("multi-dep-thunk", "Main.roc");
+        let emit = format!("{}=llvm-ir", EMIT_FLAG);
+        let out = run_roc(
+            [CMD_BUILD, DEBUG_FLAG, emit.as_str(), file.to_str().unwrap()],
+            &[],
+        );
+
+        assert!(out.status.success(), "bad status {:?}", out);
+
+        let path = file.with_file_name("multi-dep-thunk").with_extension("ll");
+        let ll = std::fs::read_to_string(&path).expect("--emit did not write a .ll file");
+
+        // the metadata nodes by their id, e.g. `!12 = !DIFile(...)` under "!12"
+        let nodes: std::collections::HashMap<&str, &str> = ll
+            .lines()
+            .filter(|line| line.starts_with('!'))
+            .filter_map(|line| line.split_once(" = "))
+            .collect();
+
+        let file_name = |node: &str| {
+            let file = nodes[field(node, "file").unwrap()];
+
+            field(file, "filename")
+                .unwrap()
+                .trim_matches('"')
+                .to_string()
+        };
+
+        let subprogram = |roc_name: &str| {
+            let name = format!("\"{}\"", roc_name);
+
+            nodes
+                .iter()
+                .find(|(_, node)| {
+                    node.contains("!DISubprogram(") && field(node, "name") == Some(name.as_str())
+                })
+                .unwrap_or_else(|| panic!("no subprogram for {}:\n{}", roc_name, ll))
+        };
+
+        // procedures are named as in Roc, and defined where their body is
+        let (_, main) = subprogram("main");
+        assert_eq!(file_name(main), "Main.roc");
+        assert_eq!(field(main, "line"), Some("7"));
+
+        let (_, value1) = subprogram("Dep1.value1");
+        assert_eq!(file_name(value1), "Dep1.roc");
+        assert_eq!(field(value1, "line"), Some("4"));
+
+        // calls are located where they are made, in the scope of the procedure making them
+        let located_call = |roc_name: &str, line: &str, column: &str| {
+            let (scope, _) = subprogram(roc_name);
+
+            nodes.values().any(|node| {
+                node.contains("!DILocation(")
+                    && field(node, "line") == Some(line)
+                    && field(node, "column") == Some(column)
+                    && field(node, "scope") == Some(*scope)
+            })
+        };
+
+        assert!(located_call("main", "7", "8"), "{}", ll);
+        assert!(located_call("Dep1.value1", "4", "16"), "{}", ll);
+    }
+
+    #[test]
+    #[cfg(feature = "wasm32-cli-run")]
+    fn emit_wat() {
+        let file = examples_dir("benchmarks").join("Issue2279.roc");
+        let emit = format!("{}=wat", EMIT_FLAG);
+        let target = concatcp!(TARGET_FLAG, "=wasm32");
+        let out = run_roc(
+            [CMD_BUILD, target, emit.as_str(), file.to_str().unwrap()],
+            &[],
+        );
+
+        assert!(out.status.success(), "bad status {:?}", out);
+
+        let path = file.with_file_name("issue2279").with_extension("wat");
+        let wat = std::fs::read_to_string(&path).expect("--emit did not write a .wat file");
+
+        assert!(wat.starts_with("(module"), "{}", wat);
+        assert!(wat.contains("(func "), "{}", wat);
+    }
+
+    #[test]
+    fn test_list_names_expects_after_their_def() {
+        let file = fixture_file("expects", "Main.roc");
+        let out = run_roc([CMD_TEST, LIST_FLAG, file.to_str().unwrap()], &[]);
+
+        assert!(out.status.success(), "bad status {:?}", out);
+
+        let names: Vec<_> = out
+            .stdout
+            .lines()
+            .map(|line| line.split_whitespace().next().unwrap())
+            .collect();
+
+        assert_eq!(names, ["Arith.double", "Main", "Main.half"]);
+        assert!(out.stdout.contains("Main.roc:10:1"), "{}", out.stdout);
+    }
+
+    #[test]
+    fn test_filter_by_module() {
+        let file = fixture_file("expects", "Main.roc");
+        let out = run_roc(
+            [CMD_TEST, FILTER_FLAG, "Arith", file.to_str().unwrap()],
+            &[],
+        );
+
+        assert!(out.status.success(), "bad status {:?}", out);
+        assert!(
+            strip_colors(&out.stdout).contains("0 failed and 1 passed"),
+            "{}",
+            out.stdout
+        );
+
+        let out = run_roc([CMD_TEST, FILTER_FLAG, "Nope", file.to_str().unwrap()], &[]);
+
+        assert_eq!(out.status.code(), Some(2), "{:?}", out);
+    }
+
+    #[test]
+    fn test_format_json() {
+        let file = fixture_file("expects", "Main.roc");
+        let format = format!("{}=json", FORMAT_FLAG);
+        let out = run_roc([CMD_TEST, format.as_str(), file.to_str().unwrap()], &[]);
+
+        assert_eq!(out.status.code(), Some(1), "{:?}", out);
+
+        let results: Vec<serde_json::Value> = out
+            .stdout
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        let outcomes: Vec<_> = results
+            .iter()
+            .map(|result| {
+                (
+                    result["name"].as_str().unwrap(),
+                    result["outcome"].as_str().unwrap(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            outcomes,
+            [
+                ("Arith.double", "passed"),
+                ("Main", "passed"),
+                ("Main.half", "failed")
+            ]
+        );
+
+        let failure = &results[2];
+        assert_eq!(failure["region"]["start"]["line"], 10);
+        assert!(failure["duration_ms"].is_number());
+
+        let message = failure["message"].as_str().unwrap();
+        assert!(message.contains("EXPECT FAILED"), "{}", message);
+        assert!(message.contains("a : I64"), "{}", message);
+        assert!(message.contains("a = 5"), "{}", message);
+    }
+
+    #[test]
+    fn test_format_junit() {
+        let file = fixture_file("expects", "Main.roc");
+        let format = format!("{}=junit", FORMAT_FLAG);
+        let out = run_roc([CMD_TEST, format.as_str(), file.to_str().unwrap()], &[]);
+
+        assert_eq!(out.status.code(), Some(1), "{:?}", out);
+
+        let report = out.stdout;
+        assert!(report.starts_with("<?xml"), "{}", report);
+        assert!(
+            report.contains(r#"<testsuites name="roc test" tests="3" failures="1""#),
+            "{}",
+            report
+        );
+        assert!(
+            report.contains(r#"<testsuite name="Main" tests="2" failures="1""#),
+            "{}",
+            report
+        );
+        assert!(
+            report.contains(r#"<testcase name="Main.half""#),
+            "{}",
+            report
+        );
+        assert!(
+            report.contains("<failure message=\"expect failed at "),
+            "{}",
+            report
+        );
+        assert!(report.contains("a = 5"), "{}", report);
+    }
+
+    fn run_with_mock_effects(script: &str) -> Out {
+        let file = fixture_file("mock-effects", "Effect.roc");
+        let script = fixture_file("mock-effects", script);
+        let mock_effects = format!("{}={}", MOCK_EFFECTS_FLAG, script.to_str().unwrap());
+
+        run_roc(
+            [CMD_TEST, mock_effects.as_str(), file.to_str().unwrap()],
+            &[],
+        )
+    }
+
+    #[test]
+    fn test_mock_effects_passing() {
+        let out = run_with_mock_effects("passing.txt");
+
+        assert!(out.status.success(), "bad status {:?}", out);
+        assert!(
+            strip_colors(&out.stdout).contains("0 failed and 2 passed"),
+            "{}",
+            out.stdout
+        );
+    }
+
+    #[test]
+    fn test_mock_effects_missing() {
+        let out = run_with_mock_effects("missing.txt");
+
+        assert_eq!(out.status.code(), Some(1), "{:?}", out);
+
+        let stdout = strip_colors(&out.stdout);
+        assert!(stdout.contains("MISSING EFFECTS"), "{}", stdout);
+        assert!(stdout.contains("1 failed and 1 passed"), "{}", stdout);
+    }
+
+    #[test]
+    fn test_mock_effects_unexpected() {
+        let out = run_with_mock_effects("unexpected.txt");
+
+        assert_eq!(out.status.code(), Some(1), "{:?}", out);
+
+        let stdout = strip_colors(&out.stdout);
+        assert!(stdout.contains("UNEXPECTED EFFECT"), "{}", stdout);
+        assert!(stdout.contains("2 failed and 0 passed"), "{}", stdout);
+    }
+
+    #[test]
+    fn test_timeout_and_crash_fail_only_their_expect() {
+        let file = fixture_file("isolation", "Main.roc");
+        let timeout = format!("{}=1", TIMEOUT_FLAG);
+        let out = run_roc([CMD_TEST, timeout.as_str(), file.to_str().unwrap()], &[]);
+
+        assert_eq!(out.status.code(), Some(1), "{:?}", out);
+
+        let stdout = strip_colors(&out.stdout);
+        assert!(stdout.contains("EXPECT TIMED OUT"), "{}", stdout);
+        assert!(stdout.contains("EXPECT CRASHED"), "{}", stdout);
+        assert!(stdout.contains("2 failed and 1 passed"), "{}", stdout);
+    }
+
+    #[test]
+    fn test_jobs_run_modules_in_parallel() {
+        // Every module has an expect that only stops when it times out, so running the modules
+        // one after the other takes a timeout longer per module than running them all at once.
+        let file = fixture_file("parallel", "Main.roc");
+        let timeout = format!("{}=2", TIMEOUT_FLAG);
+
+        let time_with_jobs = |jobs: usize| {
+            let jobs = format!("{}={}", JOBS_FLAG, jobs);
+            let start = Instant::now();
+            let out = run_roc(
+                [
+                    CMD_TEST,
+                    timeout.as_str(),
+                    jobs.as_str(),
+                    file.to_str().unwrap(),
+                ],
+                &[],
+            );
+            let elapsed = start.elapsed();
+
+            assert_eq!(out.status.code(), Some(1), "{:?}", out);
+
+            let stdout = strip_colors(&out.stdout);
+            assert!(stdout.contains("3 failed and 0 passed"), "{}", stdout);
+
+            elapsed
+        };
+
+        let one_at_a_time = time_with_jobs(1);
+        let all_at_once = time_with_jobs(3);
+
+        // 6 seconds of expects against 2, with room for compiling to take longer in one run
+        assert!(
+            all_at_once + Duration::from_secs(3) < one_at_a_time,
+            "{:?} with 3 jobs, {:?} with 1",
+            all_at_once,
+            one_at_a_time
+        );
+    }
+
+    #[test]
+    fn test_jobs_report_every_expect() {
+        let file = fixture_file("expects", "Main.roc");
+        let jobs = format!("{}=2", JOBS_FLAG);
+        let out = run_roc([CMD_TEST, jobs.as_str(), file.to_str().unwrap()], &[]);
+
+        assert_eq!(out.status.code(), Some(1), "{:?}", out);
+
+        let stdout = strip_colors(&out.stdout);
+        assert!(stdout.contains("a = 5"), "{}", stdout);
+        assert!(stdout.contains("1 failed and 2 passed"), "{}", stdout);
+    }
+
+    #[test]
+    fn test_coverage_writes_lcov() {
+        let dir = tempfile::tempdir().unwrap();
+        let lcov_path = dir.path().join("lcov.info");
+
+        let file = fixture_file("coverage", "Main.roc");
+        let coverage = format!("{}={}", COVERAGE_FLAG, lcov_path.display());
+        let out = run_roc([CMD_TEST, coverage.as_str(), file.to_str().unwrap()], &[]);
+
+        assert_eq!(out.status.code(), Some(0), "{:?}", out);
+
+        let stdout = strip_colors(&out.stdout);
+        assert!(
+            stdout.contains("Main     functions 2/3 (66.7%)    branches 2/6 (33.3%)"),
+            "{}",
+            stdout
+        );
+
+        let lcov = std::fs::read_to_string(&lcov_path).unwrap();
+        assert!(lcov.contains("FNDA:1,sign"), "{}", lcov);
+        assert!(lcov.contains("FNDA:0,unused"), "{}", lcov);
+        assert!(lcov.contains("BRDA:8,0,0,0"), "{}", lcov);
+        assert!(lcov.contains("BRDA:12,0,2,1"), "{}", lcov);
+        assert!(lcov.contains("BRDA:19,1,0,1"), "{}", lcov);
+        assert!(lcov.contains("BRF:6\nBRH:2"), "{}", lcov);
+        assert!(lcov.ends_with("end_of_record\n"), "{}", lcov);
+    }
+
+    #[test]
+    fn test_doc_runs_the_examples_in_doc_comments() {
+        let file = fixture_file("doc-tests", "Main.roc");
+        let out = run_roc([CMD_TEST, DOC_FLAG, file.to_str().unwrap()], &[]);
+
+        assert_eq!(out.status.code(), Some(1), "{:?}", out);
+
+        // the failure points into the doc comment of `halve`
+        let stdout = strip_colors(&out.stdout);
+        assert!(stdout.contains("EXPECT FAILED"), "{}", stdout);
+        assert!(stdout.contains("18│"), "{}", stdout);
+        assert!(stdout.contains("##     expect halved == 4"), "{}", stdout);
+        assert!(stdout.contains("1 failed and 2 passed"), "{}", stdout);
+
+        let out = run_roc([CMD_TEST, DOC_FLAG, LIST_FLAG, file.to_str().unwrap()], &[]);
+
+        let names: Vec<_> = out
+            .stdout
+            .lines()
+            .map(|line| line.split_whitespace().next().unwrap())
+            .collect();
+
+        assert_eq!(names, ["Main.double", "Main.halve", "Main.halve"]);
+    }
+
+    #[test]
+    fn test_doc_builtins_also_runs_the_examples_in_the_builtins_docs() {
+        let file = fixture_file("doc-tests", "Main.roc");
+        let out = run_roc(
+            [CMD_TEST, DOC_FLAG, BUILTINS_FLAG, file.to_str().unwrap()],
+            &[],
+        );
+
+        // the builtins' examples all pass, which leaves the failing one of `halve`
+        let stdout = strip_colors(&out.stdout);
+        assert_eq!(out.status.code(), Some(1), "{:?}", out);
+        assert!(stdout.contains("1 failed and 10 passed"), "{}", stdout);
+
+        let out = run_roc(
+            [
+                CMD_TEST,
+                DOC_FLAG,
+                BUILTINS_FLAG,
+                LIST_FLAG,
+                file.to_str().unwrap(),
+            ],
+            &[],
+        );
+
+        let names: Vec<_> = out
+            .stdout
+            .lines()
+            .map(|line| line.split_whitespace().next().unwrap())
+            .collect();
+
+        assert!(
+            names.iter().any(|name| name.starts_with("Dict")),
+            "{:?}",
+            names
+        );
+        assert!(names.contains(&"List.splitFirst"), "{:?}", names);
+    }
+
+    #[test]
+    fn bench_compares_with_a_saved_baseline() {
+        let dir = tempfile::tempdir().unwrap();
+        let baseline_path = dir.path().join("baseline.json");
+        let baseline_path = baseline_path.to_str().unwrap();
+
+        let file = fixture_file("bench", "Main.roc");
+        let file = file.to_str().unwrap();
+
+        // short runs, so the test is quick
+        let timing = [
+            WARM_UP_FLAG,
+            "0.01",
+            MEASURE_FLAG,
+            "0.05",
+            SAMPLES_FLAG,
+            "5",
+        ];
+
+        let out = run_roc(
+            [CMD_BENCH, FILTER_FLAG, "Total"]
+                .into_iter()
+                .chain(timing)
+                .chain([SAVE_BASELINE_FLAG, baseline_path, file]),
+            &[],
+        );
+
+        assert_eq!(out.status.code(), Some(0), "{:?}", out);
+
+        let stdout = strip_colors(&out.stdout);
+        assert!(stdout.contains("Main.benchTotal    mean "), "{}", stdout);
+        assert!(stdout.contains("(5 × "), "{}", stdout);
+        assert!(!stdout.contains("benchTotalOf"), "{}", stdout);
+
+        let baseline = std::fs::read_to_string(baseline_path).unwrap();
+        assert!(
+            baseline.contains(r#""name": "Main.benchTotal""#),
+            "{}",
+            baseline
+        );
+        assert!(!baseline.contains("Main.benchSum"), "{}", baseline);
+
+        // a crashing benchmark is reported, and the others still run
+        let out = run_roc(
+            [CMD_BENCH]
+                .into_iter()
+                .chain(timing)
+                .chain([BASELINE_FLAG, baseline_path, file]),
+            &[],
+        );
+
+        assert_eq!(out.status.code(), Some(1), "{:?}", out);
+
+        let stdout = strip_colors(&out.stdout);
+        assert!(stdout.contains("compared to the baseline"), "{}", stdout);
+        assert!(stdout.contains("Main.benchSum      mean "), "{}", stdout);
+        assert!(
+            stdout.contains("Main.benchCrash    crashed: not benchmarked yet"),
+            "{}",
+            stdout
+        );
+
+        let out = run_roc([CMD_BENCH, FILTER_FLAG, "Nothing", file], &[]);
+
+        assert_eq!(out.status.code(), Some(2), "{:?}", out);
+        assert!(
+            out.stdout.contains("No benchmarks matched"),
+            "{}",
+            out.stdout
+        );
+    }
+
+    #[test]
+    fn bench_runs_modules_without_expectations() {
+        let file = fixture_file("bench-no-expects", "Main.roc");
+        let out = run_roc(
+            [
+                CMD_BENCH,
+                WARM_UP_FLAG,
+                "0.01",
+                MEASURE_FLAG,
+                "0.05",
+                SAMPLES_FLAG,
+                "5",
+                file.to_str().unwrap(),
+            ],
+            &[],
+        );
+
+        assert_eq!(out.status.code(), Some(0), "{:?}", out);
+
+        let stdout = strip_colors(&out.stdout);
+        assert!(stdout.contains("Main.benchTotal"), "{}", stdout);
+    }
+
+    #[test]
+    fn test_mock_effects_wrong_arguments() {
+        let out = run_with_mock_effects("wrong-arguments.txt");
+
+        assert_eq!(out.status.code(), Some(1), "{:?}", out);
+
+        let stdout = strip_colors(&out.stdout);
+        assert!(stdout.contains("UNEXPECTED EFFECT ARGUMENTS"), "{}", stdout);
+        assert!(stdout.contains(r#""Hello, Alice!""#), "{}", stdout);
+        assert!(stdout.contains("1 failed and 1 passed"), "{}", stdout);
+    }
+
     #[test]
     fn known_type_error() {
         check_compile_error(
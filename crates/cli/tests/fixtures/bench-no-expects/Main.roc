@@ -0,0 +1,8 @@
+interface Main
+    exposes [total]
+    imports []
+
+total : List I64 -> I64
+total = \numbers -> List.walk numbers 0 Num.add
+
+benchTotal = \{} -> total (List.repeat 3 1000)
@@ -0,0 +1,18 @@
+interface Main
+    exposes [total]
+    imports []
+
+total : List I64 -> I64
+total = \numbers -> List.walk numbers 0 Num.add
+
+benchTotal = \{} -> total (List.repeat 3 1000)
+
+benchSum = \{} -> List.sum (List.repeat 3 1000)
+
+benchCrash : {} -> I64
+benchCrash = \{} -> crash "not benchmarked yet"
+
+# not a benchmark, since it takes more than a `{}`
+benchTotalOf = \n -> total (List.repeat 3 n)
+
+expect total [1, 2] == 3
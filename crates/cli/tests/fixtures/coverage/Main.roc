@@ -0,0 +1,26 @@
+interface Main
+    exposes [sign, describe, unused]
+    imports []
+
+sign : I64 -> I64
+sign = \n ->
+    if n < 0 then
+        -1
+    else if n == 0 then
+        0
+    else
+        1
+
+expect sign 5 == 1
+
+describe : [Red, Green, Blue] -> Str
+describe = \color ->
+    when color is
+        Red -> "red"
+        Green -> "green"
+        Blue -> "blue"
+
+expect describe Red == "red"
+
+unused : U64 -> U64
+unused = \n -> n + 1
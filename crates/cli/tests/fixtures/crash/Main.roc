@@ -0,0 +1,8 @@
+app "crash"
+    packages { pf: "../multi-dep-str/platform/main.roc" }
+    imports []
+    provides [main] to pf
+
+main : Str
+main =
+    crash "the answer is not known yet"
@@ -0,0 +1,10 @@
+app "dbg"
+    packages { pf: "../multi-dep-str/platform/main.roc" }
+    imports []
+    provides [main] to pf
+
+main : Str
+main =
+    answer = dbg 6 * 7
+
+    "The answer is \(Num.toStr answer)\n"
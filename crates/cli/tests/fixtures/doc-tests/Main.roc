@@ -0,0 +1,22 @@
+interface Main
+    exposes [double, halve]
+    imports []
+
+## Doubles a number.
+##
+## ```
+## double 2 == 4
+## ```
+double : I64 -> I64
+double = \n -> n * 2
+
+## Halves a number, rounding toward zero.
+##
+##     halved = halve 7
+##
+##     expect halved == 3
+##     expect halved == 4
+halve : I64 -> I64
+halve = \n -> n // 2
+
+expect double 3 == 7
@@ -0,0 +1,8 @@
+interface Arith
+    exposes [double]
+    imports []
+
+double : I64 -> I64
+double = \x -> x * 2
+
+expect double 2 == 4
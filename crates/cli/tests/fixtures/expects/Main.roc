@@ -0,0 +1,13 @@
+interface Main
+    exposes [half]
+    imports [Arith]
+
+expect Arith.double 3 == 6
+
+half : I64 -> I64
+half = \x -> x // 2
+
+expect
+    a = half 10
+
+    a == 4
@@ -0,0 +1,18 @@
+interface Main
+    exposes [countForever, nest, double]
+    imports []
+
+countForever : U64 -> U64
+countForever = \n -> countForever (n + 1)
+
+expect countForever 0 == 0
+
+nest : U64 -> Str
+nest = \n -> Str.concat "(" (nest (n + 1))
+
+expect nest 0 == "()"
+
+double : U64 -> U64
+double = \n -> n * 2
+
+expect double 2 == 4
@@ -0,0 +1,25 @@
+hosted Effect
+    exposes [Effect, after, map, always, getLine, putLine]
+    imports []
+    generates Effect with [after, map, always]
+
+putLine : Str -> Effect {}
+
+getLine : Effect Str
+
+# performs an effect, like the host does
+run : Effect a -> a
+run = \@Effect thunk -> thunk {}
+
+expect-fx
+    name = run getLine
+
+    name == "Alice"
+
+greet : Effect {}
+greet = @Effect \{} ->
+    name = run getLine
+
+    run (putLine "Hello, \(name)!")
+
+expect-fx run greet == {}
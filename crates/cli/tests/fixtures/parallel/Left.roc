@@ -0,0 +1,8 @@
+interface Left
+    exposes [countLeft]
+    imports []
+
+countLeft : U64 -> U64
+countLeft = \n -> countLeft (n + 1)
+
+expect countLeft 0 == 0
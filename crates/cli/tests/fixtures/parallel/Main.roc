@@ -0,0 +1,8 @@
+interface Main
+    exposes [countMain]
+    imports [Left, Right]
+
+countMain : U64 -> U64
+countMain = \n -> countMain (n + 1)
+
+expect countMain 0 == Left.countLeft 0 + Right.countRight 0
@@ -0,0 +1,8 @@
+interface Right
+    exposes [countRight]
+    imports []
+
+countRight : U64 -> U64
+countRight = \n -> countRight (n + 1)
+
+expect countRight 0 == 0
@@ -494,6 +494,7 @@ fn stmt_spec<'a>(
         }
         Expect { remainder, .. } => stmt_spec(builder, interner, env, block, layout, remainder),
         ExpectFx { remainder, .. } => stmt_spec(builder, interner, env, block, layout, remainder),
+        Dbg { remainder, .. } => stmt_spec(builder, interner, env, block, layout, remainder),
         Ret(symbol) => Ok(env.symbols[symbol]),
         Refcounting(modify_rc, continuation) => match modify_rc {
             ModifyRc::Inc(symbol, _) => {
@@ -585,7 +586,7 @@ fn stmt_spec<'a>(
             let jpid = env.join_points[id];
             builder.add_jump(block, jpid, argument, ret_type_id)
         }
-        RuntimeError(_) => {
+        Crash { .. } | RuntimeError(_) => {
             let type_id = layout_spec(builder, interner, layout, &WhenRecursive::Unreachable)?;
 
             builder.add_terminate(block, type_id)
@@ -694,6 +695,7 @@ fn call_spec(
             ret_layout,
             arg_layouts,
             specialization_id,
+            ..
         } => {
             let array = specialization_id.to_bytes();
             let spec_var = CalleeSpecVar(&array);
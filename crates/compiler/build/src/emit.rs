@@ -0,0 +1,173 @@
+//! Writing intermediate representations to disk for `roc build --emit`.
+//!
+//! Unlike the `ROC_PRINT_*` debug flags, these work in release builds of the compiler, so the
+//! artifacts can be attached to bug reports.
+
+use roc_load::{EmitKind, EmittedIr};
+use roc_module::symbol::Interns;
+use std::path::{Path, PathBuf};
+use target_lexicon::{Architecture, Triple};
+
+/// The intermediate representations to emit, and the output they are written next to.
+#[derive(Debug, Clone, Default)]
+pub struct Emit {
+    pub kinds: Vec<EmitKind>,
+    /// Every artifact is written to this path, with an extension of its own
+    pub output_path: PathBuf,
+}
+
+impl Emit {
+    pub fn contains(&self, kind: EmitKind) -> bool {
+        self.kinds.contains(&kind)
+    }
+
+    pub fn path(&self, extension: &str) -> PathBuf {
+        self.output_path.with_extension(extension)
+    }
+
+    /// Write the canonical AST and mono IR collected while loading, if they were asked for.
+    pub fn write_ir(&self, emitted: &EmittedIr, interns: &Interns) {
+        if self.contains(EmitKind::Can) {
+            let mut buf = String::new();
+
+            for (module_id, declarations) in emitted.can.iter() {
+                let module_name = interns.module_name(*module_id).as_str();
+                let module_name = if module_name.is_empty() {
+                    // the App module
+                    "Application Module"
+                } else {
+                    module_name
+                };
+
+                buf.push_str(&format!("# {}\n\n", module_name));
+                buf.push_str(declarations);
+                buf.push('\n');
+            }
+
+            write(&self.path("can"), buf.as_bytes());
+        }
+
+        if self.contains(EmitKind::Mono) {
+            for (pass, procedures) in emitted.mono.iter() {
+                write(&self.path(&format!("{}.mono", pass)), procedures.as_bytes());
+            }
+        }
+    }
+
+    /// Write the WebAssembly text format of a wasm binary, if it was asked for.
+    pub fn write_wat(&self, wasm_path: &Path) {
+        if !self.contains(EmitKind::Wat) {
+            return;
+        }
+
+        let wat = wasmprinter::print_file(wasm_path).unwrap_or_else(|e| {
+            panic!(
+                "I wasn't able to print {} as WebAssembly text\n{}",
+                wasm_path.display(),
+                e
+            )
+        });
+
+        write(&self.path("wat"), wat.as_bytes());
+    }
+
+    /// Write the disassembly of an object file produced by the dev backend, if it was asked for.
+    pub fn write_object_asm(&self, target: &Triple, object_bytes: &[u8]) {
+        if self.contains(EmitKind::Asm) {
+            let asm = disassemble(target, object_bytes);
+
+            write(&self.path("s"), asm.as_bytes());
+        }
+    }
+}
+
+fn write(path: &Path, bytes: &[u8]) {
+    std::fs::write(path, bytes).unwrap_or_else(|e| {
+        panic!(
+            "I wasn't able to write to the output file {}\n{}",
+            path.display(),
+            e
+        )
+    });
+}
+
+/// Disassembles the functions in the text sections of an object file, one after the other.
+fn disassemble(target: &Triple, object_bytes: &[u8]) -> String {
+    use capstone::prelude::*;
+    use object::{Object, ObjectSection, ObjectSymbol, SectionKind, SymbolKind};
+
+    let cs = match target.architecture {
+        Architecture::X86_64 => Capstone::new()
+            .x86()
+            .mode(arch::x86::ArchMode::Mode64)
+            .syntax(arch::x86::ArchSyntax::Intel)
+            .build(),
+        Architecture::Aarch64(_) => Capstone::new()
+            .arm64()
+            .mode(arch::arm64::ArchMode::Arm)
+            .build(),
+        _ => panic!(
+            "TODO gracefully handle disassembling for {:?}",
+            target.architecture
+        ),
+    }
+    .expect("Failed to create Capstone object");
+
+    let file = object::File::parse(object_bytes).expect("failed to parse the object we just built");
+
+    let mut buf = String::new();
+
+    for section in file.sections() {
+        if section.kind() != SectionKind::Text {
+            continue;
+        }
+
+        let data = section.data().unwrap_or_default();
+
+        let mut functions: Vec<_> = file
+            .symbols()
+            .filter(|sym| {
+                sym.kind() == SymbolKind::Text && sym.section_index() == Some(section.index())
+            })
+            .map(|sym| {
+                let start = (sym.address() - section.address()) as usize;
+                let name = sym.name().unwrap_or("<unnamed>").to_string();
+
+                (start, sym.size() as usize, name)
+            })
+            .collect();
+
+        functions.sort_by_key(|(start, _, _)| *start);
+
+        for (i, (start, size, name)) in functions.iter().enumerate() {
+            // Not every object format records the size of a function; assume it goes on until
+            // the next one does.
+            let end = match functions.get(i + 1) {
+                Some((next_start, _, _)) if *size == 0 => *next_start,
+                _ if *size == 0 => data.len(),
+                _ => start + size,
+            };
+
+            buf.push_str(name);
+            buf.push_str(":\n");
+
+            let code = &data[*start..end.min(data.len())];
+            let instructions = cs
+                .disasm_all(code, *start as u64)
+                .expect("failed to disassemble");
+
+            for inst in instructions.iter() {
+                buf.push_str(&format!(
+                    "    {:6x}:  {} {}\n",
+                    inst.address(),
+                    inst.mnemonic().unwrap_or_default(),
+                    inst.op_str().unwrap_or_default()
+                ));
+            }
+
+            buf.push('\n');
+        }
+    }
+
+    buf
+}
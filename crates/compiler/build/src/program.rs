@@ -1,10 +1,12 @@
+use crate::emit::Emit;
 pub use roc_gen_llvm::llvm::build::FunctionIterator;
 use roc_gen_llvm::llvm::build::{module_from_builtins, LlvmBackendMode};
 use roc_gen_llvm::llvm::externs::add_default_roc_externs;
-use roc_load::{EntryPoint, LoadedModule, MonomorphizedModule};
+use roc_load::{EmitKind, EntryPoint, LoadedModule, MonomorphizedModule};
 use roc_module::symbol::{Interns, ModuleId};
-use roc_mono::ir::OptLevel;
+use roc_mono::ir::{OptLevel, Stmt};
 use roc_region::all::LineInfo;
+use roc_reporting::report::RenderTarget;
 use roc_solve_problem::TypeError;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
@@ -19,23 +21,30 @@ pub struct CodeGenTiming {
     pub emit_o_file: Duration,
 }
 
-pub fn report_problems_monomorphized(loaded: &mut MonomorphizedModule) -> Problems {
+pub fn report_problems_monomorphized(
+    loaded: &mut MonomorphizedModule,
+    render: RenderTarget,
+) -> Problems {
     report_problems_help(
         loaded.total_problems(),
         &loaded.sources,
         &loaded.interns,
+        &mut loaded.parse_problems,
         &mut loaded.can_problems,
         &mut loaded.type_problems,
+        render,
     )
 }
 
-pub fn report_problems_typechecked(loaded: &mut LoadedModule) -> Problems {
+pub fn report_problems_typechecked(loaded: &mut LoadedModule, render: RenderTarget) -> Problems {
     report_problems_help(
         loaded.total_problems(),
         &loaded.sources,
         &loaded.interns,
+        &mut loaded.parse_problems,
         &mut loaded.can_problems,
         &mut loaded.type_problems,
+        render,
     )
 }
 
@@ -60,8 +69,10 @@ fn report_problems_help(
     total_problems: usize,
     sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
     interns: &Interns,
+    parse_problems: &mut MutMap<ModuleId, Vec<String>>,
     can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+    render: RenderTarget,
 ) -> Problems {
     use roc_reporting::report::{
         can_problem, type_problem, Report, RocDocAllocator, Severity::*, DEFAULT_PALETTE,
@@ -83,14 +94,18 @@ fn report_problems_help(
         // Report parsing and canonicalization problems
         let alloc = RocDocAllocator::new(&src_lines, *home, interns);
 
+        // syntax errors are always errors, and come to us already rendered
+        errors.extend(parse_problems.remove(home).unwrap_or_default());
+
         let problems = can_problems.remove(home).unwrap_or_default();
 
         for problem in problems.into_iter() {
+            let region = problem.region().map(|region| lines.convert_region(region));
             let report = can_problem(&alloc, &lines, module_path.clone(), problem);
             let severity = report.severity;
             let mut buf = String::new();
 
-            report.render_color_terminal(&mut buf, &alloc, &palette);
+            report.render_at(render, region, &mut buf, &alloc, &palette);
 
             match severity {
                 Warning => {
@@ -105,11 +120,13 @@ fn report_problems_help(
         let problems = type_problems.remove(home).unwrap_or_default();
 
         for problem in problems {
+            let region = problem.region().map(|region| lines.convert_region(region));
+
             if let Some(report) = type_problem(&alloc, &lines, module_path.clone(), problem) {
                 let severity = report.severity;
                 let mut buf = String::new();
 
-                report.render_color_terminal(&mut buf, &alloc, &palette);
+                report.render_at(render, region, &mut buf, &alloc, &palette);
 
                 match severity {
                     Warning => {
@@ -123,6 +140,18 @@ fn report_problems_help(
         }
     }
 
+    if let RenderTarget::Json = render {
+        // Tools want to see everything, and each report is already on its own line.
+        for report in errors.iter().chain(warnings.iter()) {
+            print!("{}", report);
+        }
+
+        return Problems {
+            errors: errors.len(),
+            warnings: warnings.len(),
+        };
+    }
+
     let problems_reported;
 
     // Only print warnings if there are no errors
@@ -167,6 +196,8 @@ pub fn gen_from_mono_module(
     emit_debug_info: bool,
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
+    runs_under_roc_dev: bool,
+    emit: &Emit,
 ) -> CodeGenTiming {
     match opt_level {
         OptLevel::Normal | OptLevel::Size | OptLevel::Optimize => gen_from_mono_module_llvm(
@@ -177,21 +208,60 @@ pub fn gen_from_mono_module(
             app_o_file,
             opt_level,
             emit_debug_info,
+            runs_under_roc_dev,
+            emit,
         ),
-        OptLevel::Development => gen_from_mono_module_dev(
-            arena,
-            loaded,
-            target,
-            app_o_file,
-            preprocessed_host_path,
-            wasm_dev_stack_bytes,
-        ),
+        OptLevel::Development => {
+            if loaded
+                .procedures
+                .values()
+                .any(|proc| contains_dbg(&proc.body))
+            {
+                eprintln!(
+                    "Note: `dbg` does not print anything in builds that use --dev yet. Build without --dev to see its output.\n"
+                );
+            }
+
+            gen_from_mono_module_dev(
+                arena,
+                loaded,
+                target,
+                app_o_file,
+                preprocessed_host_path,
+                wasm_dev_stack_bytes,
+                emit,
+            )
+        }
+    }
+}
+
+/// The dev backends skip `dbg`, so we tell the user that its output will be missing.
+fn contains_dbg(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Dbg { .. } => true,
+        Stmt::Let(_, _, _, remainder) | Stmt::Refcounting(_, remainder) => contains_dbg(remainder),
+        Stmt::Expect { remainder, .. } | Stmt::ExpectFx { remainder, .. } => {
+            contains_dbg(remainder)
+        }
+        Stmt::Switch {
+            branches,
+            default_branch,
+            ..
+        } => {
+            branches.iter().any(|(_, _, branch)| contains_dbg(branch))
+                || contains_dbg(default_branch.1)
+        }
+        Stmt::Join {
+            body, remainder, ..
+        } => contains_dbg(body) || contains_dbg(remainder),
+        Stmt::Ret(_) | Stmt::Jump(..) | Stmt::Crash { .. } | Stmt::RuntimeError(_) => false,
     }
 }
 
 // TODO how should imported modules factor into this? What if those use builtins too?
 // TODO this should probably use more helper functions
 // TODO make this polymorphic in the llvm functions so it can be reused for another backend.
+#[allow(clippy::too_many_arguments)]
 pub fn gen_from_mono_module_llvm(
     arena: &bumpalo::Bump,
     loaded: MonomorphizedModule,
@@ -200,11 +270,13 @@ pub fn gen_from_mono_module_llvm(
     app_o_file: &Path,
     opt_level: OptLevel,
     emit_debug_info: bool,
+    runs_under_roc_dev: bool,
+    emit: &Emit,
 ) -> CodeGenTiming {
     use crate::target::{self, convert_opt_level};
     use inkwell::attributes::{Attribute, AttributeLoc};
     use inkwell::context::Context;
-    use inkwell::module::Linkage;
+    use inkwell::module::{FlagBehavior, Linkage};
     use inkwell::targets::{FileType, RelocMode};
 
     let code_gen_start = Instant::now();
@@ -250,9 +322,28 @@ pub fn gen_from_mono_module_llvm(
     }
 
     let builder = context.create_builder();
-    let (dibuilder, compile_unit) = roc_gen_llvm::llvm::build::Env::new_debug_info(module);
+    let (dibuilder, compile_unit) =
+        roc_gen_llvm::llvm::build::Env::new_debug_info_for_file(module, roc_file_path);
     let (mpm, _fpm) = roc_gen_llvm::llvm::build::construct_optimization_passes(module, opt_level);
 
+    // Without --debug the debug info is stripped below, so there is no need to read the line
+    // breaks of every source file.
+    let debug_sources = if emit_debug_info {
+        roc_gen_llvm::llvm::build::DebugSources::new(&dibuilder, &loaded.sources)
+    } else {
+        Default::default()
+    };
+
+    // `roc dev` and `roc run` render the `dbg`s and report the failing `expect`s of the program.
+    // Other programs print where their `dbg`s are, and optimized ones leave them out.
+    let mode = match opt_level {
+        OptLevel::Normal | OptLevel::Development if runs_under_roc_dev => {
+            LlvmBackendMode::BinaryDev
+        }
+        OptLevel::Normal | OptLevel::Development => LlvmBackendMode::Binary,
+        OptLevel::Size | OptLevel::Optimize => LlvmBackendMode::BinaryOptimized,
+    };
+
     // Compile and add all the Procs before adding main
     let env = roc_gen_llvm::llvm::build::Env {
         arena,
@@ -260,11 +351,12 @@ pub fn gen_from_mono_module_llvm(
         builder: &builder,
         dibuilder: &dibuilder,
         compile_unit: &compile_unit,
+        debug_sources,
         context: &context,
         interns: loaded.interns,
         module,
         target_info,
-        mode: LlvmBackendMode::Binary,
+        mode,
         exposed_to_host: loaded.exposed_to_host.values.keys().copied().collect(),
     };
 
@@ -289,8 +381,23 @@ pub fn gen_from_mono_module_llvm(
 
     env.dibuilder.finalize();
 
-    // we don't use the debug info, and it causes weird errors.
-    module.strip_debug_info();
+    if emit_debug_info {
+        // LLVM only emits debug info for modules that say which version of it they contain
+        const DEBUG_INFO_VERSION: &str = "Debug Info Version";
+
+        if module.get_flag(DEBUG_INFO_VERSION).is_none() {
+            let version = inkwell::debug_info::debug_metadata_version();
+
+            module.add_basic_value_flag(
+                DEBUG_INFO_VERSION,
+                FlagBehavior::Warning,
+                context.i32_type().const_int(version as u64, false),
+            );
+        }
+    } else {
+        // we don't use the debug info, and it causes weird errors.
+        module.strip_debug_info();
+    }
 
     // Uncomment this to see the module's optimized LLVM instruction output:
     // env.module.print_to_stderr();
@@ -312,101 +419,72 @@ pub fn gen_from_mono_module_llvm(
     // Uncomment this to see the module's optimized LLVM instruction output:
     // env.module.print_to_stderr();
 
-    let code_gen = code_gen_start.elapsed();
-    let emit_o_file_start = Instant::now();
+    if emit.contains(EmitKind::LlvmIr) {
+        let path = emit.path("ll");
 
-    // annotate the LLVM IR output with debug info
-    // so errors are reported with the line number of the LLVM source
-    if emit_debug_info {
-        module.strip_debug_info();
-
-        let mut app_ll_dbg_file = PathBuf::from(roc_file_path);
-        app_ll_dbg_file.set_extension("dbg.ll");
+        env.module.print_to_file(&path).unwrap_or_else(|e| {
+            panic!(
+                "I wasn't able to write to the output file {}\n{}",
+                path.display(),
+                e
+            )
+        });
+    }
 
-        let mut app_bc_file = PathBuf::from(roc_file_path);
-        app_bc_file.set_extension("bc");
+    if emit.contains(EmitKind::LlvmBc) {
+        let path = emit.path("bc");
 
-        use std::process::Command;
+        if !env.module.write_bitcode_to_path(&path) {
+            panic!(
+                "I wasn't able to write to the output file {}",
+                path.display()
+            );
+        }
+    }
 
-        // write the ll code to a file, so we can modify it
-        module.print_to_file(&app_ll_file).unwrap();
+    if emit.contains(EmitKind::Asm) {
+        let target_machine =
+            target::target_machine(target, convert_opt_level(opt_level), RelocMode::PIC).unwrap();
+        let path = emit.path("s");
+
+        // Code generation changes the module it runs on, so leave the original for the .o file.
+        let module = env.module.clone();
+
+        target_machine
+            .write_to_file(&module, FileType::Assembly, &path)
+            .unwrap_or_else(|e| {
+                panic!(
+                    "I wasn't able to write to the output file {}\n{}",
+                    path.display(),
+                    e
+                )
+            });
+    }
 
-        // run the debugir https://github.com/vaivaswatha/debugir tool
-        match Command::new("debugir")
-            .args(&["-instnamer", app_ll_file.to_str().unwrap()])
-            .output()
-        {
-            Ok(_) => {}
-            Err(error) => {
-                use std::io::ErrorKind;
-                match error.kind() {
-                    ErrorKind::NotFound => panic!(
-                        r"I could not find the `debugir` tool on the PATH, install it from https://github.com/vaivaswatha/debugir"
-                    ),
-                    _ => panic!("{:?}", error),
-                }
-            }
-        }
+    let code_gen = code_gen_start.elapsed();
+    let emit_o_file_start = Instant::now();
 
-        use target_lexicon::Architecture;
-        match target.architecture {
-            Architecture::X86_64
-            | Architecture::X86_32(_)
-            | Architecture::Aarch64(_)
-            | Architecture::Wasm32 => {
-                let ll_to_bc = Command::new("llvm-as")
-                    .args(&[
-                        app_ll_dbg_file.to_str().unwrap(),
-                        "-o",
-                        app_bc_file.to_str().unwrap(),
-                    ])
-                    .output()
-                    .unwrap();
-
-                assert!(ll_to_bc.stderr.is_empty(), "{:#?}", ll_to_bc);
-
-                let llc_args = &[
-                    "-relocation-model=pic",
-                    "-filetype=obj",
-                    app_bc_file.to_str().unwrap(),
-                    "-o",
-                    app_o_file.to_str().unwrap(),
-                ];
-
-                // write the .o file. Note that this builds the .o for the local machine,
-                // and ignores the `target_machine` entirely.
-                //
-                // different systems name this executable differently, so we shotgun for
-                // the most common ones and then give up.
-                let bc_to_object = Command::new("llc").args(llc_args).output().unwrap();
-
-                assert!(bc_to_object.stderr.is_empty(), "{:#?}", bc_to_object);
-            }
-            _ => unreachable!(),
+    // Emit the .o file
+    use target_lexicon::Architecture;
+    match target.architecture {
+        Architecture::X86_64 | Architecture::X86_32(_) | Architecture::Aarch64(_) => {
+            let reloc = RelocMode::PIC;
+            let target_machine =
+                target::target_machine(target, convert_opt_level(opt_level), reloc).unwrap();
+
+            target_machine
+                .write_to_file(env.module, FileType::Object, app_o_file)
+                .expect("Writing .o file failed");
         }
-    } else {
-        // Emit the .o file
-        use target_lexicon::Architecture;
-        match target.architecture {
-            Architecture::X86_64 | Architecture::X86_32(_) | Architecture::Aarch64(_) => {
-                let reloc = RelocMode::PIC;
-                let target_machine =
-                    target::target_machine(target, convert_opt_level(opt_level), reloc).unwrap();
-
-                target_machine
-                    .write_to_file(env.module, FileType::Object, app_o_file)
-                    .expect("Writing .o file failed");
-            }
-            Architecture::Wasm32 => {
-                // Useful for debugging
-                // module.print_to_file(app_ll_file);
-                module.write_bitcode_to_path(app_o_file);
-            }
-            _ => panic!(
-                "TODO gracefully handle unsupported architecture: {:?}",
-                target.architecture
-            ),
+        Architecture::Wasm32 => {
+            // Useful for debugging
+            // module.print_to_file(app_ll_file);
+            module.write_bitcode_to_path(app_o_file);
         }
+        _ => panic!(
+            "TODO gracefully handle unsupported architecture: {:?}",
+            target.architecture
+        ),
     }
 
     let emit_o_file = emit_o_file_start.elapsed();
@@ -424,6 +502,7 @@ pub fn gen_from_mono_module_dev(
     app_o_file: &Path,
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
+    emit: &Emit,
 ) -> CodeGenTiming {
     use target_lexicon::Architecture;
 
@@ -436,7 +515,7 @@ pub fn gen_from_mono_module_dev(
             wasm_dev_stack_bytes,
         ),
         Architecture::X86_64 | Architecture::Aarch64(_) => {
-            gen_from_mono_module_dev_assembly(arena, loaded, target, app_o_file)
+            gen_from_mono_module_dev_assembly(arena, loaded, target, app_o_file, emit)
         }
         _ => todo!(),
     }
@@ -450,12 +529,13 @@ pub fn gen_from_mono_module_dev(
     app_o_file: &Path,
     _host_input_path: &Path,
     _wasm_dev_stack_bytes: Option<u32>,
+    emit: &Emit,
 ) -> CodeGenTiming {
     use target_lexicon::Architecture;
 
     match target.architecture {
         Architecture::X86_64 | Architecture::Aarch64(_) => {
-            gen_from_mono_module_dev_assembly(arena, loaded, target, app_o_file)
+            gen_from_mono_module_dev_assembly(arena, loaded, target, app_o_file, emit)
         }
         _ => todo!(),
     }
@@ -537,6 +617,7 @@ fn gen_from_mono_module_dev_assembly(
     loaded: MonomorphizedModule,
     target: &target_lexicon::Triple,
     app_o_file: &Path,
+    emit: &Emit,
 ) -> CodeGenTiming {
     let code_gen_start = Instant::now();
 
@@ -569,7 +650,9 @@ fn gen_from_mono_module_dev_assembly(
     let module_out = module_object
         .write()
         .expect("failed to build output object");
-    std::fs::write(&app_o_file, module_out).expect("failed to write object to file");
+    std::fs::write(&app_o_file, &module_out).expect("failed to write object to file");
+
+    emit.write_object_asm(target, &module_out);
 
     let emit_o_file = emit_o_file_start.elapsed();
 
@@ -0,0 +1,7 @@
+// `roc bench` keeps the result of each run of a benchmark in an expect on this, which is always
+// true, but read through a volatile pointer so the optimizer cannot know that
+var BENCH_KEEP: bool = true;
+
+pub fn benchKeep() callconv(.C) bool {
+    return @ptrCast(*volatile bool, &BENCH_KEEP).*;
+}
@@ -0,0 +1,18 @@
+// `roc test --coverage` counts the runs of functions and branches: the code generated for each of
+// them reports its module and region (start offset in the high bits, end offset in the low ones)
+const CoverageHandler = fn (module_id: u32, region: u64) callconv(.C) void;
+
+var COVERAGE_HANDLER: ?CoverageHandler = null;
+
+pub fn setCoverageHandler(handler: CoverageHandler) callconv(.C) usize {
+    COVERAGE_HANDLER = handler;
+
+    // the rust side expects that a pointer is returned
+    return 0;
+}
+
+pub fn coverageHit(module_id: u32, region: u64) callconv(.C) void {
+    if (COVERAGE_HANDLER) |handler| {
+        handler(module_id, region);
+    }
+}
@@ -1,10 +1,13 @@
 const std = @import("std");
+const builtin = @import("builtin");
+const RocStr = @import("str.zig").RocStr;
 
-const SIGUSR1: c_int = 10;
+const SIGUSR1: c_int = if (builtin.os.tag == .macos) 30 else 10;
 
 const O_RDWR: c_int = 2;
 const O_CREAT: c_int = 64;
 
+pub const PROT_READ: c_int = 1;
 pub const PROT_WRITE: c_int = 2;
 pub const MAP_SHARED: c_int = 0x0001;
 
@@ -21,3 +24,149 @@ pub fn setSharedBuffer(ptr: [*]u8, length: usize) callconv(.C) usize {
 pub fn expectFailedStart() callconv(.C) [*]u8 {
     return SHARED_BUFFER.ptr;
 }
+
+// `roc dev` runs a program in a process of its own, and shares a memory object with it that is
+// named after the `roc dev` process. The program writes the frames of its `dbg`s, failed `expect`s
+// and `crash`es there, and signals `roc dev` to report them. Hosts provide the primitives for this,
+// as thin wrappers of getppid, shm_open, mmap and kill, when `roc dev` or `roc run` build the
+// program without optimizations.
+extern fn roc_getppid() callconv(.C) c_int;
+extern fn roc_shm_open(name: [*:0]const u8, oflag: c_int, mode: c_uint) callconv(.C) c_int;
+extern fn roc_mmap(addr: ?*anyopaque, length: usize, prot: c_int, flags: c_int, fd: c_int, offset: i64) callconv(.C) *anyopaque;
+extern fn roc_send_signal(pid: c_int, sig: c_int) callconv(.C) c_int;
+
+const DEV_SUPPORTED = builtin.os.tag == .macos or builtin.os.tag == .linux;
+
+// the same size as the buffer `roc test` uses
+const DEV_BUFFER_SIZE: usize = 1024;
+
+// when the program does not run under `roc dev`, its frames are written here and dropped
+var LOCAL_BUFFER: [DEV_BUFFER_SIZE]u8 align(@alignOf(usize)) = undefined;
+var DEV_BUFFER: ?[]u8 = null;
+var RUNS_UNDER_ROC_DEV: bool = false;
+
+pub fn expectFailedStartSharedFile() callconv(.C) [*]u8 {
+    return devBuffer().ptr;
+}
+
+fn devBuffer() []u8 {
+    if (DEV_BUFFER) |buffer| {
+        return buffer;
+    }
+
+    if (openDevBuffer()) |buffer| {
+        DEV_BUFFER = buffer;
+        RUNS_UNDER_ROC_DEV = true;
+    } else {
+        DEV_BUFFER = &LOCAL_BUFFER;
+        clearFrames();
+    }
+
+    return DEV_BUFFER.?;
+}
+
+fn openDevBuffer() ?[]u8 {
+    if (DEV_SUPPORTED) {
+        var name_buffer: [64]u8 = undefined;
+        const name = std.fmt.bufPrintZ(&name_buffer, "/roc_expect_buffer_{d}", .{roc_getppid()}) catch unreachable;
+
+        // `roc dev` has created the object; there is none when the program runs on its own
+        const fd = roc_shm_open(name.ptr, O_RDWR, 0);
+        if (fd < 0) {
+            return null;
+        }
+
+        const ptr = roc_mmap(null, DEV_BUFFER_SIZE, PROT_READ | PROT_WRITE, MAP_SHARED, fd, 0);
+        if (@ptrToInt(ptr) == std.math.maxInt(usize)) {
+            return null;
+        }
+
+        return @ptrCast([*]u8, ptr)[0..DEV_BUFFER_SIZE];
+    } else {
+        return null;
+    }
+}
+
+// The buffer starts with the number of frames in it, followed by the offset of the next frame.
+fn frameCount() *usize {
+    return @ptrCast(*usize, @alignCast(@alignOf(usize), devBuffer().ptr));
+}
+
+fn clearFrames() void {
+    const state = @ptrCast([*]usize, frameCount());
+
+    state[1] = 2 * @sizeOf(usize);
+    @atomicStore(usize, &state[0], 0, .SeqCst);
+}
+
+// Under `roc dev`, this spin-waits on the thread that wrote the frame until `roc dev` has reported
+// it and cleared the buffer. There is only one buffer, so hosts must not run Roc code that writes
+// frames on several threads at once.
+pub fn expectFailedFinalize() callconv(.C) void {
+    if (DEV_SUPPORTED and RUNS_UNDER_ROC_DEV) {
+        _ = roc_send_signal(roc_getppid(), SIGUSR1);
+
+        // `roc dev` clears the frames once it has reported them
+        while (@atomicLoad(usize, frameCount(), .SeqCst) != 0) {
+            std.atomic.spinLoopHint();
+        }
+    } else {
+        clearFrames();
+    }
+}
+
+// Hosts print the value of a `dbg`: where it is in the source, and the value as Roc would write it
+extern fn roc_dbg(location: *const RocStr, value: *const RocStr) callconv(.C) void;
+
+// the frame starts after the frame count and the offset of the next frame
+const FRAME_OFFSET: usize = 2 * @sizeOf(usize);
+
+// A `dbg` has written its frame, which `roc dev` renders: it writes the lengths of the location
+// and of the value over the frame, followed by their bytes, and moves the offset of the next frame
+// past them. It leaves that offset alone when it cannot render the frame.
+pub fn dbgFinalize() callconv(.C) void {
+    const frame = devBuffer()[FRAME_OFFSET..];
+
+    // the region of the `dbg` and the id of its module, in case `roc dev` does not render them
+    const region_start = std.mem.readIntNative(u32, frame[0..4]);
+    const region_end = std.mem.readIntNative(u32, frame[4..8]);
+    const module_id = std.mem.readIntNative(u32, frame[8..12]);
+
+    expectFailedFinalize();
+
+    const state = @ptrCast([*]usize, frameCount());
+
+    if (RUNS_UNDER_ROC_DEV and state[1] > FRAME_OFFSET) {
+        const location_len = std.mem.readIntNative(usize, frame[0..@sizeOf(usize)]);
+        const value_len = std.mem.readIntNative(usize, frame[@sizeOf(usize) .. 2 * @sizeOf(usize)]);
+        const text = frame[2 * @sizeOf(usize) ..];
+
+        // this leaves the text of the frame alone
+        clearFrames();
+
+        printDbg(text[0..location_len], text[location_len .. location_len + value_len]);
+    } else {
+        clearFrames();
+
+        dbgLocation(module_id, region_start, region_end);
+    }
+}
+
+// A `dbg` in a program that `roc dev` does not run, or on a target it cannot share values from:
+// the host prints where the `dbg` is, without its value.
+pub fn dbgLocation(module_id: u32, region_start: u32, region_end: u32) callconv(.C) void {
+    var location_buffer: [64]u8 = undefined;
+    const location = std.fmt.bufPrint(&location_buffer, "module {d}, bytes {d}-{d}", .{ module_id, region_start, region_end }) catch unreachable;
+
+    printDbg(location, "(only `roc dev` and `roc run` can show this value)");
+}
+
+fn printDbg(location_bytes: []const u8, value_bytes: []const u8) void {
+    var location = RocStr.fromSlice(location_bytes);
+    var value = RocStr.fromSlice(value_bytes);
+
+    roc_dbg(&location, &value);
+
+    location.deinit();
+    value.deinit();
+}
@@ -3,6 +3,9 @@ const builtin = @import("builtin");
 const math = std.math;
 const utils = @import("utils.zig");
 const expect = @import("expect.zig");
+const mock = @import("mock.zig");
+const coverage = @import("coverage.zig");
+const bench = @import("bench.zig");
 
 const ROC_BUILTINS = "roc_builtins";
 const NUM = "num";
@@ -166,11 +169,31 @@ comptime {
 
     @export(utils.panic, .{ .name = "roc_builtins.utils." ++ "panic", .linkage = .Weak });
 
+    // prints where a `dbg` is, when nothing renders its value
+    exportUtilsFn(expect.dbgLocation, "dbg_location");
+
     if (builtin.target.cpu.arch != .wasm32) {
         exportUtilsFn(expect.expectFailedStart, "expect_failed_start");
 
+        // share the frames of a program that `roc dev` runs with it
+        exportUtilsFn(expect.expectFailedStartSharedFile, "expect_failed_start_shared_file");
+        exportUtilsFn(expect.expectFailedFinalize, "expect_failed_finalize");
+        exportUtilsFn(expect.dbgFinalize, "dbg_finalize");
+
         // sets the buffer used for expect failures
         @export(expect.setSharedBuffer, .{ .name = "set_shared_buffer", .linkage = .Weak });
+
+        // lets `roc test --mock-effects` answer the calls of hosted functions
+        @export(mock.setMockEffectHandler, .{ .name = "set_mock_effect_handler", .linkage = .Weak });
+        @export(mock.mockNextCall, .{ .name = "roc_mock_next_call", .linkage = .Weak });
+        @export(mock.mockUnexpectedArguments, .{ .name = "roc_mock_unexpected_arguments", .linkage = .Weak });
+
+        // lets `roc test --coverage` count the runs of functions and branches
+        @export(coverage.setCoverageHandler, .{ .name = "set_coverage_handler", .linkage = .Weak });
+        @export(coverage.coverageHit, .{ .name = "roc_coverage_hit", .linkage = .Weak });
+
+        // keeps `roc bench` from optimizing away the results of the benchmarks it runs
+        @export(bench.benchKeep, .{ .name = "roc_bench_keep", .linkage = .Weak });
     }
 
     if (builtin.target.cpu.arch == .aarch64) {
@@ -0,0 +1,44 @@
+const std = @import("std");
+const utils = @import("utils.zig");
+const RocStr = @import("str.zig").RocStr;
+
+// `roc test --mock-effects` runs effects against a script rather than a platform: every call of
+// a hosted function asks this handler for the key of the scripted call it should respond as
+const MockEffectHandler = fn (effect: *const RocStr) callconv(.C) u64;
+
+// and tells this one when its arguments are not the ones that call expects
+const MockArgumentsHandler = fn (effect: *const RocStr, key: u64) callconv(.C) void;
+
+var MOCK_EFFECT_HANDLER: ?MockEffectHandler = null;
+var MOCK_ARGUMENTS_HANDLER: ?MockArgumentsHandler = null;
+
+// the handler returns this when the script did not expect the call
+const UNEXPECTED_CALL: u64 = std.math.maxInt(u64);
+
+pub fn setMockEffectHandler(handler: MockEffectHandler, arguments_handler: MockArgumentsHandler) callconv(.C) usize {
+    MOCK_EFFECT_HANDLER = handler;
+    MOCK_ARGUMENTS_HANDLER = arguments_handler;
+
+    // the rust side expects that a pointer is returned
+    return 0;
+}
+
+pub fn mockNextCall(effect: *const RocStr) callconv(.C) u64 {
+    const key = if (MOCK_EFFECT_HANDLER) |handler| handler(effect) else UNEXPECTED_CALL;
+
+    if (key == UNEXPECTED_CALL) {
+        utils.panic("an effect was called that the mock effects script did not expect", 0);
+    }
+
+    return key;
+}
+
+pub fn mockUnexpectedArguments(effect: *const RocStr, key: u64) callconv(.C) u64 {
+    if (MOCK_ARGUMENTS_HANDLER) |handler| {
+        handler(effect, key);
+    }
+
+    utils.panic("an effect was called with arguments that the mock effects script did not expect", 0);
+
+    return key;
+}
@@ -1,9 +1,34 @@
 interface Bool
-    exposes [Bool, and, or, not, isEq, isNotEq]
+    exposes [Bool, Eq, isEq, isNotEq, and, or, not]
     imports []
 
 Bool : [True, False]
 
+## Defines a type that can be compared for total equality.
+##
+## Total equality means that all values of the type can be compared to each
+## other, and two values `a`, `b` are identical if and only if `isEq a b` is
+## `True`. `a == b` is shorthand for `Bool.isEq a b`.
+##
+## Not all types support total equality. For example, functions cannot be
+## compared for equality.
+##
+## Structural types automatically implement [Eq] when all of the types they
+## contain implement [Eq], and their equality works as follows:
+##
+## 1. Tags are equal if they have the same tag name, and also their contents (if any) are equal.
+## 2. Records are equal if all their fields are equal.
+## 3. Collections ([Str], [List], [Dict], and [Set]) are equal if they are the same length, and also all their corresponding elements are equal.
+## 4. [Num](Num#Num) values are equal if their numbers are equal, with one exception: if both arguments to `isEq` are *NaN*, then `isEq` returns `False`. See `Num.isNaN` for more about *NaN*.
+##
+## An opaque type can provide its own notion of equality by implementing [Eq]:
+##
+##     Id := U64 has [Eq { isEq: idIsEq }]
+##
+##     idIsEq = \@Id a, @Id b -> a == b
+Eq has
+    isEq : a, a -> Bool | a has Eq
+
 ## Returns `True` when given `True` and `True`, and `False` when either argument is `False`.
 ##
 ## `a && b` is shorthand for `Bool.and a b`
@@ -59,25 +84,16 @@ or : Bool, Bool -> Bool
 ## Returns `False` when given `True`, and vice versa.
 not : Bool -> Bool
 
-## Returns `True` if the two values are *structurally equal*, and `False` otherwise.
-##
-## `a == b` is shorthand for `Bool.isEq a b`
-##
-## Structural equality works as follows:
-##
-## 1. Tags are equal if they have the same tag name, and also their contents (if any) are equal.
-## 2. Records are equal if all their fields are equal.
-## 3. Collections ([Str], [List], [Dict], and [Set]) are equal if they are the same length, and also all their corresponding elements are equal.
-## 4. [Num](Num#Num) values are equal if their numbers are equal, with one exception: if both arguments to `isEq` are *NaN*, then `isEq` returns `False`. See `Num.isNaN` for more about *NaN*.
-##
-## Note that `isEq` takes `'val` instead of `val`, which means `isEq` does not
-## accept arguments whose types contain functions.
-isEq : a, a -> Bool
-
 ## Calls [isEq] on the given values, then calls [not] on the result.
 ##
 ## `a != b` is shorthand for `Bool.isNotEq a b`
-##
-## Note that `isNotEq` takes `'val` instead of `val`, which means `isNotEq` does not
-## accept arguments whose types contain functions.
-isNotEq : a, a -> Bool
+isNotEq : a, a -> Bool | a has Eq
+isNotEq = \a, b -> not (isEq a b)
+
+# INTERNAL COMPILER USE ONLY: compares two values for structural equality, without consulting
+# any [Eq] implementations. This is what derived implementations of [isEq] use.
+structuralEq : a, a -> Bool
+
+# INTERNAL COMPILER USE ONLY: compares two values for structural inequality, without consulting
+# any [Eq] implementations.
+structuralNotEq : a, a -> Bool
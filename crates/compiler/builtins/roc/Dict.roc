@@ -17,13 +17,17 @@ interface Dict
         removeAll,
     ]
     imports [
-        Bool.{ Bool },
+        Bool.{ Bool, Eq },
         Result.{ Result },
         List,
+        Hash.{ Hash, Hasher },
     ]
 
 ## A [dictionary](https://en.wikipedia.org/wiki/Associative_array) that lets you can associate keys with values.
 ##
+## Dictionaries are hash tables, so inserting, looking up, and removing a key all take
+## amortized constant time. Keys must have the [Hash] ability.
+##
 ## ### Inserting
 ##
 ## The most basic way to use a dictionary is to start with an empty one and then:
@@ -52,11 +56,17 @@ interface Dict
 ##
 ## We can remove an element from the dictionary, like so:
 ##
-##     populationByCity
+##     cities =
+##         Dict.empty
+##         |> Dict.insert "London" 8_961_989
+##         |> Dict.insert "Philadelphia" 1_603_797
+##         |> Dict.insert "Shanghai" 24_870_895
+##         |> Dict.insert "Delhi" 16_787_941
+##         |> Dict.insert "Amsterdam" 872_680
 ##         |> Dict.remove "Philadelphia"
 ##         |> Dict.keys
-##         ==
-##         ["London", "Amsterdam", "Shanghai", "Delhi"]
+##
+##     expect cities == ["London", "Amsterdam", "Shanghai", "Delhi"]
 ##
 ## Notice that the order changed! Philadelphia has been not only removed from the list, but Amsterdam - the last
 ## entry we inserted - has been moved into the spot where Philadelphia was previously. This is exactly what
@@ -67,114 +77,369 @@ interface Dict
 ##
 ## ### Equality
 ##
-## When comparing two dictionaries for equality, they are `==` only if their both their contents and their
-## orderings match. This preserves the property that if `dict1 == dict2`, you should be able to rely on
-## `fn dict1 == fn dict2` also being `True`, even if `fn` relies on the dictionary's ordering.
-Dict k v := List [Pair k v]
+## Two dictionaries are `==` when they have the same keys, and each key is associated with
+## `==` values in both. The order in which the entries were inserted doesn't matter, so
+## functions that depend on that order, like [Dict.keys], may give different answers for
+## dictionaries that are `==`.
+Dict k v := {
+    # Entries are stored in insertion order in `data`. `slots` is an open-addressing table with
+    # linear probing; each slot is either `0` (vacant) or one more than the index of its entry in
+    # `data`. The table is kept at most three-quarters full, so probing always ends at a vacant slot.
+    data : List [Pair k v],
+    slots : List Nat,
+}
+     has [Eq { isEq: dictIsEq }]
 
 ## An empty dictionary.
 empty : Dict k v
-empty = @Dict []
+empty = @Dict { data: [], slots: [] }
 
 withCapacity : Nat -> Dict k v
-withCapacity = \n -> @Dict (List.withCapacity n)
+withCapacity = \n ->
+    @Dict { data: List.withCapacity n, slots: List.repeat 0 (slotCountFor n) }
+
+get : Dict k v, k -> Result v [KeyNotFound]* | k has Hash
+get = \@Dict { data, slots }, needle ->
+    when find data slots needle is
+        Found _ dataIndex ->
+            when List.get data dataIndex is
+                Ok (Pair _ v) ->
+                    Ok v
 
-get : Dict k v, k -> Result v [KeyNotFound]*
-get = \@Dict list, needle ->
-    when List.findFirst list (\Pair key _ -> key == needle) is
-        Ok (Pair _ v) ->
-            Ok v
+                Err OutOfBounds ->
+                    Err KeyNotFound
 
-        Err NotFound ->
+        Vacant _ ->
             Err KeyNotFound
 
 walk : Dict k v, state, (state, k, v -> state) -> state
-walk = \@Dict list, initialState, transform ->
-    List.walk list initialState (\state, Pair k v -> transform state k v)
+walk = \@Dict { data }, initialState, transform ->
+    List.walk data initialState (\state, Pair k v -> transform state k v)
 
-insert : Dict k v, k, v -> Dict k v
-insert = \@Dict list, k, v ->
-    when List.findFirstIndex list (\Pair key _ -> key == k) is
-        Err NotFound ->
-            insertFresh (@Dict list) k v
+insert : Dict k v, k, v -> Dict k v | k has Hash
+insert = \@Dict { data, slots }, key, value ->
+    when find data slots key is
+        Found _ dataIndex ->
+            @Dict { data: List.set data dataIndex (Pair key value), slots }
 
-        Ok index ->
-            list
-            |> List.set index (Pair k v)
-            |> @Dict
+        Vacant slotIndex ->
+            newLen = List.len data + 1
+
+            if newLen * 4 > List.len slots * 3 then
+                newData = List.append data (Pair key value)
+
+                @Dict { data: newData, slots: rebuildSlots newData (slotCountFor newLen) }
+            else
+                @Dict {
+                    data: List.append data (Pair key value),
+                    slots: List.set slots slotIndex newLen,
+                }
 
 len : Dict k v -> Nat
-len = \@Dict list ->
-    List.len list
-
-remove : Dict k v, k -> Dict k v
-remove = \@Dict list, key ->
-    when List.findFirstIndex list (\Pair k _ -> k == key) is
-        Err NotFound ->
-            @Dict list
-
-        Ok index ->
-            lastIndex = List.len list - 1
-
-            list
-            |> List.swap index lastIndex
-            |> List.dropLast
-            |> @Dict
-
-contains : Dict k v, k -> Bool
-contains = \@Dict list, needle ->
-    step = \_, Pair key _val ->
-        if key == needle then
-            Break {}
-        else
-            Continue {}
+len = \@Dict { data } ->
+    List.len data
+
+dictIsEq : Dict k v, Dict k v -> Bool | k has Hash, v has Eq
+dictIsEq = \@Dict { data }, ys ->
+    if List.len data == len ys then
+        List.all data \Pair k v ->
+            when get ys k is
+                Ok yv -> yv == v
+                Err KeyNotFound -> False
+    else
+        False
+
+remove : Dict k v, k -> Dict k v | k has Hash
+remove = \@Dict { data, slots }, key ->
+    when find data slots key is
+        Vacant _ ->
+            @Dict { data, slots }
+
+        Found slotIndex dataIndex ->
+            lastIndex = List.len data - 1
+            slotCount = List.len slots
+
+            vacatedSlots =
+                slots
+                |> List.set slotIndex 0
+                |> shiftBack data slotIndex (nextSlot slotIndex slotCount)
+
+            if dataIndex == lastIndex then
+                @Dict { data: List.dropLast data, slots: vacatedSlots }
+            else
+                # The last entry moves into the spot we vacated, so the slot that refers to it
+                # must be repointed.
+                movedSlots =
+                    when List.get data lastIndex is
+                        Ok (Pair movedKey _) ->
+                            movedSlot = slotOfEntry vacatedSlots (homeSlot movedKey slotCount) (lastIndex + 1)
 
-    when List.iterate list {} step is
-        Continue _ -> False
-        Break _ -> True
+                            List.set vacatedSlots movedSlot (dataIndex + 1)
 
-single : k, v -> Dict k v
+                        Err OutOfBounds ->
+                            vacatedSlots
+
+                @Dict {
+                    data: data |> List.swap dataIndex lastIndex |> List.dropLast,
+                    slots: movedSlots,
+                }
+
+contains : Dict k v, k -> Bool | k has Hash
+contains = \@Dict { data, slots }, needle ->
+    when find data slots needle is
+        Found _ _ -> True
+        Vacant _ -> False
+
+single : k, v -> Dict k v | k has Hash
 single = \key, value ->
-    @Dict [Pair key value]
+    insert empty key value
 
 ## Returns a [List] of the dictionary's keys.
 keys : Dict k v -> List k
-keys = \@Dict list ->
-    List.map list (\Pair k _ -> k)
+keys = \@Dict { data } ->
+    List.map data (\Pair k _ -> k)
 
 ## Returns a [List] of the Dict's values
 values : Dict k v -> List v
-values = \@Dict list ->
-    List.map list (\Pair _ v -> v)
+values = \@Dict { data } ->
+    List.map data (\Pair _ v -> v)
 
 # union : Dict k v, Dict k v -> Dict k v
-insertAll : Dict k v, Dict k v -> Dict k v
-insertAll = \xs, @Dict ys ->
-    List.walk ys xs (\state, Pair k v -> Dict.insertIfVacant state k v)
+insertAll : Dict k v, Dict k v -> Dict k v | k has Hash
+insertAll = \xs, @Dict { data: ys } ->
+    List.walk ys xs (\state, Pair k v -> insertIfVacant state k v)
 
 # intersection : Dict k v, Dict k v -> Dict k v
-keepShared : Dict k v, Dict k v -> Dict k v
-keepShared = \@Dict xs, ys ->
-    List.keepIf xs (\Pair k _ -> Dict.contains ys k)
-    |> @Dict
+keepShared : Dict k v, Dict k v -> Dict k v | k has Hash
+keepShared = \xs, ys ->
+    walk xs empty \state, k, v ->
+        if contains ys k then
+            insert state k v
+        else
+            state
 
 # difference : Dict k v, Dict k v -> Dict k v
-removeAll : Dict k v, Dict k v -> Dict k v
-removeAll = \xs, @Dict ys ->
-    List.walk ys xs (\state, Pair k _ -> Dict.remove state k)
+removeAll : Dict k v, Dict k v -> Dict k v | k has Hash
+removeAll = \xs, @Dict { data: ys } ->
+    List.walk ys xs (\state, Pair k _ -> remove state k)
 
-## Internal helper function to insert a new association
-##
-## Precondition: `k` should not exist in the Dict yet.
-insertFresh : Dict k v, k, v -> Dict k v
-insertFresh = \@Dict list, k, v ->
-    list
-    |> List.append (Pair k v)
-    |> @Dict
-
-insertIfVacant : Dict k v, k, v -> Dict k v
+insertIfVacant : Dict k v, k, v -> Dict k v | k has Hash
 insertIfVacant = \dict, key, value ->
-    if Dict.contains dict key then
+    if contains dict key then
         dict
     else
-        Dict.insert dict key value
+        insert dict key value
+
+## Internal helper to look up a key.
+##
+## Returns the slot and the index into `data` of the key if it is present, or otherwise the
+## vacant slot the key would be inserted into.
+find : List [Pair k v], List Nat, k -> [Found Nat Nat, Vacant Nat] | k has Hash
+find = \data, slots, key ->
+    if List.isEmpty slots then
+        Vacant 0
+    else
+        findHelp data slots key (homeSlot key (List.len slots))
+
+findHelp : List [Pair k v], List Nat, k, Nat -> [Found Nat Nat, Vacant Nat] | k has Hash
+findHelp = \data, slots, key, slotIndex ->
+    when List.get slots slotIndex is
+        Ok entry if entry != 0 ->
+            when List.get data (entry - 1) is
+                Ok (Pair candidate _) if candidate == key ->
+                    Found slotIndex (entry - 1)
+
+                _ ->
+                    findHelp data slots key (nextSlot slotIndex (List.len slots))
+
+        _ ->
+            Vacant slotIndex
+
+## Internal helper to find the slot that refers to the given entry (one more than its index into
+## `data`), starting the probe at `slotIndex`.
+slotOfEntry : List Nat, Nat, Nat -> Nat
+slotOfEntry = \slots, slotIndex, entry ->
+    when List.get slots slotIndex is
+        Ok current if current != entry && current != 0 ->
+            slotOfEntry slots (nextSlot slotIndex (List.len slots)) entry
+
+        _ ->
+            slotIndex
+
+## Internal helper to close the gap left by vacating the slot `hole`.
+##
+## Walks the probe run after the hole and moves back every entry that would otherwise become
+## unreachable, so that lookups never need tombstones.
+shiftBack : List Nat, List [Pair k v], Nat, Nat -> List Nat | k has Hash
+shiftBack = \slots, data, hole, current ->
+    when List.get slots current is
+        Ok entry if entry != 0 ->
+            slotCount = List.len slots
+            next = nextSlot current slotCount
+            home =
+                when List.get data (entry - 1) is
+                    Ok (Pair key _) -> homeSlot key slotCount
+                    Err OutOfBounds -> current
+
+            if isCyclicallyWithin hole home current then
+                # The entry is still reachable from its home slot; leave it where it is.
+                shiftBack slots data hole next
+            else
+                slots
+                |> List.set hole entry
+                |> List.set current 0
+                |> shiftBack data current next
+
+        _ ->
+            slots
+
+## Whether `index` lies in the cyclic range `(low, high]`.
+isCyclicallyWithin : Nat, Nat, Nat -> Bool
+isCyclicallyWithin = \low, index, high ->
+    if low <= high then
+        low < index && index <= high
+    else
+        low < index || index <= high
+
+## Internal helper to create the slot table for `data` from scratch.
+rebuildSlots : List [Pair k v], Nat -> List Nat | k has Hash
+rebuildSlots = \data, slotCount ->
+    initial = { slots: List.repeat 0 slotCount, entry: 1 }
+
+    rebuilt =
+        List.walk data initial \{ slots, entry }, Pair key _ ->
+            slotIndex = vacantSlot slots (homeSlot key slotCount)
+
+            { slots: List.set slots slotIndex entry, entry: entry + 1 }
+
+    rebuilt.slots
+
+vacantSlot : List Nat, Nat -> Nat
+vacantSlot = \slots, slotIndex ->
+    when List.get slots slotIndex is
+        Ok entry if entry != 0 ->
+            vacantSlot slots (nextSlot slotIndex (List.len slots))
+
+        _ ->
+            slotIndex
+
+## The smallest power-of-two slot count (of at least 8) that keeps `n` entries at most
+## three-quarters full.
+slotCountFor : Nat -> Nat
+slotCountFor = \n ->
+    slotCountForHelp n 8
+
+slotCountForHelp : Nat, Nat -> Nat
+slotCountForHelp = \n, slotCount ->
+    if n * 4 > slotCount * 3 then
+        slotCountForHelp n (slotCount * 2)
+    else
+        slotCount
+
+homeSlot : k, Nat -> Nat | k has Hash
+homeSlot = \key, slotCount ->
+    hashKey key
+    |> Num.bitwiseAnd (Num.toU64 (slotCount - 1))
+    |> Num.toNat
+
+nextSlot : Nat, Nat -> Nat
+nextSlot = \slotIndex, slotCount ->
+    Num.bitwiseAnd (slotIndex + 1) (slotCount - 1)
+
+hashKey : k -> U64 | k has Hash
+hashKey = \key ->
+    createLowLevelHasher {}
+    |> Hash.hash key
+    |> Hash.complete
+
+# The hasher used by dictionaries. It packs its input into 64-bit words, and mixes each word into
+# its state with the splitmix64 finalizer. It is fast and has a good distribution, but is not
+# resistant to hash flooding.
+LowLevelHasher := U64 has [
+         Hasher {
+             addBytes: lowLevelAddBytes,
+             addU8: lowLevelAddU8,
+             addU16: lowLevelAddU16,
+             addU32: lowLevelAddU32,
+             addU64: lowLevelAddU64,
+             addU128: lowLevelAddU128,
+             addI8: lowLevelAddI8,
+             addI16: lowLevelAddI16,
+             addI32: lowLevelAddI32,
+             addI64: lowLevelAddI64,
+             addI128: lowLevelAddI128,
+             complete: lowLevelComplete,
+         },
+     ]
+
+createLowLevelHasher : {} -> LowLevelHasher
+createLowLevelHasher = \{} -> @LowLevelHasher 0x243F6A8885A308D3u64
+
+combine : U64, U64 -> U64
+combine = \state, word ->
+    Num.bitwiseXor state word
+    |> Num.addWrap 0x9E3779B97F4A7C15u64
+    |> mix
+
+mix : U64 -> U64
+mix = \x ->
+    a = Num.mulWrap (Num.bitwiseXor x (Num.shiftRightZfBy x 30)) 0xBF58476D1CE4E5B9u64
+    b = Num.mulWrap (Num.bitwiseXor a (Num.shiftRightZfBy a 27)) 0x94D049BB133111EBu64
+
+    Num.bitwiseXor b (Num.shiftRightZfBy b 31)
+
+lowLevelAddBytes : LowLevelHasher, List U8 -> LowLevelHasher
+lowLevelAddBytes = \@LowLevelHasher state, bytes ->
+    initial = { state, word: 0, count: 0 }
+
+    packed =
+        List.walk bytes initial \acc, byte ->
+            word = Num.bitwiseOr (Num.shiftLeftBy acc.word 8) (Num.toU64 byte)
+
+            if acc.count == 7 then
+                { state: combine acc.state word, word: 0, count: 0 }
+            else
+                { state: acc.state, word, count: acc.count + 1 }
+
+    # The trailing word has at most 7 bytes, so the length fits in its top byte. This keeps e.g.
+    # `[]` and `[0]` from hashing the same.
+    lengthByte = Num.shiftLeftBy (Num.toU64 (List.len bytes)) 56
+
+    @LowLevelHasher (combine packed.state (Num.bitwiseOr packed.word lengthByte))
+
+lowLevelAddU8 : LowLevelHasher, U8 -> LowLevelHasher
+lowLevelAddU8 = \hasher, n -> lowLevelAddU64 hasher (Num.toU64 n)
+
+lowLevelAddU16 : LowLevelHasher, U16 -> LowLevelHasher
+lowLevelAddU16 = \hasher, n -> lowLevelAddU64 hasher (Num.toU64 n)
+
+lowLevelAddU32 : LowLevelHasher, U32 -> LowLevelHasher
+lowLevelAddU32 = \hasher, n -> lowLevelAddU64 hasher (Num.toU64 n)
+
+lowLevelAddU64 : LowLevelHasher, U64 -> LowLevelHasher
+lowLevelAddU64 = \@LowLevelHasher state, n -> @LowLevelHasher (combine state n)
+
+lowLevelAddU128 : LowLevelHasher, U128 -> LowLevelHasher
+lowLevelAddU128 = \hasher, n ->
+    lower = Num.toU64 n
+    upper = Num.toU64 (Num.shiftRightZfBy n 64)
+
+    lowLevelAddU64 (lowLevelAddU64 hasher lower) upper
+
+lowLevelAddI8 : LowLevelHasher, I8 -> LowLevelHasher
+lowLevelAddI8 = \hasher, n -> lowLevelAddU8 hasher (Num.toU8 n)
+
+lowLevelAddI16 : LowLevelHasher, I16 -> LowLevelHasher
+lowLevelAddI16 = \hasher, n -> lowLevelAddU16 hasher (Num.toU16 n)
+
+lowLevelAddI32 : LowLevelHasher, I32 -> LowLevelHasher
+lowLevelAddI32 = \hasher, n -> lowLevelAddU32 hasher (Num.toU32 n)
+
+lowLevelAddI64 : LowLevelHasher, I64 -> LowLevelHasher
+lowLevelAddI64 = \hasher, n -> lowLevelAddU64 hasher (Num.toU64 n)
+
+lowLevelAddI128 : LowLevelHasher, I128 -> LowLevelHasher
+lowLevelAddI128 = \hasher, n -> lowLevelAddU128 hasher (Num.toU128 n)
+
+lowLevelComplete : LowLevelHasher -> U64
+lowLevelComplete = \@LowLevelHasher state -> state
@@ -0,0 +1,94 @@
+interface Hash
+    exposes [
+        Hash,
+        hash,
+        Hasher,
+        addBytes,
+        addU8,
+        addU16,
+        addU32,
+        addU64,
+        addU128,
+        addI8,
+        addI16,
+        addI32,
+        addI64,
+        addI128,
+        complete,
+        hashNat,
+        hashStrBytes,
+        hashList,
+        hashF32,
+        hashF64,
+        hashDec,
+    ]
+    imports [
+        List,
+        Str,
+    ]
+
+## A value that can be hashed.
+##
+## [hash] does not produce a hash value itself; it feeds the value into a [Hasher],
+## which must be [complete]d to extract the hash value.
+##
+## Every type that can be hashed can also be compared with `==`. Opaque types that
+## implement [Hash] but not `Eq` are compared structurally.
+Hash has
+    hash : hasher, a -> hasher | a has Hash, hasher has Hasher
+
+## Describes a hashing algorithm that is fed bytes and integers, and produces a
+## 64-bit unsigned integer hash. [Hasher]s are not required to be suitable for
+## cryptographically-secure hashing.
+Hasher has
+    addBytes : a, List U8 -> a | a has Hasher
+    addU8 : a, U8 -> a | a has Hasher
+    addU16 : a, U16 -> a | a has Hasher
+    addU32 : a, U32 -> a | a has Hasher
+    addU64 : a, U64 -> a | a has Hasher
+    addU128 : a, U128 -> a | a has Hasher
+    addI8 : a, I8 -> a | a has Hasher
+    addI16 : a, I16 -> a | a has Hasher
+    addI32 : a, I32 -> a | a has Hasher
+    addI64 : a, I64 -> a | a has Hasher
+    addI128 : a, I128 -> a | a has Hasher
+    complete : a -> U64 | a has Hasher
+
+## Adds a [Nat] to a [Hasher]. The [Nat] is hashed as a [U64], so its hash does
+## not depend on the target architecture.
+hashNat : hasher, Nat -> hasher | hasher has Hasher
+hashNat = \hasher, n -> addU64 hasher (Num.toU64 n)
+
+## Adds a string to a [Hasher] by hashing its UTF-8 bytes.
+hashStrBytes : hasher, Str -> hasher | hasher has Hasher
+hashStrBytes = \hasher, s -> addBytes hasher (Str.toUtf8 s)
+
+## Adds a list of [Hash]able elements to a [Hasher] by hashing each element in order.
+hashList : hasher, List a -> hasher | a has Hash, hasher has Hasher
+hashList = \hasher, lst ->
+    List.walk lst hasher \accumHasher, elem -> hash accumHasher elem
+
+## Adds an [F32] to a [Hasher] by hashing its bits. Zero and negative zero are equal,
+## so they hash the same. *NaN* is not equal to anything, so it doesn't matter what it hashes to.
+hashF32 : hasher, F32 -> hasher | hasher has Hasher
+hashF32 = \hasher, n ->
+    # -0.0 has a different bit pattern than 0.0, so fold it onto 0.0 first
+    if Num.isZero n then
+        addU32 hasher 0
+    else
+        addU32 hasher (Num.f32ToBits n)
+
+## Adds an [F64] to a [Hasher] by hashing its bits. Zero and negative zero are equal,
+## so they hash the same. *NaN* is not equal to anything, so it doesn't matter what it hashes to.
+hashF64 : hasher, F64 -> hasher | hasher has Hasher
+hashF64 = \hasher, n ->
+    # -0.0 has a different bit pattern than 0.0, so fold it onto 0.0 first
+    if Num.isZero n then
+        addU64 hasher 0
+    else
+        addU64 hasher (Num.f64ToBits n)
+
+## Adds a [Dec] to a [Hasher] by hashing its bits. A [Dec] is a fixed-point number, so
+## every value has exactly one bit pattern.
+hashDec : hasher, Dec -> hasher | hasher has Hasher
+hashDec = \hasher, n -> addI128 hasher (Num.decToBits n)
@@ -40,6 +40,7 @@ Json := {} has [
              list: encodeList,
              record: encodeRecord,
              tag: encodeTag,
+             tuple: encodeTuple,
          },
          DecoderFormatting {
              u8: decodeU8,
@@ -59,6 +60,8 @@ Json := {} has [
              string: decodeString,
              list: decodeList,
              record: decodeRecord,
+             tag: decodeTag,
+             tuple: decodeTuple,
          },
      ]
 
@@ -174,6 +177,24 @@ encodeTag = \name, payload ->
         List.append bytesWithPayload (Num.toU8 ']')
         |> List.append (Num.toU8 '}')
 
+encodeTuple = \elems ->
+    Encode.custom \bytes, @Json {} ->
+        # Idea: encode `(v1, v2)` as `[v1, v2]`
+        writeTuple = \{ buffer, elemsLeft }, encoder ->
+            bufferWithElem = appendWith buffer encoder (@Json {})
+            bufferWithSuffix =
+                if elemsLeft > 1 then
+                    List.append bufferWithElem (Num.toU8 ',')
+                else
+                    bufferWithElem
+
+            { buffer: bufferWithSuffix, elemsLeft: elemsLeft - 1 }
+
+        bytesHead = List.append bytes (Num.toU8 '[')
+        { buffer: bytesWithTuple } = List.walk elems { buffer: bytesHead, elemsLeft: List.len elems } writeTuple
+
+        List.append bytesWithTuple (Num.toU8 ']')
+
 takeWhile = \list, predicate ->
     helper = \{ taken, rest } ->
         when List.first rest is
@@ -410,6 +431,12 @@ colon = \bytes -> parseExactChar bytes (asciiByte ':')
 comma : List U8 -> DecodeResult {}
 comma = \bytes -> parseExactChar bytes (asciiByte ',')
 
+openBracket : List U8 -> DecodeResult {}
+openBracket = \bytes -> parseExactChar bytes (asciiByte '[')
+
+closingBracket : List U8 -> DecodeResult {}
+closingBracket = \bytes -> parseExactChar bytes (asciiByte ']')
+
 tryDecode : DecodeResult a, ({ val : a, rest : List U8 } -> DecodeResult b) -> DecodeResult b
 tryDecode = \{ result, rest }, mapper ->
     when result is
@@ -447,3 +474,73 @@ decodeRecord = \initialState, stepField, finalizer -> Decode.custom \bytes, @Jso
         when finalizer endStateResult is
             Ok val -> { result: Ok val, rest: afterRecordBytes }
             Err e -> { result: Err e, rest: afterRecordBytes }
+
+# Decodes tags in the shape `encodeTag` produces, that is `A v1 v2` as `{"A": [v1, v2]}`
+decodeTag = \initialState, stepPayload, finalizer -> Decode.custom \bytes, @Json {} ->
+        # NB: the stepper function must be passed explicitly until #2894 is resolved.
+        decodePayload = \stepper, state, name, index, itemBytes ->
+            { val: newState, rest: beforeCommaOrBreak } <- tryDecode
+                    (
+                        when stepper state name index is
+                            # We have no way to skip over a value we don't know the type of
+                            Skip -> { result: Err TooShort, rest: itemBytes }
+                            Keep decoder -> Decode.decodeWith itemBytes decoder (@Json {})
+                    )
+
+            { result: commaResult, rest: nextBytes } = comma beforeCommaOrBreak
+
+            when commaResult is
+                Ok {} -> decodePayload stepPayload newState name (index + 1) nextBytes
+                Err _ -> { result: Ok newState, rest: nextBytes }
+
+        { rest: afterBraceBytes } <- bytes |> openBrace |> tryDecode
+
+        { val: name, rest: afterNameBytes } <- afterBraceBytes |> recordKey |> tryDecode
+
+        { rest: afterColonBytes } <- afterNameBytes |> colon |> tryDecode
+
+        { rest: afterBracketBytes } <- afterColonBytes |> openBracket |> tryDecode
+
+        { val: endState, rest: beforeClosingBracketBytes } <- tryDecode
+                (
+                    if List.first afterBracketBytes == Ok (asciiByte ']') then
+                        { result: Ok initialState, rest: afterBracketBytes }
+                    else
+                        decodePayload stepPayload initialState name 0 afterBracketBytes
+                )
+
+        { rest: beforeClosingBraceBytes } <- beforeClosingBracketBytes |> closingBracket |> tryDecode
+
+        { rest: afterTagBytes } <- beforeClosingBraceBytes |> closingBrace |> tryDecode
+
+        when finalizer endState name is
+            Ok val -> { result: Ok val, rest: afterTagBytes }
+            Err e -> { result: Err e, rest: afterTagBytes }
+
+# Decodes tuples in the shape `encodeTuple` produces, that is `(v1, v2)` as `[v1, v2]`
+decodeTuple = \initialState, stepElem, finalizer -> Decode.custom \bytes, @Json {} ->
+        # NB: the stepper function must be passed explicitly until #2894 is resolved.
+        decodeElems = \stepper, state, index, elemBytes ->
+            { val: newState, rest: beforeCommaOrBreak } <- tryDecode
+                    (
+                        when stepper state index is
+                            # The tuple has fewer elements than we were given
+                            Skip -> { result: Err TooShort, rest: elemBytes }
+                            Keep decoder -> Decode.decodeWith elemBytes decoder (@Json {})
+                    )
+
+            { result: commaResult, rest: nextBytes } = comma beforeCommaOrBreak
+
+            when commaResult is
+                Ok {} -> decodeElems stepElem newState (index + 1) nextBytes
+                Err _ -> { result: Ok newState, rest: nextBytes }
+
+        { rest: afterBracketBytes } <- bytes |> openBracket |> tryDecode
+
+        { val: endState, rest: beforeClosingBracketBytes } <- decodeElems stepElem initialState 0 afterBracketBytes |> tryDecode
+
+        { rest: afterTupleBytes } <- beforeClosingBracketBytes |> closingBracket |> tryDecode
+
+        when finalizer endState is
+            Ok val -> { result: Ok val, rest: afterTupleBytes }
+            Err e -> { result: Err e, rest: afterTupleBytes }
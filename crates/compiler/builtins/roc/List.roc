@@ -60,6 +60,7 @@ interface List
         sortAsc,
         sortDesc,
         reserve,
+        isEqList,
     ]
     imports [
         Bool.{ Bool },
@@ -351,10 +352,34 @@ join = \lists ->
 
     List.walk lists (List.withCapacity totalLength) (\state, list -> List.concat state list)
 
-contains : List a, a -> Bool
+contains : List a, a -> Bool | a has Eq
 contains = \list, needle ->
     List.any list (\x -> x == needle)
 
+## Returns `True` if the two lists have the same length, and each of their
+## corresponding elements are equal according to their [Eq](Bool#Eq) implementation.
+##
+## This is how `==` compares lists whose elements might have a custom
+## implementation of [Eq](Bool#Eq).
+isEqList : List a, List a -> Bool | a has Eq
+isEqList = \xs, ys ->
+    length = List.len xs
+
+    if length == List.len ys then
+        isEqListHelp xs ys 0 length
+    else
+        False
+
+isEqListHelp : List a, List a, Nat, Nat -> Bool | a has Eq
+isEqListHelp = \xs, ys, index, length ->
+    if index < length then
+        if List.getUnsafe xs index == List.getUnsafe ys index then
+            isEqListHelp xs ys (index + 1) length
+        else
+            False
+    else
+        True
+
 ## Build a value using each element in the list.
 ##
 ## Starting with a given `state` value, this walks through each element in the
@@ -881,11 +906,11 @@ intersperse = \list, sep ->
 ## is considered to "start with" an empty list.
 ##
 ## If the first list is empty, this only returns `True` if the second list is empty.
-startsWith : List elem, List elem -> Bool
+startsWith : List elem, List elem -> Bool | elem has Eq
 startsWith = \list, prefix ->
     # TODO once we have seamless slices, verify that this wouldn't
     # have better performance with a function like List.compareSublists
-    prefix == List.sublist list { start: 0, len: List.len prefix }
+    isEqList prefix (List.sublist list { start: 0, len: List.len prefix })
 
 ## Returns `True` if the first list ends with the second list.
 ##
@@ -893,14 +918,14 @@ startsWith = \list, prefix ->
 ## is considered to "end with" an empty list.
 ##
 ## If the first list is empty, this only returns `True` if the second list is empty.
-endsWith : List elem, List elem -> Bool
+endsWith : List elem, List elem -> Bool | elem has Eq
 endsWith = \list, suffix ->
     # TODO once we have seamless slices, verify that this wouldn't
     # have better performance with a function like List.compareSublists
     length = List.len suffix
     start = Num.subSaturated (List.len list) length
 
-    suffix == List.sublist list { start, len: length }
+    isEqList suffix (List.sublist list { start, len: length })
 
 ## Splits the list into two lists, around the given index.
 ##
@@ -921,8 +946,8 @@ split = \elements, userSplitIndex ->
 ## Returns the elements before the first occurrence of a delimiter, as well as the
 ## remaining elements after that occurrence. If the delimiter is not found, returns `Err`.
 ##
-##     List.splitFirst [Foo, Z, Bar, Z, Baz] Z == Ok { before: [Foo], after: [Bar, Baz] }
-splitFirst : List elem, elem -> Result { before : List elem, after : List elem } [NotFound]*
+##     List.splitFirst [Foo, Z, Bar, Z, Baz] Z == Ok { before: [Foo], after: [Bar, Z, Baz] }
+splitFirst : List elem, elem -> Result { before : List elem, after : List elem } [NotFound]* | elem has Eq
 splitFirst = \list, delimiter ->
     when List.findFirstIndex list (\elem -> elem == delimiter) is
         Ok index ->
@@ -936,8 +961,8 @@ splitFirst = \list, delimiter ->
 ## Returns the elements before the last occurrence of a delimiter, as well as the
 ## remaining elements after that occurrence. If the delimiter is not found, returns `Err`.
 ##
-##     List.splitLast [Foo, Z, Bar, Z, Baz] Z == Ok { before: [Foo, Bar], after: [Baz] }
-splitLast : List elem, elem -> Result { before : List elem, after : List elem } [NotFound]*
+##     List.splitLast [Foo, Z, Bar, Z, Baz] Z == Ok { before: [Foo, Z, Bar], after: [Baz] }
+splitLast : List elem, elem -> Result { before : List elem, after : List elem } [NotFound]* | elem has Eq
 splitLast = \list, delimiter ->
     when List.findLastIndex list (\elem -> elem == delimiter) is
         Ok index ->
@@ -512,6 +512,12 @@ intCast : Int a -> Int b
 bytesToU16Lowlevel : List U8, Nat -> U16
 bytesToU32Lowlevel : List U8, Nat -> U32
 
+# INTERNAL COMPILER USE ONLY: the bits of a fractional number, reinterpreted as an integer.
+# Hash uses these to hash fractions.
+f32ToBits : F32 -> U32
+f64ToBits : F64 -> U64
+decToBits : Dec -> I128
+
 bytesToU16 : List U8, Nat -> Result U16 [OutOfBounds]
 bytesToU16 = \bytes, index ->
     # we need at least 1 more byte
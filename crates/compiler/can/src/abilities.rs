@@ -9,6 +9,12 @@ use roc_types::{
     types::{MemberImpl, Type},
 };
 
+/// Abilities that every type with another ability has as well, as `(ability, implied ability)`.
+///
+/// Every type that can be hashed can also be compared for equality, so `Hash` implies `Eq`.
+/// Implied abilities must be derivable.
+const IMPLIED_ABILITIES: &[(Symbol, Symbol)] = &[(Symbol::HASH_HASH_ABILITY, Symbol::BOOL_EQ)];
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MemberVariables {
     pub able_vars: Vec<Variable>,
@@ -104,6 +110,17 @@ pub struct SpecializationId(NonZeroU32);
 
 static_assertions::assert_eq_size!(SpecializationId, Option<SpecializationId>);
 
+impl SpecializationId {
+    pub const fn to_u32(self) -> u32 {
+        self.0.get()
+    }
+
+    /// `None` for 0, which is never handed out as an id.
+    pub fn from_u32(id: u32) -> Option<Self> {
+        NonZeroU32::new(id).map(Self)
+    }
+}
+
 pub enum SpecializationLambdaSetError {}
 
 /// A key into a particular implementation of an ability member for an opaque type.
@@ -256,6 +273,34 @@ impl<Phase: ResolvePhase> IAbilitiesStore<Phase> {
         }
     }
 
+    /// Whether every type with `ability` also has `other_ability`.
+    pub fn ability_implies(ability: Symbol, other_ability: Symbol) -> bool {
+        ability == other_ability || IMPLIED_ABILITIES.contains(&(ability, other_ability))
+    }
+
+    /// Records derived implementations for the abilities implied by the `declared_abilities` of
+    /// an opaque type, unless the opaque declares them itself.
+    pub fn register_implied_implementations(
+        &mut self,
+        implementing_type: Symbol,
+        declared_abilities: &[Symbol],
+    ) {
+        let implied_abilities = IMPLIED_ABILITIES
+            .iter()
+            .filter(|(ability, _)| declared_abilities.contains(ability))
+            .map(|(_, implied)| *implied)
+            .filter(|implied| !declared_abilities.contains(implied));
+
+        for implied in implied_abilities {
+            let (_, members) = implied
+                .derivable_ability()
+                .unwrap_or_else(|| internal_error!("{:?} is implied but not derivable", implied));
+            let impls = members.iter().map(|member| (*member, MemberImpl::Derived));
+
+            self.register_declared_implementations(implementing_type, impls);
+        }
+    }
+
     /// Returns whether a symbol is declared to specialize an ability member.
     pub fn is_specialization_name(&self, symbol: Symbol) -> bool {
         self.specialization_to_root.contains_key(&symbol)
@@ -265,6 +310,26 @@ impl<Phase: ResolvePhase> IAbilitiesStore<Phase> {
         self.members_of_ability.get(&ability).map(|v| v.as_ref())
     }
 
+    /// Returns an iterator over pairs ((ability member, type), implementation) specifying that
+    /// the give type has an implementation of an ability member.
+    pub fn iter_declared_implementations(
+        &self,
+    ) -> impl Iterator<Item = (ImplKey, &MemberImpl)> + '_ {
+        self.declared_implementations.iter().map(|(k, v)| (*k, v))
+    }
+
+    /// Iterator over the specializations of ability members this store knows about.
+    pub fn iter_specializations(
+        &self,
+    ) -> impl Iterator<Item = (Symbol, &MemberSpecializationInfo<Phase>)> + '_ {
+        self.specializations.iter().map(|(k, v)| (*k, v))
+    }
+
+    /// How many specialization ids [`Self::fresh_specialization_id`] has handed out.
+    pub fn specialization_ids_used(&self) -> u32 {
+        self.next_specialization_id.get() - 1
+    }
+
     pub fn fresh_specialization_id(&mut self) -> SpecializationId {
         debug_assert!(self.next_specialization_id.get() != std::u32::MAX);
 
@@ -405,14 +470,6 @@ impl IAbilitiesStore<Resolved> {
         self.ability_members.get(&member)
     }
 
-    /// Returns an iterator over pairs ((ability member, type), implementation) specifying that
-    /// the give type has an implementation of an ability member.
-    pub fn iter_declared_implementations(
-        &self,
-    ) -> impl Iterator<Item = (ImplKey, &MemberImpl)> + '_ {
-        self.declared_implementations.iter().map(|(k, v)| (*k, v))
-    }
-
     /// Retrieves the declared implementation of `member` for `typ`, if it exists.
     pub fn get_implementation(&self, impl_key: ImplKey) -> Option<&MemberImpl> {
         self.declared_implementations.get(&impl_key)
@@ -489,7 +546,7 @@ impl IAbilitiesStore<Pending> {
         );
     }
 
-    fn import_specialization(
+    pub fn import_specialization(
         &mut self,
         specialization: &MemberSpecializationInfo<impl ResolvePhase>,
     ) {
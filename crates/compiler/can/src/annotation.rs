@@ -409,7 +409,8 @@ pub fn find_type_def_symbols(
                         }
                         AssignedField::LabelOnly(_) => {}
                         AssignedField::SpaceBefore(inner, _)
-                        | AssignedField::SpaceAfter(inner, _) => inner_stack.push(inner),
+                        | AssignedField::SpaceAfter(inner, _)
+                        | AssignedField::Nested(_, inner) => inner_stack.push(inner),
                         AssignedField::Malformed(_) => {}
                     }
                 }
@@ -418,6 +419,15 @@ pub fn find_type_def_symbols(
                     stack.push(&t.value);
                 }
             }
+            Tuple { elems, ext } => {
+                for t in elems.iter() {
+                    stack.push(&t.value);
+                }
+
+                for t in ext.iter() {
+                    stack.push(&t.value);
+                }
+            }
             TagUnion { ext, tags } => {
                 let mut inner_stack = Vec::with_capacity(tags.items.len());
 
@@ -792,7 +802,7 @@ fn can_annotation_help(
                         // just `a` does not mean the same as `{}a`, so even
                         // if there are no fields, still make this a `Record`,
                         // not an EmptyRec
-                        Type::Record(Default::default(), TypeExtension::from_type(ext_type))
+                        Type::Record(Default::default(), ext_type)
                     }
 
                     None => Type::EmptyRec,
@@ -809,9 +819,40 @@ fn can_annotation_help(
                     references,
                 );
 
-                Type::Record(field_types, TypeExtension::from_type(ext_type))
+                Type::Record(field_types, ext_type)
             }
         }
+        Tuple { elems, ext } => {
+            let ext_type = can_extension_type(
+                env,
+                scope,
+                var_store,
+                introduced_variables,
+                local_aliases,
+                references,
+                ext,
+                roc_problem::can::ExtensionTypeKind::Tuple,
+            );
+
+            let mut elem_types = VecMap::with_capacity(elems.len());
+
+            for (index, loc_elem) in elems.iter().enumerate() {
+                let elem_type = can_annotation_help(
+                    env,
+                    &loc_elem.value,
+                    loc_elem.region,
+                    scope,
+                    var_store,
+                    introduced_variables,
+                    local_aliases,
+                    references,
+                );
+
+                elem_types.insert(index, elem_type);
+            }
+
+            Type::Tuple(elem_types, ext_type)
+        }
         TagUnion { tags, ext, .. } => {
             let ext_type = can_extension_type(
                 env,
@@ -830,7 +871,7 @@ fn can_annotation_help(
                         // just `a` does not mean the same as `{}a`, so even
                         // if there are no fields, still make this a `Record`,
                         // not an EmptyRec
-                        Type::TagUnion(Default::default(), TypeExtension::from_type(ext_type))
+                        Type::TagUnion(Default::default(), ext_type)
                     }
 
                     None => Type::EmptyTagUnion,
@@ -852,7 +893,7 @@ fn can_annotation_help(
                 // in theory we save a lot of time by sorting once here
                 insertion_sort_by(&mut tag_types, |a, b| a.0.cmp(&b.0));
 
-                Type::TagUnion(tag_types, TypeExtension::from_type(ext_type))
+                Type::TagUnion(tag_types, ext_type)
             }
         }
         SpaceBefore(nested, _) | SpaceAfter(nested, _) => can_annotation_help(
@@ -980,7 +1021,7 @@ fn can_extension_type<'a>(
     references: &mut VecSet<Symbol>,
     opt_ext: &Option<&Loc<TypeAnnotation<'a>>>,
     ext_problem_kind: roc_problem::can::ExtensionTypeKind,
-) -> Type {
+) -> TypeExtension {
     fn valid_record_ext_type(typ: &Type) -> bool {
         // Include erroneous types so that we don't overreport errors.
         matches!(
@@ -994,12 +1035,19 @@ fn can_extension_type<'a>(
             Type::EmptyTagUnion | Type::TagUnion(..) | Type::Variable(..) | Type::Erroneous(..)
         )
     }
+    fn valid_tuple_ext_type(typ: &Type) -> bool {
+        matches!(
+            typ,
+            Type::Tuple(..) | Type::Variable(..) | Type::Erroneous(..)
+        )
+    }
 
     use roc_problem::can::ExtensionTypeKind;
 
-    let (empty_ext_type, valid_extension_type): (_, fn(&Type) -> bool) = match ext_problem_kind {
-        ExtensionTypeKind::Record => (Type::EmptyRec, valid_record_ext_type),
-        ExtensionTypeKind::TagUnion => (Type::EmptyTagUnion, valid_tag_ext_type),
+    let valid_extension_type: fn(&Type) -> bool = match ext_problem_kind {
+        ExtensionTypeKind::Record => valid_record_ext_type,
+        ExtensionTypeKind::TagUnion => valid_tag_ext_type,
+        ExtensionTypeKind::Tuple => valid_tuple_ext_type,
     };
 
     match opt_ext {
@@ -1015,7 +1063,7 @@ fn can_extension_type<'a>(
                 references,
             );
             if valid_extension_type(shallow_dealias_with_scope(scope, &ext_type)) {
-                ext_type
+                TypeExtension::from_type(ext_type)
             } else {
                 // Report an error but mark the extension variable to be inferred
                 // so that we're as permissive as possible.
@@ -1031,10 +1079,10 @@ fn can_extension_type<'a>(
 
                 introduced_variables.insert_inferred(Loc::at_zero(var));
 
-                Type::Variable(var)
+                TypeExtension::Open(Box::new(Type::Variable(var)))
             }
         }
-        None => empty_ext_type,
+        None => TypeExtension::Closed,
     }
 }
 
@@ -1249,6 +1297,9 @@ fn can_assigned_fields<'a>(
                     field = nested;
                     continue 'inner;
                 }
+                Nested(_, _) => {
+                    unreachable!("only record updates have nested fields")
+                }
                 Malformed(string) => {
                     malformed(env, region, string);
 
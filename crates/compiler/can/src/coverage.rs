@@ -0,0 +1,125 @@
+//! The code that `roc test --coverage` counts the runs of.
+//!
+//! Mono counts a run of each function body and of each branch of an `if` or `when` (see
+//! `roc_mono::ir::Env::count_coverage`), keyed by the region of that code. This module lists
+//! the same regions up front, so that the coverage report also shows the code that never ran.
+use crate::expr::{ClosureData, DeclarationTag, Declarations, Expr};
+use crate::traverse::{walk_expr, Visitor};
+use roc_module::symbol::Symbol;
+use roc_region::all::Region;
+use roc_types::subs::Variable;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoveragePoint {
+    /// The body of a function, named or anonymous
+    Function { symbol: Symbol, region: Region },
+    /// One branch of the `if` or `when` numbered `block` in its module
+    Branch {
+        block: u32,
+        branch: u32,
+        region: Region,
+    },
+}
+
+impl CoveragePoint {
+    pub fn region(&self) -> Region {
+        match self {
+            CoveragePoint::Function { region, .. } | CoveragePoint::Branch { region, .. } => {
+                *region
+            }
+        }
+    }
+}
+
+/// The functions and branches of a module, in the order they appear in its declarations.
+/// Expects are left out, since they are what runs the rest.
+pub fn coverage_points(declarations: &Declarations) -> Vec<CoveragePoint> {
+    let mut collector = CoverageCollector {
+        points: Vec::new(),
+        blocks: 0,
+    };
+
+    let var = Variable::EMPTY_RECORD;
+
+    // declarations are sorted by their dependencies, but the report follows the source
+    let mut indices: Vec<usize> = (0..declarations.len()).collect();
+    indices.sort_by_key(|index| declarations.symbols[*index].region.start());
+
+    for index in indices {
+        use DeclarationTag::*;
+
+        let loc_expr = &declarations.expressions[index];
+
+        match declarations.declarations[index] {
+            Function(_) | Recursive(_) | TailRecursive(_) => {
+                // the body of a top-level function is stored without its closure
+                collector.push_function(declarations.symbols[index].value, loc_expr.region);
+                collector.visit_expr(&loc_expr.value, loc_expr.region, var);
+            }
+            Value | Destructure(_) => {
+                collector.visit_expr(&loc_expr.value, loc_expr.region, var);
+            }
+            MutualRecursion { .. } => {
+                // the members of this group are visited by later iterations
+            }
+            Expectation | ExpectationFx => {}
+        }
+    }
+
+    collector.points
+}
+
+struct CoverageCollector {
+    points: Vec<CoveragePoint>,
+    blocks: u32,
+}
+
+impl CoverageCollector {
+    fn push_function(&mut self, symbol: Symbol, region: Region) {
+        if !region.is_empty() {
+            self.points.push(CoveragePoint::Function { symbol, region });
+        }
+    }
+
+    fn push_block(&mut self, regions: impl Iterator<Item = Region>) {
+        let block = self.blocks;
+        self.blocks += 1;
+
+        let branches =
+            regions
+                .filter(|region| !region.is_empty())
+                .enumerate()
+                .map(|(branch, region)| CoveragePoint::Branch {
+                    block,
+                    branch: branch as u32,
+                    region,
+                });
+
+        self.points.extend(branches);
+    }
+}
+
+impl Visitor for CoverageCollector {
+    fn visit_expr(&mut self, expr: &Expr, _region: Region, var: Variable) {
+        match expr {
+            Expr::Closure(ClosureData { name, loc_body, .. }) => {
+                self.push_function(*name, loc_body.region);
+            }
+            Expr::If {
+                branches,
+                final_else,
+                ..
+            } => {
+                let thens = branches.iter().map(|(_, loc_then)| loc_then.region);
+
+                self.push_block(thens.chain(std::iter::once(final_else.region)));
+            }
+            Expr::When { branches, .. } => {
+                self.push_block(branches.iter().map(|branch| branch.value.region));
+            }
+            _ => (),
+        }
+
+        walk_expr(self, expr, var)
+    }
+}
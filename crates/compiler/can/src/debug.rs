@@ -0,0 +1,3 @@
+mod pretty_print;
+
+pub use pretty_print::{pretty_print_declarations, pretty_print_def, Ctx};
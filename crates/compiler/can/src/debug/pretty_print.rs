@@ -1,11 +1,16 @@
-//! Pretty-prints the canonical AST back to check our work - do things look reasonable?
+//! Pretty-prints the canonical AST back to Roc-like source - do things look reasonable?
 
-use roc_can::def::Def;
-use roc_can::expr::Expr::{self, *};
-use roc_can::expr::{ClosureData, OpaqueWrapFunctionData, WhenBranch};
-use roc_can::pattern::{Pattern, RecordDestruct};
+use crate::def::Def;
+use crate::expr::Expr::{self, *};
+use crate::expr::{
+    AccessorData, AnnotatedMark, ClosureData, DeclarationTag, Declarations, FunctionDef,
+    OpaqueWrapFunctionData, WhenBranch,
+};
+use crate::pattern::{DestructType, ListPatterns, Pattern, RecordDestruct, TupleDestruct};
 
-use roc_module::symbol::Interns;
+use roc_module::symbol::{Interns, Symbol};
+use roc_region::all::Loc;
+use roc_types::subs::Variable;
 use ven_pretty::{Arena, DocAllocator, DocBuilder};
 
 pub struct Ctx<'a> {
@@ -17,6 +22,23 @@ pub fn pretty_print_def(c: &Ctx, d: &Def) -> String {
     def(c, &f, d).append(f.hardline()).1.pretty(80).to_string()
 }
 
+/// Prints the top-level declarations of a module, in the order they are declared.
+pub fn pretty_print_declarations(c: &Ctx, declarations: &Declarations) -> String {
+    let f = Arena::new();
+
+    let docs = declarations
+        .declarations
+        .iter()
+        .enumerate()
+        .filter_map(|(index, tag)| declaration(c, &f, declarations, index, *tag));
+
+    f.intersperse(docs, f.hardline().append(f.hardline()))
+        .append(f.hardline())
+        .1
+        .pretty(80)
+        .to_string()
+}
+
 macro_rules! maybe_paren {
     ($paren_if_above:expr, $my_prec:expr, $doc:expr) => {
         maybe_paren!($paren_if_above, $my_prec, || true, $doc)
@@ -47,6 +69,92 @@ fn def<'a>(c: &Ctx, f: &'a Arena<'a>, d: &'a Def) -> DocBuilder<'a, Arena<'a>> {
         .group()
 }
 
+fn declaration<'a>(
+    c: &Ctx,
+    f: &'a Arena<'a>,
+    decls: &'a Declarations,
+    index: usize,
+    tag: DeclarationTag,
+) -> Option<DocBuilder<'a, Arena<'a>>> {
+    use DeclarationTag::*;
+
+    let loc_expr = &decls.expressions[index];
+
+    let doc = match tag {
+        Value => symbol(c, f, decls.symbols[index].value)
+            .append(f.text(" ="))
+            .append(f.line())
+            .append(expr(c, EPrec::Free, f, &loc_expr.value))
+            .nest(2)
+            .group(),
+        Expectation => f
+            .reflow("expect ")
+            .append(expr(c, EPrec::Free, f, &loc_expr.value))
+            .nest(2)
+            .group(),
+        ExpectationFx => f
+            .reflow("expect-fx ")
+            .append(expr(c, EPrec::Free, f, &loc_expr.value))
+            .nest(2)
+            .group(),
+        Function(function_index) | Recursive(function_index) | TailRecursive(function_index) => {
+            let FunctionDef { arguments, .. } =
+                &decls.function_bodies[function_index.index() as usize].value;
+
+            symbol(c, f, decls.symbols[index].value)
+                .append(f.text(" ="))
+                .append(f.line())
+                .append(closure(c, f, arguments, loc_expr))
+                .nest(2)
+                .group()
+        }
+        Destructure(destructure_index) => {
+            let destructure = &decls.destructs[destructure_index.index() as usize];
+
+            pattern(c, PPrec::Free, f, &destructure.loc_pattern.value)
+                .append(f.text(" ="))
+                .append(f.line())
+                .append(expr(c, EPrec::Free, f, &loc_expr.value))
+                .nest(2)
+                .group()
+        }
+        // The members of the cycle follow as declarations of their own.
+        MutualRecursion { .. } => return None,
+    };
+
+    Some(doc)
+}
+
+fn symbol<'a>(c: &Ctx, f: &'a Arena<'a>, sym: Symbol) -> DocBuilder<'a, Arena<'a>> {
+    f.text(format!(
+        "{}.{}",
+        sym.module_string(c.interns),
+        sym.as_str(c.interns),
+    ))
+}
+
+fn closure<'a>(
+    c: &Ctx,
+    f: &'a Arena<'a>,
+    arguments: &'a [(Variable, AnnotatedMark, Loc<Pattern>)],
+    loc_body: &'a Loc<Expr>,
+) -> DocBuilder<'a, Arena<'a>> {
+    f.text("\\")
+        .append(
+            f.intersperse(
+                arguments
+                    .iter()
+                    .map(|(_, _, arg)| pattern(c, PPrec::Free, f, &arg.value)),
+                f.text(", "),
+            ),
+        )
+        .append(f.text(" ->"))
+        .append(f.line())
+        .append(expr(c, EPrec::Free, f, &loc_body.value))
+        .nest(2)
+        .group()
+}
+
 #[derive(PartialEq, PartialOrd)]
 enum EPrec {
     Free,
@@ -86,11 +194,7 @@ fn expr<'a>(c: &Ctx, p: EPrec, f: &'a Arena<'a>, e: &'a Expr) -> DocBuilder<'a,
                     .append("]")
                     .group(),
             ),
-        Var(sym) | AbilityMember(sym, _, _) => f.text(format!(
-            "{}.{}",
-            sym.module_string(c.interns),
-            sym.as_str(c.interns),
-        )),
+        Var(sym) | AbilityMember(sym, _, _) => symbol(c, f, *sym),
         When {
             loc_cond, branches, ..
         } => maybe_paren!(
@@ -133,8 +237,14 @@ fn expr<'a>(c: &Ctx, p: EPrec, f: &'a Arena<'a>, e: &'a Expr) -> DocBuilder<'a,
                     .nest(2),
             )
             .group(),
-        LetRec(_, _, _) => todo!(),
-        LetNonRec(_, _) => todo!(),
+        LetRec(defs, body, _) => f
+            .concat(defs.iter().map(|d| def(c, f, d).append(f.hardline())))
+            .append(expr(c, Free, f, &body.value))
+            .group(),
+        LetNonRec(d, body) => def(c, f, d)
+            .append(f.hardline())
+            .append(expr(c, Free, f, &body.value))
+            .group(),
         Call(fun, args, _) => {
             let (_, fun, _, _) = &**fun;
             maybe_paren!(
@@ -154,27 +264,43 @@ fn expr<'a>(c: &Ctx, p: EPrec, f: &'a Arena<'a>, e: &'a Expr) -> DocBuilder<'a,
                     .nest(2)
             )
         }
-        RunLowLevel { .. } => todo!(),
-        ForeignCall { .. } => todo!(),
+        RunLowLevel { op, args, .. } => maybe_paren!(
+            Free,
+            p,
+            f.text(format!("#lowlevel.{:?}", op))
+                .append(
+                    f.concat(
+                        args.iter()
+                            .map(|(_, arg)| f.line().append(expr(c, AppArg, f, arg)))
+                    )
+                    .group()
+                )
+                .group()
+                .nest(2)
+        ),
+        ForeignCall {
+            foreign_symbol,
+            args,
+            ..
+        } => maybe_paren!(
+            Free,
+            p,
+            f.text(format!("#foreign.{}", foreign_symbol.as_str()))
+                .append(
+                    f.concat(
+                        args.iter()
+                            .map(|(_, arg)| f.line().append(expr(c, AppArg, f, arg)))
+                    )
+                    .group()
+                )
+                .group()
+                .nest(2)
+        ),
         Closure(ClosureData {
             arguments,
             loc_body,
             ..
-        }) => f
-            .text("\\")
-            .append(
-                f.intersperse(
-                    arguments
-                        .iter()
-                        .map(|(_, _, arg)| pattern(c, PPrec::Free, f, &arg.value)),
-                    f.text(", "),
-                ),
-            )
-            .append(f.text(" ->"))
-            .append(f.line())
-            .append(expr(c, Free, f, &loc_body.value))
-            .nest(2)
-            .group(),
+        }) => closure(c, f, arguments, loc_body),
         Record { fields, .. } => f
             .reflow("{")
             .append(
@@ -197,15 +323,36 @@ fn expr<'a>(c: &Ctx, p: EPrec, f: &'a Arena<'a>, e: &'a Expr) -> DocBuilder<'a,
             .append(f.text("}"))
             .group(),
         EmptyRecord => f.text("{}"),
+        Tuple { elems, .. } => f
+            .reflow("(")
+            .append(
+                f.intersperse(
+                    elems.iter().map(|(_, elem)| {
+                        f.line()
+                            .append(expr(c, Free, f, &elem.value).nest(2).group())
+                    }),
+                    f.reflow(","),
+                )
+                .nest(2)
+                .group(),
+            )
+            .append(f.line())
+            .append(f.text(")"))
+            .group(),
         Access {
             loc_expr, field, ..
         } => expr(c, AppArg, f, &loc_expr.value)
             .append(f.text(format!(".{}", field.as_str())))
             .group(),
+        TupleAccess {
+            loc_expr, index, ..
+        } => expr(c, AppArg, f, &loc_expr.value)
+            .append(f.text(format!(".{}", index)))
+            .group(),
         OpaqueWrapFunction(OpaqueWrapFunctionData { opaque_name, .. }) => {
             f.text(format!("@{}", opaque_name.as_str(c.interns)))
         }
-        Accessor(_) => todo!(),
+        Accessor(AccessorData { field, .. }) => f.text(format!(".{}", field)),
         Update {
             symbol, updates, ..
         } => f
@@ -254,12 +401,53 @@ fn expr<'a>(c: &Ctx, p: EPrec, f: &'a Arena<'a>, e: &'a Expr) -> DocBuilder<'a,
                 )
                 .group()
         ),
-        ZeroArgumentTag { .. } => todo!(),
-        OpaqueRef { .. } => todo!(),
-        Expect { .. } => todo!(),
-        ExpectFx { .. } => todo!(),
-        TypedHole(_) => todo!(),
-        RuntimeError(_) => todo!(),
+        ZeroArgumentTag { name, .. } => f.text(name.0.as_str()),
+        OpaqueRef { name, argument, .. } => maybe_paren!(
+            Free,
+            p,
+            f.text(format!("@{}", name.as_str(c.interns)))
+                .append(f.space())
+                .append(expr(c, AppArg, f, &argument.1.value))
+                .group()
+        ),
+        Expect {
+            loc_condition,
+            loc_continuation,
+            ..
+        } => f
+            .reflow("expect ")
+            .append(expr(c, Free, f, &loc_condition.value))
+            .nest(2)
+            .group()
+            .append(f.hardline())
+            .append(expr(c, Free, f, &loc_continuation.value)),
+        ExpectFx {
+            loc_condition,
+            loc_continuation,
+            ..
+        } => f
+            .reflow("expect-fx ")
+            .append(expr(c, Free, f, &loc_condition.value))
+            .nest(2)
+            .group()
+            .append(f.hardline())
+            .append(expr(c, Free, f, &loc_continuation.value)),
+        Dbg { loc_expr, .. } => maybe_paren!(
+            Free,
+            p,
+            f.reflow("dbg ")
+                .append(expr(c, AppArg, f, &loc_expr.value))
+                .group()
+        ),
+        Crash { msg, .. } => maybe_paren!(
+            Free,
+            p,
+            f.reflow("crash ")
+                .append(expr(c, AppArg, f, &msg.value))
+                .group()
+        ),
+        TypedHole(_) => f.text("_"),
+        RuntimeError(error) => f.text(format!("#runtime_error({:?})", error)),
     }
 }
 
@@ -306,11 +494,7 @@ fn pattern<'a>(
         Identifier(sym)
         | AbilityMemberSpecialization {
             specializes: sym, ..
-        } => f.text(format!(
-            "{}.{}",
-            sym.module_string(c.interns),
-            sym.as_str(c.interns),
-        )),
+        } => symbol(c, f, *sym),
         AppliedTag {
             tag_name,
             arguments,
@@ -346,12 +530,12 @@ fn pattern<'a>(
                 f.intersperse(
                     destructs.iter().map(|l| &l.value).map(
                         |RecordDestruct { label, typ, .. }| match typ {
-                            roc_can::pattern::DestructType::Required => f.text(label.as_str()),
-                            roc_can::pattern::DestructType::Optional(_, e) => f
+                            DestructType::Required => f.text(label.as_str()),
+                            DestructType::Optional(_, e) => f
                                 .text(label.as_str())
                                 .append(f.text(" ? "))
                                 .append(expr(c, EPrec::Free, f, &e.value)),
-                            roc_can::pattern::DestructType::Guard(_, p) => f
+                            DestructType::Guard(_, p) => f
                                 .text(label.as_str())
                                 .append(f.text(": "))
                                 .append(pattern(c, Free, f, &p.value)),
@@ -362,6 +546,41 @@ fn pattern<'a>(
             )
             .append(f.text("}"))
             .group(),
+        TupleDestructure { destructs, .. } => f
+            .text("(")
+            .append(
+                f.intersperse(
+                    destructs
+                        .iter()
+                        .map(|l| &l.value)
+                        .map(|TupleDestruct { typ: (_, p), .. }| pattern(c, Free, f, &p.value)),
+                    f.text(", "),
+                ),
+            )
+            .append(f.text(")"))
+            .group(),
+        List {
+            patterns: ListPatterns { patterns, opt_rest },
+            ..
+        } => {
+            let mut elems: Vec<_> = patterns
+                .iter()
+                .map(|lp| pattern(c, Free, f, &lp.value))
+                .collect();
+
+            if let Some((index, opt_name)) = opt_rest {
+                let rest = match opt_name {
+                    Some(name) => f.text(format!("..{}", name.value.as_str(c.interns))),
+                    None => f.text(".."),
+                };
+                elems.insert(*index, rest);
+            }
+
+            f.text("[")
+                .append(f.intersperse(elems, f.text(", ")))
+                .append(f.text("]"))
+                .group()
+        }
         NumLiteral(_, n, _, _) | IntLiteral(_, _, n, _, _) | FloatLiteral(_, _, n, _, _) => {
             f.text(&**n)
         }
@@ -369,9 +588,8 @@ fn pattern<'a>(
         SingleQuote(c) => f.text(format!("'{}'", c)),
         Underscore => f.text("_"),
 
-        Shadowed(_, _, _) => todo!(),
-        OpaqueNotInScope(_) => todo!(),
-        UnsupportedPattern(_) => todo!(),
-        MalformedPattern(_, _) => todo!(),
+        Shadowed(_, _, sym) => symbol(c, f, *sym),
+        OpaqueNotInScope(ident) => f.text(format!("@{}", ident.value.as_str())),
+        UnsupportedPattern(_) | MalformedPattern(_, _) => f.text("#malformed_pattern"),
     }
 }
@@ -535,6 +535,9 @@ fn canonicalize_claimed_ability_impl<'a>(
         AssignedField::SpaceBefore(_, _) | AssignedField::SpaceAfter(_, _) => {
             internal_error!("unreachable")
         }
+        AssignedField::Nested(_, _) => {
+            internal_error!("only record updates have nested fields")
+        }
     }
 }
 
@@ -626,6 +629,7 @@ fn canonicalize_opaque<'a>(
     if let Some(has_abilities) = has_abilities {
         let has_abilities = has_abilities.value.collection();
 
+        let mut declared_abilities = vec![];
         let mut derived_abilities = vec![];
 
         for has_ability in has_abilities.items {
@@ -670,6 +674,8 @@ fn canonicalize_opaque<'a>(
                 }
             };
 
+            declared_abilities.push(ability);
+
             if let Some(impls) = opt_impls {
                 let mut impl_map: VecMap<Symbol, Loc<MemberImpl>> = VecMap::default();
 
@@ -778,6 +784,11 @@ fn canonicalize_opaque<'a>(
             }
         }
 
+        // e.g. an opaque with `Hash` can also be compared with `==`.
+        scope
+            .abilities_store
+            .register_implied_implementations(name.value, &declared_abilities);
+
         if !derived_abilities.is_empty() {
             // Fresh instance of this opaque to be checked for derivability during solving.
             let fresh_inst = Type::DelayedAlias(AliasCommon {
@@ -927,7 +938,7 @@ fn canonicalize_value_defs<'a>(
                 // that get would have gotten added later in the defs list!
                 pending_value_defs.push(pending_def);
             }
-            PendingValue::SignatureDefMismatch => { /* skip */ }
+            PendingValue::SignatureDefMismatch | PendingValue::Malformed => { /* skip */ }
             PendingValue::Expect(pending_expect) => {
                 pending_expects.push(pending_expect);
             }
@@ -1811,6 +1822,27 @@ fn pattern_to_vars_by_symbol(
             }
         }
 
+        TupleDestructure { destructs, .. } => {
+            for destruct in destructs {
+                let (var, nested) = &destruct.value.typ;
+                pattern_to_vars_by_symbol(vars_by_symbol, &nested.value, *var);
+            }
+        }
+
+        List {
+            list_var,
+            elem_var,
+            patterns,
+        } => {
+            for pat in patterns.patterns.iter() {
+                pattern_to_vars_by_symbol(vars_by_symbol, &pat.value, *elem_var);
+            }
+
+            if let Some((_, Some(rest_sym))) = &patterns.opt_rest {
+                vars_by_symbol.insert(rest_sym.value, *list_var);
+            }
+        }
+
         NumLiteral(..)
         | IntLiteral(..)
         | FloatLiteral(..)
@@ -2444,6 +2476,8 @@ enum PendingValue<'a> {
     Expect(PendingExpect<'a>),
     ExpectFx(PendingExpect<'a>),
     SignatureDefMismatch,
+    /// A def the parser couldn't make sense of; it already reported the syntax error.
+    Malformed,
 }
 
 struct PendingExpect<'a> {
@@ -2563,6 +2597,8 @@ fn to_pending_value_def<'a>(
             condition,
             preceding_comment: *preceding_comment,
         }),
+
+        Malformed(_) => PendingValue::Malformed,
     }
 }
 
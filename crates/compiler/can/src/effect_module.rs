@@ -1,10 +1,14 @@
 use crate::annotation::IntroducedVariables;
 use crate::def::Def;
-use crate::expr::{AnnotatedMark, ClosureData, Declarations, Expr, Recursive, WhenBranchPattern};
+use crate::expr::{
+    AnnotatedMark, ClosureData, Declarations, Expr, IntValue, Recursive, WhenBranchPattern,
+};
+use crate::mock_effects::CanonicalMockedCall;
+use crate::num::{IntBound, IntLitWidth};
 use crate::pattern::Pattern;
 use crate::scope::Scope;
 use roc_collections::{SendMap, VecMap, VecSet};
-use roc_module::called_via::CalledVia;
+use roc_module::called_via::{BinOp, CalledVia};
 use roc_module::ident::TagName;
 use roc_module::symbol::Symbol;
 use roc_region::all::{Loc, Region};
@@ -1314,6 +1318,7 @@ pub fn build_host_exposed_def(
     effect_symbol: Symbol,
     var_store: &mut VarStore,
     annotation: crate::annotation::Annotation,
+    mocked_calls: Option<Vec<CanonicalMockedCall>>,
 ) -> Def {
     let expr_var = var_store.fresh();
     let pattern = Pattern::Identifier(symbol);
@@ -1354,12 +1359,8 @@ pub fn build_host_exposed_def(
                     linked_symbol_arguments.push((arg_var, Expr::Var(arg_symbol)));
                 }
 
-                let foreign_symbol_name = format!("roc_fx_{}", ident);
-                let low_level_call = Expr::ForeignCall {
-                    foreign_symbol: foreign_symbol_name.into(),
-                    args: linked_symbol_arguments,
-                    ret_var: var_store.fresh(),
-                };
+                let low_level_call =
+                    build_effect_call(ident, linked_symbol_arguments, mocked_calls, var_store);
 
                 let effect_closure_symbol = {
                     let name = format!("effect_closure_{}", ident);
@@ -1408,12 +1409,8 @@ pub fn build_host_exposed_def(
             _ => {
                 // not a function
 
-                let foreign_symbol_name = format!("roc_fx_{}", ident);
-                let low_level_call = Expr::ForeignCall {
-                    foreign_symbol: foreign_symbol_name.into(),
-                    args: linked_symbol_arguments,
-                    ret_var: var_store.fresh(),
-                };
+                let low_level_call =
+                    build_effect_call(ident, linked_symbol_arguments, mocked_calls, var_store);
 
                 let effect_closure_symbol = {
                     let name = format!("effect_closure_{}", ident);
@@ -1473,6 +1470,218 @@ pub fn build_host_exposed_def(
     }
 }
 
+/// The call that performs a hosted effect: the platform's `roc_fx_<ident>` function, unless
+/// `roc test --mock-effects` gave a script for it. Then the effect responds with the script's
+/// response to whichever call of it the script expects next, like
+///
+/// ```roc
+/// when roc_mock_next_call "stdinLine" is
+///     0 -> "Alice"
+///     3 -> "Bob"
+///     _ -> crash "..."
+/// ```
+fn build_effect_call(
+    ident: &str,
+    arguments: Vec<(Variable, Expr)>,
+    mocked_calls: Option<Vec<CanonicalMockedCall>>,
+    var_store: &mut VarStore,
+) -> Expr {
+    let calls = match mocked_calls {
+        None => {
+            let foreign_symbol_name = format!("roc_fx_{}", ident);
+
+            return Expr::ForeignCall {
+                foreign_symbol: foreign_symbol_name.into(),
+                args: arguments,
+                ret_var: var_store.fresh(),
+            };
+        }
+        Some(calls) => calls,
+    };
+
+    // the builtins ask the test runner for the key of the scripted call
+    let next_call = Expr::ForeignCall {
+        foreign_symbol: "roc_mock_next_call".into(),
+        args: vec![(var_store.fresh(), Expr::Str(ident.into()))],
+        ret_var: var_store.fresh(),
+    };
+
+    let mut branches: Vec<_> = calls
+        .into_iter()
+        .map(|call| {
+            let pattern = Pattern::IntLiteral(
+                var_store.fresh(),
+                var_store.fresh(),
+                call.key.to_string().into(),
+                IntValue::I128((call.key as i128).to_ne_bytes()),
+                IntBound::Exact(IntLitWidth::U64),
+            );
+
+            crate::expr::WhenBranch {
+                patterns: vec![WhenBranchPattern {
+                    pattern: Loc::at_zero(pattern),
+                    degenerate: false,
+                }],
+                value: build_mocked_response(ident, &arguments, call, var_store),
+                guard: None,
+                redundant: RedundantMark::new(var_store),
+            }
+        })
+        .collect();
+
+    // the runner only answers with keys of calls to this effect
+    let unreachable = build_crash(
+        format!(
+            "the mock effects script has no response for this call of {}",
+            ident
+        ),
+        var_store,
+    );
+
+    branches.push(crate::expr::WhenBranch {
+        patterns: vec![WhenBranchPattern {
+            pattern: Loc::at_zero(Pattern::Underscore),
+            degenerate: false,
+        }],
+        value: Loc::at_zero(unreachable),
+        guard: None,
+        redundant: RedundantMark::new(var_store),
+    });
+
+    build_when(next_call, branches, var_store)
+}
+
+/// The response to a scripted call, if the effect was called with the arguments the script
+/// expects. Otherwise the test runner is told, and reports the call like an unexpected one.
+///
+/// ```roc
+/// if Bool.isEq closure_arg_stdoutLine_0 "Hello" then
+///     {}
+/// else
+///     when roc_mock_unexpected_arguments "stdoutLine" 3 is
+///         _ -> crash "..."
+/// ```
+fn build_mocked_response(
+    ident: &str,
+    arguments: &[(Variable, Expr)],
+    call: CanonicalMockedCall,
+    var_store: &mut VarStore,
+) -> Loc<Expr> {
+    let CanonicalMockedCall {
+        key,
+        arguments: expected,
+        response,
+    } = call;
+
+    if expected.is_empty() {
+        return response;
+    }
+
+    if expected.len() != arguments.len() {
+        let message = format!(
+            "the mock effects script calls {} with {} arguments, but it takes {}",
+            ident,
+            expected.len(),
+            arguments.len()
+        );
+
+        return Loc::at_zero(build_crash(message, var_store));
+    }
+
+    // compare the arguments in order, so the first one is outermost
+    arguments.iter().zip(expected).rev().fold(
+        response,
+        |matched, ((_, argument), (is_eq, value))| {
+            let boxed = (
+                var_store.fresh(),
+                is_eq,
+                var_store.fresh(),
+                var_store.fresh(),
+            );
+            let arguments = vec![
+                (var_store.fresh(), Loc::at_zero(argument.clone())),
+                (var_store.fresh(), value),
+            ];
+            let condition = Expr::Call(Box::new(boxed), arguments, CalledVia::BinOp(BinOp::Equals));
+
+            Loc::at_zero(Expr::If {
+                cond_var: var_store.fresh(),
+                branch_var: var_store.fresh(),
+                branches: vec![(Loc::at_zero(condition), matched)],
+                // every branch gets its own copy, with variables of its own
+                final_else: Box::new(Loc::at_zero(build_mismatch(ident, key, var_store))),
+            })
+        },
+    )
+}
+
+/// Tell the test runner that a mocked call got other arguments than the script expected.
+fn build_mismatch(ident: &str, key: u64, var_store: &mut VarStore) -> Expr {
+    let unexpected_arguments = Expr::ForeignCall {
+        foreign_symbol: "roc_mock_unexpected_arguments".into(),
+        args: vec![
+            (var_store.fresh(), Expr::Str(ident.into())),
+            (
+                var_store.fresh(),
+                Expr::Int(
+                    var_store.fresh(),
+                    var_store.fresh(),
+                    key.to_string().into(),
+                    IntValue::I128((key as i128).to_ne_bytes()),
+                    IntBound::Exact(IntLitWidth::U64),
+                ),
+            ),
+        ],
+        ret_var: var_store.fresh(),
+    };
+
+    // the builtins panic once the runner knows, so this crash is never reached
+    let crash = build_crash(
+        format!(
+            "the mock effects script expected other arguments for this call of {}",
+            ident
+        ),
+        var_store,
+    );
+
+    build_when(
+        unexpected_arguments,
+        vec![crate::expr::WhenBranch {
+            patterns: vec![WhenBranchPattern {
+                pattern: Loc::at_zero(Pattern::Underscore),
+                degenerate: false,
+            }],
+            value: Loc::at_zero(crash),
+            guard: None,
+            redundant: RedundantMark::new(var_store),
+        }],
+        var_store,
+    )
+}
+
+fn build_crash(message: String, var_store: &mut VarStore) -> Expr {
+    Expr::Crash {
+        msg: Box::new(Loc::at_zero(Expr::Str(message.into()))),
+        ret_var: var_store.fresh(),
+    }
+}
+
+fn build_when(
+    cond: Expr,
+    branches: Vec<crate::expr::WhenBranch>,
+    var_store: &mut VarStore,
+) -> Expr {
+    Expr::When {
+        cond_var: var_store.fresh(),
+        expr_var: var_store.fresh(),
+        region: Region::zero(),
+        loc_cond: Box::new(Loc::at_zero(cond)),
+        branches,
+        branches_cond_var: var_store.fresh(),
+        exhaustive: ExhaustiveMark::new(var_store),
+    }
+}
+
 pub fn build_effect_actual(a_type: Type, var_store: &mut VarStore) -> Type {
     let closure_var = var_store.fresh();
 
@@ -3,7 +3,7 @@ use crate::scope::Scope;
 use bumpalo::Bump;
 use roc_collections::{MutMap, VecSet};
 use roc_module::ident::{Ident, Lowercase, ModuleName};
-use roc_module::symbol::{IdentIdsByModule, ModuleId, ModuleIds, Symbol};
+use roc_module::symbol::{IdentIds, IdentIdsByModule, ModuleId, ModuleIds, Symbol};
 use roc_problem::can::{Problem, RuntimeError};
 use roc_region::all::{Loc, Region};
 
@@ -142,6 +142,14 @@ impl<'a> Env<'a> {
         } else {
             match self.dep_idents.get(&module_id) {
                 Some(exposed_ids) => match exposed_ids.get_id(ident) {
+                    Some(ident_id)
+                        if !self.home.is_builtin()
+                            && Symbol::new(module_id, ident_id)
+                                .is_exposed_for_builtin_derivers() =>
+                    {
+                        // only the compiler and builtin modules may use these
+                        Err(self.value_not_exposed(module_id, exposed_ids, ident, region))
+                    }
                     Some(ident_id) => {
                         let symbol = Symbol::new(module_id, ident_id);
 
@@ -153,23 +161,7 @@ impl<'a> Env<'a> {
 
                         Ok(symbol)
                     }
-                    None => {
-                        let exposed_values = exposed_ids
-                            .ident_strs()
-                            .filter(|(_, ident)| ident.starts_with(|c: char| c.is_lowercase()))
-                            .map(|(_, ident)| Lowercase::from(ident))
-                            .collect();
-                        Err(RuntimeError::ValueNotExposed {
-                            module_name: self
-                                .module_ids
-                                .get_name(module_id)
-                                .expect("Module ID known, but not in the module IDs somehow")
-                                .clone(),
-                            ident: Ident::from(ident),
-                            region,
-                            exposed_values,
-                        })
-                    }
+                    None => Err(self.value_not_exposed(module_id, exposed_ids, ident, region)),
                 },
                 None => Err(RuntimeError::ModuleNotImported {
                     module_name: self
@@ -190,6 +182,34 @@ impl<'a> Env<'a> {
         }
     }
 
+    pub fn value_not_exposed(
+        &self,
+        module_id: ModuleId,
+        exposed_ids: &IdentIds,
+        ident: &str,
+        region: Region,
+    ) -> RuntimeError {
+        let exposed_values = exposed_ids
+            .ident_strs()
+            .filter(|(ident_id, ident)| {
+                ident.starts_with(|c: char| c.is_lowercase())
+                    && !Symbol::new(module_id, *ident_id).is_exposed_for_builtin_derivers()
+            })
+            .map(|(_, ident)| Lowercase::from(ident))
+            .collect();
+
+        RuntimeError::ValueNotExposed {
+            module_name: self
+                .module_ids
+                .get_name(module_id)
+                .expect("Module ID known, but not in the module IDs somehow")
+                .clone(),
+            ident: Ident::from(ident),
+            region,
+            exposed_values,
+        }
+    }
+
     pub fn problem(&mut self, problem: Problem) {
         self.problems.push(problem)
     }
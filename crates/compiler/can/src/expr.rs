@@ -15,11 +15,12 @@ use roc_collections::soa::Index;
 use roc_collections::{SendMap, VecMap, VecSet};
 use roc_error_macros::internal_error;
 use roc_module::called_via::CalledVia;
-use roc_module::ident::{ForeignSymbol, Lowercase, TagName};
+use roc_module::ident::{ForeignSymbol, IndexOrField, Lowercase, TagName};
 use roc_module::low_level::LowLevel;
 use roc_module::symbol::Symbol;
 use roc_parse::ast::{self, Defs, EscapedChar, StrLiteral};
 use roc_parse::pattern::PatternType::*;
+use roc_parse::string_literal::block_string_segments;
 use roc_problem::can::{PrecedenceProblem, Problem, RuntimeError};
 use roc_region::all::{Loc, Region};
 use roc_types::subs::{ExhaustiveMark, IllegalCycleMark, RedundantMark, VarStore, Variable};
@@ -164,6 +165,11 @@ pub enum Expr {
     /// Empty record constant
     EmptyRecord,
 
+    Tuple {
+        tuple_var: Variable,
+        elems: Vec<(Variable, Box<Loc<Expr>>)>,
+    },
+
     /// Look up exactly one field on a record, e.g. (expr).foo.
     Access {
         record_var: Variable,
@@ -172,7 +178,15 @@ pub enum Expr {
         loc_expr: Box<Loc<Expr>>,
         field: Lowercase,
     },
-    /// field accessor as a function, e.g. (.foo) expr
+    /// Look up exactly one element on a tuple, e.g. (expr).1.
+    TupleAccess {
+        tuple_var: Variable,
+        ext_var: Variable,
+        elem_var: Variable,
+        loc_expr: Box<Loc<Expr>>,
+        index: usize,
+    },
+    /// field or element accessor as a function, e.g. (.foo) expr or (.1) expr
     Accessor(AccessorData),
 
     Update {
@@ -238,6 +252,21 @@ pub enum Expr {
         lookups_in_cond: Vec<(Symbol, Variable)>,
     },
 
+    /// `dbg expr`: evaluates to `expr`, printing its value along the way.
+    /// The value is bound to `symbol`, so it can be looked up when printing it.
+    Dbg {
+        loc_expr: Box<Loc<Expr>>,
+        variable: Variable,
+        symbol: Symbol,
+    },
+
+    /// `crash msg`: aborts the program with the message `msg`.
+    /// It can stand in for a value of any type, so `ret_var` is left unconstrained.
+    Crash {
+        msg: Box<Loc<Expr>>,
+        ret_var: Variable,
+    },
+
     /// Rendered as empty box in editor
     TypedHole(Variable),
 
@@ -266,7 +295,9 @@ impl Expr {
             Self::Closure(..) => Category::Lambda,
             Self::Record { .. } => Category::Record,
             Self::EmptyRecord => Category::Record,
+            Self::Tuple { .. } => Category::Tuple,
             Self::Access { field, .. } => Category::Access(field.clone()),
+            Self::TupleAccess { index, .. } => Category::TupleAccess(*index),
             Self::Accessor(data) => Category::Accessor(data.field.clone()),
             Self::Update { .. } => Category::Record,
             Self::Tag {
@@ -285,9 +316,10 @@ impl Expr {
             }
             Self::Expect { .. } => Category::Expect,
             Self::ExpectFx { .. } => Category::Expect,
+            Self::Dbg { loc_expr, .. } => loc_expr.value.category(),
 
             // these nodes place no constraints on the expression's type
-            Self::TypedHole(_) | Self::RuntimeError(..) => Category::Unknown,
+            Self::TypedHole(_) | Self::RuntimeError(..) | Self::Crash { .. } => Category::Unknown,
         }
     }
 }
@@ -330,7 +362,8 @@ pub struct ClosureData {
     pub loc_body: Box<Loc<Expr>>,
 }
 
-/// A record accessor like `.foo`, which is equivalent to `\r -> r.foo`
+/// A record accessor like `.foo`, which is equivalent to `\r -> r.foo`,
+/// or a tuple accessor like `.1`, which is equivalent to `\t -> t.1`
 /// Accessors are desugared to closures; they need to have a name
 /// so the closure can have a correct lambda set.
 ///
@@ -344,7 +377,7 @@ pub struct AccessorData {
     pub closure_var: Variable,
     pub ext_var: Variable,
     pub field_var: Variable,
-    pub field: Lowercase,
+    pub field: IndexOrField,
 }
 
 impl AccessorData {
@@ -366,12 +399,22 @@ impl AccessorData {
         // into
         //
         // (\r -> r.foo)
-        let body = Expr::Access {
-            record_var,
-            ext_var,
-            field_var,
-            loc_expr: Box::new(Loc::at_zero(Expr::Var(record_symbol))),
-            field,
+        let loc_expr = Box::new(Loc::at_zero(Expr::Var(record_symbol)));
+        let body = match field {
+            IndexOrField::Field(field) => Expr::Access {
+                record_var,
+                ext_var,
+                field_var,
+                loc_expr,
+                field,
+            },
+            IndexOrField::Index(index) => Expr::TupleAccess {
+                tuple_var: record_var,
+                ext_var,
+                elem_var: field_var,
+                loc_expr,
+                index,
+            },
         };
 
         let loc_body = Loc::at_zero(body);
@@ -581,9 +624,36 @@ pub fn canonicalize_expr<'a>(
                         }),
                         Output::default(),
                     ),
+                    Err(CanonicalizeRecordProblem::Malformed { record_region }) => (
+                        Expr::RuntimeError(roc_problem::can::RuntimeError::MalformedExpr(
+                            record_region,
+                        )),
+                        Output::default(),
+                    ),
                 }
             }
         }
+        ast::Expr::Tuple(elems) => {
+            let mut can_elems = Vec::with_capacity(elems.len());
+            let mut output = Output::default();
+
+            for loc_elem in elems.iter() {
+                let (can_elem, elem_out) =
+                    canonicalize_expr(env, var_store, scope, loc_elem.region, &loc_elem.value);
+
+                output.references.union_mut(&elem_out.references);
+
+                can_elems.push((var_store.fresh(), Box::new(can_elem)));
+            }
+
+            (
+                Tuple {
+                    tuple_var: var_store.fresh(),
+                    elems: can_elems,
+                },
+                output,
+            )
+        }
         ast::Expr::RecordUpdate {
             fields,
             update: loc_update,
@@ -591,7 +661,25 @@ pub fn canonicalize_expr<'a>(
             let (can_update, update_out) =
                 canonicalize_expr(env, var_store, scope, loc_update.region, &loc_update.value);
             if let Var(symbol) = &can_update.value {
-                match canonicalize_fields(env, var_store, scope, region, fields.items) {
+                let record = Loc::at(can_update.region, *symbol);
+                let record_name = match loc_update.value {
+                    ast::Expr::Var {
+                        module_name: "",
+                        ident,
+                    } => ident.to_string(),
+                    ast::Expr::Var { module_name, ident } => format!("{}.{}", module_name, ident),
+                    _ => internal_error!("record updates only parse an identifier"),
+                };
+
+                match canonicalize_update_fields(
+                    env,
+                    var_store,
+                    scope,
+                    region,
+                    record,
+                    &record_name,
+                    fields.items,
+                ) {
                     Ok((can_fields, mut output)) => {
                         output.references.union_mut(&update_out.references);
 
@@ -616,6 +704,12 @@ pub fn canonicalize_expr<'a>(
                         }),
                         Output::default(),
                     ),
+                    Err(CanonicalizeRecordProblem::Malformed { record_region }) => (
+                        Expr::RuntimeError(roc_problem::can::RuntimeError::MalformedExpr(
+                            record_region,
+                        )),
+                        Output::default(),
+                    ),
                 }
             } else {
                 // only (optionally qualified) variables can be updated, not arbitrary expressions
@@ -744,6 +838,25 @@ pub fn canonicalize_expr<'a>(
                         }
                     }
                 }
+            } else if let ast::Expr::Crash = loc_fn.value {
+                // We treat crash specially, since crashing must be applied with one argument.
+
+                debug_assert!(!args.is_empty());
+
+                if args.len() > 1 {
+                    let problem =
+                        roc_problem::can::RuntimeError::CrashAppliedToMultipleArgs(region);
+                    env.problem(Problem::RuntimeError(problem.clone()));
+                    (RuntimeError(problem), output)
+                } else {
+                    let (_, msg) = args.pop().unwrap();
+                    let crash = Crash {
+                        msg: Box::new(msg),
+                        ret_var: var_store.fresh(),
+                    };
+
+                    (crash, output)
+                }
             } else {
                 // Canonicalize the function expression and its arguments
                 let (fn_expr, fn_expr_output) =
@@ -834,6 +947,13 @@ pub fn canonicalize_expr<'a>(
 
             (RuntimeError(problem), Output::default())
         }
+        ast::Expr::Crash => {
+            // Crash cannot be used on its own; it must be applied to a message.
+            let problem = roc_problem::can::RuntimeError::CrashNotApplied(region);
+            env.problem(Problem::RuntimeError(problem.clone()));
+
+            (RuntimeError(problem), Output::default())
+        }
         ast::Expr::Defs(loc_defs, loc_ret) => {
             // The body expression gets a new scope for canonicalization,
             scope.inner_scope(|inner_scope| {
@@ -901,16 +1021,24 @@ pub fn canonicalize_expr<'a>(
         ast::Expr::Access(record_expr, field) => {
             let (loc_expr, output) = canonicalize_expr(env, var_store, scope, region, record_expr);
 
-            (
-                Access {
+            let expr = match IndexOrField::from(*field) {
+                IndexOrField::Field(field) => Access {
                     record_var: var_store.fresh(),
                     field_var: var_store.fresh(),
                     ext_var: var_store.fresh(),
                     loc_expr: Box::new(loc_expr),
-                    field: Lowercase::from(*field),
+                    field,
                 },
-                output,
-            )
+                IndexOrField::Index(index) => TupleAccess {
+                    tuple_var: var_store.fresh(),
+                    elem_var: var_store.fresh(),
+                    ext_var: var_store.fresh(),
+                    loc_expr: Box::new(loc_expr),
+                    index,
+                },
+            };
+
+            (expr, output)
         }
         ast::Expr::AccessorFunction(field) => (
             Accessor(AccessorData {
@@ -1006,6 +1134,19 @@ pub fn canonicalize_expr<'a>(
                 output,
             )
         }
+        ast::Expr::Dbg(loc_expr) => {
+            let (loc_expr, output) =
+                canonicalize_expr(env, var_store, scope, loc_expr.region, &loc_expr.value);
+
+            (
+                Dbg {
+                    loc_expr: Box::new(loc_expr),
+                    variable: var_store.fresh(),
+                    symbol: scope.gen_unique_symbol(),
+                },
+                output,
+            )
+        }
         ast::Expr::If(if_thens, final_else_branch) => {
             let mut branches = Vec::with_capacity(if_thens.len());
             let mut output = Output::default();
@@ -1085,6 +1226,12 @@ pub fn canonicalize_expr<'a>(
             use roc_problem::can::RuntimeError::*;
             (RuntimeError(MalformedClosure(region)), Output::default())
         }
+        ast::Expr::Malformed(_) => {
+            use roc_problem::can::RuntimeError::*;
+
+            // the syntax error was reported by the parser
+            (RuntimeError(MalformedExpr(region)), Output::default())
+        }
         ast::Expr::MalformedIdent(name, bad_ident) => {
             use roc_problem::can::RuntimeError::*;
 
@@ -1461,6 +1608,9 @@ enum CanonicalizeRecordProblem {
         field_region: Region,
         record_region: Region,
     },
+    Malformed {
+        record_region: Region,
+    },
 }
 fn canonicalize_fields<'a>(
     env: &mut Env<'a>,
@@ -1509,17 +1659,265 @@ fn canonicalize_fields<'a>(
                     record_region: region,
                 });
             }
+            Err(CanonicalizeFieldProblem::Malformed) => {
+                // the syntax error was reported by the parser
+                return Err(CanonicalizeRecordProblem::Malformed {
+                    record_region: region,
+                });
+            }
         }
     }
 
     Ok((can_fields, output))
 }
 
+/// A field set by a record update, grouped by the first segment of its path.
+enum UpdateField<'a> {
+    /// e.g. `count: 0`
+    Value(Field),
+    /// e.g. `settings.theme: Dark, settings.size: 12`; holds the rest of each path
+    Nested {
+        label: Loc<&'a str>,
+        region: Region,
+        fields: Vec<(&'a [Loc<&'a str>], Field)>,
+    },
+}
+
+impl UpdateField<'_> {
+    fn region(&self) -> Region {
+        match self {
+            UpdateField::Value(field) => field.region,
+            UpdateField::Nested { region, .. } => *region,
+        }
+    }
+}
+
+/// Canonicalize the fields of a record update. Nested paths are desugared into
+/// updates of the intermediate records, so that
+///
+/// ```roc
+/// { model & settings.theme: Dark }
+/// ```
+///
+/// becomes
+///
+/// ```roc
+/// { model & settings: { model.settings & theme: Dark } }
+/// ```
+///
+/// where `model.settings` is a generated definition bound to that field access.
+fn canonicalize_update_fields<'a>(
+    env: &mut Env<'a>,
+    var_store: &mut VarStore,
+    scope: &mut Scope,
+    region: Region,
+    record: Loc<Symbol>,
+    record_name: &str,
+    fields: &'a [Loc<ast::AssignedField<'a, ast::Expr<'a>>>],
+) -> Result<(SendMap<Lowercase, Field>, Output), CanonicalizeRecordProblem> {
+    let mut paths = Vec::with_capacity(fields.len());
+    let mut output = Output::default();
+
+    for loc_field in fields.iter() {
+        match canonicalize_field(env, var_store, scope, &loc_field.value, loc_field.region) {
+            Ok((_, field_expr, field_out, field_var)) => {
+                let field = Field {
+                    var: field_var,
+                    region: loc_field.region,
+                    loc_expr: Box::new(field_expr),
+                };
+
+                let path = field_label_path(env.arena, &loc_field.value);
+                paths.push((path, field));
+
+                output.references.union_mut(&field_out.references);
+            }
+            Err(CanonicalizeFieldProblem::InvalidOptionalValue {
+                field_name,
+                field_region,
+            }) => {
+                env.problems.push(Problem::InvalidOptionalValue {
+                    field_name: field_name.clone(),
+                    field_region,
+                    record_region: region,
+                });
+                return Err(CanonicalizeRecordProblem::InvalidOptionalValue {
+                    field_name,
+                    field_region,
+                    record_region: region,
+                });
+            }
+            Err(CanonicalizeFieldProblem::Malformed) => {
+                // the syntax error was reported by the parser
+                return Err(CanonicalizeRecordProblem::Malformed {
+                    record_region: region,
+                });
+            }
+        }
+    }
+
+    let can_fields =
+        desugar_update_paths(env, var_store, scope, region, record, record_name, paths);
+
+    Ok((can_fields, output))
+}
+
+/// The labels of a (possibly nested) record update field like `settings.theme`, outermost first.
+fn field_label_path<'a>(
+    arena: &'a bumpalo::Bump,
+    mut field: &ast::AssignedField<'a, ast::Expr<'a>>,
+) -> &'a [Loc<&'a str>] {
+    use roc_parse::ast::AssignedField::*;
+
+    let mut segments = bumpalo::collections::Vec::new_in(arena);
+
+    loop {
+        match field {
+            RequiredValue(label, _, _) | OptionalValue(label, _, _) | LabelOnly(label) => {
+                segments.push(*label);
+
+                return segments.into_bump_slice();
+            }
+            Nested(label, sub_field) => {
+                segments.push(*label);
+                field = sub_field;
+            }
+            SpaceBefore(sub_field, _) | SpaceAfter(sub_field, _) => field = sub_field,
+            Malformed(_) => internal_error!("malformed record fields are not canonicalized"),
+        }
+    }
+}
+
+fn desugar_update_paths<'a>(
+    env: &mut Env<'a>,
+    var_store: &mut VarStore,
+    scope: &mut Scope,
+    record_region: Region,
+    record: Loc<Symbol>,
+    record_name: &str,
+    paths: Vec<(&'a [Loc<&'a str>], Field)>,
+) -> SendMap<Lowercase, Field> {
+    let mut grouped: Vec<(Lowercase, UpdateField)> = Vec::with_capacity(paths.len());
+
+    for (path, field) in paths {
+        let (label, rest) = path.split_first().unwrap();
+        let field_name = Lowercase::from(label.value);
+
+        let update_field = if rest.is_empty() {
+            UpdateField::Value(field)
+        } else {
+            UpdateField::Nested {
+                label: *label,
+                region: field.region,
+                fields: vec![(rest, field)],
+            }
+        };
+
+        let existing = match grouped.iter_mut().find(|(name, _)| *name == field_name) {
+            Some((_, existing)) => existing,
+            None => {
+                grouped.push((field_name, update_field));
+                continue;
+            }
+        };
+
+        match (existing, update_field) {
+            (
+                UpdateField::Nested { region, fields, .. },
+                UpdateField::Nested {
+                    region: new_region,
+                    fields: new_fields,
+                    ..
+                },
+            ) => {
+                *region = Region::span_across(region, &new_region);
+                fields.extend(new_fields);
+            }
+            (existing, update_field) => {
+                env.problems.push(Problem::DuplicateRecordFieldValue {
+                    field_name,
+                    field_region: update_field.region(),
+                    record_region,
+                    replaced_region: existing.region(),
+                });
+
+                *existing = update_field;
+            }
+        }
+    }
+
+    let mut can_fields = SendMap::default();
+
+    for (field_name, update_field) in grouped {
+        let field = match update_field {
+            UpdateField::Value(field) => field,
+            UpdateField::Nested {
+                label,
+                region,
+                fields,
+            } => {
+                // Bind the record being updated, e.g. `model.settings = model.settings`
+                let nested_name = format!("{}.{}", record_name, label.value);
+                let nested_symbol =
+                    scope.scopeless_symbol(&nested_name.as_str().into(), label.region);
+
+                let access = Expr::Access {
+                    record_var: var_store.fresh(),
+                    ext_var: var_store.fresh(),
+                    field_var: var_store.fresh(),
+                    loc_expr: Box::new(Loc::at(record.region, Expr::Var(record.value))),
+                    field: field_name.clone(),
+                };
+
+                let def = Def {
+                    loc_pattern: Loc::at(label.region, Pattern::Identifier(nested_symbol)),
+                    loc_expr: Loc::at(label.region, access),
+                    expr_var: var_store.fresh(),
+                    pattern_vars: SendMap::default(),
+                    annotation: None,
+                };
+
+                let updates = desugar_update_paths(
+                    env,
+                    var_store,
+                    scope,
+                    record_region,
+                    Loc::at(label.region, nested_symbol),
+                    &nested_name,
+                    fields,
+                );
+
+                let update = Expr::Update {
+                    record_var: var_store.fresh(),
+                    ext_var: var_store.fresh(),
+                    symbol: nested_symbol,
+                    updates,
+                };
+
+                Field {
+                    var: var_store.fresh(),
+                    region,
+                    loc_expr: Box::new(Loc::at(
+                        region,
+                        Expr::LetNonRec(Box::new(def), Box::new(Loc::at(region, update))),
+                    )),
+                }
+            }
+        };
+
+        can_fields.insert(field_name, field);
+    }
+
+    can_fields
+}
+
 enum CanonicalizeFieldProblem {
     InvalidOptionalValue {
         field_name: Lowercase,
         field_region: Region,
     },
+    /// A field the parser skipped over after a syntax error
+    Malformed,
 }
 fn canonicalize_field<'a>(
     env: &mut Env<'a>,
@@ -1559,9 +1957,11 @@ fn canonicalize_field<'a>(
             canonicalize_field(env, var_store, scope, sub_field, region)
         }
 
-        Malformed(_string) => {
-            panic!("TODO canonicalize malformed record field");
-        }
+        // The `settings.` in `{ model & settings.theme: Dark }`; record updates
+        // get the whole path from `field_label_path`
+        Nested(_, sub_field) => canonicalize_field(env, var_store, scope, sub_field, region),
+
+        Malformed(_) => Err(CanonicalizeFieldProblem::Malformed),
     }
 }
 
@@ -1802,6 +2202,35 @@ pub fn inline_calls(var_store: &mut VarStore, scope: &mut Scope, expr: Expr) ->
             }
         }
 
+        Dbg {
+            loc_expr,
+            variable,
+            symbol,
+        } => {
+            let loc_expr = Loc {
+                region: loc_expr.region,
+                value: inline_calls(var_store, scope, loc_expr.value),
+            };
+
+            Dbg {
+                loc_expr: Box::new(loc_expr),
+                variable,
+                symbol,
+            }
+        }
+
+        Crash { msg, ret_var } => {
+            let msg = Loc {
+                region: msg.region,
+                value: inline_calls(var_store, scope, msg.value),
+            };
+
+            Crash {
+                msg: Box::new(msg),
+                ret_var,
+            }
+        }
+
         LetRec(defs, loc_expr, mark) => {
             let mut new_defs = Vec::with_capacity(defs.len());
 
@@ -1892,6 +2321,24 @@ pub fn inline_calls(var_store: &mut VarStore, scope: &mut Scope, expr: Expr) ->
             todo!("Inlining for Access with record_var {:?}, ext_var {:?}, field_var {:?}, loc_expr {:?}, field {:?}", record_var, ext_var, field_var, loc_expr, field);
         }
 
+        Tuple { tuple_var, elems } => {
+            todo!(
+                "Inlining for Tuple with tuple_var {:?} and elems {:?}",
+                tuple_var,
+                elems
+            );
+        }
+
+        TupleAccess {
+            tuple_var,
+            ext_var,
+            elem_var,
+            loc_expr,
+            index,
+        } => {
+            todo!("Inlining for TupleAccess with tuple_var {:?}, ext_var {:?}, elem_var {:?}, loc_expr {:?}, index {:?}", tuple_var, ext_var, elem_var, loc_expr, index);
+        }
+
         Tag {
             tag_union_var: variant_var,
             ext_var,
@@ -2035,7 +2482,11 @@ fn flatten_str_literal<'a>(
     match literal {
         PlainLine(str_slice) => (Expr::Str((*str_slice).into()), Output::default()),
         Line(segments) => flatten_str_lines(env, var_store, scope, &[segments]),
-        Block(lines) => flatten_str_lines(env, var_store, scope, lines),
+        Block(lines) => {
+            let segments = block_string_segments(lines);
+
+            flatten_str_lines(env, var_store, scope, &[&segments])
+        }
     }
 }
 
@@ -2521,9 +2972,11 @@ impl Declarations {
             })
     }
 
-    pub fn expects(&self) -> VecMap<Region, Vec<(Symbol, Variable)>> {
+    pub fn expects(&self) -> ExpectLookups {
         let mut collector = ExpectCollector {
             expects: VecMap::default(),
+            dbgs: VecMap::default(),
+            crashes: VecSet::default(),
         };
 
         let var = Variable::EMPTY_RECORD;
@@ -2556,10 +3009,23 @@ impl Declarations {
             }
         }
 
-        collector.expects
+        ExpectLookups {
+            expects: collector.expects,
+            dbgs: collector.dbgs,
+            crashes: collector.crashes,
+        }
     }
 }
 
+/// The values we need to look up to print failed `expect`s and `dbg`s, by their region
+#[derive(Debug, Default)]
+pub struct ExpectLookups {
+    pub expects: VecMap<Region, Vec<(Symbol, Variable)>>,
+    pub dbgs: VecMap<Region, (Symbol, Variable)>,
+    /// The regions of the messages of `crash`es, which is how a crash reports where it happened
+    pub crashes: VecSet<Region>,
+}
+
 roc_error_macros::assert_sizeof_default!(DeclarationTag, 8);
 
 #[derive(Clone, Copy, Debug)]
@@ -2678,6 +3144,9 @@ fn get_lookup_symbols(expr: &Expr, var_store: &mut VarStore) -> Vec<(Symbol, Var
                 stack.push(&argument.1.value);
             }
             Expr::Access { loc_expr, .. }
+            | Expr::TupleAccess { loc_expr, .. }
+            | Expr::Dbg { loc_expr, .. }
+            | Expr::Crash { msg: loc_expr, .. }
             | Expr::Closure(ClosureData {
                 loc_body: loc_expr, ..
             }) => {
@@ -2686,6 +3155,9 @@ fn get_lookup_symbols(expr: &Expr, var_store: &mut VarStore) -> Vec<(Symbol, Var
             Expr::Record { fields, .. } => {
                 stack.extend(fields.iter().map(|(_, field)| &field.loc_expr.value));
             }
+            Expr::Tuple { elems, .. } => {
+                stack.extend(elems.iter().map(|(_, elem)| &elem.value));
+            }
             Expr::Expect {
                 loc_continuation, ..
             }
@@ -2809,6 +3281,8 @@ fn toplevel_expect_to_inline_expect_help(mut loc_expr: Loc<Expr>, has_effects: b
 
 struct ExpectCollector {
     expects: VecMap<Region, Vec<(Symbol, Variable)>>,
+    dbgs: VecMap<Region, (Symbol, Variable)>,
+    crashes: VecSet<Region>,
 }
 
 impl crate::traverse::Visitor for ExpectCollector {
@@ -2827,6 +3301,16 @@ impl crate::traverse::Visitor for ExpectCollector {
                 self.expects
                     .insert(loc_condition.region, lookups_in_cond.to_vec());
             }
+            Expr::Dbg {
+                loc_expr,
+                variable,
+                symbol,
+            } => {
+                self.dbgs.insert(loc_expr.region, (*symbol, *variable));
+            }
+            Expr::Crash { msg, .. } => {
+                self.crashes.insert(msg.region);
+            }
             _ => (),
         }
 
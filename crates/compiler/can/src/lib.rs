@@ -6,12 +6,15 @@ pub mod annotation;
 pub mod builtins;
 pub mod constraint;
 pub mod copy;
+pub mod coverage;
+pub mod debug;
 pub mod def;
 pub mod effect_module;
 pub mod env;
 pub mod exhaustive;
 pub mod expected;
 pub mod expr;
+pub mod mock_effects;
 pub mod module;
 pub mod num;
 pub mod operator;
@@ -0,0 +1,251 @@
+//! Scripts that `roc test --mock-effects` runs `expect-fx` against, instead of a platform.
+//!
+//! A script lists the effect calls an expect-fx should make, in order, along with what each
+//! call responds with. The response is a Roc expression; calls without one respond with `{}`.
+//! A call can also give the arguments it expects, as Roc expressions after the effect's name;
+//! calls without them accept any arguments. Lines starting with `#` are comments.
+//!
+//! ```text
+//! # calls that any expect-fx makes
+//! stdinLine -> "Alice"
+//! stdoutLine
+//!
+//! # calls of the expects written beneath `greet` in the `Main` module
+//! [Main.greet]
+//! stdinLine -> "Bob"
+//! stdoutLine "Hello, Bob!"
+//! ```
+//!
+//! When the script is given, each hosted function responds with the script's canned values
+//! (see [`crate::effect_module::build_host_exposed_def`]), and the test runner checks that the
+//! effects were called in the order the script expects, with the arguments it expects.
+use crate::env::Env;
+use crate::expr::{canonicalize_expr, Expr};
+use crate::scope::Scope;
+use bumpalo::Bump;
+use roc_error_macros::internal_error;
+use roc_parse::ast;
+use roc_region::all::{Loc, Region};
+use roc_types::subs::VarStore;
+use std::fmt;
+
+#[derive(Debug, Clone, Default)]
+pub struct MockEffects {
+    calls: Vec<MockedCall>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockedCall {
+    /// The expect this call is scripted for, or `None` for any expect without a section of its own
+    pub section: Option<String>,
+    /// The name of the hosted function, e.g. `stdoutLine`
+    pub effect: String,
+    /// The source of the Roc expression each argument should equal, or none to accept any
+    pub arguments: Vec<String>,
+    /// The source of the Roc expression the call responds with
+    pub response: String,
+    /// The 1-based line of the script this call is on
+    pub line: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockEffectsError {
+    pub line: u32,
+    pub message: String,
+}
+
+impl fmt::Display for MockEffectsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for MockEffectsError {}
+
+impl MockEffects {
+    pub fn parse(script: &str) -> Result<Self, MockEffectsError> {
+        let mut calls = Vec::new();
+        let mut section = None;
+
+        for (index, line) in script.lines().enumerate() {
+            let line_number = index as u32 + 1;
+            let error = |message: String| MockEffectsError {
+                line: line_number,
+                message,
+            };
+
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                match header.strip_suffix(']').map(str::trim) {
+                    Some(name) if !name.is_empty() => section = Some(name.to_string()),
+                    _ => {
+                        return Err(error(format!(
+                        "`{}` is not a section header; those name an expect, like `[Main.greet]`",
+                        line
+                    )))
+                    }
+                }
+
+                continue;
+            }
+
+            let (call, response) = match line.split_once("->") {
+                Some((call, response)) => (call.trim(), response.trim()),
+                None => (line, "{}"),
+            };
+
+            let arena = Bump::new();
+            let (effect, arguments) = match split_call(&arena, call) {
+                Some((effect, arguments)) if is_lowercase_ident(effect) => (effect, arguments),
+                _ => {
+                    return Err(error(format!(
+                        "`{}` is not a call of a hosted function, like `stdoutLine \"Hello\"`",
+                        call
+                    )))
+                }
+            };
+
+            if response.is_empty() {
+                return Err(error(format!(
+                    "the call of `{}` is missing its response after the `->`",
+                    effect
+                )));
+            }
+
+            if roc_parse::test_helpers::parse_loc_with(&arena, response).is_err() {
+                return Err(error(format!(
+                    "the response `{}` is not a valid Roc expression",
+                    response
+                )));
+            }
+
+            calls.push(MockedCall {
+                section: section.clone(),
+                effect: effect.to_string(),
+                arguments,
+                response: response.to_string(),
+                line: line_number,
+            });
+        }
+
+        Ok(MockEffects { calls })
+    }
+
+    /// The calls that the expect with this name should make, in order, each with a key that is
+    /// unique within the whole script.
+    pub fn script_for(&self, expect_name: &str) -> Vec<(u64, &MockedCall)> {
+        let has_section = self
+            .calls
+            .iter()
+            .any(|call| call.section.as_deref() == Some(expect_name));
+
+        let wanted = if has_section { Some(expect_name) } else { None };
+
+        self.keyed_calls()
+            .filter(|(_, call)| call.section.as_deref() == wanted)
+            .collect()
+    }
+
+    /// Canonicalize all the calls of this effect, anywhere in the script.
+    pub(crate) fn canonicalize_calls<'a>(
+        &self,
+        env: &mut Env<'a>,
+        var_store: &mut VarStore,
+        scope: &mut Scope,
+        effect: &str,
+    ) -> Vec<CanonicalMockedCall> {
+        self.keyed_calls()
+            .filter(|(_, call)| call.effect == effect)
+            .map(|(key, call)| {
+                let mut canonicalize = |source: &str| {
+                    let arena = env.arena;
+                    let source = arena.alloc_str(source);
+
+                    let parsed = match roc_parse::test_helpers::parse_loc_with(arena, source) {
+                        Ok(parsed) => parsed,
+                        Err(_) => {
+                            internal_error!("mocked calls are parsed when reading the script")
+                        }
+                    };
+
+                    let desugared = crate::operator::desugar_expr(arena, arena.alloc(parsed));
+                    let (loc_expr, _output) =
+                        canonicalize_expr(env, var_store, scope, Region::zero(), &desugared.value);
+
+                    loc_expr
+                };
+
+                // compared with `Bool.isEq`, like `==` would
+                let arguments = call
+                    .arguments
+                    .iter()
+                    .map(|argument| (canonicalize("Bool.isEq"), canonicalize(argument)))
+                    .collect();
+
+                CanonicalMockedCall {
+                    key,
+                    arguments,
+                    response: canonicalize(&call.response),
+                }
+            })
+            .collect()
+    }
+
+    fn keyed_calls(&self) -> impl Iterator<Item = (u64, &MockedCall)> {
+        self.calls
+            .iter()
+            .enumerate()
+            .map(|(index, call)| (index as u64, call))
+    }
+}
+
+/// A scripted call of a hosted function, ready to be answered by its generated definition.
+#[derive(Debug)]
+pub struct CanonicalMockedCall {
+    /// The key the test runner answers with when this call is next
+    pub key: u64,
+    /// A lookup of `Bool.isEq`, and the value to compare with it, for each expected argument
+    pub arguments: Vec<(Loc<Expr>, Loc<Expr>)>,
+    pub response: Loc<Expr>,
+}
+
+/// Split a call like `stdoutLine "Hello"` into the name of the effect and the source of each
+/// of its arguments.
+fn split_call<'a>(arena: &'a Bump, call: &'a str) -> Option<(&'a str, Vec<String>)> {
+    let parsed = roc_parse::test_helpers::parse_loc_with(arena, call).ok()?;
+
+    match parsed.value {
+        ast::Expr::Var { module_name, ident } if module_name.is_empty() => {
+            Some((ident, Vec::new()))
+        }
+        ast::Expr::Apply(function, arguments, _) => match function.value {
+            ast::Expr::Var { module_name, ident } if module_name.is_empty() => {
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| {
+                        let start = argument.region.start().offset as usize;
+                        let end = argument.region.end().offset as usize;
+
+                        call[start..end].to_string()
+                    })
+                    .collect();
+
+                Some((ident, arguments))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_lowercase_ident(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    matches!(chars.next(), Some(c) if c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_alphanumeric())
+}
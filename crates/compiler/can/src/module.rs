@@ -3,7 +3,8 @@ use crate::annotation::canonicalize_annotation;
 use crate::def::{canonicalize_defs, Def};
 use crate::effect_module::HostedGeneratedFunctions;
 use crate::env::Env;
-use crate::expr::{ClosureData, Declarations, Expr, Output, PendingDerives};
+use crate::expr::{ClosureData, Declarations, ExpectLookups, Expr, Output, PendingDerives};
+use crate::mock_effects::MockEffects;
 use crate::pattern::{BindingsFromPattern, Pattern};
 use crate::scope::Scope;
 use bumpalo::Bump;
@@ -131,6 +132,8 @@ pub struct Module {
     pub rigid_variables: RigidVariables,
     pub abilities_store: PendingAbilitiesStore,
     pub loc_expects: VecMap<Region, Vec<(Symbol, Variable)>>,
+    pub loc_dbgs: VecMap<Region, (Symbol, Variable)>,
+    pub loc_crashes: VecSet<Region>,
 }
 
 #[derive(Debug, Default)]
@@ -154,6 +157,8 @@ pub struct ModuleOutput {
     pub pending_derives: PendingDerives,
     pub scope: Scope,
     pub loc_expects: VecMap<Region, Vec<(Symbol, Variable)>>,
+    pub loc_dbgs: VecMap<Region, (Symbol, Variable)>,
+    pub loc_crashes: VecSet<Region>,
 }
 
 fn validate_generate_with<'a>(
@@ -273,6 +278,7 @@ pub fn canonicalize_module_defs<'a>(
     exposed_symbols: &VecSet<Symbol>,
     symbols_from_requires: &[(Loc<Symbol>, Loc<TypeAnnotation<'a>>)],
     var_store: &mut VarStore,
+    mock_effects: Option<&MockEffects>,
 ) -> ModuleOutput {
     let mut can_exposed_imports = MutMap::default();
     let mut scope = Scope::new(home, exposed_ident_ids, imported_abilities_state);
@@ -318,7 +324,17 @@ pub fn canonicalize_module_defs<'a>(
     for (ident, (symbol, region)) in exposed_imports {
         let first_char = ident.as_inline_str().as_str().chars().next().unwrap();
 
-        if first_char.is_lowercase() {
+        if first_char.is_lowercase()
+            && !home.is_builtin()
+            && symbol.is_exposed_for_builtin_derivers()
+        {
+            // only the compiler and builtin modules may use these
+            let exposed_ids = dep_idents.get(&symbol.module_id()).unwrap();
+            let error =
+                env.value_not_exposed(symbol.module_id(), exposed_ids, ident.as_str(), region);
+
+            env.problem(Problem::RuntimeError(error));
+        } else if first_char.is_lowercase() {
             // this is a value definition
             let expr_var = var_store.fresh();
 
@@ -379,10 +395,11 @@ pub fn canonicalize_module_defs<'a>(
     let pending_derives = output.pending_derives;
 
     // See if any of the new idents we defined went unused.
-    // If any were unused and also not exposed, report it.
+    // If any were unused and also not exposed (to user code or to the compiler), report it.
     for (symbol, region) in symbols_introduced {
         if !output.references.has_type_or_value_lookup(symbol)
             && !exposed_symbols.contains(&symbol)
+            && !symbol.is_exposed_for_builtin_derivers()
             && !scope.abilities_store.is_specialization_name(symbol)
         {
             env.problem(Problem::UnusedDef(symbol, region));
@@ -536,6 +553,11 @@ pub fn canonicalize_module_defs<'a>(
                                 aliases: Default::default(),
                             };
 
+                            let mocked_calls = mock_effects.map(|mock_effects| {
+                                mock_effects
+                                    .canonicalize_calls(&mut env, var_store, &mut scope, &ident)
+                            });
+
                             let hosted_def = crate::effect_module::build_host_exposed_def(
                                 &mut scope,
                                 *symbol,
@@ -543,6 +565,7 @@ pub fn canonicalize_module_defs<'a>(
                                 effect_symbol,
                                 var_store,
                                 annotation,
+                                mocked_calls,
                             );
 
                             declarations.update_builtin_def(index, hosted_def);
@@ -594,6 +617,11 @@ pub fn canonicalize_module_defs<'a>(
                                 aliases: Default::default(),
                             };
 
+                            let mocked_calls = mock_effects.map(|mock_effects| {
+                                mock_effects
+                                    .canonicalize_calls(&mut env, var_store, &mut scope, &ident)
+                            });
+
                             let hosted_def = crate::effect_module::build_host_exposed_def(
                                 &mut scope,
                                 *symbol,
@@ -601,6 +629,7 @@ pub fn canonicalize_module_defs<'a>(
                                 effect_symbol,
                                 var_store,
                                 annotation,
+                                mocked_calls,
                             );
 
                             declarations.update_builtin_def(index, hosted_def);
@@ -782,7 +811,11 @@ pub fn canonicalize_module_defs<'a>(
         }
     }
 
-    let loc_expects = declarations.expects();
+    let ExpectLookups {
+        expects: loc_expects,
+        dbgs: loc_dbgs,
+        crashes: loc_crashes,
+    } = declarations.expects();
 
     ModuleOutput {
         scope,
@@ -797,6 +830,8 @@ pub fn canonicalize_module_defs<'a>(
         pending_derives,
         lookups,
         loc_expects,
+        loc_dbgs,
+        loc_crashes,
     }
 }
 
@@ -905,6 +940,25 @@ fn fix_values_captured_in_closure_pattern(
                 }
             }
         }
+        TupleDestructure { destructs, .. } => {
+            for loc_destruct in destructs.iter_mut() {
+                let (_, loc_pattern) = &mut loc_destruct.value.typ;
+                fix_values_captured_in_closure_pattern(
+                    &mut loc_pattern.value,
+                    no_capture_symbols,
+                    closure_captures,
+                );
+            }
+        }
+        List { patterns, .. } => {
+            for loc_pat in patterns.patterns.iter_mut() {
+                fix_values_captured_in_closure_pattern(
+                    &mut loc_pat.value,
+                    no_capture_symbols,
+                    closure_captures,
+                );
+            }
+        }
         Identifier(_)
         | NumLiteral(..)
         | IntLiteral(..)
@@ -981,6 +1035,14 @@ fn fix_values_captured_in_closure_expr(
             );
         }
 
+        Dbg { loc_expr, .. } | Crash { msg: loc_expr, .. } => {
+            fix_values_captured_in_closure_expr(
+                &mut loc_expr.value,
+                no_capture_symbols,
+                closure_captures,
+            );
+        }
+
         Closure(ClosureData {
             captured_symbols,
             name,
@@ -1162,7 +1224,17 @@ fn fix_values_captured_in_closure_expr(
             }
         }
 
-        Access { loc_expr, .. } => {
+        Tuple { elems, .. } => {
+            for (_, elem) in elems.iter_mut() {
+                fix_values_captured_in_closure_expr(
+                    &mut elem.value,
+                    no_capture_symbols,
+                    closure_captures,
+                );
+            }
+        }
+
+        Access { loc_expr, .. } | TupleAccess { loc_expr, .. } => {
             fix_values_captured_in_closure_expr(
                 &mut loc_expr.value,
                 no_capture_symbols,
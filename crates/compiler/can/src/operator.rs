@@ -102,6 +102,7 @@ fn desugar_value_def<'a>(arena: &'a Bump, def: &'a ValueDef<'a>) -> ValueDef<'a>
                 preceding_comment: *preceding_comment,
             }
         }
+        malformed @ Malformed(_) => *malformed,
     }
 }
 
@@ -123,8 +124,10 @@ pub fn desugar_expr<'a>(arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> &'a Loc
         | AccessorFunction(_)
         | Var { .. }
         | Underscore { .. }
+        | Crash
         | MalformedIdent(_, _)
         | MalformedClosure
+        | Malformed(_)
         | PrecedenceConflict { .. }
         | Tag(_)
         | OpaqueRef(_) => loc_expr,
@@ -164,6 +167,11 @@ pub fn desugar_expr<'a>(arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> &'a Loc
             })),
         }),
 
+        Tuple(elems) => arena.alloc(Loc {
+            region: loc_expr.region,
+            value: Tuple(elems.map_items(arena, |elem| desugar_expr(arena, elem))),
+        }),
+
         RecordUpdate { fields, update } => {
             // NOTE the `update` field is always a `Var { .. }`, we only desugar it to get rid of
             // any spaces before/after
@@ -344,6 +352,13 @@ pub fn desugar_expr<'a>(arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> &'a Loc
                 region: loc_expr.region,
             })
         }
+        Dbg(loc_inner) => {
+            let desugared_inner = &*arena.alloc(desugar_expr(arena, loc_inner));
+            arena.alloc(Loc {
+                value: Dbg(desugared_inner),
+                region: loc_expr.region,
+            })
+        }
     }
 }
 
@@ -391,6 +406,7 @@ fn desugar_field<'a>(
         }
         SpaceBefore(field, _spaces) => desugar_field(arena, field),
         SpaceAfter(field, _spaces) => desugar_field(arena, field),
+        Nested(loc_str, field) => Nested(*loc_str, arena.alloc(desugar_field(arena, field))),
 
         Malformed(string) => Malformed(string),
     }
@@ -6,10 +6,12 @@ use crate::num::{
     ParsedNumResult,
 };
 use crate::scope::{PendingAbilitiesInScope, Scope};
+use roc_exhaustive::ListArity;
 use roc_module::ident::{Ident, Lowercase, TagName};
 use roc_module::symbol::Symbol;
-use roc_parse::ast::{self, StrLiteral, StrSegment};
+use roc_parse::ast::{self, ExtractSpaces, StrLiteral, StrSegment};
 use roc_parse::pattern::PatternType;
+use roc_parse::string_literal::block_string_segments;
 use roc_problem::can::{MalformedPatternProblem, Problem, RuntimeError, ShadowKind};
 use roc_region::all::{Loc, Region};
 use roc_types::subs::{VarStore, Variable};
@@ -55,6 +57,16 @@ pub enum Pattern {
         ext_var: Variable,
         destructs: Vec<Loc<RecordDestruct>>,
     },
+    TupleDestructure {
+        whole_var: Variable,
+        ext_var: Variable,
+        destructs: Vec<Loc<TupleDestruct>>,
+    },
+    List {
+        list_var: Variable,
+        elem_var: Variable,
+        patterns: ListPatterns,
+    },
     NumLiteral(Variable, Box<str>, IntValue, NumBound),
     IntLiteral(Variable, Variable, Box<str>, IntValue, IntBound),
     FloatLiteral(Variable, Variable, Box<str>, f64, FloatBound),
@@ -91,6 +103,8 @@ impl Pattern {
             AppliedTag { whole_var, .. } => Some(*whole_var),
             UnwrappedOpaque { whole_var, .. } => Some(*whole_var),
             RecordDestructure { whole_var, .. } => Some(*whole_var),
+            TupleDestructure { whole_var, .. } => Some(*whole_var),
+            List { list_var, .. } => Some(*list_var),
             NumLiteral(var, ..) => Some(*var),
             IntLiteral(var, ..) => Some(*var),
             FloatLiteral(var, ..) => Some(*var),
@@ -118,6 +132,10 @@ impl Pattern {
             | MalformedPattern(..)
             | AbilityMemberSpecialization { .. } => true,
             RecordDestructure { destructs, .. } => destructs.is_empty(),
+            TupleDestructure { destructs, .. } => destructs
+                .iter()
+                .all(|d| d.value.typ.1.value.surely_exhaustive()),
+            List { patterns, .. } => patterns.surely_exhaustive(),
             AppliedTag { .. }
             | NumLiteral(..)
             | IntLiteral(..)
@@ -144,6 +162,8 @@ impl Pattern {
             UnwrappedOpaque { opaque, .. } => C::Opaque(*opaque),
             RecordDestructure { destructs, .. } if destructs.is_empty() => C::EmptyRecord,
             RecordDestructure { .. } => C::Record,
+            TupleDestructure { .. } => C::Tuple,
+            List { .. } => C::List,
             NumLiteral(..) => C::Num,
             IntLiteral(..) => C::Int,
             FloatLiteral(..) => C::Float,
@@ -160,6 +180,32 @@ impl Pattern {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct ListPatterns {
+    pub patterns: Vec<Loc<Pattern>>,
+    /// Where the rest pattern is, if there is one: patterns at indices from here on match the
+    /// end of the list. The symbol, if any, is bound to the rest of the list.
+    pub opt_rest: Option<(usize, Option<Loc<Symbol>>)>,
+}
+
+impl ListPatterns {
+    pub fn arity(&self) -> ListArity {
+        match self.opt_rest {
+            Some((i, _)) => {
+                let before = i;
+                let after = self.patterns.len() - before;
+                ListArity::Slice(before, after)
+            }
+            None => ListArity::Exact(self.patterns.len()),
+        }
+    }
+
+    /// Is this `[..]` or `[..rest]`, which match any list?
+    fn surely_exhaustive(&self) -> bool {
+        self.patterns.is_empty() && matches!(self.opt_rest, Some((0, _)))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct RecordDestruct {
     pub var: Variable,
@@ -168,6 +214,13 @@ pub struct RecordDestruct {
     pub typ: DestructType,
 }
 
+#[derive(Clone, Debug)]
+pub struct TupleDestruct {
+    pub var: Variable,
+    pub destruct_index: usize,
+    pub typ: (Variable, Loc<Pattern>),
+}
+
 #[derive(Clone, Debug)]
 pub enum DestructType {
     Required,
@@ -608,6 +661,119 @@ pub fn canonicalize_pattern<'a>(
             })
         }
 
+        Tuple(patterns) => {
+            let ext_var = var_store.fresh();
+            let whole_var = var_store.fresh();
+            let mut destructs = Vec::with_capacity(patterns.len());
+
+            for (destruct_index, loc_pattern) in patterns.iter().enumerate() {
+                let can_guard = canonicalize_pattern(
+                    env,
+                    var_store,
+                    scope,
+                    output,
+                    pattern_type,
+                    &loc_pattern.value,
+                    loc_pattern.region,
+                    permit_shadows,
+                );
+
+                destructs.push(Loc {
+                    region: loc_pattern.region,
+                    value: TupleDestruct {
+                        var: var_store.fresh(),
+                        destruct_index,
+                        typ: (var_store.fresh(), can_guard),
+                    },
+                });
+            }
+
+            Pattern::TupleDestructure {
+                whole_var,
+                ext_var,
+                destructs,
+            }
+        }
+
+        List(patterns) => {
+            let mut can_pats = Vec::with_capacity(patterns.len());
+            let mut opt_rest: Option<(usize, Option<Loc<Symbol>>)> = None;
+            let mut opt_erroneous = None;
+
+            for loc_pattern in patterns.iter() {
+                match loc_pattern.value.extract_spaces().item {
+                    ListRest(opt_name) => {
+                        if opt_rest.is_some() {
+                            env.problem(Problem::MultipleListRestPattern {
+                                region: loc_pattern.region,
+                            });
+                            opt_erroneous = Some(Pattern::UnsupportedPattern(region));
+                            continue;
+                        }
+
+                        let opt_symbol = opt_name.map(|name| {
+                            let symbol = match scope.introduce_str(name, loc_pattern.region) {
+                                Ok(symbol) => symbol,
+                                Err((shadowed_symbol, shadow, new_symbol)) => {
+                                    if permit_shadows.0 {
+                                        shadowed_symbol.value
+                                    } else {
+                                        env.problem(Problem::RuntimeError(
+                                            RuntimeError::Shadowing {
+                                                original_region: shadowed_symbol.region,
+                                                shadow: shadow.clone(),
+                                                kind: ShadowKind::Variable,
+                                            },
+                                        ));
+                                        opt_erroneous = Some(Pattern::Shadowed(
+                                            shadowed_symbol.region,
+                                            shadow,
+                                            new_symbol,
+                                        ));
+                                        new_symbol
+                                    }
+                                }
+                            };
+                            output.references.insert_bound(symbol);
+
+                            Loc::at(loc_pattern.region, symbol)
+                        });
+
+                        opt_rest = Some((can_pats.len(), opt_symbol));
+                    }
+                    pattern => {
+                        let can_pattern = canonicalize_pattern(
+                            env,
+                            var_store,
+                            scope,
+                            output,
+                            pattern_type,
+                            &pattern,
+                            loc_pattern.region,
+                            permit_shadows,
+                        );
+
+                        can_pats.push(can_pattern);
+                    }
+                }
+            }
+
+            // If we encountered an erroneous pattern (e.g. one with shadowing),
+            // use the resulting RuntimeError. Otherwise, return a successful list pattern.
+            opt_erroneous.unwrap_or(Pattern::List {
+                list_var: var_store.fresh(),
+                elem_var: var_store.fresh(),
+                patterns: ListPatterns {
+                    patterns: can_pats,
+                    opt_rest,
+                },
+            })
+        }
+
+        ListRest(_) => {
+            unreachable!("should have been handled in List");
+        }
+
         RequiredField(_name, _loc_pattern) => {
             unreachable!("should have been handled in RecordDestructure");
         }
@@ -671,6 +837,7 @@ pub enum BindingsFromPattern<'a> {
 pub enum BindingsFromPatternWork<'a> {
     Pattern(&'a Loc<Pattern>),
     Destruct(&'a Loc<RecordDestruct>),
+    ListRest(&'a Loc<Symbol>),
 }
 
 impl<'a> BindingsFromPattern<'a> {
@@ -720,6 +887,24 @@ impl<'a> BindingsFromPattern<'a> {
                             let it = destructs.iter().rev().map(Destruct);
                             stack.extend(it);
                         }
+                        TupleDestructure { destructs, .. } => {
+                            let it = destructs.iter().rev().map(|destruct| {
+                                let (_, loc_pattern) = &destruct.value.typ;
+                                Pattern(loc_pattern)
+                            });
+                            stack.extend(it);
+                        }
+                        List {
+                            patterns: ListPatterns { patterns, opt_rest },
+                            ..
+                        } => {
+                            if let Some((_, Some(rest_sym))) = opt_rest {
+                                stack.push(BindingsFromPatternWork::ListRest(rest_sym));
+                            }
+
+                            let it = patterns.iter().rev().map(Pattern);
+                            stack.extend(it);
+                        }
                         NumLiteral(..)
                         | IntLiteral(..)
                         | FloatLiteral(..)
@@ -732,6 +917,9 @@ impl<'a> BindingsFromPattern<'a> {
                         | OpaqueNotInScope(..) => (),
                     }
                 }
+                BindingsFromPatternWork::ListRest(loc_symbol) => {
+                    return Some((loc_symbol.value, loc_symbol.region));
+                }
                 BindingsFromPatternWork::Destruct(loc_destruct) => {
                     match &loc_destruct.value.typ {
                         DestructType::Required | DestructType::Optional(_, _) => {
@@ -784,7 +972,7 @@ fn flatten_str_literal(literal: &StrLiteral<'_>) -> Pattern {
     match literal {
         PlainLine(str_slice) => Pattern::StrLiteral((*str_slice).into()),
         Line(segments) => flatten_str_lines(&[segments]),
-        Block(lines) => flatten_str_lines(lines),
+        Block(lines) => flatten_str_lines(&[&block_string_segments(lines)]),
     }
 }
 
@@ -1,7 +1,7 @@
 //! Traversals over the can ast.
 
 use roc_module::{ident::Lowercase, symbol::Symbol};
-use roc_region::all::{Loc, Region};
+use roc_region::all::{Loc, Position, Region};
 use roc_types::{subs::Variable, types::MemberImpl};
 
 use crate::{
@@ -226,6 +226,12 @@ pub fn walk_expr<V: Visitor>(visitor: &mut V, expr: &Expr, var: Variable) {
             walk_record_fields(visitor, fields.iter());
         }
         Expr::EmptyRecord => { /* terminal */ }
+        Expr::Tuple {
+            tuple_var: _,
+            elems,
+        } => elems
+            .iter()
+            .for_each(|(var, elem)| visitor.visit_expr(&elem.value, elem.region, *var)),
         Expr::Access {
             field_var,
             loc_expr,
@@ -233,6 +239,13 @@ pub fn walk_expr<V: Visitor>(visitor: &mut V, expr: &Expr, var: Variable) {
             record_var: _,
             ext_var: _,
         } => visitor.visit_expr(&loc_expr.value, loc_expr.region, *field_var),
+        Expr::TupleAccess {
+            tuple_var,
+            loc_expr,
+            index: _,
+            elem_var: _,
+            ext_var: _,
+        } => visitor.visit_expr(&loc_expr.value, loc_expr.region, *tuple_var),
         Expr::Accessor(AccessorData { .. }) => { /* terminal */ }
         Expr::OpaqueWrapFunction(OpaqueWrapFunctionData { .. }) => { /* terminal */ }
         Expr::Update {
@@ -289,6 +302,16 @@ pub fn walk_expr<V: Visitor>(visitor: &mut V, expr: &Expr, var: Variable) {
                 Variable::NULL,
             );
         }
+        Expr::Dbg {
+            loc_expr,
+            variable,
+            symbol: _,
+        } => {
+            visitor.visit_expr(&loc_expr.value, loc_expr.region, *variable);
+        }
+        Expr::Crash { msg, ret_var: _ } => {
+            visitor.visit_expr(&msg.value, msg.region, Variable::STR);
+        }
         Expr::TypedHole(_) => { /* terminal */ }
         Expr::RuntimeError(..) => { /* terminal */ }
     }
@@ -472,6 +495,16 @@ pub fn walk_pattern<V: Visitor>(visitor: &mut V, pattern: &Pattern) {
         RecordDestructure { destructs, .. } => destructs
             .iter()
             .for_each(|d| visitor.visit_record_destruct(&d.value, d.region)),
+        TupleDestructure { destructs, .. } => destructs.iter().for_each(|d| {
+            let (v, lp) = &d.value.typ;
+            visitor.visit_pattern(&lp.value, lp.region, Some(*v))
+        }),
+        List {
+            patterns, elem_var, ..
+        } => patterns
+            .patterns
+            .iter()
+            .for_each(|p| visitor.visit_pattern(&p.value, p.region, Some(*elem_var))),
         NumLiteral(..) => { /* terminal */ }
         IntLiteral(..) => { /* terminal */ }
         FloatLiteral(..) => { /* terminal */ }
@@ -533,6 +566,199 @@ pub fn find_type_at(region: Region, decls: &Declarations) -> Option<Variable> {
     visitor.typ
 }
 
+/// Finds the innermost expression or pattern whose region contains `position`, returning its
+/// region and type. Unlike [find_type_at], the position need not point at the exact start of
+/// the node, which makes this suitable for editor queries like hovering.
+pub fn find_closest_type_at(
+    position: Position,
+    decls: &Declarations,
+) -> Option<(Region, Variable)> {
+    let mut visitor = Finder {
+        position,
+        found: None,
+    };
+    visitor.visit_decls(decls);
+    return visitor.found;
+
+    struct Finder {
+        position: Position,
+        found: Option<(Region, Variable)>,
+    }
+
+    impl Visitor for Finder {
+        fn should_visit(&mut self, region: Region) -> bool {
+            region_contains_position(region, self.position)
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            if self.should_visit(region) {
+                if var != Variable::NULL {
+                    self.found = Some((region, var));
+                }
+
+                walk_expr(self, expr, var);
+            }
+        }
+
+        fn visit_pattern(&mut self, pattern: &Pattern, region: Region, opt_var: Option<Variable>) {
+            if self.should_visit(region) {
+                if let Some(var) = opt_var {
+                    self.found = Some((region, var));
+                }
+
+                walk_pattern(self, pattern);
+            }
+        }
+    }
+}
+
+/// Finds the symbol that is referenced or introduced by the innermost lookup or pattern
+/// containing `position`.
+pub fn find_symbol_at(position: Position, decls: &Declarations) -> Option<Loc<Symbol>> {
+    let mut visitor = Finder {
+        position,
+        found: None,
+    };
+    visitor.visit_decls(decls);
+    return visitor.found;
+
+    struct Finder {
+        position: Position,
+        found: Option<Loc<Symbol>>,
+    }
+
+    impl Visitor for Finder {
+        fn should_visit(&mut self, region: Region) -> bool {
+            region_contains_position(region, self.position)
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            if self.should_visit(region) {
+                match expr {
+                    Expr::Var(symbol) | Expr::AbilityMember(symbol, _, _) => {
+                        self.found = Some(Loc::at(region, *symbol));
+                    }
+                    _ => walk_expr(self, expr, var),
+                }
+            }
+        }
+
+        fn visit_pattern(&mut self, pattern: &Pattern, region: Region, _opt_var: Option<Variable>) {
+            if self.should_visit(region) {
+                match pattern {
+                    Pattern::Identifier(symbol)
+                    | Pattern::Shadowed(_, _, symbol)
+                    | Pattern::AbilityMemberSpecialization { ident: symbol, .. } => {
+                        self.found = Some(Loc::at(region, *symbol));
+                    }
+                    _ => walk_pattern(self, pattern),
+                }
+            }
+        }
+
+        fn visit_record_destruct(&mut self, destruct: &RecordDestruct, region: Region) {
+            if self.should_visit(region) {
+                // with a pattern guard, the field itself does not introduce a symbol
+                if !matches!(destruct.typ, DestructType::Guard(..)) {
+                    self.found = Some(Loc::at(region, destruct.symbol));
+                }
+
+                walk_record_destruct(self, destruct);
+            }
+        }
+    }
+}
+
+/// Returns the region of the pattern that introduces `symbol`, if it is defined in `decls`.
+pub fn find_declaration(symbol: Symbol, decls: &Declarations) -> Option<Region> {
+    let mut visitor = Finder {
+        symbol,
+        found: None,
+    };
+    visitor.visit_decls(decls);
+    return visitor.found;
+
+    struct Finder {
+        symbol: Symbol,
+        found: Option<Region>,
+    }
+
+    impl Visitor for Finder {
+        fn should_visit(&mut self, _region: Region) -> bool {
+            self.found.is_none()
+        }
+
+        fn visit_pattern(&mut self, pattern: &Pattern, region: Region, _opt_var: Option<Variable>) {
+            if self.should_visit(region) {
+                match pattern {
+                    Pattern::Identifier(symbol)
+                    | Pattern::Shadowed(_, _, symbol)
+                    | Pattern::AbilityMemberSpecialization { ident: symbol, .. }
+                        if *symbol == self.symbol =>
+                    {
+                        self.found = Some(region);
+                    }
+                    _ => walk_pattern(self, pattern),
+                }
+            }
+        }
+
+        fn visit_record_destruct(&mut self, destruct: &RecordDestruct, region: Region) {
+            if self.should_visit(region) {
+                if destruct.symbol == self.symbol {
+                    self.found = Some(region);
+                } else {
+                    walk_record_destruct(self, destruct);
+                }
+            }
+        }
+    }
+}
+
+/// Returns every symbol that `decls` define by name, together with the region that introduces
+/// it: the pattern binding the symbol, or the closure expression for (anonymous) closures.
+/// Doc tests use these to find the definition that a doc comment's example belongs to.
+pub fn find_definitions(decls: &Declarations) -> Vec<Loc<Symbol>> {
+    let mut visitor = Finder { found: Vec::new() };
+    visitor.visit_decls(decls);
+    return visitor.found;
+
+    struct Finder {
+        found: Vec<Loc<Symbol>>,
+    }
+
+    impl Visitor for Finder {
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            if let Expr::Closure(closure_data) = expr {
+                self.found.push(Loc::at(region, closure_data.name));
+            }
+
+            walk_expr(self, expr, var);
+        }
+
+        fn visit_pattern(&mut self, pattern: &Pattern, region: Region, _opt_var: Option<Variable>) {
+            match pattern {
+                Pattern::Identifier(symbol)
+                | Pattern::Shadowed(_, _, symbol)
+                | Pattern::AbilityMemberSpecialization { ident: symbol, .. } => {
+                    self.found.push(Loc::at(region, *symbol));
+                }
+                _ => walk_pattern(self, pattern),
+            }
+        }
+
+        fn visit_record_destruct(&mut self, destruct: &RecordDestruct, region: Region) {
+            self.found.push(Loc::at(region, destruct.symbol));
+
+            walk_record_destruct(self, destruct);
+        }
+    }
+}
+
+fn region_contains_position(region: Region, position: Position) -> bool {
+    region.start() <= position && position <= region.end()
+}
+
 /// Given an ability Foo has foo : ..., returns (T, foo1) if the symbol at the given region is a
 /// symbol foo1 that specializes foo for T. Otherwise if the symbol is foo but the specialization
 /// is unknown, (Foo, foo) is returned. Otherwise [None] is returned.
@@ -582,17 +808,15 @@ pub fn find_ability_member_and_owning_type_at(
             if region == self.region {
                 if let &Expr::AbilityMember(member_symbol, specialization_id, _var) = expr {
                     debug_assert!(self.found.is_none());
+                    // The specialization may be unknown, or resolved to something that is not
+                    // a declared implementation (e.g. after a type error).
                     self.found = match specialization_id
                         .and_then(|id| self.abilities_store.get_resolved(id))
-                    {
-                        Some(spec_symbol) => {
-                            let spec_type = find_specialization_type_of_symbol(
-                                spec_symbol,
-                                self.abilities_store,
-                            )
-                            .unwrap();
-                            Some((spec_type, spec_symbol))
-                        }
+                        .and_then(|spec_symbol| {
+                            find_specialization_type_of_symbol(spec_symbol, self.abilities_store)
+                                .map(|spec_type| (spec_type, spec_symbol))
+                        }) {
+                        Some(found) => Some(found),
                         None => {
                             let parent_ability = self
                                 .abilities_store
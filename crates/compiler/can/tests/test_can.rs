@@ -16,6 +16,7 @@ mod test_can {
     use bumpalo::Bump;
     use roc_can::expr::Expr::{self, *};
     use roc_can::expr::{ClosureData, IntValue, Recursive};
+    use roc_can::mock_effects::MockEffects;
     use roc_problem::can::{CycleEntry, FloatErrorKind, IntErrorKind, Problem, RuntimeError};
     use roc_region::all::{Position, Region};
     use std::{f64, i64};
@@ -1597,6 +1598,95 @@ mod test_can {
         );
     }
 
+    // MOCK EFFECTS
+
+    #[test]
+    fn mock_effects_sections() {
+        let mock_effects = MockEffects::parse(indoc!(
+            r#"
+            # any expect
+            stdinLine -> "Alice"
+            stdoutLine
+
+            [Main.greet]
+            stdinLine -> Str.concat "B" "ob"
+            "#
+        ))
+        .unwrap();
+
+        let script = |name| {
+            mock_effects
+                .script_for(name)
+                .into_iter()
+                .map(|(key, call)| (key, call.effect.as_str(), call.response.as_str()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            script("Main.greet"),
+            vec![(2, "stdinLine", r#"Str.concat "B" "ob""#)]
+        );
+        assert_eq!(
+            script("Main"),
+            vec![(0, "stdinLine", r#""Alice""#), (1, "stdoutLine", "{}")]
+        );
+    }
+
+    #[test]
+    fn mock_effects_invalid_response() {
+        let error = MockEffects::parse(indoc!(
+            r#"
+            stdoutLine
+            stdinLine -> "Alice
+            "#
+        ))
+        .unwrap_err();
+
+        assert_eq!(error.line, 2);
+    }
+
+    #[test]
+    fn mock_effects_arguments() {
+        let mock_effects = MockEffects::parse(indoc!(
+            r#"
+            stdoutLine "Hello, Alice!"
+            stdoutLine (Str.concat "B" "ob") -> {}
+            send 42 { retries: 3 }
+            stdinLine
+            "#
+        ))
+        .unwrap();
+
+        let arguments = mock_effects
+            .script_for("Main")
+            .into_iter()
+            .map(|(_, call)| (call.effect.as_str(), call.arguments.clone()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            arguments,
+            vec![
+                ("stdoutLine", vec![r#""Hello, Alice!""#.to_string()]),
+                ("stdoutLine", vec![r#"Str.concat "B" "ob""#.to_string()]),
+                ("send", vec!["42".to_string(), "{ retries: 3 }".to_string()]),
+                ("stdinLine", vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn mock_effects_invalid_call() {
+        for call in [
+            "Stdout.line",
+            "stdoutLine \"Hello",
+            "\"Hello\" |> stdoutLine",
+        ] {
+            let error = MockEffects::parse(call).unwrap_err();
+
+            assert_eq!(error.line, 1, "{}", call);
+        }
+    }
+
     //     #[test]
     //     fn string_with_too_large_unicode_escape() {
     //         // Should be too big - max size should be 10FFFF.
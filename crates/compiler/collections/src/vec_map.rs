@@ -1,4 +1,4 @@
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct VecMap<K, V> {
     keys: Vec<K>,
     values: Vec<V>,
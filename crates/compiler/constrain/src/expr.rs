@@ -20,7 +20,7 @@ use roc_can::traverse::symbols_introduced_from_pattern;
 use roc_collections::all::{HumanIndex, MutMap, SendMap};
 use roc_collections::soa::Index;
 use roc_collections::VecMap;
-use roc_module::ident::Lowercase;
+use roc_module::ident::{IndexOrField, Lowercase};
 use roc_module::symbol::{ModuleId, Symbol};
 use roc_region::all::{Loc, Region};
 use roc_types::subs::{IllegalCycleMark, Variable};
@@ -227,6 +227,39 @@ pub fn constrain_expr(
                 constraints.exists(field_vars, and_constraint)
             }
         }
+        Expr::Tuple { tuple_var, elems } => {
+            let mut elem_types = VecMap::with_capacity(elems.len());
+            let mut elem_vars = Vec::with_capacity(elems.len());
+
+            // Constraints need capacity for each elem
+            // + 1 for the tuple itself + 1 for tuple var
+            let mut tuple_constraints = Vec::with_capacity(2 + elems.len());
+
+            for (index, (elem_var, loc_elem)) in elems.iter().enumerate() {
+                let (elem_type, elem_con) = constrain_field(constraints, env, *elem_var, loc_elem);
+
+                elem_vars.push(*elem_var);
+                elem_types.insert(index, elem_type);
+
+                tuple_constraints.push(elem_con);
+            }
+
+            let tuple_type = Type::Tuple(elem_types, TypeExtension::Closed);
+
+            let tuple_con = constraints.equal_types_with_storage(
+                tuple_type,
+                expected,
+                Category::Tuple,
+                region,
+                *tuple_var,
+            );
+
+            tuple_constraints.push(tuple_con);
+            elem_vars.push(*tuple_var);
+
+            let and_constraint = constraints.and_constraint(tuple_constraints);
+            constraints.exists(elem_vars, and_constraint)
+        }
         Update {
             record_var,
             ext_var,
@@ -384,6 +417,7 @@ pub fn constrain_expr(
                 let reason = Reason::FnArg {
                     name: opt_symbol,
                     arg_index: HumanIndex::zero_based(index),
+                    called_via: *called_via,
                 };
                 let expected_arg = ForReason(reason, arg_type.clone(), region);
                 let arg_con = constrain_expr(
@@ -567,6 +601,47 @@ pub fn constrain_expr(
             constraints.exists_many(vars, all_constraints)
         }
 
+        Dbg {
+            loc_expr,
+            variable,
+            symbol: _,
+        } => {
+            // `dbg x` has the same type as `x`
+            let expr_con = constrain_expr(
+                constraints,
+                env,
+                loc_expr.region,
+                &loc_expr.value,
+                expected.clone(),
+            );
+
+            // store the type, so the printed value can be looked up later
+            let store_con = constraints.equal_types_var(
+                *variable,
+                expected,
+                Category::Storage(std::file!(), std::line!()),
+                region,
+            );
+
+            constraints.exists_many([*variable], [expr_con, store_con])
+        }
+
+        Crash { msg, ret_var } => {
+            // the message must be a string
+            let expected_msg = Expected::ForReason(Reason::CrashArg, str_type(), msg.region);
+            let msg_con = constrain_expr(constraints, env, msg.region, &msg.value, expected_msg);
+
+            // a crash never returns, so it can stand in for a value of any type
+            let ret_con = constraints.equal_types_var(
+                *ret_var,
+                expected,
+                Category::Storage(std::file!(), std::line!()),
+                region,
+            );
+
+            constraints.exists_many([*ret_var], [msg_con, ret_con])
+        }
+
         If {
             cond_var,
             branch_var,
@@ -937,6 +1012,40 @@ pub fn constrain_expr(
                 [constraint, eq, record_con],
             )
         }
+        TupleAccess {
+            tuple_var,
+            ext_var,
+            elem_var,
+            loc_expr,
+            index,
+        } => {
+            let ext_var = *ext_var;
+            let ext_type = Type::Variable(ext_var);
+            let elem_var = *elem_var;
+            let elem_type = Type::Variable(elem_var);
+
+            let mut tuple_elem_types = VecMap::default();
+
+            tuple_elem_types.insert(*index, elem_type);
+
+            let tuple_type = Type::Tuple(tuple_elem_types, TypeExtension::from_type(ext_type));
+            let tuple_expected = Expected::NoExpectation(tuple_type);
+
+            let category = Category::TupleAccess(*index);
+
+            let tuple_con = constraints.equal_types_var(
+                *tuple_var,
+                tuple_expected.clone(),
+                category.clone(),
+                region,
+            );
+
+            let constraint =
+                constrain_expr(constraints, env, region, &loc_expr.value, tuple_expected);
+
+            let eq = constraints.equal_types_var(elem_var, expected, category, region);
+            constraints.exists_many([*tuple_var, elem_var, ext_var], [constraint, eq, tuple_con])
+        }
         Accessor(AccessorData {
             name: closure_name,
             function_var,
@@ -951,10 +1060,19 @@ pub fn constrain_expr(
             let field_var = *field_var;
             let field_type = Variable(field_var);
 
-            let mut field_types = SendMap::default();
-            let label = field.clone();
-            field_types.insert(label, RecordField::Demanded(field_type.clone()));
-            let record_type = Type::Record(field_types, TypeExtension::from_type(ext_type));
+            let record_type = match field {
+                IndexOrField::Field(field) => {
+                    let mut field_types = SendMap::default();
+                    let label = field.clone();
+                    field_types.insert(label, RecordField::Demanded(field_type.clone()));
+                    Type::Record(field_types, TypeExtension::from_type(ext_type))
+                }
+                IndexOrField::Index(index) => {
+                    let mut elem_types = VecMap::default();
+                    elem_types.insert(*index, field_type.clone());
+                    Type::Tuple(elem_types, TypeExtension::from_type(ext_type))
+                }
+            };
 
             let category = Category::Accessor(field.clone());
 
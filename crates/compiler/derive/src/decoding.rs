@@ -1,19 +1,20 @@
 //! Derivers for the `Decoding` ability.
 
 use roc_can::expr::{
-    AnnotatedMark, ClosureData, Expr, Field, Recursive, WhenBranch, WhenBranchPattern,
+    AnnotatedMark, ClosureData, Expr, Field, IntValue, Recursive, WhenBranch, WhenBranchPattern,
 };
 use roc_can::pattern::Pattern;
 use roc_collections::SendMap;
 use roc_derive_key::decoding::FlatDecodableKey;
 use roc_error_macros::internal_error;
 use roc_module::called_via::CalledVia;
-use roc_module::ident::Lowercase;
+use roc_module::ident::{Lowercase, TagName};
 use roc_module::symbol::Symbol;
 use roc_region::all::{Loc, Region};
+use roc_types::num::{IntBound, IntLitWidth};
 use roc_types::subs::{
     Content, ExhaustiveMark, FlatType, GetSubsSlice, LambdaSet, OptVariable, RecordFields,
-    RedundantMark, SubsSlice, UnionLambdas, UnionTags, Variable,
+    RedundantMark, SubsSlice, TupleElems, UnionLambdas, UnionTags, Variable,
 };
 use roc_types::types::{AliasKind, RecordField};
 
@@ -28,6 +29,8 @@ pub(crate) fn derive_decoder(
     let (body, body_type) = match key {
         FlatDecodableKey::List() => decoder_list(env, def_symbol),
         FlatDecodableKey::Record(fields) => decoder_record(env, def_symbol, fields),
+        FlatDecodableKey::Tuple(arity) => decoder_tuple(env, def_symbol, arity),
+        FlatDecodableKey::TagUnion(tags) => decoder_tag_union(env, def_symbol, tags),
     };
 
     let specialization_lambda_sets =
@@ -103,59 +106,68 @@ fn decoder_record(env: &mut Env, _def_symbol: Symbol, fields: Vec<Lowercase>) ->
         decode_err_var,
     );
 
+    decoder_with_state(
+        env,
+        Symbol::DECODE_RECORD,
+        (initial_state, initial_state_var),
+        (step_field, step_var),
+        (finalizer, finalizer_var),
+    )
+}
+
+// Calls a decoder formatting member that is driven by a state, like `Decode.record`:
+//
+//     Decode.custom \bytes, fmt -> Decode.decodeWith bytes (Decode.record initialState stepField finalizer) fmt
+fn decoder_with_state(
+    env: &mut Env,
+    member: Symbol,
+    (initial_state, initial_state_var): (Expr, Variable),
+    (step, step_var): (Expr, Variable),
+    (finalizer, finalizer_var): (Expr, Variable),
+) -> (Expr, Variable) {
     // Build up the type of `Decode.record` we expect
-    let record_decoder_var = env.subs.fresh_unnamed_flex_var();
-    let decode_record_lambda_set = env.subs.fresh_unnamed_flex_var();
-    let decode_record_var = env.import_builtin_symbol_var(Symbol::DECODE_RECORD);
-    let this_decode_record_var = {
+    let member_decoder_var = env.subs.fresh_unnamed_flex_var();
+    let member_lambda_set = env.subs.fresh_unnamed_flex_var();
+    let member_var = env.import_builtin_symbol_var(member);
+    let this_member_var = {
         let flat_type = FlatType::Func(
             SubsSlice::insert_into_subs(env.subs, [initial_state_var, step_var, finalizer_var]),
-            decode_record_lambda_set,
-            record_decoder_var,
+            member_lambda_set,
+            member_decoder_var,
         );
 
         synth_var(env.subs, Content::Structure(flat_type))
     };
 
-    env.unify(decode_record_var, this_decode_record_var);
+    env.unify(member_var, this_member_var);
 
     // Decode.record initialState stepField finalizer
-    let call_decode_record = Expr::Call(
+    let call_member = Expr::Call(
         Box::new((
-            this_decode_record_var,
-            Loc::at_zero(Expr::AbilityMember(
-                Symbol::DECODE_RECORD,
-                None,
-                this_decode_record_var,
-            )),
-            decode_record_lambda_set,
-            record_decoder_var,
+            this_member_var,
+            Loc::at_zero(Expr::AbilityMember(member, None, this_member_var)),
+            member_lambda_set,
+            member_decoder_var,
         )),
         vec![
             (initial_state_var, Loc::at_zero(initial_state)),
-            (step_var, Loc::at_zero(step_field)),
+            (step_var, Loc::at_zero(step)),
             (finalizer_var, Loc::at_zero(finalizer)),
         ],
         CalledVia::Space,
     );
 
-    let (call_decode_custom, decode_custom_ret_var) = {
-        let bytes_sym = env.new_symbol("bytes");
-        let fmt_sym = env.new_symbol("fmt");
-        let fmt_var = env.subs.fresh_unnamed_flex_var();
-
-        let (decode_custom, decode_custom_var) = wrap_in_decode_custom_decode_with(
-            env,
-            bytes_sym,
-            (fmt_sym, fmt_var),
-            vec![],
-            (call_decode_record, record_decoder_var),
-        );
-
-        (decode_custom, decode_custom_var)
-    };
+    let bytes_sym = env.new_symbol("bytes");
+    let fmt_sym = env.new_symbol("fmt");
+    let fmt_var = env.subs.fresh_unnamed_flex_var();
 
-    (call_decode_custom, decode_custom_ret_var)
+    wrap_in_decode_custom_decode_with(
+        env,
+        bytes_sym,
+        (fmt_sym, fmt_var),
+        vec![],
+        (call_member, member_decoder_var),
+    )
 }
 
 // Example:
@@ -199,436 +211,14 @@ fn decoder_record_step_field(
 
     // +1 because of the default branch.
     let mut branches = Vec::with_capacity(fields.len() + 1);
-    let keep_payload_var = env.subs.fresh_unnamed_flex_var();
-    let keep_or_skip_var = {
-        let keep_payload_subs_slice = SubsSlice::insert_into_subs(env.subs, [keep_payload_var]);
-        let flat_type = FlatType::TagUnion(
-            UnionTags::insert_slices_into_subs(
-                env.subs,
-                [
-                    ("Keep".into(), keep_payload_subs_slice),
-                    ("Skip".into(), Default::default()),
-                ],
-            ),
-            Variable::EMPTY_TAG_UNION,
-        );
-
-        synth_var(env.subs, Content::Structure(flat_type))
-    };
+    let step = StepContext::new(env, state_arg_symbol, state_record_var, decode_err_var);
 
     for ((field_name, &field_var), &result_field_var) in fields
         .into_iter()
         .zip(field_vars.iter())
         .zip(result_field_vars.iter())
     {
-        // Example:
-        // "first" ->
-        //     Keep (Decode.custom \bytes, fmt ->
-        //         # Uses a single-branch `when` because `let` is more expensive to monomorphize
-        //         # due to checks for polymorphic expressions, and `rec` would be polymorphic.
-        //         when Decode.decodeWith bytes Decode.decoder fmt is
-        //             rec ->
-        //                 {
-        //                     rest: rec.rest,
-        //                     result: when rec.result is
-        //                         Ok val -> Ok {state & first: Ok val},
-        //                         Err err -> Err err
-        //                 }
-        //     )
-
-        let this_custom_callback_var;
-        let custom_callback_ret_var;
-        let custom_callback = {
-            // \bytes, fmt ->
-            //     when Decode.decodeWith bytes Decode.decoder fmt is
-            //         rec ->
-            //             {
-            //                 rest: rec.rest,
-            //                 result: when rec.result is
-            //                     Ok val -> Ok {state & first: Ok val},
-            //                     Err err -> Err err
-            //             }
-            let bytes_arg_symbol = env.new_symbol("bytes");
-            let fmt_arg_symbol = env.new_symbol("fmt");
-            let bytes_arg_var = env.subs.fresh_unnamed_flex_var();
-            let fmt_arg_var = env.subs.fresh_unnamed_flex_var();
-
-            // rec.result : [Ok field_var, Err DecodeError]
-            let rec_dot_result = {
-                let tag_union = FlatType::TagUnion(
-                    UnionTags::for_result(env.subs, field_var, decode_err_var),
-                    Variable::EMPTY_TAG_UNION,
-                );
-
-                synth_var(env.subs, Content::Structure(tag_union))
-            };
-
-            // rec : { rest: List U8, result: (typeof rec.result) }
-            let rec_var = {
-                let fields = RecordFields::insert_into_subs(
-                    env.subs,
-                    [
-                        ("rest".into(), RecordField::Required(Variable::LIST_U8)),
-                        ("result".into(), RecordField::Required(rec_dot_result)),
-                    ],
-                );
-                let record = FlatType::Record(fields, Variable::EMPTY_RECORD);
-
-                synth_var(env.subs, Content::Structure(record))
-            };
-
-            // `Decode.decoder` for the field's value
-            let decoder_var = env.import_builtin_symbol_var(Symbol::DECODE_DECODER);
-            let decode_with_var = env.import_builtin_symbol_var(Symbol::DECODE_DECODE_WITH);
-            let lambda_set_var = env.subs.fresh_unnamed_flex_var();
-            let this_decode_with_var = {
-                let subs_slice = SubsSlice::insert_into_subs(
-                    env.subs,
-                    [bytes_arg_var, decoder_var, fmt_arg_var],
-                );
-                let this_decode_with_var = synth_var(
-                    env.subs,
-                    Content::Structure(FlatType::Func(subs_slice, lambda_set_var, rec_var)),
-                );
-
-                env.unify(decode_with_var, this_decode_with_var);
-
-                this_decode_with_var
-            };
-
-            // The result of decoding this field's value - either the updated state, or a decoding error.
-            let when_expr_var = {
-                let flat_type = FlatType::TagUnion(
-                    UnionTags::for_result(env.subs, state_record_var, decode_err_var),
-                    Variable::EMPTY_TAG_UNION,
-                );
-
-                synth_var(env.subs, Content::Structure(flat_type))
-            };
-
-            // What our decoder passed to `Decode.custom` returns - the result of decoding the
-            // field's value, and the remaining bytes.
-            custom_callback_ret_var = {
-                let rest_field = RecordField::Required(Variable::LIST_U8);
-                let result_field = RecordField::Required(when_expr_var);
-                let flat_type = FlatType::Record(
-                    RecordFields::insert_into_subs(
-                        env.subs,
-                        [("rest".into(), rest_field), ("result".into(), result_field)],
-                    ),
-                    Variable::EMPTY_RECORD,
-                );
-
-                synth_var(env.subs, Content::Structure(flat_type))
-            };
-
-            let custom_callback_body = {
-                let rec_symbol = env.new_symbol("rec");
-
-                // # Uses a single-branch `when` because `let` is more expensive to monomorphize
-                // # due to checks for polymorphic expressions, and `rec` would be polymorphic.
-                // when Decode.decodeWith bytes Decode.decoder fmt is
-                //     rec ->
-                //         {
-                //             rest: rec.rest,
-                //             result: when rec.result is
-                //                 Ok val -> Ok {state & first: Ok val},
-                //                 Err err -> Err err
-                //         }
-                let branch_body = {
-                    let result_val = {
-                        // result: when rec.result is
-                        //     Ok val -> Ok {state & first: Ok val},
-                        //     Err err -> Err err
-                        let ok_val_symbol = env.new_symbol("val");
-                        let err_val_symbol = env.new_symbol("err");
-                        let ok_branch_expr = {
-                            // Ok {state & first: Ok val},
-                            let mut updates = SendMap::default();
-
-                            updates.insert(
-                                field_name.clone(),
-                                Field {
-                                    var: result_field_var,
-                                    region: Region::zero(),
-                                    loc_expr: Box::new(Loc::at_zero(Expr::Tag {
-                                        tag_union_var: result_field_var,
-                                        ext_var: env.new_ext_var(ExtensionKind::TagUnion),
-                                        name: "Ok".into(),
-                                        arguments: vec![(
-                                            field_var,
-                                            Loc::at_zero(Expr::Var(ok_val_symbol)),
-                                        )],
-                                    })),
-                                },
-                            );
-
-                            let updated_record = Expr::Update {
-                                record_var: state_record_var,
-                                ext_var: env.new_ext_var(ExtensionKind::Record),
-                                symbol: state_arg_symbol,
-                                updates,
-                            };
-
-                            Expr::Tag {
-                                tag_union_var: when_expr_var,
-                                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
-                                name: "Ok".into(),
-                                arguments: vec![(state_record_var, Loc::at_zero(updated_record))],
-                            }
-                        };
-
-                        let branches = vec![
-                            // Ok val -> Ok {state & first: Ok val},
-                            WhenBranch {
-                                patterns: vec![WhenBranchPattern {
-                                    pattern: Loc::at_zero(Pattern::AppliedTag {
-                                        whole_var: rec_dot_result,
-                                        ext_var: Variable::EMPTY_TAG_UNION,
-                                        tag_name: "Ok".into(),
-                                        arguments: vec![(
-                                            field_var,
-                                            Loc::at_zero(Pattern::Identifier(ok_val_symbol)),
-                                        )],
-                                    }),
-                                    degenerate: false,
-                                }],
-                                value: Loc::at_zero(ok_branch_expr),
-                                guard: None,
-                                redundant: RedundantMark::known_non_redundant(),
-                            },
-                            // Err err -> Err err
-                            WhenBranch {
-                                patterns: vec![WhenBranchPattern {
-                                    pattern: Loc::at_zero(Pattern::AppliedTag {
-                                        whole_var: rec_dot_result,
-                                        ext_var: Variable::EMPTY_TAG_UNION,
-                                        tag_name: "Err".into(),
-                                        arguments: vec![(
-                                            decode_err_var,
-                                            Loc::at_zero(Pattern::Identifier(err_val_symbol)),
-                                        )],
-                                    }),
-                                    degenerate: false,
-                                }],
-                                value: Loc::at_zero(Expr::Tag {
-                                    tag_union_var: when_expr_var,
-                                    ext_var: env.new_ext_var(ExtensionKind::TagUnion),
-                                    name: "Err".into(),
-                                    arguments: vec![(
-                                        decode_err_var,
-                                        Loc::at_zero(Expr::Var(err_val_symbol)),
-                                    )],
-                                }),
-                                guard: None,
-                                redundant: RedundantMark::known_non_redundant(),
-                            },
-                        ];
-
-                        // when rec.result is
-                        //     Ok val -> Ok {state & first: Ok val},
-                        //     Err err -> Err err
-                        Expr::When {
-                            loc_cond: Box::new(Loc::at_zero(Expr::Access {
-                                record_var: rec_var,
-                                ext_var: env.new_ext_var(ExtensionKind::Record),
-                                field_var: rec_dot_result,
-                                loc_expr: Box::new(Loc::at_zero(Expr::Var(rec_symbol))),
-                                field: "result".into(),
-                            })),
-                            cond_var: rec_dot_result,
-                            expr_var: when_expr_var,
-                            region: Region::zero(),
-                            branches,
-                            branches_cond_var: rec_dot_result,
-                            exhaustive: ExhaustiveMark::known_exhaustive(),
-                        }
-                    };
-
-                    // {
-                    //     rest: rec.rest,
-                    //     result: when rec.result is
-                    //         Ok val -> Ok {state & first: Ok val},
-                    //         Err err -> Err err
-                    // }
-                    let mut fields_map = SendMap::default();
-
-                    fields_map.insert(
-                        "rest".into(),
-                        Field {
-                            var: Variable::LIST_U8,
-                            region: Region::zero(),
-                            loc_expr: Box::new(Loc::at_zero(Expr::Access {
-                                record_var: rec_var,
-                                ext_var: env.new_ext_var(ExtensionKind::Record),
-                                field_var: Variable::LIST_U8,
-                                loc_expr: Box::new(Loc::at_zero(Expr::Var(rec_symbol))),
-                                field: "rest".into(),
-                            })),
-                        },
-                    );
-
-                    // result: when rec.result is
-                    //     Ok val -> Ok {state & first: Ok val},
-                    //     Err err -> Err err
-                    fields_map.insert(
-                        "result".into(),
-                        Field {
-                            var: when_expr_var,
-                            region: Region::zero(),
-                            loc_expr: Box::new(Loc::at_zero(result_val)),
-                        },
-                    );
-
-                    Expr::Record {
-                        record_var: custom_callback_ret_var,
-                        fields: fields_map,
-                    }
-                };
-
-                let branch = WhenBranch {
-                    patterns: vec![WhenBranchPattern {
-                        pattern: Loc::at_zero(Pattern::Identifier(rec_symbol)),
-                        degenerate: false,
-                    }],
-                    value: Loc::at_zero(branch_body),
-                    guard: None,
-                    redundant: RedundantMark::known_non_redundant(),
-                };
-
-                let condition_expr = Expr::Call(
-                    Box::new((
-                        this_decode_with_var,
-                        Loc::at_zero(Expr::Var(Symbol::DECODE_DECODE_WITH)),
-                        lambda_set_var,
-                        rec_var,
-                    )),
-                    vec![
-                        (Variable::LIST_U8, Loc::at_zero(Expr::Var(bytes_arg_symbol))),
-                        (
-                            decoder_var,
-                            Loc::at_zero(Expr::AbilityMember(
-                                Symbol::DECODE_DECODER,
-                                None,
-                                decoder_var,
-                            )),
-                        ),
-                        (fmt_arg_var, Loc::at_zero(Expr::Var(fmt_arg_symbol))),
-                    ],
-                    CalledVia::Space,
-                );
-
-                // when Decode.decodeWith bytes Decode.decoder fmt is
-                Expr::When {
-                    loc_cond: Box::new(Loc::at_zero(condition_expr)),
-                    cond_var: rec_var,
-                    expr_var: custom_callback_ret_var,
-                    region: Region::zero(),
-                    branches: vec![branch],
-                    branches_cond_var: rec_var,
-                    exhaustive: ExhaustiveMark::known_exhaustive(),
-                }
-            };
-
-            let custom_closure_symbol = env.new_symbol("customCallback");
-            this_custom_callback_var = env.subs.fresh_unnamed_flex_var();
-            let custom_callback_lambda_set_var = {
-                let content = Content::LambdaSet(LambdaSet {
-                    solved: UnionLambdas::insert_into_subs(
-                        env.subs,
-                        [(custom_closure_symbol, [state_record_var])],
-                    ),
-                    recursion_var: OptVariable::NONE,
-                    unspecialized: Default::default(),
-                    ambient_function: this_custom_callback_var,
-                });
-                let custom_callback_lambda_set_var = synth_var(env.subs, content);
-                let subs_slice =
-                    SubsSlice::insert_into_subs(env.subs, [bytes_arg_var, fmt_arg_var]);
-
-                env.subs.set_content(
-                    this_custom_callback_var,
-                    Content::Structure(FlatType::Func(
-                        subs_slice,
-                        custom_callback_lambda_set_var,
-                        custom_callback_ret_var,
-                    )),
-                );
-
-                custom_callback_lambda_set_var
-            };
-
-            // \bytes, fmt -> …
-            Expr::Closure(ClosureData {
-                function_type: this_custom_callback_var,
-                closure_type: custom_callback_lambda_set_var,
-                return_type: custom_callback_ret_var,
-                name: custom_closure_symbol,
-                captured_symbols: vec![(state_arg_symbol, state_record_var)],
-                recursive: Recursive::NotRecursive,
-                arguments: vec![
-                    (
-                        bytes_arg_var,
-                        AnnotatedMark::known_exhaustive(),
-                        Loc::at_zero(Pattern::Identifier(bytes_arg_symbol)),
-                    ),
-                    (
-                        fmt_arg_var,
-                        AnnotatedMark::known_exhaustive(),
-                        Loc::at_zero(Pattern::Identifier(fmt_arg_symbol)),
-                    ),
-                ],
-                loc_body: Box::new(Loc::at_zero(custom_callback_body)),
-            })
-        };
-
-        let decode_custom_ret_var = env.subs.fresh_unnamed_flex_var();
-        let decode_custom = {
-            let decode_custom_var = env.import_builtin_symbol_var(Symbol::DECODE_CUSTOM);
-            let decode_custom_closure_var = env.subs.fresh_unnamed_flex_var();
-            let this_decode_custom_var = {
-                let subs_slice = SubsSlice::insert_into_subs(env.subs, [this_custom_callback_var]);
-                let flat_type =
-                    FlatType::Func(subs_slice, decode_custom_closure_var, decode_custom_ret_var);
-
-                synth_var(env.subs, Content::Structure(flat_type))
-            };
-
-            env.unify(decode_custom_var, this_decode_custom_var);
-
-            // Decode.custom \bytes, fmt -> …
-            Expr::Call(
-                Box::new((
-                    this_decode_custom_var,
-                    Loc::at_zero(Expr::Var(Symbol::DECODE_CUSTOM)),
-                    decode_custom_closure_var,
-                    decode_custom_ret_var,
-                )),
-                vec![(this_custom_callback_var, Loc::at_zero(custom_callback))],
-                CalledVia::Space,
-            )
-        };
-
-        env.unify(keep_payload_var, decode_custom_ret_var);
-
-        let keep = {
-            // Keep (Decode.custom \bytes, fmt ->
-            //     when Decode.decodeWith bytes Decode.decoder fmt is
-            //         rec ->
-            //             {
-            //                 rest: rec.rest,
-            //                 result: when rec.result is
-            //                     Ok val -> Ok {state & first: Ok val},
-            //                     Err err -> Err err
-            //             }
-            // )
-            Expr::Tag {
-                tag_union_var: keep_or_skip_var,
-                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
-                name: "Keep".into(),
-                arguments: vec![(decode_custom_ret_var, Loc::at_zero(decode_custom))],
-            }
-        };
+        let keep = decoder_step_keep(env, &step, &field_name, field_var, result_field_var);
 
         let branch = {
             // "first" ->
@@ -644,7 +234,7 @@ fn decoder_record_step_field(
             //     )
             WhenBranch {
                 patterns: vec![WhenBranchPattern {
-                    pattern: Loc::at_zero(Pattern::StrLiteral(field_name.into())),
+                    pattern: Loc::at_zero(Pattern::StrLiteral(field_name.as_str().into())),
                     degenerate: false,
                 }],
                 value: Loc::at_zero(keep),
@@ -662,12 +252,7 @@ fn decoder_record_step_field(
             pattern: Loc::at_zero(Pattern::Underscore),
             degenerate: false,
         }],
-        value: Loc::at_zero(Expr::Tag {
-            tag_union_var: keep_or_skip_var,
-            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
-            name: "Skip".into(),
-            arguments: Vec::new(),
-        }),
+        value: Loc::at_zero(step.skip(env)),
         guard: None,
         redundant: RedundantMark::known_non_redundant(),
     };
@@ -678,7 +263,7 @@ fn decoder_record_step_field(
     let body = Expr::When {
         loc_cond: Box::new(Loc::at_zero(Expr::Var(field_arg_symbol))),
         cond_var: Variable::STR,
-        expr_var: keep_or_skip_var,
+        expr_var: step.keep_or_skip_var,
         region: Region::zero(),
         branches,
         branches_cond_var: Variable::STR,
@@ -703,14 +288,18 @@ fn decoder_record_step_field(
 
         env.subs.set_content(
             function_type,
-            Content::Structure(FlatType::Func(args_slice, closure_type, keep_or_skip_var)),
+            Content::Structure(FlatType::Func(
+                args_slice,
+                closure_type,
+                step.keep_or_skip_var,
+            )),
         )
     };
 
     let expr = Expr::Closure(ClosureData {
         function_type,
         closure_type,
-        return_type: keep_or_skip_var,
+        return_type: step.keep_or_skip_var,
         name: step_field_closure,
         captured_symbols: Vec::new(),
         recursive: Recursive::NotRecursive,
@@ -732,158 +321,686 @@ fn decoder_record_step_field(
     (expr, function_type)
 }
 
-// Example:
-// finalizer = \rec ->
-//     when rec.first is
-//         Ok first ->
-//             when rec.second is
-//                 Ok second -> Ok {first, second}
-//                 Err NoField -> Err TooShort
-//         Err NoField -> Err TooShort
-fn decoder_record_finalizer(
-    env: &mut Env,
+/// What every branch of a step function like `stepField` shares: the state it updates, and the
+/// `[Keep (Decoder state fmt), Skip]` it returns.
+#[derive(Clone, Copy)]
+struct StepContext {
+    state_arg_symbol: Symbol,
     state_record_var: Variable,
-    fields: &[Lowercase],
-    field_vars: &[Variable],
-    result_field_vars: &[Variable],
-) -> (Expr, Variable, Variable) {
-    let state_arg_symbol = env.new_symbol("stateRecord");
-    let mut fields_map = SendMap::default();
-    let mut pattern_symbols = Vec::with_capacity(fields.len());
-    let decode_err_var = {
-        let flat_type = FlatType::TagUnion(
-            UnionTags::tag_without_arguments(env.subs, "TooShort".into()),
-            Variable::EMPTY_TAG_UNION,
-        );
-
-        synth_var(env.subs, Content::Structure(flat_type))
-    };
-
-    for (field_name, &field_var) in fields.iter().zip(field_vars.iter()) {
-        let symbol = env.new_symbol(field_name.as_str());
+    decode_err_var: Variable,
+    keep_payload_var: Variable,
+    keep_or_skip_var: Variable,
+}
 
-        pattern_symbols.push(symbol);
+impl StepContext {
+    fn new(
+        env: &mut Env,
+        state_arg_symbol: Symbol,
+        state_record_var: Variable,
+        decode_err_var: Variable,
+    ) -> Self {
+        let keep_payload_var = env.subs.fresh_unnamed_flex_var();
+        let keep_or_skip_var = {
+            let keep_payload_subs_slice = SubsSlice::insert_into_subs(env.subs, [keep_payload_var]);
+            let flat_type = FlatType::TagUnion(
+                UnionTags::insert_slices_into_subs(
+                    env.subs,
+                    [
+                        ("Keep".into(), keep_payload_subs_slice),
+                        ("Skip".into(), Default::default()),
+                    ],
+                ),
+                Variable::EMPTY_TAG_UNION,
+            );
 
-        let field_expr = Expr::Var(symbol);
-        let field = Field {
-            var: field_var,
-            region: Region::zero(),
-            loc_expr: Box::new(Loc::at_zero(field_expr)),
+            synth_var(env.subs, Content::Structure(flat_type))
         };
 
-        fields_map.insert(field_name.clone(), field);
+        StepContext {
+            state_arg_symbol,
+            state_record_var,
+            decode_err_var,
+            keep_payload_var,
+            keep_or_skip_var,
+        }
     }
 
-    // The bottom of the happy path - return the decoded record {first: a, second: b} wrapped with
-    // "Ok".
-    let return_type_var;
-    let mut body = {
-        let subs = &mut env.subs;
-        let record_field_iter = fields
-            .iter()
-            .zip(field_vars.iter())
-            .map(|(field_name, &field_var)| (field_name.clone(), RecordField::Required(field_var)));
-        let flat_type = FlatType::Record(
-            RecordFields::insert_into_subs(subs, record_field_iter),
-            Variable::EMPTY_RECORD,
-        );
-        let done_record_var = synth_var(subs, Content::Structure(flat_type));
-        let done_record = Expr::Record {
-            record_var: done_record_var,
-            fields: fields_map,
-        };
+    fn skip(&self, env: &mut Env) -> Expr {
+        Expr::Tag {
+            tag_union_var: self.keep_or_skip_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: "Skip".into(),
+            arguments: Vec::new(),
+        }
+    }
+}
 
-        return_type_var = {
-            let flat_type = FlatType::TagUnion(
-                UnionTags::for_result(subs, done_record_var, decode_err_var),
+// Example:
+// Keep (Decode.custom \bytes, fmt ->
+//     when Decode.decodeWith bytes Decode.decoder fmt is
+//         rec ->
+//             {
+//                 rest: rec.rest,
+//                 result: when rec.result is
+//                     Ok val -> Ok {state & first: Ok val},
+//                     Err err -> Err err
+//             }
+// )
+fn decoder_step_keep(
+    env: &mut Env,
+    step: &StepContext,
+    field_name: &Lowercase,
+    field_var: Variable,
+    result_field_var: Variable,
+) -> Expr {
+    let StepContext {
+        state_arg_symbol,
+        state_record_var,
+        decode_err_var,
+        keep_payload_var,
+        keep_or_skip_var,
+    } = *step;
+
+    let this_custom_callback_var;
+    let custom_callback_ret_var;
+    let custom_callback = {
+        // \bytes, fmt ->
+        //     when Decode.decodeWith bytes Decode.decoder fmt is
+        //         rec ->
+        //             {
+        //                 rest: rec.rest,
+        //                 result: when rec.result is
+        //                     Ok val -> Ok {state & first: Ok val},
+        //                     Err err -> Err err
+        //             }
+        let bytes_arg_symbol = env.new_symbol("bytes");
+        let fmt_arg_symbol = env.new_symbol("fmt");
+        let bytes_arg_var = env.subs.fresh_unnamed_flex_var();
+        let fmt_arg_var = env.subs.fresh_unnamed_flex_var();
+
+        // rec.result : [Ok field_var, Err DecodeError]
+        let rec_dot_result = {
+            let tag_union = FlatType::TagUnion(
+                UnionTags::for_result(env.subs, field_var, decode_err_var),
                 Variable::EMPTY_TAG_UNION,
             );
 
-            synth_var(subs, Content::Structure(flat_type))
+            synth_var(env.subs, Content::Structure(tag_union))
         };
 
-        Expr::Tag {
-            tag_union_var: return_type_var,
-            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
-            name: "Ok".into(),
-            arguments: vec![(done_record_var, Loc::at_zero(done_record))],
-        }
-    };
+        // rec : { rest: List U8, result: (typeof rec.result) }
+        let rec_var = {
+            let fields = RecordFields::insert_into_subs(
+                env.subs,
+                [
+                    ("rest".into(), RecordField::Required(Variable::LIST_U8)),
+                    ("result".into(), RecordField::Required(rec_dot_result)),
+                ],
+            );
+            let record = FlatType::Record(fields, Variable::EMPTY_RECORD);
 
-    // Unwrap each result in the decoded state
-    //
-    // when rec.first is
-    //     Ok first -> ...happy path...
-    //     Err NoField -> Err TooShort
-    for (((symbol, field_name), &field_var), &result_field_var) in pattern_symbols
-        .iter()
-        .rev()
-        .zip(fields.iter().rev())
-        .zip(field_vars.iter().rev())
-        .zip(result_field_vars.iter().rev())
-    {
-        // when rec.first is
-        let cond_expr = Expr::Access {
-            record_var: state_record_var,
-            ext_var: env.new_ext_var(ExtensionKind::Record),
-            field_var: result_field_var,
-            loc_expr: Box::new(Loc::at_zero(Expr::Var(state_arg_symbol))),
-            field: field_name.clone(),
+            synth_var(env.subs, Content::Structure(record))
         };
 
-        // Example: `Ok x -> expr`
-        let ok_branch = WhenBranch {
-            patterns: vec![WhenBranchPattern {
-                pattern: Loc::at_zero(Pattern::AppliedTag {
-                    whole_var: result_field_var,
-                    ext_var: Variable::EMPTY_TAG_UNION,
-                    tag_name: "Ok".into(),
-                    arguments: vec![(field_var, Loc::at_zero(Pattern::Identifier(*symbol)))],
-                }),
-                degenerate: false,
-            }],
-            value: Loc::at_zero(body),
-            guard: None,
-            redundant: RedundantMark::known_non_redundant(),
+        // `Decode.decoder` for the field's value
+        let decoder_var = env.import_builtin_symbol_var(Symbol::DECODE_DECODER);
+        let decode_with_var = env.import_builtin_symbol_var(Symbol::DECODE_DECODE_WITH);
+        let lambda_set_var = env.subs.fresh_unnamed_flex_var();
+        let this_decode_with_var = {
+            let subs_slice =
+                SubsSlice::insert_into_subs(env.subs, [bytes_arg_var, decoder_var, fmt_arg_var]);
+            let this_decode_with_var = synth_var(
+                env.subs,
+                Content::Structure(FlatType::Func(subs_slice, lambda_set_var, rec_var)),
+            );
+
+            env.unify(decode_with_var, this_decode_with_var);
+
+            this_decode_with_var
         };
 
-        // Example: `_ -> Err TooShort`
-        let err_branch = WhenBranch {
-            patterns: vec![WhenBranchPattern {
-                pattern: Loc::at_zero(Pattern::Underscore),
-                degenerate: false,
-            }],
-            value: Loc::at_zero(Expr::Tag {
-                tag_union_var: return_type_var,
-                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
-                name: "Err".into(),
-                arguments: vec![(
-                    decode_err_var,
-                    Loc::at_zero(Expr::Tag {
-                        tag_union_var: decode_err_var,
-                        ext_var: Variable::EMPTY_TAG_UNION,
-                        name: "TooShort".into(),
-                        arguments: Vec::new(),
-                    }),
-                )],
-            }),
-            guard: None,
-            redundant: RedundantMark::known_non_redundant(),
+        // The result of decoding this field's value - either the updated state, or a decoding error.
+        let when_expr_var = {
+            let flat_type = FlatType::TagUnion(
+                UnionTags::for_result(env.subs, state_record_var, decode_err_var),
+                Variable::EMPTY_TAG_UNION,
+            );
+
+            synth_var(env.subs, Content::Structure(flat_type))
         };
 
-        body = Expr::When {
-            loc_cond: Box::new(Loc::at_zero(cond_expr)),
-            cond_var: result_field_var,
+        // What our decoder passed to `Decode.custom` returns - the result of decoding the
+        // field's value, and the remaining bytes.
+        custom_callback_ret_var = {
+            let rest_field = RecordField::Required(Variable::LIST_U8);
+            let result_field = RecordField::Required(when_expr_var);
+            let flat_type = FlatType::Record(
+                RecordFields::insert_into_subs(
+                    env.subs,
+                    [("rest".into(), rest_field), ("result".into(), result_field)],
+                ),
+                Variable::EMPTY_RECORD,
+            );
+
+            synth_var(env.subs, Content::Structure(flat_type))
+        };
+
+        let custom_callback_body = {
+            let rec_symbol = env.new_symbol("rec");
+
+            // # Uses a single-branch `when` because `let` is more expensive to monomorphize
+            // # due to checks for polymorphic expressions, and `rec` would be polymorphic.
+            // when Decode.decodeWith bytes Decode.decoder fmt is
+            //     rec ->
+            //         {
+            //             rest: rec.rest,
+            //             result: when rec.result is
+            //                 Ok val -> Ok {state & first: Ok val},
+            //                 Err err -> Err err
+            //         }
+            let branch_body = {
+                let result_val = {
+                    // result: when rec.result is
+                    //     Ok val -> Ok {state & first: Ok val},
+                    //     Err err -> Err err
+                    let ok_val_symbol = env.new_symbol("val");
+                    let err_val_symbol = env.new_symbol("err");
+                    let ok_branch_expr = {
+                        // Ok {state & first: Ok val},
+                        let mut updates = SendMap::default();
+
+                        updates.insert(
+                            field_name.clone(),
+                            Field {
+                                var: result_field_var,
+                                region: Region::zero(),
+                                loc_expr: Box::new(Loc::at_zero(Expr::Tag {
+                                    tag_union_var: result_field_var,
+                                    ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                                    name: "Ok".into(),
+                                    arguments: vec![(
+                                        field_var,
+                                        Loc::at_zero(Expr::Var(ok_val_symbol)),
+                                    )],
+                                })),
+                            },
+                        );
+
+                        let updated_record = Expr::Update {
+                            record_var: state_record_var,
+                            ext_var: env.new_ext_var(ExtensionKind::Record),
+                            symbol: state_arg_symbol,
+                            updates,
+                        };
+
+                        Expr::Tag {
+                            tag_union_var: when_expr_var,
+                            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                            name: "Ok".into(),
+                            arguments: vec![(state_record_var, Loc::at_zero(updated_record))],
+                        }
+                    };
+
+                    let branches = vec![
+                        // Ok val -> Ok {state & first: Ok val},
+                        WhenBranch {
+                            patterns: vec![WhenBranchPattern {
+                                pattern: Loc::at_zero(Pattern::AppliedTag {
+                                    whole_var: rec_dot_result,
+                                    ext_var: Variable::EMPTY_TAG_UNION,
+                                    tag_name: "Ok".into(),
+                                    arguments: vec![(
+                                        field_var,
+                                        Loc::at_zero(Pattern::Identifier(ok_val_symbol)),
+                                    )],
+                                }),
+                                degenerate: false,
+                            }],
+                            value: Loc::at_zero(ok_branch_expr),
+                            guard: None,
+                            redundant: RedundantMark::known_non_redundant(),
+                        },
+                        // Err err -> Err err
+                        WhenBranch {
+                            patterns: vec![WhenBranchPattern {
+                                pattern: Loc::at_zero(Pattern::AppliedTag {
+                                    whole_var: rec_dot_result,
+                                    ext_var: Variable::EMPTY_TAG_UNION,
+                                    tag_name: "Err".into(),
+                                    arguments: vec![(
+                                        decode_err_var,
+                                        Loc::at_zero(Pattern::Identifier(err_val_symbol)),
+                                    )],
+                                }),
+                                degenerate: false,
+                            }],
+                            value: Loc::at_zero(Expr::Tag {
+                                tag_union_var: when_expr_var,
+                                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                                name: "Err".into(),
+                                arguments: vec![(
+                                    decode_err_var,
+                                    Loc::at_zero(Expr::Var(err_val_symbol)),
+                                )],
+                            }),
+                            guard: None,
+                            redundant: RedundantMark::known_non_redundant(),
+                        },
+                    ];
+
+                    // when rec.result is
+                    //     Ok val -> Ok {state & first: Ok val},
+                    //     Err err -> Err err
+                    Expr::When {
+                        loc_cond: Box::new(Loc::at_zero(Expr::Access {
+                            record_var: rec_var,
+                            ext_var: env.new_ext_var(ExtensionKind::Record),
+                            field_var: rec_dot_result,
+                            loc_expr: Box::new(Loc::at_zero(Expr::Var(rec_symbol))),
+                            field: "result".into(),
+                        })),
+                        cond_var: rec_dot_result,
+                        expr_var: when_expr_var,
+                        region: Region::zero(),
+                        branches,
+                        branches_cond_var: rec_dot_result,
+                        exhaustive: ExhaustiveMark::known_exhaustive(),
+                    }
+                };
+
+                // {
+                //     rest: rec.rest,
+                //     result: when rec.result is
+                //         Ok val -> Ok {state & first: Ok val},
+                //         Err err -> Err err
+                // }
+                let mut fields_map = SendMap::default();
+
+                fields_map.insert(
+                    "rest".into(),
+                    Field {
+                        var: Variable::LIST_U8,
+                        region: Region::zero(),
+                        loc_expr: Box::new(Loc::at_zero(Expr::Access {
+                            record_var: rec_var,
+                            ext_var: env.new_ext_var(ExtensionKind::Record),
+                            field_var: Variable::LIST_U8,
+                            loc_expr: Box::new(Loc::at_zero(Expr::Var(rec_symbol))),
+                            field: "rest".into(),
+                        })),
+                    },
+                );
+
+                // result: when rec.result is
+                //     Ok val -> Ok {state & first: Ok val},
+                //     Err err -> Err err
+                fields_map.insert(
+                    "result".into(),
+                    Field {
+                        var: when_expr_var,
+                        region: Region::zero(),
+                        loc_expr: Box::new(Loc::at_zero(result_val)),
+                    },
+                );
+
+                Expr::Record {
+                    record_var: custom_callback_ret_var,
+                    fields: fields_map,
+                }
+            };
+
+            let branch = WhenBranch {
+                patterns: vec![WhenBranchPattern {
+                    pattern: Loc::at_zero(Pattern::Identifier(rec_symbol)),
+                    degenerate: false,
+                }],
+                value: Loc::at_zero(branch_body),
+                guard: None,
+                redundant: RedundantMark::known_non_redundant(),
+            };
+
+            let condition_expr = Expr::Call(
+                Box::new((
+                    this_decode_with_var,
+                    Loc::at_zero(Expr::Var(Symbol::DECODE_DECODE_WITH)),
+                    lambda_set_var,
+                    rec_var,
+                )),
+                vec![
+                    (Variable::LIST_U8, Loc::at_zero(Expr::Var(bytes_arg_symbol))),
+                    (
+                        decoder_var,
+                        Loc::at_zero(Expr::AbilityMember(
+                            Symbol::DECODE_DECODER,
+                            None,
+                            decoder_var,
+                        )),
+                    ),
+                    (fmt_arg_var, Loc::at_zero(Expr::Var(fmt_arg_symbol))),
+                ],
+                CalledVia::Space,
+            );
+
+            // when Decode.decodeWith bytes Decode.decoder fmt is
+            Expr::When {
+                loc_cond: Box::new(Loc::at_zero(condition_expr)),
+                cond_var: rec_var,
+                expr_var: custom_callback_ret_var,
+                region: Region::zero(),
+                branches: vec![branch],
+                branches_cond_var: rec_var,
+                exhaustive: ExhaustiveMark::known_exhaustive(),
+            }
+        };
+
+        let custom_closure_symbol = env.new_symbol("customCallback");
+        this_custom_callback_var = env.subs.fresh_unnamed_flex_var();
+        let custom_callback_lambda_set_var = {
+            let content = Content::LambdaSet(LambdaSet {
+                solved: UnionLambdas::insert_into_subs(
+                    env.subs,
+                    [(custom_closure_symbol, [state_record_var])],
+                ),
+                recursion_var: OptVariable::NONE,
+                unspecialized: Default::default(),
+                ambient_function: this_custom_callback_var,
+            });
+            let custom_callback_lambda_set_var = synth_var(env.subs, content);
+            let subs_slice = SubsSlice::insert_into_subs(env.subs, [bytes_arg_var, fmt_arg_var]);
+
+            env.subs.set_content(
+                this_custom_callback_var,
+                Content::Structure(FlatType::Func(
+                    subs_slice,
+                    custom_callback_lambda_set_var,
+                    custom_callback_ret_var,
+                )),
+            );
+
+            custom_callback_lambda_set_var
+        };
+
+        // \bytes, fmt -> …
+        Expr::Closure(ClosureData {
+            function_type: this_custom_callback_var,
+            closure_type: custom_callback_lambda_set_var,
+            return_type: custom_callback_ret_var,
+            name: custom_closure_symbol,
+            captured_symbols: vec![(state_arg_symbol, state_record_var)],
+            recursive: Recursive::NotRecursive,
+            arguments: vec![
+                (
+                    bytes_arg_var,
+                    AnnotatedMark::known_exhaustive(),
+                    Loc::at_zero(Pattern::Identifier(bytes_arg_symbol)),
+                ),
+                (
+                    fmt_arg_var,
+                    AnnotatedMark::known_exhaustive(),
+                    Loc::at_zero(Pattern::Identifier(fmt_arg_symbol)),
+                ),
+            ],
+            loc_body: Box::new(Loc::at_zero(custom_callback_body)),
+        })
+    };
+
+    let decode_custom_ret_var = env.subs.fresh_unnamed_flex_var();
+    let decode_custom = {
+        let decode_custom_var = env.import_builtin_symbol_var(Symbol::DECODE_CUSTOM);
+        let decode_custom_closure_var = env.subs.fresh_unnamed_flex_var();
+        let this_decode_custom_var = {
+            let subs_slice = SubsSlice::insert_into_subs(env.subs, [this_custom_callback_var]);
+            let flat_type =
+                FlatType::Func(subs_slice, decode_custom_closure_var, decode_custom_ret_var);
+
+            synth_var(env.subs, Content::Structure(flat_type))
+        };
+
+        env.unify(decode_custom_var, this_decode_custom_var);
+
+        // Decode.custom \bytes, fmt -> …
+        Expr::Call(
+            Box::new((
+                this_decode_custom_var,
+                Loc::at_zero(Expr::Var(Symbol::DECODE_CUSTOM)),
+                decode_custom_closure_var,
+                decode_custom_ret_var,
+            )),
+            vec![(this_custom_callback_var, Loc::at_zero(custom_callback))],
+            CalledVia::Space,
+        )
+    };
+
+    env.unify(keep_payload_var, decode_custom_ret_var);
+
+    {
+        // Keep (Decode.custom \bytes, fmt ->
+        //     when Decode.decodeWith bytes Decode.decoder fmt is
+        //         rec ->
+        //             {
+        //                 rest: rec.rest,
+        //                 result: when rec.result is
+        //                     Ok val -> Ok {state & first: Ok val},
+        //                     Err err -> Err err
+        //             }
+        // )
+        Expr::Tag {
+            tag_union_var: keep_or_skip_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: "Keep".into(),
+            arguments: vec![(decode_custom_ret_var, Loc::at_zero(decode_custom))],
+        }
+    }
+}
+
+// Example:
+// finalizer = \rec ->
+//     when rec.first is
+//         Ok first ->
+//             when rec.second is
+//                 Ok second -> Ok {first, second}
+//                 Err NoField -> Err TooShort
+//         Err NoField -> Err TooShort
+fn decoder_record_finalizer(
+    env: &mut Env,
+    state_record_var: Variable,
+    fields: &[Lowercase],
+    field_vars: &[Variable],
+    result_field_vars: &[Variable],
+) -> (Expr, Variable, Variable) {
+    let state_arg_symbol = env.new_symbol("stateRecord");
+    let mut fields_map = SendMap::default();
+    let mut pattern_symbols = Vec::with_capacity(fields.len());
+    let decode_err_var = decode_error_var(env);
+
+    for (field_name, &field_var) in fields.iter().zip(field_vars.iter()) {
+        let symbol = env.new_symbol(field_name.as_str());
+
+        pattern_symbols.push(symbol);
+
+        let field_expr = Expr::Var(symbol);
+        let field = Field {
+            var: field_var,
+            region: Region::zero(),
+            loc_expr: Box::new(Loc::at_zero(field_expr)),
+        };
+
+        fields_map.insert(field_name.clone(), field);
+    }
+
+    // The bottom of the happy path - return the decoded record {first: a, second: b} wrapped with
+    // "Ok".
+    let return_type_var;
+    let body = {
+        let subs = &mut env.subs;
+        let record_field_iter = fields
+            .iter()
+            .zip(field_vars.iter())
+            .map(|(field_name, &field_var)| (field_name.clone(), RecordField::Required(field_var)));
+        let flat_type = FlatType::Record(
+            RecordFields::insert_into_subs(subs, record_field_iter),
+            Variable::EMPTY_RECORD,
+        );
+        let done_record_var = synth_var(subs, Content::Structure(flat_type));
+        let done_record = Expr::Record {
+            record_var: done_record_var,
+            fields: fields_map,
+        };
+
+        return_type_var = {
+            let flat_type = FlatType::TagUnion(
+                UnionTags::for_result(subs, done_record_var, decode_err_var),
+                Variable::EMPTY_TAG_UNION,
+            );
+
+            synth_var(subs, Content::Structure(flat_type))
+        };
+
+        Expr::Tag {
+            tag_union_var: return_type_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: "Ok".into(),
+            arguments: vec![(done_record_var, Loc::at_zero(done_record))],
+        }
+    };
+
+    // Unwrap each result in the decoded state
+    //
+    // when rec.first is
+    //     Ok first -> ...happy path...
+    //     Err NoField -> Err TooShort
+    let state_fields: Vec<_> = fields
+        .iter()
+        .zip(pattern_symbols)
+        .zip(field_vars.iter().zip(result_field_vars.iter()))
+        .map(|((field_name, symbol), (&field_var, &result_field_var))| {
+            (field_name.clone(), symbol, field_var, result_field_var)
+        })
+        .collect();
+    let body = decoder_unwrap_state_fields(
+        env,
+        (state_arg_symbol, state_record_var),
+        &state_fields,
+        body,
+        (return_type_var, decode_err_var),
+    );
+
+    let (finalizer, function_var) = decoder_closure(
+        env,
+        "finalizer",
+        &[(state_arg_symbol, state_record_var)],
+        return_type_var,
+        body,
+    );
+
+    (finalizer, function_var, decode_err_var)
+}
+
+// Unwraps each field of the decoding state, so the happy path has all of them in scope.
+//
+// Example:
+// when rec.first is
+//     Ok first ->
+//         when rec.second is
+//             Ok second -> ...happy path...
+//             _ -> Err TooShort
+//     _ -> Err TooShort
+fn decoder_unwrap_state_fields(
+    env: &mut Env,
+    (state_arg_symbol, state_record_var): (Symbol, Variable),
+    state_fields: &[(Lowercase, Symbol, Variable, Variable)],
+    mut body: Expr,
+    (return_type_var, decode_err_var): (Variable, Variable),
+) -> Expr {
+    for (field_name, symbol, field_var, result_field_var) in state_fields.iter().rev() {
+        // when rec.first is
+        let cond_expr = Expr::Access {
+            record_var: state_record_var,
+            ext_var: env.new_ext_var(ExtensionKind::Record),
+            field_var: *result_field_var,
+            loc_expr: Box::new(Loc::at_zero(Expr::Var(state_arg_symbol))),
+            field: field_name.clone(),
+        };
+
+        // Example: `Ok x -> expr`
+        let ok_branch = WhenBranch {
+            patterns: vec![WhenBranchPattern {
+                pattern: Loc::at_zero(Pattern::AppliedTag {
+                    whole_var: *result_field_var,
+                    ext_var: Variable::EMPTY_TAG_UNION,
+                    tag_name: "Ok".into(),
+                    arguments: vec![(*field_var, Loc::at_zero(Pattern::Identifier(*symbol)))],
+                }),
+                degenerate: false,
+            }],
+            value: Loc::at_zero(body),
+            guard: None,
+            redundant: RedundantMark::known_non_redundant(),
+        };
+
+        // Example: `_ -> Err TooShort`
+        let err_branch = WhenBranch {
+            patterns: vec![WhenBranchPattern {
+                pattern: Loc::at_zero(Pattern::Underscore),
+                degenerate: false,
+            }],
+            value: Loc::at_zero(decoder_too_short(env, return_type_var, decode_err_var)),
+            guard: None,
+            redundant: RedundantMark::known_non_redundant(),
+        };
+
+        body = Expr::When {
+            loc_cond: Box::new(Loc::at_zero(cond_expr)),
+            cond_var: *result_field_var,
             expr_var: return_type_var,
             region: Region::zero(),
             branches: vec![ok_branch, err_branch],
-            branches_cond_var: result_field_var,
+            branches_cond_var: *result_field_var,
             exhaustive: ExhaustiveMark::known_exhaustive(),
         };
     }
 
+    body
+}
+
+// The `[TooShort]` that finalizers fail with.
+fn decode_error_var(env: &mut Env) -> Variable {
+    let flat_type = FlatType::TagUnion(
+        UnionTags::tag_without_arguments(env.subs, "TooShort".into()),
+        Variable::EMPTY_TAG_UNION,
+    );
+
+    synth_var(env.subs, Content::Structure(flat_type))
+}
+
+// Example: `Err TooShort`
+fn decoder_too_short(env: &mut Env, return_type_var: Variable, decode_err_var: Variable) -> Expr {
+    Expr::Tag {
+        tag_union_var: return_type_var,
+        ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+        name: "Err".into(),
+        arguments: vec![(
+            decode_err_var,
+            Loc::at_zero(Expr::Tag {
+                tag_union_var: decode_err_var,
+                ext_var: Variable::EMPTY_TAG_UNION,
+                name: "TooShort".into(),
+                arguments: Vec::new(),
+            }),
+        )],
+    }
+}
+
+// Builds a closure that captures nothing, like `finalizer = \rec -> ...`.
+fn decoder_closure(
+    env: &mut Env,
+    name_hint: &str,
+    arguments: &[(Symbol, Variable)],
+    return_type_var: Variable,
+    body: Expr,
+) -> (Expr, Variable) {
     let function_var = synth_var(env.subs, Content::Error); // We'll fix this up in subs later.
-    let function_symbol = env.new_symbol("finalizer");
+    let function_symbol = env.new_symbol(name_hint);
     let lambda_set = LambdaSet {
         solved: UnionLambdas::tag_without_arguments(env.subs, function_symbol),
         recursion_var: OptVariable::NONE,
@@ -892,7 +1009,7 @@ fn decoder_record_finalizer(
     };
     let closure_type = synth_var(env.subs, Content::LambdaSet(lambda_set));
     let flat_type = FlatType::Func(
-        SubsSlice::insert_into_subs(env.subs, [state_record_var]),
+        SubsSlice::insert_into_subs(env.subs, arguments.iter().map(|(_, var)| *var)),
         closure_type,
         return_type_var,
     );
@@ -901,22 +1018,27 @@ fn decoder_record_finalizer(
     env.subs
         .set_content(function_var, Content::Structure(flat_type));
 
-    let finalizer = Expr::Closure(ClosureData {
+    let closure = Expr::Closure(ClosureData {
         function_type: function_var,
         closure_type,
         return_type: return_type_var,
         name: function_symbol,
         captured_symbols: Vec::new(),
         recursive: Recursive::NotRecursive,
-        arguments: vec![(
-            state_record_var,
-            AnnotatedMark::known_exhaustive(),
-            Loc::at_zero(Pattern::Identifier(state_arg_symbol)),
-        )],
+        arguments: arguments
+            .iter()
+            .map(|&(symbol, var)| {
+                (
+                    var,
+                    AnnotatedMark::known_exhaustive(),
+                    Loc::at_zero(Pattern::Identifier(symbol)),
+                )
+            })
+            .collect(),
         loc_body: Box::new(Loc::at_zero(body)),
     });
 
-    (finalizer, function_var, decode_err_var)
+    (closure, function_var)
 }
 
 // Example:
@@ -991,6 +1113,573 @@ fn decoder_record_initial_state(
     )
 }
 
+// Implements decoding of a tag union. For example, for
+//
+//   [A a b, B c]
+//
+// we'd like to generate an impl like
+//
+// decoder : Decoder [A a b, B c] fmt | a has Decoding, b has Decoding, c has Decoding, fmt has DecoderFormatting
+// decoder =
+//     initialState : {f0: Result a [NoField], f1: Result b [NoField], f2: Result c [NoField]}
+//     initialState = {f0: Err NoField, f1: Err NoField, f2: Err NoField}
+//
+//     stepPayload = \state, tag, index ->
+//         when tag is
+//             "A" ->
+//                 when index is
+//                     0 -> Keep (Decode.custom \bytes, fmt -> ...{state & f0: Ok val}...)
+//                     1 -> Keep (Decode.custom \bytes, fmt -> ...{state & f1: Ok val}...)
+//                     _ -> Skip
+//             "B" ->
+//                 when index is
+//                     0 -> Keep (Decode.custom \bytes, fmt -> ...{state & f2: Ok val}...)
+//                     _ -> Skip
+//             _ -> Skip
+//
+//     finalizer = \state, tag ->
+//         when tag is
+//             "A" ->
+//                 when state.f0 is
+//                     Ok payload ->
+//                         when state.f1 is
+//                             Ok payload2 -> Ok (A payload payload2)
+//                             _ -> Err TooShort
+//                     _ -> Err TooShort
+//             "B" ->
+//                 when state.f2 is
+//                     Ok payload3 -> Ok (B payload3)
+//                     _ -> Err TooShort
+//             _ -> Err TooShort
+//
+//     Decode.custom \bytes, fmt -> Decode.decodeWith bytes (Decode.tag initialState stepPayload finalizer) fmt
+//
+// That is, every payload of every tag gets its own field in the decoding state, exactly as if we
+// were decoding a record.
+fn decoder_tag_union(
+    env: &mut Env,
+    _def_symbol: Symbol,
+    tags: Vec<(TagName, u16)>,
+) -> (Expr, Variable) {
+    // The state fields holding the payloads of each tag, e.g. [(A, [f0, f1]), (B, [f2])]
+    let mut field_index = 0;
+    let tag_fields: Vec<(TagName, Vec<Lowercase>)> = tags
+        .into_iter()
+        .map(|(tag_name, arity)| {
+            let fields = (0..arity)
+                .map(|_| {
+                    field_index += 1;
+                    Lowercase::from(format!("f{}", field_index - 1))
+                })
+                .collect();
+
+            (tag_name, fields)
+        })
+        .collect();
+
+    // Record fields have to be sorted, and "f10" comes before "f2".
+    let mut field_names: Vec<Lowercase> = tag_fields
+        .iter()
+        .flat_map(|(_, fields)| fields.iter().cloned())
+        .collect();
+    field_names.sort();
+
+    // The decoded type of each payload, and its type in the decoding state
+    let mut field_vars = Vec::with_capacity(field_names.len());
+    let mut result_field_vars = Vec::with_capacity(field_names.len());
+
+    // initialState = ...
+    let (initial_state_var, initial_state) =
+        decoder_record_initial_state(env, &field_names, &mut field_vars, &mut result_field_vars);
+
+    // For each tag, its payloads
+    let tag_fields: Vec<(TagName, Vec<PayloadField>)> = tag_fields
+        .into_iter()
+        .map(|(tag_name, fields)| {
+            let fields = fields
+                .into_iter()
+                .map(|field_name| {
+                    let index = field_names.binary_search(&field_name).unwrap();
+
+                    (field_name, field_vars[index], result_field_vars[index])
+                })
+                .collect();
+
+            (tag_name, fields)
+        })
+        .collect();
+
+    // [A a b, B c]
+    let tag_union_var = {
+        let union_tags = UnionTags::insert_into_subs(
+            env.subs,
+            tag_fields.iter().map(|(tag_name, fields)| {
+                (
+                    tag_name.clone(),
+                    fields
+                        .iter()
+                        .map(|&(_, field_var, _)| field_var)
+                        .collect::<Vec<_>>(),
+                )
+            }),
+        );
+
+        synth_var(
+            env.subs,
+            Content::Structure(FlatType::TagUnion(union_tags, Variable::EMPTY_TAG_UNION)),
+        )
+    };
+
+    // finalizer = ...
+    let (finalizer, finalizer_var, decode_err_var) =
+        decoder_tag_union_finalizer(env, initial_state_var, &tag_fields, tag_union_var);
+
+    // stepPayload = ...
+    let (step_payload, step_var) =
+        decoder_tag_union_step_payload(env, &tag_fields, initial_state_var, decode_err_var);
+
+    decoder_with_state(
+        env,
+        Symbol::DECODE_TAG,
+        (initial_state, initial_state_var),
+        (step_payload, step_var),
+        (finalizer, finalizer_var),
+    )
+}
+
+/// The state field holding a tag's payload or a tuple's element, its decoded type, and its type in
+/// the state.
+type PayloadField = (Lowercase, Variable, Variable);
+
+// Example:
+// stepPayload = \state, tag, index ->
+//     when tag is
+//         "A" ->
+//             when index is
+//                 0 -> Keep (Decode.custom \bytes, fmt -> ...{state & f0: Ok val}...)
+//                 1 -> Keep (Decode.custom \bytes, fmt -> ...{state & f1: Ok val}...)
+//                 _ -> Skip
+//         _ -> Skip
+fn decoder_tag_union_step_payload(
+    env: &mut Env,
+    tag_fields: &[(TagName, Vec<PayloadField>)],
+    state_record_var: Variable,
+    decode_err_var: Variable,
+) -> (Expr, Variable) {
+    let state_arg_symbol = env.new_symbol("stateRecord");
+    let tag_arg_symbol = env.new_symbol("tag");
+    let index_arg_symbol = env.new_symbol("index");
+
+    let step = StepContext::new(env, state_arg_symbol, state_record_var, decode_err_var);
+
+    let mut tag_branches = Vec::with_capacity(tag_fields.len() + 1);
+
+    // Tags without a payload never have anything to decode, so they can share the default branch.
+    for (tag_name, fields) in tag_fields.iter().filter(|(_, fields)| !fields.is_empty()) {
+        // "A" ->
+        //     when index is
+        tag_branches.push(WhenBranch {
+            patterns: vec![WhenBranchPattern {
+                pattern: Loc::at_zero(Pattern::StrLiteral(tag_name.0.as_str().into())),
+                degenerate: false,
+            }],
+            value: Loc::at_zero(decoder_step_index(env, &step, index_arg_symbol, fields)),
+            guard: None,
+            redundant: RedundantMark::known_non_redundant(),
+        });
+    }
+
+    // _ -> Skip
+    tag_branches.push(WhenBranch {
+        patterns: vec![WhenBranchPattern {
+            pattern: Loc::at_zero(Pattern::Underscore),
+            degenerate: false,
+        }],
+        value: Loc::at_zero(step.skip(env)),
+        guard: None,
+        redundant: RedundantMark::known_non_redundant(),
+    });
+
+    // when tag is
+    let body = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Var(tag_arg_symbol))),
+        cond_var: Variable::STR,
+        expr_var: step.keep_or_skip_var,
+        region: Region::zero(),
+        branches: tag_branches,
+        branches_cond_var: Variable::STR,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    decoder_closure(
+        env,
+        "stepPayload",
+        &[
+            (state_arg_symbol, state_record_var),
+            (tag_arg_symbol, Variable::STR),
+            (index_arg_symbol, Variable::NAT),
+        ],
+        step.keep_or_skip_var,
+        body,
+    )
+}
+
+// Keeps the field of the element at `index`, for payloads and tuples.
+//
+// Example:
+// when index is
+//     0 -> Keep (Decode.custom \bytes, fmt -> ...{state & f0: Ok val}...)
+//     1 -> Keep (Decode.custom \bytes, fmt -> ...{state & f1: Ok val}...)
+//     _ -> Skip
+fn decoder_step_index(
+    env: &mut Env,
+    step: &StepContext,
+    index_arg_symbol: Symbol,
+    fields: &[PayloadField],
+) -> Expr {
+    // +1 because of the default branch.
+    let mut index_branches = Vec::with_capacity(fields.len() + 1);
+
+    for (index, (field_name, field_var, result_field_var)) in fields.iter().enumerate() {
+        // 0 -> Keep (Decode.custom \bytes, fmt -> ...)
+        let keep = decoder_step_keep(env, step, field_name, *field_var, *result_field_var);
+        let index_pattern = Pattern::IntLiteral(
+            Variable::NAT,
+            Variable::NATURAL,
+            index.to_string().into_boxed_str(),
+            IntValue::I128((index as i128).to_ne_bytes()),
+            IntBound::Exact(IntLitWidth::Nat),
+        );
+
+        index_branches.push(WhenBranch {
+            patterns: vec![WhenBranchPattern {
+                pattern: Loc::at_zero(index_pattern),
+                degenerate: false,
+            }],
+            value: Loc::at_zero(keep),
+            guard: None,
+            redundant: RedundantMark::known_non_redundant(),
+        });
+    }
+
+    // _ -> Skip
+    index_branches.push(WhenBranch {
+        patterns: vec![WhenBranchPattern {
+            pattern: Loc::at_zero(Pattern::Underscore),
+            degenerate: false,
+        }],
+        value: Loc::at_zero(step.skip(env)),
+        guard: None,
+        redundant: RedundantMark::known_non_redundant(),
+    });
+
+    // when index is
+    Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Var(index_arg_symbol))),
+        cond_var: Variable::NAT,
+        expr_var: step.keep_or_skip_var,
+        region: Region::zero(),
+        branches: index_branches,
+        branches_cond_var: Variable::NAT,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    }
+}
+
+// Example:
+// finalizer = \state, tag ->
+//     when tag is
+//         "A" ->
+//             when state.f0 is
+//                 Ok payload ->
+//                     when state.f1 is
+//                         Ok payload2 -> Ok (A payload payload2)
+//                         _ -> Err TooShort
+//                 _ -> Err TooShort
+//         _ -> Err TooShort
+fn decoder_tag_union_finalizer(
+    env: &mut Env,
+    state_record_var: Variable,
+    tag_fields: &[(TagName, Vec<PayloadField>)],
+    tag_union_var: Variable,
+) -> (Expr, Variable, Variable) {
+    let state_arg_symbol = env.new_symbol("stateRecord");
+    let tag_arg_symbol = env.new_symbol("tag");
+    let decode_err_var = decode_error_var(env);
+
+    let return_type_var = {
+        let flat_type = FlatType::TagUnion(
+            UnionTags::for_result(env.subs, tag_union_var, decode_err_var),
+            Variable::EMPTY_TAG_UNION,
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    // +1 because of the default branch.
+    let mut branches = Vec::with_capacity(tag_fields.len() + 1);
+
+    for (tag_name, fields) in tag_fields {
+        let state_fields: Vec<_> = fields
+            .iter()
+            .map(|(field_name, field_var, result_field_var)| {
+                let symbol = env.new_symbol("payload");
+
+                (field_name.clone(), symbol, *field_var, *result_field_var)
+            })
+            .collect();
+
+        // The bottom of the happy path - return the decoded tag, e.g. `A payload payload2`,
+        // wrapped with "Ok".
+        let tag = Expr::Tag {
+            tag_union_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: tag_name.clone(),
+            arguments: state_fields
+                .iter()
+                .map(|&(_, symbol, field_var, _)| (field_var, Loc::at_zero(Expr::Var(symbol))))
+                .collect(),
+        };
+        let ok_tag = Expr::Tag {
+            tag_union_var: return_type_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: "Ok".into(),
+            arguments: vec![(tag_union_var, Loc::at_zero(tag))],
+        };
+
+        let body = decoder_unwrap_state_fields(
+            env,
+            (state_arg_symbol, state_record_var),
+            &state_fields,
+            ok_tag,
+            (return_type_var, decode_err_var),
+        );
+
+        // "A" -> ...
+        branches.push(WhenBranch {
+            patterns: vec![WhenBranchPattern {
+                pattern: Loc::at_zero(Pattern::StrLiteral(tag_name.0.as_str().into())),
+                degenerate: false,
+            }],
+            value: Loc::at_zero(body),
+            guard: None,
+            redundant: RedundantMark::known_non_redundant(),
+        });
+    }
+
+    // _ -> Err TooShort
+    branches.push(WhenBranch {
+        patterns: vec![WhenBranchPattern {
+            pattern: Loc::at_zero(Pattern::Underscore),
+            degenerate: false,
+        }],
+        value: Loc::at_zero(decoder_too_short(env, return_type_var, decode_err_var)),
+        guard: None,
+        redundant: RedundantMark::known_non_redundant(),
+    });
+
+    // when tag is
+    let body = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Var(tag_arg_symbol))),
+        cond_var: Variable::STR,
+        expr_var: return_type_var,
+        region: Region::zero(),
+        branches,
+        branches_cond_var: Variable::STR,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    let (finalizer, function_var) = decoder_closure(
+        env,
+        "finalizer",
+        &[
+            (state_arg_symbol, state_record_var),
+            (tag_arg_symbol, Variable::STR),
+        ],
+        return_type_var,
+        body,
+    );
+
+    (finalizer, function_var, decode_err_var)
+}
+
+// Implements decoding of a tuple. For example, for
+//
+//   (a, b)
+//
+// we'd like to generate an impl like
+//
+// decoder : Decoder (a, b) fmt | a has Decoding, b has Decoding, fmt has DecoderFormatting
+// decoder =
+//     initialState : {e0: Result a [NoField], e1: Result b [NoField]}
+//     initialState = {e0: Err NoField, e1: Err NoField}
+//
+//     stepElem = \state, index ->
+//         when index is
+//             0 -> Keep (Decode.custom \bytes, fmt -> ...{state & e0: Ok val}...)
+//             1 -> Keep (Decode.custom \bytes, fmt -> ...{state & e1: Ok val}...)
+//             _ -> Skip
+//
+//     finalizer = \state ->
+//         when state.e0 is
+//             Ok e0 ->
+//                 when state.e1 is
+//                     Ok e1 -> Ok (e0, e1)
+//                     _ -> Err TooShort
+//             _ -> Err TooShort
+//
+//     Decode.custom \bytes, fmt -> Decode.decodeWith bytes (Decode.tuple initialState stepElem finalizer) fmt
+//
+// That is, the elements are decoded by position, and kept in the decoding state exactly as if we
+// were decoding a record.
+fn decoder_tuple(env: &mut Env, _def_symbol: Symbol, arity: u32) -> (Expr, Variable) {
+    // The state field holding each element, e.g. [e0, e1]
+    let elem_names: Vec<Lowercase> = (0..arity)
+        .map(|index| Lowercase::from(format!("e{}", index)))
+        .collect();
+
+    // Record fields have to be sorted, and "e10" comes before "e2".
+    let mut field_names = elem_names.clone();
+    field_names.sort();
+
+    // The decoded type of each element, and its type in the decoding state
+    let mut field_vars = Vec::with_capacity(field_names.len());
+    let mut result_field_vars = Vec::with_capacity(field_names.len());
+
+    // initialState = ...
+    let (initial_state_var, initial_state) =
+        decoder_record_initial_state(env, &field_names, &mut field_vars, &mut result_field_vars);
+
+    // The state field of each element, in element order
+    let elem_fields: Vec<PayloadField> = elem_names
+        .into_iter()
+        .map(|elem_name| {
+            let index = field_names.binary_search(&elem_name).unwrap();
+
+            (elem_name, field_vars[index], result_field_vars[index])
+        })
+        .collect();
+
+    // (a, b)
+    let tuple_var = {
+        let elems = TupleElems::insert_into_subs(
+            env.subs,
+            elem_fields
+                .iter()
+                .enumerate()
+                .map(|(index, &(_, field_var, _))| (index, field_var)),
+        );
+        let empty_tuple_var = synth_var(env.subs, Content::Structure(FlatType::EmptyTuple));
+
+        synth_var(
+            env.subs,
+            Content::Structure(FlatType::Tuple(elems, empty_tuple_var)),
+        )
+    };
+
+    // finalizer = ...
+    let (finalizer, finalizer_var, decode_err_var) =
+        decoder_tuple_finalizer(env, initial_state_var, &elem_fields, tuple_var);
+
+    // stepElem = ...
+    let (step_elem, step_var) = {
+        let state_arg_symbol = env.new_symbol("stateRecord");
+        let index_arg_symbol = env.new_symbol("index");
+        let step = StepContext::new(env, state_arg_symbol, initial_state_var, decode_err_var);
+
+        let body = decoder_step_index(env, &step, index_arg_symbol, &elem_fields);
+
+        decoder_closure(
+            env,
+            "stepElem",
+            &[
+                (state_arg_symbol, initial_state_var),
+                (index_arg_symbol, Variable::NAT),
+            ],
+            step.keep_or_skip_var,
+            body,
+        )
+    };
+
+    decoder_with_state(
+        env,
+        Symbol::DECODE_TUPLE,
+        (initial_state, initial_state_var),
+        (step_elem, step_var),
+        (finalizer, finalizer_var),
+    )
+}
+
+// Example:
+// finalizer = \state ->
+//     when state.e0 is
+//         Ok e0 ->
+//             when state.e1 is
+//                 Ok e1 -> Ok (e0, e1)
+//                 _ -> Err TooShort
+//         _ -> Err TooShort
+fn decoder_tuple_finalizer(
+    env: &mut Env,
+    state_record_var: Variable,
+    elem_fields: &[PayloadField],
+    tuple_var: Variable,
+) -> (Expr, Variable, Variable) {
+    let state_arg_symbol = env.new_symbol("stateRecord");
+    let decode_err_var = decode_error_var(env);
+
+    let return_type_var = {
+        let flat_type = FlatType::TagUnion(
+            UnionTags::for_result(env.subs, tuple_var, decode_err_var),
+            Variable::EMPTY_TAG_UNION,
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    let state_fields: Vec<_> = elem_fields
+        .iter()
+        .map(|(field_name, field_var, result_field_var)| {
+            let symbol = env.new_symbol(field_name.as_str());
+
+            (field_name.clone(), symbol, *field_var, *result_field_var)
+        })
+        .collect();
+
+    // The bottom of the happy path - return the decoded tuple (e0, e1) wrapped with "Ok".
+    let tuple = Expr::Tuple {
+        tuple_var,
+        elems: state_fields
+            .iter()
+            .map(|&(_, symbol, field_var, _)| {
+                (field_var, Box::new(Loc::at_zero(Expr::Var(symbol))))
+            })
+            .collect(),
+    };
+    let ok_tuple = Expr::Tag {
+        tag_union_var: return_type_var,
+        ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+        name: "Ok".into(),
+        arguments: vec![(tuple_var, Loc::at_zero(tuple))],
+    };
+
+    let body = decoder_unwrap_state_fields(
+        env,
+        (state_arg_symbol, state_record_var),
+        &state_fields,
+        ok_tuple,
+        (return_type_var, decode_err_var),
+    );
+
+    let (finalizer, function_var) = decoder_closure(
+        env,
+        "finalizer",
+        &[(state_arg_symbol, state_record_var)],
+        return_type_var,
+        body,
+    );
+
+    (finalizer, function_var, decode_err_var)
+}
+
 fn decoder_list(env: &mut Env<'_>, _def_symbol: Symbol) -> (Expr, Variable) {
     // Build
     //
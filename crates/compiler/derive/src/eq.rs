@@ -0,0 +1,541 @@
+//! Derivers for the `Eq` ability.
+//!
+//! Values that cannot reach a custom `isEq` are compared structurally, without a derived
+//! implementation; the derivers here are only used for types that contain a custom `isEq`
+//! somewhere inside of them, so that each part can be compared with its own `isEq`.
+
+use std::iter::once;
+
+use roc_can::{
+    expr::{AnnotatedMark, ClosureData, Expr, Recursive, WhenBranch, WhenBranchPattern},
+    pattern::Pattern,
+};
+use roc_derive_key::eq::FlatEqKey;
+use roc_module::{
+    called_via::CalledVia,
+    ident::{Lowercase, TagName},
+    symbol::Symbol,
+};
+use roc_region::all::{Loc, Region};
+use roc_types::{
+    subs::{
+        AliasVariables, Content, ExhaustiveMark, FlatType, GetSubsSlice, LambdaSet, OptVariable,
+        RecordFields, RedundantMark, SubsSlice, TupleElems, UnionLambdas, UnionTags, Variable,
+        VariableSubsSlice,
+    },
+    types::{AliasKind, RecordField, Type},
+};
+
+use crate::{synth_var, util::Env, DerivedBody};
+
+pub(crate) fn derive_is_eq(env: &mut Env<'_>, key: FlatEqKey, def_symbol: Symbol) -> DerivedBody {
+    let (body, body_type) = match key {
+        FlatEqKey::Record(fields) => is_eq_record(env, def_symbol, fields),
+        FlatEqKey::Tuple(arity) => is_eq_tuple(env, def_symbol, arity),
+        FlatEqKey::TagUnion(tags) => is_eq_tag_union(env, def_symbol, tags),
+        FlatEqKey::Opaque(opaque, type_arity, lambda_set_arity) => {
+            is_eq_opaque(env, def_symbol, opaque, type_arity, lambda_set_arity)
+        }
+    };
+
+    let specialization_lambda_sets =
+        env.get_specialization_lambda_sets(body_type, Symbol::BOOL_IS_EQ);
+
+    DerivedBody {
+        body,
+        body_type,
+        specialization_lambda_sets,
+    }
+}
+
+fn is_eq_record(env: &mut Env<'_>, fn_name: Symbol, fields: Vec<Lowercase>) -> (Expr, Variable) {
+    // Suppose rcd = { f1, ..., fn }.
+    // Build a generalized type t_rcd = { f1: t1, ..., fn: tn }, with fresh t1, ..., tn,
+    // so that we can re-use the derived impl for many records of the same fields.
+    let (record_var, record_fields) = {
+        let flex_fields = fields
+            .into_iter()
+            .map(|name| {
+                (
+                    name,
+                    RecordField::Required(env.subs.fresh_unnamed_flex_var()),
+                )
+            })
+            .collect::<Vec<(Lowercase, _)>>();
+        let fields = RecordFields::insert_into_subs(env.subs, flex_fields);
+        let record_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::Record(fields, Variable::EMPTY_RECORD)),
+        );
+
+        (record_var, fields)
+    };
+
+    // Now, an equality check for this record is
+    //
+    // isEq_rcd : { f1: t1, ..., fn: tn }, { f1: t1, ..., fn: tn } -> Bool | t1 has Eq, ..., tn has Eq
+    // isEq_rcd = \rcd1, rcd2 ->
+    //   Bool.isEq rcd1.f1 rcd2.f1 && ... && Bool.isEq rcd1.fn rcd2.fn
+    let rcd1_sym = env.new_symbol("rcd1");
+    let rcd2_sym = env.new_symbol("rcd2");
+
+    let comparisons = record_fields
+        .iter_all()
+        .map(|(field_name, field_var, _)| {
+            let field_name = env.subs[field_name].clone();
+            let field_var = env.subs[field_var];
+
+            let mut field_access = |rcd_sym| Expr::Access {
+                record_var,
+                field_var,
+                ext_var: env.subs.fresh_unnamed_flex_var(),
+                loc_expr: Box::new(Loc::at_zero(Expr::Var(rcd_sym))),
+                field: field_name.clone(),
+            };
+            let left = field_access(rcd1_sym);
+            let right = field_access(rcd2_sym);
+
+            call_is_eq(env, field_var, left, right)
+        })
+        .collect();
+
+    let (body_var, body) = all_of(env, comparisons);
+
+    // Finally, build the closure
+    // \rcd1, rcd2 -> body
+    build_outer_derived_closure(
+        env,
+        fn_name,
+        record_var,
+        (Pattern::Identifier(rcd1_sym), Pattern::Identifier(rcd2_sym)),
+        (body_var, body),
+    )
+}
+
+fn is_eq_tuple(env: &mut Env<'_>, fn_name: Symbol, arity: u32) -> (Expr, Variable) {
+    // Suppose tup = (e0, ..., en).
+    // Build a generalized type t_tup = (t0, ..., tn), with fresh t0, ..., tn,
+    // so that we can re-use the derived impl for many tuples of the same arity.
+    let (tuple_var, tuple_elems) = {
+        let flex_elems = (0..arity as usize)
+            .map(|i| (i, env.subs.fresh_unnamed_flex_var()))
+            .collect::<Vec<_>>();
+        let elems = TupleElems::insert_into_subs(env.subs, flex_elems);
+        let empty_tuple_var = synth_var(env.subs, Content::Structure(FlatType::EmptyTuple));
+        let tuple_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::Tuple(elems, empty_tuple_var)),
+        );
+
+        (tuple_var, elems)
+    };
+
+    // Now, an equality check for this tuple is
+    //
+    // isEq_tup : (t0, ..., tn), (t0, ..., tn) -> Bool | t0 has Eq, ..., tn has Eq
+    // isEq_tup = \tup1, tup2 ->
+    //   Bool.isEq tup1.0 tup2.0 && ... && Bool.isEq tup1.n tup2.n
+    let tup1_sym = env.new_symbol("tup1");
+    let tup2_sym = env.new_symbol("tup2");
+
+    let comparisons = tuple_elems
+        .iter_all()
+        .map(|(elem_index, elem_var)| {
+            let index = env.subs[elem_index];
+            let elem_var = env.subs[elem_var];
+
+            let mut elem_access = |tup_sym| Expr::TupleAccess {
+                tuple_var,
+                elem_var,
+                ext_var: env.subs.fresh_unnamed_flex_var(),
+                loc_expr: Box::new(Loc::at_zero(Expr::Var(tup_sym))),
+                index,
+            };
+            let left = elem_access(tup1_sym);
+            let right = elem_access(tup2_sym);
+
+            call_is_eq(env, elem_var, left, right)
+        })
+        .collect();
+
+    let (body_var, body) = all_of(env, comparisons);
+
+    // Finally, build the closure
+    // \tup1, tup2 -> body
+    build_outer_derived_closure(
+        env,
+        fn_name,
+        tuple_var,
+        (Pattern::Identifier(tup1_sym), Pattern::Identifier(tup2_sym)),
+        (body_var, body),
+    )
+}
+
+fn is_eq_tag_union(
+    env: &mut Env<'_>,
+    fn_name: Symbol,
+    tags: Vec<(TagName, u16)>,
+) -> (Expr, Variable) {
+    // Suppose tags = [ A p11 .. p1n, ..., Q pq1 .. pqm ]
+    // Build a generalized type t_tags = [ A t11 .. t1n, ..., Q tq1 .. tqm ],
+    // with fresh t1, ..., tqm, so that we can re-use the derived impl for many
+    // unions of the same tags and payloads.
+    let (union_var, union_tags) = {
+        let flex_tag_labels = tags
+            .into_iter()
+            .map(|(label, arity)| {
+                let variables_slice = VariableSubsSlice::reserve_into_subs(env.subs, arity.into());
+                for var_index in variables_slice {
+                    env.subs[var_index] = env.subs.fresh_unnamed_flex_var();
+                }
+                (label, variables_slice)
+            })
+            .collect::<Vec<_>>();
+        let union_tags = UnionTags::insert_slices_into_subs(env.subs, flex_tag_labels);
+        let tag_union_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::TagUnion(union_tags, Variable::EMPTY_TAG_UNION)),
+        );
+
+        (tag_union_var, union_tags)
+    };
+
+    // Now, an equality check for this tag union is
+    //
+    // isEq_union : t_tags, t_tags -> Bool | t11 has Eq, ..., tqm has Eq
+    // isEq_union = \union1, union2 ->
+    //   when union1 is
+    //     A x11 .. x1n ->
+    //       when union2 is
+    //         A y11 .. y1n -> Bool.isEq x11 y11 && ... && Bool.isEq x1n y1n
+    //         _ -> False
+    //     ...
+    //     Q xq1 .. xqm ->
+    //       when union2 is
+    //         Q yq1 .. yqm -> Bool.isEq xq1 yq1 && ... && Bool.isEq xqm yqm
+    //         _ -> False
+    //
+    // A union with a single tag needs no `_` branch, since its values always have the same tag.
+    let union1_sym = env.new_symbol("union1");
+    let union2_sym = env.new_symbol("union2");
+
+    let has_other_tags = union_tags.len() > 1;
+
+    let tag_pattern = |env: &mut Env<'_>, tag_name: TagName, payload_vars: &[Variable]| {
+        let payload_syms: Vec<_> = std::iter::repeat_with(|| env.unique_symbol())
+            .take(payload_vars.len())
+            .collect();
+
+        let pattern = Pattern::AppliedTag {
+            whole_var: union_var,
+            tag_name,
+            ext_var: Variable::EMPTY_TAG_UNION,
+            arguments: (payload_vars.iter())
+                .zip(payload_syms.iter())
+                .map(|(var, sym)| (*var, Loc::at_zero(Pattern::Identifier(*sym))))
+                .collect(),
+        };
+
+        (pattern, payload_syms)
+    };
+
+    let when_var = env.subs.fresh_unnamed_flex_var();
+    let branches = union_tags
+        .iter_all()
+        .map(|(tag, payloads)| {
+            // A
+            let tag_name = env.subs[tag].clone();
+            // t11 .. t1n
+            let payload_vars = env.subs.get_subs_slice(env.subs[payloads]).to_vec();
+
+            // `A x11 .. x1n` and `A y11 .. y1n` patterns
+            let (pattern1, payload1_syms) = tag_pattern(env, tag_name.clone(), &payload_vars);
+            let (pattern2, payload2_syms) = tag_pattern(env, tag_name, &payload_vars);
+
+            // Bool.isEq x11 y11 && ... && Bool.isEq x1n y1n
+            let comparisons = (payload_vars.iter())
+                .zip(payload1_syms.into_iter().zip(payload2_syms))
+                .map(|(var, (sym1, sym2))| call_is_eq(env, *var, Expr::Var(sym1), Expr::Var(sym2)))
+                .collect();
+            let (same_tag_var, same_tag_body) = all_of(env, comparisons);
+            env.unify(when_var, same_tag_var);
+
+            let mut inner_branches = vec![when_branch(pattern2, same_tag_body)];
+            if has_other_tags {
+                let (other_tag_var, other_tag_body) = bool_tag(env, "False");
+                env.unify(when_var, other_tag_var);
+
+                inner_branches.push(when_branch(Pattern::Underscore, other_tag_body));
+            }
+
+            let inner_when = Expr::When {
+                loc_cond: Box::new(Loc::at_zero(Expr::Var(union2_sym))),
+                cond_var: union_var,
+                expr_var: when_var,
+                region: Region::zero(),
+                branches: inner_branches,
+                branches_cond_var: union_var,
+                exhaustive: ExhaustiveMark::known_exhaustive(),
+            };
+
+            when_branch(pattern1, inner_when)
+        })
+        .collect();
+
+    // when union1 is ...
+    let when_expr = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Var(union1_sym))),
+        cond_var: union_var,
+        expr_var: when_var,
+        region: Region::zero(),
+        branches,
+        branches_cond_var: union_var,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    // Finally, build the closure
+    // \union1, union2 -> when union1 is ...
+    build_outer_derived_closure(
+        env,
+        fn_name,
+        union_var,
+        (
+            Pattern::Identifier(union1_sym),
+            Pattern::Identifier(union2_sym),
+        ),
+        (when_var, when_expr),
+    )
+}
+
+/// Build an `isEq` implementation for an opaque type with a derived `Eq`.
+fn is_eq_opaque(
+    env: &mut Env<'_>,
+    fn_name: Symbol,
+    opaque: Symbol,
+    type_arity: u16,
+    lambda_set_arity: u16,
+) -> (Expr, Variable) {
+    // Suppose the opaque is `@O a1 .. an := real`.
+    // Build a generalized type t_opaque = `O t1 .. tn` with fresh t1, ..., tn and a fresh `real`.
+    // `real` is unified with the actual definition of the opaque once the derived implementation
+    // is used at a concrete type, so we can re-use it for every use of the opaque.
+    let real_var = env.subs.fresh_unnamed_flex_var();
+    let opaque_var = {
+        let type_arguments: Vec<_> = std::iter::repeat_with(|| env.subs.fresh_unnamed_flex_var())
+            .take(type_arity.into())
+            .collect();
+        let lambda_set_arguments: Vec<_> =
+            std::iter::repeat_with(|| env.subs.fresh_unnamed_flex_var())
+                .take(lambda_set_arity.into())
+                .collect();
+        let alias_variables =
+            AliasVariables::insert_into_subs(env.subs, type_arguments, lambda_set_arguments);
+
+        synth_var(
+            env.subs,
+            Content::Alias(opaque, alias_variables, real_var, AliasKind::Opaque),
+        )
+    };
+
+    // Now, an equality check for this opaque is
+    //
+    // isEq_opaque : O t1 .. tn, O t1 .. tn -> Bool | real has Eq
+    // isEq_opaque = \@O x, @O y -> Bool.isEq x y
+    let x_sym = env.new_symbol("x");
+    let y_sym = env.new_symbol("y");
+
+    let unwrapped_opaque = |sym| Pattern::UnwrappedOpaque {
+        whole_var: opaque_var,
+        opaque,
+        argument: Box::new((real_var, Loc::at_zero(Pattern::Identifier(sym)))),
+        // These are only used for constraining, which derived implementations never go through.
+        specialized_def_type: Box::new(Type::Variable(real_var)),
+        type_arguments: vec![],
+        lambda_set_variables: vec![],
+    };
+
+    let (body_var, body) = call_is_eq(env, real_var, Expr::Var(x_sym), Expr::Var(y_sym));
+
+    // Finally, build the closure
+    // \@O x, @O y -> Bool.isEq x y
+    build_outer_derived_closure(
+        env,
+        fn_name,
+        opaque_var,
+        (unwrapped_opaque(x_sym), unwrapped_opaque(y_sym)),
+        (body_var, body),
+    )
+}
+
+fn when_branch(pattern: Pattern, body: Expr) -> WhenBranch {
+    WhenBranch {
+        patterns: vec![WhenBranchPattern {
+            pattern: Loc::at_zero(pattern),
+            degenerate: false,
+        }],
+        value: Loc::at_zero(body),
+        guard: None,
+        redundant: RedundantMark::known_non_redundant(),
+    }
+}
+
+/// Builds `True` or `False`.
+fn bool_tag(env: &mut Env<'_>, tag_name: &str) -> (Variable, Expr) {
+    let bool_tags = UnionTags::insert_into_subs(
+        env.subs,
+        [(TagName("False".into()), []), (TagName("True".into()), [])],
+    );
+    let bool_enum_var = synth_var(
+        env.subs,
+        Content::Structure(FlatType::TagUnion(bool_tags, Variable::EMPTY_TAG_UNION)),
+    );
+    let bool_var = synth_var(
+        env.subs,
+        Content::Alias(
+            Symbol::BOOL_BOOL,
+            AliasVariables::default(),
+            bool_enum_var,
+            AliasKind::Structural,
+        ),
+    );
+
+    let tag = Expr::Tag {
+        tag_union_var: bool_var,
+        ext_var: Variable::EMPTY_TAG_UNION,
+        name: TagName(tag_name.into()),
+        arguments: vec![],
+    };
+
+    (bool_var, tag)
+}
+
+/// Folds the comparisons up into `c1 && ... && cn`, or `True` if there are none.
+fn all_of(env: &mut Env<'_>, comparisons: Vec<(Variable, Expr)>) -> (Variable, Expr) {
+    let mut comparisons = comparisons.into_iter();
+
+    match comparisons.next() {
+        None => bool_tag(env, "True"),
+        Some(first) => comparisons.fold(first, |left, right| {
+            call_builtin(env, Symbol::BOOL_AND, left, right)
+        }),
+    }
+}
+
+/// Builds `Bool.isEq left right`, where both sides have type `var`.
+fn call_is_eq(env: &mut Env<'_>, var: Variable, left: Expr, right: Expr) -> (Variable, Expr) {
+    call_builtin(env, Symbol::BOOL_IS_EQ, (var, left), (var, right))
+}
+
+fn call_builtin(
+    env: &mut Env<'_>,
+    function: Symbol,
+    left: (Variable, Expr),
+    right: (Variable, Expr),
+) -> (Variable, Expr) {
+    let (left_var, left_expr) = left;
+    let (right_var, right_expr) = right;
+
+    // build the `function ...` function type. `function` here is `Bool.isEq` or `Bool.and`.
+    //
+    // a, a -[clos]-> Bool | a has Eq   or   Bool, Bool -[clos]-> Bool
+    let exposed_fn_var = env.import_builtin_symbol_var(function);
+
+    // (typeof left), (typeof right) -[clos]-> result
+    let this_arguments_slice = VariableSubsSlice::insert_into_subs(env.subs, [left_var, right_var]);
+    let this_clos_var = env.subs.fresh_unnamed_flex_var();
+    let this_result_var = env.subs.fresh_unnamed_flex_var();
+    let this_fn_var = synth_var(
+        env.subs,
+        Content::Structure(FlatType::Func(
+            this_arguments_slice,
+            this_clos_var,
+            this_result_var,
+        )),
+    );
+
+    //   a,             a              -[clos]-> Bool
+    // ~ (typeof left), (typeof right) -[clos]-> result
+    env.unify(exposed_fn_var, this_fn_var);
+
+    // `Bool.isEq` is an ability member, so it is resolved by the function type we just built.
+    let head = match function {
+        Symbol::BOOL_IS_EQ => Expr::AbilityMember(function, None, this_fn_var),
+        _ => Expr::Var(function),
+    };
+    let fn_data = Box::new((
+        this_fn_var,
+        Loc::at_zero(head),
+        this_clos_var,
+        this_result_var,
+    ));
+
+    let arguments = vec![
+        (left_var, Loc::at_zero(left_expr)),
+        (right_var, Loc::at_zero(right_expr)),
+    ];
+    let call = Expr::Call(fn_data, arguments, CalledVia::Space);
+
+    (this_result_var, call)
+}
+
+fn build_outer_derived_closure(
+    env: &mut Env<'_>,
+    fn_name: Symbol,
+    val_var: Variable,
+    patterns: (Pattern, Pattern),
+    body: (Variable, Expr),
+) -> (Expr, Variable) {
+    let (pattern1, pattern2) = patterns;
+    let (body_var, body_expr) = body;
+
+    let (fn_var, fn_clos_var) = {
+        // Create fn_var for ambient capture; we fix it up below.
+        let fn_var = synth_var(env.subs, Content::Error);
+
+        // -[fn_name]->
+        let fn_captures = vec![];
+        let fn_name_labels = UnionLambdas::insert_into_subs(env.subs, once((fn_name, fn_captures)));
+        let fn_clos_var = synth_var(
+            env.subs,
+            Content::LambdaSet(LambdaSet {
+                solved: fn_name_labels,
+                recursion_var: OptVariable::NONE,
+                unspecialized: SubsSlice::default(),
+                ambient_function: fn_var,
+            }),
+        );
+
+        // val_var, val_var -[fn_name]-> (Bool = body_var)
+        let fn_arguments = VariableSubsSlice::insert_into_subs(env.subs, [val_var, val_var]);
+        env.subs.set_content(
+            fn_var,
+            Content::Structure(FlatType::Func(fn_arguments, fn_clos_var, body_var)),
+        );
+
+        (fn_var, fn_clos_var)
+    };
+
+    let clos_expr = Expr::Closure(ClosureData {
+        function_type: fn_var,
+        closure_type: fn_clos_var,
+        return_type: body_var,
+        name: fn_name,
+        captured_symbols: vec![],
+        recursive: Recursive::NotRecursive,
+        arguments: vec![
+            (
+                val_var,
+                AnnotatedMark::known_exhaustive(),
+                Loc::at_zero(pattern1),
+            ),
+            (
+                val_var,
+                AnnotatedMark::known_exhaustive(),
+                Loc::at_zero(pattern2),
+            ),
+        ],
+        loc_body: Box::new(Loc::at_zero(body_expr)),
+    });
+
+    (clos_expr, fn_var)
+}
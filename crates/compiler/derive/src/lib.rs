@@ -18,6 +18,8 @@ use util::Env;
 
 mod decoding;
 mod encoding;
+mod eq;
+mod hash;
 
 mod util;
 
@@ -77,6 +79,8 @@ fn build_derived_body(
         DeriveKey::Decoder(decoder_key) => {
             decoding::derive_decoder(&mut env, decoder_key, derived_symbol)
         }
+        DeriveKey::Hash(hash_key) => hash::derive_hash(&mut env, hash_key, derived_symbol),
+        DeriveKey::IsEq(eq_key) => eq::derive_is_eq(&mut env, eq_key, derived_symbol),
     };
 
     let def = Def {
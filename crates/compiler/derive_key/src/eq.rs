@@ -0,0 +1,146 @@
+use roc_module::{
+    ident::{Lowercase, TagName},
+    symbol::{ModuleId, Symbol},
+};
+use roc_types::{
+    subs::{Content, FlatType, Subs, Variable},
+    types::AliasKind,
+};
+
+use crate::{
+    util::{check_derivable_ext_var, debug_name_record, debug_name_tag, debug_name_tuple},
+    DeriveError,
+};
+
+#[derive(Hash)]
+pub enum FlatEq {
+    /// A plain function of form `a, a -> Bool` that compares the values directly, e.g.
+    /// `Bool.structuralEq` or `List.isEqList`.
+    /// Such functions are not ability members, so they only ever have the one lambda set.
+    SingleLambdaSetImmediate(Symbol),
+    Key(FlatEqKey),
+}
+
+#[derive(Hash, PartialEq, Eq, Debug, Clone)]
+pub enum FlatEqKey {
+    // Unfortunate that we must allocate here, c'est la vie
+    Record(Vec<Lowercase>),
+    // Tuples, by arity
+    Tuple(u32),
+    TagUnion(Vec<(TagName, u16)>),
+    /// An opaque type with a derived `Eq` implementation, along with the number of its type
+    /// arguments and lambda set arguments.
+    Opaque(Symbol, u16, u16),
+}
+
+impl FlatEqKey {
+    pub(crate) fn debug_name(&self) -> String {
+        match self {
+            FlatEqKey::Record(fields) => debug_name_record(fields),
+            FlatEqKey::Tuple(arity) => debug_name_tuple(*arity),
+            FlatEqKey::TagUnion(tags) => debug_name_tag(tags),
+            FlatEqKey::Opaque(opaque, _, _) => format!("@{:?}", opaque),
+        }
+    }
+}
+
+impl FlatEq {
+    /// Finds how to compare values of the given type by calling `isEq` on each of their parts.
+    ///
+    /// Types that cannot contain a custom `isEq` implementation, like numbers and strings, are
+    /// always compared structurally. Whether any other type should be compared structurally
+    /// depends on the `Eq` implementations its parts reach, which only the caller can check.
+    pub(crate) fn from_var(subs: &Subs, var: Variable) -> Result<FlatEq, DeriveError> {
+        use DeriveError::*;
+        use FlatEq::*;
+        match *subs.get_content_without_compacting(var) {
+            Content::Structure(flat_type) => match flat_type {
+                FlatType::Apply(sym, _) => match sym {
+                    Symbol::LIST_LIST => Ok(SingleLambdaSetImmediate(Symbol::LIST_IS_EQ_LIST)),
+                    Symbol::STR_STR => Ok(SingleLambdaSetImmediate(Symbol::BOOL_STRUCTURAL_EQ)),
+                    _ => Err(Underivable),
+                },
+                FlatType::Record(fields, ext) => {
+                    let (fields_iter, ext) = fields.unsorted_iterator_and_ext(subs, ext);
+
+                    check_derivable_ext_var(subs, ext, |ext| {
+                        matches!(ext, Content::Structure(FlatType::EmptyRecord))
+                    })?;
+
+                    let mut field_names = Vec::with_capacity(fields.len());
+                    for (field_name, _) in fields_iter {
+                        field_names.push(field_name.clone());
+                    }
+
+                    field_names.sort();
+
+                    Ok(Key(FlatEqKey::Record(field_names)))
+                }
+                FlatType::Tuple(elems, ext) => {
+                    let (elems_iter, ext) = elems.unsorted_iterator_and_ext(subs, ext);
+
+                    check_derivable_ext_var(subs, ext, |ext| {
+                        matches!(ext, Content::Structure(FlatType::EmptyTuple))
+                    })?;
+
+                    Ok(Key(FlatEqKey::Tuple(elems_iter.count() as _)))
+                }
+                FlatType::TagUnion(tags, ext) | FlatType::RecursiveTagUnion(_, tags, ext) => {
+                    // As with `Hash`, the recursion var doesn't matter, because the derived
+                    // implementation only looks on the surface of the tag union type.
+                    let (tags_iter, ext) = tags.unsorted_tags_and_ext(subs, ext);
+
+                    check_derivable_ext_var(subs, ext, |ext| {
+                        matches!(ext, Content::Structure(FlatType::EmptyTagUnion))
+                    })?;
+
+                    let mut tag_names_and_payload_sizes: Vec<_> = tags_iter
+                        .tags
+                        .into_iter()
+                        .map(|(name, payload_slice)| {
+                            let payload_size = payload_slice.len();
+                            (name.clone(), payload_size as _)
+                        })
+                        .collect();
+
+                    tag_names_and_payload_sizes.sort_by(|(t1, _), (t2, _)| t1.cmp(t2));
+
+                    Ok(Key(FlatEqKey::TagUnion(tag_names_and_payload_sizes)))
+                }
+                FlatType::FunctionOrTagUnion(name_index, _, _) => Ok(Key(FlatEqKey::TagUnion(
+                    vec![(subs[name_index].clone(), 0)],
+                ))),
+                // There is nothing to compare inside of these.
+                FlatType::EmptyRecord | FlatType::EmptyTuple | FlatType::EmptyTagUnion => {
+                    Ok(SingleLambdaSetImmediate(Symbol::BOOL_STRUCTURAL_EQ))
+                }
+                //
+                FlatType::Erroneous(_) => Err(Underivable),
+                FlatType::Func(..) => Err(Underivable),
+            },
+            Content::Alias(sym, _, _, _) if sym.module_id() == ModuleId::NUM => {
+                Ok(SingleLambdaSetImmediate(Symbol::BOOL_STRUCTURAL_EQ))
+            }
+            Content::Alias(sym, alias_variables, _, AliasKind::Opaque) => {
+                // Unlike other derivers, we can't unwrap the opaque here: the derived
+                // implementation must have the type of the opaque, so that it can stand in for
+                // the opaque's own `isEq`.
+                Ok(Key(FlatEqKey::Opaque(
+                    sym,
+                    alias_variables.type_variables_len,
+                    alias_variables.all_variables_len - alias_variables.type_variables_len,
+                )))
+            }
+            Content::Alias(_, _, real_var, AliasKind::Structural) => Self::from_var(subs, real_var),
+            Content::RangedNumber(_) => Ok(SingleLambdaSetImmediate(Symbol::BOOL_STRUCTURAL_EQ)),
+            //
+            Content::RecursionVar { .. } => Err(Underivable),
+            Content::Error => Err(Underivable),
+            Content::FlexVar(_)
+            | Content::RigidVar(_)
+            | Content::FlexAbleVar(_, _)
+            | Content::RigidAbleVar(_, _) => Err(UnboundVar),
+            Content::LambdaSet(_) => Err(Underivable),
+        }
+    }
+}
@@ -2,23 +2,29 @@
 //! addressed by a key of their type content. However, different derived implementations can be
 //! reused based on different properties of the type. For example:
 //!
-//! - `Eq` does not care about surface type representations; its derived implementations can be
-//!   uniquely addressed by the [`Layout`][crate::layout::Layout] of a type.
+//! - `Eq` compares values structurally, unless they contain a type with a custom `isEq`. Then it
+//!   needs the surface shape of a type, like `Hash`, so that it can call `isEq` on each part.
 //! - `Encoding` must care about surface type representations; for example, `{ a: "" }` and
 //!   `{ b: "" }` have different derived implementations. However, it does not need to distinguish
 //!   between e.g. required and optional record fields.
 //! - `Decoding` is like encoding, but has some differences. For one, it *does* need to distinguish
 //!   between required and optional record fields.
+//! - `Hash` only needs to know the surface shape of a type, like encoding; however, it also does
+//!   not need to distinguish between required and optional record fields.
 //!
 //! For these reasons the content keying is based on a strategy as well, which are the variants of
 //! [`DeriveKey`].
 
 pub mod decoding;
 pub mod encoding;
+pub mod eq;
+pub mod hash;
 mod util;
 
 use decoding::{FlatDecodable, FlatDecodableKey};
 use encoding::{FlatEncodable, FlatEncodableKey};
+use eq::{FlatEq, FlatEqKey};
+use hash::{FlatHash, FlatHashKey};
 
 use roc_module::symbol::Symbol;
 use roc_types::subs::{Subs, Variable};
@@ -37,6 +43,8 @@ pub enum DeriveError {
 pub enum DeriveKey {
     ToEncoder(FlatEncodableKey),
     Decoder(FlatDecodableKey),
+    Hash(FlatHashKey),
+    IsEq(FlatEqKey),
 }
 
 impl DeriveKey {
@@ -44,6 +52,8 @@ impl DeriveKey {
         match self {
             DeriveKey::ToEncoder(key) => format!("toEncoder_{}", key.debug_name()),
             DeriveKey::Decoder(key) => format!("decoder_{}", key.debug_name()),
+            DeriveKey::Hash(key) => format!("hash_{}", key.debug_name()),
+            DeriveKey::IsEq(key) => format!("isEq_{}", key.debug_name()),
         }
     }
 }
@@ -52,7 +62,14 @@ impl DeriveKey {
 pub enum Derived {
     /// If a derived implementation name is well-known ahead-of-time, we can inline the symbol
     /// directly rather than associating a key for an implementation to be made later on.
+    ///
+    /// Immediates refer to ability members that are "inlined" at the derivation site.
     Immediate(Symbol),
+    /// Like an [Derived::Immediate], but with the additional constraint that the immediate
+    /// symbol is statically known to have exactly one lambda set.
+    /// Such immediates are plain functions rather than ability members, so they need no further
+    /// resolution.
+    SingleLambdaSetImmediate(Symbol),
     /// Key of the derived implementation to use. This allows association of derived implementation
     /// names to a key, when the key is known ahead-of-time but the implementation (and it's name)
     /// is yet-to-be-made.
@@ -64,6 +81,8 @@ pub enum Derived {
 pub enum DeriveBuiltin {
     ToEncoder,
     Decoder,
+    Hash,
+    IsEq,
 }
 
 impl TryFrom<Symbol> for DeriveBuiltin {
@@ -73,6 +92,8 @@ impl TryFrom<Symbol> for DeriveBuiltin {
         match value {
             Symbol::ENCODE_TO_ENCODER => Ok(DeriveBuiltin::ToEncoder),
             Symbol::DECODE_DECODER => Ok(DeriveBuiltin::Decoder),
+            Symbol::HASH_HASH => Ok(DeriveBuiltin::Hash),
+            Symbol::BOOL_IS_EQ => Ok(DeriveBuiltin::IsEq),
             _ => Err(value),
         }
     }
@@ -93,6 +114,17 @@ impl Derived {
                 FlatDecodable::Immediate(imm) => Ok(Derived::Immediate(imm)),
                 FlatDecodable::Key(repr) => Ok(Derived::Key(DeriveKey::Decoder(repr))),
             },
+            DeriveBuiltin::Hash => match hash::FlatHash::from_var(subs, var)? {
+                FlatHash::Immediate(imm) => Ok(Derived::Immediate(imm)),
+                FlatHash::SingleLambdaSetImmediate(imm) => {
+                    Ok(Derived::SingleLambdaSetImmediate(imm))
+                }
+                FlatHash::Key(repr) => Ok(Derived::Key(DeriveKey::Hash(repr))),
+            },
+            DeriveBuiltin::IsEq => match eq::FlatEq::from_var(subs, var)? {
+                FlatEq::SingleLambdaSetImmediate(imm) => Ok(Derived::SingleLambdaSetImmediate(imm)),
+                FlatEq::Key(repr) => Ok(Derived::Key(DeriveKey::IsEq(repr))),
+            },
         }
     }
 }
@@ -36,6 +36,7 @@ pub enum RenderAs {
     Tag,
     Opaque,
     Record(Vec<Lowercase>),
+    Tuple,
     Guard,
 }
 
@@ -69,6 +70,44 @@ pub enum Pattern {
     Anything,
     Literal(Literal),
     Ctor(Union, TagId, std::vec::Vec<Pattern>),
+    /// A list pattern. The patterns of a slice come before and after its rest, in order.
+    List(ListArity, std::vec::Vec<Pattern>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ListArity {
+    /// A list pattern of an exact length, e.g. `[a, b]`
+    Exact(usize),
+    /// A list pattern with a rest, and the number of patterns before and after the rest,
+    /// e.g. `[a, .., b, c]` is `Slice(1, 2)`
+    Slice(usize, usize),
+}
+
+impl ListArity {
+    /// The length of the shortest list this arity matches.
+    pub fn min_len(&self) -> usize {
+        match self {
+            ListArity::Exact(n) => *n,
+            ListArity::Slice(before, after) => before + after,
+        }
+    }
+
+    /// Does this arity match lists of the given length?
+    pub fn covers_length(&self, length: usize) -> bool {
+        match self {
+            ListArity::Exact(n) => *n == length,
+            ListArity::Slice(..) => self.min_len() <= length,
+        }
+    }
+
+    /// Does this arity match every list that `other` matches?
+    pub fn covers_arities_of(&self, other: &ListArity) -> bool {
+        match (self, other) {
+            (_, ListArity::Exact(n)) => self.covers_length(*n),
+            (ListArity::Exact(_), ListArity::Slice(..)) => false,
+            (ListArity::Slice(..), ListArity::Slice(..)) => self.min_len() <= other.min_len(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -140,6 +179,21 @@ fn is_exhaustive(matrix: &RefPatternMatrix, n: usize) -> PatternMatrix {
         vec![std::iter::repeat(Anything).take(n).collect()]
     } else if n == 0 {
         vec![]
+    } else if let Some(arities) = collect_list_arities(matrix) {
+        // All lists are covered by the list constructors that the lengths of the patterns split
+        // them into, so we check each of them.
+        split_list_arity(ListArity::Slice(0, 0), &arities)
+            .into_iter()
+            .flat_map(|ctor| {
+                let new_matrix: Vec<_> = matrix
+                    .iter()
+                    .filter_map(|row| specialize_row_by_list(ctor, row))
+                    .collect();
+                let rest = is_exhaustive(&new_matrix, ctor.min_len() + n - 1);
+
+                rest.into_iter().map(move |row| recover_list(ctor, row))
+            })
+            .collect()
     } else {
         let ctors = collect_ctors(matrix);
         let num_seen = ctors.len();
@@ -235,6 +289,15 @@ fn recover_ctor(
     rest
 }
 
+fn recover_list(ctor: ListArity, mut patterns: Vec<Pattern>) -> Vec<Pattern> {
+    let mut rest = patterns.split_off(ctor.min_len());
+    let args = patterns;
+
+    rest.push(List(ctor, args));
+
+    rest
+}
+
 /// Check if a new row "vector" is useful given previous rows "matrix"
 pub fn is_useful(mut old_matrix: PatternMatrix, mut vector: Row) -> bool {
     let mut matrix = Vec::with_capacity(old_matrix.len());
@@ -264,6 +327,62 @@ pub fn is_useful(mut old_matrix: PatternMatrix, mut vector: Row) -> bool {
                         vector.extend(args);
                     }
 
+                    List(arity, args) => {
+                        // The rows may distinguish lists that this pattern matches by their
+                        // length, so check each list constructor this pattern covers.
+                        let arities = collect_list_arities(&old_matrix).unwrap_or_default();
+
+                        for ctor in split_list_arity(arity, &arities) {
+                            let mut matrix = vec![];
+                            specialize_row_by_list2(ctor, &mut old_matrix.clone(), &mut matrix);
+
+                            let mut vector = vector.clone();
+                            vector.extend(specialize_list_args(ctor, arity, args.clone()));
+
+                            if is_useful(matrix, vector) {
+                                break 'outer true;
+                            }
+                        }
+
+                        break false;
+                    }
+
+                    Anything if collect_list_arities(&old_matrix).is_some() => {
+                        let arities = collect_list_arities(&old_matrix).unwrap();
+                        let ctors = split_list_arity(ListArity::Slice(0, 0), &arities);
+
+                        let is_complete = ctors
+                            .iter()
+                            .all(|ctor| arities.iter().any(|arity| arity.covers_arities_of(ctor)));
+
+                        if is_complete {
+                            // Every list is matched by some row, but those rows may have
+                            // subpatterns that make them less general.
+                            for ctor in ctors {
+                                let mut matrix = vec![];
+                                specialize_row_by_list2(ctor, &mut old_matrix.clone(), &mut matrix);
+
+                                let mut vector = vector.clone();
+                                vector.extend(std::iter::repeat(Anything).take(ctor.min_len()));
+
+                                if is_useful(matrix, vector) {
+                                    break 'outer true;
+                                }
+                            }
+
+                            break false;
+                        } else {
+                            // Some lists are only matched by the rows that start with Anything.
+                            for mut row in old_matrix.drain(..) {
+                                if let Some(Anything) = row.pop() {
+                                    matrix.push(row);
+                                }
+                            }
+
+                            std::mem::swap(&mut old_matrix, &mut matrix);
+                        }
+                    }
+
                     Anything => {
                         // check if all alternatives appear in matrix
                         match is_complete(&old_matrix) {
@@ -325,7 +444,7 @@ pub fn is_useful(mut old_matrix: PatternMatrix, mut vector: Row) -> bool {
                                 }
                                 Some(Anything) => matrix.push(patterns),
 
-                                Some(Ctor(_, _, _)) => panic!(
+                                Some(Ctor(_, _, _) | List(_, _)) => panic!(
                                     r#"Compiler bug! After type checking, constructors and literals should never align in pattern match exhaustiveness checks."#
                                 ),
 
@@ -366,7 +485,7 @@ fn specialize_row_by_ctor2(
             patterns.extend(std::iter::repeat(Anything).take(arity));
             matrix.push(patterns);
             }
-        Some(Literal(_)) => panic!( "Compiler bug! After type checking, constructors and literal should never align in pattern match exhaustiveness checks."),
+        Some(Literal(_) | List(_, _)) => panic!( "Compiler bug! After type checking, constructors and literal should never align in pattern match exhaustiveness checks."),
         None => panic!("Compiler error! Empty matrices should not get specialized."),
     }
     }
@@ -399,13 +518,89 @@ fn specialize_row_by_ctor(tag_id: TagId, arity: usize, row: &RefRow) -> Option<R
                 .collect();
             Some(new_patterns)
         }
-        Some(Literal(_)) => unreachable!(
+        Some(Literal(_) | List(_, _)) => unreachable!(
             r#"Compiler bug! After type checking, a constructor can never align with a literal: that should be a type error!"#
         ),
         None => panic!("Compiler error! Empty matrices should not get specialized."),
     }
 }
 
+/// INVARIANT: (length row == N) ==> (length result == ctor.min_len() + N - 1)
+fn specialize_row_by_list2(
+    ctor: ListArity,
+    old_matrix: &mut PatternMatrix,
+    matrix: &mut PatternMatrix,
+) {
+    for mut row in old_matrix.drain(..) {
+        let head = row.pop();
+        let mut patterns = row;
+
+        match head {
+            Some(List(arity, args)) => {
+                if arity.covers_arities_of(&ctor) {
+                    patterns.extend(specialize_list_args(ctor, arity, args));
+                    matrix.push(patterns);
+                } else {
+                    // do nothing
+                }
+            }
+            Some(Anything) => {
+                patterns.extend(std::iter::repeat(Anything).take(ctor.min_len()));
+                matrix.push(patterns);
+            }
+            Some(Literal(_) | Ctor(_, _, _)) => panic!("Compiler bug! After type checking, lists and other patterns should never align in pattern match exhaustiveness checks."),
+            None => panic!("Compiler error! Empty matrices should not get specialized."),
+        }
+    }
+}
+
+/// INVARIANT: (length row == N) ==> (length result == ctor.min_len() + N - 1)
+fn specialize_row_by_list(ctor: ListArity, row: &RefRow) -> Option<Row> {
+    let mut row = row.to_vec();
+
+    let head = row.pop();
+    let patterns = row;
+
+    match head {
+        Some(List(arity, args)) => {
+            if arity.covers_arities_of(&ctor) {
+                let mut new_patterns = specialize_list_args(ctor, arity, args);
+                new_patterns.extend(patterns);
+                Some(new_patterns)
+            } else {
+                None
+            }
+        }
+        Some(Anything) => {
+            let new_patterns = std::iter::repeat(Anything)
+                .take(ctor.min_len())
+                .chain(patterns)
+                .collect();
+            Some(new_patterns)
+        }
+        Some(Literal(_) | Ctor(_, _, _)) => unreachable!(
+            r#"Compiler bug! After type checking, a list can never align with other patterns: that should be a type error!"#
+        ),
+        None => panic!("Compiler error! Empty matrices should not get specialized."),
+    }
+}
+
+/// Lines up the patterns of a list pattern of `arity`, which covers `ctor`, with the elements of
+/// the lists of `ctor`. The elements matched by the rest of a slice become `Anything`.
+fn specialize_list_args(ctor: ListArity, arity: ListArity, mut args: Vec<Pattern>) -> Vec<Pattern> {
+    match arity {
+        ListArity::Exact(_) => args,
+        ListArity::Slice(before, after) => {
+            let after_args = args.split_off(before);
+
+            args.extend(std::iter::repeat(Anything).take(ctor.min_len() - before - after));
+            args.extend(after_args);
+
+            args
+        }
+    }
+}
+
 /// INVARIANT: (length row == N) ==> (length result == N-1)
 fn specialize_row_by_anything(row: &RefRow) -> Option<Row> {
     let mut row = row.to_vec();
@@ -447,6 +642,60 @@ type PatternMatrix = Vec<Vec<Pattern>>;
 type RefRow = [Pattern];
 type Row = Vec<Pattern>;
 
+/// The arities of the list patterns at the head of the rows, if there are any.
+fn collect_list_arities(matrix: &RefPatternMatrix) -> Option<Vec<ListArity>> {
+    let arities: Vec<_> = matrix
+        .iter()
+        .filter_map(|row| match row.last() {
+            Some(List(arity, _)) => Some(*arity),
+            _ => None,
+        })
+        .collect();
+
+    if arities.is_empty() {
+        None
+    } else {
+        Some(arities)
+    }
+}
+
+/// Splits the lists matched by `arity` into list constructors, such that each of `arities`
+/// either matches all lists of a constructor or none of them.
+///
+/// Lists at least as long as the longest exact arity and the longest slice are all matched by
+/// the same arities, so they make up a single slice constructor; shorter lists each get an
+/// exact constructor.
+pub fn split_list_arity(arity: ListArity, arities: &[ListArity]) -> Vec<ListArity> {
+    let (mut before, mut after) = match arity {
+        ListArity::Exact(_) => return vec![arity],
+        ListArity::Slice(before, after) => (before, after),
+    };
+
+    let min_len = arity.min_len();
+    let mut max_exact = None;
+
+    for other in arities {
+        match other {
+            ListArity::Exact(n) => max_exact = max_exact.max(Some(*n)),
+            ListArity::Slice(other_before, other_after) => {
+                before = before.max(*other_before);
+                after = after.max(*other_after);
+            }
+        }
+    }
+
+    if let Some(max_exact) = max_exact {
+        if max_exact >= before + after {
+            before = max_exact + 1 - after;
+        }
+    }
+
+    let mut ctors: Vec<_> = (min_len..before + after).map(ListArity::Exact).collect();
+    ctors.push(ListArity::Slice(before, after));
+
+    ctors
+}
+
 fn collect_ctors(matrix: &RefPatternMatrix) -> MutMap<TagId, Union> {
     let mut ctors = MutMap::default();
 
@@ -185,6 +185,15 @@ impl<'a> Formattable for TypeAnnotation<'a> {
                 fields.items.iter().any(|field| field.value.is_multiline())
             }
 
+            Tuple { elems, ext } => {
+                match ext {
+                    Some(ann) if ann.value.is_multiline() => return true,
+                    _ => {}
+                }
+
+                elems.iter().any(|elem| elem.value.is_multiline())
+            }
+
             TagUnion { tags, ext } => {
                 match ext {
                     Some(ann) if ann.value.is_multiline() => return true,
@@ -307,6 +316,14 @@ impl<'a> Formattable for TypeAnnotation<'a> {
                 }
             }
 
+            Tuple { elems, ext } => {
+                fmt_collection(buf, indent, Braces::Round, *elems, newlines);
+
+                if let Some(loc_ext_ann) = *ext {
+                    loc_ext_ann.value.format(buf, indent);
+                }
+            }
+
             As(lhs, _spaces, TypeHeader { name, vars }) => {
                 // TODO use _spaces?
                 lhs.value
@@ -405,12 +422,13 @@ fn is_multiline_assigned_field_help<T: Formattable>(afield: &AssignedField<'_, T
             !spaces.is_empty() || ann.value.is_multiline()
         }
         LabelOnly(_) => false,
+        Nested(_, sub_field) => is_multiline_assigned_field_help(sub_field),
         AssignedField::SpaceBefore(_, _) | AssignedField::SpaceAfter(_, _) => true,
         Malformed(text) => text.chars().any(|c| c == '\n'),
     }
 }
 
-fn format_assigned_field_help<'a, 'buf, T>(
+pub(crate) fn format_assigned_field_help<'a, 'buf, T>(
     zelf: &AssignedField<'a, T>,
     buf: &mut Buf<'buf>,
     parens: Parens,
@@ -464,6 +482,18 @@ fn format_assigned_field_help<'a, 'buf, T>(
 
             buf.push_str(name.value);
         }
+        Nested(label, sub_field) => {
+            if is_multiline {
+                buf.newline();
+            }
+
+            buf.indent(indent);
+            buf.push_str(label.value);
+            buf.push('.');
+
+            // the rest of the path goes on the same line
+            format_assigned_field_help(sub_field, buf, parens, indent, separator_spaces, false);
+        }
         AssignedField::SpaceBefore(sub_field, spaces) => {
             fmt_comments_only(buf, spaces.iter(), NewlineAt::Bottom, indent);
             format_assigned_field_help(
@@ -160,6 +160,7 @@ impl<'a> Formattable for ValueDef<'a> {
             AnnotatedBody { .. } => true,
             Expect { condition, .. } => condition.is_multiline(),
             ExpectFx { condition, .. } => condition.is_multiline(),
+            Malformed(source) => source.contains('\n'),
         }
     }
 
@@ -180,13 +181,17 @@ impl<'a> Formattable for ValueDef<'a> {
 
                     let should_outdent = match loc_annotation.value {
                         TypeAnnotation::SpaceBefore(sub_def, spaces) => match sub_def {
-                            TypeAnnotation::Record { .. } | TypeAnnotation::TagUnion { .. } => {
+                            TypeAnnotation::Record { .. }
+                            | TypeAnnotation::Tuple { .. }
+                            | TypeAnnotation::TagUnion { .. } => {
                                 let is_only_newlines = spaces.iter().all(|s| s.is_newline());
                                 is_only_newlines && sub_def.is_multiline()
                             }
                             _ => false,
                         },
-                        TypeAnnotation::Record { .. } | TypeAnnotation::TagUnion { .. } => true,
+                        TypeAnnotation::Record { .. }
+                        | TypeAnnotation::Tuple { .. }
+                        | TypeAnnotation::TagUnion { .. } => true,
                         _ => false,
                     };
 
@@ -237,6 +242,11 @@ impl<'a> Formattable for ValueDef<'a> {
             ExpectFx { condition, .. } => {
                 fmt_expect_fx(buf, condition, self.is_multiline(), indent)
             }
+            Malformed(source) => {
+                // we can't format what we couldn't parse, so keep it as it was
+                buf.indent(indent);
+                buf.push_str_allow_spaces(source);
+            }
             AnnotatedBody {
                 ann_pattern,
                 ann_type,
@@ -357,7 +367,7 @@ pub fn fmt_body<'a, 'buf>(
         match body {
             Expr::SpaceBefore(sub_def, spaces) => {
                 let should_outdent = match sub_def {
-                    Expr::Record { .. } | Expr::List { .. } => {
+                    Expr::Record { .. } | Expr::Tuple { .. } | Expr::List { .. } => {
                         let is_only_newlines = spaces.iter().all(|s| s.is_newline());
                         is_only_newlines && sub_def.is_multiline()
                     }
@@ -1,4 +1,4 @@
-use crate::annotation::{Formattable, Newlines, Parens};
+use crate::annotation::{format_assigned_field_help, Formattable, Newlines, Parens};
 use crate::collection::{fmt_collection, Braces};
 use crate::def::fmt_defs;
 use crate::pattern::fmt_pattern;
@@ -38,6 +38,7 @@ impl<'a> Formattable for Expr<'a> {
             | AccessorFunction(_)
             | Var { .. }
             | Underscore { .. }
+            | Crash
             | MalformedIdent(_, _)
             | MalformedClosure
             | Tag(_)
@@ -46,7 +47,10 @@ impl<'a> Formattable for Expr<'a> {
             // These expressions always have newlines
             Defs(_, _) | When(_, _) => true,
 
+            Malformed(source) => source.contains('\n'),
+
             List(items) => items.iter().any(|loc_expr| loc_expr.is_multiline()),
+            Tuple(elems) => elems.iter().any(|loc_expr| loc_expr.is_multiline()),
 
             Str(literal) => {
                 use roc_parse::ast::StrLiteral::*;
@@ -59,6 +63,11 @@ impl<'a> Formattable for Expr<'a> {
                     Block(lines) => {
                         // Block strings don't *have* to be multiline!
                         lines.len() > 1
+                            || lines.iter().any(|segments| {
+                                segments.iter().any(|segment| {
+                                    matches!(segment, StrSegment::Plaintext(string) if string.contains('\n'))
+                                })
+                            })
                     }
                 }
             }
@@ -69,6 +78,7 @@ impl<'a> Formattable for Expr<'a> {
             Expect(condition, continuation) => {
                 condition.is_multiline() || continuation.is_multiline()
             }
+            Dbg(loc_expr) => loc_expr.is_multiline(),
 
             If(branches, final_else) => {
                 final_else.is_multiline()
@@ -187,6 +197,10 @@ impl<'a> Formattable for Expr<'a> {
                 buf.push('_');
                 buf.push_str(name);
             }
+            Crash => {
+                buf.indent(indent);
+                buf.push_str("crash");
+            }
             Apply(loc_expr, loc_args, _) => {
                 buf.indent(indent);
                 if apply_needs_parens && !loc_args.is_empty() {
@@ -204,7 +218,7 @@ impl<'a> Formattable for Expr<'a> {
                     if iter.peek().is_none() {
                         found_multiline_expr = match loc_arg.value {
                             SpaceBefore(sub_expr, spaces) => match sub_expr {
-                                Record { .. } | List { .. } => {
+                                Record { .. } | Tuple { .. } | List { .. } => {
                                     let is_only_newlines = spaces.iter().all(|s| s.is_newline());
                                     is_only_newlines
                                         && !found_multiline_expr
@@ -212,7 +226,7 @@ impl<'a> Formattable for Expr<'a> {
                                 }
                                 _ => false,
                             },
-                            Record { .. } | List { .. } | Closure { .. } => {
+                            Record { .. } | Tuple { .. } | List { .. } | Closure { .. } => {
                                 !found_multiline_expr && loc_arg.is_multiline()
                             }
                             _ => false,
@@ -367,11 +381,15 @@ impl<'a> Formattable for Expr<'a> {
             Expect(condition, continuation) => {
                 fmt_expect(buf, condition, continuation, self.is_multiline(), indent);
             }
+            Dbg(loc_expr) => {
+                fmt_dbg(buf, loc_expr, self.is_multiline(), indent);
+            }
             If(branches, final_else) => {
                 fmt_if(buf, branches, final_else, self.is_multiline(), indent);
             }
             When(loc_condition, branches) => fmt_when(buf, loc_condition, branches, indent),
             List(items) => fmt_collection(buf, indent, Braces::Square, *items, Newlines::No),
+            Tuple(elems) => fmt_collection(buf, indent, Braces::Round, *elems, Newlines::No),
             BinOps(lefts, right) => fmt_binops(buf, lefts, right, false, parens, indent),
             UnaryOp(sub_expr, unary_op) => {
                 buf.indent(indent);
@@ -398,6 +416,11 @@ impl<'a> Formattable for Expr<'a> {
             }
             MalformedIdent(_, _) => {}
             MalformedClosure => {}
+            Malformed(source) => {
+                // we can't format what we couldn't parse, so keep it as it was
+                buf.indent(indent);
+                buf.push_str_allow_spaces(source);
+            }
             PrecedenceConflict { .. } => {}
         }
     }
@@ -505,18 +528,34 @@ pub fn fmt_str_literal<'buf>(buf: &mut Buf<'buf>, literal: StrLiteral, indent: u
             }
         }
         Block(lines) => {
-            // Block strings will always be formatted with """ on new lines
+            // Block strings are kept the way they were written, so that the indentation
+            // of their lines (which gets stripped from the string) doesn't change.
             buf.push_str("\"\"");
-            buf.newline();
 
             for segments in lines.iter() {
                 for seg in segments.iter() {
-                    buf.indent(indent);
-                    format_str_segment(seg, buf, indent);
-                }
+                    match seg {
+                        StrSegment::Plaintext(string) => {
+                            for (index, line) in string.split('\n').enumerate() {
+                                if index > 0 {
+                                    buf.newline();
+                                }
 
-                buf.newline();
+                                // Don't leave trailing spaces on blank lines
+                                if !line.is_empty() {
+                                    buf.indent(indent);
+                                    buf.push_str_allow_spaces(line);
+                                }
+                            }
+                        }
+                        _ => {
+                            buf.indent(indent);
+                            format_str_segment(seg, buf, indent);
+                        }
+                    }
+                }
             }
+
             buf.indent(indent);
             buf.push_str("\"\"");
         }
@@ -839,6 +878,26 @@ fn fmt_expect<'a, 'buf>(
     continuation.format(buf, indent);
 }
 
+fn fmt_dbg<'a, 'buf>(
+    buf: &mut Buf<'buf>,
+    loc_expr: &'a Loc<Expr<'a>>,
+    is_multiline: bool,
+    indent: u16,
+) {
+    buf.indent(indent);
+    buf.push_str("dbg");
+
+    let return_indent = if is_multiline {
+        buf.newline();
+        indent + INDENT
+    } else {
+        buf.spaces(1);
+        indent
+    };
+
+    loc_expr.format(buf, return_indent);
+}
+
 fn fmt_if<'a, 'buf>(
     buf: &mut Buf<'buf>,
     branches: &'a [(Loc<Expr<'a>>, Loc<Expr<'a>>)],
@@ -1063,7 +1122,7 @@ fn fmt_closure<'a, 'buf>(
         match &loc_ret.value {
             SpaceBefore(sub_expr, spaces) => {
                 let should_outdent = match sub_expr {
-                    Record { .. } | List { .. } => {
+                    Record { .. } | Tuple { .. } | List { .. } => {
                         let is_only_newlines = spaces.iter().all(|s| s.is_newline());
                         is_only_newlines && sub_expr.is_multiline()
                     }
@@ -1077,7 +1136,7 @@ fn fmt_closure<'a, 'buf>(
                     loc_ret.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, body_indent);
                 }
             }
-            Record { .. } | List { .. } => {
+            Record { .. } | Tuple { .. } | List { .. } => {
                 loc_ret.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
             }
             _ => {
@@ -1322,6 +1381,13 @@ fn format_field_multiline<'a, 'buf, T>(
             buf.push_str(name.value);
             buf.push(',');
         }
+        Nested(_, _) => {
+            // a path like `settings.theme: Dark` goes on one line
+            buf.newline();
+            buf.indent(indent);
+            format_assigned_field_help(field, buf, Parens::NotNeeded, indent, 0, false);
+            buf.push(',');
+        }
         AssignedField::SpaceBefore(sub_field, _spaces) => {
             // We have something like that:
             // ```
@@ -24,6 +24,7 @@ impl<'a> Formattable for Pattern<'a> {
             }
 
             Pattern::RecordDestructure(fields) => fields.iter().any(|f| f.is_multiline()),
+            Pattern::Tuple(elems) | Pattern::List(elems) => elems.iter().any(|e| e.is_multiline()),
             Pattern::RequiredField(_, subpattern) => subpattern.is_multiline(),
 
             Pattern::OptionalField(_, expr) => expr.is_multiline(),
@@ -38,6 +39,7 @@ impl<'a> Formattable for Pattern<'a> {
             | Pattern::StrLiteral(_)
             | Pattern::SingleQuote(_)
             | Pattern::Underscore(_)
+            | Pattern::ListRest(_)
             | Pattern::Malformed(_)
             | Pattern::MalformedIdent(_, _)
             | Pattern::QualifiedIdentifier { .. } => false,
@@ -104,6 +106,49 @@ impl<'a> Formattable for Pattern<'a> {
                 buf.push_str("}");
             }
 
+            Tuple(loc_patterns) => {
+                buf.indent(indent);
+                buf.push_str("(");
+
+                let mut it = loc_patterns.iter().peekable();
+                while let Some(loc_pattern) = it.next() {
+                    loc_pattern.format(buf, indent);
+
+                    if it.peek().is_some() {
+                        buf.push_str(",");
+                        buf.spaces(1);
+                    }
+                }
+
+                buf.push_str(")");
+            }
+
+            List(loc_patterns) => {
+                buf.indent(indent);
+                buf.push_str("[");
+
+                let mut it = loc_patterns.iter().peekable();
+                while let Some(loc_pattern) = it.next() {
+                    loc_pattern.format(buf, indent);
+
+                    if it.peek().is_some() {
+                        buf.push_str(",");
+                        buf.spaces(1);
+                    }
+                }
+
+                buf.push_str("]");
+            }
+
+            ListRest(opt_name) => {
+                buf.indent(indent);
+                buf.push_str("..");
+
+                if let Some(name) = opt_name {
+                    buf.push_str(name);
+                }
+            }
+
             RequiredField(name, loc_pattern) => {
                 buf.indent(indent);
                 buf.push_str(name);
@@ -554,6 +554,7 @@ impl<'a> RemoveSpaces<'a> for ValueDef<'a> {
                 condition: arena.alloc(condition.remove_spaces(arena)),
                 preceding_comment: Region::zero(),
             },
+            Malformed(a) => Malformed(a),
         }
     }
 }
@@ -597,6 +598,9 @@ impl<'a, T: RemoveSpaces<'a> + Copy + std::fmt::Debug> RemoveSpaces<'a> for Assi
                 arena.alloc(c.remove_spaces(arena)),
             ),
             AssignedField::LabelOnly(a) => AssignedField::LabelOnly(a.remove_spaces(arena)),
+            AssignedField::Nested(a, b) => {
+                AssignedField::Nested(a.remove_spaces(arena), arena.alloc(b.remove_spaces(arena)))
+            }
             AssignedField::Malformed(a) => AssignedField::Malformed(a),
             AssignedField::SpaceBefore(a, _) => a.remove_spaces(arena),
             AssignedField::SpaceAfter(a, _) => a.remove_spaces(arena),
@@ -648,8 +652,10 @@ impl<'a> RemoveSpaces<'a> for Expr<'a> {
                 fields: fields.remove_spaces(arena),
             },
             Expr::Record(a) => Expr::Record(a.remove_spaces(arena)),
+            Expr::Tuple(a) => Expr::Tuple(a.remove_spaces(arena)),
             Expr::Var { module_name, ident } => Expr::Var { module_name, ident },
             Expr::Underscore(a) => Expr::Underscore(a),
+            Expr::Crash => Expr::Crash,
             Expr::Tag(a) => Expr::Tag(a),
             Expr::OpaqueRef(a) => Expr::OpaqueRef(a),
             Expr::Closure(a, b) => Expr::Closure(
@@ -682,6 +688,7 @@ impl<'a> RemoveSpaces<'a> for Expr<'a> {
                 arena.alloc(a.remove_spaces(arena)),
                 arena.alloc(b.remove_spaces(arena)),
             ),
+            Expr::Dbg(a) => Expr::Dbg(arena.alloc(a.remove_spaces(arena))),
             Expr::Apply(a, b, c) => Expr::Apply(
                 arena.alloc(a.remove_spaces(arena)),
                 b.remove_spaces(arena),
@@ -703,6 +710,7 @@ impl<'a> RemoveSpaces<'a> for Expr<'a> {
             }
             Expr::MalformedIdent(a, b) => Expr::MalformedIdent(a, b),
             Expr::MalformedClosure => Expr::MalformedClosure,
+            Expr::Malformed(a) => Expr::Malformed(a),
             Expr::PrecedenceConflict(a) => Expr::PrecedenceConflict(a),
             Expr::SpaceBefore(a, _) => a.remove_spaces(arena),
             Expr::SpaceAfter(a, _) => a.remove_spaces(arena),
@@ -722,6 +730,9 @@ impl<'a> RemoveSpaces<'a> for Pattern<'a> {
                 arena.alloc(b.remove_spaces(arena)),
             ),
             Pattern::RecordDestructure(a) => Pattern::RecordDestructure(a.remove_spaces(arena)),
+            Pattern::Tuple(a) => Pattern::Tuple(a.remove_spaces(arena)),
+            Pattern::List(a) => Pattern::List(a.remove_spaces(arena)),
+            Pattern::ListRest(a) => Pattern::ListRest(a),
             Pattern::RequiredField(a, b) => {
                 Pattern::RequiredField(a, arena.alloc(b.remove_spaces(arena)))
             }
@@ -774,6 +785,10 @@ impl<'a> RemoveSpaces<'a> for TypeAnnotation<'a> {
                 fields: fields.remove_spaces(arena),
                 ext: ext.remove_spaces(arena),
             },
+            TypeAnnotation::Tuple { elems, ext } => TypeAnnotation::Tuple {
+                elems: elems.remove_spaces(arena),
+                ext: ext.remove_spaces(arena),
+            },
             TypeAnnotation::TagUnion { ext, tags } => TypeAnnotation::TagUnion {
                 ext: ext.remove_spaces(arena),
                 tags: tags.remove_spaces(arena),
@@ -1110,19 +1110,11 @@ mod test_fmt {
 
     #[test]
     fn oneline_empty_block_string() {
-        expr_formats_to(
-            indoc!(
-                r#"
-                """"""
-                "#
-            ),
-            indoc!(
-                r#"
-                """
-                """
-                "#
-            ),
-        );
+        expr_formats_same(indoc!(
+            r#"
+            """"""
+            "#
+        ));
     }
 
     #[test]
@@ -1143,44 +1135,23 @@ mod test_fmt {
 
     #[test]
     fn multiline_basic_block_string() {
-        expr_formats_to(
-            indoc!(
-                r#"
-                """griffin
-                harpy"""
-                "#
-            ),
-            indoc!(
-                r#"
-                """
-                griffin
-                harpy
-                """
-                "#
-            ),
-        );
+        expr_formats_same(indoc!(
+            r#"
+            """griffin
+            harpy"""
+            "#
+        ));
     }
 
     #[test]
     fn newlines_block_string() {
-        expr_formats_to(
-            indoc!(
-                r#"
-                """griffin
-                        harpy
-                phoenix"""
-                "#
-            ),
-            indoc!(
-                r#"
-                """
-                griffin
-                        harpy
-                phoenix
-                """
-                "#
-            ),
-        );
+        expr_formats_same(indoc!(
+            r#"
+            """griffin
+                    harpy
+            phoenix"""
+            "#
+        ));
     }
 
     #[test]
@@ -1207,6 +1178,46 @@ mod test_fmt {
         ));
     }
 
+    #[test]
+    fn indented_block_string() {
+        expr_formats_same(indoc!(
+            r#"
+            """
+                SELECT name
+                  FROM users
+                 WHERE id = \(id)
+            """
+            "#
+        ));
+    }
+
+    #[test]
+    fn less_indented_block_string_closing() {
+        expr_formats_same(indoc!(
+            r#"
+            """
+                griffin
+                  harpy
+              """
+            "#
+        ));
+    }
+
+    #[test]
+    fn block_string_in_def() {
+        expr_formats_same(indoc!(
+            r#"
+            html = """
+                <p>
+                  hello
+                </p>
+                """
+
+            html
+            "#
+        ));
+    }
+
     #[test]
     fn zero() {
         expr_formats_same(indoc!(
@@ -1528,6 +1539,77 @@ mod test_fmt {
         ));
     }
 
+    #[test]
+    fn tuple_destructuring() {
+        expr_formats_same(indoc!(
+            r#"
+            (x, y) = pair
+
+            when foo is
+                (0, _) -> 1
+                (_, b) -> b
+            "#
+        ));
+    }
+
+    #[test]
+    fn tuple_expr() {
+        expr_formats_to("( 1,2 , \"a\" )", "(1, 2, \"a\")");
+        expr_formats_same("(x, y).0");
+
+        expr_formats_same(indoc!(
+            r#"
+            (
+                1,
+                # a comment
+                2,
+            )
+            "#
+        ));
+    }
+
+    #[test]
+    fn tuple_type() {
+        expr_formats_same(indoc!(
+            r#"
+            swap : (a, b) -> (b, a)
+            swap = \(x, y) -> (y, x)
+
+            swap
+            "#
+        ));
+    }
+
+    #[test]
+    fn list_patterns() {
+        expr_formats_same(indoc!(
+            r#"
+            when list is
+                [] -> 0
+                [x] -> x
+                [first, .., last] -> first + last
+                [a, b, ..rest] -> a + b + List.len rest
+            "#
+        ));
+
+        expr_formats_to(
+            indoc!(
+                r#"
+                when list is
+                    [ x , ..  ] -> x
+                    _ -> 0
+                "#
+            ),
+            indoc!(
+                r#"
+                when list is
+                    [x, ..] -> x
+                    _ -> 0
+                "#
+            ),
+        );
+    }
+
     #[test]
     fn record_field_destructuring() {
         expr_formats_same(indoc!(
@@ -1987,6 +2069,53 @@ mod test_fmt {
         );
     }
 
+    #[test]
+    fn nested_record_updating() {
+        expr_formats_same(indoc!(
+            r#"
+            { model & settings.theme.color: Red }
+            "#
+        ));
+
+        expr_formats_to(
+            indoc!(
+                r#"
+                {  model &  settings.theme : Dark,count:0 }
+                "#
+            ),
+            indoc!(
+                r#"
+                { model & settings.theme: Dark, count: 0 }
+                "#
+            ),
+        );
+
+        expr_formats_same(indoc!(
+            r#"
+            { model &
+                settings.theme.color: Red,
+                settings.size: 14,
+            }
+            "#
+        ));
+
+        expr_formats_same(indoc!(
+            r#"
+            { model & settings.theme, count: 0 }
+            "#
+        ));
+
+        expr_formats_same(indoc!(
+            r#"
+            { model &
+                # the pun
+                settings.theme,
+                count: 0,
+            }
+            "#
+        ));
+    }
+
     #[test]
     fn final_comments_in_records() {
         expr_formats_same(indoc!(
@@ -5524,6 +5653,34 @@ mod test_fmt {
         ));
     }
 
+    #[test]
+    fn dbg_expr() {
+        expr_formats_to("dbg   (x + 1)", "dbg (x + 1)");
+        expr_formats_same("List.map list \\x -> dbg x");
+
+        expr_formats_same(indoc!(
+            r#"
+            dbg
+                foo bar
+                |> baz
+            "#
+        ));
+    }
+
+    #[test]
+    fn crash_expr() {
+        expr_formats_to("crash   \"oh no\"", "crash \"oh no\"");
+        expr_formats_same("if x > 0 then x else crash \"x must be positive\"");
+
+        expr_formats_same(indoc!(
+            r#"
+            when result is
+                Ok x -> x
+                Err _ -> crash "unreachable"
+            "#
+        ));
+    }
+
     #[test]
     fn single_line_string_literal_in_pattern() {
         expr_formats_same(indoc!(
@@ -5548,23 +5705,15 @@ mod test_fmt {
     }
 
     #[test]
-    fn multi_line_string_literal_that_can_be_single_line_in_pattern() {
-        expr_formats_to(
-            indoc!(
-                r#"
-                when foo is
-                    """
-                    abc
-                    """ -> ""
-                "#
-            ),
-            indoc!(
-                r#"
-                when foo is
-                    "abc" -> ""
-                "#
-            ),
-        );
+    fn single_line_block_string_in_pattern() {
+        expr_formats_same(indoc!(
+            r#"
+            when foo is
+                """
+                abc
+                """ -> ""
+            "#
+        ));
     }
 
     // this is a parse error atm
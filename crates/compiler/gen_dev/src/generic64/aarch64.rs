@@ -1,10 +1,17 @@
 use crate::generic64::{storage::StorageManager, Assembler, CallConv, RegTrait};
-use crate::Relocation;
+use crate::{
+    single_register_floats, single_register_int_builtins, single_register_integers,
+    single_register_layouts, Relocation,
+};
 use bumpalo::collections::Vec;
 use packed_struct::prelude::*;
+use roc_builtins::bitcode::{FloatWidth, IntWidth};
 use roc_error_macros::internal_error;
 use roc_module::symbol::Symbol;
-use roc_mono::layout::Layout;
+use roc_mono::layout::{Builtin, Layout, STLayoutInterner};
+use roc_target::TargetInfo;
+
+const TARGET_INFO: TargetInfo = TargetInfo::default_aarch64();
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
 #[allow(dead_code)]
@@ -101,7 +108,40 @@ impl AArch64GeneralReg {
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
 #[allow(dead_code)]
-pub enum AArch64FloatReg {}
+pub enum AArch64FloatReg {
+    V0 = 0,
+    V1 = 1,
+    V2 = 2,
+    V3 = 3,
+    V4 = 4,
+    V5 = 5,
+    V6 = 6,
+    V7 = 7,
+    V8 = 8,
+    V9 = 9,
+    V10 = 10,
+    V11 = 11,
+    V12 = 12,
+    V13 = 13,
+    V14 = 14,
+    V15 = 15,
+    V16 = 16,
+    V17 = 17,
+    V18 = 18,
+    V19 = 19,
+    V20 = 20,
+    V21 = 21,
+    V22 = 22,
+    V23 = 23,
+    V24 = 24,
+    V25 = 25,
+    V26 = 26,
+    V27 = 27,
+    V28 = 28,
+    V29 = 29,
+    V30 = 30,
+    V31 = 31,
+}
 impl RegTrait for AArch64FloatReg {
     fn value(&self) -> u8 {
         *self as u8
@@ -109,7 +149,51 @@ impl RegTrait for AArch64FloatReg {
 }
 impl std::fmt::Display for AArch64FloatReg {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "TODO",)
+        write!(
+            f,
+            "{}",
+            match self {
+                AArch64FloatReg::V0 => "v0",
+                AArch64FloatReg::V1 => "v1",
+                AArch64FloatReg::V2 => "v2",
+                AArch64FloatReg::V3 => "v3",
+                AArch64FloatReg::V4 => "v4",
+                AArch64FloatReg::V5 => "v5",
+                AArch64FloatReg::V6 => "v6",
+                AArch64FloatReg::V7 => "v7",
+                AArch64FloatReg::V8 => "v8",
+                AArch64FloatReg::V9 => "v9",
+                AArch64FloatReg::V10 => "v10",
+                AArch64FloatReg::V11 => "v11",
+                AArch64FloatReg::V12 => "v12",
+                AArch64FloatReg::V13 => "v13",
+                AArch64FloatReg::V14 => "v14",
+                AArch64FloatReg::V15 => "v15",
+                AArch64FloatReg::V16 => "v16",
+                AArch64FloatReg::V17 => "v17",
+                AArch64FloatReg::V18 => "v18",
+                AArch64FloatReg::V19 => "v19",
+                AArch64FloatReg::V20 => "v20",
+                AArch64FloatReg::V21 => "v21",
+                AArch64FloatReg::V22 => "v22",
+                AArch64FloatReg::V23 => "v23",
+                AArch64FloatReg::V24 => "v24",
+                AArch64FloatReg::V25 => "v25",
+                AArch64FloatReg::V26 => "v26",
+                AArch64FloatReg::V27 => "v27",
+                AArch64FloatReg::V28 => "v28",
+                AArch64FloatReg::V29 => "v29",
+                AArch64FloatReg::V30 => "v30",
+                AArch64FloatReg::V31 => "v31",
+            }
+        )
+    }
+}
+
+impl AArch64FloatReg {
+    #[inline(always)]
+    fn id(&self) -> u8 {
+        *self as u8
     }
 }
 
@@ -123,6 +207,14 @@ pub struct AArch64Call {}
 
 const STACK_ALIGNMENT: u8 = 16;
 
+// IP0 and IP1 are never given out by the storage manager.
+// The assembler uses IP0 to build immediates and addresses that don't fit in an instruction.
+// The calling convention uses IP1 as a buffer when copying args and return values around.
+const TMP_REG: AArch64GeneralReg = AArch64GeneralReg::IP0;
+const ARG_BUFFER_REG: AArch64GeneralReg = AArch64GeneralReg::IP1;
+// All caller saved float regs are already free when storing args for a call.
+const FLOAT_ARG_BUFFER_REG: AArch64FloatReg = AArch64FloatReg::V31;
+
 impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64Call {
     const BASE_PTR_REG: AArch64GeneralReg = AArch64GeneralReg::FP;
     const STACK_PTR_REG: AArch64GeneralReg = AArch64GeneralReg::ZRSP;
@@ -147,6 +239,7 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         // Don't use platform register: AArch64GeneralReg::PR,
         // Don't use link register: AArch64GeneralReg::LR,
         // Don't use zero register/stack pointer: AArch64GeneralReg::ZRSP,
+        // Don't use scratch registers: AArch64GeneralReg::IP0, AArch64GeneralReg::IP1,
 
         // Use callee saved regs last.
         AArch64GeneralReg::X19,
@@ -175,12 +268,57 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         AArch64GeneralReg::X13,
         AArch64GeneralReg::X14,
         AArch64GeneralReg::X15,
-        AArch64GeneralReg::IP0,
-        AArch64GeneralReg::IP1,
     ];
-    const FLOAT_PARAM_REGS: &'static [AArch64FloatReg] = &[];
+    const FLOAT_PARAM_REGS: &'static [AArch64FloatReg] = &[
+        AArch64FloatReg::V0,
+        AArch64FloatReg::V1,
+        AArch64FloatReg::V2,
+        AArch64FloatReg::V3,
+        AArch64FloatReg::V4,
+        AArch64FloatReg::V5,
+        AArch64FloatReg::V6,
+        AArch64FloatReg::V7,
+    ];
     const FLOAT_RETURN_REGS: &'static [AArch64FloatReg] = Self::FLOAT_PARAM_REGS;
-    const FLOAT_DEFAULT_FREE_REGS: &'static [AArch64FloatReg] = &[];
+    const FLOAT_DEFAULT_FREE_REGS: &'static [AArch64FloatReg] = &[
+        // The regs we want to use first should be at the end of this vec.
+        // We will use pop to get which reg to use next
+
+        // Use callee saved regs last.
+        AArch64FloatReg::V8,
+        AArch64FloatReg::V9,
+        AArch64FloatReg::V10,
+        AArch64FloatReg::V11,
+        AArch64FloatReg::V12,
+        AArch64FloatReg::V13,
+        AArch64FloatReg::V14,
+        AArch64FloatReg::V15,
+        // Use caller saved regs first.
+        AArch64FloatReg::V16,
+        AArch64FloatReg::V17,
+        AArch64FloatReg::V18,
+        AArch64FloatReg::V19,
+        AArch64FloatReg::V20,
+        AArch64FloatReg::V21,
+        AArch64FloatReg::V22,
+        AArch64FloatReg::V23,
+        AArch64FloatReg::V24,
+        AArch64FloatReg::V25,
+        AArch64FloatReg::V26,
+        AArch64FloatReg::V27,
+        AArch64FloatReg::V28,
+        AArch64FloatReg::V29,
+        AArch64FloatReg::V30,
+        AArch64FloatReg::V31,
+        AArch64FloatReg::V0,
+        AArch64FloatReg::V1,
+        AArch64FloatReg::V2,
+        AArch64FloatReg::V3,
+        AArch64FloatReg::V4,
+        AArch64FloatReg::V5,
+        AArch64FloatReg::V6,
+        AArch64FloatReg::V7,
+    ];
 
     const SHADOW_SPACE_SIZE: u8 = 0;
 
@@ -201,8 +339,19 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         )
     }
     #[inline(always)]
-    fn float_callee_saved(_reg: &AArch64FloatReg) -> bool {
-        todo!("AArch64 FloatRegs");
+    fn float_callee_saved(reg: &AArch64FloatReg) -> bool {
+        // Only the bottom 64 bits of these are callee saved, but we never use more than that.
+        matches!(
+            reg,
+            AArch64FloatReg::V8
+                | AArch64FloatReg::V9
+                | AArch64FloatReg::V10
+                | AArch64FloatReg::V11
+                | AArch64FloatReg::V12
+                | AArch64FloatReg::V13
+                | AArch64FloatReg::V14
+                | AArch64FloatReg::V15
+        )
     }
 
     #[inline(always)]
@@ -213,9 +362,8 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         requested_stack_size: i32,
         fn_call_stack_size: i32,
     ) -> i32 {
-        // Full size is upcast to i64 to make sure we don't overflow here.
         let full_stack_size = match requested_stack_size
-            .checked_add(8 * (saved_general_regs.len() + saved_float_regs.len()) as i32 + 8) // The extra 8 is space to store the frame pointer.
+            .checked_add(8 * (saved_general_regs.len() + saved_float_regs.len()) as i32 + 16) // The extra 16 is space to store the frame pointer and link register.
             .and_then(|size| size.checked_add(fn_call_stack_size))
         {
             Some(size) => size,
@@ -232,39 +380,41 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
             STACK_ALIGNMENT - alignment as u8
         };
         if let Some(aligned_stack_size) = full_stack_size.checked_add(offset as i32) {
-            if aligned_stack_size > 0 {
-                AArch64Assembler::mov_reg64_reg64(
-                    buf,
-                    AArch64GeneralReg::FP,
-                    AArch64GeneralReg::ZRSP,
-                );
-                AArch64Assembler::sub_reg64_reg64_imm32(
-                    buf,
-                    AArch64GeneralReg::ZRSP,
-                    AArch64GeneralReg::ZRSP,
-                    aligned_stack_size,
-                );
-
-                // All the following stores could be optimized by using `STP` to store pairs.
-                let mut offset = aligned_stack_size;
+            AArch64Assembler::sub_reg64_reg64_imm32(
+                buf,
+                AArch64GeneralReg::ZRSP,
+                AArch64GeneralReg::ZRSP,
+                aligned_stack_size,
+            );
+
+            // The frame record (the old frame pointer and link register) goes at the top of the frame.
+            // The frame pointer then points at it, just like in code generated by C compilers.
+            // All the following stores could be optimized by using `STP` to store pairs.
+            let frame_record_offset = aligned_stack_size - 16;
+            AArch64Assembler::mov_stack32_reg64(buf, frame_record_offset, AArch64GeneralReg::FP);
+            AArch64Assembler::mov_stack32_reg64(
+                buf,
+                frame_record_offset + 8,
+                AArch64GeneralReg::LR,
+            );
+            AArch64Assembler::add_reg64_reg64_imm32(
+                buf,
+                AArch64GeneralReg::FP,
+                AArch64GeneralReg::ZRSP,
+                frame_record_offset,
+            );
+
+            // Saved registers go right above the space used for the args of function calls.
+            let mut offset = frame_record_offset - fn_call_stack_size;
+            for reg in saved_general_regs {
+                AArch64Assembler::mov_base32_reg64(buf, -offset, *reg);
                 offset -= 8;
-                AArch64Assembler::mov_stack32_reg64(buf, offset, AArch64GeneralReg::LR);
+            }
+            for reg in saved_float_regs {
+                AArch64Assembler::mov_base32_freg64(buf, -offset, *reg);
                 offset -= 8;
-                AArch64Assembler::mov_stack32_reg64(buf, offset, AArch64GeneralReg::FP);
-
-                offset = aligned_stack_size - fn_call_stack_size;
-                for reg in saved_general_regs {
-                    offset -= 8;
-                    AArch64Assembler::mov_base32_reg64(buf, offset, *reg);
-                }
-                for reg in saved_float_regs {
-                    offset -= 8;
-                    AArch64Assembler::mov_base32_freg64(buf, offset, *reg);
-                }
-                aligned_stack_size
-            } else {
-                0
             }
+            aligned_stack_size
         } else {
             internal_error!("Ran out of stack space");
         }
@@ -278,111 +428,387 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         aligned_stack_size: i32,
         fn_call_stack_size: i32,
     ) {
-        if aligned_stack_size > 0 {
-            // All the following stores could be optimized by using `STP` to store pairs.
-            let mut offset = aligned_stack_size;
+        let frame_record_offset = aligned_stack_size - 16;
+        let mut offset = frame_record_offset - fn_call_stack_size;
+        for reg in saved_general_regs {
+            AArch64Assembler::mov_reg64_base32(buf, *reg, -offset);
             offset -= 8;
-            AArch64Assembler::mov_reg64_stack32(buf, AArch64GeneralReg::LR, offset);
+        }
+        for reg in saved_float_regs {
+            AArch64Assembler::mov_freg64_base32(buf, *reg, -offset);
             offset -= 8;
-            AArch64Assembler::mov_reg64_stack32(buf, AArch64GeneralReg::FP, offset);
-
-            offset = aligned_stack_size - fn_call_stack_size;
-            for reg in saved_general_regs {
-                offset -= 8;
-                AArch64Assembler::mov_reg64_base32(buf, *reg, offset);
-            }
-            for reg in saved_float_regs {
-                offset -= 8;
-                AArch64Assembler::mov_freg64_base32(buf, *reg, offset);
-            }
-            AArch64Assembler::add_reg64_reg64_imm32(
-                buf,
-                AArch64GeneralReg::ZRSP,
-                AArch64GeneralReg::ZRSP,
-                aligned_stack_size,
-            );
         }
+
+        // All the following loads could be optimized by using `LDP` to load pairs.
+        AArch64Assembler::mov_reg64_stack32(buf, AArch64GeneralReg::FP, frame_record_offset);
+        AArch64Assembler::mov_reg64_stack32(buf, AArch64GeneralReg::LR, frame_record_offset + 8);
+        AArch64Assembler::add_reg64_reg64_imm32(
+            buf,
+            AArch64GeneralReg::ZRSP,
+            AArch64GeneralReg::ZRSP,
+            aligned_stack_size,
+        );
     }
 
     #[inline(always)]
     fn load_args<'a>(
-        _buf: &mut Vec<'a, u8>,
-        _storage_manager: &mut StorageManager<
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<
             'a,
             AArch64GeneralReg,
             AArch64FloatReg,
             AArch64Assembler,
             AArch64Call,
         >,
-        _args: &'a [(Layout<'a>, Symbol)],
-        _ret_layout: &Layout<'a>,
+        args: &'a [(Layout<'a>, Symbol)],
+        ret_layout: &Layout<'a>,
     ) {
-        todo!("Loading args for AArch64");
+        let mut arg_offset = 16; // 16 is the size of the frame record that the frame pointer points to.
+        let mut general_i = 0;
+        let mut float_i = 0;
+        if Self::returns_via_arg_pointer(storage_manager.env.layout_interner, ret_layout) {
+            // The return pointer is passed in the indirect result location register, not a param reg.
+            storage_manager.ret_pointer_arg(AArch64GeneralReg::XR);
+        }
+        for (layout, sym) in args.iter() {
+            let stack_size = layout.stack_size(storage_manager.env.layout_interner, TARGET_INFO);
+            match layout {
+                single_register_integers!() => {
+                    if general_i < Self::GENERAL_PARAM_REGS.len() {
+                        storage_manager.general_reg_arg(sym, Self::GENERAL_PARAM_REGS[general_i]);
+                        general_i += 1;
+                    } else {
+                        storage_manager.primitive_stack_arg(sym, arg_offset);
+                        arg_offset += 8;
+                    }
+                }
+                single_register_floats!() => {
+                    if float_i < Self::FLOAT_PARAM_REGS.len() {
+                        storage_manager.float_reg_arg(sym, Self::FLOAT_PARAM_REGS[float_i]);
+                        float_i += 1;
+                    } else {
+                        storage_manager.primitive_stack_arg(sym, arg_offset);
+                        arg_offset += 8;
+                    }
+                }
+                _ if stack_size == 0 => {
+                    storage_manager.no_data_arg(sym);
+                }
+                _ if stack_size > 16 => {
+                    // The caller passes a pointer to a copy of the data.
+                    // Copy it into our own stack frame so it acts like any other complex symbol.
+                    let ptr_reg = if general_i < Self::GENERAL_PARAM_REGS.len() {
+                        general_i += 1;
+                        Self::GENERAL_PARAM_REGS[general_i - 1]
+                    } else {
+                        // X9 is not a param reg and nothing has been loaded into it yet.
+                        AArch64Assembler::mov_reg64_base32(buf, AArch64GeneralReg::X9, arg_offset);
+                        arg_offset += 8;
+                        AArch64GeneralReg::X9
+                    };
+                    let base_offset = storage_manager.claim_stack_area(sym, stack_size);
+                    for i in (0..stack_size as i32).step_by(8) {
+                        AArch64Assembler::mov_reg64_mem64_offset32(buf, ARG_BUFFER_REG, ptr_reg, i);
+                        AArch64Assembler::mov_base32_reg64(buf, base_offset + i, ARG_BUFFER_REG);
+                    }
+                }
+                _ => {
+                    // Small complex types are passed in consecutive general regs if they all fit.
+                    // Otherwise, they are passed on the stack and no more general regs are used.
+                    let reg_count = (stack_size as usize + 7) / 8;
+                    if general_i + reg_count <= Self::GENERAL_PARAM_REGS.len() {
+                        let base_offset = storage_manager.claim_stack_area(sym, stack_size);
+                        for (i, reg) in Self::GENERAL_PARAM_REGS[general_i..general_i + reg_count]
+                            .iter()
+                            .enumerate()
+                        {
+                            AArch64Assembler::mov_base32_reg64(
+                                buf,
+                                base_offset + 8 * i as i32,
+                                *reg,
+                            );
+                        }
+                        general_i += reg_count;
+                    } else {
+                        general_i = Self::GENERAL_PARAM_REGS.len();
+                        storage_manager.complex_stack_arg(sym, arg_offset, stack_size);
+                        arg_offset += 8 * reg_count as i32;
+                    }
+                }
+            }
+        }
     }
 
     #[inline(always)]
     fn store_args<'a>(
-        _buf: &mut Vec<'a, u8>,
-        _storage_manager: &mut StorageManager<
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<
             'a,
             AArch64GeneralReg,
             AArch64FloatReg,
             AArch64Assembler,
             AArch64Call,
         >,
-        _dst: &Symbol,
-        _args: &[Symbol],
-        _arg_layouts: &[Layout<'a>],
-        _ret_layout: &Layout<'a>,
+        dst: &Symbol,
+        args: &[Symbol],
+        arg_layouts: &[Layout<'a>],
+        ret_layout: &Layout<'a>,
     ) {
-        todo!("Storing args for AArch64");
+        let interner = storage_manager.env.layout_interner;
+        let mut tmp_stack_offset = Self::SHADOW_SPACE_SIZE as i32;
+        // Copies of large args are passed by pointer and live after the args passed on the stack.
+        let mut copy_stack_offset = tmp_stack_offset + Self::stack_args_size(interner, arg_layouts);
+        let mut general_i = 0;
+        let mut float_i = 0;
+        if Self::returns_via_arg_pointer(interner, ret_layout) {
+            // Save space on the stack for the result we will be return.
+            let base_offset =
+                storage_manager.claim_stack_area(dst, ret_layout.stack_size(interner, TARGET_INFO));
+            // Set the indirect result location reg to the address base + offset.
+            AArch64Assembler::add_reg64_reg64_imm32(
+                buf,
+                AArch64GeneralReg::XR,
+                AArch64GeneralReg::FP,
+                base_offset,
+            );
+        }
+        for (sym, layout) in args.iter().zip(arg_layouts.iter()) {
+            let stack_size = layout.stack_size(interner, TARGET_INFO);
+            match layout {
+                single_register_integers!() => {
+                    if general_i < Self::GENERAL_PARAM_REGS.len() {
+                        storage_manager.load_to_specified_general_reg(
+                            buf,
+                            sym,
+                            Self::GENERAL_PARAM_REGS[general_i],
+                        );
+                        general_i += 1;
+                    } else {
+                        storage_manager.load_to_specified_general_reg(buf, sym, ARG_BUFFER_REG);
+                        AArch64Assembler::mov_stack32_reg64(buf, tmp_stack_offset, ARG_BUFFER_REG);
+                        tmp_stack_offset += 8;
+                    }
+                }
+                single_register_floats!() => {
+                    if float_i < Self::FLOAT_PARAM_REGS.len() {
+                        storage_manager.load_to_specified_float_reg(
+                            buf,
+                            sym,
+                            Self::FLOAT_PARAM_REGS[float_i],
+                        );
+                        float_i += 1;
+                    } else {
+                        storage_manager.load_to_specified_float_reg(buf, sym, FLOAT_ARG_BUFFER_REG);
+                        AArch64Assembler::mov_stack32_freg64(
+                            buf,
+                            tmp_stack_offset,
+                            FLOAT_ARG_BUFFER_REG,
+                        );
+                        tmp_stack_offset += 8;
+                    }
+                }
+                _ if stack_size == 0 => {}
+                _ if stack_size > 16 => {
+                    // The callee is allowed to modify the data, so it gets a pointer to a copy.
+                    let (base_offset, size) = storage_manager.stack_offset_and_size(sym);
+                    for i in (0..size as i32).step_by(8) {
+                        AArch64Assembler::mov_reg64_base32(buf, ARG_BUFFER_REG, base_offset + i);
+                        AArch64Assembler::mov_stack32_reg64(
+                            buf,
+                            copy_stack_offset + i,
+                            ARG_BUFFER_REG,
+                        );
+                    }
+                    if general_i < Self::GENERAL_PARAM_REGS.len() {
+                        AArch64Assembler::add_reg64_reg64_imm32(
+                            buf,
+                            Self::GENERAL_PARAM_REGS[general_i],
+                            AArch64GeneralReg::ZRSP,
+                            copy_stack_offset,
+                        );
+                        general_i += 1;
+                    } else {
+                        AArch64Assembler::add_reg64_reg64_imm32(
+                            buf,
+                            ARG_BUFFER_REG,
+                            AArch64GeneralReg::ZRSP,
+                            copy_stack_offset,
+                        );
+                        AArch64Assembler::mov_stack32_reg64(buf, tmp_stack_offset, ARG_BUFFER_REG);
+                        tmp_stack_offset += 8;
+                    }
+                    copy_stack_offset += 8 * ((size as i32 + 7) / 8);
+                }
+                _ => {
+                    let (base_offset, _) = storage_manager.stack_offset_and_size(sym);
+                    let reg_count = (stack_size as usize + 7) / 8;
+                    if general_i + reg_count <= Self::GENERAL_PARAM_REGS.len() {
+                        for (i, reg) in Self::GENERAL_PARAM_REGS[general_i..general_i + reg_count]
+                            .iter()
+                            .enumerate()
+                        {
+                            AArch64Assembler::mov_reg64_base32(
+                                buf,
+                                *reg,
+                                base_offset + 8 * i as i32,
+                            );
+                        }
+                        general_i += reg_count;
+                    } else {
+                        general_i = Self::GENERAL_PARAM_REGS.len();
+                        for i in (0..8 * reg_count as i32).step_by(8) {
+                            AArch64Assembler::mov_reg64_base32(
+                                buf,
+                                ARG_BUFFER_REG,
+                                base_offset + i,
+                            );
+                            AArch64Assembler::mov_stack32_reg64(
+                                buf,
+                                tmp_stack_offset + i,
+                                ARG_BUFFER_REG,
+                            );
+                        }
+                        tmp_stack_offset += 8 * reg_count as i32;
+                    }
+                }
+            }
+        }
+        storage_manager.update_fn_call_stack_size(copy_stack_offset as u32);
     }
 
     fn return_complex_symbol<'a>(
-        _buf: &mut Vec<'a, u8>,
-        _storage_manager: &mut StorageManager<
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<
             'a,
             AArch64GeneralReg,
             AArch64FloatReg,
             AArch64Assembler,
             AArch64Call,
         >,
-        _sym: &Symbol,
-        _layout: &Layout<'a>,
+        sym: &Symbol,
+        layout: &Layout<'a>,
     ) {
-        todo!("Returning complex symbols for AArch64");
+        match layout {
+            single_register_layouts!() => {
+                internal_error!("single register layouts are not complex symbols");
+            }
+            x if x.stack_size(storage_manager.env.layout_interner, TARGET_INFO) == 0 => {}
+            x if !Self::returns_via_arg_pointer(storage_manager.env.layout_interner, x) => {
+                let (base_offset, size) = storage_manager.stack_offset_and_size(sym);
+                AArch64Assembler::mov_reg64_base32(buf, Self::GENERAL_RETURN_REGS[0], base_offset);
+                if size > 8 {
+                    AArch64Assembler::mov_reg64_base32(
+                        buf,
+                        Self::GENERAL_RETURN_REGS[1],
+                        base_offset + 8,
+                    );
+                }
+            }
+            _ => {
+                // This is a large type returned via the arg pointer.
+                // Unlike x86_64, the arg pointer does not need to be returned.
+                storage_manager.copy_symbol_to_arg_pointer(buf, sym, layout);
+            }
+        }
     }
 
     fn load_returned_complex_symbol<'a>(
-        _buf: &mut Vec<'a, u8>,
-        _storage_manager: &mut StorageManager<
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<
             'a,
             AArch64GeneralReg,
             AArch64FloatReg,
             AArch64Assembler,
             AArch64Call,
         >,
-        _sym: &Symbol,
-        _layout: &Layout<'a>,
+        sym: &Symbol,
+        layout: &Layout<'a>,
     ) {
-        todo!("Loading returned complex symbols for AArch64");
+        match layout {
+            single_register_layouts!() => {
+                internal_error!("single register layouts are not complex symbols");
+            }
+            x if x.stack_size(storage_manager.env.layout_interner, TARGET_INFO) == 0 => {}
+            x if !Self::returns_via_arg_pointer(storage_manager.env.layout_interner, x) => {
+                let size = layout.stack_size(storage_manager.env.layout_interner, TARGET_INFO);
+                let offset = storage_manager.claim_stack_area(sym, size);
+                AArch64Assembler::mov_base32_reg64(buf, offset, Self::GENERAL_RETURN_REGS[0]);
+                if size > 8 {
+                    AArch64Assembler::mov_base32_reg64(
+                        buf,
+                        offset + 8,
+                        Self::GENERAL_RETURN_REGS[1],
+                    );
+                }
+            }
+            _ => {
+                // This should have been recieved via an arg pointer.
+                // That means the value is already loaded onto the stack area we allocated before the call.
+                // Nothing to do.
+            }
+        }
+    }
+}
+
+impl AArch64Call {
+    fn returns_via_arg_pointer<'a>(
+        interner: &STLayoutInterner<'a>,
+        ret_layout: &Layout<'a>,
+    ) -> bool {
+        // TODO: This will need to be more complex/extended to fully support the calling convention.
+        // For example, structs of only floats should be returned in float registers.
+        // details here: https://github.com/ARM-software/abi-aa/blob/main/aapcs64/aapcs64.rst
+        ret_layout.stack_size(interner, TARGET_INFO) > 16
+    }
+
+    /// Returns the number of bytes needed on the stack for args that don't fit in registers.
+    /// This follows the same rules as `store_args`.
+    fn stack_args_size<'a>(interner: &STLayoutInterner<'a>, arg_layouts: &[Layout<'a>]) -> i32 {
+        let mut general_i = 0;
+        let mut float_i = 0;
+        let mut size = 0;
+        for layout in arg_layouts {
+            let stack_size = layout.stack_size(interner, TARGET_INFO);
+            let reg_count = match layout {
+                single_register_floats!() => {
+                    if float_i < Self::FLOAT_PARAM_REGS.len() {
+                        float_i += 1;
+                    } else {
+                        size += 8;
+                    }
+                    continue;
+                }
+                single_register_integers!() => 1,
+                _ if stack_size == 0 => 0,
+                _ if stack_size > 16 => 1,
+                _ => (stack_size as usize + 7) / 8,
+            };
+            if general_i + reg_count <= Self::GENERAL_PARAM_REGS.len() {
+                general_i += reg_count;
+            } else {
+                general_i = Self::GENERAL_PARAM_REGS.len();
+                size += 8 * reg_count as i32;
+            }
+        }
+        size
     }
 }
 
 impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     #[inline(always)]
-    fn abs_reg64_reg64(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg, _src: AArch64GeneralReg) {
-        todo!("abs_reg64_reg64 for AArch64");
+    fn abs_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
+        cmp_reg64_imm12(buf, src, 0);
+        cneg_reg64_reg64_cond(buf, dst, src, ConditionCode::LT);
     }
 
     #[inline(always)]
     fn abs_freg64_freg64(
-        _buf: &mut Vec<'_, u8>,
+        buf: &mut Vec<'_, u8>,
         _relocs: &mut Vec<'_, Relocation>,
-        _dst: AArch64FloatReg,
-        _src: AArch64FloatReg,
+        dst: AArch64FloatReg,
+        src: AArch64FloatReg,
     ) {
-        todo!("abs_reg64_reg64 for AArch64");
+        fabs_freg64_freg64(buf, dst, src);
     }
 
     #[inline(always)]
@@ -392,12 +818,13 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         src: AArch64GeneralReg,
         imm32: i32,
     ) {
-        if imm32 < 0 {
-            todo!("immediate addition with values less than 0");
-        } else if imm32 < 0xFFF {
+        if (0..=0xFFF).contains(&imm32) {
             add_reg64_reg64_imm12(buf, dst, src, imm32 as u16);
+        } else if (-0xFFF..0).contains(&imm32) {
+            sub_reg64_reg64_imm12(buf, dst, src, -imm32 as u16);
         } else {
-            todo!("immediate additions with values greater than 12bits");
+            Self::mov_reg64_imm64(buf, TMP_REG, imm32 as i64);
+            add_reg64_reg64_reg64_extended(buf, dst, src, TMP_REG);
         }
     }
     #[inline(always)]
@@ -407,157 +834,175 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         src1: AArch64GeneralReg,
         src2: AArch64GeneralReg,
     ) {
-        add_reg64_reg64_reg64(buf, dst, src1, src2);
+        // This sets the flags so that `set_if_overflow` can be used afterwards.
+        adds_reg64_reg64_reg64(buf, dst, src1, src2);
     }
     #[inline(always)]
     fn add_freg32_freg32_freg32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("adding floats for AArch64");
+        fadd_freg32_freg32_freg32(buf, dst, src1, src2);
     }
     #[inline(always)]
     fn add_freg64_freg64_freg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("adding floats for AArch64");
+        fadd_freg64_freg64_freg64(buf, dst, src1, src2);
     }
 
     #[inline(always)]
-    fn call(_buf: &mut Vec<'_, u8>, _relocs: &mut Vec<'_, Relocation>, _fn_name: String) {
-        todo!("calling functions literal for AArch64");
+    fn call(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>, fn_name: String) {
+        bl_imm26(buf, 0);
+        relocs.push(Relocation::LinkedFunction {
+            offset: buf.len() as u64 - 4,
+            name: fn_name,
+        });
     }
 
     #[inline(always)]
     fn imul_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("register signed multiplication for AArch64");
+        mul_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 
     fn umul_reg64_reg64_reg64<'a, ASM, CC>(
-        _buf: &mut Vec<'a, u8>,
+        buf: &mut Vec<'a, u8>,
         _storage_manager: &mut StorageManager<'a, AArch64GeneralReg, AArch64FloatReg, ASM, CC>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) where
         ASM: Assembler<AArch64GeneralReg, AArch64FloatReg>,
         CC: CallConv<AArch64GeneralReg, AArch64FloatReg, ASM>,
     {
-        todo!("register unsigned multiplication for AArch64");
+        // The low 64 bits of the product are the same for signed and unsigned multiplication.
+        mul_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 
     fn idiv_reg64_reg64_reg64<'a, ASM, CC>(
-        _buf: &mut Vec<'a, u8>,
+        buf: &mut Vec<'a, u8>,
         _storage_manager: &mut StorageManager<'a, AArch64GeneralReg, AArch64FloatReg, ASM, CC>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) where
         ASM: Assembler<AArch64GeneralReg, AArch64FloatReg>,
         CC: CallConv<AArch64GeneralReg, AArch64FloatReg, ASM>,
     {
-        todo!("register signed division for AArch64");
+        sdiv_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 
     fn udiv_reg64_reg64_reg64<'a, ASM, CC>(
-        _buf: &mut Vec<'a, u8>,
+        buf: &mut Vec<'a, u8>,
         _storage_manager: &mut StorageManager<'a, AArch64GeneralReg, AArch64FloatReg, ASM, CC>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) where
         ASM: Assembler<AArch64GeneralReg, AArch64FloatReg>,
         CC: CallConv<AArch64GeneralReg, AArch64FloatReg, ASM>,
     {
-        todo!("register unsigned division for AArch64");
+        udiv_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 
     #[inline(always)]
     fn mul_freg32_freg32_freg32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("multiplication for floats for AArch64");
+        fmul_freg32_freg32_freg32(buf, dst, src1, src2);
     }
     #[inline(always)]
     fn mul_freg64_freg64_freg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("multiplication for floats for AArch64");
+        fmul_freg64_freg64_freg64(buf, dst, src1, src2);
     }
 
     #[inline(always)]
     fn div_freg32_freg32_freg32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("division for floats for AArch64");
+        fdiv_freg32_freg32_freg32(buf, dst, src1, src2);
     }
     #[inline(always)]
     fn div_freg64_freg64_freg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("division for floats for AArch64");
+        fdiv_freg64_freg64_freg64(buf, dst, src1, src2);
     }
 
     #[inline(always)]
-    fn jmp_imm32(_buf: &mut Vec<'_, u8>, _offset: i32) -> usize {
-        todo!("jump instructions for AArch64");
+    fn jmp_imm32(buf: &mut Vec<'_, u8>, offset: i32) -> usize {
+        b_imm26(buf, offset);
+        // Branches in AArch64 are relative to the branch instruction itself.
+        buf.len() - 4
     }
 
     #[inline(always)]
     fn tail_call(buf: &mut Vec<'_, u8>) -> u64 {
         Self::jmp_imm32(buf, 0);
-        buf.len() as u64 - 4 // TODO is 4 the correct offset in ARM?
+        buf.len() as u64 - 4
     }
 
     #[inline(always)]
     fn jne_reg64_imm64_imm32(
-        _buf: &mut Vec<'_, u8>,
-        _reg: AArch64GeneralReg,
-        _imm: u64,
-        _offset: i32,
+        buf: &mut Vec<'_, u8>,
+        reg: AArch64GeneralReg,
+        imm: u64,
+        offset: i32,
     ) -> usize {
-        todo!("jump not equal instructions for AArch64");
+        if imm <= 0xFFF {
+            cmp_reg64_imm12(buf, reg, imm as u16);
+        } else {
+            Self::mov_reg64_imm64(buf, TMP_REG, imm as i64);
+            cmp_reg64_reg64(buf, reg, TMP_REG);
+        }
+        b_cond_imm19(buf, ConditionCode::NE, offset);
+        // Branches in AArch64 are relative to the branch instruction itself.
+        buf.len() - 4
     }
 
     #[inline(always)]
     fn mov_freg32_imm32(
-        _buf: &mut Vec<'_, u8>,
+        buf: &mut Vec<'_, u8>,
         _relocs: &mut Vec<'_, Relocation>,
-        _dst: AArch64FloatReg,
-        _imm: f32,
+        dst: AArch64FloatReg,
+        imm: f32,
     ) {
-        todo!("loading f32 literal for AArch64");
+        Self::mov_reg64_imm64(buf, TMP_REG, imm.to_bits() as i64);
+        fmov_freg32_reg32(buf, dst, TMP_REG);
     }
     #[inline(always)]
     fn mov_freg64_imm64(
-        _buf: &mut Vec<'_, u8>,
+        buf: &mut Vec<'_, u8>,
         _relocs: &mut Vec<'_, Relocation>,
-        _dst: AArch64FloatReg,
-        _imm: f64,
+        dst: AArch64FloatReg,
+        imm: f64,
     ) {
-        todo!("loading f64 literal for AArch64");
+        Self::mov_reg64_imm64(buf, TMP_REG, imm.to_bits() as i64);
+        fmov_freg64_reg64(buf, dst, TMP_REG);
     }
     #[inline(always)]
     fn mov_reg64_imm64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, imm: i64) {
@@ -577,8 +1022,8 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         }
     }
     #[inline(always)]
-    fn mov_freg64_freg64(_buf: &mut Vec<'_, u8>, _dst: AArch64FloatReg, _src: AArch64FloatReg) {
-        todo!("moving data between float registers for AArch64");
+    fn mov_freg64_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+        fmov_freg64_freg64(buf, dst, src);
     }
     #[inline(always)]
     fn mov_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
@@ -586,34 +1031,20 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     }
 
     #[inline(always)]
-    fn mov_freg64_base32(_buf: &mut Vec<'_, u8>, _dst: AArch64FloatReg, _offset: i32) {
-        todo!("loading floating point reg from base offset for AArch64");
+    fn mov_freg64_base32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, offset: i32) {
+        Self::mov_freg64_mem64_offset32(buf, dst, AArch64GeneralReg::FP, offset);
     }
     #[inline(always)]
     fn mov_reg64_base32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, offset: i32) {
-        if offset < 0 {
-            todo!("negative base offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            ldr_reg64_reg64_imm12(buf, dst, AArch64GeneralReg::FP, (offset as u16) >> 3);
-        } else {
-            todo!("base offsets over 32k for AArch64");
-        }
+        Self::mov_reg64_mem64_offset32(buf, dst, AArch64GeneralReg::FP, offset);
     }
     #[inline(always)]
-    fn mov_base32_freg64(_buf: &mut Vec<'_, u8>, _offset: i32, _src: AArch64FloatReg) {
-        todo!("saving floating point reg to base offset for AArch64");
+    fn mov_base32_freg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64FloatReg) {
+        Self::mov_mem64_offset32_freg64(buf, AArch64GeneralReg::FP, offset, src);
     }
     #[inline(always)]
     fn mov_base32_reg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64GeneralReg) {
-        if offset < 0 {
-            todo!("negative base offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            str_reg64_reg64_imm12(buf, src, AArch64GeneralReg::FP, (offset as u16) >> 3);
-        } else {
-            todo!("base offsets over 32k for AArch64");
-        }
+        Self::mov_mem64_offset32_reg64(buf, AArch64GeneralReg::FP, offset, src);
     }
 
     #[inline(always)]
@@ -623,13 +1054,11 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         src: AArch64GeneralReg,
         offset: i32,
     ) {
-        if offset < 0 {
-            todo!("negative mem offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            ldr_reg64_reg64_imm12(buf, dst, src, (offset as u16) >> 3);
+        if let Some(imm12) = scaled_imm12(offset) {
+            ldr_reg64_reg64_imm12(buf, dst, src, imm12);
         } else {
-            todo!("mem offsets over 32k for AArch64");
+            let (base, imm9) = Self::unscaled_base_and_imm9(buf, src, offset);
+            ldur_reg64_reg64_imm9(buf, dst, base, imm9);
         }
     }
     #[inline(always)]
@@ -639,80 +1068,66 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         offset: i32,
         src: AArch64GeneralReg,
     ) {
-        if offset < 0 {
-            todo!("negative mem offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            str_reg64_reg64_imm12(buf, src, dst, (offset as u16) >> 3);
+        if let Some(imm12) = scaled_imm12(offset) {
+            str_reg64_reg64_imm12(buf, src, dst, imm12);
         } else {
-            todo!("mem offsets over 32k for AArch64");
+            let (base, imm9) = Self::unscaled_base_and_imm9(buf, dst, offset);
+            stur_reg64_reg64_imm9(buf, src, base, imm9);
         }
     }
 
     #[inline(always)]
     fn movsx_reg64_base32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, offset: i32, size: u8) {
         debug_assert!(size <= 8);
-        if size == 8 {
-            Self::mov_reg64_base32(buf, dst, offset);
-        } else if size == 4 {
-            todo!("sign extending 4 byte values");
-        } else if size == 2 {
-            todo!("sign extending 2 byte values");
-        } else if size == 1 {
-            todo!("sign extending 1 byte values");
-        } else {
-            internal_error!("Invalid size for sign extension: {}", size);
+        match size {
+            8 => Self::mov_reg64_base32(buf, dst, offset),
+            4 | 2 | 1 => {
+                let (base, imm9) = Self::unscaled_base_and_imm9(buf, AArch64GeneralReg::FP, offset);
+                match size {
+                    4 => ldursw_reg64_reg64_imm9(buf, dst, base, imm9),
+                    2 => ldursh_reg64_reg64_imm9(buf, dst, base, imm9),
+                    _ => ldursb_reg64_reg64_imm9(buf, dst, base, imm9),
+                }
+            }
+            _ => internal_error!("Invalid size for sign extension: {size}"),
         }
     }
     #[inline(always)]
     fn movzx_reg64_base32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, offset: i32, size: u8) {
         debug_assert!(size <= 8);
-        if size == 8 {
-            Self::mov_reg64_base32(buf, dst, offset);
-        } else if size == 4 {
-            todo!("zero extending 4 byte values");
-        } else if size == 2 {
-            todo!("zero extending 2 byte values");
-        } else if size == 1 {
-            todo!("zero extending 1 byte values");
-        } else {
-            internal_error!("Invalid size for zero extension: {}", size);
+        match size {
+            8 => Self::mov_reg64_base32(buf, dst, offset),
+            4 | 2 | 1 => {
+                let (base, imm9) = Self::unscaled_base_and_imm9(buf, AArch64GeneralReg::FP, offset);
+                match size {
+                    4 => ldur_reg32_reg64_imm9(buf, dst, base, imm9),
+                    2 => ldurh_reg64_reg64_imm9(buf, dst, base, imm9),
+                    _ => ldurb_reg64_reg64_imm9(buf, dst, base, imm9),
+                }
+            }
+            _ => internal_error!("Invalid size for zero extension: {size}"),
         }
     }
 
     #[inline(always)]
-    fn mov_freg64_stack32(_buf: &mut Vec<'_, u8>, _dst: AArch64FloatReg, _offset: i32) {
-        todo!("loading floating point reg from stack for AArch64");
+    fn mov_freg64_stack32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, offset: i32) {
+        Self::mov_freg64_mem64_offset32(buf, dst, AArch64GeneralReg::ZRSP, offset);
     }
     #[inline(always)]
     fn mov_reg64_stack32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, offset: i32) {
-        if offset < 0 {
-            todo!("negative stack offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            ldr_reg64_reg64_imm12(buf, dst, AArch64GeneralReg::ZRSP, (offset as u16) >> 3);
-        } else {
-            todo!("stack offsets over 32k for AArch64");
-        }
+        Self::mov_reg64_mem64_offset32(buf, dst, AArch64GeneralReg::ZRSP, offset);
     }
     #[inline(always)]
-    fn mov_stack32_freg64(_buf: &mut Vec<'_, u8>, _offset: i32, _src: AArch64FloatReg) {
-        todo!("saving floating point reg to stack for AArch64");
+    fn mov_stack32_freg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64FloatReg) {
+        Self::mov_mem64_offset32_freg64(buf, AArch64GeneralReg::ZRSP, offset, src);
     }
     #[inline(always)]
     fn mov_stack32_reg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64GeneralReg) {
-        if offset < 0 {
-            todo!("negative stack offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            str_reg64_reg64_imm12(buf, src, AArch64GeneralReg::ZRSP, (offset as u16) >> 3);
-        } else {
-            todo!("stack offsets over 32k for AArch64");
-        }
+        Self::mov_mem64_offset32_reg64(buf, AArch64GeneralReg::ZRSP, offset, src);
     }
     #[inline(always)]
-    fn neg_reg64_reg64(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg, _src: AArch64GeneralReg) {
-        todo!("neg for AArch64");
+    fn neg_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
+        neg_reg64_reg64(buf, dst, src);
     }
 
     #[inline(always)]
@@ -722,112 +1137,102 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         src: AArch64GeneralReg,
         imm32: i32,
     ) {
-        if imm32 < 0 {
-            todo!("immediate subtractions with values less than 0");
-        } else if imm32 < 0xFFF {
+        if (0..=0xFFF).contains(&imm32) {
             sub_reg64_reg64_imm12(buf, dst, src, imm32 as u16);
+        } else if (-0xFFF..0).contains(&imm32) {
+            add_reg64_reg64_imm12(buf, dst, src, -imm32 as u16);
         } else {
-            todo!("immediate subtractions with values greater than 12bits");
+            Self::mov_reg64_imm64(buf, TMP_REG, -(imm32 as i64));
+            add_reg64_reg64_reg64_extended(buf, dst, src, TMP_REG);
         }
     }
     #[inline(always)]
     fn sub_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers subtractions for AArch64");
+        sub_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 
     #[inline(always)]
     fn eq_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers equality for AArch64");
+        cmp_reg64_reg64(buf, src1, src2);
+        cset_reg64_cond(buf, dst, ConditionCode::EQ);
     }
 
     #[inline(always)]
     fn neq_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers non-equality for AArch64");
+        cmp_reg64_reg64(buf, src1, src2);
+        cset_reg64_cond(buf, dst, ConditionCode::NE);
     }
 
     #[inline(always)]
     fn lt_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers less than for AArch64");
+        cmp_reg64_reg64(buf, src1, src2);
+        cset_reg64_cond(buf, dst, ConditionCode::LT);
     }
 
     #[inline(always)]
-    fn to_float_freg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64GeneralReg,
-    ) {
-        todo!("registers to float for AArch64");
+    fn to_float_freg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+        scvtf_freg64_reg64(buf, dst, src);
     }
 
     #[inline(always)]
-    fn to_float_freg32_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64GeneralReg,
-    ) {
-        todo!("registers to float for AArch64");
+    fn to_float_freg32_reg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+        scvtf_freg32_reg64(buf, dst, src);
     }
 
     #[inline(always)]
-    fn to_float_freg32_freg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64FloatReg,
-    ) {
-        todo!("registers to float for AArch64");
+    fn to_float_freg32_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+        fcvt_freg32_freg64(buf, dst, src);
     }
 
     #[inline(always)]
-    fn to_float_freg64_freg32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64FloatReg,
-    ) {
-        todo!("registers to float for AArch64");
+    fn to_float_freg64_freg32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+        fcvt_freg64_freg32(buf, dst, src);
     }
 
     #[inline(always)]
     fn lte_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers less than or equal for AArch64");
+        cmp_reg64_reg64(buf, src1, src2);
+        cset_reg64_cond(buf, dst, ConditionCode::LE);
     }
 
     #[inline(always)]
     fn gte_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers greater than or equal for AArch64");
+        cmp_reg64_reg64(buf, src1, src2);
+        cset_reg64_cond(buf, dst, ConditionCode::GE);
     }
 
-    fn set_if_overflow(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg) {
-        todo!("set if overflow for AArch64");
+    fn set_if_overflow(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg) {
+        cset_reg64_cond(buf, dst, ConditionCode::VS);
     }
 
     #[inline(always)]
@@ -836,34 +1241,90 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     }
 
     fn and_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("bitwise and for AArch64")
+        and_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 
     fn or_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("bitwise or for AArch64")
+        orr_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 
     fn xor_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
+    ) {
+        eor_reg64_reg64_reg64(buf, dst, src1, src2);
+    }
+}
+
+impl AArch64Assembler {
+    #[inline(always)]
+    fn mov_freg64_mem64_offset32(
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src: AArch64GeneralReg,
+        offset: i32,
+    ) {
+        if let Some(imm12) = scaled_imm12(offset) {
+            ldr_freg64_reg64_imm12(buf, dst, src, imm12);
+        } else {
+            let (base, imm9) = Self::unscaled_base_and_imm9(buf, src, offset);
+            ldur_freg64_reg64_imm9(buf, dst, base, imm9);
+        }
+    }
+
+    #[inline(always)]
+    fn mov_mem64_offset32_freg64(
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        offset: i32,
+        src: AArch64FloatReg,
     ) {
-        todo!("bitwise xor for AArch64")
+        if let Some(imm12) = scaled_imm12(offset) {
+            str_freg64_reg64_imm12(buf, src, dst, imm12);
+        } else {
+            let (base, imm9) = Self::unscaled_base_and_imm9(buf, dst, offset);
+            stur_freg64_reg64_imm9(buf, src, base, imm9);
+        }
+    }
+
+    /// Returns the base register and immediate to use for an unscaled access of `base + offset`.
+    /// If the offset does not fit in 9 bits, the full address is calculated in the temporary register.
+    #[inline(always)]
+    fn unscaled_base_and_imm9(
+        buf: &mut Vec<'_, u8>,
+        base: AArch64GeneralReg,
+        offset: i32,
+    ) -> (AArch64GeneralReg, i16) {
+        if (-0x100..0x100).contains(&offset) {
+            (base, offset as i16)
+        } else {
+            Self::add_reg64_reg64_imm32(buf, TMP_REG, base, offset);
+            (TMP_REG, 0)
+        }
     }
 }
 
-impl AArch64Assembler {}
+/// Returns the scaled immediate for an 8 byte access at `offset` if one can encode it.
+#[inline(always)]
+fn scaled_imm12(offset: i32) -> Option<u16> {
+    if offset >= 0 && offset % 8 == 0 && offset <= (0xFFF << 3) {
+        Some((offset >> 3) as u16)
+    } else {
+        None
+    }
+}
 
 // Instructions
 // ARM manual section C3
@@ -1090,328 +1551,1929 @@ impl LogicalShiftedRegister {
 }
 
 #[derive(PackedStruct)]
-pub struct UnconditionalBranchRegister {
-    fixed: Integer<u8, packed_bits::Bits<7>>,
-    z: bool,
-    fixed2: bool,
-    op: Integer<u8, packed_bits::Bits<2>>,
-    fixed3: Integer<u8, packed_bits::Bits<5>>,
-    fixed4: Integer<u8, packed_bits::Bits<4>>,
-    a: bool,
-    m: bool,
-    rn: Integer<u8, packed_bits::Bits<5>>,
-    fixed5: Integer<u8, packed_bits::Bits<5>>,
+#[packed_struct(endian = "msb")]
+pub struct ArithmeticExtended {
+    sf: bool,
+    op: bool, // add or subtract
+    s: bool,
+    fixed: Integer<u8, packed_bits::Bits<5>>, // = 0b01011,
+    opt: Integer<u8, packed_bits::Bits<2>>,   // = 0b00,
+    fixed2: bool,                             // = 0b1,
+    reg_m: Integer<u8, packed_bits::Bits<5>>,
+    option: Integer<u8, packed_bits::Bits<3>>,
+    imm3: Integer<u8, packed_bits::Bits<3>>,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
 }
 
-impl Aarch64Bytes for UnconditionalBranchRegister {}
+impl Aarch64Bytes for ArithmeticExtended {}
 
-impl UnconditionalBranchRegister {
+impl ArithmeticExtended {
     #[inline(always)]
-    fn new(op: u8, rn: AArch64GeneralReg) -> Self {
-        debug_assert!(op <= 0b11);
-
+    fn new(
+        op: bool,
+        s: bool,
+        rm: AArch64GeneralReg,
+        rn: AArch64GeneralReg,
+        rd: AArch64GeneralReg,
+    ) -> Self {
         Self {
-            fixed5: 0b00000.into(),
-            rn: rn.id().into(),
-            m: false,
-            a: false,
-            fixed4: 0b0000.into(),
-            fixed3: 0b11111.into(),
-            op: op.into(),
-            fixed2: false,
-            z: false,
-            fixed: 0b1101011.into(),
-        }
-    }
-}
-
-// Uses unsigned Offset
-// opc = 0b01 means load
-// opc = 0b00 means store
-#[derive(PackedStruct, Debug)]
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            imm3: 0.into(),
+            // UXTX, which is the same as LSL for 64 bit registers.
+            option: 0b011.into(),
+            reg_m: rm.id().into(),
+            fixed2: true,
+            opt: 0b00.into(),
+            fixed: 0b01011.into(),
+            s,
+            op,
+            // true for 64 bit addition
+            // false for 32 bit addition
+            sf: true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[allow(dead_code)]
+enum ConditionCode {
+    EQ = 0b0000,
+    NE = 0b0001,
+    HS = 0b0010,
+    LO = 0b0011,
+    MI = 0b0100,
+    PL = 0b0101,
+    VS = 0b0110,
+    VC = 0b0111,
+    HI = 0b1000,
+    LS = 0b1001,
+    GE = 0b1010,
+    LT = 0b1011,
+    GT = 0b1100,
+    LE = 0b1101,
+    AL = 0b1110,
+    NV = 0b1111,
+}
+
+impl ConditionCode {
+    #[inline(always)]
+    fn id(&self) -> u8 {
+        *self as u8
+    }
+
+    /// Returns the id of the opposite condition.
+    /// This is not valid for AL and NV.
+    #[inline(always)]
+    fn invert_id(&self) -> u8 {
+        debug_assert!(!matches!(self, ConditionCode::AL | ConditionCode::NV));
+        self.id() ^ 1
+    }
+}
+
+#[derive(PackedStruct)]
 #[packed_struct(endian = "msb")]
-pub struct LoadStoreRegisterImmediate {
-    size: Integer<u8, packed_bits::Bits<2>>,
-    fixed: Integer<u8, packed_bits::Bits<3>>, // = 0b111,
-    fixed2: bool,
-    fixed3: Integer<u8, packed_bits::Bits<2>>,
-    opc: Integer<u8, packed_bits::Bits<2>>,
-    imm12: Integer<u16, packed_bits::Bits<12>>,
-    rn: Integer<u8, packed_bits::Bits<5>>,
-    rt: Integer<u8, packed_bits::Bits<5>>,
+pub struct ConditionalSelect {
+    sf: bool,
+    op: bool,
+    s: bool,
+    fixed: Integer<u8, packed_bits::Bits<8>>, // = 0b11010100,
+    reg_m: Integer<u8, packed_bits::Bits<5>>,
+    cond: Integer<u8, packed_bits::Bits<4>>,
+    fixed2: bool, // = 0b0,
+    o2: bool,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
 }
 
-impl Aarch64Bytes for LoadStoreRegisterImmediate {}
+impl Aarch64Bytes for ConditionalSelect {}
 
-impl LoadStoreRegisterImmediate {
+impl ConditionalSelect {
     #[inline(always)]
-    fn new(size: u8, opc: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64GeneralReg) -> Self {
-        debug_assert!(size <= 0b11);
-        debug_assert!(imm12 <= 0xFFF);
+    fn new(
+        op: bool,
+        o2: bool,
+        cond: u8,
+        rm: AArch64GeneralReg,
+        rn: AArch64GeneralReg,
+        rd: AArch64GeneralReg,
+    ) -> Self {
+        debug_assert!(cond <= 0b1111);
 
         Self {
-            rt: rt.id().into(),
-            rn: rn.id().into(),
-            imm12: imm12.into(),
-            opc: opc.into(),
-            fixed3: 0b01.into(),
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            o2,
             fixed2: false,
-            fixed: 0b111.into(),
-            size: size.into(),
+            cond: cond.into(),
+            reg_m: rm.id().into(),
+            fixed: 0b11010100.into(),
+            s: false,
+            op,
+            sf: true,
         }
     }
+}
 
-    #[inline(always)]
-    fn new_load(size: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64GeneralReg) -> Self {
-        Self::new(size, 0b01, imm12, rn, rt)
-    }
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct DataProcessingTwoSource {
+    sf: bool,
+    fixed: bool, // = 0b0,
+    s: bool,
+    fixed2: Integer<u8, packed_bits::Bits<8>>, // = 0b11010110,
+    reg_m: Integer<u8, packed_bits::Bits<5>>,
+    opcode: Integer<u8, packed_bits::Bits<6>>,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
+}
 
+impl Aarch64Bytes for DataProcessingTwoSource {}
+
+impl DataProcessingTwoSource {
     #[inline(always)]
-    fn new_store(size: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64GeneralReg) -> Self {
-        Self::new(size, 0b00, imm12, rn, rt)
+    fn new(
+        opcode: u8,
+        rm: AArch64GeneralReg,
+        rn: AArch64GeneralReg,
+        rd: AArch64GeneralReg,
+    ) -> Self {
+        debug_assert!(opcode <= 0b111111);
+
+        Self {
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            opcode: opcode.into(),
+            reg_m: rm.id().into(),
+            fixed2: 0b11010110.into(),
+            s: false,
+            fixed: false,
+            sf: true,
+        }
     }
 }
 
-// Below here are the functions for all of the assembly instructions.
-// Their names are based on the instruction and operators combined.
-// You should call `buf.reserve()` if you push or extend more than once.
-// Unit tests are added at the bottom of the file to ensure correct asm generation.
-// Please keep these in alphanumeric order.
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct DataProcessingThreeSource {
+    sf: bool,
+    op54: Integer<u8, packed_bits::Bits<2>>,
+    fixed: Integer<u8, packed_bits::Bits<5>>, // = 0b11011,
+    op31: Integer<u8, packed_bits::Bits<3>>,
+    reg_m: Integer<u8, packed_bits::Bits<5>>,
+    o0: bool,
+    reg_a: Integer<u8, packed_bits::Bits<5>>,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
+}
 
-/// `ADD Xd, Xn, imm12` -> Add Xn and imm12 and place the result into Xd.
-#[inline(always)]
-fn add_reg64_reg64_imm12(
-    buf: &mut Vec<'_, u8>,
-    dst: AArch64GeneralReg,
-    src: AArch64GeneralReg,
-    imm12: u16,
-) {
-    let inst = ArithmeticImmediate::new(false, false, dst, src, imm12, false);
+impl Aarch64Bytes for DataProcessingThreeSource {}
 
-    buf.extend(inst.bytes());
-}
+impl DataProcessingThreeSource {
+    #[inline(always)]
+    fn new(
+        op31: u8,
+        o0: bool,
+        rm: AArch64GeneralReg,
+        ra: AArch64GeneralReg,
+        rn: AArch64GeneralReg,
+        rd: AArch64GeneralReg,
+    ) -> Self {
+        debug_assert!(op31 <= 0b111);
 
-/// `ADD Xd, Xm, Xn` -> Add Xm and Xn and place the result into Xd.
-#[inline(always)]
-fn add_reg64_reg64_reg64(
-    buf: &mut Vec<'_, u8>,
-    dst: AArch64GeneralReg,
-    src1: AArch64GeneralReg,
-    src2: AArch64GeneralReg,
-) {
-    let inst = ArithmeticShifted::new(false, false, ShiftType::LSL, 0, src2, src1, dst);
+        Self {
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            reg_a: ra.id().into(),
+            o0,
+            reg_m: rm.id().into(),
+            op31: op31.into(),
+            fixed: 0b11011.into(),
+            op54: 0b00.into(),
+            sf: true,
+        }
+    }
+}
 
-    buf.extend(inst.bytes());
+#[derive(PackedStruct)]
+pub struct UnconditionalBranchRegister {
+    fixed: Integer<u8, packed_bits::Bits<7>>,
+    z: bool,
+    fixed2: bool,
+    op: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: Integer<u8, packed_bits::Bits<5>>,
+    fixed4: Integer<u8, packed_bits::Bits<4>>,
+    a: bool,
+    m: bool,
+    rn: Integer<u8, packed_bits::Bits<5>>,
+    fixed5: Integer<u8, packed_bits::Bits<5>>,
 }
 
-/// `LDR Xt, [Xn, #offset]` -> Load Xn + Offset Xt. ZRSP is SP.
-/// Note: imm12 is the offest divided by 8.
-#[inline(always)]
-fn ldr_reg64_reg64_imm12(
-    buf: &mut Vec<'_, u8>,
-    dst: AArch64GeneralReg,
-    base: AArch64GeneralReg,
-    imm12: u16,
-) {
-    let inst = LoadStoreRegisterImmediate::new_load(0b11, imm12, base, dst);
+impl Aarch64Bytes for UnconditionalBranchRegister {}
 
-    buf.extend(inst.bytes());
-}
+impl UnconditionalBranchRegister {
+    #[inline(always)]
+    fn new(op: u8, rn: AArch64GeneralReg) -> Self {
+        debug_assert!(op <= 0b11);
 
-/// `MOV Xd, Xm` -> Move Xm to Xd.
-#[inline(always)]
-fn mov_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
-    // MOV is equvalent to `ORR Xd, XZR, XM` in AARCH64.
-    let inst = LogicalShiftedRegister::new(
-        LogicalOp::ORR,
-        ShiftType::LSL,
-        0,
-        src,
-        AArch64GeneralReg::ZRSP,
-        dst,
-    );
+        Self {
+            fixed5: 0b00000.into(),
+            rn: rn.id().into(),
+            m: false,
+            a: false,
+            fixed4: 0b0000.into(),
+            fixed3: 0b11111.into(),
+            op: op.into(),
+            fixed2: false,
+            z: false,
+            fixed: 0b1101011.into(),
+        }
+    }
+}
 
-    buf.extend(inst.bytes());
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct UnconditionalBranchImmediate {
+    op: bool,
+    fixed: Integer<u8, packed_bits::Bits<5>>, // = 0b00101,
+    imm26: Integer<u32, packed_bits::Bits<26>>,
 }
 
-/// `MOVK Xd, imm16` -> Keeps Xd and moves an optionally shifted imm16 to Xd.
-#[inline(always)]
-fn movk_reg64_imm16(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, imm16: u16, hw: u8) {
-    let inst = MoveWideImmediate::new(0b11, dst, imm16, hw, true);
+impl Aarch64Bytes for UnconditionalBranchImmediate {}
 
-    buf.extend(inst.bytes());
-}
+impl UnconditionalBranchImmediate {
+    #[inline(always)]
+    fn new(op: bool, offset: i32) -> Self {
+        debug_assert!(offset % 4 == 0);
+        // The immediate is the offset in instructions.
+        // Masking keeps the two's complement representation of negative offsets.
+        let imm26 = ((offset >> 2) as u32) & 0x3FF_FFFF;
 
-/// `MOVZ Xd, imm16` -> Zeros Xd and moves an optionally shifted imm16 to Xd.
-#[inline(always)]
-fn movz_reg64_imm16(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, imm16: u16, hw: u8) {
-    let inst = MoveWideImmediate::new(0b10, dst, imm16, hw, true);
+        Self {
+            imm26: imm26.into(),
+            fixed: 0b00101.into(),
+            op,
+        }
+    }
+}
 
-    buf.extend(inst.bytes());
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct ConditionalBranchImmediate {
+    fixed: Integer<u8, packed_bits::Bits<7>>, // = 0b0101010,
+    o1: bool,
+    imm19: Integer<u32, packed_bits::Bits<19>>,
+    o0: bool,
+    cond: Integer<u8, packed_bits::Bits<4>>,
 }
 
-/// `STR Xt, [Xn, #offset]` -> Store Xt to Xn + Offset. ZRSP is SP.
-/// Note: imm12 is the offest divided by 8.
-#[inline(always)]
-fn str_reg64_reg64_imm12(
-    buf: &mut Vec<'_, u8>,
-    src: AArch64GeneralReg,
-    base: AArch64GeneralReg,
-    imm12: u16,
-) {
-    let inst = LoadStoreRegisterImmediate::new_store(0b11, imm12, base, src);
+impl Aarch64Bytes for ConditionalBranchImmediate {}
 
-    buf.extend(inst.bytes());
-}
+impl ConditionalBranchImmediate {
+    #[inline(always)]
+    fn new(cond: ConditionCode, offset: i32) -> Self {
+        debug_assert!(offset % 4 == 0);
+        // The immediate is the offset in instructions.
+        // Masking keeps the two's complement representation of negative offsets.
+        let imm19 = ((offset >> 2) as u32) & 0x7_FFFF;
 
-/// `SUB Xd, Xn, imm12` -> Subtract Xn and imm12 and place the result into Xd.
-#[inline(always)]
-fn sub_reg64_reg64_imm12(
-    buf: &mut Vec<'_, u8>,
-    dst: AArch64GeneralReg,
-    src: AArch64GeneralReg,
-    imm12: u16,
-) {
-    let inst = ArithmeticImmediate::new(true, false, dst, src, imm12, false);
+        Self {
+            cond: cond.id().into(),
+            o0: false,
+            imm19: imm19.into(),
+            o1: false,
+            fixed: 0b0101010.into(),
+        }
+    }
+}
 
-    buf.extend(inst.bytes());
+#[derive(Clone, Copy)]
+enum FloatType {
+    Single = 0b00,
+    Double = 0b01,
 }
 
-/// `RET Xn` -> Return to the address stored in Xn.
-#[inline(always)]
-fn ret_reg64(buf: &mut Vec<'_, u8>, xn: AArch64GeneralReg) {
-    let inst = UnconditionalBranchRegister::new(0b10, xn);
+impl FloatType {
+    #[inline(always)]
+    fn id(&self) -> u8 {
+        *self as u8
+    }
+}
 
-    buf.extend(inst.bytes());
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct FloatingPointDataProcessingOneSource {
+    m: bool,
+    fixed: bool, // = 0b0,
+    s: bool,
+    fixed2: Integer<u8, packed_bits::Bits<5>>, // = 0b11110,
+    ptype: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool, // = 0b1,
+    opcode: Integer<u8, packed_bits::Bits<6>>,
+    fixed4: Integer<u8, packed_bits::Bits<5>>, // = 0b10000,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
 }
 
-#[cfg(test)]
-mod tests {
+impl Aarch64Bytes for FloatingPointDataProcessingOneSource {}
+
+impl FloatingPointDataProcessingOneSource {
+    #[inline(always)]
+    fn new(ptype: FloatType, opcode: u8, rn: AArch64FloatReg, rd: AArch64FloatReg) -> Self {
+        debug_assert!(opcode <= 0b111111);
+
+        Self {
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            fixed4: 0b10000.into(),
+            opcode: opcode.into(),
+            fixed3: true,
+            ptype: ptype.id().into(),
+            fixed2: 0b11110.into(),
+            s: false,
+            fixed: false,
+            m: false,
+        }
+    }
+}
+
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct FloatingPointDataProcessingTwoSource {
+    m: bool,
+    fixed: bool, // = 0b0,
+    s: bool,
+    fixed2: Integer<u8, packed_bits::Bits<5>>, // = 0b11110,
+    ptype: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool, // = 0b1,
+    reg_m: Integer<u8, packed_bits::Bits<5>>,
+    opcode: Integer<u8, packed_bits::Bits<4>>,
+    fixed4: Integer<u8, packed_bits::Bits<2>>, // = 0b10,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for FloatingPointDataProcessingTwoSource {}
+
+impl FloatingPointDataProcessingTwoSource {
+    #[inline(always)]
+    fn new(
+        ptype: FloatType,
+        opcode: u8,
+        rm: AArch64FloatReg,
+        rn: AArch64FloatReg,
+        rd: AArch64FloatReg,
+    ) -> Self {
+        debug_assert!(opcode <= 0b1111);
+
+        Self {
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            fixed4: 0b10.into(),
+            opcode: opcode.into(),
+            reg_m: rm.id().into(),
+            fixed3: true,
+            ptype: ptype.id().into(),
+            fixed2: 0b11110.into(),
+            s: false,
+            fixed: false,
+            m: false,
+        }
+    }
+}
+
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct ConversionBetweenFloatingPointAndInteger {
+    sf: bool,
+    fixed: bool, // = 0b0,
+    s: bool,
+    fixed2: Integer<u8, packed_bits::Bits<5>>, // = 0b11110,
+    ptype: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool, // = 0b1,
+    rmode: Integer<u8, packed_bits::Bits<2>>,
+    opcode: Integer<u8, packed_bits::Bits<3>>,
+    fixed4: Integer<u8, packed_bits::Bits<6>>, // = 0b000000,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for ConversionBetweenFloatingPointAndInteger {}
+
+impl ConversionBetweenFloatingPointAndInteger {
+    /// Creates a conversion from a general register to a float register.
+    #[inline(always)]
+    fn new_to_float(
+        sf: bool,
+        ptype: FloatType,
+        rmode: u8,
+        opcode: u8,
+        rn: AArch64GeneralReg,
+        rd: AArch64FloatReg,
+    ) -> Self {
+        debug_assert!(rmode <= 0b11);
+        debug_assert!(opcode <= 0b111);
+
+        Self {
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            fixed4: 0b000000.into(),
+            opcode: opcode.into(),
+            rmode: rmode.into(),
+            fixed3: true,
+            ptype: ptype.id().into(),
+            fixed2: 0b11110.into(),
+            s: false,
+            fixed: false,
+            sf,
+        }
+    }
+}
+
+// Uses unsigned Offset
+// opc = 0b01 means load
+// opc = 0b00 means store
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct LoadStoreRegisterImmediate {
+    size: Integer<u8, packed_bits::Bits<2>>,
+    fixed: Integer<u8, packed_bits::Bits<3>>,  // = 0b111,
+    v: bool,                                   // true for float registers
+    fixed2: Integer<u8, packed_bits::Bits<2>>, // = 0b01,
+    opc: Integer<u8, packed_bits::Bits<2>>,
+    imm12: Integer<u16, packed_bits::Bits<12>>,
+    rn: Integer<u8, packed_bits::Bits<5>>,
+    rt: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for LoadStoreRegisterImmediate {}
+
+impl LoadStoreRegisterImmediate {
+    #[inline(always)]
+    fn new(size: u8, v: bool, opc: u8, imm12: u16, rn: AArch64GeneralReg, rt: u8) -> Self {
+        debug_assert!(size <= 0b11);
+        debug_assert!(imm12 <= 0xFFF);
+
+        Self {
+            rt: rt.into(),
+            rn: rn.id().into(),
+            imm12: imm12.into(),
+            opc: opc.into(),
+            fixed2: 0b01.into(),
+            v,
+            fixed: 0b111.into(),
+            size: size.into(),
+        }
+    }
+
+    #[inline(always)]
+    fn new_load(size: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64GeneralReg) -> Self {
+        Self::new(size, false, 0b01, imm12, rn, rt.id())
+    }
+
+    #[inline(always)]
+    fn new_store(size: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64GeneralReg) -> Self {
+        Self::new(size, false, 0b00, imm12, rn, rt.id())
+    }
+
+    #[inline(always)]
+    fn new_float_load(size: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64FloatReg) -> Self {
+        Self::new(size, true, 0b01, imm12, rn, rt.id())
+    }
+
+    #[inline(always)]
+    fn new_float_store(size: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64FloatReg) -> Self {
+        Self::new(size, true, 0b00, imm12, rn, rt.id())
+    }
+}
+
+// Uses a signed unscaled offset.
+// opc = 0b01 means load
+// opc = 0b00 means store
+// opc = 0b10 means load and sign extend to 64 bits
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct LoadStoreRegisterUnscaledImmediate {
+    size: Integer<u8, packed_bits::Bits<2>>,
+    fixed: Integer<u8, packed_bits::Bits<3>>,  // = 0b111,
+    v: bool,                                   // true for float registers
+    fixed2: Integer<u8, packed_bits::Bits<2>>, // = 0b00,
+    opc: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool, // = 0b0,
+    imm9: Integer<u16, packed_bits::Bits<9>>,
+    fixed4: Integer<u8, packed_bits::Bits<2>>, // = 0b00,
+    rn: Integer<u8, packed_bits::Bits<5>>,
+    rt: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for LoadStoreRegisterUnscaledImmediate {}
+
+impl LoadStoreRegisterUnscaledImmediate {
+    #[inline(always)]
+    fn new(size: u8, v: bool, opc: u8, imm9: i16, rn: AArch64GeneralReg, rt: u8) -> Self {
+        debug_assert!(size <= 0b11);
+        debug_assert!(opc <= 0b11);
+        debug_assert!((-0x100..0x100).contains(&imm9));
+
+        Self {
+            rt: rt.into(),
+            rn: rn.id().into(),
+            fixed4: 0b00.into(),
+            imm9: ((imm9 as u16) & 0x1FF).into(),
+            fixed3: false,
+            opc: opc.into(),
+            fixed2: 0b00.into(),
+            v,
+            fixed: 0b111.into(),
+            size: size.into(),
+        }
+    }
+
+    #[inline(always)]
+    fn new_general(
+        size: u8,
+        opc: u8,
+        imm9: i16,
+        rn: AArch64GeneralReg,
+        rt: AArch64GeneralReg,
+    ) -> Self {
+        Self::new(size, false, opc, imm9, rn, rt.id())
+    }
+
+    #[inline(always)]
+    fn new_float(size: u8, opc: u8, imm9: i16, rn: AArch64GeneralReg, rt: AArch64FloatReg) -> Self {
+        Self::new(size, true, opc, imm9, rn, rt.id())
+    }
+}
+
+// Below here are the functions for all of the assembly instructions.
+// Their names are based on the instruction and operators combined.
+// You should call `buf.reserve()` if you push or extend more than once.
+// Unit tests are added at the bottom of the file to ensure correct asm generation.
+// Please keep these in alphanumeric order.
+
+/// `ADD Xd, Xn, imm12` -> Add Xn and imm12 and place the result into Xd.
+#[inline(always)]
+fn add_reg64_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = ArithmeticImmediate::new(false, false, dst, src, imm12, false);
+
+    buf.extend(inst.bytes());
+}
+
+/// `ADD Xd, Xm, Xn` -> Add Xm and Xn and place the result into Xd.
+#[inline(always)]
+#[allow(dead_code)]
+fn add_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = ArithmeticShifted::new(false, false, ShiftType::LSL, 0, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `ADD Xd, Xn, Xm, UXTX` -> Add Xn and Xm and place the result into Xd.
+/// Unlike the shifted version, ZRSP is SP for Xd and Xn.
+#[inline(always)]
+fn add_reg64_reg64_reg64_extended(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = ArithmeticExtended::new(false, false, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `ADDS Xd, Xm, Xn` -> Add Xm and Xn, place the result into Xd, and set the flags.
+#[inline(always)]
+fn adds_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = ArithmeticShifted::new(false, true, ShiftType::LSL, 0, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `AND Xd, Xn, Xm` -> Bitwise and Xn and Xm and place the result into Xd.
+#[inline(always)]
+fn and_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = LogicalShiftedRegister::new(LogicalOp::AND, ShiftType::LSL, 0, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `B.cond offset` -> Branch by offset bytes if the condition holds.
+#[inline(always)]
+fn b_cond_imm19(buf: &mut Vec<'_, u8>, cond: ConditionCode, offset: i32) {
+    let inst = ConditionalBranchImmediate::new(cond, offset);
+
+    buf.extend(inst.bytes());
+}
+
+/// `B offset` -> Branch by offset bytes.
+#[inline(always)]
+fn b_imm26(buf: &mut Vec<'_, u8>, offset: i32) {
+    let inst = UnconditionalBranchImmediate::new(false, offset);
+
+    buf.extend(inst.bytes());
+}
+
+/// `BL offset` -> Branch by offset bytes and store the return address in LR.
+#[inline(always)]
+fn bl_imm26(buf: &mut Vec<'_, u8>, offset: i32) {
+    let inst = UnconditionalBranchImmediate::new(true, offset);
+
+    buf.extend(inst.bytes());
+}
+
+/// `CMP Xn, imm12` -> Compare Xn and imm12 and set the flags. ZRSP is SP.
+#[inline(always)]
+fn cmp_reg64_imm12(buf: &mut Vec<'_, u8>, src: AArch64GeneralReg, imm12: u16) {
+    // CMP is equivalent to `SUBS XZR, Xn, imm12`.
+    let inst = ArithmeticImmediate::new(true, true, AArch64GeneralReg::ZRSP, src, imm12, false);
+
+    buf.extend(inst.bytes());
+}
+
+/// `CMP Xn, Xm` -> Compare Xn and Xm and set the flags.
+#[inline(always)]
+fn cmp_reg64_reg64(buf: &mut Vec<'_, u8>, src1: AArch64GeneralReg, src2: AArch64GeneralReg) {
+    // CMP is equivalent to `SUBS XZR, Xn, Xm`.
+    let inst = ArithmeticShifted::new(
+        true,
+        true,
+        ShiftType::LSL,
+        0,
+        src2,
+        src1,
+        AArch64GeneralReg::ZRSP,
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `CNEG Xd, Xn, cond` -> Place the negation of Xn into Xd if the condition holds, otherwise place Xn into Xd.
+#[inline(always)]
+fn cneg_reg64_reg64_cond(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src: AArch64GeneralReg,
+    cond: ConditionCode,
+) {
+    // CNEG is equivalent to `CSNEG Xd, Xn, Xn, invert(cond)`.
+    let inst = ConditionalSelect::new(true, true, cond.invert_id(), src, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `CSET Xd, cond` -> Set Xd to 1 if the condition holds, otherwise set it to 0.
+#[inline(always)]
+fn cset_reg64_cond(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, cond: ConditionCode) {
+    // CSET is equivalent to `CSINC Xd, XZR, XZR, invert(cond)`.
+    let inst = ConditionalSelect::new(
+        false,
+        true,
+        cond.invert_id(),
+        AArch64GeneralReg::ZRSP,
+        AArch64GeneralReg::ZRSP,
+        dst,
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `EOR Xd, Xn, Xm` -> Bitwise exclusive or Xn and Xm and place the result into Xd.
+#[inline(always)]
+fn eor_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = LogicalShiftedRegister::new(LogicalOp::EOR, ShiftType::LSL, 0, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FABS Dd, Dn` -> Place the absolute value of Dn into Dd.
+#[inline(always)]
+fn fabs_freg64_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+    let inst = FloatingPointDataProcessingOneSource::new(FloatType::Double, 0b000001, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FADD Sd, Sn, Sm` -> Add Sn and Sm and place the result into Sd.
+#[inline(always)]
+fn fadd_freg32_freg32_freg32(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Single, 0b0010, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FADD Dd, Dn, Dm` -> Add Dn and Dm and place the result into Dd.
+#[inline(always)]
+fn fadd_freg64_freg64_freg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Double, 0b0010, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FCVT Sd, Dn` -> Convert the 64 bit float Dn to a 32 bit float and place the result into Sd.
+#[inline(always)]
+fn fcvt_freg32_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+    let inst = FloatingPointDataProcessingOneSource::new(FloatType::Double, 0b000100, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FCVT Dd, Sn` -> Convert the 32 bit float Sn to a 64 bit float and place the result into Dd.
+#[inline(always)]
+fn fcvt_freg64_freg32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+    let inst = FloatingPointDataProcessingOneSource::new(FloatType::Single, 0b000101, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FDIV Sd, Sn, Sm` -> Divide Sn by Sm and place the result into Sd.
+#[inline(always)]
+fn fdiv_freg32_freg32_freg32(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Single, 0b0001, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FDIV Dd, Dn, Dm` -> Divide Dn by Dm and place the result into Dd.
+#[inline(always)]
+fn fdiv_freg64_freg64_freg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Double, 0b0001, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FMOV Sd, Wn` -> Move the low 32 bits of Xn to Sd.
+#[inline(always)]
+fn fmov_freg32_reg32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+    let inst = ConversionBetweenFloatingPointAndInteger::new_to_float(
+        false,
+        FloatType::Single,
+        0b00,
+        0b111,
+        src,
+        dst,
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `FMOV Dd, Dn` -> Move Dn to Dd.
+#[inline(always)]
+fn fmov_freg64_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+    let inst = FloatingPointDataProcessingOneSource::new(FloatType::Double, 0b000000, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FMOV Dd, Xn` -> Move the bits of Xn to Dd.
+#[inline(always)]
+fn fmov_freg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+    let inst = ConversionBetweenFloatingPointAndInteger::new_to_float(
+        true,
+        FloatType::Double,
+        0b00,
+        0b111,
+        src,
+        dst,
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `FMUL Sd, Sn, Sm` -> Multiply Sn and Sm and place the result into Sd.
+#[inline(always)]
+fn fmul_freg32_freg32_freg32(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Single, 0b0000, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FMUL Dd, Dn, Dm` -> Multiply Dn and Dm and place the result into Dd.
+#[inline(always)]
+fn fmul_freg64_freg64_freg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Double, 0b0000, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDR Dt, [Xn, #offset]` -> Load Xn + Offset to Dt. ZRSP is SP.
+/// Note: imm12 is the offest divided by 8.
+#[inline(always)]
+fn ldr_freg64_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    base: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = LoadStoreRegisterImmediate::new_float_load(0b11, imm12, base, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDR Xt, [Xn, #offset]` -> Load Xn + Offset Xt. ZRSP is SP.
+/// Note: imm12 is the offest divided by 8.
+#[inline(always)]
+fn ldr_reg64_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = LoadStoreRegisterImmediate::new_load(0b11, imm12, base, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDUR Dt, [Xn, #offset]` -> Load Xn + Offset to Dt. ZRSP is SP.
+#[inline(always)]
+fn ldur_freg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new_float(0b11, 0b01, imm9, base, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDUR Wt, [Xn, #offset]` -> Load 32 bits from Xn + Offset to Xt, zero extending them. ZRSP is SP.
+#[inline(always)]
+fn ldur_reg32_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new_general(0b10, 0b01, imm9, base, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDUR Xt, [Xn, #offset]` -> Load Xn + Offset to Xt. ZRSP is SP.
+#[inline(always)]
+fn ldur_reg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new_general(0b11, 0b01, imm9, base, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDURB Wt, [Xn, #offset]` -> Load a byte from Xn + Offset to Xt, zero extending it. ZRSP is SP.
+#[inline(always)]
+fn ldurb_reg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new_general(0b00, 0b01, imm9, base, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDURH Wt, [Xn, #offset]` -> Load 16 bits from Xn + Offset to Xt, zero extending them. ZRSP is SP.
+#[inline(always)]
+fn ldurh_reg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new_general(0b01, 0b01, imm9, base, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDURSB Xt, [Xn, #offset]` -> Load a byte from Xn + Offset to Xt, sign extending it. ZRSP is SP.
+#[inline(always)]
+fn ldursb_reg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new_general(0b00, 0b10, imm9, base, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDURSH Xt, [Xn, #offset]` -> Load 16 bits from Xn + Offset to Xt, sign extending them. ZRSP is SP.
+#[inline(always)]
+fn ldursh_reg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new_general(0b01, 0b10, imm9, base, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDURSW Xt, [Xn, #offset]` -> Load 32 bits from Xn + Offset to Xt, sign extending them. ZRSP is SP.
+#[inline(always)]
+fn ldursw_reg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new_general(0b10, 0b10, imm9, base, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `MOV Xd, Xm` -> Move Xm to Xd.
+#[inline(always)]
+fn mov_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
+    // MOV is equvalent to `ORR Xd, XZR, XM` in AARCH64.
+    let inst = LogicalShiftedRegister::new(
+        LogicalOp::ORR,
+        ShiftType::LSL,
+        0,
+        src,
+        AArch64GeneralReg::ZRSP,
+        dst,
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `MOVK Xd, imm16` -> Keeps Xd and moves an optionally shifted imm16 to Xd.
+#[inline(always)]
+fn movk_reg64_imm16(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, imm16: u16, hw: u8) {
+    let inst = MoveWideImmediate::new(0b11, dst, imm16, hw, true);
+
+    buf.extend(inst.bytes());
+}
+
+/// `MOVZ Xd, imm16` -> Zeros Xd and moves an optionally shifted imm16 to Xd.
+#[inline(always)]
+fn movz_reg64_imm16(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, imm16: u16, hw: u8) {
+    let inst = MoveWideImmediate::new(0b10, dst, imm16, hw, true);
+
+    buf.extend(inst.bytes());
+}
+
+/// `MUL Xd, Xn, Xm` -> Multiply Xn and Xm and place the low 64 bits of the result into Xd.
+#[inline(always)]
+fn mul_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    // MUL is equivalent to `MADD Xd, Xn, Xm, XZR`.
+    let inst =
+        DataProcessingThreeSource::new(0b000, false, src2, AArch64GeneralReg::ZRSP, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `NEG Xd, Xm` -> Negate Xm and place the result into Xd.
+#[inline(always)]
+fn neg_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
+    // NEG is equivalent to `SUB Xd, XZR, Xm`.
+    sub_reg64_reg64_reg64(buf, dst, AArch64GeneralReg::ZRSP, src);
+}
+
+/// `ORR Xd, Xn, Xm` -> Bitwise or Xn and Xm and place the result into Xd.
+#[inline(always)]
+fn orr_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = LogicalShiftedRegister::new(LogicalOp::ORR, ShiftType::LSL, 0, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `SCVTF Sd, Xn` -> Convert the signed integer Xn to a 32 bit float and place the result into Sd.
+#[inline(always)]
+fn scvtf_freg32_reg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+    let inst = ConversionBetweenFloatingPointAndInteger::new_to_float(
+        true,
+        FloatType::Single,
+        0b00,
+        0b010,
+        src,
+        dst,
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `SCVTF Dd, Xn` -> Convert the signed integer Xn to a 64 bit float and place the result into Dd.
+#[inline(always)]
+fn scvtf_freg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+    let inst = ConversionBetweenFloatingPointAndInteger::new_to_float(
+        true,
+        FloatType::Double,
+        0b00,
+        0b010,
+        src,
+        dst,
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `SDIV Xd, Xn, Xm` -> Divide the signed Xn by Xm and place the result into Xd.
+#[inline(always)]
+fn sdiv_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = DataProcessingTwoSource::new(0b000011, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `STR Dt, [Xn, #offset]` -> Store Dt to Xn + Offset. ZRSP is SP.
+/// Note: imm12 is the offest divided by 8.
+#[inline(always)]
+fn str_freg64_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    src: AArch64FloatReg,
+    base: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = LoadStoreRegisterImmediate::new_float_store(0b11, imm12, base, src);
+
+    buf.extend(inst.bytes());
+}
+
+/// `STR Xt, [Xn, #offset]` -> Store Xt to Xn + Offset. ZRSP is SP.
+/// Note: imm12 is the offest divided by 8.
+#[inline(always)]
+fn str_reg64_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    src: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = LoadStoreRegisterImmediate::new_store(0b11, imm12, base, src);
+
+    buf.extend(inst.bytes());
+}
+
+/// `STUR Dt, [Xn, #offset]` -> Store Dt to Xn + Offset. ZRSP is SP.
+#[inline(always)]
+fn stur_freg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    src: AArch64FloatReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new_float(0b11, 0b00, imm9, base, src);
+
+    buf.extend(inst.bytes());
+}
+
+/// `STUR Xt, [Xn, #offset]` -> Store Xt to Xn + Offset. ZRSP is SP.
+#[inline(always)]
+fn stur_reg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    src: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new_general(0b11, 0b00, imm9, base, src);
+
+    buf.extend(inst.bytes());
+}
+
+/// `SUB Xd, Xn, imm12` -> Subtract Xn and imm12 and place the result into Xd.
+#[inline(always)]
+fn sub_reg64_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = ArithmeticImmediate::new(true, false, dst, src, imm12, false);
+
+    buf.extend(inst.bytes());
+}
+
+/// `SUB Xd, Xn, Xm` -> Subtract Xm from Xn and place the result into Xd.
+#[inline(always)]
+fn sub_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = ArithmeticShifted::new(true, false, ShiftType::LSL, 0, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `UDIV Xd, Xn, Xm` -> Divide the unsigned Xn by Xm and place the result into Xd.
+#[inline(always)]
+fn udiv_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = DataProcessingTwoSource::new(0b000010, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `RET Xn` -> Return to the address stored in Xn.
+#[inline(always)]
+fn ret_reg64(buf: &mut Vec<'_, u8>, xn: AArch64GeneralReg) {
+    let inst = UnconditionalBranchRegister::new(0b10, xn);
+
+    buf.extend(inst.bytes());
+}
+
+#[cfg(test)]
+mod tests {
     use super::*;
     use crate::disassembler_test;
     use capstone::prelude::*;
 
-    enum ZRSPKind {
-        UsesZR,
-        UsesSP,
+    enum ZRSPKind {
+        UsesZR,
+        UsesSP,
+    }
+    use ZRSPKind::*;
+
+    impl AArch64GeneralReg {
+        fn capstone_string(&self, zrsp_kind: ZRSPKind) -> String {
+            match self {
+                AArch64GeneralReg::XR => "x8".to_owned(),
+                AArch64GeneralReg::IP0 => "x16".to_owned(),
+                AArch64GeneralReg::IP1 => "x17".to_owned(),
+                AArch64GeneralReg::PR => "x18".to_owned(),
+                AArch64GeneralReg::FP => "x29".to_owned(),
+                AArch64GeneralReg::LR => "x30".to_owned(),
+                AArch64GeneralReg::ZRSP => match zrsp_kind {
+                    UsesZR => "xzr".to_owned(),
+                    UsesSP => "sp".to_owned(),
+                },
+                _ => format!("{}", self),
+            }
+        }
+
+        fn capstone_string_32bit(&self, zrsp_kind: ZRSPKind) -> String {
+            match self {
+                AArch64GeneralReg::ZRSP => match zrsp_kind {
+                    UsesZR => "wzr".to_owned(),
+                    UsesSP => "wsp".to_owned(),
+                },
+                _ => format!("w{}", self.id()),
+            }
+        }
+    }
+
+    impl AArch64FloatReg {
+        fn capstone_string(&self, float_width: FloatWidth) -> String {
+            match float_width {
+                FloatWidth::F32 => format!("s{}", self.id()),
+                FloatWidth::F64 => format!("d{}", self.id()),
+                FloatWidth::F128 => format!("q{}", self.id()),
+            }
+        }
+    }
+
+    impl ConditionCode {
+        fn capstone_string(&self) -> String {
+            format!("{:?}", self).to_lowercase()
+        }
+    }
+
+    /// Formats an unscaled offset the way capstone prints it in a memory operand.
+    fn capstone_imm9(imm9: i16) -> String {
+        if imm9 < 0 {
+            format!("#-0x{:x}", -imm9)
+        } else {
+            format!("#0x{:x}", imm9)
+        }
+    }
+
+    const TEST_U16: u16 = 0x1234;
+    //const TEST_I32: i32 = 0x12345678;
+    //const TEST_I64: i64 = 0x12345678_9ABCDEF0;
+
+    const ALL_GENERAL_REGS: &[AArch64GeneralReg] = &[
+        AArch64GeneralReg::X0,
+        AArch64GeneralReg::X1,
+        AArch64GeneralReg::X2,
+        AArch64GeneralReg::X3,
+        AArch64GeneralReg::X4,
+        AArch64GeneralReg::X5,
+        AArch64GeneralReg::X6,
+        AArch64GeneralReg::X7,
+        AArch64GeneralReg::XR,
+        AArch64GeneralReg::X9,
+        AArch64GeneralReg::X10,
+        AArch64GeneralReg::X11,
+        AArch64GeneralReg::X12,
+        AArch64GeneralReg::X13,
+        AArch64GeneralReg::X14,
+        AArch64GeneralReg::X15,
+        AArch64GeneralReg::IP0,
+        AArch64GeneralReg::IP1,
+        AArch64GeneralReg::PR,
+        AArch64GeneralReg::X19,
+        AArch64GeneralReg::X20,
+        AArch64GeneralReg::X21,
+        AArch64GeneralReg::X22,
+        AArch64GeneralReg::X23,
+        AArch64GeneralReg::X24,
+        AArch64GeneralReg::X25,
+        AArch64GeneralReg::X26,
+        AArch64GeneralReg::X27,
+        AArch64GeneralReg::X28,
+        AArch64GeneralReg::FP,
+        AArch64GeneralReg::LR,
+        AArch64GeneralReg::ZRSP,
+    ];
+
+    const ALL_FLOAT_REGS: &[AArch64FloatReg] = &[
+        AArch64FloatReg::V0,
+        AArch64FloatReg::V1,
+        AArch64FloatReg::V2,
+        AArch64FloatReg::V3,
+        AArch64FloatReg::V4,
+        AArch64FloatReg::V5,
+        AArch64FloatReg::V6,
+        AArch64FloatReg::V7,
+        AArch64FloatReg::V8,
+        AArch64FloatReg::V9,
+        AArch64FloatReg::V10,
+        AArch64FloatReg::V11,
+        AArch64FloatReg::V12,
+        AArch64FloatReg::V13,
+        AArch64FloatReg::V14,
+        AArch64FloatReg::V15,
+        AArch64FloatReg::V16,
+        AArch64FloatReg::V17,
+        AArch64FloatReg::V18,
+        AArch64FloatReg::V19,
+        AArch64FloatReg::V20,
+        AArch64FloatReg::V21,
+        AArch64FloatReg::V22,
+        AArch64FloatReg::V23,
+        AArch64FloatReg::V24,
+        AArch64FloatReg::V25,
+        AArch64FloatReg::V26,
+        AArch64FloatReg::V27,
+        AArch64FloatReg::V28,
+        AArch64FloatReg::V29,
+        AArch64FloatReg::V30,
+        AArch64FloatReg::V31,
+    ];
+
+    // AL and NV are left out because they have no inverse.
+    const ALL_CONDITION_CODES: &[ConditionCode] = &[
+        ConditionCode::EQ,
+        ConditionCode::NE,
+        ConditionCode::HS,
+        ConditionCode::LO,
+        ConditionCode::MI,
+        ConditionCode::PL,
+        ConditionCode::VS,
+        ConditionCode::VC,
+        ConditionCode::HI,
+        ConditionCode::LS,
+        ConditionCode::GE,
+        ConditionCode::LT,
+        ConditionCode::GT,
+        ConditionCode::LE,
+    ];
+
+    fn setup_capstone_and_arena<T>(
+        arena: &bumpalo::Bump,
+    ) -> (bumpalo::collections::Vec<T>, Capstone) {
+        let buf = bumpalo::vec![in arena];
+        let cs = Capstone::new()
+            .arm64()
+            .mode(arch::arm64::ArchMode::Arm)
+            .detail(true)
+            .build()
+            .expect("Failed to create Capstone object");
+        (buf, cs)
+    }
+
+    #[test]
+    fn test_add_reg64_reg64_reg64() {
+        disassembler_test!(
+            add_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
+                "add {}, {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                reg3.capstone_string(UsesZR)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_add_reg64_reg64_reg64_extended() {
+        disassembler_test!(
+            add_reg64_reg64_reg64_extended,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| {
+                if reg1 == AArch64GeneralReg::ZRSP || reg2 == AArch64GeneralReg::ZRSP {
+                    // With the stack pointer, UXTX is printed as `LSL #0`, which gets omitted.
+                    format!(
+                        "add {}, {}, {}",
+                        reg1.capstone_string(UsesSP),
+                        reg2.capstone_string(UsesSP),
+                        reg3.capstone_string(UsesZR)
+                    )
+                } else {
+                    format!(
+                        "add {}, {}, {}, uxtx",
+                        reg1.capstone_string(UsesSP),
+                        reg2.capstone_string(UsesSP),
+                        reg3.capstone_string(UsesZR)
+                    )
+                }
+            },
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_add_reg64_reg64_imm12() {
+        disassembler_test!(
+            add_reg64_reg64_imm12,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "add {}, {}, #0x{:x}",
+                reg1.capstone_string(UsesSP),
+                reg2.capstone_string(UsesSP),
+                imm
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
+    #[test]
+    fn test_adds_reg64_reg64_reg64() {
+        disassembler_test!(
+            adds_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| {
+                if reg1 == AArch64GeneralReg::ZRSP {
+                    format!(
+                        "cmn {}, {}",
+                        reg2.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesZR)
+                    )
+                } else {
+                    format!(
+                        "adds {}, {}, {}",
+                        reg1.capstone_string(UsesZR),
+                        reg2.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesZR)
+                    )
+                }
+            },
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_and_reg64_reg64_reg64() {
+        disassembler_test!(
+            and_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
+                "and {}, {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                reg3.capstone_string(UsesZR)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_b_cond_imm19() {
+        disassembler_test!(
+            b_cond_imm19,
+            |cond: ConditionCode, offset| format!("b.{} #0x{:x}", cond.capstone_string(), offset),
+            ALL_CONDITION_CODES,
+            [0x10, 0x1234]
+        );
+    }
+
+    #[test]
+    fn test_b_imm26() {
+        disassembler_test!(
+            b_imm26,
+            |offset| format!("b #0x{:x}", offset),
+            [0x10, 0x123_4564]
+        );
+    }
+
+    #[test]
+    fn test_bl_imm26() {
+        disassembler_test!(
+            bl_imm26,
+            |offset| format!("bl #0x{:x}", offset),
+            [0x10, 0x123_4564]
+        );
+    }
+
+    #[test]
+    fn test_cmp_reg64_imm12() {
+        disassembler_test!(
+            cmp_reg64_imm12,
+            |reg1: AArch64GeneralReg, imm| format!(
+                "cmp {}, #0x{:x}",
+                reg1.capstone_string(UsesSP),
+                imm
+            ),
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
+    #[test]
+    fn test_cmp_reg64_reg64() {
+        disassembler_test!(
+            cmp_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg| format!(
+                "cmp {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR)
+            ),
+            // With XZR as the first operand, this is printed as `negs`.
+            &ALL_GENERAL_REGS[..31],
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_cneg_reg64_reg64_cond() {
+        disassembler_test!(
+            cneg_reg64_reg64_cond,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, cond: ConditionCode| format!(
+                "cneg {}, {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                cond.capstone_string()
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_CONDITION_CODES
+        );
+    }
+
+    #[test]
+    fn test_cset_reg64_cond() {
+        disassembler_test!(
+            cset_reg64_cond,
+            |reg1: AArch64GeneralReg, cond: ConditionCode| format!(
+                "cset {}, {}",
+                reg1.capstone_string(UsesZR),
+                cond.capstone_string()
+            ),
+            ALL_GENERAL_REGS,
+            ALL_CONDITION_CODES
+        );
+    }
+
+    #[test]
+    fn test_eor_reg64_reg64_reg64() {
+        disassembler_test!(
+            eor_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
+                "eor {}, {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                reg3.capstone_string(UsesZR)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_fabs_freg64_freg64() {
+        disassembler_test!(
+            fabs_freg64_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fabs {}, {}",
+                reg1.capstone_string(FloatWidth::F64),
+                reg2.capstone_string(FloatWidth::F64)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fadd_freg32_freg32_freg32() {
+        disassembler_test!(
+            fadd_freg32_freg32_freg32,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fadd {}, {}, {}",
+                reg1.capstone_string(FloatWidth::F32),
+                reg2.capstone_string(FloatWidth::F32),
+                reg3.capstone_string(FloatWidth::F32)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fadd_freg64_freg64_freg64() {
+        disassembler_test!(
+            fadd_freg64_freg64_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fadd {}, {}, {}",
+                reg1.capstone_string(FloatWidth::F64),
+                reg2.capstone_string(FloatWidth::F64),
+                reg3.capstone_string(FloatWidth::F64)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fcvt_freg32_freg64() {
+        disassembler_test!(
+            fcvt_freg32_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fcvt {}, {}",
+                reg1.capstone_string(FloatWidth::F32),
+                reg2.capstone_string(FloatWidth::F64)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fcvt_freg64_freg32() {
+        disassembler_test!(
+            fcvt_freg64_freg32,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fcvt {}, {}",
+                reg1.capstone_string(FloatWidth::F64),
+                reg2.capstone_string(FloatWidth::F32)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fdiv_freg32_freg32_freg32() {
+        disassembler_test!(
+            fdiv_freg32_freg32_freg32,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fdiv {}, {}, {}",
+                reg1.capstone_string(FloatWidth::F32),
+                reg2.capstone_string(FloatWidth::F32),
+                reg3.capstone_string(FloatWidth::F32)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fdiv_freg64_freg64_freg64() {
+        disassembler_test!(
+            fdiv_freg64_freg64_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fdiv {}, {}, {}",
+                reg1.capstone_string(FloatWidth::F64),
+                reg2.capstone_string(FloatWidth::F64),
+                reg3.capstone_string(FloatWidth::F64)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fmov_freg32_reg32() {
+        disassembler_test!(
+            fmov_freg32_reg32,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg| format!(
+                "fmov {}, {}",
+                reg1.capstone_string(FloatWidth::F32),
+                reg2.capstone_string_32bit(UsesZR)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_fmov_freg64_freg64() {
+        disassembler_test!(
+            fmov_freg64_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fmov {}, {}",
+                reg1.capstone_string(FloatWidth::F64),
+                reg2.capstone_string(FloatWidth::F64)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fmov_freg64_reg64() {
+        disassembler_test!(
+            fmov_freg64_reg64,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg| format!(
+                "fmov {}, {}",
+                reg1.capstone_string(FloatWidth::F64),
+                reg2.capstone_string(UsesZR)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_fmul_freg32_freg32_freg32() {
+        disassembler_test!(
+            fmul_freg32_freg32_freg32,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fmul {}, {}, {}",
+                reg1.capstone_string(FloatWidth::F32),
+                reg2.capstone_string(FloatWidth::F32),
+                reg3.capstone_string(FloatWidth::F32)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fmul_freg64_freg64_freg64() {
+        disassembler_test!(
+            fmul_freg64_freg64_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fmul {}, {}, {}",
+                reg1.capstone_string(FloatWidth::F64),
+                reg2.capstone_string(FloatWidth::F64),
+                reg3.capstone_string(FloatWidth::F64)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_ldr_freg64_reg64_imm12() {
+        disassembler_test!(
+            ldr_freg64_reg64_imm12,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg, imm| format!(
+                "ldr {}, [{}, #0x{:x}]",
+                reg1.capstone_string(FloatWidth::F64),
+                reg2.capstone_string(UsesSP),
+                imm << 3
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
+    #[test]
+    fn test_ldr_reg64_reg64_imm12() {
+        disassembler_test!(
+            ldr_reg64_reg64_imm12,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "ldr {}, [{}, #0x{:x}]",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesSP),
+                imm << 3
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
+    #[test]
+    fn test_ldur_freg64_reg64_imm9() {
+        disassembler_test!(
+            ldur_freg64_reg64_imm9,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg, imm| format!(
+                "ldur {}, [{}, {}]",
+                reg1.capstone_string(FloatWidth::F64),
+                reg2.capstone_string(UsesSP),
+                capstone_imm9(imm)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS,
+            [-0x10, 0x7c]
+        );
     }
-    use ZRSPKind::*;
 
-    impl AArch64GeneralReg {
-        fn capstone_string(&self, zrsp_kind: ZRSPKind) -> String {
-            match self {
-                AArch64GeneralReg::XR => "x8".to_owned(),
-                AArch64GeneralReg::IP0 => "x16".to_owned(),
-                AArch64GeneralReg::IP1 => "x17".to_owned(),
-                AArch64GeneralReg::PR => "x18".to_owned(),
-                AArch64GeneralReg::FP => "x29".to_owned(),
-                AArch64GeneralReg::LR => "x30".to_owned(),
-                AArch64GeneralReg::ZRSP => match zrsp_kind {
-                    UsesZR => "xzr".to_owned(),
-                    UsesSP => "sp".to_owned(),
-                },
-                _ => format!("{}", self),
-            }
-        }
+    #[test]
+    fn test_ldur_reg32_reg64_imm9() {
+        disassembler_test!(
+            ldur_reg32_reg64_imm9,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "ldur {}, [{}, {}]",
+                reg1.capstone_string_32bit(UsesZR),
+                reg2.capstone_string(UsesSP),
+                capstone_imm9(imm)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [-0x10, 0x7c]
+        );
     }
 
-    const TEST_U16: u16 = 0x1234;
-    //const TEST_I32: i32 = 0x12345678;
-    //const TEST_I64: i64 = 0x12345678_9ABCDEF0;
+    #[test]
+    fn test_ldur_reg64_reg64_imm9() {
+        disassembler_test!(
+            ldur_reg64_reg64_imm9,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "ldur {}, [{}, {}]",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesSP),
+                capstone_imm9(imm)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [-0x10, 0x7c]
+        );
+    }
 
-    const ALL_GENERAL_REGS: &[AArch64GeneralReg] = &[
-        AArch64GeneralReg::X0,
-        AArch64GeneralReg::X1,
-        AArch64GeneralReg::X2,
-        AArch64GeneralReg::X3,
-        AArch64GeneralReg::X4,
-        AArch64GeneralReg::X5,
-        AArch64GeneralReg::X6,
-        AArch64GeneralReg::X7,
-        AArch64GeneralReg::XR,
-        AArch64GeneralReg::X9,
-        AArch64GeneralReg::X10,
-        AArch64GeneralReg::X11,
-        AArch64GeneralReg::X12,
-        AArch64GeneralReg::X13,
-        AArch64GeneralReg::X14,
-        AArch64GeneralReg::X15,
-        AArch64GeneralReg::IP0,
-        AArch64GeneralReg::IP1,
-        AArch64GeneralReg::PR,
-        AArch64GeneralReg::X19,
-        AArch64GeneralReg::X20,
-        AArch64GeneralReg::X21,
-        AArch64GeneralReg::X22,
-        AArch64GeneralReg::X23,
-        AArch64GeneralReg::X24,
-        AArch64GeneralReg::X25,
-        AArch64GeneralReg::X26,
-        AArch64GeneralReg::X27,
-        AArch64GeneralReg::X28,
-        AArch64GeneralReg::FP,
-        AArch64GeneralReg::LR,
-        AArch64GeneralReg::ZRSP,
-    ];
+    #[test]
+    fn test_ldurb_reg64_reg64_imm9() {
+        disassembler_test!(
+            ldurb_reg64_reg64_imm9,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "ldurb {}, [{}, {}]",
+                reg1.capstone_string_32bit(UsesZR),
+                reg2.capstone_string(UsesSP),
+                capstone_imm9(imm)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [-0x10, 0x7c]
+        );
+    }
 
-    fn setup_capstone_and_arena<T>(
-        arena: &bumpalo::Bump,
-    ) -> (bumpalo::collections::Vec<T>, Capstone) {
-        let buf = bumpalo::vec![in arena];
-        let cs = Capstone::new()
-            .arm64()
-            .mode(arch::arm64::ArchMode::Arm)
-            .detail(true)
-            .build()
-            .expect("Failed to create Capstone object");
-        (buf, cs)
+    #[test]
+    fn test_ldurh_reg64_reg64_imm9() {
+        disassembler_test!(
+            ldurh_reg64_reg64_imm9,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "ldurh {}, [{}, {}]",
+                reg1.capstone_string_32bit(UsesZR),
+                reg2.capstone_string(UsesSP),
+                capstone_imm9(imm)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [-0x10, 0x7c]
+        );
     }
 
     #[test]
-    fn test_add_reg64_reg64_reg64() {
+    fn test_ldursb_reg64_reg64_imm9() {
         disassembler_test!(
-            add_reg64_reg64_reg64,
-            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
-                "add {}, {}, {}",
+            ldursb_reg64_reg64_imm9,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "ldursb {}, [{}, {}]",
                 reg1.capstone_string(UsesZR),
-                reg2.capstone_string(UsesZR),
-                reg3.capstone_string(UsesZR)
+                reg2.capstone_string(UsesSP),
+                capstone_imm9(imm)
             ),
             ALL_GENERAL_REGS,
             ALL_GENERAL_REGS,
-            ALL_GENERAL_REGS
+            [-0x10, 0x7c]
         );
     }
 
     #[test]
-    fn test_add_reg64_reg64_imm12() {
+    fn test_ldursh_reg64_reg64_imm9() {
         disassembler_test!(
-            add_reg64_reg64_imm12,
+            ldursh_reg64_reg64_imm9,
             |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
-                "add {}, {}, #0x{:x}",
-                reg1.capstone_string(UsesSP),
+                "ldursh {}, [{}, {}]",
+                reg1.capstone_string(UsesZR),
                 reg2.capstone_string(UsesSP),
-                imm
+                capstone_imm9(imm)
             ),
             ALL_GENERAL_REGS,
             ALL_GENERAL_REGS,
-            [0x123]
+            [-0x10, 0x7c]
         );
     }
 
     #[test]
-    fn test_ldr_reg64_reg64_imm12() {
+    fn test_ldursw_reg64_reg64_imm9() {
         disassembler_test!(
-            ldr_reg64_reg64_imm12,
+            ldursw_reg64_reg64_imm9,
             |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
-                "ldr {}, [{}, #0x{:x}]",
+                "ldursw {}, [{}, {}]",
                 reg1.capstone_string(UsesZR),
                 reg2.capstone_string(UsesSP),
-                imm << 3
+                capstone_imm9(imm)
             ),
             ALL_GENERAL_REGS,
             ALL_GENERAL_REGS,
-            [0x123]
+            [-0x10, 0x7c]
         );
     }
 
@@ -1465,6 +3527,122 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_mul_reg64_reg64_reg64() {
+        disassembler_test!(
+            mul_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
+                "mul {}, {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                reg3.capstone_string(UsesZR)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_neg_reg64_reg64() {
+        disassembler_test!(
+            neg_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg| format!(
+                "neg {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_orr_reg64_reg64_reg64() {
+        disassembler_test!(
+            orr_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| {
+                if reg2 == AArch64GeneralReg::ZRSP {
+                    format!(
+                        "mov {}, {}",
+                        reg1.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesZR)
+                    )
+                } else {
+                    format!(
+                        "orr {}, {}, {}",
+                        reg1.capstone_string(UsesZR),
+                        reg2.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesZR)
+                    )
+                }
+            },
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_scvtf_freg32_reg64() {
+        disassembler_test!(
+            scvtf_freg32_reg64,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg| format!(
+                "scvtf {}, {}",
+                reg1.capstone_string(FloatWidth::F32),
+                reg2.capstone_string(UsesZR)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_scvtf_freg64_reg64() {
+        disassembler_test!(
+            scvtf_freg64_reg64,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg| format!(
+                "scvtf {}, {}",
+                reg1.capstone_string(FloatWidth::F64),
+                reg2.capstone_string(UsesZR)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_sdiv_reg64_reg64_reg64() {
+        disassembler_test!(
+            sdiv_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
+                "sdiv {}, {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                reg3.capstone_string(UsesZR)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_str_freg64_reg64_imm12() {
+        disassembler_test!(
+            str_freg64_reg64_imm12,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg, imm| format!(
+                "str {}, [{}, #0x{:x}]",
+                reg1.capstone_string(FloatWidth::F64),
+                reg2.capstone_string(UsesSP),
+                imm << 3
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
     #[test]
     fn test_str_reg64_reg64_imm12() {
         disassembler_test!(
@@ -1481,6 +3659,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_stur_freg64_reg64_imm9() {
+        disassembler_test!(
+            stur_freg64_reg64_imm9,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg, imm| format!(
+                "stur {}, [{}, {}]",
+                reg1.capstone_string(FloatWidth::F64),
+                reg2.capstone_string(UsesSP),
+                capstone_imm9(imm)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS,
+            [-0x10, 0x7c]
+        );
+    }
+
+    #[test]
+    fn test_stur_reg64_reg64_imm9() {
+        disassembler_test!(
+            stur_reg64_reg64_imm9,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "stur {}, [{}, {}]",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesSP),
+                capstone_imm9(imm)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [-0x10, 0x7c]
+        );
+    }
+
     #[test]
     fn test_sub_reg64_reg64_imm12() {
         disassembler_test!(
@@ -1497,6 +3707,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sub_reg64_reg64_reg64() {
+        disassembler_test!(
+            sub_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| {
+                if reg2 == AArch64GeneralReg::ZRSP {
+                    format!(
+                        "neg {}, {}",
+                        reg1.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesZR)
+                    )
+                } else {
+                    format!(
+                        "sub {}, {}, {}",
+                        reg1.capstone_string(UsesZR),
+                        reg2.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesZR)
+                    )
+                }
+            },
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_udiv_reg64_reg64_reg64() {
+        disassembler_test!(
+            udiv_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
+                "udiv {}, {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                reg3.capstone_string(UsesZR)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
     #[test]
     fn test_ret_reg64() {
         disassembler_test!(
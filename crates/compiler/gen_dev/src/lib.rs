@@ -11,8 +11,8 @@ use roc_module::low_level::{LowLevel, LowLevelWrapperType};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::code_gen_help::CodeGenHelp;
 use roc_mono::ir::{
-    BranchInfo, CallType, Expr, JoinPointId, ListLiteralElement, Literal, Param, Proc, ProcLayout,
-    SelfRecursive, Stmt,
+    BranchInfo, CallType, CrashTag, Expr, JoinPointId, ListLiteralElement, Literal, Param, Proc,
+    ProcLayout, SelfRecursive, Stmt,
 };
 use roc_mono::layout::{
     Builtin, Layout, LayoutId, LayoutIds, STLayoutInterner, TagIdIntType, UnionLayout,
@@ -209,6 +209,40 @@ trait Backend<'a> {
                 self.build_jump(id, args, arg_layouts.into_bump_slice(), ret_layout);
                 self.free_symbols(stmt);
             }
+            Stmt::Dbg { remainder, .. } => {
+                // not supported yet; roc_build warns that `dbg` prints nothing in dev builds
+                self.build_stmt(remainder, ret_layout)
+            }
+            Stmt::Crash { msg_bytes, .. } => {
+                // The message bytes are zero-terminated.
+                // Pass the list's elements pointer to roc_panic, which does not return.
+                let u64_layout = Layout::Builtin(Builtin::Int(IntWidth::U64));
+                let u32_layout = Layout::Builtin(Builtin::Int(IntWidth::U32));
+                let list_field_layouts = self.env().arena.alloc([u64_layout; 3]);
+
+                self.load_struct_at_index(
+                    &Symbol::DEV_TMP,
+                    msg_bytes,
+                    Builtin::WRAPPER_PTR as u64,
+                    list_field_layouts,
+                );
+                self.load_literal(
+                    &Symbol::DEV_TMP2,
+                    &u32_layout,
+                    &Literal::Int((CrashTag::User as i128).to_ne_bytes()),
+                );
+                self.build_fn_call(
+                    &Symbol::DEV_TMP3,
+                    "roc_panic".to_string(),
+                    &[Symbol::DEV_TMP, Symbol::DEV_TMP2],
+                    &[u64_layout, u32_layout],
+                    &Layout::UNIT,
+                );
+
+                self.free_symbol(&Symbol::DEV_TMP);
+                self.free_symbol(&Symbol::DEV_TMP2);
+                self.free_symbol(&Symbol::DEV_TMP3);
+            }
             x => todo!("the statement, {:?}", x),
         }
     }
@@ -1097,6 +1131,13 @@ trait Backend<'a> {
             Stmt::Expect { .. } => todo!("expect is not implemented in the dev backend"),
             Stmt::ExpectFx { .. } => todo!("expect-fx is not implemented in the dev backend"),
 
+            Stmt::Dbg { remainder, .. } => self.scan_ast(remainder),
+
+            Stmt::Crash { msg, msg_bytes, .. } => {
+                self.set_last_seen(*msg, stmt);
+                self.set_last_seen(*msg_bytes, stmt);
+            }
+
             Stmt::RuntimeError(_) => {}
         }
     }
@@ -144,14 +144,7 @@ fn generate_wrapper<'a, B: Backend<'a>>(
     };
     output.add_symbol(symbol);
     if let Some(sym_id) = output.symbol_id(name) {
-        let reloc = write::Relocation {
-            offset: offset + proc_offset,
-            size: 32,
-            kind: RelocationKind::PltRelative,
-            encoding: RelocationEncoding::X86Branch,
-            symbol: sym_id,
-            addend: -4,
-        };
+        let reloc = branch_relocation(output, offset + proc_offset, sym_id);
 
         match output.add_relocation(text_section, reloc) {
             Ok(obj) => obj,
@@ -162,6 +155,39 @@ fn generate_wrapper<'a, B: Backend<'a>>(
     }
 }
 
+/// Creates the relocation for a call or jump to `symbol` from the branch instruction at `offset`.
+fn branch_relocation(output: &Object, offset: u64, symbol: SymbolId) -> write::Relocation {
+    match (output.architecture(), output.format()) {
+        (Architecture::Aarch64, BinaryFormat::MachO) => write::Relocation {
+            offset,
+            size: 26,
+            kind: RelocationKind::MachO {
+                value: object::macho::ARM64_RELOC_BRANCH26,
+                relative: true,
+            },
+            encoding: RelocationEncoding::Generic,
+            symbol,
+            addend: 0,
+        },
+        (Architecture::Aarch64, _) => write::Relocation {
+            offset,
+            size: 26,
+            kind: RelocationKind::PltRelative,
+            encoding: RelocationEncoding::AArch64Call,
+            symbol,
+            addend: 0,
+        },
+        _ => write::Relocation {
+            offset,
+            size: 32,
+            kind: RelocationKind::PltRelative,
+            encoding: RelocationEncoding::X86Branch,
+            symbol,
+            addend: -4,
+        },
+    }
+}
+
 fn build_object<'a, B: Backend<'a>>(
     procedures: MutMap<(symbol::Symbol, ProcLayout<'a>), Proc<'a>>,
     mut backend: B,
@@ -459,14 +485,7 @@ fn build_proc<'a, B: Backend<'a>>(
                     }
                 }
                 if let Some(sym_id) = output.symbol_id(name.as_bytes()) {
-                    write::Relocation {
-                        offset: offset + proc_offset,
-                        size: 32,
-                        kind: RelocationKind::PltRelative,
-                        encoding: RelocationEncoding::X86Branch,
-                        symbol: sym_id,
-                        addend: -4,
-                    }
+                    branch_relocation(output, offset + proc_offset, sym_id)
                 } else {
                     internal_error!("failed to find fn symbol for {:?}", name);
                 }
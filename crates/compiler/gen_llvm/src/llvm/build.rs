@@ -6,13 +6,15 @@ use crate::llvm::build_list::{
     self, allocate_list, empty_polymorphic_list, list_append_unsafe, list_capacity, list_concat,
     list_drop_at, list_get_unsafe, list_len, list_map, list_map2, list_map3, list_map4,
     list_prepend, list_replace_unsafe, list_reserve, list_sort_with, list_sublist, list_swap,
-    list_symbol_to_c_abi, list_with_capacity, pass_update_mode,
+    list_symbol_to_c_abi, list_with_capacity, load_list_ptr, pass_update_mode,
 };
 use crate::llvm::compare::{generic_eq, generic_neq};
 use crate::llvm::convert::{
     self, argument_type_from_layout, basic_type_from_builtin, basic_type_from_layout, zig_str_type,
 };
-use crate::llvm::expect::clone_to_shared_memory;
+use crate::llvm::expect::{
+    clone_to_shared_memory, finalize_dbg, notify_parent, print_dbg_location,
+};
 use crate::llvm::refcounting::{
     build_reset, decrement_refcount_layout, increment_refcount_layout, PointerToRefcount,
 };
@@ -23,7 +25,7 @@ use inkwell::basic_block::BasicBlock;
 use inkwell::builder::Builder;
 use inkwell::context::Context;
 use inkwell::debug_info::{
-    AsDIScope, DICompileUnit, DIFlagsConstants, DISubprogram, DebugInfoBuilder,
+    AsDIScope, DICompileUnit, DIFile, DIFlagsConstants, DISubprogram, DebugInfoBuilder,
 };
 use inkwell::memory_buffer::MemoryBuffer;
 use inkwell::module::{Linkage, Module};
@@ -48,20 +50,23 @@ use roc_debug_flags::dbg_do;
 #[cfg(debug_assertions)]
 use roc_debug_flags::ROC_PRINT_LLVM_FN_VERIFICATION;
 use roc_error_macros::internal_error;
+use roc_module::ident::ModuleName;
 use roc_module::low_level::LowLevel;
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::ir::{
-    BranchInfo, CallType, EntryPoint, HigherOrderLowLevel, JoinPointId, ListLiteralElement,
-    ModifyRc, OptLevel, ProcLayout,
+    BranchInfo, CallType, CrashTag, EntryPoint, HigherOrderLowLevel, JoinPointId,
+    ListLiteralElement, ModifyRc, OptLevel, ProcLayout,
 };
 use roc_mono::layout::{
     Builtin, CapturesNiche, LambdaName, LambdaSet, Layout, LayoutIds, RawFunctionLayout,
     STLayoutInterner, TagIdIntType, UnionLayout,
 };
+use roc_region::all::{LineInfo, Region};
 use roc_std::RocDec;
 use roc_target::{PtrWidth, TargetInfo};
+use std::borrow::Cow;
 use std::convert::TryInto;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use target_lexicon::{Architecture, OperatingSystem, Triple};
 
 use super::convert::{zig_dec_type, zig_with_overflow_roc_dec, RocUnion};
@@ -161,8 +166,14 @@ impl<'a, 'ctx> Scope<'a, 'ctx> {
 
 #[derive(Debug, Clone, Copy)]
 pub enum LlvmBackendMode {
-    /// Assumes primitives (roc_alloc, roc_panic, etc) are provided by the host
+    /// Assumes primitives (roc_alloc, roc_panic, etc) are provided by the host. Nothing renders
+    /// the value of a `dbg`, so the host's `roc_dbg` prints where it is.
     Binary,
+    /// Like [`Binary`](Self::Binary), but leaves out `dbg`, for builds with --optimize or --opt-size
+    BinaryOptimized,
+    /// Like [`Binary`](Self::Binary), but keeps `dbg` and failing `expect`s for `roc dev`, which
+    /// reports them. The host also provides the primitives that share their values with `roc dev`.
+    BinaryDev,
     /// Creates a test wrapper around the main roc function to catch and report panics.
     /// Provides a testing implementation of primitives (roc_alloc, roc_panic, etc)
     GenTest,
@@ -174,6 +185,8 @@ impl LlvmBackendMode {
     pub(crate) fn has_host(self) -> bool {
         match self {
             LlvmBackendMode::Binary => true,
+            LlvmBackendMode::BinaryOptimized => true,
+            LlvmBackendMode::BinaryDev => true,
             LlvmBackendMode::GenTest => false,
             LlvmBackendMode::WasmGenTest => true,
             LlvmBackendMode::CliTest => false,
@@ -184,6 +197,8 @@ impl LlvmBackendMode {
     fn returns_roc_result(self) -> bool {
         match self {
             LlvmBackendMode::Binary => false,
+            LlvmBackendMode::BinaryOptimized => false,
+            LlvmBackendMode::BinaryDev => false,
             LlvmBackendMode::GenTest => true,
             LlvmBackendMode::WasmGenTest => true,
             LlvmBackendMode::CliTest => true,
@@ -193,11 +208,74 @@ impl LlvmBackendMode {
     fn runs_expects(self) -> bool {
         match self {
             LlvmBackendMode::Binary => false,
+            LlvmBackendMode::BinaryOptimized => false,
+            LlvmBackendMode::BinaryDev => true,
             LlvmBackendMode::GenTest => false,
             LlvmBackendMode::WasmGenTest => false,
             LlvmBackendMode::CliTest => true,
         }
     }
+
+    /// Whether the host prints `dbg`s whose values aren't rendered
+    pub(crate) fn prints_dbg(self) -> bool {
+        match self {
+            LlvmBackendMode::Binary => true,
+            LlvmBackendMode::BinaryOptimized => false,
+            LlvmBackendMode::BinaryDev => true,
+            LlvmBackendMode::GenTest => false,
+            LlvmBackendMode::WasmGenTest => false,
+            LlvmBackendMode::CliTest => false,
+        }
+    }
+}
+
+/// The `.roc` files of a program. With these, the debug info we emit points at Roc source code.
+/// When empty, all debug locations are placeholders.
+#[derive(Default)]
+pub struct DebugSources<'ctx> {
+    files: MutMap<ModuleId, (DIFile<'ctx>, LineInfo)>,
+}
+
+impl<'ctx> DebugSources<'ctx> {
+    pub fn new(
+        dibuilder: &DebugInfoBuilder<'ctx>,
+        sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    ) -> Self {
+        let files = sources
+            .iter()
+            .map(|(module_id, (path, src))| {
+                let (filename, directory) = debug_file_name_and_directory(path);
+                let file = dibuilder.create_file(&filename, &directory);
+
+                (*module_id, (file, LineInfo::new(src)))
+            })
+            .collect();
+
+        Self { files }
+    }
+
+    /// The file, line and column (both 1-based, as DWARF expects) where `region` of `module_id`
+    /// starts. Compiler-generated code has a zero region, and no location.
+    fn location(&self, module_id: ModuleId, region: Region) -> Option<(DIFile<'ctx>, u32, u32)> {
+        if region == Region::zero() {
+            return None;
+        }
+
+        let (file, line_info) = self.files.get(&module_id)?;
+        let line_column = line_info.convert_pos(region.start());
+
+        Some((*file, line_column.line + 1, line_column.column + 1))
+    }
+}
+
+fn debug_file_name_and_directory(path: &Path) -> (Cow<str>, Cow<str>) {
+    let filename = path.file_name().unwrap_or_default().to_string_lossy();
+    let directory = match path.parent() {
+        Some(parent) if parent != Path::new("") => parent.to_string_lossy(),
+        _ => ".".into(),
+    };
+
+    (filename, directory)
 }
 
 pub struct Env<'a, 'ctx, 'env> {
@@ -207,6 +285,7 @@ pub struct Env<'a, 'ctx, 'env> {
     pub builder: &'env Builder<'ctx>,
     pub dibuilder: &'env DebugInfoBuilder<'ctx>,
     pub compile_unit: &'env DICompileUnit<'ctx>,
+    pub debug_sources: DebugSources<'ctx>,
     pub module: &'ctx Module<'ctx>,
     pub interns: Interns,
     pub target_info: TargetInfo,
@@ -214,22 +293,6 @@ pub struct Env<'a, 'ctx, 'env> {
     pub exposed_to_host: MutSet<Symbol>,
 }
 
-#[repr(u32)]
-pub enum PanicTagId {
-    NullTerminatedString = 0,
-}
-
-impl std::convert::TryFrom<u32> for PanicTagId {
-    type Error = ();
-
-    fn try_from(value: u32) -> Result<Self, Self::Error> {
-        match value {
-            0 => Ok(PanicTagId::NullTerminatedString),
-            _ => Err(()),
-        }
-    }
-}
-
 impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
     /// The integer type representing a pointer
     ///
@@ -375,7 +438,7 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
         )
     }
 
-    pub fn call_panic(&self, message: PointerValue<'ctx>, tag_id: PanicTagId) {
+    pub fn call_panic(&self, message: PointerValue<'ctx>, tag_id: CrashTag) {
         let function = self.module.get_function("roc_panic").unwrap();
         let tag_id = self
             .context
@@ -390,12 +453,25 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
     }
 
     pub fn new_debug_info(module: &Module<'ctx>) -> (DebugInfoBuilder<'ctx>, DICompileUnit<'ctx>) {
+        Self::new_debug_info_for_file(module, Path::new("roc_app"))
+    }
+
+    /// Like [Env::new_debug_info], but with a compile unit for the `.roc` file at `path`,
+    /// which should be the root module of the program.
+    pub fn new_debug_info_for_file(
+        module: &Module<'ctx>,
+        path: &Path,
+    ) -> (DebugInfoBuilder<'ctx>, DICompileUnit<'ctx>) {
+        let (filename, directory) = debug_file_name_and_directory(path);
+
+        // DWARF has no language code for Roc. Debuggers treat C code the most neutrally: they
+        // show our symbol names as-is, and don't try to demangle them.
         module.create_debug_info_builder(
             true,
             /* language */ inkwell::debug_info::DWARFSourceLanguage::C,
-            /* filename */ "roc_app",
-            /* directory */ ".",
-            /* producer */ "my llvm compiler frontend",
+            /* filename */ &filename,
+            /* directory */ &directory,
+            /* producer */ "roc",
             /* is_optimized */ false,
             /* compiler command line flags */ "",
             /* runtime_ver */ 0,
@@ -410,8 +486,40 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
     }
 
     pub fn new_subprogram(&self, function_name: &str) -> DISubprogram<'ctx> {
+        self.new_subprogram_help(function_name, None, self.compile_unit.get_file(), 0)
+    }
+
+    /// Creates the subprogram for a procedure of the Roc definition `symbol`. Debuggers show it
+    /// under the definition's Roc name, at the place in the `.roc` file where its body is.
+    pub fn new_proc_subprogram(
+        &self,
+        linkage_name: &str,
+        symbol: Symbol,
+        region: Region,
+    ) -> DISubprogram<'ctx> {
+        let module_name = symbol.module_string(&self.interns);
+        let roc_name = if module_name.as_str() == ModuleName::APP {
+            symbol.as_str(&self.interns).to_string()
+        } else {
+            format!("{}.{}", module_name.as_str(), symbol.as_str(&self.interns))
+        };
+
+        let (file, line_no) = match self.debug_sources.location(symbol.module_id(), region) {
+            Some((file, line, _column)) => (file, line),
+            None => (self.compile_unit.get_file(), 0),
+        };
+
+        self.new_subprogram_help(&roc_name, Some(linkage_name), file, line_no)
+    }
+
+    fn new_subprogram_help(
+        &self,
+        function_name: &str,
+        linkage_name: Option<&str>,
+        file: DIFile<'ctx>,
+        line_no: u32,
+    ) -> DISubprogram<'ctx> {
         let dibuilder = self.dibuilder;
-        let compile_unit = self.compile_unit;
 
         let ditype = dibuilder
             .create_basic_type(
@@ -423,26 +531,53 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
             .unwrap();
 
         let subroutine_type = dibuilder.create_subroutine_type(
-            compile_unit.get_file(),
+            file,
             /* return type */ Some(ditype.as_type()),
             /* parameter types */ &[],
             inkwell::debug_info::DIFlags::PUBLIC,
         );
 
         dibuilder.create_function(
-            /* scope */ compile_unit.get_file().as_debug_info_scope(),
+            /* scope */ file.as_debug_info_scope(),
             /* func name */ function_name,
-            /* linkage_name */ None,
-            /* file */ compile_unit.get_file(),
-            /* line_no */ 0,
+            /* linkage_name */ linkage_name,
+            /* file */ file,
+            /* line_no */ line_no,
             /* DIType */ subroutine_type,
             /* is_local_to_unit */ true,
             /* is_definition */ true,
-            /* scope_line */ 0,
+            /* scope_line */ line_no,
             /* flags */ inkwell::debug_info::DIFlags::PUBLIC,
             /* is_optimized */ false,
         )
     }
+
+    /// Points the debug location of the instructions built next at `region` of `module_id`, if
+    /// we know where that is. Otherwise, the current debug location is kept.
+    ///
+    /// A procedure's body is in the same file as its subprogram, so the location goes right in
+    /// the subprogram's scope.
+    pub fn set_debug_location(
+        &self,
+        function: FunctionValue<'ctx>,
+        module_id: ModuleId,
+        region: Region,
+    ) {
+        let (line, column) = match self.debug_sources.location(module_id, region) {
+            Some((_file, line, column)) => (line, column),
+            None => return,
+        };
+
+        let func_scope = function.get_subprogram().expect("subprogram");
+        let loc = self.dibuilder.create_debug_location(
+            self.context,
+            line,
+            column,
+            /* current_scope */ func_scope.as_debug_info_scope(),
+            /* inlined_at */ None,
+        );
+        self.builder.set_current_debug_location(self.context, loc);
+    }
 }
 
 pub fn module_from_builtins<'ctx>(
@@ -2472,6 +2607,16 @@ pub fn build_exp_stmt<'a, 'ctx, 'env>(
             for (symbol, expr, layout) in queue {
                 debug_assert!(layout != &Layout::RecursivePointer);
 
+                // A call gets the line of the Roc code that makes it, which lives in the same
+                // module as the symbol it is assigned to.
+                if let roc_mono::ir::Expr::Call(roc_mono::ir::Call {
+                    call_type: CallType::ByName { region, .. },
+                    ..
+                }) = expr
+                {
+                    env.set_debug_location(parent, symbol.module_id(), *region);
+                }
+
                 let val = build_exp_expr(
                     env,
                     layout_ids,
@@ -2831,6 +2976,8 @@ pub fn build_exp_stmt<'a, 'ctx, 'env>(
                             lookups,
                         );
 
+                        notify_parent(env);
+
                         bd.build_unconditional_branch(then_block);
                     }
                     roc_target::PtrWidth::Bytes4 => {
@@ -2893,6 +3040,8 @@ pub fn build_exp_stmt<'a, 'ctx, 'env>(
                             lookups,
                         );
 
+                        notify_parent(env);
+
                         bd.build_unconditional_branch(then_block);
                     }
                     roc_target::PtrWidth::Bytes4 => {
@@ -2917,6 +3066,58 @@ pub fn build_exp_stmt<'a, 'ctx, 'env>(
             )
         }
 
+        Dbg {
+            symbol,
+            region,
+            remainder,
+        } => {
+            // `roc dev` and `roc test` render the value of a `dbg` (for now, only on 64-bit
+            // targets). Otherwise the host prints where it is, unless the build is optimized.
+            if env.mode.runs_expects() && env.target_info.ptr_width() == PtrWidth::Bytes8 {
+                clone_to_shared_memory(env, scope, layout_ids, *symbol, *region, &[*symbol]);
+
+                finalize_dbg(env);
+            } else if env.mode.prints_dbg() {
+                print_dbg_location(env, *symbol, *region);
+            }
+
+            build_exp_stmt(
+                env,
+                layout_ids,
+                func_spec_solutions,
+                scope,
+                parent,
+                remainder,
+            )
+        }
+
+        Crash {
+            msg,
+            msg_bytes,
+            region,
+        } => {
+            // record where the crash happened, so `roc test` and `roc dev` can report it
+            if env.mode.runs_expects() && env.target_info.ptr_width() == PtrWidth::Bytes8 {
+                clone_to_shared_memory(env, scope, layout_ids, *msg, *region, &[*msg]);
+
+                notify_parent(env);
+            }
+
+            let (msg_bytes, _) = load_symbol_and_layout(scope, msg_bytes);
+            let c_msg = load_list_ptr(
+                env.builder,
+                msg_bytes.into_struct_value(),
+                env.context.i8_type().ptr_type(AddressSpace::Generic),
+            );
+
+            env.call_panic(c_msg, CrashTag::User);
+            env.builder.build_unreachable();
+
+            // unused value (must return a BasicValue)
+            let zero = env.context.i64_type().const_zero();
+            zero.into()
+        }
+
         RuntimeError(error_msg) => {
             throw_exception(env, error_msg);
 
@@ -3935,7 +4136,8 @@ fn expose_function_to_host_help_c_abi<'a, 'ctx, 'env>(
             )
         }
 
-        LlvmBackendMode::Binary => {}
+        LlvmBackendMode::Binary | LlvmBackendMode::BinaryOptimized | LlvmBackendMode::BinaryDev => {
+        }
     }
 
     // a generic version that writes the result into a passed *u8 pointer
@@ -3986,7 +4188,9 @@ fn expose_function_to_host_help_c_abi<'a, 'ctx, 'env>(
             roc_result_type(env, roc_function.get_type().get_return_type().unwrap()).into()
         }
 
-        LlvmBackendMode::Binary => basic_type_from_layout(env, &return_layout),
+        LlvmBackendMode::Binary | LlvmBackendMode::BinaryOptimized | LlvmBackendMode::BinaryDev => {
+            basic_type_from_layout(env, &return_layout)
+        }
     };
 
     let size: BasicValueEnum = return_type.size_of().unwrap().into();
@@ -4630,7 +4834,7 @@ fn build_proc_header<'a, 'ctx, 'env>(
         Linkage::Internal,
     );
 
-    let subprogram = env.new_subprogram(&fn_name);
+    let subprogram = env.new_proc_subprogram(&fn_name, symbol, proc.region);
     fn_val.set_subprogram(subprogram);
 
     if env.exposed_to_host.contains(&symbol) {
@@ -4958,7 +5162,7 @@ pub fn build_proc<'a, 'ctx, 'env>(
                 GenTest | WasmGenTest | CliTest => {
                     /* no host, or exposing types is not supported */
                 }
-                Binary => {
+                Binary | BinaryOptimized | BinaryDev => {
                     for (alias_name, (generated_function, top_level, layout)) in aliases.iter() {
                         expose_alias_to_host(
                             env,
@@ -4985,6 +5189,7 @@ pub fn build_proc<'a, 'ctx, 'env>(
     builder.position_at_end(entry);
 
     debug_info_init!(env, fn_val);
+    env.set_debug_location(fn_val, proc.name.name().module_id(), proc.region);
 
     // Add args to scope
     for (arg_val, (layout, arg_symbol)) in fn_val.get_param_iter().zip(args) {
@@ -6301,6 +6506,14 @@ fn run_low_level<'a, 'ctx, 'env>(
                 }
             }
         }
+        NumF32ToBits | NumF64ToBits | NumDecToBits => {
+            debug_assert_eq!(args.len(), 1);
+
+            let arg = load_symbol(scope, &args[0]);
+            let bits_type = basic_type_from_layout(env, layout);
+
+            env.builder.build_bitcast(arg, bits_type, "to_bits")
+        }
         NumBytesToU16 => {
             debug_assert_eq!(args.len(), 2);
             let list = load_symbol(scope, &args[0]).into_struct_value();
@@ -8193,7 +8406,7 @@ fn throw_exception<'a, 'ctx, 'env>(env: &Env<'a, 'ctx, 'env>, message: &str) {
         )
         .into_pointer_value();
 
-    env.call_panic(cast, PanicTagId::NullTerminatedString);
+    env.call_panic(cast, CrashTag::Roc);
 
     builder.build_unreachable();
 }
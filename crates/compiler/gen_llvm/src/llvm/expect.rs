@@ -1,6 +1,8 @@
 use crate::debug_info_init;
-use crate::llvm::bitcode::call_str_bitcode_fn;
-use crate::llvm::build::{get_tag_id, store_roc_value, tag_pointer_clear_tag_id, Env};
+use crate::llvm::bitcode::{call_str_bitcode_fn, call_void_bitcode_fn};
+use crate::llvm::build::{
+    get_tag_id, store_roc_value, tag_pointer_clear_tag_id, Env, LlvmBackendMode,
+};
 use crate::llvm::build_list::{self, incrementing_elem_loop};
 use crate::llvm::convert::{basic_type_from_layout, RocUnion};
 use inkwell::builder::Builder;
@@ -93,6 +95,38 @@ fn write_state<'a, 'ctx, 'env>(
     env.builder.build_store(offset_ptr, offset);
 }
 
+/// The buffer that expect frames are written to. `roc test` sets it before running an expect;
+/// a program that `roc dev` runs shares it with `roc dev` instead.
+fn expect_buffer<'a, 'ctx, 'env>(env: &Env<'a, 'ctx, 'env>) -> PointerValue<'ctx> {
+    let name = match env.mode {
+        LlvmBackendMode::BinaryDev => bitcode::UTILS_EXPECT_FAILED_START_SHARED_FILE,
+        _ => bitcode::UTILS_EXPECT_FAILED_START,
+    };
+
+    let func = env.module.get_function(name).unwrap();
+
+    let call_result = env.builder.build_call(func, &[], "call_expect_start");
+
+    call_result
+        .try_as_basic_value()
+        .left()
+        .unwrap()
+        .into_pointer_value()
+}
+
+/// Tell `roc dev` that a frame was written, so it reports it. Under `roc test`, the frames are
+/// reported once the expect is done instead.
+pub(crate) fn notify_parent<'a, 'ctx, 'env>(env: &Env<'a, 'ctx, 'env>) {
+    if let LlvmBackendMode::BinaryDev = env.mode {
+        let func = env
+            .module
+            .get_function(bitcode::UTILS_EXPECT_FAILED_FINALIZE)
+            .unwrap();
+
+        env.builder.build_call(func, &[], "call_expect_finalize");
+    }
+}
+
 pub(crate) fn clone_to_shared_memory<'a, 'ctx, 'env>(
     env: &Env<'a, 'ctx, 'env>,
     scope: &Scope<'a, 'ctx>,
@@ -101,20 +135,7 @@ pub(crate) fn clone_to_shared_memory<'a, 'ctx, 'env>(
     region: Region,
     lookups: &[Symbol],
 ) {
-    let func = env
-        .module
-        .get_function(bitcode::UTILS_EXPECT_FAILED_START)
-        .unwrap();
-
-    let call_result = env
-        .builder
-        .build_call(func, &[], "call_expect_start_failed");
-
-    let original_ptr = call_result
-        .try_as_basic_value()
-        .left()
-        .unwrap()
-        .into_pointer_value();
+    let original_ptr = expect_buffer(env);
 
     let (count, mut offset) = read_state(env, original_ptr);
 
@@ -181,6 +202,38 @@ pub(crate) fn clone_to_shared_memory<'a, 'ctx, 'env>(
     write_state(env, original_ptr, new_count, offset)
 }
 
+/// Have `roc dev` render the `dbg` frame that was just written, and the host's `roc_dbg` print
+/// it. Under `roc test`, the frames are rendered once the expect is done instead.
+pub(crate) fn finalize_dbg<'a, 'ctx, 'env>(env: &Env<'a, 'ctx, 'env>) {
+    if let LlvmBackendMode::BinaryDev = env.mode {
+        let func = env
+            .module
+            .get_function(bitcode::UTILS_DBG_FINALIZE)
+            .unwrap();
+
+        env.builder.build_call(func, &[], "call_dbg_finalize");
+    }
+}
+
+/// Have the host's `roc_dbg` print where a `dbg` is, when there is nothing to render its value
+pub(crate) fn print_dbg_location<'a, 'ctx, 'env>(
+    env: &Env<'a, 'ctx, 'env>,
+    symbol: Symbol,
+    region: Region,
+) {
+    let i32_type = env.context.i32_type();
+
+    let module_id = i32_type.const_int(symbol.module_id().to_u32() as _, false);
+    let region_start = i32_type.const_int(region.start().offset as _, false);
+    let region_end = i32_type.const_int(region.end().offset as _, false);
+
+    call_void_bitcode_fn(
+        env,
+        &[module_id.into(), region_start.into(), region_end.into()],
+        bitcode::UTILS_DBG_LOCATION,
+    );
+}
+
 #[derive(Clone, Debug, Copy)]
 enum WhenRecursive<'a> {
     Unreachable,
@@ -21,19 +21,51 @@ pub fn add_default_roc_externs(env: &Env<'_, '_, '_>) {
 
     match env.mode {
         super::build::LlvmBackendMode::CliTest => {
-            // expose this function
-            if let Some(fn_val) = module.get_function("set_shared_buffer") {
-                fn_val.set_linkage(Linkage::External);
+            // expose these functions
+            for name in [
+                "set_shared_buffer",
+                "set_mock_effect_handler",
+                "set_coverage_handler",
+            ] {
+                if let Some(fn_val) = module.get_function(name) {
+                    fn_val.set_linkage(Linkage::External);
+                }
             }
         }
         _ => {
-            // remove this function from the module
-            if let Some(fn_val) = module.get_function("set_shared_buffer") {
+            // remove these functions from the module
+            for name in [
+                "set_shared_buffer",
+                "set_mock_effect_handler",
+                "set_coverage_handler",
+            ] {
+                if let Some(fn_val) = module.get_function(name) {
+                    unsafe { fn_val.delete() };
+                }
+            }
+        }
+    }
+
+    if !matches!(env.mode, super::build::LlvmBackendMode::BinaryDev) {
+        // these share expect frames with `roc dev`, using primitives that only its hosts provide
+        for name in [
+            bitcode::UTILS_EXPECT_FAILED_START_SHARED_FILE,
+            bitcode::UTILS_EXPECT_FAILED_FINALIZE,
+            bitcode::UTILS_DBG_FINALIZE,
+        ] {
+            if let Some(fn_val) = module.get_function(name) {
                 unsafe { fn_val.delete() };
             }
         }
     }
 
+    if !env.mode.prints_dbg() {
+        // this calls the host's `roc_dbg`, which only hosts of programs that keep `dbg` provide
+        if let Some(fn_val) = module.get_function(bitcode::UTILS_DBG_LOCATION) {
+            unsafe { fn_val.delete() };
+        }
+    }
+
     if !env.mode.has_host() {
         // roc_alloc
         {
@@ -9,8 +9,8 @@ use roc_module::low_level::{LowLevel, LowLevelWrapperType};
 use roc_module::symbol::{Interns, Symbol};
 use roc_mono::code_gen_help::{CodeGenHelp, HelperOp, REFCOUNT_MAX};
 use roc_mono::ir::{
-    BranchInfo, CallType, Expr, JoinPointId, ListLiteralElement, Literal, ModifyRc, Param, Proc,
-    ProcLayout, Stmt,
+    BranchInfo, CallType, CrashTag, Expr, JoinPointId, ListLiteralElement, Literal, ModifyRc,
+    Param, Proc, ProcLayout, Stmt,
 };
 use roc_mono::layout::{Builtin, Layout, LayoutIds, TagIdIntType, UnionLayout};
 use roc_std::RocDec;
@@ -714,6 +714,11 @@ impl<'a> WasmBackend<'a> {
             Stmt::Expect { .. } => todo!("expect is not implemented in the wasm backend"),
             Stmt::ExpectFx { .. } => todo!("expect-fx is not implemented in the wasm backend"),
 
+            // not supported yet: the value isn't printed, but the rest of the program still runs
+            Stmt::Dbg { remainder, .. } => self.stmt(remainder),
+
+            Stmt::Crash { msg_bytes, .. } => self.stmt_crash(*msg_bytes),
+
             Stmt::RuntimeError(msg) => self.stmt_runtime_error(msg),
         }
     }
@@ -994,9 +999,24 @@ impl<'a> WasmBackend<'a> {
         let elements_addr = self.store_bytes_in_data_section(&bytes);
 
         // Pass its address to roc_panic
-        let tag_id = 0;
         self.code_builder.i32_const(elements_addr as i32);
-        self.code_builder.i32_const(tag_id);
+        self.code_builder.i32_const(CrashTag::Roc as i32);
+        self.call_host_fn_after_loading_args("roc_panic", 2, false);
+
+        self.code_builder.unreachable_();
+    }
+
+    fn stmt_crash(&mut self, msg_bytes: Symbol) {
+        // The message bytes are zero-terminated. Pass the list's elements pointer to roc_panic.
+        if let StoredValue::StackMemory { location, .. } = self.storage.get(&msg_bytes) {
+            let (fp, offset) = location.local_and_offset(self.storage.stack_frame_pointer);
+            self.code_builder.get_local(fp);
+            self.code_builder.i32_load(Align::Bytes4, offset);
+        } else {
+            internal_error!("Lists are always stored in stack memory");
+        }
+
+        self.code_builder.i32_const(CrashTag::User as i32);
         self.call_host_fn_after_loading_args("roc_panic", 2, false);
 
         self.code_builder.unreachable_();
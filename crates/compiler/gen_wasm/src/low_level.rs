@@ -1585,6 +1585,24 @@ impl<'a> LowLevelCall<'a> {
                 }
                 _ => panic_ret_type(),
             },
+            NumF32ToBits => {
+                self.load_args(backend);
+                backend.code_builder.i32_reinterpret_f32();
+            }
+            NumF64ToBits => {
+                self.load_args(backend);
+                backend.code_builder.i64_reinterpret_f64();
+            }
+            NumDecToBits => {
+                // Dec and I128 are both stored as 16 bytes of stack memory, so the bits are a copy
+                let arg_storage = backend.storage.get(&self.arguments[0]).to_owned();
+                backend.storage.clone_value(
+                    &mut backend.code_builder,
+                    &self.ret_storage,
+                    &arg_storage,
+                    self.arguments[0],
+                );
+            }
             NumBytesToU16 => self.load_args_and_call_zig(backend, bitcode::NUM_BYTES_TO_U16),
             NumBytesToU32 => self.load_args_and_call_zig(backend, bitcode::NUM_BYTES_TO_U32),
             NumBitwiseAnd => {
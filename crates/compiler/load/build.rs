@@ -17,6 +17,7 @@ const MODULES: &[(ModuleId, &str)] = &[
     (ModuleId::NUM, "Num.roc"),
     (ModuleId::LIST, "List.roc"),
     (ModuleId::STR, "Str.roc"),
+    (ModuleId::HASH, "Hash.roc"),
     (ModuleId::DICT, "Dict.roc"),
     (ModuleId::SET, "Set.roc"),
     (ModuleId::BOX, "Box.roc"),
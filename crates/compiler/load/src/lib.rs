@@ -14,10 +14,11 @@ const SKIP_SUBS_CACHE: bool = {
     }
 };
 
+pub use roc_load_internal::cache::default_cache_dir;
 pub use roc_load_internal::docs;
 pub use roc_load_internal::file::{
-    EntryPoint, ExecutionMode, Expectations, LoadConfig, LoadResult, LoadStart, LoadedModule,
-    LoadingProblem, MonomorphizedModule, Phase, Threading,
+    EmitKind, EmittedIr, EntryPoint, ExecutionMode, Expectations, LoadConfig, LoadResult,
+    LoadStart, LoadedModule, LoadingProblem, MonomorphizedModule, Phase, Threading,
 };
 
 #[allow(clippy::too_many_arguments)]
@@ -43,14 +44,20 @@ pub fn load_single_threaded<'a>(
 ) -> Result<LoadResult<'a>, LoadingProblem<'a>> {
     let cached_subs = read_cached_subs();
 
+    let load_config = LoadConfig {
+        target_info,
+        render,
+        threading: Threading::Single,
+        exec_mode,
+        ..Default::default()
+    };
+
     roc_load_internal::file::load_single_threaded(
         arena,
         load_start,
         exposed_types,
-        target_info,
         cached_subs,
-        render,
-        exec_mode,
+        load_config,
     )
 }
 
@@ -157,12 +164,9 @@ pub fn load_and_typecheck_str<'a>(
     }
 }
 
-const BOOL: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Bool.dat")) as &[_];
 const RESULT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Result.dat")) as &[_];
 const LIST: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/List.dat")) as &[_];
 const STR: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Str.dat")) as &[_];
-const DICT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Dict.dat")) as &[_];
-const SET: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Set.dat")) as &[_];
 const BOX: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Box.dat")) as &[_];
 const NUM: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Num.dat")) as &[_];
 
@@ -178,16 +182,15 @@ fn read_cached_subs() -> MutMap<ModuleId, (Subs, Vec<(Symbol, Variable)>)> {
     // Wasm seems to re-order definitions between build time and runtime, but only in release mode.
     // That is very strange, but we can solve it separately
     if !cfg!(target_family = "wasm") && !cfg!(windows) && !SKIP_SUBS_CACHE {
-        output.insert(ModuleId::BOOL, deserialize_help(BOOL));
         output.insert(ModuleId::RESULT, deserialize_help(RESULT));
         output.insert(ModuleId::NUM, deserialize_help(NUM));
 
         output.insert(ModuleId::LIST, deserialize_help(LIST));
         output.insert(ModuleId::STR, deserialize_help(STR));
-        output.insert(ModuleId::DICT, deserialize_help(DICT));
-
-        output.insert(ModuleId::SET, deserialize_help(SET));
         output.insert(ModuleId::BOX, deserialize_help(BOX));
+
+        // NOTE: Bool, Dict and Set are not cached, because they define or implement abilities
+        // (`Eq`, `Hash` and `Hasher`), and we do not cache abilities for builtins yet.
     }
 
     output
@@ -0,0 +1,70 @@
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The module cache only trusts entries written by the same compiler build, which we tell apart
+/// by a hash of the compiler's sources. Unlike the commit the compiler was built from, this also
+/// changes with uncommitted edits, e.g. to canonicalization or solving.
+fn main() {
+    let compiler_dir = Path::new("..");
+
+    let mut paths = Vec::new();
+    collect_sources(compiler_dir, &mut paths);
+
+    // the order of directory entries depends on the file system
+    paths.sort();
+
+    // cargo scans a directory for changes to anything inside it, so one line per crate covers
+    // every source file, including ones added after this script last ran
+    for entry in fs::read_dir(compiler_dir).unwrap() {
+        let path = entry.unwrap().path();
+
+        if path.is_dir() && !skip_dir(&path) {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+    }
+
+    // the same hash the module cache uses for its keys
+    let mut hasher = Sha256::new();
+
+    for path in paths {
+        let relative = path.strip_prefix(compiler_dir).unwrap().to_string_lossy();
+        let contents = fs::read(&path).unwrap();
+
+        // lengths keep the boundaries between paths and contents unambiguous
+        hasher.update((relative.len() as u64).to_le_bytes());
+        hasher.update(relative.as_bytes());
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(&contents);
+    }
+
+    let digest = hasher.finalize();
+    let build_id: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+    println!("cargo:rustc-env=ROC_COMPILER_BUILD_ID={}", build_id);
+}
+
+/// The Rust and Roc sources of the compiler crates, leaving out tests and build artifacts.
+fn collect_sources(dir: &Path, paths: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy();
+
+        if path.is_dir() {
+            if !skip_dir(&path) {
+                collect_sources(&path, paths);
+            }
+        } else if name.ends_with(".rs") || name.ends_with(".roc") || name == "Cargo.toml" {
+            paths.push(path);
+        }
+    }
+}
+
+/// Tests and build artifacts don't change what the compiler does.
+fn skip_dir(path: &Path) -> bool {
+    let name = path.file_name().unwrap().to_string_lossy();
+
+    name.starts_with('.')
+        || name.starts_with("test_")
+        || matches!(name.as_ref(), "tests" | "target" | "zig-cache" | "zig-out")
+}
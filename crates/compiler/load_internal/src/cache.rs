@@ -0,0 +1,1006 @@
+//! An on-disk cache of solved module interfaces.
+//!
+//! When type checking, every module we solve can store its exposed types and resolved ability
+//! implementations on disk. A later run reuses them instead of solving the module again, as long
+//! as neither the module's source nor the interfaces of the modules it imports have changed.
+//!
+//! Each entry is keyed by a [Fingerprint] of
+//!
+//! - the compiler itself and [CACHE_VERSION],
+//! - the module's name, source and identifiers (so the symbols in the entry still mean the same
+//!   thing),
+//! - the interface fingerprints of all non-builtin modules it imports.
+//!
+//! The interface fingerprint of a module covers its key (minus the source), its aliases and
+//! abilities, and its solved exposed types. Editing the body of a definition without changing
+//! any of those keeps the modules that import it cached.
+//!
+//! Module ids are not the same from one run to the next, so neither keys nor entries contain
+//! them: entries store the names of the modules their symbols belong to.
+//!
+//! Canonicalizing a module is cached separately, in a second entry per module that holds its
+//! canonical declarations, aliases and abilities (see the `canonical` submodule). Its key covers
+//! the module's source and everything canonicalization takes from the modules it imports, which
+//! is known before they are solved. A module that was canonicalized from the cache is still
+//! constrained, and then solved or taken from its other entry as usual.
+mod canonical;
+
+pub(crate) use canonical::CanonicalModule;
+
+use roc_can::abilities::{ImplKey, MemberSpecializationInfo, PendingAbilitiesStore, ResolvedImpl};
+use roc_can::expr::{Declarations, PendingDerives};
+use roc_can::module::{Module, ResolvedImplementations};
+use roc_collections::{MutMap, MutSet, VecMap, VecSet};
+use roc_module::ident::ModuleName;
+use roc_module::symbol::{IdentIds, IdentIdsByModule, Interns, ModuleId, ModuleIds, Symbol};
+use roc_region::all::{Loc, Position, Region};
+use roc_types::subs::{ExposedTypesStorageSubs, StorageSubs, Subs, Variable};
+use roc_types::types::Alias;
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::hash::{Hash, Hasher};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::{env, fs, io};
+
+/// Bump this whenever the layout of an entry, or what goes into a key, changes.
+const CACHE_VERSION: u32 = 4;
+
+const MAGIC: [u8; 8] = *b"roccache";
+
+/// magic (8) + version (4) + padding (4) + key (32) + length of the body (8)
+/// + SHA-256 of the body (32) + length of the serialized subs (8)
+const HEADER_SIZE: usize = 96;
+
+/// The directory `roc check` keeps its cache in, unless told otherwise.
+pub fn default_cache_dir() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+    };
+
+    base.map(|dir| dir.join("roc").join("modules"))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub(crate) struct Fingerprint([u8; 32]);
+
+impl Fingerprint {
+    fn to_hex(self) -> String {
+        let mut hex = String::with_capacity(2 * self.0.len());
+
+        for byte in self.0 {
+            write!(hex, "{:02x}", byte).unwrap();
+        }
+
+        hex
+    }
+}
+
+/// Feeds anything that implements [Hash] into a SHA-256 digest, so that the result is stable
+/// across runs (and compiler builds) unlike the hashers we use for our maps.
+struct FingerprintHasher(Sha256);
+
+impl FingerprintHasher {
+    fn new() -> Self {
+        Self(Sha256::new())
+    }
+
+    fn fingerprint(self) -> Fingerprint {
+        Fingerprint(self.0.finalize().into())
+    }
+}
+
+impl Hasher for FingerprintHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let digest = self.0.clone().finalize();
+
+        u64::from_le_bytes(digest[..8].try_into().unwrap())
+    }
+}
+
+/// Changes with the sources the compiler was built from (see build.rs), so a compiler built from
+/// other sources never trusts old entries.
+fn compiler_fingerprint() -> Fingerprint {
+    let mut hasher = FingerprintHasher::new();
+
+    CACHE_VERSION.hash(&mut hasher);
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    env!("ROC_COMPILER_BUILD_ID").hash(&mut hasher);
+
+    hasher.fingerprint()
+}
+
+/// Tracks the interface fingerprints of the modules solved so far in this load.
+#[derive(Debug)]
+pub(crate) struct InterfaceCache {
+    dir: PathBuf,
+    compiler: Fingerprint,
+    interfaces: MutMap<ModuleId, Fingerprint>,
+    reused: MutSet<ModuleId>,
+    reused_declarations: MutSet<ModuleId>,
+}
+
+impl InterfaceCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            compiler: compiler_fingerprint(),
+            interfaces: MutMap::default(),
+            reused: MutSet::default(),
+            reused_declarations: MutSet::default(),
+        }
+    }
+
+    /// Entries are stored per source file, so the cache only ever holds the latest version.
+    fn entry_name(&self, path: &Path) -> String {
+        let mut path_hasher = FingerprintHasher::new();
+        path.hash(&mut path_hasher);
+
+        path_hasher.fingerprint().to_hex()
+    }
+
+    /// The cache entry a module should be canonicalized against. Unlike [Self::entry_for], its
+    /// key can only be computed once everything the module imports has been canonicalized.
+    pub fn canonical_entry_for(&self, path: &Path) -> CanonicalEntry {
+        CanonicalEntry {
+            path: self.dir.join(format!("{}.can", self.entry_name(path))),
+            compiler: self.compiler,
+        }
+    }
+
+    /// The cache entry a module should be solved against, or `None` if it cannot be cached
+    /// because one of its imports has no interface fingerprint.
+    pub fn entry_for<'a>(
+        &self,
+        module_ids: ModuleIds,
+        module_id: ModuleId,
+        path: &Path,
+        source: &str,
+        ident_ids: &IdentIds,
+        imported_modules: impl Iterator<Item = &'a ModuleId>,
+    ) -> Option<CacheEntry> {
+        let mut imported_interfaces = Vec::new();
+
+        for imported in imported_modules {
+            // builtins are part of the compiler, which is already in the key
+            if !imported.is_builtin() {
+                imported_interfaces.push(*self.interfaces.get(imported)?);
+            }
+        }
+
+        imported_interfaces.sort();
+
+        // Everything the symbols in the entry depend on, but not the source itself: editing a
+        // module should only change its interface if it changes what other modules see.
+        let mut hasher = FingerprintHasher::new();
+
+        self.compiler.hash(&mut hasher);
+        module_ids.get_name(module_id)?.as_str().hash(&mut hasher);
+
+        for (_, ident) in ident_ids.ident_strs() {
+            ident.hash(&mut hasher);
+        }
+
+        imported_interfaces.hash(&mut hasher);
+
+        let identity = hasher.fingerprint();
+
+        let mut hasher = FingerprintHasher::new();
+
+        identity.hash(&mut hasher);
+        source.hash(&mut hasher);
+
+        Some(CacheEntry {
+            path: self.dir.join(self.entry_name(path)),
+            key: hasher.fingerprint(),
+            identity,
+            module_ids,
+        })
+    }
+
+    /// Record the outcome of solving `module_id`. Modules without an interface fingerprint make
+    /// everything that imports them uncacheable.
+    pub fn record(&mut self, module_id: ModuleId, interface: Option<Fingerprint>, reused: bool) {
+        if let Some(interface) = interface {
+            self.interfaces.insert(module_id, interface);
+        }
+
+        if reused {
+            self.reused.insert(module_id);
+        }
+    }
+
+    /// Record that the canonical form of `module_id` was taken from its entry.
+    pub fn record_canonical(&mut self, module_id: ModuleId) {
+        self.reused_declarations.insert(module_id);
+    }
+
+    /// The modules whose solved types were reused, and those whose declarations were.
+    pub fn into_reused(self) -> (MutSet<ModuleId>, MutSet<ModuleId>) {
+        (self.reused, self.reused_declarations)
+    }
+}
+
+/// Where the canonical form of a module is stored.
+#[derive(Debug)]
+pub(crate) struct CanonicalEntry {
+    path: PathBuf,
+    compiler: Fingerprint,
+}
+
+impl CanonicalEntry {
+    /// The key of the entry, covering the module's source and everything canonicalization takes
+    /// from the modules it imports. `None` if those contain something we can't store.
+    #[allow(clippy::too_many_arguments)]
+    pub fn key(
+        &self,
+        module_ids: &ModuleIds,
+        module_id: ModuleId,
+        source: &str,
+        exposed_ident_ids: &IdentIds,
+        dep_idents: &IdentIdsByModule,
+        exposed_symbols: &VecSet<Symbol>,
+        aliases: &MutMap<Symbol, Alias>,
+        abilities_store: &PendingAbilitiesStore,
+    ) -> Option<Fingerprint> {
+        let mut hasher = FingerprintHasher::new();
+
+        self.compiler.hash(&mut hasher);
+        module_ids.get_name(module_id)?.as_str().hash(&mut hasher);
+        source.hash(&mut hasher);
+        hash_idents(exposed_ident_ids, &mut hasher);
+
+        // the symbols of the imported modules must still mean the same thing; builtins are part
+        // of the compiler, which is already in the key
+        let mut deps = Vec::new();
+
+        for dep_module in dep_idents.keys() {
+            if !dep_module.is_builtin() {
+                let mut dep_hasher = FingerprintHasher::new();
+
+                module_ids
+                    .get_name(*dep_module)?
+                    .as_str()
+                    .hash(&mut dep_hasher);
+                hash_idents(dep_idents.get(dep_module).unwrap(), &mut dep_hasher);
+
+                deps.push(dep_hasher.fingerprint());
+            }
+        }
+
+        deps.sort();
+        deps.hash(&mut hasher);
+
+        canonical::encode_imports(module_ids, exposed_symbols, aliases, abilities_store)?
+            .hash(&mut hasher);
+
+        Some(hasher.fingerprint())
+    }
+
+    pub fn load(
+        &self,
+        key: Fingerprint,
+        module_ids: &ModuleIds,
+        module_id: ModuleId,
+        exposed_symbols: VecSet<Symbol>,
+    ) -> Option<CanonicalModule> {
+        let (words, len) = read_aligned(&self.path).ok()?;
+
+        // Safety: the words are plain integers, viewed as the bytes we read into them
+        let bytes = unsafe { std::slice::from_raw_parts(words.as_ptr() as *const u8, len) };
+
+        read_header(bytes, key)?;
+
+        canonical::decode(
+            &bytes[HEADER_SIZE..],
+            module_ids,
+            module_id,
+            exposed_symbols,
+        )
+    }
+
+    /// Write the entry, unless the module contains something we don't store. Like
+    /// [CacheEntry::store], failing to write only means canonicalizing again next time.
+    #[allow(clippy::too_many_arguments)]
+    pub fn store(
+        &self,
+        key: Fingerprint,
+        module_ids: &ModuleIds,
+        module: &Module,
+        declarations: &Declarations,
+        ident_ids: &IdentIds,
+        next_var: Variable,
+        pending_derives: &PendingDerives,
+    ) {
+        let body = canonical::encode(
+            module_ids,
+            module,
+            declarations,
+            ident_ids,
+            next_var,
+            pending_derives,
+        );
+
+        match body {
+            Some(body) => {
+                let mut buf = vec![0; HEADER_SIZE];
+                buf.extend_from_slice(&body);
+                write_header(&mut buf, key, 0);
+
+                if write_atomically(&self.path, &buf).is_err() {
+                    let _ = fs::remove_file(&self.path);
+                }
+            }
+            None => {
+                let _ = fs::remove_file(&self.path);
+            }
+        }
+    }
+}
+
+/// Where a module's solved interface is stored, and the key it must match to be reused.
+#[derive(Debug)]
+pub(crate) struct CacheEntry {
+    path: PathBuf,
+    key: Fingerprint,
+    identity: Fingerprint,
+    /// The modules of this run, to store symbols by the name of their module.
+    module_ids: ModuleIds,
+}
+
+impl CacheEntry {
+    /// Fingerprint of what canonicalization of the module hands to the modules importing it.
+    pub fn canonical_fingerprint(
+        &self,
+        aliases: &MutMap<Symbol, (bool, Alias)>,
+        abilities_store: &PendingAbilitiesStore,
+    ) -> Fingerprint {
+        let mut hasher = FingerprintHasher::new();
+
+        canonical::encode_exports(&self.module_ids, aliases, abilities_store).hash(&mut hasher);
+
+        hasher.fingerprint()
+    }
+
+    /// The module's exposed types and ability implementations, and its interface fingerprint.
+    pub fn load(
+        &self,
+        canonical: Fingerprint,
+    ) -> Option<(
+        ExposedTypesStorageSubs,
+        ResolvedImplementations,
+        Fingerprint,
+    )> {
+        let (words, len) = read_aligned(&self.path).ok()?;
+
+        // Safety: the words are plain integers, viewed as the bytes we read into them
+        let bytes = unsafe { std::slice::from_raw_parts(words.as_ptr() as *const u8, len) };
+
+        let subs_end = HEADER_SIZE.checked_add(read_header(bytes, self.key)?)?;
+
+        // The subs start 8-byte aligned, which is what deserializing them in place requires.
+        let (mut subs, _) = Subs::deserialize(bytes.get(HEADER_SIZE..subs_end)?);
+        let rest = &bytes[subs_end..];
+
+        // fingerprinted as stored, like when it was written
+        let interface = self.interface(canonical, &subs, rest);
+
+        let mut reader = Reader::new(rest);
+        let modules = read_module_names(&mut reader, &self.module_ids)?;
+        reader.modules = &modules;
+
+        subs.map_symbols(|stored| restore_symbol(&modules, stored))?;
+
+        let (exposed, impls) = decode(&mut reader, subs)?;
+
+        Some((exposed, impls, interface))
+    }
+
+    /// Write the entry, unless it refers to something that only exists during this run.
+    /// Failing to write is not an error; we'll just solve the module again next time. Any
+    /// older entry for the same file is removed then, so it can't be mistaken for this one.
+    ///
+    /// Returns the module's interface fingerprint, whether or not the entry was written.
+    pub fn store(
+        &self,
+        canonical: Fingerprint,
+        exposed: &ExposedTypesStorageSubs,
+        impls: &ResolvedImplementations,
+    ) -> Fingerprint {
+        let mut writer = Writer::new(&self.module_ids);
+
+        let mut subs = exposed.storage_subs.as_inner().clone();
+        subs.map_symbols(|symbol| Some(writer.store_symbol(symbol)))
+            .unwrap();
+
+        encode(&mut writer, exposed, impls);
+
+        let rest = writer.bytes();
+        let interface = self.interface(canonical, &subs, &rest);
+
+        if is_cacheable(exposed) && !writer.unsupported {
+            let mut serialized_subs = Vec::new();
+            subs.serialize(&[], &mut serialized_subs)
+                .expect("writing to a Vec cannot fail");
+
+            let mut buf = vec![0; HEADER_SIZE];
+            buf.extend_from_slice(&serialized_subs);
+            buf.extend_from_slice(&rest);
+            write_header(&mut buf, self.key, serialized_subs.len());
+
+            if write_atomically(&self.path, &buf).is_err() {
+                let _ = fs::remove_file(&self.path);
+            }
+        }
+
+        interface
+    }
+
+    /// Covers the entry as stored, so it is the same in every run that stores the same types.
+    fn interface(&self, canonical: Fingerprint, stored_subs: &Subs, rest: &[u8]) -> Fingerprint {
+        let mut hasher = FingerprintHasher::new();
+
+        self.identity.hash(&mut hasher);
+        canonical.hash(&mut hasher);
+        stored_subs.hash_structure(&mut hasher);
+        rest.hash(&mut hasher);
+
+        hasher.fingerprint()
+    }
+}
+
+fn hash_idents(ident_ids: &IdentIds, hasher: &mut FingerprintHasher) {
+    ident_ids.len().hash(hasher);
+
+    for (_, ident) in ident_ids.ident_strs() {
+        ident.hash(hasher);
+    }
+}
+
+/// Symbols of the derived modules are created on demand, so they mean nothing in a later run.
+fn is_cacheable(exposed: &ExposedTypesStorageSubs) -> bool {
+    let subs = exposed.storage_subs.as_inner();
+
+    subs.problems.is_empty()
+        && !subs.closure_names.iter().any(|symbol| {
+            symbol.module_id() == ModuleId::DERIVED_GEN
+                || symbol.module_id() == ModuleId::DERIVED_SYNTH
+        })
+}
+
+/// Everything but the subs, which are written before it.
+fn encode(writer: &mut Writer, exposed: &ExposedTypesStorageSubs, impls: &ResolvedImplementations) {
+    writer.write(exposed.stored_vars_by_symbol.len() as u64);
+
+    for (symbol, var) in exposed.stored_vars_by_symbol.iter() {
+        writer.write(*symbol);
+        writer.write(*var);
+    }
+
+    for map in [
+        &exposed.stored_specialization_lambda_set_vars,
+        &exposed.stored_ability_member_vars,
+    ] {
+        writer.write(map.len() as u64);
+
+        for (from, to) in map.iter() {
+            writer.write(*from);
+            writer.write(*to);
+        }
+    }
+
+    // resolved from a hash map, so their order differs from run to run
+    let mut impls: Vec<_> = impls.iter().collect();
+    impls.sort_by_key(|(impl_key, _)| {
+        (
+            writer.modules.order(impl_key.opaque),
+            writer.modules.order(impl_key.ability_member),
+        )
+    });
+
+    writer.write(impls.len() as u64);
+
+    for (impl_key, resolved) in impls {
+        writer.write(impl_key.opaque);
+        writer.write(impl_key.ability_member);
+
+        match resolved {
+            ResolvedImpl::Impl(specialization) => {
+                writer.write(0u8);
+                writer.write(specialization.symbol);
+                writer.write(specialization.specialization_lambda_sets.len() as u64);
+
+                for (region, var) in specialization.specialization_lambda_sets.iter() {
+                    writer.write(*region);
+                    writer.write(*var);
+                }
+            }
+            ResolvedImpl::Derived => writer.write(1u8),
+            ResolvedImpl::Error => writer.write(2u8),
+        }
+    }
+}
+
+/// Fill in the header of `buf`, which starts with [HEADER_SIZE] bytes set aside for it.
+fn write_header(buf: &mut [u8], key: Fingerprint, subs_len: usize) {
+    let body = &buf[HEADER_SIZE..];
+    let mut header = Vec::with_capacity(HEADER_SIZE);
+
+    header.extend_from_slice(&MAGIC);
+    header.extend_from_slice(&CACHE_VERSION.to_ne_bytes());
+    header.extend_from_slice(&0u32.to_ne_bytes());
+    header.extend_from_slice(&key.0);
+    header.extend_from_slice(&(body.len() as u64).to_ne_bytes());
+    header.extend_from_slice(&Sha256::digest(body));
+    header.extend_from_slice(&(subs_len as u64).to_ne_bytes());
+
+    buf[..HEADER_SIZE].copy_from_slice(&header);
+}
+
+/// Check the header of an entry against `key` and the body that follows it, returning the
+/// length of the serialized subs.
+fn read_header(bytes: &[u8], key: Fingerprint) -> Option<usize> {
+    let mut header = Reader::new(bytes.get(..HEADER_SIZE)?);
+
+    if header.read::<[u8; 8]>()? != MAGIC
+        || header.read::<u32>()? != CACHE_VERSION
+        || header.read::<u32>()? != 0
+        || header.read::<[u8; 32]>()? != key.0
+    {
+        return None;
+    }
+
+    // A truncated or otherwise damaged entry is a miss, like any other mismatch.
+    let body = &bytes[HEADER_SIZE..];
+
+    if header.read::<u64>()? != body.len() as u64
+        || header.read::<[u8; 32]>()?[..] != Sha256::digest(body)[..]
+    {
+        return None;
+    }
+
+    Some(header.read::<u64>()? as usize)
+}
+
+/// The inverse of [encode], given the subs read back before it.
+fn decode(
+    reader: &mut Reader,
+    subs: Subs,
+) -> Option<(ExposedTypesStorageSubs, ResolvedImplementations)> {
+    let mut stored_vars_by_symbol = VecMap::default();
+
+    for _ in 0..reader.read::<u64>()? {
+        stored_vars_by_symbol.insert(reader.read::<Symbol>()?, reader.read::<Variable>()?);
+    }
+
+    let mut var_maps = [VecMap::default(), VecMap::default()];
+
+    for map in var_maps.iter_mut() {
+        for _ in 0..reader.read::<u64>()? {
+            map.insert(reader.read::<Variable>()?, reader.read::<Variable>()?);
+        }
+    }
+
+    let [stored_specialization_lambda_set_vars, stored_ability_member_vars] = var_maps;
+
+    let mut impls = VecMap::default();
+
+    for _ in 0..reader.read::<u64>()? {
+        let impl_key = ImplKey {
+            opaque: reader.read()?,
+            ability_member: reader.read()?,
+        };
+
+        let resolved = match reader.read::<u8>()? {
+            0 => {
+                let symbol = reader.read()?;
+                let mut specialization_lambda_sets = VecMap::default();
+
+                for _ in 0..reader.read::<u64>()? {
+                    specialization_lambda_sets
+                        .insert(reader.read::<u8>()?, reader.read::<Variable>()?);
+                }
+
+                ResolvedImpl::Impl(MemberSpecializationInfo::new(
+                    symbol,
+                    specialization_lambda_sets,
+                ))
+            }
+            1 => ResolvedImpl::Derived,
+            2 => ResolvedImpl::Error,
+            _ => return None,
+        };
+
+        impls.insert(impl_key, resolved);
+    }
+
+    let exposed = ExposedTypesStorageSubs {
+        storage_subs: StorageSubs::new(subs),
+        stored_vars_by_symbol,
+        stored_specialization_lambda_set_vars,
+        stored_ability_member_vars,
+    };
+
+    Some((exposed, impls))
+}
+
+/// Module ids are handed out in the order the loader comes across modules, which changes from run
+/// to run since headers are parsed in parallel. So entries refer to modules by name: an entry has
+/// a table of the modules its symbols belong to, and a symbol is stored with the position of its
+/// module in that table instead of the module's id.
+struct ModuleTable<'a> {
+    module_ids: &'a ModuleIds,
+    modules: Vec<ModuleId>,
+    positions: MutMap<ModuleId, u32>,
+}
+
+impl<'a> ModuleTable<'a> {
+    fn new(module_ids: &'a ModuleIds) -> Self {
+        Self {
+            module_ids,
+            modules: Vec::new(),
+            positions: MutMap::default(),
+        }
+    }
+
+    /// The symbol as it is stored, or `None` if its module can't be found by name again.
+    fn store(&mut self, symbol: Symbol) -> Option<Symbol> {
+        let module_id = symbol.module_id();
+
+        let position = match self.positions.get(&module_id) {
+            Some(position) => *position,
+            None => {
+                // modules of different packages may share a name
+                let name = self.module_ids.get_name(module_id)?;
+
+                if self.module_ids.get_id(name) != Some(module_id) {
+                    return None;
+                }
+
+                let position = self.modules.len() as u32;
+                self.modules.push(module_id);
+                self.positions.insert(module_id, position);
+
+                position
+            }
+        };
+
+        // counted from 1, like module ids themselves
+        Some(Interns::from_index(
+            ModuleId::from_u32(position + 1)?,
+            symbol.ident_id().index() as u32,
+        ))
+    }
+
+    /// Sorting symbols by this gives the same order in every run, unlike sorting the symbols.
+    fn order(&self, symbol: Symbol) -> (Option<&'a str>, usize) {
+        let name = self.module_ids.get_name(symbol.module_id());
+
+        (name.map(|name| name.as_str()), symbol.ident_id().index())
+    }
+
+    fn write_names(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&(self.modules.len() as u64).to_ne_bytes());
+
+        for module_id in self.modules.iter() {
+            let name = self.module_ids.get_name(*module_id).unwrap().as_str();
+
+            buf.extend_from_slice(&(name.len() as u64).to_ne_bytes());
+            buf.extend_from_slice(name.as_bytes());
+        }
+    }
+}
+
+/// Read the table written by [ModuleTable::write_names], giving the ids its modules have in this
+/// run, or `None` if one of them isn't part of it.
+fn read_module_names(reader: &mut Reader, module_ids: &ModuleIds) -> Option<Vec<ModuleId>> {
+    let mut modules = Vec::new();
+
+    for _ in 0..reader.read::<u64>()? {
+        let len = reader.read::<u64>()?;
+        let name = std::str::from_utf8(reader.read_bytes(usize::try_from(len).ok()?)?).ok()?;
+
+        modules.push(module_ids.get_id(&ModuleName::from(name))?);
+    }
+
+    Some(modules)
+}
+
+/// The inverse of [ModuleTable::store], given the modules read from the entry's table.
+fn restore_symbol(modules: &[ModuleId], stored: Symbol) -> Option<Symbol> {
+    let position = stored.module_id().to_u32() - 1;
+
+    Some(Interns::from_index(
+        *modules.get(position as usize)?,
+        stored.ident_id().index() as u32,
+    ))
+}
+
+/// Collects the bytes of an entry, and the modules its symbols belong to.
+struct Writer<'a> {
+    buf: Vec<u8>,
+    modules: ModuleTable<'a>,
+    unsupported: bool,
+}
+
+impl<'a> Writer<'a> {
+    fn new(module_ids: &'a ModuleIds) -> Self {
+        Self {
+            buf: Vec::new(),
+            modules: ModuleTable::new(module_ids),
+            unsupported: false,
+        }
+    }
+
+    fn write<T: Pod>(&mut self, value: T) {
+        value.write(self);
+    }
+
+    /// The symbol as it is stored. One that can't be stored is kept as it is, but makes the
+    /// entry unusable.
+    fn store_symbol(&mut self, symbol: Symbol) -> Symbol {
+        self.modules.store(symbol).unwrap_or_else(|| {
+            self.unsupported = true;
+            symbol
+        })
+    }
+
+    /// The table of modules followed by what was written, even if something could not be
+    /// stored; that is enough to fingerprint it.
+    fn bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        self.modules.write_names(&mut bytes);
+        bytes.extend_from_slice(&self.buf);
+
+        bytes
+    }
+
+    /// `None` if something was written that can't be read back.
+    fn finish(self) -> Option<Vec<u8>> {
+        if self.unsupported {
+            None
+        } else {
+            Some(self.bytes())
+        }
+    }
+}
+
+/// Plain data we store field by field as native-endian integers, so no padding is ever written.
+/// Reading one back checks that the bytes make a valid value: an entry that doesn't is a miss.
+trait Pod: Copy {
+    fn write(self, writer: &mut Writer);
+
+    fn read(reader: &mut Reader) -> Option<Self>;
+}
+
+macro_rules! impl_pod_for_int {
+    ($($int:ty),*) => {
+        $(
+            impl Pod for $int {
+                fn write(self, writer: &mut Writer) {
+                    writer.buf.extend_from_slice(&self.to_ne_bytes());
+                }
+
+                fn read(reader: &mut Reader) -> Option<Self> {
+                    Some(Self::from_ne_bytes(reader.read()?))
+                }
+            }
+        )*
+    };
+}
+
+impl_pod_for_int!(u16, u32, u64);
+
+impl Pod for u8 {
+    fn write(self, writer: &mut Writer) {
+        writer.buf.push(self);
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        Some(reader.read_bytes(1)?[0])
+    }
+}
+
+impl<const N: usize> Pod for [u8; N] {
+    fn write(self, writer: &mut Writer) {
+        writer.buf.extend_from_slice(&self);
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        reader.read_bytes(N)?.try_into().ok()
+    }
+}
+
+impl Pod for Symbol {
+    fn write(self, writer: &mut Writer) {
+        let stored = writer.store_symbol(self);
+
+        stored.module_id().to_u32().write(writer);
+        (stored.ident_id().index() as u32).write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        let stored = Interns::from_index(ModuleId::from_u32(reader.read()?)?, reader.read()?);
+
+        restore_symbol(reader.modules, stored)
+    }
+}
+
+impl Pod for Variable {
+    fn write(self, writer: &mut Writer) {
+        self.index().write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        // Safety: the variables of an entry index into the subs it was written with, which is
+        // what they are used with again
+        Some(unsafe { Variable::from_index(reader.read()?) })
+    }
+}
+
+impl Pod for Position {
+    fn write(self, writer: &mut Writer) {
+        self.offset.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        Some(Position::new(reader.read()?))
+    }
+}
+
+impl Pod for Region {
+    fn write(self, writer: &mut Writer) {
+        self.start().write(writer);
+        self.end().write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        Some(Region::new(reader.read()?, reader.read()?))
+    }
+}
+
+impl Pod for f64 {
+    fn write(self, writer: &mut Writer) {
+        self.to_bits().write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        Some(f64::from_bits(reader.read()?))
+    }
+}
+
+impl Pod for char {
+    fn write(self, writer: &mut Writer) {
+        u32::from(self).write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        char::from_u32(reader.read()?)
+    }
+}
+
+impl<A: Pod, B: Pod> Pod for (A, B) {
+    fn write(self, writer: &mut Writer) {
+        self.0.write(writer);
+        self.1.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        Some((reader.read()?, reader.read()?))
+    }
+}
+
+impl<T: Pod> Pod for Loc<T> {
+    fn write(self, writer: &mut Writer) {
+        self.region.write(writer);
+        self.value.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        Some(Loc::at(reader.read()?, reader.read()?))
+    }
+}
+
+/// Enums without payloads are written as the index of their variant. Listing the variants here
+/// makes the match in `write` fail to compile when one is added, rather than reading back wrong.
+macro_rules! impl_pod_for_enum {
+    ($($enum:ty => [$($variant:ident),* $(,)?];)*) => {
+        $(
+            impl Pod for $enum {
+                fn write(self, writer: &mut Writer) {
+                    type This = $enum;
+                    const VARIANTS: &[This] = &[$(This::$variant),*];
+
+                    match self {
+                        $(This::$variant)|* => {}
+                    }
+
+                    let tag = VARIANTS.iter().position(|variant| *variant == self).unwrap();
+
+                    (tag as u8).write(writer);
+                }
+
+                fn read(reader: &mut Reader) -> Option<Self> {
+                    type This = $enum;
+                    const VARIANTS: &[This] = &[$(This::$variant),*];
+
+                    VARIANTS.get(reader.read::<u8>()? as usize).copied()
+                }
+            }
+        )*
+    };
+}
+
+pub(crate) use impl_pod_for_enum;
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    /// The modules of the entry's table, see [ModuleTable].
+    modules: &'a [ModuleId],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+            modules: &[],
+        }
+    }
+
+    fn read<T: Pod>(&mut self) -> Option<T> {
+        T::read(self)
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.offset.checked_add(len)?;
+        let chunk = self.bytes.get(self.offset..end)?;
+        self.offset = end;
+
+        Some(chunk)
+    }
+}
+
+/// Read a file into a buffer that is aligned to 8 bytes, returning the buffer and the file size.
+fn read_aligned(path: &Path) -> io::Result<(Vec<u64>, usize)> {
+    let bytes = fs::read(path)?;
+    let mut words = vec![0u64; bytes.len() / 8 + 1];
+
+    // Safety: the words have room for at least `bytes.len()` bytes
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), words.as_mut_ptr() as *mut u8, bytes.len());
+    }
+
+    Ok((words, bytes.len()))
+}
+
+/// Write to a temporary file first, so a concurrent `roc check` never reads half an entry.
+fn write_atomically(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(dir)?;
+
+    // appended rather than replacing an extension, since the entries of a module differ in theirs
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(format!(".tmp{}", std::process::id()));
+    let tmp_path = PathBuf::from(tmp_path);
+
+    let result = fs::File::create(&tmp_path)
+        .and_then(|mut file| file.write_all(bytes))
+        .and_then(|()| fs::rename(&tmp_path, path));
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+
+    result
+}
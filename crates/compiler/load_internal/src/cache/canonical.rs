@@ -0,0 +1,2390 @@
+//! The canonical form of a module, as stored in its second cache entry.
+//!
+//! This holds everything later phases take from canonicalization: the module's declarations,
+//! aliases, abilities and rigid variables, its identifiers (canonicalization adds generated
+//! ones), its pending derives and the first variable it did not use. Restoring those lets us
+//! constrain the module without parsing or canonicalizing it again.
+//!
+//! Like the solved entry, this is only ever read back by the same compiler build. Symbols,
+//! variables, regions and other plain data are written as fixed-size integers, and enums as a
+//! tag that is checked when reading it back. Like all entries, it starts with the names of the
+//! modules its symbols belong to. Runtime errors, malformed patterns and erroneous
+//! types are not stored; a module containing any of them is canonicalized every time, which
+//! also keeps its problems reported.
+use super::{impl_pod_for_enum, read_module_names, ModuleTable, Pod, Reader, Writer};
+use roc_can::abilities::{
+    AbilityMemberData, MemberSpecializationInfo, MemberVariables, PendingAbilitiesStore,
+    PendingMemberType, Resolved, SpecializationId,
+};
+use roc_can::annotation::{AbleVariable, IntroducedVariables, NamedVariable};
+use roc_can::def::{Annotation, Def};
+use roc_can::expr::{
+    AccessorData, AnnotatedMark, ClosureData, DeclarationTag, Declarations, DestructureDef, Expr,
+    Field, FunctionDef, IntValue, OpaqueWrapFunctionData, PendingDerives, Recursive, WhenBranch,
+    WhenBranchPattern,
+};
+use roc_can::module::{Module, RigidVariables};
+use roc_can::pattern::{DestructType, ListPatterns, Pattern, RecordDestruct, TupleDestruct};
+use roc_collections::soa::Index;
+use roc_collections::{MutMap, SendMap, VecSet};
+use roc_module::called_via::{BinOp, CalledVia, UnaryOp};
+use roc_module::ident::{ForeignSymbol, IndexOrField, Lowercase, TagName};
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::{IdentIds, ModuleId, ModuleIds, Symbol};
+use roc_region::all::Loc;
+use roc_types::num::{
+    FloatBound, FloatWidth, IntBound, IntLitWidth, NumBound, NumericRange, SignDemand,
+};
+use roc_types::subs::{ExhaustiveMark, IllegalCycleMark, RedundantMark, Variable};
+use roc_types::types::{
+    Alias, AliasCommon, AliasKind, AliasVar, LambdaSet, MemberImpl, OptAbleType, OptAbleVar,
+    RecordField, Type, TypeExtension, Uls,
+};
+
+/// A module as it was right after canonicalization.
+pub(crate) struct CanonicalModule {
+    pub module: Module,
+    pub declarations: Declarations,
+    pub ident_ids: IdentIds,
+    pub next_var: Variable,
+    pub pending_derives: PendingDerives,
+}
+
+/// `None` if the module contains something we don't store.
+pub(super) fn encode(
+    module_ids: &ModuleIds,
+    module: &Module,
+    declarations: &Declarations,
+    ident_ids: &IdentIds,
+    next_var: Variable,
+    pending_derives: &PendingDerives,
+) -> Option<Vec<u8>> {
+    let mut encoder = Encoder::new(module_ids);
+
+    encoder.module(module);
+    encoder.declarations(declarations);
+    encoder.ident_ids(ident_ids);
+    encoder.pod(next_var);
+    encoder.seq(pending_derives.iter(), |e, (opaque, (typ, derives))| {
+        e.pod(*opaque);
+        e.typ(typ);
+        e.pods(derives);
+    });
+
+    encoder.finish()
+}
+
+/// What canonicalizing a module takes from the modules it imports, for the key of its entry.
+/// Like everything we fingerprint, this gives the same bytes in every run for the same imports,
+/// so maps are written in order of their keys.
+pub(super) fn encode_imports(
+    module_ids: &ModuleIds,
+    exposed_symbols: &VecSet<Symbol>,
+    aliases: &MutMap<Symbol, Alias>,
+    abilities_store: &PendingAbilitiesStore,
+) -> Option<Vec<u8>> {
+    let mut encoder = Encoder::new(module_ids);
+
+    encoder.pod_set(exposed_symbols);
+
+    let aliases = sorted_by_symbol(&encoder.writer.modules, aliases);
+    encoder.seq(aliases.into_iter(), |e, (symbol, alias)| {
+        e.pod(*symbol);
+        e.alias(alias);
+    });
+
+    encoder.abilities_store(abilities_store);
+
+    encoder.finish()
+}
+
+/// What canonicalizing a module hands to the modules importing it, to fingerprint its interface.
+pub(super) fn encode_exports(
+    module_ids: &ModuleIds,
+    aliases: &MutMap<Symbol, (bool, Alias)>,
+    abilities_store: &PendingAbilitiesStore,
+) -> Vec<u8> {
+    let mut encoder = Encoder::new(module_ids);
+
+    encoder.aliases(aliases);
+    encoder.abilities_store(abilities_store);
+
+    encoder.writer.bytes()
+}
+
+pub(super) fn decode(
+    bytes: &[u8],
+    module_ids: &ModuleIds,
+    module_id: ModuleId,
+    exposed_symbols: VecSet<Symbol>,
+) -> Option<CanonicalModule> {
+    let mut reader = Reader::new(bytes);
+    let modules = read_module_names(&mut reader, module_ids)?;
+    reader.modules = &modules;
+
+    let mut decoder = Decoder { reader };
+
+    let module = decoder.module(module_id, exposed_symbols)?;
+    let declarations = decoder.declarations()?;
+    let ident_ids = decoder.ident_ids()?;
+    let next_var = decoder.pod()?;
+    let pending_derives = decoder
+        .seq(|d| Some((d.pod::<Symbol>()?, (d.typ()?, d.pods()?))))?
+        .into_iter()
+        .collect();
+
+    // trailing bytes mean the entry does not have the layout we expect
+    if decoder.reader.offset != bytes.len() {
+        return None;
+    }
+
+    Some(CanonicalModule {
+        module,
+        declarations,
+        ident_ids,
+        next_var,
+        pending_derives,
+    })
+}
+
+/// Sort a hash map by its keys, so its entries are written in the same order every time.
+fn sorted<K: Ord, V>(map: &MutMap<K, V>) -> Vec<(&K, &V)> {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    entries
+}
+
+/// Like [sorted], for maps keyed by symbols, whose order changes with the ids of their modules.
+fn sorted_by_symbol<'m, V>(
+    modules: &ModuleTable,
+    map: &'m MutMap<Symbol, V>,
+) -> Vec<(&'m Symbol, &'m V)> {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_by_key(|(symbol, _)| modules.order(**symbol));
+
+    entries
+}
+
+struct Encoder<'a> {
+    writer: Writer<'a>,
+}
+
+impl<'a> Encoder<'a> {
+    fn new(module_ids: &'a ModuleIds) -> Self {
+        Self {
+            writer: Writer::new(module_ids),
+        }
+    }
+
+    fn finish(self) -> Option<Vec<u8>> {
+        self.writer.finish()
+    }
+
+    fn pod<T: Pod>(&mut self, value: T) {
+        self.writer.write(value);
+    }
+
+    fn len(&mut self, len: usize) {
+        self.pod(len as u64);
+    }
+
+    fn bool(&mut self, value: bool) {
+        self.pod(value as u8);
+    }
+
+    fn str(&mut self, string: &str) {
+        self.len(string.len());
+        self.writer.buf.extend_from_slice(string.as_bytes());
+    }
+
+    fn pods<T: Pod>(&mut self, values: &[T]) {
+        self.len(values.len());
+
+        for value in values {
+            self.pod(*value);
+        }
+    }
+
+    fn seq<T>(&mut self, items: impl ExactSizeIterator<Item = T>, mut f: impl FnMut(&mut Self, T)) {
+        self.len(items.len());
+
+        for item in items {
+            f(self, item);
+        }
+    }
+
+    fn option<T>(&mut self, value: Option<T>, f: impl FnOnce(&mut Self, T)) {
+        match value {
+            None => self.pod(0u8),
+            Some(value) => {
+                self.pod(1u8);
+                f(self, value);
+            }
+        }
+    }
+
+    fn pod_set<T: Pod + PartialEq>(&mut self, set: &VecSet<T>) {
+        self.len(set.len());
+
+        for value in set.iter() {
+            self.pod(*value);
+        }
+    }
+
+    fn send_map<V>(&mut self, map: &SendMap<Lowercase, V>, mut f: impl FnMut(&mut Self, &V)) {
+        let mut entries: Vec<_> = map.iter().collect();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        self.seq(entries.into_iter(), |e, (label, value)| {
+            e.str(label.as_str());
+            f(e, value);
+        });
+    }
+
+    fn ident_ids(&mut self, ident_ids: &IdentIds) {
+        self.len(ident_ids.len());
+
+        for (_, ident) in ident_ids.ident_strs() {
+            self.str(ident);
+        }
+    }
+
+    fn module(&mut self, module: &Module) {
+        // the id and exposed symbols are known before canonicalizing, so they are not stored
+        let Module {
+            module_id: _,
+            exposed_imports,
+            exposed_symbols: _,
+            referenced_values,
+            referenced_types,
+            aliases,
+            rigid_variables,
+            abilities_store,
+            loc_expects,
+            loc_dbgs,
+            loc_crashes,
+        } = module;
+
+        let exposed_imports = sorted_by_symbol(&self.writer.modules, exposed_imports);
+        self.seq(exposed_imports.into_iter(), |e, (symbol, var)| {
+            e.pod(*symbol);
+            e.pod(*var);
+        });
+        self.pod_set(referenced_values);
+        self.pod_set(referenced_types);
+        self.aliases(aliases);
+
+        let RigidVariables {
+            named,
+            able,
+            wildcards,
+        } = rigid_variables;
+
+        self.seq(sorted(named).into_iter(), |e, (var, name)| {
+            e.pod(*var);
+            e.str(name.as_str());
+        });
+        self.seq(sorted(able).into_iter(), |e, (var, (name, ability))| {
+            e.pod(*var);
+            e.str(name.as_str());
+            e.pod(*ability);
+        });
+        self.pod_set(wildcards);
+
+        self.abilities_store(abilities_store);
+        self.seq(loc_expects.iter(), |e, (region, lookups)| {
+            e.pod(*region);
+            e.pods(lookups);
+        });
+        self.seq(loc_dbgs.iter(), |e, (region, lookup)| {
+            e.pod(*region);
+            e.pod(*lookup);
+        });
+        self.pod_set(loc_crashes);
+    }
+
+    fn aliases(&mut self, aliases: &MutMap<Symbol, (bool, Alias)>) {
+        let aliases = sorted_by_symbol(&self.writer.modules, aliases);
+
+        self.seq(aliases.into_iter(), |e, (symbol, (exposed, alias))| {
+            e.pod(*symbol);
+            e.bool(*exposed);
+            e.alias(alias);
+        });
+    }
+
+    fn abilities_store(&mut self, store: &PendingAbilitiesStore) {
+        let mut abilities: Vec<_> = store.iter_abilities().collect();
+        abilities.sort_by_key(|(ability, _)| self.writer.modules.order(*ability));
+
+        self.seq(abilities.into_iter(), |e, (ability, members)| {
+            e.pod(ability);
+            e.seq(members.iter(), |e, member| {
+                let AbilityMemberData {
+                    parent_ability,
+                    region,
+                    typ,
+                } = &store.root_ability_members()[member];
+
+                e.pod(*member);
+                e.pod(*parent_ability);
+                e.pod(*region);
+
+                match typ {
+                    PendingMemberType::Local {
+                        signature_var,
+                        signature,
+                        variables,
+                    } => {
+                        let MemberVariables {
+                            able_vars,
+                            rigid_vars,
+                            flex_vars,
+                        } = variables;
+
+                        e.pod(0u8);
+                        e.pod(*signature_var);
+                        e.typ(signature);
+                        e.pods(able_vars);
+                        e.pods(rigid_vars);
+                        e.pods(flex_vars);
+                    }
+                    PendingMemberType::Imported => e.pod(1u8),
+                }
+            });
+        });
+
+        let mut implementations: Vec<_> = store.iter_declared_implementations().collect();
+        implementations.sort_by_key(|(impl_key, _)| {
+            (
+                self.writer.modules.order(impl_key.opaque),
+                self.writer.modules.order(impl_key.ability_member),
+            )
+        });
+
+        self.seq(implementations.into_iter(), |e, (impl_key, member_impl)| {
+            e.pod(impl_key.opaque);
+            e.pod(impl_key.ability_member);
+
+            match member_impl {
+                MemberImpl::Impl(symbol) => {
+                    e.pod(0u8);
+                    e.pod(*symbol);
+                }
+                MemberImpl::Derived => e.pod(1u8),
+                MemberImpl::Error => e.pod(2u8),
+            }
+        });
+
+        let mut specializations: Vec<_> = store.iter_specializations().collect();
+        specializations.sort_by_key(|(symbol, _)| self.writer.modules.order(*symbol));
+
+        self.seq(specializations.into_iter(), |e, (symbol, info)| {
+            e.pod(symbol);
+            e.seq(
+                info.specialization_lambda_sets.iter(),
+                |e, (region, var)| {
+                    e.pod(*region);
+                    e.pod(*var);
+                },
+            );
+        });
+
+        self.pod(store.specialization_ids_used());
+    }
+
+    fn declarations(&mut self, declarations: &Declarations) {
+        let Declarations {
+            declarations,
+            variables,
+            symbols,
+            annotations,
+            specializes,
+            function_bodies,
+            expressions,
+            destructs,
+        } = declarations;
+
+        self.pods(declarations);
+        self.pods(variables);
+        self.pods(symbols);
+        self.seq(annotations.iter(), |e, annotation| {
+            e.option(annotation.as_ref(), |e, annotation| {
+                e.annotation(annotation)
+            })
+        });
+        self.seq(specializes.iter(), |e, (index, symbol)| {
+            e.len(*index);
+            e.pod(*symbol);
+        });
+        self.seq(function_bodies.iter(), |e, loc_function_def| {
+            let FunctionDef {
+                closure_type,
+                return_type,
+                captured_symbols,
+                arguments,
+            } = &loc_function_def.value;
+
+            e.pod(loc_function_def.region);
+            e.pod(*closure_type);
+            e.pod(*return_type);
+            e.pods(captured_symbols);
+            e.arguments(arguments);
+        });
+        self.seq(expressions.iter(), |e, loc_expr| e.loc_expr(loc_expr));
+        self.seq(destructs.iter(), |e, destruct| {
+            let DestructureDef {
+                loc_pattern,
+                pattern_vars,
+            } = destruct;
+
+            e.loc_pattern(loc_pattern);
+            e.seq(pattern_vars.iter(), |e, (symbol, var)| {
+                e.pod(*symbol);
+                e.pod(*var);
+            });
+        });
+    }
+
+    fn def(&mut self, def: &Def) {
+        let Def {
+            loc_pattern,
+            loc_expr,
+            expr_var,
+            pattern_vars,
+            annotation,
+        } = def;
+
+        self.loc_pattern(loc_pattern);
+        self.loc_expr(loc_expr);
+        self.pod(*expr_var);
+
+        let mut pattern_vars: Vec<_> = pattern_vars.iter().collect();
+        pattern_vars.sort_by_key(|(symbol, _)| self.writer.modules.order(**symbol));
+
+        self.seq(pattern_vars.into_iter(), |e, (symbol, var)| {
+            e.pod(*symbol);
+            e.pod(*var);
+        });
+        self.option(annotation.as_ref(), |e, annotation| {
+            e.annotation(annotation)
+        });
+    }
+
+    fn annotation(&mut self, annotation: &Annotation) {
+        let Annotation {
+            signature,
+            introduced_variables,
+            aliases,
+            region,
+        } = annotation;
+
+        let IntroducedVariables {
+            wildcards,
+            lambda_sets,
+            inferred,
+            named,
+            able,
+            host_exposed_aliases,
+        } = introduced_variables;
+
+        self.typ(signature);
+        self.pods(wildcards);
+        self.pods(lambda_sets);
+        self.pods(inferred);
+        self.len(named.len());
+
+        for named in named.iter() {
+            self.pod(named.variable);
+            self.str(named.name.as_str());
+            self.pod(named.first_seen);
+        }
+
+        self.len(able.len());
+
+        for able in able.iter() {
+            self.pod(able.variable);
+            self.str(able.name.as_str());
+            self.pod(able.ability);
+            self.pod(able.first_seen);
+        }
+
+        self.seq(host_exposed_aliases.iter(), |e, (symbol, var)| {
+            e.pod(*symbol);
+            e.pod(*var);
+        });
+        self.seq(aliases.iter(), |e, (symbol, alias)| {
+            e.pod(*symbol);
+            e.alias(alias);
+        });
+        self.pod(*region);
+    }
+
+    fn alias(&mut self, alias: &Alias) {
+        let Alias {
+            region,
+            type_variables,
+            lambda_set_variables,
+            recursion_variables,
+            typ,
+            kind,
+        } = alias;
+
+        let mut recursion_variables: Vec<_> = recursion_variables.iter().copied().collect();
+        recursion_variables.sort();
+
+        self.pod(*region);
+        self.seq(type_variables.iter(), |e, loc_var| {
+            let AliasVar {
+                name,
+                var,
+                opt_bound_ability,
+            } = &loc_var.value;
+
+            e.pod(loc_var.region);
+            e.str(name.as_str());
+            e.pod(*var);
+            e.option(*opt_bound_ability, |e, ability| e.pod(ability));
+        });
+        self.lambda_sets(lambda_set_variables);
+        self.pods(&recursion_variables);
+        self.typ(typ);
+        self.pod(*kind);
+    }
+
+    fn lambda_sets(&mut self, lambda_sets: &[LambdaSet]) {
+        self.seq(lambda_sets.iter(), |e, LambdaSet(typ)| e.typ(typ));
+    }
+
+    fn types(&mut self, types: &[Type]) {
+        self.seq(types.iter(), |e, typ| e.typ(typ));
+    }
+
+    fn tags(&mut self, tags: &[(TagName, Vec<Type>)]) {
+        self.seq(tags.iter(), |e, (tag_name, arguments)| {
+            e.str(tag_name.0.as_str());
+            e.types(arguments);
+        });
+    }
+
+    fn ext(&mut self, ext: &TypeExtension) {
+        match ext {
+            TypeExtension::Open(typ) => {
+                self.pod(0u8);
+                self.typ(typ);
+            }
+            TypeExtension::Closed => self.pod(1u8),
+        }
+    }
+
+    fn typ(&mut self, typ: &Type) {
+        match typ {
+            Type::EmptyRec => self.pod(0u8),
+            Type::EmptyTagUnion => self.pod(1u8),
+            Type::Function(arguments, closure, ret) => {
+                self.pod(2u8);
+                self.types(arguments);
+                self.typ(closure);
+                self.typ(ret);
+            }
+            Type::Record(fields, ext) => {
+                self.pod(3u8);
+                self.send_map(fields, |e, field| {
+                    let (tag, typ) = match field {
+                        RecordField::Demanded(typ) => (0u8, typ),
+                        RecordField::Required(typ) => (1, typ),
+                        RecordField::Optional(typ) => (2, typ),
+                        RecordField::RigidOptional(typ) => (3, typ),
+                    };
+
+                    e.pod(tag);
+                    e.typ(typ);
+                });
+                self.ext(ext);
+            }
+            Type::TagUnion(tags, ext) => {
+                self.pod(4u8);
+                self.tags(tags);
+                self.ext(ext);
+            }
+            Type::FunctionOrTagUnion(tag_name, symbol, ext) => {
+                self.pod(5u8);
+                self.str(tag_name.0.as_str());
+                self.pod(*symbol);
+                self.ext(ext);
+            }
+            Type::ClosureTag {
+                name,
+                captures,
+                ambient_function,
+            } => {
+                self.pod(6u8);
+                self.pod(*name);
+                self.types(captures);
+                self.pod(*ambient_function);
+            }
+            Type::UnspecializedLambdaSet {
+                unspecialized: Uls(var, member, region),
+            } => {
+                self.pod(7u8);
+                self.pod(*var);
+                self.pod(*member);
+                self.pod(*region);
+            }
+            Type::DelayedAlias(AliasCommon {
+                symbol,
+                type_arguments,
+                lambda_set_variables,
+            }) => {
+                self.pod(8u8);
+                self.pod(*symbol);
+                self.types(type_arguments);
+                self.lambda_sets(lambda_set_variables);
+            }
+            Type::Alias {
+                symbol,
+                type_arguments,
+                lambda_set_variables,
+                actual,
+                kind,
+            } => {
+                self.pod(9u8);
+                self.pod(*symbol);
+                self.seq(
+                    type_arguments.iter(),
+                    |e, OptAbleType { typ, opt_ability }| {
+                        e.typ(typ);
+                        e.option(*opt_ability, |e, ability| e.pod(ability));
+                    },
+                );
+                self.lambda_sets(lambda_set_variables);
+                self.typ(actual);
+                self.pod(*kind);
+            }
+            Type::HostExposedAlias {
+                name,
+                type_arguments,
+                lambda_set_variables,
+                actual_var,
+                actual,
+            } => {
+                self.pod(10u8);
+                self.pod(*name);
+                self.types(type_arguments);
+                self.lambda_sets(lambda_set_variables);
+                self.pod(*actual_var);
+                self.typ(actual);
+            }
+            Type::RecursiveTagUnion(rec_var, tags, ext) => {
+                self.pod(11u8);
+                self.pod(*rec_var);
+                self.tags(tags);
+                self.ext(ext);
+            }
+            Type::Apply(symbol, arguments, region) => {
+                self.pod(12u8);
+                self.pod(*symbol);
+                self.types(arguments);
+                self.pod(*region);
+            }
+            Type::Variable(var) => {
+                self.pod(13u8);
+                self.pod(*var);
+            }
+            Type::RangedNumber(range) => {
+                self.pod(14u8);
+                self.pod(*range);
+            }
+            Type::Tuple(elems, ext) => {
+                self.pod(15u8);
+                self.seq(elems.iter(), |e, (index, typ)| {
+                    e.len(*index);
+                    e.typ(typ);
+                });
+                self.ext(ext);
+            }
+            Type::Erroneous(_) => self.writer.unsupported = true,
+        }
+    }
+
+    fn loc_expr(&mut self, loc_expr: &Loc<Expr>) {
+        self.pod(loc_expr.region);
+        self.expr(&loc_expr.value);
+    }
+
+    fn loc_exprs(&mut self, loc_exprs: &[Loc<Expr>]) {
+        self.seq(loc_exprs.iter(), |e, loc_expr| e.loc_expr(loc_expr));
+    }
+
+    fn var_loc_exprs(&mut self, arguments: &[(Variable, Loc<Expr>)]) {
+        self.seq(arguments.iter(), |e, (var, loc_expr)| {
+            e.pod(*var);
+            e.loc_expr(loc_expr);
+        });
+    }
+
+    fn var_exprs(&mut self, arguments: &[(Variable, Expr)]) {
+        self.seq(arguments.iter(), |e, (var, expr)| {
+            e.pod(*var);
+            e.expr(expr);
+        });
+    }
+
+    fn fields(&mut self, fields: &SendMap<Lowercase, Field>) {
+        self.send_map(fields, |e, field| {
+            let Field {
+                var,
+                region,
+                loc_expr,
+            } = field;
+
+            e.pod(*var);
+            e.pod(*region);
+            e.loc_expr(loc_expr);
+        });
+    }
+
+    fn index_or_field(&mut self, index_or_field: &IndexOrField) {
+        match index_or_field {
+            IndexOrField::Field(field) => {
+                self.pod(0u8);
+                self.str(field.as_str());
+            }
+            IndexOrField::Index(index) => {
+                self.pod(1u8);
+                self.len(*index);
+            }
+        }
+    }
+
+    fn arguments(&mut self, arguments: &[(Variable, AnnotatedMark, Loc<Pattern>)]) {
+        self.seq(arguments.iter(), |e, (var, mark, loc_pattern)| {
+            e.pod(*var);
+            e.pod(*mark);
+            e.loc_pattern(loc_pattern);
+        });
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Num(var, string, value, bound) => {
+                self.pod(0u8);
+                self.pod(*var);
+                self.str(string);
+                self.pod(*value);
+                self.pod(*bound);
+            }
+            Expr::Int(var, precision_var, string, value, bound) => {
+                self.pod(1u8);
+                self.pod(*var);
+                self.pod(*precision_var);
+                self.str(string);
+                self.pod(*value);
+                self.pod(*bound);
+            }
+            Expr::Float(var, precision_var, string, value, bound) => {
+                self.pod(2u8);
+                self.pod(*var);
+                self.pod(*precision_var);
+                self.str(string);
+                self.pod(*value);
+                self.pod(*bound);
+            }
+            Expr::Str(string) => {
+                self.pod(3u8);
+                self.str(string);
+            }
+            Expr::SingleQuote(character) => {
+                self.pod(4u8);
+                self.pod(*character);
+            }
+            Expr::List {
+                elem_var,
+                loc_elems,
+            } => {
+                self.pod(5u8);
+                self.pod(*elem_var);
+                self.loc_exprs(loc_elems);
+            }
+            Expr::Var(symbol) => {
+                self.pod(6u8);
+                self.pod(*symbol);
+            }
+            Expr::AbilityMember(symbol, specialization_id, var) => {
+                self.pod(7u8);
+                self.pod(*symbol);
+                self.pod(*specialization_id);
+                self.pod(*var);
+            }
+            Expr::When {
+                loc_cond,
+                cond_var,
+                expr_var,
+                region,
+                branches,
+                branches_cond_var,
+                exhaustive,
+            } => {
+                self.pod(8u8);
+                self.loc_expr(loc_cond);
+                self.pod(*cond_var);
+                self.pod(*expr_var);
+                self.pod(*region);
+                self.seq(branches.iter(), |e, branch| {
+                    let WhenBranch {
+                        patterns,
+                        value,
+                        guard,
+                        redundant,
+                    } = branch;
+
+                    e.seq(patterns.iter(), |e, pattern| {
+                        e.loc_pattern(&pattern.pattern);
+                        e.bool(pattern.degenerate);
+                    });
+                    e.loc_expr(value);
+                    e.option(guard.as_ref(), |e, guard| e.loc_expr(guard));
+                    e.pod(*redundant);
+                });
+                self.pod(*branches_cond_var);
+                self.pod(*exhaustive);
+            }
+            Expr::If {
+                cond_var,
+                branch_var,
+                branches,
+                final_else,
+            } => {
+                self.pod(9u8);
+                self.pod(*cond_var);
+                self.pod(*branch_var);
+                self.seq(branches.iter(), |e, (loc_cond, loc_then)| {
+                    e.loc_expr(loc_cond);
+                    e.loc_expr(loc_then);
+                });
+                self.loc_expr(final_else);
+            }
+            Expr::LetRec(defs, loc_continuation, cycle_mark) => {
+                self.pod(10u8);
+                self.seq(defs.iter(), |e, def| e.def(def));
+                self.loc_expr(loc_continuation);
+                self.pod(*cycle_mark);
+            }
+            Expr::LetNonRec(def, loc_continuation) => {
+                self.pod(11u8);
+                self.def(def);
+                self.loc_expr(loc_continuation);
+            }
+            Expr::Call(boxed, arguments, called_via) => {
+                let (fn_var, loc_fn, closure_var, ret_var) = &**boxed;
+
+                self.pod(12u8);
+                self.pod(*fn_var);
+                self.loc_expr(loc_fn);
+                self.pod(*closure_var);
+                self.pod(*ret_var);
+                self.var_loc_exprs(arguments);
+                self.pod(*called_via);
+            }
+            Expr::RunLowLevel { op, args, ret_var } => {
+                self.pod(13u8);
+                self.pod(*op);
+                self.var_exprs(args);
+                self.pod(*ret_var);
+            }
+            Expr::ForeignCall {
+                foreign_symbol,
+                args,
+                ret_var,
+            } => {
+                self.pod(14u8);
+                self.str(foreign_symbol.as_str());
+                self.var_exprs(args);
+                self.pod(*ret_var);
+            }
+            Expr::Closure(closure_data) => {
+                let ClosureData {
+                    function_type,
+                    closure_type,
+                    return_type,
+                    name,
+                    captured_symbols,
+                    recursive,
+                    arguments,
+                    loc_body,
+                } = closure_data;
+
+                self.pod(15u8);
+                self.pod(*function_type);
+                self.pod(*closure_type);
+                self.pod(*return_type);
+                self.pod(*name);
+                self.pods(captured_symbols);
+                self.pod(*recursive);
+                self.arguments(arguments);
+                self.loc_expr(loc_body);
+            }
+            Expr::Record { record_var, fields } => {
+                self.pod(16u8);
+                self.pod(*record_var);
+                self.fields(fields);
+            }
+            Expr::EmptyRecord => self.pod(17u8),
+            Expr::Access {
+                record_var,
+                ext_var,
+                field_var,
+                loc_expr,
+                field,
+            } => {
+                self.pod(18u8);
+                self.pod(*record_var);
+                self.pod(*ext_var);
+                self.pod(*field_var);
+                self.loc_expr(loc_expr);
+                self.str(field.as_str());
+            }
+            Expr::Accessor(accessor_data) => {
+                let AccessorData {
+                    name,
+                    function_var,
+                    record_var,
+                    closure_var,
+                    ext_var,
+                    field_var,
+                    field,
+                } = accessor_data;
+
+                self.pod(19u8);
+                self.pod(*name);
+                self.pod(*function_var);
+                self.pod(*record_var);
+                self.pod(*closure_var);
+                self.pod(*ext_var);
+                self.pod(*field_var);
+                self.index_or_field(field);
+            }
+            Expr::Update {
+                record_var,
+                ext_var,
+                symbol,
+                updates,
+            } => {
+                self.pod(20u8);
+                self.pod(*record_var);
+                self.pod(*ext_var);
+                self.pod(*symbol);
+                self.fields(updates);
+            }
+            Expr::Tag {
+                tag_union_var,
+                ext_var,
+                name,
+                arguments,
+            } => {
+                self.pod(21u8);
+                self.pod(*tag_union_var);
+                self.pod(*ext_var);
+                self.str(name.0.as_str());
+                self.var_loc_exprs(arguments);
+            }
+            Expr::ZeroArgumentTag {
+                closure_name,
+                variant_var,
+                ext_var,
+                name,
+            } => {
+                self.pod(22u8);
+                self.pod(*closure_name);
+                self.pod(*variant_var);
+                self.pod(*ext_var);
+                self.str(name.0.as_str());
+            }
+            Expr::OpaqueRef {
+                opaque_var,
+                name,
+                argument,
+                specialized_def_type,
+                type_arguments,
+                lambda_set_variables,
+            } => {
+                let (argument_var, loc_argument) = &**argument;
+
+                self.pod(23u8);
+                self.pod(*opaque_var);
+                self.pod(*name);
+                self.pod(*argument_var);
+                self.loc_expr(loc_argument);
+                self.typ(specialized_def_type);
+                self.pods(type_arguments);
+                self.lambda_sets(lambda_set_variables);
+            }
+            Expr::OpaqueWrapFunction(wrap_data) => {
+                let OpaqueWrapFunctionData {
+                    opaque_name,
+                    opaque_var,
+                    specialized_def_type,
+                    type_arguments,
+                    lambda_set_variables,
+                    function_name,
+                    function_var,
+                    argument_var,
+                    closure_var,
+                } = wrap_data;
+
+                self.pod(24u8);
+                self.pod(*opaque_name);
+                self.pod(*opaque_var);
+                self.typ(specialized_def_type);
+                self.pods(type_arguments);
+                self.lambda_sets(lambda_set_variables);
+                self.pod(*function_name);
+                self.pod(*function_var);
+                self.pod(*argument_var);
+                self.pod(*closure_var);
+            }
+            Expr::Expect {
+                loc_condition,
+                loc_continuation,
+                lookups_in_cond,
+            } => {
+                self.pod(25u8);
+                self.loc_expr(loc_condition);
+                self.loc_expr(loc_continuation);
+                self.pods(lookups_in_cond);
+            }
+            Expr::ExpectFx {
+                loc_condition,
+                loc_continuation,
+                lookups_in_cond,
+            } => {
+                self.pod(26u8);
+                self.loc_expr(loc_condition);
+                self.loc_expr(loc_continuation);
+                self.pods(lookups_in_cond);
+            }
+            Expr::Dbg {
+                loc_expr,
+                variable,
+                symbol,
+            } => {
+                self.pod(27u8);
+                self.loc_expr(loc_expr);
+                self.pod(*variable);
+                self.pod(*symbol);
+            }
+            Expr::Crash { msg, ret_var } => {
+                self.pod(28u8);
+                self.loc_expr(msg);
+                self.pod(*ret_var);
+            }
+            Expr::TypedHole(var) => {
+                self.pod(29u8);
+                self.pod(*var);
+            }
+            Expr::Tuple { tuple_var, elems } => {
+                self.pod(30u8);
+                self.pod(*tuple_var);
+                self.seq(elems.iter(), |e, (var, loc_expr)| {
+                    e.pod(*var);
+                    e.loc_expr(loc_expr);
+                });
+            }
+            Expr::TupleAccess {
+                tuple_var,
+                ext_var,
+                elem_var,
+                loc_expr,
+                index,
+            } => {
+                self.pod(31u8);
+                self.pod(*tuple_var);
+                self.pod(*ext_var);
+                self.pod(*elem_var);
+                self.loc_expr(loc_expr);
+                self.len(*index);
+            }
+            Expr::RuntimeError(_) => self.writer.unsupported = true,
+        }
+    }
+
+    fn loc_pattern(&mut self, loc_pattern: &Loc<Pattern>) {
+        self.pod(loc_pattern.region);
+        self.pattern(&loc_pattern.value);
+    }
+
+    fn pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Identifier(symbol) => {
+                self.pod(0u8);
+                self.pod(*symbol);
+            }
+            Pattern::AppliedTag {
+                whole_var,
+                ext_var,
+                tag_name,
+                arguments,
+            } => {
+                self.pod(1u8);
+                self.pod(*whole_var);
+                self.pod(*ext_var);
+                self.str(tag_name.0.as_str());
+                self.seq(arguments.iter(), |e, (var, loc_pattern)| {
+                    e.pod(*var);
+                    e.loc_pattern(loc_pattern);
+                });
+            }
+            Pattern::UnwrappedOpaque {
+                whole_var,
+                opaque,
+                argument,
+                specialized_def_type,
+                type_arguments,
+                lambda_set_variables,
+            } => {
+                let (argument_var, loc_argument) = &**argument;
+
+                self.pod(2u8);
+                self.pod(*whole_var);
+                self.pod(*opaque);
+                self.pod(*argument_var);
+                self.loc_pattern(loc_argument);
+                self.typ(specialized_def_type);
+                self.pods(type_arguments);
+                self.lambda_sets(lambda_set_variables);
+            }
+            Pattern::RecordDestructure {
+                whole_var,
+                ext_var,
+                destructs,
+            } => {
+                self.pod(3u8);
+                self.pod(*whole_var);
+                self.pod(*ext_var);
+                self.seq(destructs.iter(), |e, loc_destruct| {
+                    let RecordDestruct {
+                        var,
+                        label,
+                        symbol,
+                        typ,
+                    } = &loc_destruct.value;
+
+                    e.pod(loc_destruct.region);
+                    e.pod(*var);
+                    e.str(label.as_str());
+                    e.pod(*symbol);
+
+                    match typ {
+                        DestructType::Required => e.pod(0u8),
+                        DestructType::Optional(var, loc_expr) => {
+                            e.pod(1u8);
+                            e.pod(*var);
+                            e.loc_expr(loc_expr);
+                        }
+                        DestructType::Guard(var, loc_pattern) => {
+                            e.pod(2u8);
+                            e.pod(*var);
+                            e.loc_pattern(loc_pattern);
+                        }
+                    }
+                });
+            }
+            Pattern::List {
+                list_var,
+                elem_var,
+                patterns,
+            } => {
+                let ListPatterns { patterns, opt_rest } = patterns;
+
+                self.pod(4u8);
+                self.pod(*list_var);
+                self.pod(*elem_var);
+                self.seq(patterns.iter(), |e, loc_pattern| e.loc_pattern(loc_pattern));
+                self.option(opt_rest.as_ref(), |e, (index, opt_name)| {
+                    e.len(*index);
+                    e.option(*opt_name, |e, name| e.pod(name));
+                });
+            }
+            Pattern::NumLiteral(var, string, value, bound) => {
+                self.pod(5u8);
+                self.pod(*var);
+                self.str(string);
+                self.pod(*value);
+                self.pod(*bound);
+            }
+            Pattern::IntLiteral(var, precision_var, string, value, bound) => {
+                self.pod(6u8);
+                self.pod(*var);
+                self.pod(*precision_var);
+                self.str(string);
+                self.pod(*value);
+                self.pod(*bound);
+            }
+            Pattern::FloatLiteral(var, precision_var, string, value, bound) => {
+                self.pod(7u8);
+                self.pod(*var);
+                self.pod(*precision_var);
+                self.str(string);
+                self.pod(*value);
+                self.pod(*bound);
+            }
+            Pattern::StrLiteral(string) => {
+                self.pod(8u8);
+                self.str(string);
+            }
+            Pattern::SingleQuote(character) => {
+                self.pod(9u8);
+                self.pod(*character);
+            }
+            Pattern::Underscore => self.pod(10u8),
+            Pattern::AbilityMemberSpecialization { ident, specializes } => {
+                self.pod(11u8);
+                self.pod(*ident);
+                self.pod(*specializes);
+            }
+            Pattern::TupleDestructure {
+                whole_var,
+                ext_var,
+                destructs,
+            } => {
+                self.pod(12u8);
+                self.pod(*whole_var);
+                self.pod(*ext_var);
+                self.seq(destructs.iter(), |e, loc_destruct| {
+                    let TupleDestruct {
+                        var,
+                        destruct_index,
+                        typ: (guard_var, loc_pattern),
+                    } = &loc_destruct.value;
+
+                    e.pod(loc_destruct.region);
+                    e.pod(*var);
+                    e.len(*destruct_index);
+                    e.pod(*guard_var);
+                    e.loc_pattern(loc_pattern);
+                });
+            }
+            Pattern::Shadowed(..)
+            | Pattern::OpaqueNotInScope(_)
+            | Pattern::UnsupportedPattern(_)
+            | Pattern::MalformedPattern(..) => self.writer.unsupported = true,
+        }
+    }
+}
+
+struct Decoder<'a> {
+    reader: Reader<'a>,
+}
+
+impl<'a> Decoder<'a> {
+    fn pod<T: Pod>(&mut self) -> Option<T> {
+        self.reader.read()
+    }
+
+    fn len(&mut self) -> Option<usize> {
+        usize::try_from(self.pod::<u64>()?).ok()
+    }
+
+    fn bool(&mut self) -> Option<bool> {
+        match self.pod::<u8>()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    fn str(&mut self) -> Option<&'a str> {
+        let len = self.len()?;
+
+        std::str::from_utf8(self.reader.read_bytes(len)?).ok()
+    }
+
+    fn lowercase(&mut self) -> Option<Lowercase> {
+        Some(self.str()?.into())
+    }
+
+    fn tag_name(&mut self) -> Option<TagName> {
+        Some(TagName(self.str()?.into()))
+    }
+
+    fn pods<T: Pod>(&mut self) -> Option<Vec<T>> {
+        self.seq(|d| d.pod())
+    }
+
+    fn seq<T>(&mut self, mut f: impl FnMut(&mut Self) -> Option<T>) -> Option<Vec<T>> {
+        let len = self.len()?;
+
+        // don't trust the length with an allocation before we've read that many items
+        let mut items = Vec::with_capacity(len.min(self.reader.bytes.len()));
+
+        for _ in 0..len {
+            items.push(f(self)?);
+        }
+
+        Some(items)
+    }
+
+    fn option<T>(&mut self, f: impl FnOnce(&mut Self) -> Option<T>) -> Option<Option<T>> {
+        match self.pod::<u8>()? {
+            0 => Some(None),
+            1 => Some(Some(f(self)?)),
+            _ => None,
+        }
+    }
+
+    /// Sets are written in their own order, which [VecSet]'s `FromIterator` would not keep.
+    fn set<T: PartialEq>(items: Vec<T>) -> VecSet<T> {
+        let mut set = VecSet::with_capacity(items.len());
+
+        for item in items {
+            set.insert(item);
+        }
+
+        set
+    }
+
+    fn pod_set<T: Pod + PartialEq>(&mut self) -> Option<VecSet<T>> {
+        Some(Self::set(self.pods()?))
+    }
+
+    fn send_map<V>(
+        &mut self,
+        mut f: impl FnMut(&mut Self) -> Option<V>,
+    ) -> Option<SendMap<Lowercase, V>>
+    where
+        V: Clone,
+    {
+        let entries = self.seq(|d| Some((d.lowercase()?, f(d)?)))?;
+
+        Some(entries.into_iter().collect())
+    }
+
+    fn ident_ids(&mut self) -> Option<IdentIds> {
+        let mut ident_ids = IdentIds::default();
+
+        for index in 0..self.len()? {
+            let ident = self.str()?;
+
+            // generated names are their own index, and are kept apart from the others
+            if ident == index.to_string() {
+                ident_ids.gen_unique();
+            } else {
+                ident_ids.add_str(ident);
+            }
+        }
+
+        Some(ident_ids)
+    }
+
+    fn module(&mut self, module_id: ModuleId, exposed_symbols: VecSet<Symbol>) -> Option<Module> {
+        let exposed_imports = self
+            .seq(|d| Some((d.pod::<Symbol>()?, d.pod::<Variable>()?)))?
+            .into_iter()
+            .collect();
+        let referenced_values = self.pod_set()?;
+        let referenced_types = self.pod_set()?;
+        let aliases = self
+            .seq(|d| Some((d.pod::<Symbol>()?, (d.bool()?, d.alias()?))))?
+            .into_iter()
+            .collect();
+
+        let named = self
+            .seq(|d| Some((d.pod::<Variable>()?, d.lowercase()?)))?
+            .into_iter()
+            .collect();
+        let able = self
+            .seq(|d| Some((d.pod::<Variable>()?, (d.lowercase()?, d.pod::<Symbol>()?))))?
+            .into_iter()
+            .collect();
+        let wildcards = self.pod_set()?;
+
+        let abilities_store = self.abilities_store()?;
+        let loc_expects = self
+            .seq(|d| Some((d.pod()?, d.pods()?)))?
+            .into_iter()
+            .collect();
+        let loc_dbgs = self
+            .seq(|d| Some((d.pod()?, d.pod()?)))?
+            .into_iter()
+            .collect();
+        let loc_crashes = self.pod_set()?;
+
+        Some(Module {
+            module_id,
+            exposed_imports,
+            exposed_symbols,
+            referenced_values,
+            referenced_types,
+            aliases,
+            rigid_variables: RigidVariables {
+                named,
+                able,
+                wildcards,
+            },
+            abilities_store,
+            loc_expects,
+            loc_dbgs,
+            loc_crashes,
+        })
+    }
+
+    fn abilities_store(&mut self) -> Option<PendingAbilitiesStore> {
+        let mut store = PendingAbilitiesStore::default();
+
+        for _ in 0..self.len()? {
+            let ability = self.pod::<Symbol>()?;
+            let members = self.seq(|d| {
+                let member = d.pod::<Symbol>()?;
+                let parent_ability = d.pod()?;
+                let region = d.pod()?;
+                let typ = match d.pod::<u8>()? {
+                    0 => PendingMemberType::Local {
+                        signature_var: d.pod()?,
+                        signature: d.typ()?,
+                        variables: MemberVariables {
+                            able_vars: d.pods()?,
+                            rigid_vars: d.pods()?,
+                            flex_vars: d.pods()?,
+                        },
+                    },
+                    1 => PendingMemberType::Imported,
+                    _ => return None,
+                };
+
+                Some((
+                    member,
+                    AbilityMemberData {
+                        parent_ability,
+                        region,
+                        typ,
+                    },
+                ))
+            })?;
+
+            store.register_ability(ability, members);
+        }
+
+        for _ in 0..self.len()? {
+            let opaque = self.pod::<Symbol>()?;
+            let ability_member = self.pod::<Symbol>()?;
+            let member_impl = match self.pod::<u8>()? {
+                0 => MemberImpl::Impl(self.pod()?),
+                1 => MemberImpl::Derived,
+                2 => MemberImpl::Error,
+                _ => return None,
+            };
+
+            store.register_declared_implementations(opaque, [(ability_member, member_impl)]);
+        }
+
+        for _ in 0..self.len()? {
+            let symbol = self.pod::<Symbol>()?;
+            let lambda_sets = self.seq(|d| Some((d.pod::<u8>()?, d.pod::<Variable>()?)))?;
+
+            store.import_specialization(&MemberSpecializationInfo::<Resolved>::new(
+                symbol,
+                lambda_sets.into_iter().collect(),
+            ));
+        }
+
+        // the specialization ids handed out during canonicalization must not be handed out again
+        for _ in 0..self.pod::<u32>()? {
+            store.fresh_specialization_id();
+        }
+
+        Some(store)
+    }
+
+    fn declarations(&mut self) -> Option<Declarations> {
+        Some(Declarations {
+            declarations: self.pods()?,
+            variables: self.pods()?,
+            symbols: self.pods()?,
+            annotations: self.seq(|d| d.option(|d| d.annotation()))?,
+            specializes: self
+                .seq(|d| Some((d.len()?, d.pod::<Symbol>()?)))?
+                .into_iter()
+                .collect(),
+            function_bodies: self.seq(|d| {
+                let region = d.pod()?;
+                let function_def = FunctionDef {
+                    closure_type: d.pod()?,
+                    return_type: d.pod()?,
+                    captured_symbols: d.pods()?,
+                    arguments: d.arguments()?,
+                };
+
+                Some(Loc::at(region, function_def))
+            })?,
+            expressions: self.seq(|d| d.loc_expr())?,
+            destructs: self.seq(|d| {
+                Some(DestructureDef {
+                    loc_pattern: d.loc_pattern()?,
+                    pattern_vars: d
+                        .seq(|d| Some((d.pod::<Symbol>()?, d.pod::<Variable>()?)))?
+                        .into_iter()
+                        .collect(),
+                })
+            })?,
+        })
+    }
+
+    fn def(&mut self) -> Option<Def> {
+        Some(Def {
+            loc_pattern: self.loc_pattern()?,
+            loc_expr: self.loc_expr()?,
+            expr_var: self.pod()?,
+            pattern_vars: self
+                .seq(|d| Some((d.pod::<Symbol>()?, d.pod::<Variable>()?)))?
+                .into_iter()
+                .collect(),
+            annotation: self.option(|d| d.annotation())?,
+        })
+    }
+
+    fn annotation(&mut self) -> Option<Annotation> {
+        let signature = self.typ()?;
+        let introduced_variables = IntroducedVariables {
+            wildcards: self.pods()?,
+            lambda_sets: self.pods()?,
+            inferred: self.pods()?,
+            named: Self::set(self.seq(|d| {
+                Some(NamedVariable {
+                    variable: d.pod()?,
+                    name: d.lowercase()?,
+                    first_seen: d.pod()?,
+                })
+            })?),
+            able: Self::set(self.seq(|d| {
+                Some(AbleVariable {
+                    variable: d.pod()?,
+                    name: d.lowercase()?,
+                    ability: d.pod()?,
+                    first_seen: d.pod()?,
+                })
+            })?),
+            host_exposed_aliases: self
+                .seq(|d| Some((d.pod::<Symbol>()?, d.pod::<Variable>()?)))?
+                .into_iter()
+                .collect(),
+        };
+
+        Some(Annotation {
+            signature,
+            introduced_variables,
+            aliases: self
+                .seq(|d| Some((d.pod::<Symbol>()?, d.alias()?)))?
+                .into_iter()
+                .collect(),
+            region: self.pod()?,
+        })
+    }
+
+    fn alias(&mut self) -> Option<Alias> {
+        Some(Alias {
+            region: self.pod()?,
+            type_variables: self.seq(|d| {
+                let region = d.pod()?;
+                let alias_var = AliasVar {
+                    name: d.lowercase()?,
+                    var: d.pod()?,
+                    opt_bound_ability: d.option(|d| d.pod())?,
+                };
+
+                Some(Loc::at(region, alias_var))
+            })?,
+            lambda_set_variables: self.lambda_sets()?,
+            recursion_variables: self.pods::<Variable>()?.into_iter().collect(),
+            typ: self.typ()?,
+            kind: self.pod()?,
+        })
+    }
+
+    fn lambda_sets(&mut self) -> Option<Vec<LambdaSet>> {
+        self.seq(|d| Some(LambdaSet(d.typ()?)))
+    }
+
+    fn types(&mut self) -> Option<Vec<Type>> {
+        self.seq(|d| d.typ())
+    }
+
+    fn tags(&mut self) -> Option<Vec<(TagName, Vec<Type>)>> {
+        self.seq(|d| Some((d.tag_name()?, d.types()?)))
+    }
+
+    fn ext(&mut self) -> Option<TypeExtension> {
+        match self.pod::<u8>()? {
+            0 => Some(TypeExtension::Open(Box::new(self.typ()?))),
+            1 => Some(TypeExtension::Closed),
+            _ => None,
+        }
+    }
+
+    fn typ(&mut self) -> Option<Type> {
+        let typ = match self.pod::<u8>()? {
+            0 => Type::EmptyRec,
+            1 => Type::EmptyTagUnion,
+            2 => Type::Function(self.types()?, Box::new(self.typ()?), Box::new(self.typ()?)),
+            3 => {
+                let fields = self.send_map(|d| {
+                    let tag = d.pod::<u8>()?;
+                    let typ = d.typ()?;
+
+                    match tag {
+                        0 => Some(RecordField::Demanded(typ)),
+                        1 => Some(RecordField::Required(typ)),
+                        2 => Some(RecordField::Optional(typ)),
+                        3 => Some(RecordField::RigidOptional(typ)),
+                        _ => None,
+                    }
+                })?;
+
+                Type::Record(fields, self.ext()?)
+            }
+            4 => Type::TagUnion(self.tags()?, self.ext()?),
+            5 => Type::FunctionOrTagUnion(self.tag_name()?, self.pod()?, self.ext()?),
+            6 => Type::ClosureTag {
+                name: self.pod()?,
+                captures: self.types()?,
+                ambient_function: self.pod()?,
+            },
+            7 => Type::UnspecializedLambdaSet {
+                unspecialized: Uls(self.pod()?, self.pod()?, self.pod()?),
+            },
+            8 => Type::DelayedAlias(AliasCommon {
+                symbol: self.pod()?,
+                type_arguments: self.types()?,
+                lambda_set_variables: self.lambda_sets()?,
+            }),
+            9 => Type::Alias {
+                symbol: self.pod()?,
+                type_arguments: self.seq(|d| {
+                    Some(OptAbleType {
+                        typ: d.typ()?,
+                        opt_ability: d.option(|d| d.pod())?,
+                    })
+                })?,
+                lambda_set_variables: self.lambda_sets()?,
+                actual: Box::new(self.typ()?),
+                kind: self.pod()?,
+            },
+            10 => Type::HostExposedAlias {
+                name: self.pod()?,
+                type_arguments: self.types()?,
+                lambda_set_variables: self.lambda_sets()?,
+                actual_var: self.pod()?,
+                actual: Box::new(self.typ()?),
+            },
+            11 => Type::RecursiveTagUnion(self.pod()?, self.tags()?, self.ext()?),
+            12 => Type::Apply(self.pod()?, self.types()?, self.pod()?),
+            13 => Type::Variable(self.pod()?),
+            14 => Type::RangedNumber(self.pod()?),
+            15 => Type::Tuple(
+                self.seq(|d| Some((d.len()?, d.typ()?)))?
+                    .into_iter()
+                    .collect(),
+                self.ext()?,
+            ),
+            _ => return None,
+        };
+
+        Some(typ)
+    }
+
+    fn loc_expr(&mut self) -> Option<Loc<Expr>> {
+        Some(Loc::at(self.pod()?, self.expr()?))
+    }
+
+    fn boxed_loc_expr(&mut self) -> Option<Box<Loc<Expr>>> {
+        Some(Box::new(self.loc_expr()?))
+    }
+
+    fn loc_exprs(&mut self) -> Option<Vec<Loc<Expr>>> {
+        self.seq(|d| d.loc_expr())
+    }
+
+    fn var_loc_exprs(&mut self) -> Option<Vec<(Variable, Loc<Expr>)>> {
+        self.seq(|d| Some((d.pod()?, d.loc_expr()?)))
+    }
+
+    fn var_exprs(&mut self) -> Option<Vec<(Variable, Expr)>> {
+        self.seq(|d| Some((d.pod()?, d.expr()?)))
+    }
+
+    fn fields(&mut self) -> Option<SendMap<Lowercase, Field>> {
+        self.send_map(|d| {
+            Some(Field {
+                var: d.pod()?,
+                region: d.pod()?,
+                loc_expr: d.boxed_loc_expr()?,
+            })
+        })
+    }
+
+    fn index_or_field(&mut self) -> Option<IndexOrField> {
+        match self.pod::<u8>()? {
+            0 => Some(IndexOrField::Field(self.lowercase()?)),
+            1 => Some(IndexOrField::Index(self.len()?)),
+            _ => None,
+        }
+    }
+
+    fn arguments(&mut self) -> Option<Vec<(Variable, AnnotatedMark, Loc<Pattern>)>> {
+        self.seq(|d| Some((d.pod()?, d.pod()?, d.loc_pattern()?)))
+    }
+
+    fn expr(&mut self) -> Option<Expr> {
+        let expr = match self.pod::<u8>()? {
+            0 => Expr::Num(self.pod()?, self.str()?.into(), self.pod()?, self.pod()?),
+            1 => Expr::Int(
+                self.pod()?,
+                self.pod()?,
+                self.str()?.into(),
+                self.pod()?,
+                self.pod()?,
+            ),
+            2 => Expr::Float(
+                self.pod()?,
+                self.pod()?,
+                self.str()?.into(),
+                self.pod()?,
+                self.pod()?,
+            ),
+            3 => Expr::Str(self.str()?.into()),
+            4 => Expr::SingleQuote(self.pod()?),
+            5 => Expr::List {
+                elem_var: self.pod()?,
+                loc_elems: self.loc_exprs()?,
+            },
+            6 => Expr::Var(self.pod()?),
+            7 => Expr::AbilityMember(self.pod()?, self.pod()?, self.pod()?),
+            8 => Expr::When {
+                loc_cond: self.boxed_loc_expr()?,
+                cond_var: self.pod()?,
+                expr_var: self.pod()?,
+                region: self.pod()?,
+                branches: self.seq(|d| {
+                    Some(WhenBranch {
+                        patterns: d.seq(|d| {
+                            Some(WhenBranchPattern {
+                                pattern: d.loc_pattern()?,
+                                degenerate: d.bool()?,
+                            })
+                        })?,
+                        value: d.loc_expr()?,
+                        guard: d.option(|d| d.loc_expr())?,
+                        redundant: d.pod()?,
+                    })
+                })?,
+                branches_cond_var: self.pod()?,
+                exhaustive: self.pod()?,
+            },
+            9 => Expr::If {
+                cond_var: self.pod()?,
+                branch_var: self.pod()?,
+                branches: self.seq(|d| Some((d.loc_expr()?, d.loc_expr()?)))?,
+                final_else: self.boxed_loc_expr()?,
+            },
+            10 => Expr::LetRec(self.seq(|d| d.def())?, self.boxed_loc_expr()?, self.pod()?),
+            11 => Expr::LetNonRec(Box::new(self.def()?), self.boxed_loc_expr()?),
+            12 => Expr::Call(
+                Box::new((self.pod()?, self.loc_expr()?, self.pod()?, self.pod()?)),
+                self.var_loc_exprs()?,
+                self.pod()?,
+            ),
+            13 => Expr::RunLowLevel {
+                op: self.pod()?,
+                args: self.var_exprs()?,
+                ret_var: self.pod()?,
+            },
+            14 => Expr::ForeignCall {
+                foreign_symbol: ForeignSymbol::from(self.str()?),
+                args: self.var_exprs()?,
+                ret_var: self.pod()?,
+            },
+            15 => Expr::Closure(ClosureData {
+                function_type: self.pod()?,
+                closure_type: self.pod()?,
+                return_type: self.pod()?,
+                name: self.pod()?,
+                captured_symbols: self.pods()?,
+                recursive: self.pod()?,
+                arguments: self.arguments()?,
+                loc_body: self.boxed_loc_expr()?,
+            }),
+            16 => Expr::Record {
+                record_var: self.pod()?,
+                fields: self.fields()?,
+            },
+            17 => Expr::EmptyRecord,
+            18 => Expr::Access {
+                record_var: self.pod()?,
+                ext_var: self.pod()?,
+                field_var: self.pod()?,
+                loc_expr: self.boxed_loc_expr()?,
+                field: self.lowercase()?,
+            },
+            19 => Expr::Accessor(AccessorData {
+                name: self.pod()?,
+                function_var: self.pod()?,
+                record_var: self.pod()?,
+                closure_var: self.pod()?,
+                ext_var: self.pod()?,
+                field_var: self.pod()?,
+                field: self.index_or_field()?,
+            }),
+            20 => Expr::Update {
+                record_var: self.pod()?,
+                ext_var: self.pod()?,
+                symbol: self.pod()?,
+                updates: self.fields()?,
+            },
+            21 => Expr::Tag {
+                tag_union_var: self.pod()?,
+                ext_var: self.pod()?,
+                name: self.tag_name()?,
+                arguments: self.var_loc_exprs()?,
+            },
+            22 => Expr::ZeroArgumentTag {
+                closure_name: self.pod()?,
+                variant_var: self.pod()?,
+                ext_var: self.pod()?,
+                name: self.tag_name()?,
+            },
+            23 => Expr::OpaqueRef {
+                opaque_var: self.pod()?,
+                name: self.pod()?,
+                argument: Box::new((self.pod()?, self.loc_expr()?)),
+                specialized_def_type: Box::new(self.typ()?),
+                type_arguments: self.pods()?,
+                lambda_set_variables: self.lambda_sets()?,
+            },
+            24 => Expr::OpaqueWrapFunction(OpaqueWrapFunctionData {
+                opaque_name: self.pod()?,
+                opaque_var: self.pod()?,
+                specialized_def_type: self.typ()?,
+                type_arguments: self.pods()?,
+                lambda_set_variables: self.lambda_sets()?,
+                function_name: self.pod()?,
+                function_var: self.pod()?,
+                argument_var: self.pod()?,
+                closure_var: self.pod()?,
+            }),
+            25 => Expr::Expect {
+                loc_condition: self.boxed_loc_expr()?,
+                loc_continuation: self.boxed_loc_expr()?,
+                lookups_in_cond: self.pods()?,
+            },
+            26 => Expr::ExpectFx {
+                loc_condition: self.boxed_loc_expr()?,
+                loc_continuation: self.boxed_loc_expr()?,
+                lookups_in_cond: self.pods()?,
+            },
+            27 => Expr::Dbg {
+                loc_expr: self.boxed_loc_expr()?,
+                variable: self.pod()?,
+                symbol: self.pod()?,
+            },
+            28 => Expr::Crash {
+                msg: self.boxed_loc_expr()?,
+                ret_var: self.pod()?,
+            },
+            29 => Expr::TypedHole(self.pod()?),
+            30 => Expr::Tuple {
+                tuple_var: self.pod()?,
+                elems: self.seq(|d| Some((d.pod()?, d.boxed_loc_expr()?)))?,
+            },
+            31 => Expr::TupleAccess {
+                tuple_var: self.pod()?,
+                ext_var: self.pod()?,
+                elem_var: self.pod()?,
+                loc_expr: self.boxed_loc_expr()?,
+                index: self.len()?,
+            },
+            _ => return None,
+        };
+
+        Some(expr)
+    }
+
+    fn loc_pattern(&mut self) -> Option<Loc<Pattern>> {
+        Some(Loc::at(self.pod()?, self.pattern()?))
+    }
+
+    fn pattern(&mut self) -> Option<Pattern> {
+        let pattern = match self.pod::<u8>()? {
+            0 => Pattern::Identifier(self.pod()?),
+            1 => Pattern::AppliedTag {
+                whole_var: self.pod()?,
+                ext_var: self.pod()?,
+                tag_name: self.tag_name()?,
+                arguments: self.seq(|d| Some((d.pod()?, d.loc_pattern()?)))?,
+            },
+            2 => Pattern::UnwrappedOpaque {
+                whole_var: self.pod()?,
+                opaque: self.pod()?,
+                argument: Box::new((self.pod()?, self.loc_pattern()?)),
+                specialized_def_type: Box::new(self.typ()?),
+                type_arguments: self.pods()?,
+                lambda_set_variables: self.lambda_sets()?,
+            },
+            3 => Pattern::RecordDestructure {
+                whole_var: self.pod()?,
+                ext_var: self.pod()?,
+                destructs: self.seq(|d| {
+                    let region = d.pod()?;
+                    let var = d.pod()?;
+                    let label = d.lowercase()?;
+                    let symbol = d.pod()?;
+                    let typ = match d.pod::<u8>()? {
+                        0 => DestructType::Required,
+                        1 => DestructType::Optional(d.pod()?, d.loc_expr()?),
+                        2 => DestructType::Guard(d.pod()?, d.loc_pattern()?),
+                        _ => return None,
+                    };
+
+                    Some(Loc::at(
+                        region,
+                        RecordDestruct {
+                            var,
+                            label,
+                            symbol,
+                            typ,
+                        },
+                    ))
+                })?,
+            },
+            4 => Pattern::List {
+                list_var: self.pod()?,
+                elem_var: self.pod()?,
+                patterns: ListPatterns {
+                    patterns: self.seq(|d| d.loc_pattern())?,
+                    opt_rest: self.option(|d| Some((d.len()?, d.option(|d| d.pod())?)))?,
+                },
+            },
+            5 => Pattern::NumLiteral(self.pod()?, self.str()?.into(), self.pod()?, self.pod()?),
+            6 => Pattern::IntLiteral(
+                self.pod()?,
+                self.pod()?,
+                self.str()?.into(),
+                self.pod()?,
+                self.pod()?,
+            ),
+            7 => Pattern::FloatLiteral(
+                self.pod()?,
+                self.pod()?,
+                self.str()?.into(),
+                self.pod()?,
+                self.pod()?,
+            ),
+            8 => Pattern::StrLiteral(self.str()?.into()),
+            9 => Pattern::SingleQuote(self.pod()?),
+            10 => Pattern::Underscore,
+            11 => Pattern::AbilityMemberSpecialization {
+                ident: self.pod()?,
+                specializes: self.pod()?,
+            },
+            12 => Pattern::TupleDestructure {
+                whole_var: self.pod()?,
+                ext_var: self.pod()?,
+                destructs: self.seq(|d| {
+                    let region = d.pod()?;
+
+                    Some(Loc::at(
+                        region,
+                        TupleDestruct {
+                            var: d.pod()?,
+                            destruct_index: d.len()?,
+                            typ: (d.pod()?, d.loc_pattern()?),
+                        },
+                    ))
+                })?,
+            },
+            _ => return None,
+        };
+
+        Some(pattern)
+    }
+}
+
+impl_pod_for_enum! {
+    AliasKind => [Structural, Opaque];
+    Recursive => [NotRecursive, Recursive, TailRecursive];
+    IntLitWidth => [U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, Nat, F32, F64, Dec];
+    FloatWidth => [Dec, F32, F64];
+    SignDemand => [NoDemand, Signed];
+    UnaryOp => [Negate, Not];
+    BinOp => [
+        Caret, Star, Slash, DoubleSlash, Percent, Plus, Minus, Equals, NotEquals, LessThan,
+        GreaterThan, LessThanOrEq, GreaterThanOrEq, And, Or, Pizza, Assignment, IsAliasType,
+        IsOpaqueType, Backpassing,
+    ];
+    LowLevel => [
+        StrConcat, StrJoinWith, StrIsEmpty, StrStartsWith, StrStartsWithScalar, StrEndsWith,
+        StrSplit, StrCountGraphemes, StrCountUtf8Bytes, StrFromInt, StrFromUtf8Range, StrToUtf8,
+        StrRepeat, StrFromFloat, StrTrim, StrTrimLeft, StrTrimRight, StrToNum, StrToScalars,
+        StrGetUnsafe, StrSubstringUnsafe, StrReserve, StrAppendScalar, StrGetScalarUnsafe,
+        StrGetCapacity, ListLen, ListWithCapacity, ListReserve, ListAppendUnsafe, ListGetUnsafe,
+        ListReplaceUnsafe, ListConcat, ListPrepend, ListMap, ListMap2, ListMap3, ListMap4,
+        ListSortWith, ListSublist, ListDropAt, ListSwap, ListIsUnique, ListGetCapacity, NumAdd,
+        NumAddWrap, NumAddChecked, NumAddSaturated, NumSub, NumSubWrap, NumSubChecked,
+        NumSubSaturated, NumMul, NumMulWrap, NumMulSaturated, NumMulChecked, NumGt, NumGte, NumLt,
+        NumLte, NumCompare, NumDivFrac, NumDivTruncUnchecked, NumDivCeilUnchecked, NumRemUnchecked,
+        NumIsMultipleOf, NumAbs, NumNeg, NumSin, NumCos, NumSqrtUnchecked, NumLogUnchecked,
+        NumRound, NumToFrac, NumPow, NumCeiling, NumPowInt, NumFloor, NumIsFinite, NumAtan,
+        NumAcos, NumAsin, NumBytesToU16, NumBytesToU32, NumF32ToBits, NumF64ToBits, NumDecToBits, NumBitwiseAnd, NumBitwiseXor,
+        NumBitwiseOr, NumShiftLeftBy, NumShiftRightBy, NumShiftRightZfBy, NumIntCast,
+        NumToFloatCast, NumToIntChecked, NumToFloatChecked, NumToStr, Eq, NotEq, And, Or, Not,
+        Hash, PtrCast, RefCountInc, RefCountDec, BoxExpr, UnboxExpr, Unreachable,
+    ];
+}
+
+impl Pod for ExhaustiveMark {
+    fn write(self, writer: &mut Writer) {
+        self.variable().write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        Some(Self::from_variable(reader.read()?))
+    }
+}
+
+impl Pod for RedundantMark {
+    fn write(self, writer: &mut Writer) {
+        self.variable().write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        Some(Self::from_variable(reader.read()?))
+    }
+}
+
+impl Pod for IllegalCycleMark {
+    fn write(self, writer: &mut Writer) {
+        match self.variable() {
+            None => 0u8.write(writer),
+            Some(var) => {
+                1u8.write(writer);
+                var.write(writer);
+            }
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        match reader.read::<u8>()? {
+            0 => Some(Self::from_variable(None)),
+            1 => Some(Self::from_variable(Some(reader.read()?))),
+            _ => None,
+        }
+    }
+}
+
+impl Pod for AnnotatedMark {
+    fn write(self, writer: &mut Writer) {
+        self.annotation_var.write(writer);
+        self.exhaustive.write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        Some(AnnotatedMark {
+            annotation_var: reader.read()?,
+            exhaustive: reader.read()?,
+        })
+    }
+}
+
+impl Pod for OptAbleVar {
+    fn write(self, writer: &mut Writer) {
+        self.var.write(writer);
+
+        match self.opt_ability {
+            None => 0u8.write(writer),
+            Some(ability) => (1u8, ability).write(writer),
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        let var = reader.read()?;
+        let opt_ability = match reader.read::<u8>()? {
+            0 => None,
+            1 => Some(reader.read()?),
+            _ => return None,
+        };
+
+        Some(OptAbleVar { var, opt_ability })
+    }
+}
+
+/// Written as the id itself, or zero for `None`, which no id uses.
+impl Pod for Option<SpecializationId> {
+    fn write(self, writer: &mut Writer) {
+        self.map_or(0, SpecializationId::to_u32).write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        Some(SpecializationId::from_u32(reader.read()?))
+    }
+}
+
+impl<T> Pod for Index<T> {
+    fn write(self, writer: &mut Writer) {
+        (self.index() as u32).write(writer);
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        Some(Index::new(reader.read()?))
+    }
+}
+
+impl Pod for DeclarationTag {
+    fn write(self, writer: &mut Writer) {
+        match self {
+            DeclarationTag::Value => 0u8.write(writer),
+            DeclarationTag::Expectation => 1u8.write(writer),
+            DeclarationTag::ExpectationFx => 2u8.write(writer),
+            DeclarationTag::Function(index) => (3u8, index).write(writer),
+            DeclarationTag::Recursive(index) => (4u8, index).write(writer),
+            DeclarationTag::TailRecursive(index) => (5u8, index).write(writer),
+            DeclarationTag::Destructure(index) => (6u8, index).write(writer),
+            DeclarationTag::MutualRecursion { length, cycle_mark } => {
+                7u8.write(writer);
+                length.write(writer);
+                cycle_mark.write(writer);
+            }
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        let tag = match reader.read::<u8>()? {
+            0 => DeclarationTag::Value,
+            1 => DeclarationTag::Expectation,
+            2 => DeclarationTag::ExpectationFx,
+            3 => DeclarationTag::Function(reader.read()?),
+            4 => DeclarationTag::Recursive(reader.read()?),
+            5 => DeclarationTag::TailRecursive(reader.read()?),
+            6 => DeclarationTag::Destructure(reader.read()?),
+            7 => DeclarationTag::MutualRecursion {
+                length: reader.read()?,
+                cycle_mark: reader.read()?,
+            },
+            _ => return None,
+        };
+
+        Some(tag)
+    }
+}
+
+impl Pod for CalledVia {
+    fn write(self, writer: &mut Writer) {
+        match self {
+            CalledVia::Space => 0u8.write(writer),
+            CalledVia::BinOp(op) => (1u8, op).write(writer),
+            CalledVia::UnaryOp(op) => (2u8, op).write(writer),
+            CalledVia::StringInterpolation => 3u8.write(writer),
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        let called_via = match reader.read::<u8>()? {
+            0 => CalledVia::Space,
+            1 => CalledVia::BinOp(reader.read()?),
+            2 => CalledVia::UnaryOp(reader.read()?),
+            3 => CalledVia::StringInterpolation,
+            _ => return None,
+        };
+
+        Some(called_via)
+    }
+}
+
+impl Pod for IntValue {
+    fn write(self, writer: &mut Writer) {
+        match self {
+            IntValue::I128(bytes) => (0u8, bytes).write(writer),
+            IntValue::U128(bytes) => (1u8, bytes).write(writer),
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        match reader.read::<u8>()? {
+            0 => Some(IntValue::I128(reader.read()?)),
+            1 => Some(IntValue::U128(reader.read()?)),
+            _ => None,
+        }
+    }
+}
+
+impl Pod for NumericRange {
+    fn write(self, writer: &mut Writer) {
+        match self {
+            NumericRange::IntAtLeastSigned(width) => (0u8, width).write(writer),
+            NumericRange::IntAtLeastEitherSign(width) => (1u8, width).write(writer),
+            NumericRange::NumAtLeastSigned(width) => (2u8, width).write(writer),
+            NumericRange::NumAtLeastEitherSign(width) => (3u8, width).write(writer),
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        let range = match reader.read::<u8>()? {
+            0 => NumericRange::IntAtLeastSigned(reader.read()?),
+            1 => NumericRange::IntAtLeastEitherSign(reader.read()?),
+            2 => NumericRange::NumAtLeastSigned(reader.read()?),
+            3 => NumericRange::NumAtLeastEitherSign(reader.read()?),
+            _ => return None,
+        };
+
+        Some(range)
+    }
+}
+
+impl Pod for IntBound {
+    fn write(self, writer: &mut Writer) {
+        match self {
+            IntBound::None => 0u8.write(writer),
+            IntBound::Exact(width) => (1u8, width).write(writer),
+            IntBound::AtLeast { sign, width } => (2u8, (sign, width)).write(writer),
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        let bound = match reader.read::<u8>()? {
+            0 => IntBound::None,
+            1 => IntBound::Exact(reader.read()?),
+            2 => IntBound::AtLeast {
+                sign: reader.read()?,
+                width: reader.read()?,
+            },
+            _ => return None,
+        };
+
+        Some(bound)
+    }
+}
+
+impl Pod for FloatBound {
+    fn write(self, writer: &mut Writer) {
+        match self {
+            FloatBound::None => 0u8.write(writer),
+            FloatBound::Exact(width) => (1u8, width).write(writer),
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        match reader.read::<u8>()? {
+            0 => Some(FloatBound::None),
+            1 => Some(FloatBound::Exact(reader.read()?)),
+            _ => None,
+        }
+    }
+}
+
+impl Pod for NumBound {
+    fn write(self, writer: &mut Writer) {
+        match self {
+            NumBound::None => 0u8.write(writer),
+            NumBound::AtLeastIntOrFloat { sign, width } => (1u8, (sign, width)).write(writer),
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        match reader.read::<u8>()? {
+            0 => Some(NumBound::None),
+            1 => Some(NumBound::AtLeastIntOrFloat {
+                sign: reader.read()?,
+                width: reader.read()?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{read_module_names, Decoder, Encoder, Reader};
+    use crate::file::{load_and_typecheck_str, Threading};
+    use bumpalo::Bump;
+    use roc_reporting::report::RenderTarget;
+    use roc_target::TargetInfo;
+    use std::path::PathBuf;
+
+    /// Adding a variant or a field to anything we store fails to compile until the encoder writes
+    /// it, but only reading it back shows whether the decoder restores it. The declarations of the
+    /// builtins use just about every kind of expression, pattern and type, so they must all be
+    /// read back the same, and written the same again.
+    #[test]
+    fn builtins_round_trip() {
+        let source = indoc::indoc!(
+            r#"
+            app "test" imports [Decode, Encode, Hash, Json] provides [main] to "./platform"
+
+            main = 1
+            "#
+        );
+
+        let arena = Bump::new();
+        let loaded = load_and_typecheck_str(
+            &arena,
+            PathBuf::from("Test.roc"),
+            source,
+            PathBuf::from("."),
+            Default::default(),
+            TargetInfo::default_x86_64(),
+            RenderTarget::Generic,
+            Threading::Single,
+        )
+        .unwrap();
+
+        let module_ids = &loaded.interns.module_ids;
+        let mut round_tripped = Vec::new();
+
+        for (module_id, declarations) in loaded.declarations_by_id.iter() {
+            if !module_id.is_builtin() {
+                continue;
+            }
+
+            let mut encoder = Encoder::new(module_ids);
+            encoder.declarations(declarations);
+            let bytes = encoder
+                .finish()
+                .expect("builtins only contain what we store");
+
+            let mut reader = Reader::new(&bytes);
+            let modules = read_module_names(&mut reader, module_ids).unwrap();
+            reader.modules = &modules;
+
+            let mut decoder = Decoder { reader };
+            let decoded = decoder.declarations().expect("builtins read back");
+            assert_eq!(decoder.reader.offset, bytes.len());
+
+            let mut encoder = Encoder::new(module_ids);
+            encoder.declarations(&decoded);
+
+            let name = loaded.interns.module_name(*module_id);
+            assert_eq!(
+                encoder.finish().unwrap(),
+                bytes,
+                "{} is written differently",
+                name
+            );
+            assert_eq!(
+                format!("{:?}", decoded),
+                format!("{:?}", declarations),
+                "{} reads back differently",
+                name
+            );
+
+            round_tripped.push(name.to_string());
+        }
+
+        round_tripped.sort();
+
+        assert_eq!(
+            round_tripped,
+            [
+                "Bool", "Box", "Decode", "Dict", "Encode", "Hash", "Json", "List", "Num", "Result",
+                "Set", "Str"
+            ]
+        );
+    }
+}
@@ -0,0 +1,393 @@
+//! Code examples in doc comments, which `roc test --doc` runs as expects.
+//!
+//! The doc entries of [`crate::docs`] only keep the text of doc comments, so the examples are
+//! found in the module's source instead, where they can be located. An example is a fenced code
+//! block (without a language, or marked `roc`) or an indented code block:
+//!
+//! ```text
+//! ## ```
+//! ## double 2 == 4
+//! ## ```
+//! ##
+//! ##     doubled = List.map [1, 2] double
+//! ##
+//! ##     expect doubled == [2, 4]
+//! ```
+//!
+//! An example is run if it asserts something: an example with `expect` statements runs those,
+//! and an example without runs as an `expect` if it compares with `==`, unless it only defines
+//! things (like `wasItPrecise = 0.1 + 0.2 == 0.3`). Examples are parsed from
+//! the source with everything but the example blanked out, so that all of their regions point
+//! into the doc comment, which is where failures are then reported.
+use bumpalo::Bump;
+use roc_parse::ast::{Defs, Expr, ValueDef};
+use roc_parse::parser::{EExpr, SyntaxError};
+use roc_parse::state::State;
+use roc_region::all::{Loc, Position, Region};
+
+/// A code block in a doc comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DocExample {
+    /// From the start of the block's first line (the opening fence, if any) to the end of its last
+    region: Region,
+    /// The byte ranges of the code on each line, i.e. everything after the line's `##`
+    lines: Vec<(usize, usize)>,
+}
+
+impl DocExample {
+    fn code<'s>(&'s self, src: &'s str) -> impl Iterator<Item = &'s str> + 's {
+        self.lines
+            .iter()
+            .map(move |(start, end)| &src[*start..*end])
+    }
+
+    fn has_expect(&self, src: &str) -> bool {
+        self.code(src).any(|line| {
+            let line = line.trim_start();
+
+            line == "expect" || line.starts_with("expect ")
+        })
+    }
+
+    fn has_comparison(&self, src: &str) -> bool {
+        self.code(src).any(|line| line.contains("=="))
+    }
+}
+
+/// Add expects to `defs` for the examples in the doc comments of `src` that assert something: one
+/// for each `expect` statement, or one for the whole example if it only compares with `==`.
+/// Returns the regions of the examples, along with the syntax errors of the ones that did not parse.
+pub fn add_doc_tests<'a>(
+    arena: &'a Bump,
+    src: &'a str,
+    defs: &mut Defs<'a>,
+) -> (Vec<Region>, Vec<SyntaxError<'a>>) {
+    let mut regions = Vec::new();
+    let mut problems = Vec::new();
+
+    for example in find_examples(src) {
+        let has_expect = example.has_expect(src);
+
+        if !has_expect && !example.has_comparison(src) {
+            // e.g. a type signature, or a def that the text goes on to explain
+            continue;
+        }
+
+        let (masked, start, indent) = mask_source(arena, src, &example, has_expect);
+        let state = State::new(masked.as_bytes());
+
+        let parsed = match roc_parse::expr::test_parse_expr(indent, arena, state) {
+            Ok(parsed) => parsed,
+            Err(EExpr::DefMissingFinalExpr2(EExpr::Start(end), _))
+                if !has_expect && end.offset as usize == masked.len() =>
+            {
+                // only definitions, which leave nothing to compare
+                continue;
+            }
+            Err(problem) => {
+                problems.push(SyntaxError::Expr(problem, start));
+                continue;
+            }
+        };
+
+        let conditions = if has_expect {
+            split_expects(arena, parsed)
+        } else {
+            vec![parsed]
+        };
+
+        for condition in conditions {
+            // starting at the example is what marks this expect as one of its doc tests
+            let preceding_comment = Region::new(example.region.start(), condition.region.start());
+            let region = Region::span_across(&preceding_comment, &condition.region);
+
+            defs.push_value_def(
+                ValueDef::Expect {
+                    condition: arena.alloc(condition),
+                    preceding_comment,
+                },
+                region,
+                &[],
+                &[],
+            );
+        }
+
+        regions.push(example.region);
+    }
+
+    (regions, problems)
+}
+
+/// An expect for each `expect` statement of an example, with all of the example's defs in scope.
+///
+/// The statements cannot stay where they are: in a block, `expect` statements are parsed as defs,
+/// which can only be canonicalized at the top level, and a top-level expect cannot end in an
+/// inline one either. Defs do not depend on their order, so giving each expect all of them keeps
+/// the example's meaning, and reports each failing statement on its own.
+fn split_expects<'a>(arena: &'a Bump, loc_expr: Loc<Expr<'a>>) -> Vec<Loc<Expr<'a>>> {
+    let mut defs = Defs::default();
+    let mut conditions = Vec::new();
+    let mut rest = loc_expr;
+
+    // the statements are a chain of blocks and inline expects, ending in the added `Bool.true`
+    loop {
+        match rest.value {
+            Expr::SpaceBefore(expr, _) | Expr::SpaceAfter(expr, _) => {
+                rest = Loc::at(rest.region, *expr);
+            }
+            Expr::Expect(condition, continuation) => {
+                conditions.push(*condition);
+                rest = *continuation;
+            }
+            Expr::Defs(block_defs, final_expr) => {
+                for (index, def) in block_defs.defs().enumerate() {
+                    let region = block_defs.regions[index];
+
+                    match def {
+                        Err(ValueDef::Expect { condition, .. }) => conditions.push(**condition),
+                        Err(value_def) => defs.push_value_def(*value_def, region, &[], &[]),
+                        Ok(type_def) => defs.push_type_def(*type_def, region, &[], &[]),
+                    }
+                }
+
+                rest = *final_expr;
+            }
+            _ => break,
+        }
+    }
+
+    if defs.is_empty() {
+        return conditions;
+    }
+
+    let defs: &Defs = arena.alloc(defs);
+
+    conditions
+        .into_iter()
+        .map(|condition| {
+            let start = defs.regions[0].start().min(condition.region.start());
+            let region = Region::new(start, condition.region.end());
+
+            Loc::at(region, Expr::Defs(defs, arena.alloc(condition)))
+        })
+        .collect()
+}
+
+/// A copy of `src` up to the end of the example, with everything but the example's code replaced
+/// by spaces, along with where the code starts and its indentation. An example with `expect`
+/// statements is followed by a `Bool.true` for them to continue with, since they cannot end a
+/// block.
+fn mask_source<'a>(
+    arena: &'a Bump,
+    src: &str,
+    example: &DocExample,
+    has_expect: bool,
+) -> (&'a str, Position, u32) {
+    let end = example.region.end().offset as usize;
+    let mut masked: Vec<u8> = src.as_bytes()[..end]
+        .iter()
+        .map(|byte| if *byte == b'\n' { b'\n' } else { b' ' })
+        .collect();
+
+    for (start, end) in example.lines.iter() {
+        masked[*start..*end].copy_from_slice(&src.as_bytes()[*start..*end]);
+    }
+
+    // the parser wants the code to end right where the input does
+    let code_end = example
+        .lines
+        .iter()
+        .rev()
+        .map(|(start, end)| (start, src[*start..*end].trim_end()))
+        .find(|(_, code)| !code.is_empty())
+        .map_or(end, |(start, code)| start + code.len());
+
+    masked.truncate(code_end);
+
+    // the first character of code, which the rest of the example is indented relative to
+    let start = example
+        .lines
+        .iter()
+        .find_map(|(start, end)| {
+            let line = &src[*start..*end];
+            let indent = line.len() - line.trim_start().len();
+
+            if indent < line.len() {
+                Some(start + indent)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(end);
+
+    let line_start = src[..start].rfind('\n').map_or(0, |newline| newline + 1);
+    let indent = start - line_start;
+
+    if has_expect {
+        masked.push(b'\n');
+        masked.extend(std::iter::repeat(b' ').take(indent));
+        masked.extend_from_slice(b"Bool.true");
+    }
+
+    // only ASCII spaces were put in place of whole characters
+    let masked = String::from_utf8(masked).expect("masking keeps the source valid UTF-8");
+
+    (
+        arena.alloc_str(&masked),
+        Position::new(start as u32),
+        indent as u32,
+    )
+}
+
+/// The code blocks in the doc comments of `src`, in source order.
+fn find_examples(src: &str) -> Vec<DocExample> {
+    let mut examples = Vec::new();
+    let mut block: Option<Block> = None;
+    // whether the previous doc comment line was blank, which an indented block must follow
+    let mut after_blank = true;
+
+    let mut line_start = 0;
+
+    for line in src.split_inclusive('\n') {
+        let line_end = line_start + line.trim_end_matches(['\n', '\r']).len();
+        let text = &src[line_start..line_end];
+
+        let doc_text = text
+            .trim_start()
+            .strip_prefix("##")
+            .filter(|rest| !rest.starts_with('#'));
+
+        match doc_text {
+            None => {
+                // the doc comment ended, and so did any block in it
+                if let Some(block) = block.take() {
+                    examples.extend(block.finish(src));
+                }
+
+                after_blank = true;
+            }
+            Some(doc_text) => {
+                let code_start = line_end - doc_text.len();
+                let content = doc_text.strip_prefix(' ').unwrap_or(doc_text);
+                let is_blank = content.trim().is_empty();
+
+                block = match block.take() {
+                    Some(Block::Fenced {
+                        start,
+                        is_roc,
+                        mut lines,
+                    }) => {
+                        if content.trim_start().starts_with("```") {
+                            if is_roc {
+                                examples.push(DocExample {
+                                    region: Region::new(
+                                        Position::new(start as u32),
+                                        Position::new(line_end as u32),
+                                    ),
+                                    lines,
+                                });
+                            }
+
+                            None
+                        } else {
+                            lines.push((code_start, line_end));
+
+                            Some(Block::Fenced {
+                                start,
+                                is_roc,
+                                lines,
+                            })
+                        }
+                    }
+                    Some(Block::Indented { start, mut lines }) => {
+                        if is_blank || content.starts_with("    ") {
+                            lines.push((code_start, line_end));
+
+                            Some(Block::Indented { start, lines })
+                        } else {
+                            examples.extend(Block::Indented { start, lines }.finish(src));
+
+                            start_block(content, line_start, after_blank, code_start, line_end)
+                        }
+                    }
+                    None => start_block(content, line_start, after_blank, code_start, line_end),
+                };
+
+                after_blank = is_blank;
+            }
+        }
+
+        line_start += line.len();
+    }
+
+    if let Some(block) = block {
+        examples.extend(block.finish(src));
+    }
+
+    examples
+}
+
+enum Block {
+    Fenced {
+        start: usize,
+        /// Whether the block has no language, or is marked as Roc
+        is_roc: bool,
+        lines: Vec<(usize, usize)>,
+    },
+    Indented {
+        start: usize,
+        lines: Vec<(usize, usize)>,
+    },
+}
+
+fn start_block(
+    content: &str,
+    line_start: usize,
+    after_blank: bool,
+    code_start: usize,
+    line_end: usize,
+) -> Option<Block> {
+    if let Some(info) = content.trim_start().strip_prefix("```") {
+        let info = info.trim();
+
+        Some(Block::Fenced {
+            start: line_start,
+            is_roc: info.is_empty() || info == "roc",
+            lines: Vec::new(),
+        })
+    } else if after_blank && content.starts_with("    ") && !content.trim().is_empty() {
+        Some(Block::Indented {
+            start: line_start,
+            lines: vec![(code_start, line_end)],
+        })
+    } else {
+        None
+    }
+}
+
+impl Block {
+    /// The example of a block that ended without a closing fence, if it is one
+    fn finish(self, src: &str) -> Option<DocExample> {
+        let (start, mut lines) = match self {
+            // an unclosed fence runs to the end of the doc comment, as in markdown
+            Block::Fenced { is_roc: false, .. } => return None,
+            Block::Fenced { start, lines, .. } | Block::Indented { start, lines } => (start, lines),
+        };
+
+        // blank lines at the end belong to the text after the block
+        while let Some((line_start, line_end)) = lines.last() {
+            if src[*line_start..*line_end].trim().is_empty() {
+                lines.pop();
+            } else {
+                break;
+            }
+        }
+
+        let end = lines.last()?.1;
+
+        Some(DocExample {
+            region: Region::new(Position::new(start as u32), Position::new(end as u32)),
+            lines,
+        })
+    }
+}
@@ -1,5 +1,7 @@
 use crate::docs::DocEntry::DetachedDoc;
-use crate::docs::TypeAnnotation::{Apply, BoundVariable, Function, NoTypeAnn, Record, TagUnion};
+use crate::docs::TypeAnnotation::{
+    Apply, BoundVariable, Function, NoTypeAnn, Record, TagUnion, Tuple,
+};
 use crate::file::LoadedModule;
 use roc_can::scope::Scope;
 use roc_module::ident::ModuleName;
@@ -60,6 +62,10 @@ pub enum TypeAnnotation {
         fields: Vec<RecordField>,
         extension: Box<TypeAnnotation>,
     },
+    Tuple {
+        elems: Vec<TypeAnnotation>,
+        extension: Box<TypeAnnotation>,
+    },
     Ability {
         members: Vec<AbilityMember>,
     },
@@ -207,6 +213,8 @@ fn generate_entry_docs<'a>(
                 ValueDef::ExpectFx { .. } => {
                     // Don't generate docs for `expect-fx`s
                 }
+
+                ValueDef::Malformed(_) => (),
             },
             Ok(type_index) => match &defs.type_defs[type_index.index()] {
                 TypeDef::Alias {
@@ -362,6 +370,22 @@ fn type_to_docs(in_func_type_ann: bool, type_annotation: ast::TypeAnnotation) ->
                 extension: Box::new(extension),
             }
         }
+        ast::TypeAnnotation::Tuple { elems, ext } => {
+            let doc_elems = elems
+                .iter()
+                .map(|elem| type_to_docs(in_func_type_ann, elem.value))
+                .collect();
+
+            let extension = match ext {
+                None => NoTypeAnn,
+                Some(ext_type_ann) => type_to_docs(in_func_type_ann, ext_type_ann.value),
+            };
+
+            Tuple {
+                elems: doc_elems,
+                extension: Box::new(extension),
+            }
+        }
         ast::TypeAnnotation::SpaceBefore(&sub_type_ann, _) => {
             type_to_docs(in_func_type_ann, sub_type_ann)
         }
@@ -426,7 +450,8 @@ fn record_field_to_doc(
         AssignedField::LabelOnly(label) => Some(RecordField::LabelOnly {
             name: label.value.to_string(),
         }),
-        AssignedField::Malformed(_) => None,
+        // only record updates have nested fields
+        AssignedField::Nested(_, _) | AssignedField::Malformed(_) => None,
     }
 }
 
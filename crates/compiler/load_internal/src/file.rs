@@ -1,3 +1,5 @@
+use crate::cache::{CacheEntry, CanonicalEntry, CanonicalModule, Fingerprint, InterfaceCache};
+use crate::doc_tests::add_doc_tests;
 use crate::docs::ModuleDocumentation;
 use bumpalo::Bump;
 use crossbeam::channel::{bounded, Sender};
@@ -7,8 +9,10 @@ use parking_lot::Mutex;
 use roc_builtins::roc::module_source;
 use roc_can::abilities::{AbilitiesStore, PendingAbilitiesStore, ResolvedImpl};
 use roc_can::constraint::{Constraint as ConstraintSoa, Constraints};
+use roc_can::coverage::{coverage_points, CoveragePoint};
 use roc_can::expr::Declarations;
 use roc_can::expr::PendingDerives;
+use roc_can::mock_effects::MockEffects;
 use roc_can::module::{
     canonicalize_module_defs, ExposedByModule, ExposedForModule, ExposedModuleTypes, Module,
     ResolvedImplementations,
@@ -23,7 +27,7 @@ use roc_debug_flags::{
 };
 use roc_derive::SharedDerivedModule;
 use roc_error_macros::internal_error;
-use roc_intern::{GlobalInterner, SingleThreadedInterner};
+use roc_intern::{GlobalInterner, Interner, SingleThreadedInterner};
 use roc_late_solve::{AbilitiesView, WorldAbilities};
 use roc_module::ident::{Ident, ModuleName, QualifiedModuleName};
 use roc_module::symbol::{
@@ -42,9 +46,9 @@ use roc_parse::header::{ExposedName, ImportsEntry, PackageEntry, PlatformHeader,
 use roc_parse::header::{HeaderFor, ModuleNameEnum, PackageName};
 use roc_parse::ident::UppercaseIdent;
 use roc_parse::module::module_defs;
-use roc_parse::parser::{FileError, Parser, SyntaxError};
+use roc_parse::parser::{FileError, Parser, SourceError, SyntaxError};
 use roc_region::all::{LineInfo, Loc, Region};
-use roc_reporting::report::RenderTarget;
+use roc_reporting::report::{Diagnostic, RenderTarget};
 use roc_solve::module::{extract_module_owned_implementations, Solved, SolvedModule};
 use roc_solve_problem::TypeError;
 use roc_target::TargetInfo;
@@ -126,6 +130,99 @@ pub struct LoadConfig {
     pub render: RenderTarget,
     pub threading: Threading,
     pub exec_mode: ExecutionMode,
+    /// Where to keep canonicalized and solved modules between runs, if anywhere.
+    /// Only used when type checking, i.e. in [`ExecutionMode::Check`]. Modules restored from the
+    /// cache have no [`LoadedModule::documentation`], so generating docs must leave this unset.
+    pub cache_dir: Option<PathBuf>,
+    /// Intermediate representations to collect while loading, for `roc build --emit`.
+    pub emit: Vec<EmitKind>,
+    /// Canned responses that hosted functions give instead of calling the platform,
+    /// for `roc test --mock-effects`.
+    pub mock_effects: Option<Arc<MockEffects>>,
+    /// Count how often the functions and branches of the root package run,
+    /// for `roc test --coverage`.
+    pub coverage: bool,
+    /// Add the code examples in the doc comments of the root package as expects,
+    /// for `roc test --doc`.
+    pub doc_tests: bool,
+    /// Also add the code examples in the doc comments of the builtins as expects,
+    /// for `roc test --doc --builtins`. The builtins are then solved again rather than cached.
+    /// Their own top-level expects are still not run.
+    pub builtin_doc_tests: bool,
+    /// Collect the benchmarks of the root package, for `roc bench`.
+    pub benches: bool,
+}
+
+impl Default for LoadConfig {
+    /// Type checking for a 64-bit target, with none of the extras that only some commands use.
+    /// Callers set what they need and take the rest from here with `..Default::default()`.
+    fn default() -> Self {
+        Self {
+            target_info: TargetInfo::default_x86_64(),
+            render: RenderTarget::ColorTerminal,
+            threading: Threading::AllAvailable,
+            exec_mode: ExecutionMode::Check,
+            cache_dir: None,
+            emit: Vec::new(),
+            mock_effects: None,
+            coverage: false,
+            doc_tests: false,
+            builtin_doc_tests: false,
+            benches: false,
+        }
+    }
+}
+
+/// An intermediate representation that `roc build --emit` can write next to its output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmitKind {
+    /// The canonical AST of each module in the app's package
+    Can,
+    /// The mono IR after each pass over it
+    Mono,
+    /// Textual LLVM IR
+    LlvmIr,
+    /// LLVM bitcode
+    LlvmBc,
+    /// Assembly, from LLVM or the dev backend
+    Asm,
+    /// The WebAssembly text format
+    Wat,
+}
+
+impl EmitKind {
+    pub const ALL: [EmitKind; 6] = [
+        EmitKind::Can,
+        EmitKind::Mono,
+        EmitKind::LlvmIr,
+        EmitKind::LlvmBc,
+        EmitKind::Asm,
+        EmitKind::Wat,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EmitKind::Can => "can",
+            EmitKind::Mono => "mono",
+            EmitKind::LlvmIr => "llvm-ir",
+            EmitKind::LlvmBc => "llvm-bc",
+            EmitKind::Asm => "asm",
+            EmitKind::Wat => "wat",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+/// Intermediate representations collected while loading, for `roc build --emit`
+#[derive(Debug, Default)]
+pub struct EmittedIr {
+    /// The pretty-printed canonical AST of each module in the app's package
+    pub can: VecMap<ModuleId, String>,
+    /// The pretty-printed mono IR after each pass, by the name of the pass
+    pub mono: Vec<(&'static str, String)>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -162,11 +259,19 @@ struct ModuleCache<'a> {
     late_specializations: MutMap<ModuleId, LateSpecializationsModule<'a>>,
     external_specializations_requested: MutMap<ModuleId, Vec<ExternalSpecializations<'a>>>,
     expectations: VecMap<ModuleId, Expectations>,
+    /// The functions and branches counted by `roc test --coverage`, for root package modules
+    coverage: VecMap<ModuleId, Vec<CoveragePoint>>,
+    /// The regions of the doc comment examples added as expects, for root package modules
+    /// (and the builtins, under `roc test --doc --builtins`)
+    doc_tests: MutMap<ModuleId, Vec<Region>>,
+    /// Syntax errors in doc comment examples, kept apart from the module's own parse problems
+    doc_test_problems: MutMap<ModuleId, Vec<SyntaxError<'a>>>,
 
     /// Various information
     imports: MutMap<ModuleId, MutSet<ModuleId>>,
     top_level_thunks: MutMap<ModuleId, MutSet<Symbol>>,
     documentation: MutMap<ModuleId, ModuleDocumentation>,
+    parse_problems: MutMap<ModuleId, Vec<SyntaxError<'a>>>,
     can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     type_problems: MutMap<ModuleId, Vec<TypeError>>,
 
@@ -177,6 +282,10 @@ impl<'a> ModuleCache<'a> {
     pub fn total_problems(&self) -> usize {
         let mut total = 0;
 
+        for problems in self.parse_problems.values() {
+            total += problems.len();
+        }
+
         for problems in self.can_problems.values() {
             total += problems.len();
         }
@@ -214,6 +323,7 @@ impl Default for ModuleCache<'_> {
             ENCODE,
             DECODE,
             JSON,
+            HASH,
         }
 
         Self {
@@ -230,10 +340,14 @@ impl Default for ModuleCache<'_> {
             imports: Default::default(),
             top_level_thunks: Default::default(),
             documentation: Default::default(),
+            parse_problems: Default::default(),
             can_problems: Default::default(),
             type_problems: Default::default(),
             sources: Default::default(),
             expectations: Default::default(),
+            coverage: Default::default(),
+            doc_tests: Default::default(),
+            doc_test_problems: Default::default(),
         }
     }
 }
@@ -391,6 +505,23 @@ fn start_phase<'a>(
                     state.cached_subs.lock().contains_key(&module_id)
                 };
 
+                // Doc tests and parse problems mean the defs no longer match the source, and
+                // mocked effects change what the module canonicalizes to.
+                let cache_entry = match &state.interface_cache {
+                    Some(cache)
+                        if !module_id.is_builtin()
+                            && !state.is_host_exposed(module_id)
+                            && !state.module_cache.doc_tests.contains_key(&module_id)
+                            && !state.module_cache.parse_problems.contains_key(&module_id)
+                            && state.mock_effects.is_none() =>
+                    {
+                        let (path, _) = &state.module_cache.sources[&module_id];
+
+                        Some(cache.canonical_entry_for(path))
+                    }
+                    _ => None,
+                };
+
                 BuildTask::CanonicalizeAndConstrain {
                     parsed,
                     dep_idents,
@@ -399,6 +530,8 @@ fn start_phase<'a>(
                     aliases,
                     abilities_store,
                     skip_constraint_gen,
+                    mock_effects: state.mock_effects.clone(),
+                    cache_entry,
                 }
             }
 
@@ -421,6 +554,24 @@ fn start_phase<'a>(
 
                 let derived_module = SharedDerivedModule::clone(&state.derived_module);
 
+                // The host-exposed module is needed in full, so we always solve it.
+                let cache_entry = match &state.interface_cache {
+                    Some(cache) if !module_id.is_builtin() && !state.is_host_exposed(module_id) => {
+                        let (path, source) = &state.module_cache.sources[&module_id];
+                        let module_ids = (*state.arc_modules).lock().clone().into_module_ids();
+
+                        cache.entry_for(
+                            module_ids,
+                            module_id,
+                            path,
+                            source,
+                            &ident_ids,
+                            imported_modules.keys(),
+                        )
+                    }
+                    _ => None,
+                };
+
                 BuildTask::solve_module(
                     module,
                     ident_ids,
@@ -435,6 +586,7 @@ fn start_phase<'a>(
                     declarations,
                     state.cached_subs.clone(),
                     derived_module,
+                    cache_entry,
                 )
             }
             Phase::FindSpecializations => {
@@ -464,8 +616,31 @@ fn start_phase<'a>(
 
                 let derived_module = SharedDerivedModule::clone(&state.derived_module);
 
-                let build_expects = matches!(state.exec_mode, ExecutionMode::Test)
-                    && state.module_cache.expectations.contains_key(&module_id);
+                let build_expects = if !matches!(state.exec_mode, ExecutionMode::Test)
+                    || !state.module_cache.expectations.contains_key(&module_id)
+                {
+                    BuildExpects::None
+                } else {
+                    match state.module_cache.doc_tests.get(&module_id) {
+                        // under `roc test --doc --builtins`, which runs the examples in the
+                        // builtins' docs but not the builtins' own expects
+                        Some(examples) if module_id.is_builtin() => {
+                            BuildExpects::DocTests(arena.alloc_slice_copy(examples))
+                        }
+                        _ => BuildExpects::All,
+                    }
+                };
+
+                // Benchmarks don't need the module to have any expectations of its own.
+                let build_benches = state.benches
+                    && matches!(state.exec_mode, ExecutionMode::Test)
+                    && state
+                        .arc_modules
+                        .lock()
+                        .package_eq(module_id, state.root_id)
+                        .unwrap_or(false);
+
+                let count_coverage = state.module_cache.coverage.contains_key(&module_id);
 
                 BuildTask::BuildPendingSpecializations {
                     layout_cache,
@@ -481,6 +656,8 @@ fn start_phase<'a>(
                     exposed_by_module: state.exposed_types.clone(),
                     derived_module,
                     build_expects,
+                    build_benches,
+                    count_coverage,
                 }
             }
             Phase::MakeSpecializations => {
@@ -528,24 +705,8 @@ fn start_phase<'a>(
                             procs_base,
                             layout_cache,
                             module_timing,
-                            abilities_store,
                         } = found_specializations;
 
-                        let our_exposed_types = state
-                            .exposed_types
-                            .get(&module_id)
-                            .unwrap_or_else(|| {
-                                internal_error!("Exposed types for {:?} missing", module_id)
-                            })
-                            .clone();
-
-                        // Add our abilities to the world.
-                        state.world_abilities.insert(
-                            module_id,
-                            abilities_store,
-                            our_exposed_types.exposed_types_storage_subs,
-                        );
-
                         (ident_ids, subs, procs_base, layout_cache, module_timing)
                     } else {
                         let LateSpecializationsModule {
@@ -580,6 +741,8 @@ fn start_phase<'a>(
 
                 let derived_module = SharedDerivedModule::clone(&state.derived_module);
 
+                let count_coverage = state.module_cache.coverage.contains_key(&module_id);
+
                 BuildTask::MakeSpecializations {
                     module_id,
                     ident_ids,
@@ -592,6 +755,7 @@ fn start_phase<'a>(
                     // TODO: awful, how can we get rid of the clone?
                     exposed_by_module: state.exposed_types.clone(),
                     derived_module,
+                    count_coverage,
                 }
             }
         }
@@ -605,6 +769,10 @@ pub struct LoadedModule {
     pub module_id: ModuleId,
     pub interns: Interns,
     pub solved: Solved<Subs>,
+    /// Syntax errors the parser recovered from, already rendered as reports
+    pub parse_problems: MutMap<ModuleId, Vec<String>>,
+    /// The same syntax errors, with their title and region kept apart for editors
+    pub parse_diagnostics: MutMap<ModuleId, Vec<Diagnostic>>,
     pub can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     pub type_problems: MutMap<ModuleId, Vec<TypeError>>,
     pub declarations_by_id: MutMap<ModuleId, Declarations>,
@@ -618,12 +786,20 @@ pub struct LoadedModule {
     pub timings: MutMap<ModuleId, ModuleTiming>,
     pub documentation: MutMap<ModuleId, ModuleDocumentation>,
     pub abilities_store: AbilitiesStore,
+    /// Modules whose types were taken from the cache instead of being solved again
+    pub cached_modules: MutSet<ModuleId>,
+    /// Modules whose declarations were taken from the cache instead of being canonicalized again
+    pub cached_declarations: MutSet<ModuleId>,
 }
 
 impl LoadedModule {
     pub fn total_problems(&self) -> usize {
         let mut total = 0;
 
+        for problems in self.parse_problems.values() {
+            total += problems.len();
+        }
+
         for problems in self.can_problems.values() {
             total += problems.len();
         }
@@ -701,7 +877,6 @@ struct FoundSpecializationsModule<'a> {
     procs_base: ProcsBase<'a>,
     subs: Subs,
     module_timing: ModuleTiming,
-    abilities_store: AbilitiesStore,
 }
 
 #[derive(Debug)]
@@ -717,6 +892,21 @@ struct LateSpecializationsModule<'a> {
 pub struct ToplevelExpects {
     pub pure: VecMap<Symbol, Region>,
     pub fx: VecMap<Symbol, Region>,
+    /// The top-level def that each expect is written beneath, if there is one.
+    /// `roc test` names expects after these.
+    pub enclosing_defs: VecMap<Symbol, Symbol>,
+    /// The expects that are code examples from doc comments. Their enclosing def is the def
+    /// that the doc comment documents, if any.
+    pub doc_tests: VecSet<Symbol>,
+}
+
+/// The benchmarks that `roc bench` runs: top-level functions of the root package named `bench`
+/// followed by an uppercase letter (like `benchSort`) that take a `{}`.
+#[derive(Debug, Default)]
+pub struct ToplevelBenches {
+    /// A 0-arity thunk for each benchmark, which runs it once and keeps its result from being
+    /// optimized away, along with the benchmark it runs.
+    pub thunks: VecMap<Symbol, Loc<Symbol>>,
 }
 
 #[derive(Debug)]
@@ -726,15 +916,23 @@ pub struct MonomorphizedModule<'a> {
     pub subs: Subs,
     pub layout_interner: SingleThreadedInterner<'a, Layout<'a>>,
     pub output_path: Box<Path>,
+    /// Syntax errors the parser recovered from, already rendered as reports
+    pub parse_problems: MutMap<ModuleId, Vec<String>>,
+    /// Doc comment examples that did not parse, already rendered as reports
+    pub doc_test_problems: MutMap<ModuleId, Vec<String>>,
     pub can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     pub type_problems: MutMap<ModuleId, Vec<TypeError>>,
     pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
     pub toplevel_expects: ToplevelExpects,
+    pub toplevel_benches: ToplevelBenches,
     pub entry_point: EntryPoint<'a>,
     pub exposed_to_host: ExposedToHost,
     pub sources: MutMap<ModuleId, (PathBuf, Box<str>)>,
     pub timings: MutMap<ModuleId, ModuleTiming>,
     pub expectations: VecMap<ModuleId, Expectations>,
+    /// The functions and branches that `roc test --coverage` counted the runs of, by module
+    pub coverage: VecMap<ModuleId, Vec<CoveragePoint>>,
+    pub emitted_ir: EmittedIr,
 }
 
 #[derive(Debug)]
@@ -752,6 +950,10 @@ pub struct Expectations {
     pub subs: roc_types::subs::Subs,
     pub path: PathBuf,
     pub expectations: VecMap<Region, Vec<(Symbol, Variable)>>,
+    /// the value printed by each `dbg`, by the region of the printed expression
+    pub dbgs: VecMap<Region, (Symbol, Variable)>,
+    /// the regions of the messages of `crash`es
+    pub crashes: VecSet<Region>,
     pub ident_ids: IdentIds,
 }
 
@@ -767,6 +969,10 @@ impl<'a> MonomorphizedModule<'a> {
     pub fn total_problems(&self) -> usize {
         let mut total = 0;
 
+        for problems in self.parse_problems.values() {
+            total += problems.len();
+        }
+
         for problems in self.can_problems.values() {
             total += problems.len();
         }
@@ -790,12 +996,14 @@ struct ParsedModule<'a> {
     exposed_ident_ids: IdentIds,
     exposed_imports: MutMap<Ident, (Symbol, Region)>,
     parsed_defs: Defs<'a>,
+    parse_problems: Vec<SyntaxError<'a>>,
     module_name: ModuleNameEnum<'a>,
     symbols_from_requires: Vec<(Loc<Symbol>, Loc<TypeAnnotation<'a>>)>,
     header_for: HeaderFor<'a>,
 }
 
 type LocExpects = VecMap<Region, Vec<(Symbol, Variable)>>;
+type LocDbgs = VecMap<Region, (Symbol, Variable)>;
 
 /// A message sent out _from_ a worker thread,
 /// representing a result of work done, or a request for further work
@@ -815,6 +1023,10 @@ enum Msg<'a> {
         module_timing: ModuleTiming,
         abilities_store: AbilitiesStore,
         loc_expects: LocExpects,
+        loc_dbgs: LocDbgs,
+        loc_crashes: VecSet<Region>,
+        interface: Option<Fingerprint>,
+        from_cache: bool,
     },
     FinishedAllTypeChecking {
         solved_subs: Solved<Subs>,
@@ -835,6 +1047,8 @@ enum Msg<'a> {
         module_timing: ModuleTiming,
         abilities_store: AbilitiesStore,
         toplevel_expects: ToplevelExpects,
+        toplevel_benches: ToplevelBenches,
+        definition_regions: Vec<Loc<Symbol>>,
     },
     MadeSpecializations {
         module_id: ModuleId,
@@ -870,6 +1084,7 @@ struct CanAndCon {
     constrained_module: ConstrainedModule,
     canonicalization_problems: Vec<roc_problem::can::Problem>,
     module_docs: Option<ModuleDocumentation>,
+    from_cache: bool,
 }
 
 #[derive(Debug)]
@@ -925,6 +1140,7 @@ struct State<'a> {
     pub dependencies: Dependencies<'a>,
     pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
     pub toplevel_expects: ToplevelExpects,
+    pub toplevel_benches: ToplevelBenches,
     pub exposed_to_host: ExposedToHost,
 
     /// This is the "final" list of IdentIds, after canonicalization and constraint gen
@@ -963,6 +1179,25 @@ struct State<'a> {
     // cached subs (used for builtin modules, could include packages in the future too)
     cached_subs: CachedSubs,
 
+    /// Solved interfaces of user modules, kept on disk between runs of `roc check`
+    interface_cache: Option<InterfaceCache>,
+
+    /// Intermediate representations requested by `roc build --emit`
+    emit: Vec<EmitKind>,
+    /// The script that hosted functions respond from in `roc test --mock-effects`
+    mock_effects: Option<Arc<MockEffects>>,
+    /// Whether `roc test --coverage` counts the runs of the root package's functions and branches
+    coverage: bool,
+    /// Whether `roc test --doc` runs the code examples in the root package's doc comments
+    doc_tests: bool,
+    /// Whether `roc test --doc --builtins` also runs the examples in the builtins' doc comments
+    builtin_doc_tests: bool,
+    /// Whether `roc bench` runs the benchmarks of the root package
+    benches: bool,
+    /// Canonical declarations kept around for `--emit=can`, printed once all interns are known
+    emitted_can: VecMap<ModuleId, Declarations>,
+    emitted_mono: Vec<(&'static str, String)>,
+
     layout_interner: Arc<GlobalInterner<'a, Layout<'a>>>,
 }
 
@@ -973,22 +1208,61 @@ impl<'a> State<'a> {
         self.exec_mode.goal_phase()
     }
 
-    #[allow(clippy::too_many_arguments)]
+    /// If there is a platform, the `platform` module provides host-exposed,
+    /// otherwise the App module exposes host-exposed
+    fn is_host_exposed(&self, module_id: ModuleId) -> bool {
+        match self.platform_data {
+            None => module_id == self.root_id,
+            Some(ref platform_data) => module_id == platform_data.module_id,
+        }
+    }
+
+    /// Whether the code examples in this module's doc comments are added as expects
+    fn adds_doc_tests(&self, module_id: ModuleId) -> bool {
+        if !self.doc_tests {
+            false
+        } else if module_id.is_builtin() {
+            self.builtin_doc_tests
+        } else {
+            self.arc_modules
+                .lock()
+                .package_eq(module_id, self.root_id)
+                .unwrap_or(false)
+        }
+    }
+
     fn new(
         root_id: ModuleId,
-        target_info: TargetInfo,
         exposed_types: ExposedByModule,
         arc_modules: Arc<Mutex<PackageModuleIds<'a>>>,
         ident_ids_by_module: SharedIdentIdsByModule,
         cached_subs: MutMap<ModuleId, (Subs, Vec<(Symbol, Variable)>)>,
-        render: RenderTarget,
         number_of_workers: usize,
-        exec_mode: ExecutionMode,
+        load_config: LoadConfig,
     ) -> Self {
+        let LoadConfig {
+            target_info,
+            render,
+            threading: _,
+            exec_mode,
+            cache_dir,
+            emit,
+            mock_effects,
+            coverage,
+            doc_tests,
+            builtin_doc_tests,
+            benches,
+        } = load_config;
+
         let arc_shorthands = Arc::new(Mutex::new(MutMap::default()));
 
         let dependencies = Dependencies::new(exec_mode.goal_phase());
 
+        let interface_cache = match exec_mode {
+            ExecutionMode::Check => cache_dir.map(InterfaceCache::new),
+            _ => None,
+        };
+
         Self {
             root_id,
             root_subs: None,
@@ -1000,6 +1274,7 @@ impl<'a> State<'a> {
             dependencies,
             procedures: MutMap::default(),
             toplevel_expects: ToplevelExpects::default(),
+            toplevel_benches: ToplevelBenches::default(),
             exposed_to_host: ExposedToHost::default(),
             exposed_types,
             arc_modules,
@@ -1011,7 +1286,21 @@ impl<'a> State<'a> {
             exposed_symbols_by_module: MutMap::default(),
             timings: MutMap::default(),
             layout_caches: std::vec::Vec::with_capacity(number_of_workers),
-            cached_subs: Arc::new(Mutex::new(cached_subs)),
+            cached_subs: Arc::new(Mutex::new(if builtin_doc_tests {
+                // the examples add defs that the cached subs know nothing about
+                MutMap::default()
+            } else {
+                cached_subs
+            })),
+            interface_cache,
+            emit,
+            mock_effects,
+            coverage,
+            doc_tests,
+            builtin_doc_tests,
+            benches,
+            emitted_can: VecMap::default(),
+            emitted_mono: Vec::new(),
             render,
             exec_mode,
             make_specializations_pass: MakeSpecializationsPass::Pass(1),
@@ -1091,6 +1380,29 @@ impl ModuleTiming {
     }
 }
 
+/// Which of a module's top-level expects are specialized, so that `roc test` can run them
+#[derive(Debug, Clone, Copy)]
+enum BuildExpects<'a> {
+    None,
+    All,
+    /// Only the expects added for the doc comment examples at these regions
+    DocTests(&'a [Region]),
+}
+
+impl BuildExpects<'_> {
+    /// Whether to build the expect whose preceding comment is at `comment_region`
+    fn includes(self, comment_region: Region) -> bool {
+        match self {
+            BuildExpects::None => false,
+            BuildExpects::All => true,
+            // the expect of an example starts where the example does
+            BuildExpects::DocTests(examples) => examples
+                .iter()
+                .any(|example| example.start() == comment_region.start()),
+        }
+    }
+}
+
 /// A message sent _to_ a worker thread, describing the work to be done
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -1112,6 +1424,8 @@ enum BuildTask<'a> {
         aliases: MutMap<Symbol, Alias>,
         abilities_store: PendingAbilitiesStore,
         skip_constraint_gen: bool,
+        mock_effects: Option<Arc<MockEffects>>,
+        cache_entry: Option<CanonicalEntry>,
     },
     Solve {
         module: Module,
@@ -1126,6 +1440,7 @@ enum BuildTask<'a> {
         dep_idents: IdentIdsByModule,
         cached_subs: CachedSubs,
         derived_module: SharedDerivedModule,
+        cache_entry: Option<CacheEntry>,
     },
     BuildPendingSpecializations {
         module_timing: ModuleTiming,
@@ -1139,7 +1454,9 @@ enum BuildTask<'a> {
         exposed_by_module: ExposedByModule,
         abilities_store: AbilitiesStore,
         derived_module: SharedDerivedModule,
-        build_expects: bool,
+        build_expects: BuildExpects<'a>,
+        build_benches: bool,
+        count_coverage: bool,
     },
     MakeSpecializations {
         module_id: ModuleId,
@@ -1152,6 +1469,7 @@ enum BuildTask<'a> {
         exposed_by_module: ExposedByModule,
         world_abilities: WorldAbilities,
         derived_module: SharedDerivedModule,
+        count_coverage: bool,
     },
 }
 
@@ -1227,6 +1545,7 @@ pub fn load_and_typecheck_str<'a>(
         render,
         threading,
         exec_mode: ExecutionMode::Check,
+        ..Default::default()
     };
 
     match load(arena, load_start, exposed_types, cached_subs, load_config)? {
@@ -1466,38 +1785,27 @@ pub fn load<'a>(
     };
 
     match threads {
-        Threads::Single => load_single_threaded(
-            arena,
-            load_start,
-            exposed_types,
-            load_config.target_info,
-            cached_subs,
-            load_config.render,
-            load_config.exec_mode,
-        ),
+        Threads::Single => {
+            load_single_threaded(arena, load_start, exposed_types, cached_subs, load_config)
+        }
         Threads::Many(threads) => load_multi_threaded(
             arena,
             load_start,
             exposed_types,
-            load_config.target_info,
             cached_subs,
-            load_config.render,
             threads,
-            load_config.exec_mode,
+            load_config,
         ),
     }
 }
 
 /// Load using only a single thread; used when compiling to webassembly
-#[allow(clippy::too_many_arguments)]
 pub fn load_single_threaded<'a>(
     arena: &'a Bump,
     load_start: LoadStart<'a>,
     exposed_types: ExposedByModule,
-    target_info: TargetInfo,
     cached_subs: MutMap<ModuleId, (Subs, Vec<(Symbol, Variable)>)>,
-    render: RenderTarget,
-    exec_mode: ExecutionMode,
+    load_config: LoadConfig,
 ) -> Result<LoadResult<'a>, LoadingProblem<'a>> {
     let LoadStart {
         arc_modules,
@@ -1515,16 +1823,15 @@ pub fn load_single_threaded<'a>(
         .map_err(|_| LoadingProblem::MsgChannelDied)?;
 
     let number_of_workers = 1;
+    let target_info = load_config.target_info;
     let mut state = State::new(
         root_id,
-        target_info,
         exposed_types,
         arc_modules,
         ident_ids_by_module,
         cached_subs,
-        render,
         number_of_workers,
-        exec_mode,
+        load_config,
     );
 
     // We'll add tasks to this, and then worker threads will take tasks from it.
@@ -1692,16 +1999,13 @@ fn state_thread_step<'a>(
     }
 }
 
-#[allow(clippy::too_many_arguments)]
 fn load_multi_threaded<'a>(
     arena: &'a Bump,
     load_start: LoadStart<'a>,
     exposed_types: ExposedByModule,
-    target_info: TargetInfo,
     cached_subs: MutMap<ModuleId, (Subs, Vec<(Symbol, Variable)>)>,
-    render: RenderTarget,
     available_threads: usize,
-    exec_mode: ExecutionMode,
+    load_config: LoadConfig,
 ) -> Result<LoadResult<'a>, LoadingProblem<'a>> {
     let LoadStart {
         arc_modules,
@@ -1734,16 +2038,15 @@ fn load_multi_threaded<'a>(
         "`load_multi_threaded` needs at least one worker"
     );
 
+    let target_info = load_config.target_info;
     let mut state = State::new(
         root_id,
-        target_info,
         exposed_types,
         arc_modules,
         ident_ids_by_module,
         cached_subs,
-        render,
         num_workers,
-        exec_mode,
+        load_config,
     );
 
     // an arena for every worker, stored in an arena-allocated bumpalo vec to make the lifetimes work
@@ -2000,16 +2303,39 @@ fn start_tasks<'a>(
 macro_rules! debug_print_ir {
     ($state:expr, $interner:expr, $flag:path) => {
         dbg_do!($flag, {
-            let procs_string = $state
-                .procedures
-                .values()
-                .map(|proc| proc.to_pretty($interner, 200))
-                .collect::<Vec<_>>();
+            eprintln!("{}", pretty_print_procedures(&$state.procedures, $interner));
+        })
+    };
+}
+
+fn pretty_print_procedures<'a, I>(
+    procedures: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    interner: &I,
+) -> String
+where
+    I: Interner<'a, Layout<'a>>,
+{
+    let mut procs_string = procedures
+        .values()
+        .map(|proc| proc.to_pretty(interner, 200))
+        .collect::<Vec<_>>();
+
+    // the procedures are in a hash map, so sort them (by name, then arguments) to print them
+    // the same way every time
+    procs_string.sort();
+
+    procs_string.join("\n")
+}
 
-            let result = procs_string.join("\n");
+/// Keep the mono IR as it is after the given pass, if `roc build --emit=mono` asked for it.
+/// Unlike [`debug_print_ir`], this also works in release builds of the compiler.
+macro_rules! emit_mono_ir {
+    ($state:expr, $interner:expr, $pass:expr) => {
+        if $state.emit.contains(&EmitKind::Mono) {
+            let ir = pretty_print_procedures(&$state.procedures, $interner);
 
-            eprintln!("{}", result);
-        })
+            $state.emitted_mono.push(($pass, ir));
+        }
     };
 }
 
@@ -2184,6 +2510,10 @@ fn update<'a>(
                 header
                     .exposed_imports
                     .insert(Ident::from("Bool"), (Symbol::BOOL_BOOL, Region::zero()));
+
+                header
+                    .exposed_imports
+                    .insert(Ident::from("Eq"), (Symbol::BOOL_EQ, Region::zero()));
             }
 
             if header.module_id == ModuleId::NUM {
@@ -2278,12 +2608,37 @@ fn update<'a>(
 
             Ok(state)
         }
-        Parsed(parsed) => {
+        Parsed(mut parsed) => {
             state
                 .module_cache
                 .sources
                 .insert(parsed.module_id, (parsed.module_path.clone(), parsed.src));
 
+            if state.adds_doc_tests(parsed.module_id) {
+                let (regions, problems) = add_doc_tests(arena, parsed.src, &mut parsed.parsed_defs);
+
+                state
+                    .module_cache
+                    .doc_tests
+                    .insert(parsed.module_id, regions);
+
+                if !problems.is_empty() {
+                    state
+                        .module_cache
+                        .doc_test_problems
+                        .insert(parsed.module_id, problems);
+                }
+            }
+
+            if !parsed.parse_problems.is_empty() {
+                let problems = std::mem::take(&mut parsed.parse_problems);
+
+                state
+                    .module_cache
+                    .parse_problems
+                    .insert(parsed.module_id, problems);
+            }
+
             // If this was an app module, set the output path to be
             // the module's declared "name".
             //
@@ -2317,9 +2672,16 @@ fn update<'a>(
             constrained_module,
             canonicalization_problems,
             module_docs,
+            from_cache,
         }) => {
             let module_id = constrained_module.module.module_id;
             log!("generated constraints for {:?}", module_id);
+
+            if from_cache {
+                if let Some(cache) = state.interface_cache.as_mut() {
+                    cache.record_canonical(module_id);
+                }
+            }
             state
                 .module_cache
                 .can_problems
@@ -2364,27 +2726,41 @@ fn update<'a>(
             mut module_timing,
             abilities_store,
             loc_expects,
+            loc_dbgs,
+            loc_crashes,
+            interface,
+            from_cache,
         } => {
             log!("solved types for {:?}", module_id);
             module_timing.end_time = Instant::now();
 
+            if let Some(cache) = state.interface_cache.as_mut() {
+                cache.record(module_id, interface, from_cache);
+            }
+
             state
                 .module_cache
                 .type_problems
                 .insert(module_id, solved_module.problems);
 
-            let should_include_expects = !loc_expects.is_empty() && {
-                let modules = state.arc_modules.lock();
-                modules
-                    .package_eq(module_id, state.root_id)
-                    .expect("root or this module is not yet known - that's a bug!")
-            };
+            // A module loaded from the cache has no subs to evaluate its expectations against;
+            // that's fine, since we only use the cache when type checking.
+            let should_include_expects = !from_cache
+                && (!loc_expects.is_empty() || !loc_dbgs.is_empty() || !loc_crashes.is_empty())
+                && (state.module_cache.doc_tests.contains_key(&module_id) || {
+                    let modules = state.arc_modules.lock();
+                    modules
+                        .package_eq(module_id, state.root_id)
+                        .expect("root or this module is not yet known - that's a bug!")
+                });
 
             if should_include_expects {
                 let (path, _) = state.module_cache.sources.get(&module_id).unwrap();
 
                 let expectations = Expectations {
                     expectations: loc_expects,
+                    dbgs: loc_dbgs,
+                    crashes: loc_crashes,
                     subs: solved_subs.clone().into_inner(),
                     path: path.to_owned(),
                     ident_ids: ident_ids.clone(),
@@ -2398,12 +2774,7 @@ fn update<'a>(
 
             let work = state.dependencies.notify(module_id, Phase::SolveTypes);
 
-            // if there is a platform, the `platform` module provides host-exposed,
-            // otherwise the App module exposes host-exposed
-            let is_host_exposed = match state.platform_data {
-                None => module_id == state.root_id,
-                Some(ref platform_data) => module_id == platform_data.module_id,
-            };
+            let is_host_exposed = state.is_host_exposed(module_id);
 
             let add_to_host_exposed = is_host_exposed &&
                 // During testing, we don't need to expose anything to the host.
@@ -2501,6 +2872,29 @@ fn update<'a>(
                         LayoutCache::new(state.layout_interner.fork(), state.target_info)
                     });
 
+                    if state.emit.contains(&EmitKind::Can)
+                        && state
+                            .arc_modules
+                            .lock()
+                            .package_eq(module_id, state.root_id)
+                            .unwrap_or(false)
+                    {
+                        state.emitted_can.insert(module_id, decls.clone());
+                    }
+
+                    if state.coverage
+                        && state
+                            .arc_modules
+                            .lock()
+                            .package_eq(module_id, state.root_id)
+                            .unwrap_or(false)
+                    {
+                        state
+                            .module_cache
+                            .coverage
+                            .insert(module_id, coverage_points(&decls));
+                    }
+
                     let typechecked = TypeCheckedModule {
                         module_id,
                         layout_cache,
@@ -2516,6 +2910,9 @@ fn update<'a>(
                         .typechecked
                         .insert(module_id, typechecked);
                 } else {
+                    // Keep the declarations of every checked module around, so that tools
+                    // like the language server can look up definitions across modules.
+                    state.declarations_by_id.insert(module_id, decls);
                     state.constrained_ident_ids.insert(module_id, ident_ids);
                     state.timings.insert(module_id, module_timing);
                 }
@@ -2553,13 +2950,53 @@ fn update<'a>(
             module_timing,
             abilities_store,
             toplevel_expects,
+            toplevel_benches,
+            definition_regions,
         } => {
             log!("found specializations for {:?}", module_id);
 
             let subs = solved_subs.into_inner();
 
+            let mut toplevel_expects = toplevel_expects;
+
+            if let Some(examples) = state.module_cache.doc_tests.get(&module_id) {
+                let (_, src) = &state.module_cache.sources[&module_id];
+
+                for (symbol, region) in toplevel_expects.pure.iter() {
+                    // the expect of an example starts where the example does
+                    let example = examples
+                        .iter()
+                        .find(|example| example.start() == region.start());
+
+                    if let Some(example) = example {
+                        toplevel_expects.doc_tests.insert(*symbol);
+
+                        match documented_def(src, *example, &definition_regions) {
+                            Some(def) => {
+                                toplevel_expects.enclosing_defs.insert(*symbol, def);
+                            }
+                            None => {
+                                toplevel_expects.enclosing_defs.remove(symbol);
+                            }
+                        }
+                    }
+                }
+            }
+
             state.toplevel_expects.pure.extend(toplevel_expects.pure);
             state.toplevel_expects.fx.extend(toplevel_expects.fx);
+            state
+                .toplevel_expects
+                .enclosing_defs
+                .extend(toplevel_expects.enclosing_defs);
+            state
+                .toplevel_expects
+                .doc_tests
+                .extend(toplevel_expects.doc_tests);
+            state
+                .toplevel_benches
+                .thunks
+                .extend(toplevel_benches.thunks);
 
             state
                 .module_cache
@@ -2568,13 +3005,27 @@ fn update<'a>(
                 .or_default()
                 .extend(procs_base.module_thunks.iter().copied());
 
+            let our_exposed_types = state
+                .exposed_types
+                .get(&module_id)
+                .unwrap_or_else(|| internal_error!("Exposed types for {:?} missing", module_id))
+                .clone();
+
+            // Add our abilities to the world. This happens before any module makes its
+            // specializations, since the modules that import us make theirs before we make ours,
+            // and a builtin's doc tests can need the abilities of the builtins it imports.
+            state.world_abilities.insert(
+                module_id,
+                abilities_store,
+                our_exposed_types.exposed_types_storage_subs,
+            );
+
             let found_specializations_module = FoundSpecializationsModule {
                 ident_ids,
                 layout_cache,
                 procs_base,
                 subs,
                 module_timing,
-                abilities_store,
             };
 
             state
@@ -2713,6 +3164,7 @@ fn update<'a>(
                     log!("specializations complete from {:?}", module_id);
 
                     debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_SPECIALIZATION);
+                    emit_mono_ir!(state, &layout_interner, "specialization");
 
                     let ident_ids = state.constrained_ident_ids.get_mut(&module_id).unwrap();
 
@@ -2725,6 +3177,7 @@ fn update<'a>(
                     );
 
                     debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_RESET_REUSE);
+                    emit_mono_ir!(state, &layout_interner, "reset-reuse");
 
                     Proc::insert_refcount_operations(
                         arena,
@@ -2736,6 +3189,7 @@ fn update<'a>(
                     );
 
                     debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_REFCOUNT);
+                    emit_mono_ir!(state, &layout_interner, "refcount");
 
                     // This is not safe with the new non-recursive RC updates that we do for tag unions
                     //
@@ -2911,23 +3365,49 @@ fn finish_specialization<'a>(
 
     let State {
         toplevel_expects,
+        toplevel_benches,
         procedures,
         module_cache,
         output_path,
         platform_path,
         platform_data,
         exec_mode,
+        emitted_can,
+        emitted_mono,
         ..
     } = state;
 
+    let emitted_ir = EmittedIr {
+        can: emitted_can
+            .into_iter()
+            .map(|(module_id, decls)| {
+                let ctx = roc_can::debug::Ctx { interns: &interns };
+
+                (
+                    module_id,
+                    roc_can::debug::pretty_print_declarations(&ctx, &decls),
+                )
+            })
+            .collect(),
+        mono: emitted_mono,
+    };
+
     let ModuleCache {
         expectations,
+        coverage,
         type_problems,
+        parse_problems,
+        doc_test_problems,
         can_problems,
         sources,
         ..
     } = module_cache;
 
+    let (parse_problems, _) =
+        render_parse_problems(parse_problems, &sources, &interns, state.render);
+    let (doc_test_problems, _) =
+        render_parse_problems(doc_test_problems, &sources, &interns, state.render);
+
     let sources: MutMap<ModuleId, (PathBuf, Box<str>)> = sources
         .into_iter()
         .map(|(id, (path, src))| (id, (path, src.into())))
@@ -2996,10 +3476,13 @@ fn finish_specialization<'a>(
     };
 
     Ok(MonomorphizedModule {
+        parse_problems,
+        doc_test_problems,
         can_problems,
         type_problems,
         output_path,
         expectations,
+        coverage,
         exposed_to_host,
         module_id: state.root_id,
         subs,
@@ -3010,6 +3493,8 @@ fn finish_specialization<'a>(
         sources,
         timings: state.timings,
         toplevel_expects,
+        toplevel_benches,
+        emitted_ir,
     })
 }
 
@@ -3046,6 +3531,13 @@ fn finish(
         all_ident_ids: state.constrained_ident_ids,
     };
 
+    let (parse_problems, parse_diagnostics) = render_parse_problems(
+        state.module_cache.parse_problems,
+        &state.module_cache.sources,
+        &interns,
+        state.render,
+    );
+
     let sources = state
         .module_cache
         .sources
@@ -3055,10 +3547,17 @@ fn finish(
 
     let exposed_values = exposed_vars_by_symbol.iter().map(|x| x.0).collect();
 
+    let (cached_modules, cached_declarations) = state
+        .interface_cache
+        .map(InterfaceCache::into_reused)
+        .unwrap_or_default();
+
     LoadedModule {
         module_id: state.root_id,
         interns,
         solved,
+        parse_problems,
+        parse_diagnostics,
         can_problems: state.module_cache.can_problems,
         type_problems: state.module_cache.type_problems,
         declarations_by_id: state.declarations_by_id,
@@ -3072,6 +3571,8 @@ fn finish(
         timings: state.timings,
         documentation,
         abilities_store,
+        cached_modules,
+        cached_declarations,
     }
 }
 
@@ -3266,6 +3767,7 @@ fn load_module<'a>(
         "Encode", ModuleId::ENCODE
         "Decode", ModuleId::DECODE
         "Json", ModuleId::JSON
+        "Hash", ModuleId::HASH
     }
 
     let (filename, opt_shorthand) = module_name_to_path(src_dir, module_name, arc_shorthands);
@@ -4086,6 +4588,7 @@ impl<'a> BuildTask<'a> {
         declarations: Declarations,
         cached_subs: CachedSubs,
         derived_module: SharedDerivedModule,
+        cache_entry: Option<CacheEntry>,
     ) -> Self {
         let exposed_by_module = exposed_types.retain_modules(imported_modules.keys());
 
@@ -4106,6 +4609,7 @@ impl<'a> BuildTask<'a> {
             module_timing,
             cached_subs,
             derived_module,
+            cache_entry,
         }
     }
 }
@@ -4354,11 +4858,15 @@ fn run_solve_solve(
                 })
         };
 
-        // Expose anything that is explicitly exposed by the header, or is a specialization of an
-        // ability.
+        // Expose anything that is explicitly exposed by the header, is a specialization of an
+        // ability, or is a builtin that derived implementations call.
         let exposed_vars_by_symbol: Vec<_> = solved_env
             .vars_by_symbol()
-            .filter(|(k, _)| exposed_symbols.contains(k) || is_specialization_symbol(*k))
+            .filter(|(k, _)| {
+                exposed_symbols.contains(k)
+                    || is_specialization_symbol(*k)
+                    || k.is_exposed_for_builtin_derivers()
+            })
             .collect();
 
         (
@@ -4393,6 +4901,7 @@ fn run_solve<'a>(
     dep_idents: IdentIdsByModule,
     cached_subs: CachedSubs,
     derived_module: SharedDerivedModule,
+    cache_entry: Option<CacheEntry>,
 ) -> Msg<'a> {
     let solve_start = Instant::now();
 
@@ -4403,8 +4912,45 @@ fn run_solve<'a>(
 
     let mut module = module;
     let loc_expects = std::mem::take(&mut module.loc_expects);
+    let loc_dbgs = std::mem::take(&mut module.loc_dbgs);
+    let loc_crashes = std::mem::take(&mut module.loc_crashes);
     let module = module;
 
+    let canonical = cache_entry
+        .as_ref()
+        .map(|entry| entry.canonical_fingerprint(&aliases, &module.abilities_store));
+
+    if let (Some(entry), Some(canonical)) = (&cache_entry, canonical) {
+        if let Some((exposed_types, solved_implementations, interface)) = entry.load(canonical) {
+            let solved_module = SolvedModule {
+                exposed_vars_by_symbol: Vec::new(),
+                problems: Vec::new(),
+                aliases,
+                solved_implementations,
+                exposed_types,
+            };
+
+            module_timing.solve = Instant::now().duration_since(solve_start);
+
+            // Nothing downstream of a cached module needs more than its exposed types.
+            return Msg::SolvedTypes {
+                module_id,
+                solved_subs: Solved(Subs::new()),
+                ident_ids,
+                decls,
+                dep_idents,
+                solved_module,
+                module_timing,
+                abilities_store: AbilitiesStore::default(),
+                loc_expects,
+                loc_dbgs,
+                loc_crashes,
+                interface: Some(interface),
+                from_cache: true,
+            };
+        }
+    }
+
     let (solved_subs, solved_implementations, exposed_vars_by_symbol, problems, abilities_store) = {
         if module_id.is_builtin() {
             match cached_subs.lock().remove(&module_id) {
@@ -4451,6 +4997,14 @@ fn run_solve<'a>(
         &abilities_store,
     );
 
+    // Modules with type errors are solved again every time, so their errors get reported.
+    let interface = match (&cache_entry, canonical) {
+        (Some(entry), Some(canonical)) if problems.is_empty() => {
+            Some(entry.store(canonical, &exposed_types, &solved_implementations))
+        }
+        _ => None,
+    };
+
     let solved_module = SolvedModule {
         exposed_vars_by_symbol,
         problems,
@@ -4474,6 +5028,10 @@ fn run_solve<'a>(
         module_timing,
         abilities_store,
         loc_expects,
+        loc_dbgs,
+        loc_crashes,
+        interface,
+        from_cache: false,
     }
 }
 
@@ -4538,12 +5096,15 @@ fn canonicalize_and_constrain<'a>(
     imported_abilities_state: PendingAbilitiesStore,
     parsed: ParsedModule<'a>,
     skip_constraint_gen: bool,
+    mock_effects: Option<&MockEffects>,
+    cache_entry: Option<CanonicalEntry>,
 ) -> CanAndCon {
     let canonicalize_start = Instant::now();
 
     let ParsedModule {
         module_id,
         module_name,
+        src,
         header_for,
         exposed_ident_ids,
         parsed_defs,
@@ -4554,6 +5115,72 @@ fn canonicalize_and_constrain<'a>(
         ..
     } = parsed;
 
+    // What a platform requires from the app is not part of the key, so we don't cache platforms.
+    let cache_key = match &cache_entry {
+        Some(entry) if symbols_from_requires.is_empty() => entry.key(
+            module_ids,
+            module_id,
+            src,
+            &exposed_ident_ids,
+            &dep_idents,
+            &exposed_symbols,
+            &aliases,
+            &imported_abilities_state,
+        ),
+        _ => None,
+    };
+
+    let cached = match (&cache_entry, cache_key) {
+        (Some(entry), Some(key)) => entry.load(key, module_ids, module_id, exposed_symbols.clone()),
+        _ => None,
+    };
+
+    if let Some(CanonicalModule {
+        module,
+        declarations,
+        ident_ids,
+        next_var,
+        pending_derives,
+    }) = cached
+    {
+        module_timing.canonicalize = Instant::now().duration_since(canonicalize_start);
+
+        let mut constraints = Constraints::new();
+
+        let constraint = if skip_constraint_gen {
+            roc_can::constraint::Constraint::True
+        } else {
+            constrain_module(
+                &mut constraints,
+                Vec::new(),
+                &module.abilities_store,
+                &declarations,
+                module_id,
+            )
+        };
+
+        let constrained_module = ConstrainedModule {
+            module,
+            declarations,
+            imported_modules,
+            var_store: VarStore::new(next_var),
+            constraints,
+            constraint,
+            ident_ids,
+            dep_idents,
+            module_timing,
+            pending_derives,
+        };
+
+        // Only modules without problems are stored. Docs aren't: `roc docs` never uses the cache.
+        return CanAndCon {
+            constrained_module,
+            canonicalization_problems: Vec::new(),
+            module_docs: None,
+            from_cache: true,
+        };
+    }
+
     // _before has an underscore because it's unused in --release builds
     let _before = roc_types::types::get_type_clone_count();
 
@@ -4575,6 +5202,7 @@ fn canonicalize_and_constrain<'a>(
         &exposed_symbols,
         &symbols_from_requires,
         &mut var_store,
+        mock_effects,
     );
 
     // _after has an underscore because it's unused in --release builds
@@ -4654,7 +5282,11 @@ fn canonicalize_and_constrain<'a>(
             Vacant(vacant) => {
                 let should_include_builtin = matches!(
                     name.module_id(),
-                    ModuleId::ENCODE | ModuleId::DECODE | ModuleId::DICT | ModuleId::SET
+                    ModuleId::ENCODE
+                        | ModuleId::DECODE
+                        | ModuleId::HASH
+                        | ModuleId::DICT
+                        | ModuleId::SET
                 );
 
                 if !name.is_builtin() || should_include_builtin {
@@ -4674,8 +5306,28 @@ fn canonicalize_and_constrain<'a>(
         rigid_variables: module_output.rigid_variables,
         abilities_store: module_output.scope.abilities_store,
         loc_expects: module_output.loc_expects,
+        loc_dbgs: module_output.loc_dbgs,
+        loc_crashes: module_output.loc_crashes,
     };
 
+    if let (Some(entry), Some(key)) = (&cache_entry, cache_key) {
+        // A module with problems is canonicalized every time, so they keep being reported.
+        if module_output.problems.is_empty() {
+            // Safety: the var store hands out variables by index, this is the next one
+            let next_var = unsafe { Variable::from_index(var_store.peek()) };
+
+            entry.store(
+                key,
+                module_ids,
+                &module,
+                &module_output.declarations,
+                &module_output.scope.locals.ident_ids,
+                next_var,
+                &module_output.pending_derives,
+            );
+        }
+    }
+
     let constrained_module = ConstrainedModule {
         module,
         declarations: module_output.declarations,
@@ -4693,6 +5345,7 @@ fn canonicalize_and_constrain<'a>(
         constrained_module,
         canonicalization_problems: module_output.problems,
         module_docs,
+        from_cache: false,
     }
 }
 
@@ -4700,9 +5353,13 @@ fn parse<'a>(arena: &'a Bump, header: ModuleHeader<'a>) -> Result<Msg<'a>, Loadi
     let mut module_timing = header.module_timing;
     let parse_start = Instant::now();
     let source = header.parse_state.original_bytes();
-    let parse_state = header.parse_state;
-    let parsed_defs = match module_defs().parse(arena, parse_state) {
-        Ok((_, success, _state)) => success,
+    let parse_state = header.parse_state.with_error_recovery();
+    let (parsed_defs, parse_problems) = match module_defs().parse(arena, parse_state) {
+        Ok((_, success, state)) => {
+            let problems = state.recovered_errors().into_iter().cloned().collect();
+
+            (success, problems)
+        }
         Err((_, fail, state)) => {
             return Err(LoadingProblem::ParsingFailed(
                 fail.into_file_error(header.module_path, &state),
@@ -4746,6 +5403,7 @@ fn parse<'a>(arena: &'a Bump, header: ModuleHeader<'a>) -> Result<Msg<'a>, Loadi
         exposed_ident_ids,
         exposed_imports,
         parsed_defs,
+        parse_problems,
         symbols_from_requires,
         header_for,
     };
@@ -4807,6 +5465,7 @@ fn make_specializations<'a>(
     world_abilities: WorldAbilities,
     exposed_by_module: &ExposedByModule,
     derived_module: SharedDerivedModule,
+    count_coverage: bool,
 ) -> Msg<'a> {
     let make_specializations_start = Instant::now();
     let mut update_mode_ids = UpdateModeIds::new();
@@ -4823,6 +5482,7 @@ fn make_specializations<'a>(
         abilities: AbilitiesView::World(&world_abilities),
         exposed_by_module,
         derived_module: &derived_module,
+        count_coverage,
     };
 
     let mut procs = Procs::new_in(arena);
@@ -4885,12 +5545,16 @@ fn build_pending_specializations<'a>(
     exposed_by_module: &ExposedByModule,
     abilities_store: AbilitiesStore,
     derived_module: SharedDerivedModule,
-    build_expects: bool,
+    build_expects: BuildExpects<'a>,
+    build_benches: bool,
+    count_coverage: bool,
 ) -> Msg<'a> {
     let find_specializations_start = Instant::now();
 
     let mut module_thunks = bumpalo::collections::Vec::new_in(arena);
     let mut toplevel_expects = ToplevelExpects::default();
+    let mut toplevel_benches = ToplevelBenches::default();
+    let definition_regions = roc_can::traverse::find_definitions(&declarations);
 
     let mut procs_base = ProcsBase {
         partial_procs: BumpMap::default(),
@@ -4917,6 +5581,7 @@ fn build_pending_specializations<'a>(
         abilities: AbilitiesView::Module(&abilities_store),
         exposed_by_module,
         derived_module: &derived_module,
+        count_coverage,
     };
 
     // Add modules' decls to Procs
@@ -4983,6 +5648,7 @@ fn build_pending_specializations<'a>(
                     captured_symbols: CapturedSymbols::None,
                     body: body.value,
                     body_var: expr_var,
+                    body_region: body.region,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
                 };
@@ -5048,6 +5714,65 @@ fn build_pending_specializations<'a>(
                 );
 
                 procs_base.partial_procs.insert(symbol, partial_proc);
+
+                if !build_benches || function_def.arguments.len() != 1 {
+                    continue;
+                }
+
+                let is_bench = mono_env
+                    .ident_ids
+                    .get_name(symbol.ident_id())
+                    .map_or(false, is_bench_name);
+
+                let (fn_var, closure_var, ret_var) =
+                    match bench_function_type(mono_env.subs, expr_var) {
+                        Some(vars) if is_bench => vars,
+                        _ => continue,
+                    };
+
+                let thunk = Symbol::new(home, mono_env.ident_ids.gen_unique());
+
+                // mark this symbol as a top-level thunk before any other work on the procs
+                module_thunks.push(thunk);
+
+                let thunk_var = Variable::EMPTY_RECORD;
+
+                procs_base.host_specializations.insert_host_exposed(
+                    mono_env.subs,
+                    LambdaName::no_niche(thunk),
+                    None,
+                    thunk_var,
+                );
+
+                let name_region = declarations.symbols[index].region;
+                let expr_region = declarations.expressions[index].region;
+                let region = Region::span_across(&name_region, &expr_region);
+
+                let proc = PartialProc {
+                    annotation: thunk_var,
+                    // This is a 0-arity thunk, so it has no arguments.
+                    pattern_symbols: &[],
+                    // This is a top-level definition, so it cannot capture anything
+                    captured_symbols: CapturedSymbols::None,
+                    body: bench_thunk_body(
+                        &mut mono_env,
+                        symbol,
+                        fn_var,
+                        closure_var,
+                        ret_var,
+                        region,
+                    ),
+                    body_var: thunk_var,
+                    // the benchmark itself is what coverage would count
+                    body_region: Region::zero(),
+                    // This is a 0-arity thunk, so it cannot be recursive
+                    is_self_recursive: false,
+                };
+
+                toplevel_benches
+                    .thunks
+                    .insert(thunk, Loc::at(region, symbol));
+                procs_base.partial_procs.insert(thunk, proc);
             }
             Destructure(d_index) => {
                 let loc_pattern = &declarations.destructs[d_index.index()].loc_pattern;
@@ -5121,6 +5846,7 @@ fn build_pending_specializations<'a>(
                     captured_symbols: CapturedSymbols::None,
                     body: body.value,
                     body_var: expr_var,
+                    body_region: body.region,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
                 };
@@ -5132,7 +5858,7 @@ fn build_pending_specializations<'a>(
             }
             Expectation => {
                 // skip expectations if we're not going to run them
-                if !build_expects {
+                if !build_expects.includes(declarations.symbols[index].region) {
                     continue;
                 }
 
@@ -5190,6 +5916,8 @@ fn build_pending_specializations<'a>(
                     captured_symbols: CapturedSymbols::None,
                     body: body.value,
                     body_var: expr_var,
+                    // expects run the code that coverage is counted for, but are not counted themselves
+                    body_region: Region::zero(),
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
                 };
@@ -5201,11 +5929,14 @@ fn build_pending_specializations<'a>(
                 let region = Region::span_across(&name_region, &expr_region);
 
                 toplevel_expects.pure.insert(symbol, region);
+                if let Some(def) = enclosing_def(&declarations, region) {
+                    toplevel_expects.enclosing_defs.insert(symbol, def);
+                }
                 procs_base.partial_procs.insert(symbol, proc);
             }
             ExpectationFx => {
                 // skip expectations if we're not going to run them
-                if !build_expects {
+                if !build_expects.includes(declarations.symbols[index].region) {
                     continue;
                 }
 
@@ -5263,6 +5994,8 @@ fn build_pending_specializations<'a>(
                     captured_symbols: CapturedSymbols::None,
                     body: body.value,
                     body_var: expr_var,
+                    // expects run the code that coverage is counted for, but are not counted themselves
+                    body_region: Region::zero(),
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
                 };
@@ -5274,6 +6007,9 @@ fn build_pending_specializations<'a>(
                 let region = Region::span_across(&name_region, &expr_region);
 
                 toplevel_expects.fx.insert(symbol, region);
+                if let Some(def) = enclosing_def(&declarations, region) {
+                    toplevel_expects.enclosing_defs.insert(symbol, def);
+                }
                 procs_base.partial_procs.insert(symbol, proc);
             }
         }
@@ -5294,9 +6030,157 @@ fn build_pending_specializations<'a>(
         module_timing,
         abilities_store,
         toplevel_expects,
+        toplevel_benches,
+        definition_regions,
+    }
+}
+
+/// Benchmarks are named like `benchSort`, so that a def named e.g. `bencher` is not one.
+fn is_bench_name(name: &str) -> bool {
+    name.strip_prefix("bench")
+        .and_then(|rest| rest.chars().next())
+        .map_or(false, char::is_uppercase)
+}
+
+/// The function, closure and return variables of a benchmark, if its type is `{} -> a`.
+fn bench_function_type(subs: &Subs, var: Variable) -> Option<(Variable, Variable, Variable)> {
+    use roc_types::subs::{Content, FlatType};
+
+    let (arguments, closure_var, ret_var) = match subs.get_content_without_compacting(var) {
+        Content::Structure(FlatType::Func(arguments, closure_var, ret_var)) => {
+            (*arguments, *closure_var, *ret_var)
+        }
+        Content::Alias(_, _, real_var, _) => return bench_function_type(subs, *real_var),
+        _ => return None,
+    };
+
+    let takes_unit = match subs.variables[arguments.indices()] {
+        [argument] => is_empty_record(subs, argument),
+        _ => false,
+    };
+
+    if takes_unit {
+        Some((var, closure_var, ret_var))
+    } else {
+        None
+    }
+}
+
+fn is_empty_record(subs: &Subs, var: Variable) -> bool {
+    use roc_types::subs::{Content, FlatType};
+
+    match subs.get_content_without_compacting(var) {
+        Content::Structure(FlatType::EmptyRecord) => true,
+        Content::Structure(FlatType::Record(fields, ext)) => {
+            // `\{} -> ...` takes an open record, like `{}*`
+            fields.is_empty()
+                && (is_empty_record(subs, *ext)
+                    || matches!(
+                        subs.get_content_without_compacting(*ext),
+                        Content::FlexVar(_)
+                    ))
+        }
+        Content::Alias(_, _, real_var, _) => is_empty_record(subs, *real_var),
+        _ => false,
     }
 }
 
+/// The body of the thunk that runs a benchmark once:
+///
+/// ```roc
+/// result = bench {}
+///
+/// expect roc_bench_keep
+///
+/// {}
+/// ```
+///
+/// `roc_bench_keep` in the builtins always returns true, but does so through a volatile read,
+/// so the optimizer cannot drop the call (or its result, which a failing expect would report).
+fn bench_thunk_body(
+    env: &mut roc_mono::ir::Env,
+    bench: Symbol,
+    fn_var: Variable,
+    closure_var: Variable,
+    ret_var: Variable,
+    region: Region,
+) -> roc_can::expr::Expr {
+    use roc_can::def::Def;
+    use roc_can::expr::Expr;
+    use roc_can::pattern::Pattern;
+    use roc_module::called_via::CalledVia;
+
+    let result = env.unique_symbol();
+
+    let call = Expr::Call(
+        Box::new((
+            fn_var,
+            Loc::at(region, Expr::Var(bench)),
+            closure_var,
+            ret_var,
+        )),
+        vec![(Variable::EMPTY_RECORD, Loc::at(region, Expr::EmptyRecord))],
+        CalledVia::Space,
+    );
+
+    let def = Def {
+        loc_pattern: Loc::at(region, Pattern::Identifier(result)),
+        loc_expr: Loc::at(region, call),
+        expr_var: ret_var,
+        pattern_vars: std::iter::once((result, ret_var)).collect(),
+        annotation: None,
+    };
+
+    let keep = Expr::ForeignCall {
+        foreign_symbol: "roc_bench_keep".into(),
+        args: vec![],
+        ret_var: Variable::BOOL,
+    };
+
+    let expect = Expr::Expect {
+        loc_condition: Box::new(Loc::at(region, keep)),
+        loc_continuation: Box::new(Loc::at(region, Expr::EmptyRecord)),
+        lookups_in_cond: vec![(result, ret_var)],
+    };
+
+    Expr::LetNonRec(Box::new(def), Box::new(Loc::at(region, expect)))
+}
+
+/// The closest top-level def that comes before a top-level expect in the source.
+fn enclosing_def(declarations: &Declarations, expect_region: Region) -> Option<Symbol> {
+    use roc_can::expr::DeclarationTag::*;
+
+    declarations
+        .declarations
+        .iter()
+        .zip(declarations.symbols.iter())
+        .filter(|(tag, _)| matches!(tag, Value | Function(_) | Recursive(_) | TailRecursive(_)))
+        .map(|(_, loc_symbol)| loc_symbol)
+        .filter(|loc_symbol| loc_symbol.region.start() < expect_region.start())
+        .max_by_key(|loc_symbol| loc_symbol.region.start())
+        .map(|loc_symbol| loc_symbol.value)
+}
+
+/// The def that the doc comment with this example documents: the next def, if the doc comment
+/// runs right up to it (or to its annotation). Examples in the module's docs document no def.
+fn documented_def(
+    src: &str,
+    example: Region,
+    definition_regions: &[Loc<Symbol>],
+) -> Option<Symbol> {
+    let def = definition_regions
+        .iter()
+        .filter(|loc_symbol| loc_symbol.region.start() >= example.end())
+        .min_by_key(|loc_symbol| loc_symbol.region.start())?;
+
+    let between = &src[example.end().offset as usize..def.region.start().offset as usize];
+
+    // a blank line ends the doc comment before reaching the def
+    let reaches_def = !between.lines().skip(1).any(|line| line.trim().is_empty());
+
+    reaches_def.then(|| def.value)
+}
+
 /// Loads derived ability members up for specialization into the Derived module, prior to making
 /// their specializations.
 // TODO: right now, this runs sequentially, and no other modules are mono'd in parallel to the
@@ -5348,6 +6232,7 @@ fn load_derived_partial_procs<'a>(
             abilities: AbilitiesView::World(world_abilities),
             exposed_by_module,
             derived_module,
+            count_coverage: false,
         };
 
         let partial_proc = match derived_expr {
@@ -5383,6 +6268,8 @@ fn load_derived_partial_procs<'a>(
                     captured_symbols: CapturedSymbols::None,
                     body: derived_expr,
                     body_var: derived_expr_var,
+                    // derived code is not written anywhere
+                    body_region: Region::zero(),
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
                 }
@@ -5438,6 +6325,8 @@ fn run_task<'a>(
             aliases,
             abilities_store,
             skip_constraint_gen,
+            mock_effects,
+            cache_entry,
         } => {
             let can_and_con = canonicalize_and_constrain(
                 arena,
@@ -5448,6 +6337,8 @@ fn run_task<'a>(
                 abilities_store,
                 parsed,
                 skip_constraint_gen,
+                mock_effects.as_deref(),
+                cache_entry,
             );
 
             Ok(Msg::CanonicalizedAndConstrained(can_and_con))
@@ -5465,6 +6356,7 @@ fn run_task<'a>(
             dep_idents,
             cached_subs,
             derived_module,
+            cache_entry,
         } => Ok(run_solve(
             module,
             ident_ids,
@@ -5478,6 +6370,7 @@ fn run_task<'a>(
             dep_idents,
             cached_subs,
             derived_module,
+            cache_entry,
         )),
         BuildPendingSpecializations {
             module_id,
@@ -5492,6 +6385,8 @@ fn run_task<'a>(
             exposed_by_module,
             derived_module,
             build_expects,
+            build_benches,
+            count_coverage,
         } => Ok(build_pending_specializations(
             arena,
             solved_subs,
@@ -5507,6 +6402,8 @@ fn run_task<'a>(
             abilities_store,
             derived_module,
             build_expects,
+            build_benches,
+            count_coverage,
         )),
         MakeSpecializations {
             module_id,
@@ -5519,6 +6416,7 @@ fn run_task<'a>(
             world_abilities,
             exposed_by_module,
             derived_module,
+            count_coverage,
         } => Ok(make_specializations(
             arena,
             module_id,
@@ -5532,6 +6430,7 @@ fn run_task<'a>(
             world_abilities,
             &exposed_by_module,
             derived_module,
+            count_coverage,
         )),
     }?;
 
@@ -5647,6 +6546,12 @@ fn to_parse_problem_report<'a>(
 
     let lines = LineInfo::new(src);
 
+    let region = problem
+        .problem
+        .problem
+        .region()
+        .map(|region| lines.convert_region(region));
+
     let report = parse_problem(
         &alloc,
         &lines,
@@ -5658,11 +6563,70 @@ fn to_parse_problem_report<'a>(
     let mut buf = String::new();
     let palette = DEFAULT_PALETTE;
 
-    report.render(render, &mut buf, &alloc, &palette);
+    report.render_at(render, region, &mut buf, &alloc, &palette);
 
     buf
 }
 
+/// Render the syntax errors the parser recovered from. Unlike the ones that stop loading
+/// altogether (see `to_parse_problem_report`), these are reported alongside the problems
+/// found in the rest of the module.
+///
+/// Each one also comes back as a diagnostic, for editors that show the title and the
+/// region apart from the message.
+fn render_parse_problems(
+    parse_problems: MutMap<ModuleId, Vec<SyntaxError<'_>>>,
+    sources: &MutMap<ModuleId, (PathBuf, &str)>,
+    interns: &Interns,
+    render: RenderTarget,
+) -> (
+    MutMap<ModuleId, Vec<String>>,
+    MutMap<ModuleId, Vec<Diagnostic>>,
+) {
+    use roc_reporting::report::{parse_problem, RocDocAllocator, DEFAULT_PALETTE};
+
+    let mut rendered = MutMap::default();
+    let mut diagnostics = MutMap::default();
+
+    for (home, problems) in parse_problems {
+        let (module_path, src) = &sources[&home];
+        let src_lines: Vec<&str> = src.split('\n').collect();
+        let lines = LineInfo::new(src);
+        let alloc = RocDocAllocator::new(&src_lines, home, interns);
+
+        let report = |problem| {
+            let file_error = FileError {
+                problem: SourceError {
+                    problem,
+                    bytes: src.as_bytes(),
+                },
+                filename: module_path.clone(),
+            };
+
+            parse_problem(&alloc, &lines, module_path.clone(), 0, file_error)
+        };
+
+        let mut module_rendered = Vec::with_capacity(problems.len());
+        let mut module_diagnostics = Vec::with_capacity(problems.len());
+
+        for problem in problems {
+            let region = problem.region().map(|region| lines.convert_region(region));
+
+            module_diagnostics.push(report(problem.clone()).into_diagnostic(region));
+
+            let mut buf = String::new();
+            report(problem).render_at(render, region, &mut buf, &alloc, &DEFAULT_PALETTE);
+
+            module_rendered.push(buf);
+        }
+
+        rendered.insert(home, module_rendered);
+        diagnostics.insert(home, module_diagnostics);
+    }
+
+    (rendered, diagnostics)
+}
+
 fn to_missing_platform_report(module_id: ModuleId, other: PlatformPath) -> String {
     use roc_reporting::report::{Report, RocDocAllocator, Severity, DEFAULT_PALETTE};
     use ven_pretty::DocAllocator;
@@ -1,6 +1,8 @@
 #![warn(clippy::dbg_macro)]
 // See github.com/roc-lang/roc/issues/800 for discussion of the large_enum_variant check.
 #![allow(clippy::large_enum_variant)]
+pub mod cache;
+pub mod doc_tests;
 pub mod docs;
 pub mod file;
 mod work;
@@ -14,15 +14,16 @@ extern crate roc_module;
 
 mod helpers;
 
-use crate::helpers::fixtures_dir;
+use crate::helpers::{fixtures_dir, with_larger_debug_stack};
 use bumpalo::Bump;
 use roc_can::module::ExposedByModule;
+use roc_collections::MutSet;
 use roc_load_internal::file::{ExecutionMode, LoadConfig, Threading};
 use roc_load_internal::file::{LoadResult, LoadStart, LoadedModule, LoadingProblem};
 use roc_module::ident::ModuleName;
 use roc_module::symbol::{Interns, ModuleId};
 use roc_problem::can::Problem;
-use roc_region::all::LineInfo;
+use roc_region::all::{LineColumn, LineInfo, Region};
 use roc_reporting::report::can_problem;
 use roc_reporting::report::RenderTarget;
 use roc_reporting::report::RocDocAllocator;
@@ -46,6 +47,7 @@ fn load_and_typecheck(
         render: RenderTarget::Generic,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Check,
+        ..Default::default()
     };
 
     match roc_load_internal::file::load(
@@ -102,6 +104,17 @@ fn multiple_modules(subdir: &str, files: Vec<(&str, &str)>) -> Result<LoadedModu
             let home = loaded_module.module_id;
             let (filepath, src) = loaded_module.sources.get(&home).unwrap();
 
+            // a syntax error in any module, e.g. the platform, is fatal here
+            let parse_problems: Vec<String> = loaded_module
+                .parse_problems
+                .values()
+                .flatten()
+                .cloned()
+                .collect();
+            if !parse_problems.is_empty() {
+                return Err(parse_problems.concat());
+            }
+
             let can_problems = loaded_module.can_problems.remove(&home).unwrap_or_default();
             if !can_problems.is_empty() {
                 return Err(format_can_problems(
@@ -481,12 +494,12 @@ fn load_astar() {
     expect_types(
         loaded_module,
         hashmap! {
-            "findPath" => "{ costFunction : position, position -> F64, end : position, moveFunction : position -> Set position, start : position } -> Result (List position) [KeyNotFound]*",
-            "initialModel" => "position -> Model position",
-            "reconstructPath" => "Dict position position, position -> List position",
-            "updateCost" => "position, position, Model position -> Model position",
-            "cheapestOpen" => "(position -> F64), Model position -> Result position [KeyNotFound]*",
-            "astar" => "(position, position -> F64), (position -> Set position), position, Model position -> [Err [KeyNotFound]*, Ok (List position)]*",
+            "findPath" => "{ costFunction : position, position -> F64, end : position, moveFunction : position -> Set position, start : position } -> Result (List position) [KeyNotFound]* | position has Hash",
+            "initialModel" => "position -> Model position | position has Hash",
+            "reconstructPath" => "Dict position position, position -> List position | position has Hash",
+            "updateCost" => "position, position, Model position -> Model position | position has Hash",
+            "cheapestOpen" => "(position -> F64), Model position -> Result position [KeyNotFound]* | position has Hash",
+            "astar" => "(position, position -> F64), (position -> Set position), position, Model position -> [Err [KeyNotFound]*, Ok (List position)]* | position has Hash",
         },
     );
 }
@@ -598,6 +611,38 @@ fn parse_problem() {
     }
 }
 
+#[test]
+fn parse_problem_diagnostics() {
+    let modules = vec![(
+        "Main",
+        indoc!(
+            r#"
+                interface Main exposes [main] imports []
+
+                main = [
+                "#
+        ),
+    )];
+
+    let arena = Bump::new();
+    let mut loaded_module = multiple_modules_help("parse_problem_diagnostics", &arena, modules)
+        .unwrap()
+        .unwrap();
+
+    let home = loaded_module.module_id;
+    let diagnostics = loaded_module.parse_diagnostics.remove(&home).unwrap();
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].title, "UNFINISHED LIST");
+    assert_eq!(
+        diagnostics[0].region.map(|region| region.start),
+        Some(LineColumn { line: 2, column: 7 })
+    );
+    assert!(diagnostics[0]
+        .message
+        .starts_with("I cannot find the end of this list:"));
+}
+
 #[test]
 #[should_panic(expected = "FILE NOT FOUND")]
 fn file_not_found() {
@@ -812,6 +857,74 @@ fn opaque_wrapped_unwrapped_outside_defining_module() {
     );
 }
 
+#[test]
+fn structural_eq_is_not_exposed_to_user_code() {
+    let modules = vec![(
+        "Main",
+        indoc!(
+            r#"
+                interface Main exposes [same, different] imports [Bool.{ structuralNotEq }]
+
+                same = \a, b -> Bool.structuralEq a b
+
+                different = \a, b -> structuralNotEq a b
+                "#
+        ),
+    )];
+
+    let err = multiple_modules("structural_eq_is_not_exposed_to_user_code", modules).unwrap_err();
+    assert_eq!(
+        err,
+        indoc!(
+            r#"
+                ── NOT EXPOSED ──────────── tmp/structural_eq_is_not_exposed_to_user_code/Main ─
+
+                The Bool module does not expose `structuralNotEq`:
+
+                1│  interface Main exposes [same, different] imports [Bool.{ structuralNotEq }]
+                                                                      ^^^^^^^^^^^^^^^^^^^^^^^^
+
+                Did you mean one of these?
+
+                    Bool.isNotEq
+                    Bool.not
+                    Bool.isEq
+                    Bool.and
+
+                ── NOT EXPOSED ──────────── tmp/structural_eq_is_not_exposed_to_user_code/Main ─
+
+                The Bool module does not expose `structuralEq`:
+
+                3│  same = \a, b -> Bool.structuralEq a b
+                                    ^^^^^^^^^^^^^^^^^
+
+                Did you mean one of these?
+
+                    Bool.isNotEq
+                    Bool.isEq
+                    Bool.and
+                    Bool.or
+
+                ── UNRECOGNIZED NAME ────── tmp/structural_eq_is_not_exposed_to_user_code/Main ─
+
+                Nothing is named `structuralNotEq` in this scope.
+
+                5│  different = \a, b -> structuralNotEq a b
+                                         ^^^^^^^^^^^^^^^
+
+                Did you mean one of these?
+
+                    Natural
+                    Decimal
+                    Result
+                    Nat
+                "#
+        ),
+        "\n{}",
+        err
+    );
+}
+
 #[test]
 fn issue_2863_module_type_does_not_exist() {
     let modules = vec![
@@ -872,3 +985,1226 @@ fn issue_2863_module_type_does_not_exist() {
         Ok(_) => unreachable!("we expect failure here"),
     }
 }
+
+#[test]
+fn monomorphized_regions_of_procs_and_calls() {
+    use roc_load_internal::file::LoadResult::*;
+    use roc_mono::ir::{Call, CallType, Expr, Stmt};
+
+    // the (callee, line, column) of every call by name in `stmt` that has a region
+    fn call_positions<'a>(
+        stmt: &Stmt<'a>,
+        line_info: &LineInfo,
+        interns: &'a Interns,
+        calls: &mut Vec<(&'a str, u32, u32)>,
+    ) {
+        match stmt {
+            Stmt::Let(_, expr, _, cont) => {
+                if let Expr::Call(Call {
+                    call_type: CallType::ByName { name, region, .. },
+                    ..
+                }) = expr
+                {
+                    if *region != Region::zero() {
+                        let start = line_info.convert_pos(region.start());
+                        calls.push((name.name().as_str(interns), start.line, start.column));
+                    }
+                }
+
+                call_positions(cont, line_info, interns, calls);
+            }
+            Stmt::Switch {
+                branches,
+                default_branch,
+                ..
+            } => {
+                for (_, _, branch) in branches.iter() {
+                    call_positions(branch, line_info, interns, calls);
+                }
+                call_positions(default_branch.1, line_info, interns, calls);
+            }
+            Stmt::Join {
+                body, remainder, ..
+            } => {
+                call_positions(body, line_info, interns, calls);
+                call_positions(remainder, line_info, interns, calls);
+            }
+            Stmt::Refcounting(_, cont) => call_positions(cont, line_info, interns, calls),
+            _ => {}
+        }
+    }
+
+    let arena = Bump::new();
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main =
+            double = \n -> n * 2
+
+            x = double 21u8
+
+            y =
+                double x
+
+            x + y
+        "#
+    );
+
+    let load_start = LoadStart::from_str(
+        &arena,
+        PathBuf::from("Test.roc"),
+        src,
+        PathBuf::from("fake/test/path"),
+    )
+    .unwrap();
+    let load_config = LoadConfig {
+        target_info: TARGET_INFO,
+        render: RenderTarget::Generic,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Executable,
+        ..Default::default()
+    };
+
+    let module = match roc_load_internal::file::load(
+        &arena,
+        load_start,
+        Default::default(),
+        Default::default(), // these tests will re-compile the builtins
+        load_config,
+    )
+    .unwrap()
+    {
+        Monomorphized(module) => module,
+        TypeChecked(_) => unreachable!(""),
+    };
+
+    let line_info = LineInfo::new(src);
+    let mut procs = Vec::new();
+    let mut calls = Vec::new();
+    for ((symbol, _), proc) in module.procedures.iter() {
+        if symbol.module_id() != module.module_id {
+            continue;
+        }
+
+        let start = line_info.convert_pos(proc.region.start());
+        procs.push((symbol.as_str(&module.interns), start.line, start.column));
+
+        call_positions(&proc.body, &line_info, &module.interns, &mut calls);
+    }
+    procs.sort_unstable();
+    calls.sort_unstable();
+
+    assert_eq!(procs, vec![("double", 3, 19), ("main", 3, 4)]);
+    // binary operators are located at the operator
+    assert_eq!(
+        calls,
+        vec![
+            ("add", 10, 6),
+            ("double", 5, 8),
+            ("double", 8, 8),
+            ("mul", 3, 21)
+        ]
+    );
+}
+
+#[test]
+fn abilities_are_specialized_across_modules() {
+    use LoadResult::*;
+
+    let files = [
+        (
+            "Describe.roc",
+            indoc!(
+                r#"
+                interface Describe
+                    exposes [Describe, describe]
+                    imports []
+
+                Describe has
+                    describe : a -> Str | a has Describe
+                "#
+            ),
+        ),
+        (
+            "UserId.roc",
+            indoc!(
+                r#"
+                interface UserId
+                    exposes [UserId, fromNum]
+                    imports [Describe.{ Describe }]
+
+                UserId := U64 has [Describe { describe: describeUserId }]
+
+                describeUserId = \@UserId n -> "user \(Num.toStr n)"
+
+                fromNum = \n -> @UserId n
+                "#
+            ),
+        ),
+        (
+            "Main.roc",
+            indoc!(
+                r#"
+                interface Main
+                    exposes []
+                    imports [Describe.{ describe }, UserId]
+
+                expect describe (UserId.fromNum 7) == "user 7"
+                "#
+            ),
+        ),
+    ];
+
+    let dir = roc_test_utils::TmpDir::new("tmp/abilities_are_specialized_across_modules");
+    for (name, source) in files {
+        std::fs::write(dir.path().join(name), source).unwrap();
+    }
+
+    let arena = Bump::new();
+    let load_start =
+        LoadStart::from_path(&arena, dir.path().join("Main.roc"), RenderTarget::Generic).unwrap();
+    let load_config = LoadConfig {
+        target_info: TARGET_INFO,
+        render: RenderTarget::Generic,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Test,
+        ..Default::default()
+    };
+
+    let loaded = roc_load_internal::file::load(
+        &arena,
+        load_start,
+        Default::default(),
+        Default::default(), // these tests will re-compile the builtins
+        load_config,
+    );
+
+    let module = match loaded.unwrap() {
+        Monomorphized(module) => module,
+        TypeChecked(_) => unreachable!("test mode always monomorphizes"),
+    };
+
+    assert!(module
+        .type_problems
+        .values()
+        .all(|problems| problems.is_empty()));
+
+    // Main makes its specializations before UserId does, so resolving `describe` there
+    // must not depend on the order in which the modules add their abilities to the world.
+    assert!(module
+        .procedures
+        .keys()
+        .any(|(symbol, _)| symbol.as_str(&module.interns) == "describeUserId"));
+}
+
+/// Write `files` to `dir` and type check the last one, caching solved modules in `cache_dir`.
+fn load_with_cache(
+    dir: &roc_test_utils::TmpDir,
+    files: &[(&str, &str)],
+    cache_dir: Option<PathBuf>,
+) -> LoadedModule {
+    for (name, source) in files {
+        std::fs::write(dir.path().join(name), source).unwrap();
+    }
+
+    let (root, _) = files.last().unwrap();
+
+    load_path_with_cache(dir.path().join(root), cache_dir)
+}
+
+/// Type check the module at `path`, caching solved modules in `cache_dir`.
+fn load_path_with_cache(path: PathBuf, cache_dir: Option<PathBuf>) -> LoadedModule {
+    use LoadResult::*;
+
+    let arena = Bump::new();
+    let load_start = LoadStart::from_path(&arena, path, RenderTarget::Generic).unwrap();
+    let load_config = LoadConfig {
+        target_info: TARGET_INFO,
+        render: RenderTarget::Generic,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Check,
+        cache_dir,
+        ..Default::default()
+    };
+
+    let loaded = roc_load_internal::file::load(
+        &arena,
+        load_start,
+        Default::default(),
+        Default::default(), // these tests will re-compile the builtins
+        load_config,
+    );
+
+    let module = match loaded.unwrap() {
+        Monomorphized(_) => unreachable!(""),
+        TypeChecked(module) => module,
+    };
+
+    module
+}
+
+fn module_names<'a>(loaded_module: &'a LoadedModule, modules: &MutSet<ModuleId>) -> Vec<&'a str> {
+    let mut names: Vec<_> = modules
+        .iter()
+        .map(|module_id| loaded_module.interns.module_name(*module_id).as_str())
+        .collect();
+    names.sort_unstable();
+
+    names
+}
+
+fn cached_module_names(loaded_module: &LoadedModule) -> Vec<&str> {
+    module_names(loaded_module, &loaded_module.cached_modules)
+}
+
+fn cached_declaration_names(loaded_module: &LoadedModule) -> Vec<&str> {
+    module_names(loaded_module, &loaded_module.cached_declarations)
+}
+
+/// The declarations of every non-builtin module, printed so they can be compared across loads.
+fn declarations_by_name(loaded_module: &LoadedModule) -> HashMap<&str, String> {
+    let interns = &loaded_module.interns;
+
+    loaded_module
+        .declarations_by_id
+        .iter()
+        .filter(|(module_id, _)| !module_id.is_builtin())
+        .map(|(module_id, declarations)| {
+            let name = interns.module_name(*module_id).as_str();
+
+            // so every symbol is printed by name, however far canonicalization got registering them
+            module_id.register_debug_idents(interns.all_ident_ids.get(module_id).unwrap());
+
+            (name, format!("{:#?}", declarations))
+        })
+        .collect()
+}
+
+fn type_problem_count(loaded_module: &LoadedModule, module_name: &str) -> usize {
+    let module_id = loaded_module
+        .interns
+        .module_ids
+        .get_id(&module_name.into())
+        .unwrap();
+
+    loaded_module
+        .type_problems
+        .get(&module_id)
+        .map_or(0, |problems| problems.len())
+}
+
+const CACHED_ANSWER: &str = indoc!(
+    r#"
+    interface Answer
+        exposes [Answer, answer]
+        imports []
+
+    Answer : { value : U64 }
+
+    answer : Answer
+    answer = { value: 42 }
+    "#
+);
+
+const CACHED_DOUBLE: &str = indoc!(
+    r#"
+    interface Double
+        exposes [double]
+        imports [Answer]
+
+    double : Answer.Answer -> U64
+    double = \a -> a.value * 2
+    "#
+);
+
+const CACHED_MAIN: &str = indoc!(
+    r#"
+    interface Main
+        exposes [main]
+        imports [Answer, Double]
+
+    main = Double.double Answer.answer
+    "#
+);
+
+#[test]
+fn module_cache_reuses_unchanged_modules() {
+    let dir = roc_test_utils::TmpDir::new("tmp/module_cache_reuses_unchanged_modules");
+    let cache_dir = dir.path().join("cache");
+    let files = [
+        ("Answer.roc", CACHED_ANSWER),
+        ("Double.roc", CACHED_DOUBLE),
+        ("Main.roc", CACHED_MAIN),
+    ];
+
+    let first = load_with_cache(&dir, &files, Some(cache_dir.clone()));
+    assert!(cached_module_names(&first).is_empty());
+    assert!(cached_declaration_names(&first).is_empty());
+    // a solved and a canonical entry per module, but the root module is never cached
+    assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 4);
+
+    let second = load_with_cache(&dir, &files, Some(cache_dir));
+    assert_eq!(cached_module_names(&second), vec!["Answer", "Double"]);
+    assert_eq!(cached_declaration_names(&second), vec!["Answer", "Double"]);
+    expect_types(second, hashmap! { "main" => "U64" });
+}
+
+#[test]
+fn module_cache_reuses_modules_whose_ids_changed() {
+    let dir = roc_test_utils::TmpDir::new("tmp/module_cache_reuses_modules_whose_ids_changed");
+    let cache_dir = dir.path().join("cache");
+    let mut files = [
+        ("Answer.roc", CACHED_ANSWER),
+        ("Double.roc", CACHED_DOUBLE),
+        ("Main.roc", CACHED_MAIN),
+    ];
+
+    let first = load_with_cache(&dir, &files, Some(cache_dir.clone()));
+    let first_ids = first.interns.module_ids.clone();
+
+    // modules get their ids in the order they are imported
+    let swapped_main = CACHED_MAIN.replace("[Answer, Double]", "[Double, Answer]");
+    files[2].1 = &swapped_main;
+
+    let second = load_with_cache(&dir, &files, Some(cache_dir));
+    assert_ne!(
+        first_ids.get_id(&"Answer".into()),
+        second.interns.module_ids.get_id(&"Answer".into())
+    );
+    assert_eq!(cached_module_names(&second), vec!["Answer", "Double"]);
+    assert_eq!(cached_declaration_names(&second), vec!["Answer", "Double"]);
+    expect_types(second, hashmap! { "main" => "U64" });
+}
+
+#[test]
+fn module_cache_keeps_dependents_when_only_a_body_changes() {
+    let dir = roc_test_utils::TmpDir::new("tmp/module_cache_keeps_dependents");
+    let cache_dir = dir.path().join("cache");
+    let mut files = [
+        ("Answer.roc", CACHED_ANSWER),
+        ("Double.roc", CACHED_DOUBLE),
+        ("Main.roc", CACHED_MAIN),
+    ];
+
+    load_with_cache(&dir, &files, Some(cache_dir.clone()));
+
+    let new_answer = CACHED_ANSWER.replace("42", "43");
+    files[0].1 = &new_answer;
+
+    let second = load_with_cache(&dir, &files, Some(cache_dir));
+    assert_eq!(cached_module_names(&second), vec!["Double"]);
+    assert_eq!(cached_declaration_names(&second), vec!["Double"]);
+    expect_types(second, hashmap! { "main" => "U64" });
+}
+
+#[test]
+fn module_cache_invalidates_dependents_when_an_interface_changes() {
+    let dir = roc_test_utils::TmpDir::new("tmp/module_cache_invalidates_dependents");
+    let cache_dir = dir.path().join("cache");
+    let mut files = [
+        ("Answer.roc", CACHED_ANSWER),
+        ("Double.roc", CACHED_DOUBLE),
+        ("Main.roc", CACHED_MAIN),
+    ];
+
+    let first = load_with_cache(&dir, &files, Some(cache_dir.clone()));
+    assert_eq!(type_problem_count(&first, "Double"), 0);
+
+    let new_answer = CACHED_ANSWER
+        .replace("{ value : U64 }", "{ value : Str }")
+        .replace("42", "\"42\"");
+    files[0].1 = &new_answer;
+
+    let second = load_with_cache(&dir, &files, Some(cache_dir));
+    assert!(cached_module_names(&second).is_empty());
+    // Double is canonicalized against the alias it imports, so it must be canonicalized again
+    assert!(cached_declaration_names(&second).is_empty());
+    assert_eq!(type_problem_count(&second, "Answer"), 0);
+    assert_eq!(type_problem_count(&second, "Double"), 1);
+}
+
+#[test]
+fn module_cache_never_stores_modules_with_type_errors() {
+    let dir = roc_test_utils::TmpDir::new("tmp/module_cache_never_stores_type_errors");
+    let cache_dir = dir.path().join("cache");
+    let broken_answer = CACHED_ANSWER.replace("42", "\"42\"");
+    let files = [
+        ("Answer.roc", broken_answer.as_str()),
+        ("Double.roc", CACHED_DOUBLE),
+        ("Main.roc", CACHED_MAIN),
+    ];
+
+    for _ in 0..2 {
+        let loaded = load_with_cache(&dir, &files, Some(cache_dir.clone()));
+
+        // nothing that imports a module with errors can be cached either
+        assert!(cached_module_names(&loaded).is_empty());
+        assert_eq!(type_problem_count(&loaded, "Answer"), 1);
+    }
+}
+
+#[test]
+fn module_cache_ignores_damaged_entries() {
+    let dir = roc_test_utils::TmpDir::new("tmp/module_cache_ignores_damaged_entries");
+    let cache_dir = dir.path().join("cache");
+    let files = [
+        ("Answer.roc", CACHED_ANSWER),
+        ("Double.roc", CACHED_DOUBLE),
+        ("Main.roc", CACHED_MAIN),
+    ];
+
+    load_with_cache(&dir, &files, Some(cache_dir.clone()));
+
+    let mut entries: Vec<_> = std::fs::read_dir(&cache_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    entries.sort();
+
+    // flip the last byte of half of the entries, and cut the others short
+    for (index, entry) in entries.iter().enumerate() {
+        let mut bytes = std::fs::read(entry).unwrap();
+
+        if index % 2 == 0 {
+            *bytes.last_mut().unwrap() ^= 0xff;
+        } else {
+            bytes.pop();
+        }
+
+        std::fs::write(entry, bytes).unwrap();
+    }
+
+    let second = load_with_cache(&dir, &files, Some(cache_dir.clone()));
+    assert!(cached_module_names(&second).is_empty());
+    assert!(cached_declaration_names(&second).is_empty());
+    expect_types(second, hashmap! { "main" => "U64" });
+
+    // checking them again replaced the damaged entries
+    let third = load_with_cache(&dir, &files, Some(cache_dir));
+    assert_eq!(cached_module_names(&third), vec!["Answer", "Double"]);
+    assert_eq!(cached_declaration_names(&third), vec!["Answer", "Double"]);
+}
+
+#[test]
+fn module_cache_restores_canonical_declarations() {
+    let dir = roc_test_utils::TmpDir::new("tmp/module_cache_restores_canonical_declarations");
+    let cache_dir = dir.path().join("cache");
+    // Named is not exposed: a module importing a custom ability together with its
+    // implementations trips a debug assertion when solving, cached or not.
+    let files = [
+        (
+            "Shapes.roc",
+            indoc!(
+                r#"
+                interface Shapes
+                    exposes [
+                        Shape, area, describe, scale, total, first, widths,
+                        Counter, counter, counterEq, bump,
+                    ]
+                    imports []
+
+                Shape : [Circle F64, Rect { width : F64, height : F64 }]
+
+                area : Shape -> F64
+                area = \shape ->
+                    when shape is
+                        Circle r -> 3.14 * r * r
+                        Rect { width, height } if width > 0 -> width * height
+                        Rect _ -> 0
+
+                describe : Shape -> Str
+                describe = \shape ->
+                    size = if area shape > 10 then "big" else "small"
+                    kind =
+                        when shape is
+                            Circle _ -> "circle"
+                            Rect _ -> "rect"
+
+                    shown = dbg kind
+
+                    "\(size) \(shown)"
+
+                scale : List Shape, F64 -> List Shape
+                scale = \shapes, factor ->
+                    List.map shapes \shape ->
+                        when shape is
+                            Circle r -> Circle (r * factor)
+                            Rect rect -> Rect { rect & width: rect.width * factor, height: rect.height * factor }
+
+                total : List Shape -> F64
+                total = \shapes -> List.walk shapes 0 \sum, shape -> sum + area shape
+
+                first : List Shape -> Result Shape [Empty]
+                first = \shapes ->
+                    when shapes is
+                        [shape, ..] -> Ok shape
+                        [] -> Err Empty
+
+                widths : List { width : F64, height : F64 } -> List F64
+                widths = \rects -> List.map rects .width
+
+                Named has
+                    name : a -> Str | a has Named
+
+                Counter := { count : U64, label : Str } has [Eq { isEq: counterEq }, Named { name: counterName }]
+
+                counterEq : Counter, Counter -> Bool
+                counterEq = \@Counter a, @Counter b -> a.count == b.count
+
+                counterName = \@Counter { label } -> label
+
+                counter : Str -> Counter
+                counter = \label -> @Counter { count: 0, label }
+
+                bump : Counter -> Counter
+                bump = \@Counter { count, label } -> @Counter { count: count + 1, label }
+
+                expect area (Circle 1) > 3
+                "#
+            ),
+        ),
+        (
+            "Main.roc",
+            indoc!(
+                r#"
+                interface Main
+                    exposes [main]
+                    imports [Shapes]
+
+                main =
+                    shapes = Shapes.scale [Circle 1, Rect { width: 2, height: 3 }] 2
+                    clicks = Shapes.bump (Shapes.counter "clicks")
+
+                    {
+                        total: Shapes.total shapes,
+                        text: List.map shapes Shapes.describe,
+                        same: clicks == Shapes.counter "other",
+                    }
+                "#
+            ),
+        ),
+    ];
+
+    let first = load_with_cache(&dir, &files, Some(cache_dir.clone()));
+    let fresh = declarations_by_name(&first);
+
+    // Without the solved entry, Shapes is solved again from the declarations in its
+    // canonical entry.
+    for entry in std::fs::read_dir(&cache_dir).unwrap() {
+        let path = entry.unwrap().path();
+
+        if path.extension().is_none() {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    let second = load_with_cache(&dir, &files, Some(cache_dir));
+    assert!(cached_module_names(&second).is_empty());
+    assert_eq!(cached_declaration_names(&second), vec!["Shapes"]);
+    assert_eq!(type_problem_count(&second, "Shapes"), 0);
+    assert_eq!(declarations_by_name(&second)["Shapes"], fresh["Shapes"]);
+    expect_types(
+        second,
+        hashmap! {
+            "main" => "{ same : Bool, text : List Str, total : F64 }",
+        },
+    );
+}
+
+#[test]
+fn module_cache_canonical_entries_match_a_fresh_canonicalization() {
+    let dir = roc_test_utils::TmpDir::new("tmp/module_cache_canonical_entries_match");
+    let cache_dir = dir.path().join("cache");
+    let primary = fixtures_dir()
+        .join("interface_with_deps")
+        .join("Primary.roc");
+
+    let first = load_path_with_cache(primary.clone(), Some(cache_dir.clone()));
+    assert!(cached_declaration_names(&first).is_empty());
+    let fresh = declarations_by_name(&first);
+
+    let second = load_path_with_cache(primary, Some(cache_dir));
+    let cached = cached_declaration_names(&second);
+    assert_eq!(cached, vec!["Dep1", "Dep2", "Dep3.Blah", "Res"]);
+
+    let restored = declarations_by_name(&second);
+
+    for name in cached {
+        assert_eq!(restored[name], fresh[name], "declarations of {}", name);
+    }
+}
+
+#[test]
+fn module_cache_is_unused_without_a_cache_dir() {
+    let dir = roc_test_utils::TmpDir::new("tmp/module_cache_is_unused_without_a_cache_dir");
+    let files = [
+        ("Answer.roc", CACHED_ANSWER),
+        ("Double.roc", CACHED_DOUBLE),
+        ("Main.roc", CACHED_MAIN),
+    ];
+
+    for _ in 0..2 {
+        let loaded = load_with_cache(&dir, &files, None);
+        assert!(cached_module_names(&loaded).is_empty());
+    }
+
+    let entries = std::fs::read_dir(dir.path()).unwrap().count();
+    assert_eq!(entries, files.len());
+}
+
+/// Type check an app on a platform with a hosted `Effect` module, with its effects mocked by `script`.
+fn load_with_mock_effects(dir: &roc_test_utils::TmpDir, script: &str) -> LoadedModule {
+    use roc_can::mock_effects::MockEffects;
+    use LoadResult::*;
+
+    let files = [
+        (
+            "platform/main.roc",
+            indoc!(
+                r#"
+                platform "mocked"
+                    requires {} { main : Effect.Effect {} }
+                    exposes []
+                    packages {}
+                    imports [Effect]
+                    provides [mainForHost]
+
+                mainForHost : Effect.Effect {} as Fx
+                mainForHost = main
+                "#
+            ),
+        ),
+        (
+            "platform/Effect.roc",
+            indoc!(
+                r#"
+                hosted Effect
+                    exposes [Effect, after, always, putLine, getLine]
+                    imports []
+                    generates Effect with [after, always]
+
+                putLine : Str -> Effect {}
+
+                getLine : Effect Str
+                "#
+            ),
+        ),
+        (
+            "main.roc",
+            indoc!(
+                r#"
+                app "mocked"
+                    packages { pf: "platform/main.roc" }
+                    imports [pf.Effect]
+                    provides [main] to pf
+
+                main : Effect.Effect {}
+                main = Effect.after Effect.getLine Effect.putLine
+                "#
+            ),
+        ),
+    ];
+
+    std::fs::create_dir_all(dir.path().join("platform")).unwrap();
+    for (name, source) in files {
+        std::fs::write(dir.path().join(name), source).unwrap();
+    }
+
+    let arena = Bump::new();
+    let load_start =
+        LoadStart::from_path(&arena, dir.path().join("main.roc"), RenderTarget::Generic).unwrap();
+    let load_config = LoadConfig {
+        target_info: TARGET_INFO,
+        render: RenderTarget::Generic,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Check,
+        mock_effects: Some(std::sync::Arc::new(MockEffects::parse(script).unwrap())),
+        ..Default::default()
+    };
+
+    let loaded = roc_load_internal::file::load(
+        &arena,
+        load_start,
+        Default::default(),
+        Default::default(), // these tests will re-compile the builtins
+        load_config,
+    );
+
+    let module = match loaded.unwrap() {
+        Monomorphized(_) => unreachable!(""),
+        TypeChecked(module) => module,
+    };
+
+    module
+}
+
+#[test]
+fn mock_effects_respond_with_the_effect_type() {
+    let dir = roc_test_utils::TmpDir::new("tmp/mock_effects_respond_with_the_effect_type");
+    let loaded = load_with_mock_effects(&dir, "getLine -> \"Alice\"\nputLine\n");
+
+    assert_eq!(type_problem_count(&loaded, "Effect"), 0);
+    assert!(loaded
+        .can_problems
+        .values()
+        .all(|problems| problems.is_empty()));
+}
+
+#[test]
+fn mock_effects_responses_are_type_checked() {
+    let dir = roc_test_utils::TmpDir::new("tmp/mock_effects_responses_are_type_checked");
+    let loaded = load_with_mock_effects(&dir, "getLine -> 42\nputLine -> \"done\"\n");
+
+    assert_eq!(type_problem_count(&loaded, "Effect"), 2);
+}
+
+#[test]
+fn mock_effects_arguments_are_type_checked() {
+    let dir = roc_test_utils::TmpDir::new("tmp/mock_effects_arguments_are_type_checked");
+
+    let loaded = load_with_mock_effects(&dir, "getLine -> \"Alice\"\nputLine \"Hi, Alice\"\n");
+    assert_eq!(type_problem_count(&loaded, "Effect"), 0);
+
+    let loaded = load_with_mock_effects(&dir, "getLine -> \"Alice\"\nputLine 42\n");
+    assert_eq!(type_problem_count(&loaded, "Effect"), 1);
+}
+
+#[test]
+fn coverage_points_follow_the_source() {
+    use roc_can::coverage::CoveragePoint;
+    use LoadResult::*;
+
+    let source = indoc!(
+        r#"
+        interface Main
+            exposes [sign, twice]
+            imports []
+
+        sign = \n ->
+            when n is
+                0 -> 0
+                _ -> if n < 0 then -1 else 1
+
+        twice = \xs ->
+            double = \x -> x * 2
+
+            List.map xs double
+
+        expect sign 3 == 1
+        "#
+    );
+
+    let dir = roc_test_utils::TmpDir::new("tmp/coverage_points_follow_the_source");
+    std::fs::write(dir.path().join("Main.roc"), source).unwrap();
+
+    let arena = Bump::new();
+    let load_start =
+        LoadStart::from_path(&arena, dir.path().join("Main.roc"), RenderTarget::Generic).unwrap();
+    let load_config = LoadConfig {
+        target_info: TARGET_INFO,
+        render: RenderTarget::Generic,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Test,
+        coverage: true,
+        ..Default::default()
+    };
+
+    let loaded = roc_load_internal::file::load(
+        &arena,
+        load_start,
+        Default::default(),
+        Default::default(), // these tests will re-compile the builtins
+        load_config,
+    );
+
+    let module = match loaded.unwrap() {
+        Monomorphized(module) => module,
+        TypeChecked(_) => unreachable!("the module has no problems"),
+    };
+
+    // builtins and other packages are not counted
+    assert_eq!(module.coverage.len(), 1);
+
+    let points = module.coverage.get(&module.module_id).unwrap();
+    let described: Vec<_> = points
+        .iter()
+        .map(|point| {
+            let region = point.region();
+            let code = &source[region.start().offset as usize..region.end().offset as usize];
+
+            match point {
+                CoveragePoint::Function { symbol, .. } => {
+                    format!("{}: {}", symbol.as_str(&module.interns), code)
+                }
+                CoveragePoint::Branch { block, branch, .. } => {
+                    format!("{}.{}: {}", block, branch, code)
+                }
+            }
+        })
+        .collect();
+
+    assert_eq!(
+        described,
+        [
+            "sign: when n is\n        0 -> 0\n        _ -> if n < 0 then -1 else 1",
+            "0.0: 0",
+            "0.1: if n < 0 then -1 else 1",
+            "1.0: -1",
+            "1.1: 1",
+            "twice: double = \\x -> x * 2\n\n    List.map xs double",
+            "double: x * 2",
+        ]
+    );
+}
+
+#[test]
+fn doc_tests_are_expects_named_after_the_documented_def() {
+    use LoadResult::*;
+
+    let source = indoc!(
+        r#"
+        interface Main
+            exposes [double, twice]
+            imports []
+
+        ## Doubling a number:
+        ##
+        ## ```
+        ## double 2 == 4
+        ## ```
+        double : I64 -> I64
+        double = \n -> n * 2
+
+        ## Doubles every number.
+        ##
+        ##     doubled = twice [1, 2]
+        ##
+        ##     expect doubled == [2, 4]
+        ##
+        ##     expect List.len doubled == 2
+        ##
+        ## An example without an assertion is not run:
+        ##
+        ##     twice []
+        twice = \xs -> List.map xs double
+
+        expect double 3 == 6
+        "#
+    );
+
+    let dir =
+        roc_test_utils::TmpDir::new("tmp/doc_tests_are_expects_named_after_the_documented_def");
+    std::fs::write(dir.path().join("Main.roc"), source).unwrap();
+
+    let arena = Bump::new();
+    let load_start =
+        LoadStart::from_path(&arena, dir.path().join("Main.roc"), RenderTarget::Generic).unwrap();
+    let load_config = LoadConfig {
+        target_info: TARGET_INFO,
+        render: RenderTarget::Generic,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Test,
+        doc_tests: true,
+        ..Default::default()
+    };
+
+    let loaded = roc_load_internal::file::load(
+        &arena,
+        load_start,
+        Default::default(),
+        Default::default(), // these tests will re-compile the builtins
+        load_config,
+    );
+
+    let module = match loaded.unwrap() {
+        Monomorphized(module) => module,
+        TypeChecked(_) => unreachable!("the module has no problems"),
+    };
+
+    assert!(module.parse_problems.is_empty());
+
+    let expects = &module.toplevel_expects;
+    let mut described: Vec<_> = expects
+        .pure
+        .iter()
+        .map(|(symbol, region)| {
+            let line = source[..region.start().offset as usize].lines().count() + 1;
+            let documented = expects
+                .enclosing_defs
+                .get(symbol)
+                .map(|def| def.as_str(&module.interns));
+
+            (line, expects.doc_tests.contains(symbol), documented)
+        })
+        .collect();
+
+    described.sort_unstable();
+
+    assert_eq!(
+        described,
+        [
+            // examples start at their code block, in the doc comment
+            (7, true, Some("double")),
+            (15, true, Some("twice")),
+            (15, true, Some("twice")),
+            (26, false, Some("twice")),
+        ]
+    );
+}
+
+#[test]
+fn doc_tests_that_do_not_parse_are_not_parse_problems_of_the_module() {
+    use LoadResult::*;
+
+    let source = indoc!(
+        r#"
+        interface Main
+            exposes [double]
+            imports []
+
+        ## ```
+        ## double 2 == [4,
+        ## ```
+        double : I64 -> I64
+        double = \n -> n * 2
+        "#
+    );
+
+    let dir = roc_test_utils::TmpDir::new(
+        "tmp/doc_tests_that_do_not_parse_are_not_parse_problems_of_the_module",
+    );
+    std::fs::write(dir.path().join("Main.roc"), source).unwrap();
+
+    let arena = Bump::new();
+    let load_start =
+        LoadStart::from_path(&arena, dir.path().join("Main.roc"), RenderTarget::Generic).unwrap();
+    let load_config = LoadConfig {
+        target_info: TARGET_INFO,
+        render: RenderTarget::Generic,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Test,
+        doc_tests: true,
+        ..Default::default()
+    };
+
+    let loaded = roc_load_internal::file::load(
+        &arena,
+        load_start,
+        Default::default(),
+        Default::default(), // these tests will re-compile the builtins
+        load_config,
+    );
+
+    let module = match loaded.unwrap() {
+        Monomorphized(module) => module,
+        TypeChecked(_) => unreachable!("test mode always monomorphizes"),
+    };
+
+    assert!(module.parse_problems.is_empty());
+    assert_eq!(module.doc_test_problems.values().flatten().count(), 1);
+}
+
+#[test]
+fn builtin_doc_tests_are_added_only_when_asked_for() {
+    // solving the builtins again takes more stack than debug builds have by default
+    with_larger_debug_stack(|| {
+        use LoadResult::*;
+
+        let source = indoc!(
+            r#"
+            interface Main
+                exposes [double]
+                imports []
+
+            double = \n -> n * 2
+            "#
+        );
+
+        let dir =
+            roc_test_utils::TmpDir::new("tmp/builtin_doc_tests_are_added_only_when_asked_for");
+        std::fs::write(dir.path().join("Main.roc"), source).unwrap();
+
+        let doc_test_modules = |builtin_doc_tests| {
+            let arena = Bump::new();
+            let load_start =
+                LoadStart::from_path(&arena, dir.path().join("Main.roc"), RenderTarget::Generic)
+                    .unwrap();
+            let load_config = LoadConfig {
+                target_info: TARGET_INFO,
+                render: RenderTarget::Generic,
+                threading: Threading::Single,
+                exec_mode: ExecutionMode::Test,
+                doc_tests: true,
+                builtin_doc_tests,
+                ..Default::default()
+            };
+
+            let loaded = roc_load_internal::file::load(
+                &arena,
+                load_start,
+                Default::default(),
+                Default::default(), // these tests will re-compile the builtins
+                load_config,
+            );
+
+            let module = match loaded.unwrap() {
+                Monomorphized(module) => module,
+                TypeChecked(_) => unreachable!("test mode always monomorphizes"),
+            };
+
+            // the examples in the builtins' docs must keep compiling
+            assert!(module.doc_test_problems.is_empty());
+            assert!(module
+                .type_problems
+                .values()
+                .all(|problems| problems.is_empty()));
+
+            module
+                .toplevel_expects
+                .doc_tests
+                .iter()
+                .map(|symbol| symbol.module_id())
+                .collect::<Vec<_>>()
+        };
+
+        assert!(doc_test_modules(false).is_empty());
+        assert!(doc_test_modules(true).contains(&ModuleId::DICT));
+    })
+}
+
+#[test]
+fn benches_are_functions_named_bench_that_take_a_unit() {
+    use LoadResult::*;
+
+    let source = indoc!(
+        r#"
+        interface Main
+            exposes [double]
+            imports []
+
+        double = \n -> n * 2
+
+        benchDouble = \{} -> double 21
+
+        benchSort : {} -> List I64
+        benchSort = \{} -> List.sortAsc [3, 1, 2]
+
+        benchmark = \{} -> double 1
+
+        benchTakesAnArgument = \n -> double n
+
+        expect double 2 == 4
+        "#
+    );
+
+    let dir = roc_test_utils::TmpDir::new("tmp/benches_are_functions_named_bench_that_take_a_unit");
+    std::fs::write(dir.path().join("Main.roc"), source).unwrap();
+
+    let arena = Bump::new();
+    let load_start =
+        LoadStart::from_path(&arena, dir.path().join("Main.roc"), RenderTarget::Generic).unwrap();
+    let load_config = LoadConfig {
+        target_info: TARGET_INFO,
+        render: RenderTarget::Generic,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Test,
+        benches: true,
+        ..Default::default()
+    };
+
+    let loaded = roc_load_internal::file::load(
+        &arena,
+        load_start,
+        Default::default(),
+        Default::default(), // these tests will re-compile the builtins
+        load_config,
+    );
+
+    let module = match loaded.unwrap() {
+        Monomorphized(module) => module,
+        TypeChecked(_) => unreachable!("the module has no problems"),
+    };
+
+    let mut benches: Vec<_> = module
+        .toplevel_benches
+        .thunks
+        .iter()
+        .map(|(thunk, bench)| {
+            // the thunk that runs each benchmark is generated like an expect
+            assert!(module
+                .procedures
+                .keys()
+                .any(|(symbol, layout)| symbol == thunk && layout.arguments.is_empty()));
+
+            let line = source[..bench.region.start().offset as usize]
+                .lines()
+                .count()
+                + 1;
+
+            (line, bench.value.as_str(&module.interns))
+        })
+        .collect();
+
+    benches.sort_unstable();
+
+    assert_eq!(benches, [(7, "benchDouble"), (10, "benchSort")]);
+
+    // the expects still run as usual
+    assert_eq!(module.toplevel_expects.pure.len(), 1);
+}
+
+#[test]
+fn benches_of_a_module_without_expects() {
+    use LoadResult::*;
+
+    let source = indoc!(
+        r#"
+        interface Main
+            exposes [double]
+            imports []
+
+        double = \n -> n * 2
+
+        benchDouble = \{} -> double 21
+        "#
+    );
+
+    let dir = roc_test_utils::TmpDir::new("tmp/benches_of_a_module_without_expects");
+    std::fs::write(dir.path().join("Main.roc"), source).unwrap();
+
+    let arena = Bump::new();
+    let load_start =
+        LoadStart::from_path(&arena, dir.path().join("Main.roc"), RenderTarget::Generic).unwrap();
+    let load_config = LoadConfig {
+        target_info: TARGET_INFO,
+        render: RenderTarget::Generic,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Test,
+        benches: true,
+        ..Default::default()
+    };
+
+    let loaded = roc_load_internal::file::load(
+        &arena,
+        load_start,
+        Default::default(),
+        Default::default(), // these tests will re-compile the builtins
+        load_config,
+    );
+
+    let module = match loaded.unwrap() {
+        Monomorphized(module) => module,
+        TypeChecked(_) => unreachable!("the module has no problems"),
+    };
+
+    let benches: Vec<_> = module
+        .toplevel_benches
+        .thunks
+        .values()
+        .map(|bench| bench.value.as_str(&module.interns))
+        .collect();
+
+    assert_eq!(benches, ["benchDouble"]);
+    assert!(module.toplevel_expects.pure.is_empty());
+}
@@ -86,6 +86,7 @@ impl ModuleName {
     pub const ENCODE: &'static str = "Encode";
     pub const DECODE: &'static str = "Decode";
     pub const JSON: &'static str = "Json";
+    pub const HASH: &'static str = "Hash";
 
     pub fn as_str(&self) -> &str {
         self.0.as_str()
@@ -193,6 +194,34 @@ impl Lowercase {
     }
 }
 
+/// What an accessor like `.foo` or `.0` looks up: a record field, or a tuple element.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum IndexOrField {
+    Field(Lowercase),
+    Index(usize),
+}
+
+impl<'a> From<&'a str> for IndexOrField {
+    fn from(string: &'a str) -> Self {
+        match string.bytes().next() {
+            Some(b'0'..=b'9') => match string.parse() {
+                Ok(index) => IndexOrField::Index(index),
+                Err(_) => IndexOrField::Field(string.into()),
+            },
+            _ => IndexOrField::Field(string.into()),
+        }
+    }
+}
+
+impl fmt::Display for IndexOrField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IndexOrField::Field(field) => field.fmt(f),
+            IndexOrField::Index(index) => index.fmt(f),
+        }
+    }
+}
+
 impl From<Lowercase> for String {
     fn from(lowercase: Lowercase) -> Self {
         lowercase.0.into()
@@ -88,6 +88,9 @@ pub enum LowLevel {
     NumAsin,
     NumBytesToU16,
     NumBytesToU32,
+    NumF32ToBits,
+    NumF64ToBits,
+    NumDecToBits,
     NumBitwiseAnd,
     NumBitwiseXor,
     NumBitwiseOr,
@@ -301,6 +304,9 @@ map_symbol_to_lowlevel! {
     NumAsin <= NUM_ASIN,
     NumBytesToU16 <= NUM_BYTES_TO_U16_LOWLEVEL,
     NumBytesToU32 <= NUM_BYTES_TO_U32_LOWLEVEL,
+    NumF32ToBits <= NUM_F32_TO_BITS,
+    NumF64ToBits <= NUM_F64_TO_BITS,
+    NumDecToBits <= NUM_DEC_TO_BITS,
     NumBitwiseAnd <= NUM_BITWISE_AND,
     NumBitwiseXor <= NUM_BITWISE_XOR,
     NumBitwiseOr <= NUM_BITWISE_OR,
@@ -308,8 +314,8 @@ map_symbol_to_lowlevel! {
     NumShiftRightBy <= NUM_SHIFT_RIGHT,
     NumShiftRightZfBy <= NUM_SHIFT_RIGHT_ZERO_FILL,
     NumToStr <= NUM_TO_STR,
-    Eq <= BOOL_EQ,
-    NotEq <= BOOL_NEQ,
+    Eq <= BOOL_STRUCTURAL_EQ,
+    NotEq <= BOOL_STRUCTURAL_NOT_EQ,
     And <= BOOL_AND,
     Or <= BOOL_OR,
     Not <= BOOL_NOT,
@@ -50,6 +50,8 @@ const PRETTY_PRINT_DEBUG_SYMBOLS: bool = true;
 pub const DERIVABLE_ABILITIES: &[(Symbol, &[Symbol])] = &[
     (Symbol::ENCODE_ENCODING, &[Symbol::ENCODE_TO_ENCODER]),
     (Symbol::DECODE_DECODING, &[Symbol::DECODE_DECODER]),
+    (Symbol::HASH_HASH_ABILITY, &[Symbol::HASH_HASH]),
+    (Symbol::BOOL_EQ, &[Symbol::BOOL_IS_EQ]),
 ];
 
 /// In Debug builds only, Symbol has a name() method that lets
@@ -89,6 +91,18 @@ impl Symbol {
         self.module_id().is_builtin()
     }
 
+    /// Builtins that the compiler refers to, but that their module doesn't expose to user code
+    pub const fn is_exposed_for_builtin_derivers(self) -> bool {
+        matches!(
+            self,
+            Symbol::BOOL_STRUCTURAL_EQ
+                | Symbol::BOOL_STRUCTURAL_NOT_EQ
+                | Symbol::NUM_F32_TO_BITS
+                | Symbol::NUM_F64_TO_BITS
+                | Symbol::NUM_DEC_TO_BITS
+        )
+    }
+
     pub fn is_derivable_ability(self) -> bool {
         self.derivable_ability().is_some()
     }
@@ -360,6 +374,17 @@ impl ModuleId {
         (self.0.get() - 1) as usize
     }
 
+    /// The id as a number, e.g. to store it on disk. It only means something to the same
+    /// [ModuleIds] that handed it out.
+    pub const fn to_u32(self) -> u32 {
+        self.0.get()
+    }
+
+    /// The inverse of [ModuleId::to_u32], or `None` for 0, which is never handed out.
+    pub fn from_u32(id: u32) -> Option<Self> {
+        NonZeroU32::new(id).map(ModuleId)
+    }
+
     #[cfg(any(debug_assertions, feature = "debug-symbols"))]
     pub fn register_debug_idents(self, ident_ids: &IdentIds) {
         let mut all = DEBUG_IDENT_IDS_BY_MODULE_ID.lock().expect("Failed to acquire lock for Debug interning into DEBUG_MODULE_ID_NAMES, presumably because a thread panicked.");
@@ -1178,6 +1203,9 @@ define_builtins! {
         143 NUM_MUL_CHECKED_LOWLEVEL: "mulCheckedLowlevel"
         144 NUM_BYTES_TO_U16_LOWLEVEL: "bytesToU16Lowlevel"
         145 NUM_BYTES_TO_U32_LOWLEVEL: "bytesToU32Lowlevel"
+        146 NUM_F32_TO_BITS: "f32ToBits"
+        147 NUM_F64_TO_BITS: "f64ToBits"
+        148 NUM_DEC_TO_BITS: "decToBits"
     }
     4 BOOL: "Bool" => {
         0 BOOL_BOOL: "Bool" // the Bool.Bool type alias
@@ -1189,8 +1217,11 @@ define_builtins! {
         4 BOOL_OR: "or"
         5 BOOL_NOT: "not"
         6 BOOL_XOR: "xor"
-        7 BOOL_EQ: "isEq"
-        8 BOOL_NEQ: "isNotEq"
+        7 BOOL_STRUCTURAL_EQ: "structuralEq"
+        8 BOOL_STRUCTURAL_NOT_EQ: "structuralNotEq"
+        9 BOOL_EQ: "Eq"
+        10 BOOL_IS_EQ: "isEq"
+        11 BOOL_IS_NOT_EQ: "isNotEq"
     }
     5 STR: "Str" => {
         0 STR_STR: "Str" imported // the Str.Str type alias
@@ -1323,6 +1354,7 @@ define_builtins! {
         72 LIST_SUBLIST_LOWLEVEL: "sublistLowlevel"
         73 LIST_CAPACITY: "capacity"
         74 LIST_MAP_TRY: "mapTry"
+        75 LIST_IS_EQ_LIST: "isEqList"
     }
     7 RESULT: "Result" => {
         0 RESULT_RESULT: "Result" // the Result.Result type alias
@@ -1406,10 +1438,11 @@ define_builtins! {
         19 ENCODE_LIST: "list"
         20 ENCODE_RECORD: "record"
         21 ENCODE_TAG: "tag"
-        22 ENCODE_CUSTOM: "custom"
-        23 ENCODE_APPEND_WITH: "appendWith"
-        24 ENCODE_APPEND: "append"
-        25 ENCODE_TO_BYTES: "toBytes"
+        22 ENCODE_TUPLE: "tuple"
+        23 ENCODE_CUSTOM: "custom"
+        24 ENCODE_APPEND_WITH: "appendWith"
+        25 ENCODE_APPEND: "append"
+        26 ENCODE_TO_BYTES: "toBytes"
     }
     12 DECODE: "Decode" => {
         0 DECODE_DECODE_ERROR: "DecodeError"
@@ -1435,14 +1468,39 @@ define_builtins! {
         20 DECODE_STRING: "string"
         21 DECODE_LIST: "list"
         22 DECODE_RECORD: "record"
-        23 DECODE_CUSTOM: "custom"
-        24 DECODE_DECODE_WITH: "decodeWith"
-        25 DECODE_FROM_BYTES_PARTIAL: "fromBytesPartial"
-        26 DECODE_FROM_BYTES: "fromBytes"
+        23 DECODE_TAG: "tag"
+        24 DECODE_TUPLE: "tuple"
+        25 DECODE_CUSTOM: "custom"
+        26 DECODE_DECODE_WITH: "decodeWith"
+        27 DECODE_FROM_BYTES_PARTIAL: "fromBytesPartial"
+        28 DECODE_FROM_BYTES: "fromBytes"
     }
     13 JSON: "Json" => {
         0 JSON_JSON: "Json"
     }
-
-    num_modules: 14 // Keep this count up to date by hand! (TODO: see the mut_map! macro for how we could determine this count correctly in the macro)
+    14 HASH: "Hash" => {
+        0 HASH_HASH_ABILITY: "Hash"
+        1 HASH_HASH: "hash"
+        2 HASH_HASHER: "Hasher"
+        3 HASH_ADD_BYTES: "addBytes"
+        4 HASH_ADD_U8: "addU8"
+        5 HASH_ADD_U16: "addU16"
+        6 HASH_ADD_U32: "addU32"
+        7 HASH_ADD_U64: "addU64"
+        8 HASH_ADD_U128: "addU128"
+        9 HASH_ADD_I8: "addI8"
+        10 HASH_ADD_I16: "addI16"
+        11 HASH_ADD_I32: "addI32"
+        12 HASH_ADD_I64: "addI64"
+        13 HASH_ADD_I128: "addI128"
+        14 HASH_COMPLETE: "complete"
+        15 HASH_HASH_NAT: "hashNat"
+        16 HASH_HASH_STR_BYTES: "hashStrBytes"
+        17 HASH_HASH_LIST: "hashList"
+        18 HASH_HASH_F32: "hashF32"
+        19 HASH_HASH_F64: "hashF64"
+        20 HASH_HASH_DEC: "hashDec"
+    }
+
+    num_modules: 15 // Keep this count up to date by hand! (TODO: see the mut_map! macro for how we could determine this count correctly in the macro)
 }
@@ -2,6 +2,7 @@ use bumpalo::collections::vec::Vec;
 use bumpalo::Bump;
 use roc_module::low_level::LowLevel;
 use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+use roc_region::all::Region;
 use roc_target::TargetInfo;
 
 use crate::ir::{
@@ -181,6 +182,7 @@ impl<'a> CodeGenHelp<'a> {
                 ret_layout,
                 arg_layouts,
                 specialization_id: CallSpecId::BACKEND_DUMMY,
+                region: Region::zero(),
             },
             arguments,
         });
@@ -273,6 +275,7 @@ impl<'a> CodeGenHelp<'a> {
                     ret_layout,
                     arg_layouts,
                     specialization_id: CallSpecId::BACKEND_DUMMY,
+                    region: Region::zero(),
                 },
                 arguments,
             }))
@@ -358,6 +361,7 @@ impl<'a> CodeGenHelp<'a> {
             is_self_recursive: SelfRecursive::NotSelfRecursive,
             must_own_arguments: false,
             host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+            region: Region::zero(),
         });
 
         proc_symbol
@@ -1,18 +1,22 @@
 use crate::ir::{
-    BranchInfo, DestructType, Env, Expr, JoinPointId, Literal, Param, Pattern, Procs, Stmt,
+    build_list_index_probe, substitute_in_exprs, with_hole, BranchInfo, Call, CallType,
+    DestructType, Env, Expr, JoinPointId, ListIndex, Literal, Param, Pattern, Procs, Stmt,
 };
 use crate::layout::{Builtin, Layout, LayoutCache, TagIdIntType, UnionLayout};
 use roc_builtins::bitcode::{FloatWidth, IntWidth};
 use roc_collections::all::{MutMap, MutSet};
-use roc_exhaustive::{Ctor, CtorName, RenderAs, TagId, Union};
+use roc_error_macros::internal_error;
+use roc_exhaustive::{Ctor, CtorName, ListArity, RenderAs, TagId, Union};
 use roc_module::ident::TagName;
 use roc_module::low_level::LowLevel;
 use roc_module::symbol::Symbol;
+use roc_types::subs::Variable;
 
 /// COMPILE CASES
 
 type Label = u64;
 const RECORD_TAG_NAME: &str = "#Record";
+const TUPLE_TAG_NAME: &str = "#Tuple";
 
 /// Users of this module will mainly interact with this function. It takes
 /// some normal branches and gives out a decision tree that has "labels" at all
@@ -37,12 +41,64 @@ pub enum Guard<'a> {
     Guard {
         /// pattern
         pattern: Pattern<'a>,
-        /// after assigning to symbol, the stmt jumps to this label
-        id: JoinPointId,
-        stmt: Stmt<'a>,
+        /// how to compile the guard statement
+        stmt_spec: GuardStmtSpec,
     },
 }
 
+/// A guard expression, compiled to a statement where the guard is reached in the decision tree.
+///
+/// The same guard can be reached along several paths of a decision tree (e.g. a list pattern
+/// that matches lists of several lengths), so every occurrence gets its own statement and
+/// join point.
+#[derive(Clone, Debug)]
+pub struct GuardStmtSpec {
+    guard_expr: roc_can::expr::Expr,
+    identity: Symbol,
+}
+
+impl PartialEq for GuardStmtSpec {
+    fn eq(&self, other: &Self) -> bool {
+        self.identity == other.identity
+    }
+}
+
+impl GuardStmtSpec {
+    pub fn new(guard_expr: roc_can::expr::Expr, identity: Symbol) -> Self {
+        Self {
+            guard_expr,
+            identity,
+        }
+    }
+
+    /// Generates the guard statement, which jumps to the returned join point with the value of
+    /// the guard.
+    fn generate_guard_and_join<'a>(
+        &self,
+        env: &mut Env<'a, '_>,
+        procs: &mut Procs<'a>,
+        layout_cache: &mut LayoutCache<'a>,
+    ) -> (JoinPointId, Stmt<'a>) {
+        let join_point_id = JoinPointId(env.unique_symbol());
+        let symbol = env.unique_symbol();
+        let jump = env
+            .arena
+            .alloc(Stmt::Jump(join_point_id, env.arena.alloc([symbol])));
+
+        let stmt = with_hole(
+            env,
+            self.guard_expr.clone(),
+            Variable::BOOL,
+            procs,
+            layout_cache,
+            symbol,
+            jump,
+        );
+
+        (join_point_id, stmt)
+    }
+}
+
 impl<'a> Guard<'a> {
     fn is_none(&self) -> bool {
         self == &Guard::NoGuard
@@ -65,10 +121,8 @@ enum GuardedTest<'a> {
     GuardedNoTest {
         /// pattern
         pattern: Pattern<'a>,
-        /// after assigning to symbol, the stmt jumps to this label
-        id: JoinPointId,
-        /// body
-        stmt: Stmt<'a>,
+        /// how to compile the guard body
+        stmt_spec: GuardStmtSpec,
     },
     TestNotGuarded {
         test: Test<'a>,
@@ -94,6 +148,32 @@ enum Test<'a> {
         tag_id: TagIdIntType,
         num_alts: usize,
     },
+    IsListLen {
+        bound: ListLenBound,
+        len: u64,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum ListLenBound {
+    Exact,
+    AtLeast,
+}
+
+impl ListLenBound {
+    fn from_arity(arity: ListArity) -> (Self, u64) {
+        match arity {
+            ListArity::Exact(n) => (ListLenBound::Exact, n as u64),
+            ListArity::Slice(..) => (ListLenBound::AtLeast, arity.min_len() as u64),
+        }
+    }
+
+    fn to_arity(self, len: u64) -> ListArity {
+        match self {
+            ListLenBound::Exact => ListArity::Exact(len as usize),
+            ListLenBound::AtLeast => ListArity::Slice(len as usize, 0),
+        }
+    }
 }
 
 impl<'a> Test<'a> {
@@ -109,6 +189,7 @@ impl<'a> Test<'a> {
             Test::IsStr(_) => false,
             Test::IsBit(_) => true,
             Test::IsByte { .. } => true,
+            Test::IsListLen { .. } => false,
         }
     }
 }
@@ -152,6 +233,11 @@ impl<'a> Hash for Test<'a> {
                 state.write_u8(6);
                 v.hash(state);
             }
+            IsListLen { bound, len } => {
+                state.write_u8(7);
+                bound.hash(state);
+                len.hash(state);
+            }
         }
     }
 }
@@ -159,9 +245,9 @@ impl<'a> Hash for Test<'a> {
 impl<'a> Hash for GuardedTest<'a> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         match self {
-            GuardedTest::GuardedNoTest { id, .. } => {
+            GuardedTest::GuardedNoTest { stmt_spec, .. } => {
                 state.write_u8(1);
-                id.hash(state);
+                stmt_spec.identity.hash(state);
             }
             GuardedTest::TestNotGuarded { test } => {
                 state.write_u8(0);
@@ -200,8 +286,8 @@ fn to_decision_tree(raw_branches: Vec<Branch>) -> DecisionTree {
             match first.guard {
                 Guard::NoGuard => unreachable!(),
 
-                Guard::Guard { id, stmt, pattern } => {
-                    let guarded_test = GuardedTest::GuardedNoTest { id, stmt, pattern };
+                Guard::Guard { pattern, stmt_spec } => {
+                    let guarded_test = GuardedTest::GuardedNoTest { pattern, stmt_spec };
 
                     // the guard test does not have a path
                     let path = vec![];
@@ -330,6 +416,7 @@ fn tests_are_complete_help(last_test: &Test, number_of_tests: usize) -> bool {
         Test::IsFloat(_, _) => false,
         Test::IsDecimal(_) => false,
         Test::IsStr(_) => false,
+        Test::IsListLen { .. } => false,
     }
 }
 
@@ -462,8 +549,24 @@ fn tests_at_path<'a>(
 
     let mut all_tests = Vec::new();
 
+    // lists are tested by their length, and which lengths we test for depends on all the list
+    // patterns at this path
+    let list_arities: std::vec::Vec<_> = branches
+        .iter()
+        .filter_map(|branch| {
+            match branch
+                .patterns
+                .iter()
+                .find(|(path, _)| path == selected_path)
+            {
+                Some((_, Pattern::List { arity, .. })) => Some(*arity),
+                _ => None,
+            }
+        })
+        .collect();
+
     for branch in branches {
-        all_tests.extend(test_at_path(selected_path, branch));
+        test_at_path(selected_path, branch, &list_arities, &mut all_tests);
     }
 
     // The rust HashMap also uses equality, here we really want to use the custom hash function
@@ -494,7 +597,9 @@ fn tests_at_path<'a>(
 fn test_at_path<'a>(
     selected_path: &[PathInstruction],
     branch: &Branch<'a>,
-) -> Option<GuardedTest<'a>> {
+    list_arities: &[ListArity],
+    all_tests: &mut Vec<GuardedTest<'a>>,
+) {
     use Pattern::*;
     use Test::*;
 
@@ -503,17 +608,17 @@ fn test_at_path<'a>(
         .iter()
         .find(|(path, _)| path == selected_path)
     {
-        None => None,
+        None => {}
         Some((_, pattern)) => {
             let test = match pattern {
                 Identifier(_) | Underscore => {
                     if let Guard::Guard { .. } = &branch.guard {
                         // no tests for this pattern remain, but we cannot discard it yet
                         // because it has a guard!
-                        return Some(GuardedTest::Placeholder);
-                    } else {
-                        return None;
+                        all_tests.push(GuardedTest::Placeholder);
                     }
+
+                    return;
                 }
 
                 RecordDestructure(destructs, _) => {
@@ -548,6 +653,31 @@ fn test_at_path<'a>(
                     }
                 }
 
+                TupleDestructure(destructs, _) => {
+                    // not rendered, so pick the easiest
+                    let union = Union {
+                        render_as: RenderAs::Tag,
+                        alternatives: vec![Ctor {
+                            tag_id: TagId(0),
+                            name: CtorName::Tag(TagName(TUPLE_TAG_NAME.into())),
+                            arity: destructs.len(),
+                        }],
+                    };
+
+                    let mut arguments = std::vec::Vec::new();
+
+                    for destruct in destructs {
+                        arguments.push((destruct.pat.clone(), destruct.layout));
+                    }
+
+                    IsCtor {
+                        tag_id: 0,
+                        ctor_name: CtorName::Tag(TagName(TUPLE_TAG_NAME.into())),
+                        union,
+                        arguments,
+                    }
+                }
+
                 NewtypeDestructure {
                     tag_name,
                     arguments,
@@ -604,11 +734,28 @@ fn test_at_path<'a>(
                 FloatLiteral(v, precision) => IsFloat(*v, *precision),
                 DecimalLiteral(v) => IsDecimal(*v),
                 StrLiteral(v) => IsStr(v.clone()),
+
+                List { arity, .. } => {
+                    // test for every length constructor this pattern matches
+                    let ctors =
+                        roc_exhaustive::split_list_arity(ListArity::Slice(0, 0), list_arities);
+
+                    for ctor in ctors {
+                        if arity.covers_arities_of(&ctor) {
+                            let (bound, len) = ListLenBound::from_arity(ctor);
+                            let test = IsListLen { bound, len };
+
+                            all_tests.push(GuardedTest::TestNotGuarded { test });
+                        }
+                    }
+
+                    return;
+                }
             };
 
             let guarded_test = GuardedTest::TestNotGuarded { test };
 
-            Some(guarded_test)
+            all_tests.push(guarded_test);
         }
     }
 }
@@ -727,6 +874,40 @@ fn to_relevant_branch_help<'a>(
             _ => None,
         },
 
+        TupleDestructure(destructs, _) => match test {
+            IsCtor {
+                ctor_name: test_name,
+                tag_id,
+                ..
+            } => {
+                debug_assert!(test_name == &CtorName::Tag(TagName(TUPLE_TAG_NAME.into())));
+                let destructs_len = destructs.len();
+                let sub_positions = destructs.into_iter().enumerate().map(|(index, destruct)| {
+                    let mut new_path = path.to_vec();
+                    let next_instr = if destructs_len == 1 {
+                        PathInstruction::NewType
+                    } else {
+                        PathInstruction::TagIndex {
+                            index: index as u64,
+                            tag_id: *tag_id,
+                        }
+                    };
+                    new_path.push(next_instr);
+
+                    (new_path, destruct.pat)
+                });
+                start.extend(sub_positions);
+                start.extend(end);
+
+                Some(Branch {
+                    goal: branch.goal,
+                    guard: branch.guard.clone(),
+                    patterns: start,
+                })
+            }
+            _ => None,
+        },
+
         OpaqueUnwrap { opaque, argument } => match test {
             IsCtor {
                 ctor_name: test_opaque_tag_name,
@@ -951,6 +1132,35 @@ fn to_relevant_branch_help<'a>(
 
             _ => None,
         },
+
+        List {
+            arity: my_arity,
+            elements,
+            ..
+        } => match test {
+            IsListLen { bound, len } if my_arity.covers_arities_of(&bound.to_arity(*len)) => {
+                let sub_positions = elements.into_iter().enumerate().map(|(index, elem_pat)| {
+                    let mut new_path = path.to_vec();
+
+                    let next_instr = PathInstruction::ListIndex {
+                        index: ListIndex::from_pattern_index(index, my_arity),
+                    };
+                    new_path.push(next_instr);
+
+                    (new_path, elem_pat)
+                });
+                start.extend(sub_positions);
+                start.extend(end);
+
+                Some(Branch {
+                    goal: branch.goal,
+                    guard: branch.guard.clone(),
+                    patterns: start,
+                })
+            }
+
+            _ => None,
+        },
     }
 }
 
@@ -1010,6 +1220,7 @@ fn needs_tests(pattern: &Pattern) -> bool {
 
         NewtypeDestructure { .. }
         | RecordDestructure(..)
+        | TupleDestructure(..)
         | AppliedTag { .. }
         | OpaqueUnwrap { .. }
         | BitLiteral { .. }
@@ -1017,7 +1228,8 @@ fn needs_tests(pattern: &Pattern) -> bool {
         | IntLiteral(_, _)
         | FloatLiteral(_, _)
         | DecimalLiteral(_)
-        | StrLiteral(_) => true,
+        | StrLiteral(_)
+        | List { .. } => true,
     }
 }
 
@@ -1154,10 +1366,9 @@ fn small_branching_factor(branches: &[Branch], path: &[PathInstruction]) -> usiz
 enum Decider<'a, T> {
     Leaf(T),
     Guarded {
-        /// after assigning to symbol, the stmt jumps to this label
-        id: JoinPointId,
-        stmt: Stmt<'a>,
         pattern: Pattern<'a>,
+        /// how to compile the guard body
+        stmt_spec: GuardStmtSpec,
 
         success: Box<Decider<'a, T>>,
         failure: Box<Decider<'a, T>>,
@@ -1225,9 +1436,39 @@ pub fn optimize_when<'a>(
 
         let ((branch_index, choice), opt_jump) = create_choices(&target_counts, index, branch);
 
-        if let Some((index, body)) = opt_jump {
+        if let Some((index, mut body)) = opt_jump {
             let id = JoinPointId(env.unique_symbol());
-            jumps.push((index, id, body));
+
+            // a guarded branch binds its pattern before the guard is evaluated, so when it can
+            // be reached in several ways, those bindings are passed to the branch body
+            let mut bindings = std::vec::Vec::new();
+            if has_guard {
+                pattern_bindings(&pattern, cond_layout, &mut bindings);
+            }
+
+            let mut join_params =
+                bumpalo::collections::Vec::with_capacity_in(bindings.len(), env.arena);
+            let mut jump_pattern_bindings =
+                bumpalo::collections::Vec::with_capacity_in(bindings.len(), env.arena);
+            for (symbol, layout) in bindings {
+                let param_symbol = env.unique_symbol();
+                substitute_in_exprs(env.arena, &mut body, symbol, param_symbol);
+
+                join_params.push(Param {
+                    symbol: param_symbol,
+                    borrow: false,
+                    layout,
+                });
+                jump_pattern_bindings.push(symbol);
+            }
+
+            jumps.push(JumpSpec {
+                target_index: index,
+                id,
+                body,
+                join_params: join_params.into_bump_slice(),
+                jump_pattern_bindings: jump_pattern_bindings.into_bump_slice(),
+            });
         }
 
         choices.insert(branch_index, choice);
@@ -1246,10 +1487,16 @@ pub fn optimize_when<'a>(
         &jumps,
     );
 
-    for (_, id, body) in jumps.into_iter() {
+    for JumpSpec {
+        id,
+        body,
+        join_params,
+        ..
+    } in jumps.into_iter()
+    {
         stmt = Stmt::Join {
             id,
-            parameters: &[],
+            parameters: join_params,
             body: env.arena.alloc(body),
             remainder: env.arena.alloc(stmt),
         };
@@ -1258,10 +1505,94 @@ pub fn optimize_when<'a>(
     stmt
 }
 
+/// A branch body that can be reached in several ways, and is therefore put in a join point.
+struct JumpSpec<'a> {
+    target_index: u64,
+    id: JoinPointId,
+    body: Stmt<'a>,
+    join_params: &'a [Param<'a>],
+    /// The symbols a guarded branch's pattern binds, which are passed to the join point
+    jump_pattern_bindings: &'a [Symbol],
+}
+
+/// Collects the symbols bound by a pattern, along with their layouts.
+fn pattern_bindings<'a>(
+    pattern: &Pattern<'a>,
+    layout: Layout<'a>,
+    bindings: &mut std::vec::Vec<(Symbol, Layout<'a>)>,
+) {
+    use Pattern::*;
+
+    match pattern {
+        Identifier(symbol) => bindings.push((*symbol, layout)),
+        Underscore
+        | IntLiteral(_, _)
+        | FloatLiteral(_, _)
+        | DecimalLiteral(_)
+        | BitLiteral { .. }
+        | EnumLiteral { .. }
+        | StrLiteral(_) => {}
+        RecordDestructure(destructs, _) => {
+            for destruct in destructs {
+                match &destruct.typ {
+                    DestructType::Required(symbol) => bindings.push((*symbol, destruct.layout)),
+                    DestructType::Guard(guard) => {
+                        pattern_bindings(guard, destruct.layout, bindings)
+                    }
+                }
+            }
+        }
+        TupleDestructure(destructs, _) => {
+            for destruct in destructs {
+                pattern_bindings(&destruct.pat, destruct.layout, bindings);
+            }
+        }
+        NewtypeDestructure { arguments, .. } => {
+            for (argument, arg_layout) in arguments {
+                pattern_bindings(argument, *arg_layout, bindings);
+            }
+        }
+        AppliedTag {
+            arguments,
+            layout: union_layout,
+            ..
+        } => {
+            for (argument, arg_layout) in arguments {
+                let arg_layout = match arg_layout {
+                    Layout::RecursivePointer => Layout::Union(*union_layout),
+                    _ => *arg_layout,
+                };
+
+                pattern_bindings(argument, arg_layout, bindings);
+            }
+        }
+        OpaqueUnwrap { argument, .. } => {
+            let (argument, arg_layout) = &**argument;
+            pattern_bindings(argument, *arg_layout, bindings);
+        }
+        List {
+            list_layout,
+            element_layout,
+            elements,
+            opt_rest,
+            ..
+        } => {
+            for element in elements {
+                pattern_bindings(element, *element_layout, bindings);
+            }
+
+            if let Some((_, Some(rest))) = opt_rest {
+                bindings.push((*rest, *list_layout));
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum PathInstruction {
     NewType,
     TagIndex { index: u64, tag_id: TagIdIntType },
+    ListIndex { index: ListIndex },
 }
 
 fn path_to_expr_help<'a>(
@@ -1331,6 +1662,30 @@ fn path_to_expr_help<'a>(
                     }
                 }
             }
+
+            PathInstruction::ListIndex { index } => {
+                let elem_layout = match layout {
+                    Layout::Builtin(Builtin::List(elem_layout)) => *elem_layout,
+                    _ => internal_error!("{:?}", &layout),
+                };
+
+                let (index_sym, new_stores) = build_list_index_probe(env, symbol, index);
+                stores.extend(new_stores);
+
+                let load_sym = env.unique_symbol();
+                let load_expr = Expr::Call(Call {
+                    call_type: CallType::LowLevel {
+                        op: LowLevel::ListGetUnsafe,
+                        update_mode: env.next_update_mode_id(),
+                    },
+                    arguments: env.arena.alloc([symbol, index_sym]),
+                });
+
+                stores.push((load_sym, elem_layout, load_expr));
+
+                symbol = load_sym;
+                layout = elem_layout;
+            }
         }
     }
 
@@ -1440,6 +1795,46 @@ fn test_to_equality<'a>(
 
             (stores, lhs_symbol, rhs_symbol, None)
         }
+
+        Test::IsListLen { bound, len } => {
+            let list_sym = rhs_symbol;
+            let usize_layout = Layout::usize(env.target_info);
+
+            let list_len = env.unique_symbol();
+            let len_expr = Expr::Call(Call {
+                call_type: CallType::LowLevel {
+                    op: LowLevel::ListLen,
+                    update_mode: env.next_update_mode_id(),
+                },
+                arguments: env.arena.alloc([list_sym]),
+            });
+            stores.push((list_len, usize_layout, len_expr));
+
+            let test_len = env.unique_symbol();
+            let test_len_expr = Expr::Literal(Literal::Int((len as i128).to_ne_bytes()));
+            stores.push((test_len, usize_layout, test_len_expr));
+
+            match bound {
+                ListLenBound::Exact => (stores, test_len, list_len, None),
+                ListLenBound::AtLeast => {
+                    let len_gte = env.unique_symbol();
+                    let len_gte_expr = Expr::Call(Call {
+                        call_type: CallType::LowLevel {
+                            op: LowLevel::NumGte,
+                            update_mode: env.next_update_mode_id(),
+                        },
+                        arguments: env.arena.alloc([list_len, test_len]),
+                    });
+                    stores.push((len_gte, Layout::Builtin(Builtin::Bool), len_gte_expr));
+
+                    let true_sym = env.unique_symbol();
+                    let true_expr = Expr::Literal(Literal::Bool(true));
+                    stores.push((true_sym, Layout::Builtin(Builtin::Bool), true_expr));
+
+                    (stores, true_sym, len_gte, None)
+                }
+            }
+        }
     }
 }
 
@@ -1659,7 +2054,7 @@ fn decide_to_branching<'a>(
     cond_layout: Layout<'a>,
     ret_layout: Layout<'a>,
     decider: Decider<'a, Choice<'a>>,
-    jumps: &[(u64, JoinPointId, Stmt<'a>)],
+    jumps: &[JumpSpec<'a>],
 ) -> Stmt<'a> {
     use Choice::*;
     use Decider::*;
@@ -1669,16 +2064,15 @@ fn decide_to_branching<'a>(
     match decider {
         Leaf(Jump(label)) => {
             let index = jumps
-                .binary_search_by_key(&label, |r| r.0)
+                .binary_search_by_key(&label, |r| r.target_index)
                 .expect("jump not in list of jumps");
 
-            Stmt::Jump(jumps[index].1, &[])
+            Stmt::Jump(jumps[index].id, jumps[index].jump_pattern_bindings)
         }
         Leaf(Inline(expr)) => expr,
         Guarded {
-            id,
-            stmt,
             pattern,
+            stmt_spec,
             success,
             failure,
         } => {
@@ -1724,6 +2118,8 @@ fn decide_to_branching<'a>(
                 borrow: false,
             };
 
+            let (id, stmt) = stmt_spec.generate_guard_and_join(env, procs, layout_cache);
+
             let join = Stmt::Join {
                 id,
                 parameters: arena.alloc([param]),
@@ -1850,6 +2246,7 @@ fn decide_to_branching<'a>(
                     Test::IsCtor { tag_id, .. } => tag_id as u64,
                     Test::IsDecimal(_) => unreachable!("decimals cannot be switched on"),
                     Test::IsStr(_) => unreachable!("strings cannot be switched on"),
+                    Test::IsListLen { .. } => unreachable!("list lengths cannot be switched on"),
                 };
 
                 // branch info is only useful for refcounted values
@@ -2069,14 +2466,13 @@ fn chain_decider<'a>(
     success_tree: DecisionTree<'a>,
 ) -> Decider<'a, u64> {
     match guarded_test {
-        GuardedTest::GuardedNoTest { id, stmt, pattern } => {
+        GuardedTest::GuardedNoTest { pattern, stmt_spec } => {
             let failure = Box::new(tree_to_decider(failure_tree));
             let success = Box::new(tree_to_decider(success_tree));
 
             Decider::Guarded {
-                id,
-                stmt,
                 pattern,
+                stmt_spec,
                 success,
                 failure: failure.clone(),
             }
@@ -2201,15 +2597,13 @@ fn insert_choices<'a>(
         }
 
         Guarded {
-            id,
-            stmt,
             pattern,
+            stmt_spec,
             success,
             failure,
         } => Guarded {
-            id,
-            stmt,
             pattern,
+            stmt_spec,
             success: Box::new(insert_choices(choice_dict, *success)),
             failure: Box::new(insert_choices(choice_dict, *failure)),
         },
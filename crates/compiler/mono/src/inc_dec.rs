@@ -130,6 +130,18 @@ pub fn occurring_variables(stmt: &Stmt<'_>) -> (MutSet<Symbol>, MutSet<Symbol>)
                 stack.push(remainder);
             }
 
+            Dbg {
+                symbol, remainder, ..
+            } => {
+                result.insert(*symbol);
+                stack.push(remainder);
+            }
+
+            Crash { msg, msg_bytes, .. } => {
+                result.insert(*msg);
+                result.insert(*msg_bytes);
+            }
+
             Jump(_, arguments) => {
                 result.extend(arguments.iter().copied());
             }
@@ -560,7 +572,11 @@ impl<'a, 'i> Context<'a, 'i> {
                     arguments,
                 });
 
-                &*self.arena.alloc(Stmt::Let(z, v, l, b))
+                let b = self.arena.alloc(Stmt::Let(z, v, l, b));
+
+                // an owned argument that is still used afterwards must be incremented first
+                let pred = |i: usize| ps[i] == OWNED;
+                self.add_inc_before_help(arguments, pred, b, b_live_vars)
             }
 
             HigherOrder(lowlevel) => {
@@ -1096,13 +1112,15 @@ impl<'a, 'i> Context<'a, 'i> {
                 // get the parameters with borrow signature
                 let xs = self.param_map.get_join_point(*j);
 
-                let (v, v_live_vars) = {
+                let v = {
                     let ctx = self.update_var_info_with_params(xs);
-                    ctx.visit_stmt(codegen, v)
+                    let (v, v_live_vars) = ctx.visit_stmt(codegen, v);
+
+                    // the parameters are only known in the context of the body
+                    ctx.add_dec_for_dead_params(xs, v, &v_live_vars)
                 };
 
                 let mut ctx = self.clone();
-                let v = ctx.add_dec_for_dead_params(xs, v, &v_live_vars);
 
                 update_jp_live_vars(*j, xs, v, &mut ctx.jp_live_vars);
 
@@ -1240,6 +1258,36 @@ impl<'a, 'i> Context<'a, 'i> {
                 (expect, b_live_vars)
             }
 
+            Dbg {
+                symbol,
+                region,
+                remainder,
+            } => {
+                let (b, mut b_live_vars) = self.visit_stmt(codegen, remainder);
+
+                // `dbg` only borrows the value it prints
+                let b = self.add_dec_if_needed(*symbol, b, &b_live_vars);
+
+                let dbg = self.arena.alloc(Stmt::Dbg {
+                    symbol: *symbol,
+                    region: *region,
+                    remainder: b,
+                });
+
+                b_live_vars.insert(*symbol);
+
+                (dbg, b_live_vars)
+            }
+
+            Crash { msg, msg_bytes, .. } => {
+                // a crash never returns, so its message is only borrowed and never decremented
+                let mut live_vars = MutSet::default();
+                live_vars.insert(*msg);
+                live_vars.insert(*msg_bytes);
+
+                (stmt, live_vars)
+            }
+
             RuntimeError(_) | Refcounting(_, _) => (stmt, MutSet::default()),
         }
     }
@@ -1365,6 +1413,19 @@ pub fn collect_stmt(
             collect_stmt(remainder, jp_live_vars, vars)
         }
 
+        Dbg {
+            symbol, remainder, ..
+        } => {
+            vars.insert(*symbol);
+            collect_stmt(remainder, jp_live_vars, vars)
+        }
+
+        Crash { msg, msg_bytes, .. } => {
+            vars.insert(*msg);
+            vars.insert(*msg_bytes);
+            vars
+        }
+
         Join {
             id: j,
             parameters,
@@ -21,7 +21,7 @@ use roc_debug_flags::{
 };
 use roc_derive::SharedDerivedModule;
 use roc_error_macros::{internal_error, todo_abilities};
-use roc_exhaustive::{Ctor, CtorName, RenderAs, TagId};
+use roc_exhaustive::{Ctor, CtorName, ListArity, RenderAs, TagId};
 use roc_intern::Interner;
 use roc_late_solve::storage::{ExternalModuleStorage, ExternalModuleStorageSnapshot};
 use roc_late_solve::{resolve_ability_specialization, AbilitiesView, Resolved, UnificationFailed};
@@ -210,6 +210,8 @@ pub struct PartialProc<'a> {
     pub captured_symbols: CapturedSymbols<'a>,
     pub body: roc_can::expr::Expr,
     pub body_var: Variable,
+    /// The region of the body as it was written, before arguments were destructured into it
+    pub body_region: Region,
     pub is_self_recursive: bool,
 }
 
@@ -225,6 +227,7 @@ impl<'a> PartialProc<'a> {
         ret_var: Variable,
     ) -> PartialProc<'a> {
         let number_of_arguments = loc_args.len();
+        let body_region = loc_body.region;
 
         match patterns_to_when(env, loc_args, ret_var, loc_body) {
             Ok((_, pattern_symbols, body)) => {
@@ -239,6 +242,7 @@ impl<'a> PartialProc<'a> {
                     captured_symbols,
                     body: body.value,
                     body_var: ret_var,
+                    body_region,
                     is_self_recursive,
                 }
             }
@@ -256,6 +260,7 @@ impl<'a> PartialProc<'a> {
                     captured_symbols: CapturedSymbols::None,
                     body: roc_can::expr::Expr::RuntimeError(error.value),
                     body_var: ret_var,
+                    body_region,
                     is_self_recursive: false,
                 }
             }
@@ -315,6 +320,9 @@ pub struct Proc<'a> {
     pub is_self_recursive: SelfRecursive,
     pub must_own_arguments: bool,
     pub host_exposed_layouts: HostExposedLayouts<'a>,
+    /// Where the body of this procedure is in the source of its module;
+    /// `Region::zero()` for procedures that the compiler generates
+    pub region: Region,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -1129,6 +1137,8 @@ impl<'a> Procs<'a> {
             _ => false,
         };
 
+        let body_region = loc_body.region;
+
         match patterns_to_when(env, loc_args, ret_var, loc_body) {
             Ok((_, pattern_symbols, body)) => {
                 // an anonymous closure. These will always be specialized already
@@ -1179,6 +1189,7 @@ impl<'a> Procs<'a> {
                                         captured_symbols,
                                         body: body.value,
                                         body_var: ret_var,
+                                        body_region,
                                         is_self_recursive,
                                     };
 
@@ -1215,6 +1226,7 @@ impl<'a> Procs<'a> {
                                     captured_symbols,
                                     body: body.value,
                                     body_var: ret_var,
+                                    body_region,
                                     is_self_recursive,
                                 };
 
@@ -1412,6 +1424,9 @@ pub struct Env<'a, 'i> {
     pub abilities: AbilitiesView<'i>,
     pub exposed_by_module: &'i ExposedByModule,
     pub derived_module: &'i SharedDerivedModule,
+    /// Whether to count how often each proc and branch of this module runs, for
+    /// `roc test --coverage` (see [`count_coverage`])
+    pub count_coverage: bool,
 }
 
 impl<'a, 'i> Env<'a, 'i> {
@@ -1571,6 +1586,13 @@ pub enum Stmt<'a> {
         /// what happens after the expect
         remainder: &'a Stmt<'a>,
     },
+    /// print the value of `symbol`, which was produced by the expression at `region`
+    Dbg {
+        symbol: Symbol,
+        region: Region,
+        /// what happens after the dbg
+        remainder: &'a Stmt<'a>,
+    },
     /// a join point `join f <params> = <continuation> in remainder`
     Join {
         id: JoinPointId,
@@ -1582,9 +1604,39 @@ pub enum Stmt<'a> {
         remainder: &'a Stmt<'a>,
     },
     Jump(JoinPointId, &'a [Symbol]),
+    /// `crash` with the message `msg`, a `Str`. The host's `roc_panic` gets the message as a
+    /// C string, so `msg_bytes` holds the same message as a NUL-terminated `List U8`.
+    /// `region` is the region of the message, which is where `roc test` reports the crash.
+    Crash {
+        msg: Symbol,
+        msg_bytes: Symbol,
+        region: Region,
+    },
     RuntimeError(&'a str),
 }
 
+/// The tag id passed to `roc_panic`, telling the host what kind of crash happened
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum CrashTag {
+    /// An internal runtime error, e.g. reaching code that failed to compile
+    Roc = 0,
+    /// The program called `crash`
+    User = 1,
+}
+
+impl TryFrom<u32> for CrashTag {
+    type Error = ();
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(CrashTag::Roc),
+            1 => Ok(CrashTag::User),
+            _ => Err(()),
+        }
+    }
+}
+
 /// in the block below, symbol `scrutinee` is assumed be be of shape `tag_id`
 #[derive(Clone, Debug, PartialEq)]
 pub enum BranchInfo<'a> {
@@ -1829,6 +1881,9 @@ pub enum CallType<'a> {
         ret_layout: &'a Layout<'a>,
         arg_layouts: &'a [Layout<'a>],
         specialization_id: CallSpecId,
+        /// Where the call is in the source of the calling procedure's module;
+        /// `Region::zero()` for calls that the compiler inserts, like forcing a thunk
+        region: Region,
     },
     Foreign {
         foreign_symbol: ForeignSymbol,
@@ -2170,6 +2225,15 @@ impl<'a> Stmt<'a> {
                 .append(alloc.hardline())
                 .append(remainder.to_doc(alloc, interner)),
 
+            Dbg {
+                symbol, remainder, ..
+            } => alloc
+                .text("dbg ")
+                .append(symbol_to_doc(alloc, *symbol))
+                .append(";")
+                .append(alloc.hardline())
+                .append(remainder.to_doc(alloc, interner)),
+
             Ret(symbol) => alloc
                 .text("ret ")
                 .append(symbol_to_doc(alloc, *symbol))
@@ -2230,6 +2294,11 @@ impl<'a> Stmt<'a> {
                 }
             }
 
+            Crash { msg, .. } => alloc
+                .text("crash ")
+                .append(symbol_to_doc(alloc, *msg))
+                .append(";"),
+
             RuntimeError(s) => alloc.text(format!("Error {}", s)),
 
             Join {
@@ -2737,7 +2806,11 @@ fn pattern_to_when<'a>(
             (env.unique_symbol(), Loc::at_zero(RuntimeError(error)))
         }
 
-        AppliedTag { .. } | RecordDestructure { .. } | UnwrappedOpaque { .. } => {
+        AppliedTag { .. }
+        | RecordDestructure { .. }
+        | TupleDestructure { .. }
+        | UnwrappedOpaque { .. }
+        | roc_can::pattern::Pattern::List { .. } => {
             let symbol = env.unique_symbol();
 
             let wrapped_body = When {
@@ -3108,6 +3181,7 @@ fn generate_runtime_error_function<'a>(
         is_self_recursive: SelfRecursive::NotSelfRecursive,
         must_own_arguments: false,
         host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+        region: Region::zero(),
     }
 }
 
@@ -3215,6 +3289,7 @@ fn specialize_proc_help<'a>(
 
     let body = partial_proc.body.clone();
     let body_var = partial_proc.body_var;
+    let body_region = partial_proc.body_region;
 
     // determine the layout of aliases/rigids exposed to the host
     let host_exposed_layouts = if host_exposed_variables.is_empty() {
@@ -3267,6 +3342,7 @@ fn specialize_proc_help<'a>(
                         argument_symbols.into_bump_slice(),
                         argument_layouts,
                         return_layout,
+                        Region::zero(),
                         assigned,
                         hole,
                     );
@@ -3280,6 +3356,7 @@ fn specialize_proc_help<'a>(
                         is_self_recursive: SelfRecursive::NotSelfRecursive,
                         must_own_arguments: false,
                         host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+                        region: Region::zero(),
                     };
 
                     let top_level = ProcLayout::new(
@@ -3307,6 +3384,7 @@ fn specialize_proc_help<'a>(
                         is_self_recursive: SelfRecursive::NotSelfRecursive,
                         must_own_arguments: false,
                         host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+                        region: Region::zero(),
                     };
 
                     let top_level = ProcLayout::from_raw(
@@ -3336,7 +3414,8 @@ fn specialize_proc_help<'a>(
         }
     };
 
-    let mut specialized_body = from_can(env, body_var, body, procs, layout_cache);
+    let specialized_body = from_can(env, body_var, body, procs, layout_cache);
+    let mut specialized_body = count_coverage(env, body_region, specialized_body);
 
     let specialized_proc = match specialized {
         SpecializedLayout::FunctionPointerBody {
@@ -3368,6 +3447,7 @@ fn specialize_proc_help<'a>(
                 is_self_recursive: recursivity,
                 must_own_arguments: false,
                 host_exposed_layouts,
+                region: body_region,
             }
         }
         SpecializedLayout::FunctionBody {
@@ -3546,6 +3626,7 @@ fn specialize_proc_help<'a>(
                 is_self_recursive: recursivity,
                 must_own_arguments: false,
                 host_exposed_layouts,
+                region: body_region,
             }
         }
     };
@@ -3900,6 +3981,7 @@ fn specialize_naked_symbol<'a>(
             fn_var,
             symbol,
             std::vec::Vec::new(),
+            Region::zero(),
             layout_cache,
             assigned,
             env.arena.alloc(Stmt::Ret(assigned)),
@@ -3917,6 +3999,7 @@ fn specialize_naked_symbol<'a>(
                     variable,
                     symbol,
                     std::vec::Vec::new(),
+                    Region::zero(),
                     layout_cache,
                     assigned,
                     env.arena.alloc(match hole {
@@ -4305,11 +4388,243 @@ pub fn with_hole<'a>(
             stmt
         }
 
+        Tuple {
+            tuple_var, elems, ..
+        } => {
+            let sorted_elems_result = {
+                let mut layout_env = layout::Env::from_components(
+                    layout_cache,
+                    env.subs,
+                    env.arena,
+                    env.target_info,
+                );
+                layout::sort_tuple_elems(&mut layout_env, tuple_var)
+            };
+            let sorted_elems = match sorted_elems_result {
+                Ok(elems) => elems,
+                Err(_) => return Stmt::RuntimeError("Can't create tuple with improper layout"),
+            };
+
+            let mut elem_symbols = Vec::with_capacity_in(elems.len(), env.arena);
+            let mut can_elems = Vec::with_capacity_in(elems.len(), env.arena);
+
+            #[allow(clippy::enum_variant_names)]
+            enum Field {
+                // TODO: rename this since it can handle unspecialized expressions now too
+                FunctionOrUnspecialized(Symbol, Variable),
+                ValueSymbol,
+                Field(Variable, Loc<roc_can::expr::Expr>),
+            }
+
+            let mut elems: std::vec::Vec<_> = elems.into_iter().map(Some).collect();
+
+            for (index, variable, _) in sorted_elems.into_iter() {
+                // TODO how should function pointers be handled here?
+                use ReuseSymbol::*;
+                let (var, loc_expr) = elems[index]
+                    .take()
+                    .expect("tuple element not in its own type");
+
+                match can_reuse_symbol(env, procs, &loc_expr.value, var) {
+                    Imported(symbol) | LocalFunction(symbol) | UnspecializedExpr(symbol) => {
+                        elem_symbols.push(symbol);
+                        can_elems.push(Field::FunctionOrUnspecialized(symbol, variable));
+                    }
+                    Value(symbol) => {
+                        let reusable = procs.symbol_specializations.get_or_insert(
+                            env,
+                            layout_cache,
+                            symbol,
+                            var,
+                        );
+                        elem_symbols.push(reusable);
+                        can_elems.push(Field::ValueSymbol);
+                    }
+                    NotASymbol => {
+                        elem_symbols.push(env.unique_symbol());
+                        can_elems.push(Field::Field(var, *loc_expr));
+                    }
+                }
+            }
+
+            // creating a tuple from the var will unpack it if it's just a single element.
+            let layout = match layout_cache.from_var(env.arena, tuple_var, env.subs) {
+                Ok(layout) => layout,
+                Err(_) => return Stmt::RuntimeError("Can't create tuple with improper layout"),
+            };
+
+            let elem_symbols = elem_symbols.into_bump_slice();
+
+            let mut stmt = if let [only_elem] = elem_symbols {
+                let mut hole = hole.clone();
+                substitute_in_exprs(env.arena, &mut hole, assigned, *only_elem);
+                hole
+            } else {
+                Stmt::Let(assigned, Expr::Struct(elem_symbols), layout, hole)
+            };
+
+            for (opt_elem, symbol) in can_elems.into_iter().rev().zip(elem_symbols.iter().rev()) {
+                match opt_elem {
+                    Field::ValueSymbol => {
+                        // this symbol is already defined; nothing to do
+                    }
+                    Field::FunctionOrUnspecialized(symbol, variable) => {
+                        stmt = specialize_symbol(
+                            env,
+                            procs,
+                            layout_cache,
+                            Some(variable),
+                            symbol,
+                            stmt,
+                            symbol,
+                        );
+                    }
+                    Field::Field(var, loc_expr) => {
+                        stmt = with_hole(
+                            env,
+                            loc_expr.value,
+                            var,
+                            procs,
+                            layout_cache,
+                            *symbol,
+                            env.arena.alloc(stmt),
+                        );
+                    }
+                }
+            }
+
+            stmt
+        }
+
         EmptyRecord => let_empty_struct(assigned, hole),
 
         Expect { .. } => unreachable!("I think this is unreachable"),
         ExpectFx { .. } => unreachable!("I think this is unreachable"),
 
+        Dbg { loc_expr, .. } => {
+            // the value being printed may just be a lookup, so reuse its symbol where possible
+            let dbg_symbol = possible_reuse_symbol_or_specialize(
+                env,
+                procs,
+                layout_cache,
+                &loc_expr.value,
+                variable,
+            );
+
+            let mut rest = hole.clone();
+            substitute_in_exprs(env.arena, &mut rest, assigned, dbg_symbol);
+
+            let dbg = Stmt::Dbg {
+                symbol: dbg_symbol,
+                region: loc_expr.region,
+                remainder: env.arena.alloc(rest),
+            };
+
+            assign_to_symbol(
+                env,
+                procs,
+                layout_cache,
+                variable,
+                *loc_expr,
+                dbg_symbol,
+                dbg,
+            )
+        }
+
+        Crash { msg, ret_var: _ } => {
+            // `roc_panic` takes a C string, so we also make the bytes of the message
+            // with a NUL byte appended. `StrConcat` consumes its first argument, but
+            // the message must stay alive for `roc test` to report it; appending the
+            // message to an empty string instead gives us a copy we are free to consume.
+            let msg_symbol = possible_reuse_symbol_or_specialize(
+                env,
+                procs,
+                layout_cache,
+                &msg.value,
+                Variable::STR,
+            );
+            let empty_symbol = env.unique_symbol();
+            let copy_symbol = env.unique_symbol();
+            let nul_symbol = env.unique_symbol();
+            let terminated_symbol = env.unique_symbol();
+            let bytes_symbol = env.unique_symbol();
+
+            let str_layout = Layout::Builtin(Builtin::Str);
+            let bytes_layout = Layout::Builtin(Builtin::List(env.arena.alloc(Layout::u8())));
+
+            let stmt = Stmt::Crash {
+                msg: msg_symbol,
+                msg_bytes: bytes_symbol,
+                region: msg.region,
+            };
+
+            let to_utf8 = self::Call {
+                call_type: CallType::LowLevel {
+                    op: LowLevel::StrToUtf8,
+                    update_mode: env.next_update_mode_id(),
+                },
+                arguments: env.arena.alloc([terminated_symbol]),
+            };
+            let stmt = Stmt::Let(
+                bytes_symbol,
+                Expr::Call(to_utf8),
+                bytes_layout,
+                env.arena.alloc(stmt),
+            );
+
+            let terminate = self::Call {
+                call_type: CallType::LowLevel {
+                    op: LowLevel::StrConcat,
+                    update_mode: env.next_update_mode_id(),
+                },
+                arguments: env.arena.alloc([copy_symbol, nul_symbol]),
+            };
+            let stmt = Stmt::Let(
+                terminated_symbol,
+                Expr::Call(terminate),
+                str_layout,
+                env.arena.alloc(stmt),
+            );
+
+            let stmt = Stmt::Let(
+                nul_symbol,
+                Expr::Literal(Literal::Str("\0")),
+                str_layout,
+                env.arena.alloc(stmt),
+            );
+
+            let copy = self::Call {
+                call_type: CallType::LowLevel {
+                    op: LowLevel::StrConcat,
+                    update_mode: env.next_update_mode_id(),
+                },
+                arguments: env.arena.alloc([empty_symbol, msg_symbol]),
+            };
+            let stmt = Stmt::Let(
+                copy_symbol,
+                Expr::Call(copy),
+                str_layout,
+                env.arena.alloc(stmt),
+            );
+
+            let stmt = Stmt::Let(
+                empty_symbol,
+                Expr::Literal(Literal::Str("")),
+                str_layout,
+                env.arena.alloc(stmt),
+            );
+
+            assign_to_symbol(
+                env,
+                procs,
+                layout_cache,
+                Variable::STR,
+                *msg,
+                msg_symbol,
+                stmt,
+            )
+        }
+
         If {
             cond_var,
             branch_var,
@@ -4328,6 +4643,7 @@ pub fn with_hole<'a>(
                     if is_terminated {
                         let terminator = hole;
 
+                        let else_region = final_else.region;
                         let mut stmt = with_hole(
                             env,
                             final_else.value,
@@ -4337,6 +4653,7 @@ pub fn with_hole<'a>(
                             assigned,
                             terminator,
                         );
+                        stmt = count_coverage(env, else_region, stmt);
 
                         for (loc_cond, loc_then) in branches.into_iter().rev() {
                             let branching_symbol = env.unique_symbol();
@@ -4350,6 +4667,7 @@ pub fn with_hole<'a>(
                                 assigned,
                                 terminator,
                             );
+                            let then = count_coverage(env, loc_then.region, then);
 
                             stmt = cond(env, branching_symbol, cond_layout, then, stmt, ret_layout);
 
@@ -4373,6 +4691,7 @@ pub fn with_hole<'a>(
                             .arena
                             .alloc(Stmt::Jump(id, env.arena.alloc([assigned_in_jump])));
 
+                        let else_region = final_else.region;
                         let mut stmt = with_hole(
                             env,
                             final_else.value,
@@ -4382,6 +4701,7 @@ pub fn with_hole<'a>(
                             assigned_in_jump,
                             terminator,
                         );
+                        stmt = count_coverage(env, else_region, stmt);
 
                         for (loc_cond, loc_then) in branches.into_iter().rev() {
                             let branching_symbol = possible_reuse_symbol_or_specialize(
@@ -4401,6 +4721,7 @@ pub fn with_hole<'a>(
                                 assigned_in_jump,
                                 terminator,
                             );
+                            let then = count_coverage(env, loc_then.region, then);
 
                             stmt = cond(env, branching_symbol, cond_layout, then, stmt, ret_layout);
 
@@ -4673,6 +4994,83 @@ pub fn with_hole<'a>(
             stmt
         }
 
+        TupleAccess {
+            tuple_var,
+            elem_var,
+            index,
+            loc_expr,
+            ..
+        } => {
+            let sorted_elems_result = {
+                let mut layout_env = layout::Env::from_components(
+                    layout_cache,
+                    env.subs,
+                    env.arena,
+                    env.target_info,
+                );
+                layout::sort_tuple_elems(&mut layout_env, tuple_var)
+            };
+            let sorted_elems = match sorted_elems_result {
+                Ok(elems) => elems,
+                Err(_) => return Stmt::RuntimeError("Can't access tuple with improper layout"),
+            };
+
+            let mut final_index = None;
+            let mut elem_layouts = Vec::with_capacity_in(sorted_elems.len(), env.arena);
+
+            for (current, (elem_index, _, elem_layout)) in sorted_elems.into_iter().enumerate() {
+                elem_layouts.push(elem_layout);
+
+                if elem_index == index {
+                    final_index = Some(current);
+                }
+            }
+
+            let tuple_symbol = possible_reuse_symbol_or_specialize(
+                env,
+                procs,
+                layout_cache,
+                &loc_expr.value,
+                tuple_var,
+            );
+
+            let mut stmt = match elem_layouts.as_slice() {
+                [_] => {
+                    let mut hole = hole.clone();
+                    substitute_in_exprs(env.arena, &mut hole, assigned, tuple_symbol);
+
+                    hole
+                }
+                _ => {
+                    let expr = Expr::StructAtIndex {
+                        index: final_index.expect("elem not in its own type") as u64,
+                        field_layouts: elem_layouts.into_bump_slice(),
+                        structure: tuple_symbol,
+                    };
+
+                    let layout = layout_cache
+                        .from_var(env.arena, elem_var, env.subs)
+                        .unwrap_or_else(|err| {
+                            panic!("TODO turn fn_var into a RuntimeError {:?}", err)
+                        });
+
+                    Stmt::Let(assigned, expr, layout, hole)
+                }
+            };
+
+            stmt = assign_to_symbol(
+                env,
+                procs,
+                layout_cache,
+                tuple_var,
+                *loc_expr,
+                tuple_symbol,
+                stmt,
+            );
+
+            stmt
+        }
+
         Accessor(accessor_data) => {
             let field_var = accessor_data.field_var;
             let fresh_record_symbol = env.unique_symbol();
@@ -5043,6 +5441,7 @@ pub fn with_hole<'a>(
                         fn_var,
                         proc_name,
                         loc_args,
+                        loc_expr.region,
                         layout_cache,
                         assigned,
                         hole,
@@ -5058,6 +5457,7 @@ pub fn with_hole<'a>(
                         fn_var,
                         specialization_proc_name,
                         loc_args,
+                        loc_expr.region,
                         layout_cache,
                         assigned,
                         hole,
@@ -5137,6 +5537,7 @@ pub fn with_hole<'a>(
                                         arg_symbols,
                                         arg_layouts,
                                         ret_layout,
+                                        loc_expr.region,
                                         assigned,
                                         hole,
                                     );
@@ -5179,6 +5580,7 @@ pub fn with_hole<'a>(
                                         arg_symbols,
                                         arg_layouts,
                                         ret_layout,
+                                        loc_expr.region,
                                         assigned,
                                         hole,
                                     );
@@ -5208,6 +5610,7 @@ pub fn with_hole<'a>(
                                         fn_var,
                                         resolved_proc,
                                         loc_args,
+                                        loc_expr.region,
                                         layout_cache,
                                         assigned,
                                         hole,
@@ -5236,6 +5639,7 @@ pub fn with_hole<'a>(
                                         arg_symbols,
                                         arg_layouts,
                                         ret_layout,
+                                        loc_expr.region,
                                         assigned,
                                         hole,
                                     );
@@ -5526,6 +5930,11 @@ fn late_resolve_ability_specialization<'a>(
                         // The immediate is an ability member itself, so it must be resolved!
                         late_resolve_ability_specialization(env, imm, None, specialization_var)
                     }
+                    roc_derive_key::Derived::SingleLambdaSetImmediate(imm) => {
+                        // The immediate is a plain function with only one lambda set, so it needs
+                        // no further resolution.
+                        imm
+                    }
                     roc_derive_key::Derived::Key(derive_key) => {
                         let mut derived_module = env
                             .derived_module
@@ -6264,7 +6673,9 @@ pub fn from_can<'a>(
                 .from_var(env.arena, cond_var, env.subs)
                 .expect("invalid cond_layout");
 
+            let else_region = final_else.region;
             let mut stmt = from_can(env, branch_var, final_else.value, procs, layout_cache);
+            stmt = count_coverage(env, else_region, stmt);
 
             for (loc_cond, loc_then) in branches.into_iter().rev() {
                 let branching_symbol = possible_reuse_symbol_or_specialize(
@@ -6275,6 +6686,7 @@ pub fn from_can<'a>(
                     cond_var,
                 );
                 let then = from_can(env, branch_var, loc_then.value, procs, layout_cache);
+                let then = count_coverage(env, loc_then.region, then);
 
                 stmt = cond(env, branching_symbol, cond_layout, then, stmt, ret_layout);
 
@@ -6414,7 +6826,7 @@ fn to_opt_branches<'a>(
 ) -> std::vec::Vec<(
     Pattern<'a>,
     Option<Loc<roc_can::expr::Expr>>,
-    roc_can::expr::Expr,
+    Loc<roc_can::expr::Expr>,
 )> {
     debug_assert!(!branches.is_empty());
 
@@ -6426,6 +6838,9 @@ fn to_opt_branches<'a>(
             continue;
         }
 
+        // the region of the branch as written, which the bindings below do not have
+        let branch_region = when_branch.value.region;
+
         for loc_pattern in when_branch.patterns {
             match from_can_pattern(env, procs, layout_cache, &loc_pattern.pattern.value) {
                 Ok((mono_pattern, assignments)) => {
@@ -6459,14 +6874,21 @@ fn to_opt_branches<'a>(
                     };
 
                     // TODO remove clone?
-                    opt_branches.push((mono_pattern, when_branch.guard.clone(), loc_expr.value));
+                    opt_branches.push((
+                        mono_pattern,
+                        when_branch.guard.clone(),
+                        Loc::at(branch_region, loc_expr.value),
+                    ));
                 }
                 Err(runtime_error) => {
                     // TODO remove clone?
                     opt_branches.push((
                         Pattern::Underscore,
                         when_branch.guard.clone(),
-                        roc_can::expr::Expr::RuntimeError(runtime_error),
+                        Loc::at(
+                            branch_region,
+                            roc_can::expr::Expr::RuntimeError(runtime_error),
+                        ),
                     ));
                 }
             }
@@ -6479,13 +6901,66 @@ fn to_opt_branches<'a>(
         opt_branches.push((
             Pattern::Underscore,
             None,
-            roc_can::expr::Expr::RuntimeError(roc_problem::can::RuntimeError::NonExhaustivePattern),
+            Loc::at_zero(roc_can::expr::Expr::RuntimeError(
+                roc_problem::can::RuntimeError::NonExhaustivePattern,
+            )),
         ));
     }
 
     opt_branches
 }
 
+/// The foreign function that `roc test --coverage` counts runs with. It is given the module and
+/// the region (its start offset in the high 32 bits, its end offset in the low ones) of the
+/// code that runs.
+const COVERAGE_HIT: &str = "roc_coverage_hit";
+
+/// For `roc test --coverage`: count a run of the code written at `region` before running `stmt`.
+/// Procs count their body and branches count their expression, with regions that match the
+/// ones that `roc_can::coverage` lists.
+fn count_coverage<'a>(env: &mut Env<'a, '_>, region: Region, stmt: Stmt<'a>) -> Stmt<'a> {
+    if !env.count_coverage || region.is_empty() {
+        return stmt;
+    }
+
+    // the module id is written as its raw u32, like the frames of failed expects do
+    let module_id = env.home.to_u32();
+    let region_key = (region.start().offset as u64) << 32 | region.end().offset as u64;
+
+    let module_symbol = env.unique_symbol();
+    let region_symbol = env.unique_symbol();
+    let result_symbol = env.unique_symbol();
+
+    let call = self::Call {
+        call_type: CallType::Foreign {
+            foreign_symbol: ForeignSymbol::from(COVERAGE_HIT),
+            ret_layout: env.arena.alloc(Layout::UNIT),
+        },
+        arguments: env.arena.alloc([module_symbol, region_symbol]),
+    };
+
+    let stmt = Stmt::Let(
+        result_symbol,
+        Expr::Call(call),
+        Layout::UNIT,
+        env.arena.alloc(stmt),
+    );
+
+    let stmt = Stmt::Let(
+        region_symbol,
+        Expr::Literal(Literal::Int((region_key as i128).to_ne_bytes())),
+        Layout::u64(),
+        env.arena.alloc(stmt),
+    );
+
+    Stmt::Let(
+        module_symbol,
+        Expr::Literal(Literal::Int((module_id as i128).to_ne_bytes())),
+        Layout::u32(),
+        env.arena.alloc(stmt),
+    )
+}
+
 #[allow(clippy::too_many_arguments)]
 fn from_can_when<'a>(
     env: &mut Env<'a, '_>,
@@ -6520,7 +6995,8 @@ fn from_can_when<'a>(
     let arena = env.arena;
     let it = opt_branches
         .into_iter()
-        .map(|(pattern, opt_guard, can_expr)| {
+        .map(|(pattern, opt_guard, loc_can_expr)| {
+            let can_expr = loc_can_expr.value;
             let branch_stmt = match join_point {
                 None => from_can(env, expr_var, can_expr, procs, layout_cache),
                 Some(id) => {
@@ -6531,30 +7007,15 @@ fn from_can_when<'a>(
                     with_hole(env, can_expr, expr_var, procs, layout_cache, symbol, jump)
                 }
             };
+            let branch_stmt = count_coverage(env, loc_can_expr.region, branch_stmt);
 
-            use crate::decision_tree::Guard;
+            use crate::decision_tree::{Guard, GuardStmtSpec};
             if let Some(loc_expr) = opt_guard {
-                let id = JoinPointId(env.unique_symbol());
-                let symbol = env.unique_symbol();
-                let jump = env.arena.alloc(Stmt::Jump(id, env.arena.alloc([symbol])));
-
-                let guard_stmt = with_hole(
-                    env,
-                    loc_expr.value,
-                    Variable::BOOL,
-                    procs,
-                    layout_cache,
-                    symbol,
-                    jump,
-                );
+                let stmt_spec = GuardStmtSpec::new(loc_expr.value, env.unique_symbol());
 
                 (
                     pattern.clone(),
-                    Guard::Guard {
-                        id,
-                        pattern,
-                        stmt: guard_stmt,
-                    },
+                    Guard::Guard { pattern, stmt_spec },
                     branch_stmt,
                 )
             } else {
@@ -6584,7 +7045,12 @@ fn substitute(substitutions: &BumpMap<Symbol, Symbol>, s: Symbol) -> Option<Symb
     }
 }
 
-fn substitute_in_exprs<'a>(arena: &'a Bump, stmt: &mut Stmt<'a>, from: Symbol, to: Symbol) {
+pub(crate) fn substitute_in_exprs<'a>(
+    arena: &'a Bump,
+    stmt: &mut Stmt<'a>,
+    from: Symbol,
+    to: Symbol,
+) {
     let mut subs = BumpMap::with_capacity_in(1, arena);
     subs.insert(from, to);
 
@@ -6760,6 +7226,42 @@ fn substitute_in_stmt_help<'a>(
             Some(arena.alloc(expect))
         }
 
+        Dbg {
+            symbol,
+            region,
+            remainder,
+        } => {
+            let new_remainder =
+                substitute_in_stmt_help(arena, remainder, subs).unwrap_or(remainder);
+
+            let dbg = Dbg {
+                symbol: substitute(subs, *symbol).unwrap_or(*symbol),
+                region: *region,
+                remainder: new_remainder,
+            };
+
+            Some(arena.alloc(dbg))
+        }
+
+        Crash {
+            msg,
+            msg_bytes,
+            region,
+        } => {
+            let new_msg = substitute(subs, *msg);
+            let new_msg_bytes = substitute(subs, *msg_bytes);
+
+            if new_msg.is_none() && new_msg_bytes.is_none() {
+                return None;
+            }
+
+            Some(arena.alloc(Crash {
+                msg: new_msg.unwrap_or(*msg),
+                msg_bytes: new_msg_bytes.unwrap_or(*msg_bytes),
+                region: *region,
+            }))
+        }
+
         Jump(id, args) => {
             let mut did_change = false;
             let new_args = Vec::from_iter_in(
@@ -6802,11 +7304,13 @@ fn substitute_in_call<'a>(
             arg_layouts,
             ret_layout,
             specialization_id,
+            region,
         } => substitute(subs, name.name()).map(|new| CallType::ByName {
             name: name.replace_name(new),
             arg_layouts,
             ret_layout: *ret_layout,
             specialization_id: *specialization_id,
+            region: *region,
         }),
         CallType::Foreign { .. } => None,
         CallType::LowLevel { .. } => None,
@@ -7090,6 +7594,27 @@ fn store_pattern_help<'a>(
             return store_pattern_help(env, procs, layout_cache, pattern, outer_symbol, stmt);
         }
 
+        List {
+            arity,
+            list_layout,
+            element_layout,
+            elements,
+            opt_rest,
+        } => {
+            return store_list_pattern(
+                env,
+                procs,
+                layout_cache,
+                outer_symbol,
+                *arity,
+                *list_layout,
+                *element_layout,
+                elements,
+                opt_rest,
+                stmt,
+            )
+        }
+
         RecordDestructure(destructs, [_single_field]) => {
             for destruct in destructs {
                 match &destruct.typ {
@@ -7148,11 +7673,266 @@ fn store_pattern_help<'a>(
                 return StorePattern::NotProductive(stmt);
             }
         }
+
+        TupleDestructure(destructs, [_single_elem]) => {
+            if let Some(destruct) = destructs.first() {
+                return store_pattern_help(
+                    env,
+                    procs,
+                    layout_cache,
+                    &destruct.pat,
+                    outer_symbol,
+                    stmt,
+                );
+            }
+        }
+        TupleDestructure(destructs, sorted_elems) => {
+            let mut is_productive = false;
+            for (index, destruct) in destructs.iter().enumerate().rev() {
+                match store_tuple_destruct(
+                    env,
+                    procs,
+                    layout_cache,
+                    destruct,
+                    index as u64,
+                    outer_symbol,
+                    sorted_elems,
+                    stmt,
+                ) {
+                    StorePattern::Productive(new) => {
+                        is_productive = true;
+                        stmt = new;
+                    }
+                    StorePattern::NotProductive(new) => {
+                        stmt = new;
+                    }
+                }
+            }
+
+            if !is_productive {
+                return StorePattern::NotProductive(stmt);
+            }
+        }
     }
 
     StorePattern::Productive(stmt)
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ListIndex {
+    /// Index counting from the start of the list
+    FromStart(u64),
+    /// Index counting from the end of the list; 1 is the last element
+    FromEnd(u64),
+}
+
+impl ListIndex {
+    pub fn from_pattern_index(index: usize, arity: ListArity) -> Self {
+        match arity {
+            ListArity::Exact(_) => ListIndex::FromStart(index as _),
+            ListArity::Slice(before, after) => {
+                if index < before {
+                    ListIndex::FromStart(index as _)
+                } else {
+                    ListIndex::FromEnd((before + after - index) as _)
+                }
+            }
+        }
+    }
+}
+
+/// Builds the statements that compute the concrete index of `list_index` into `list_sym`.
+/// Returns the symbol holding the index, and the stores that compute it in evaluation order.
+pub(crate) fn build_list_index_probe<'a>(
+    env: &mut Env<'a, '_>,
+    list_sym: Symbol,
+    list_index: &ListIndex,
+) -> (Symbol, Vec<'a, (Symbol, Layout<'a>, Expr<'a>)>) {
+    let usize_layout = Layout::usize(env.target_info);
+    let mut stores = Vec::with_capacity_in(3, env.arena);
+
+    let index_sym = env.unique_symbol();
+
+    match list_index {
+        ListIndex::FromStart(i) => {
+            let index_expr = Expr::Literal(Literal::Int((*i as i128).to_ne_bytes()));
+            stores.push((index_sym, usize_layout, index_expr));
+        }
+        ListIndex::FromEnd(k) => {
+            let len_sym = env.unique_symbol();
+            let len_expr = Expr::Call(Call {
+                call_type: CallType::LowLevel {
+                    op: LowLevel::ListLen,
+                    update_mode: env.next_update_mode_id(),
+                },
+                arguments: env.arena.alloc([list_sym]),
+            });
+
+            let offset_sym = env.unique_symbol();
+            let offset_expr = Expr::Literal(Literal::Int((*k as i128).to_ne_bytes()));
+
+            let index_expr = Expr::Call(Call {
+                call_type: CallType::LowLevel {
+                    op: LowLevel::NumSub,
+                    update_mode: env.next_update_mode_id(),
+                },
+                arguments: env.arena.alloc([len_sym, offset_sym]),
+            });
+
+            stores.push((len_sym, usize_layout, len_expr));
+            stores.push((offset_sym, usize_layout, offset_expr));
+            stores.push((index_sym, usize_layout, index_expr));
+        }
+    }
+
+    (index_sym, stores)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn store_list_pattern<'a>(
+    env: &mut Env<'a, '_>,
+    procs: &mut Procs<'a>,
+    layout_cache: &mut LayoutCache<'a>,
+    list_sym: Symbol,
+    list_arity: ListArity,
+    list_layout: Layout<'a>,
+    element_layout: Layout<'a>,
+    elements: &[Pattern<'a>],
+    opt_rest: &Option<(usize, Option<Symbol>)>,
+    mut stmt: Stmt<'a>,
+) -> StorePattern<'a> {
+    use Pattern::*;
+
+    let mut is_productive = false;
+
+    if let Some((_, Some(rest_sym))) = opt_rest {
+        // the rest of the list is everything between the elements matched at the start and
+        // those matched at the end
+        let (before, after) = match list_arity {
+            ListArity::Slice(before, after) => (before, after),
+            ListArity::Exact(_) => internal_error!("a list pattern with a rest has a slice arity"),
+        };
+
+        let rest_sym = procs
+            .symbol_specializations
+            .remove_single(*rest_sym)
+            .unwrap_or(*rest_sym);
+
+        let usize_layout = Layout::usize(env.target_info);
+
+        let start_sym = env.unique_symbol();
+        let start_expr = Expr::Literal(Literal::Int((before as i128).to_ne_bytes()));
+
+        let len_sym = env.unique_symbol();
+        let len_expr = Expr::Call(Call {
+            call_type: CallType::LowLevel {
+                op: LowLevel::ListLen,
+                update_mode: env.next_update_mode_id(),
+            },
+            arguments: env.arena.alloc([list_sym]),
+        });
+
+        let dropped_sym = env.unique_symbol();
+        let dropped_expr = Expr::Literal(Literal::Int(((before + after) as i128).to_ne_bytes()));
+
+        let rest_len_sym = env.unique_symbol();
+        let rest_len_expr = Expr::Call(Call {
+            call_type: CallType::LowLevel {
+                op: LowLevel::NumSub,
+                update_mode: env.next_update_mode_id(),
+            },
+            arguments: env.arena.alloc([len_sym, dropped_sym]),
+        });
+
+        let rest_expr = Expr::Call(Call {
+            call_type: CallType::LowLevel {
+                op: LowLevel::ListSublist,
+                update_mode: env.next_update_mode_id(),
+            },
+            arguments: env.arena.alloc([list_sym, start_sym, rest_len_sym]),
+        });
+
+        stmt = Stmt::Let(rest_sym, rest_expr, list_layout, env.arena.alloc(stmt));
+        stmt = Stmt::Let(
+            rest_len_sym,
+            rest_len_expr,
+            usize_layout,
+            env.arena.alloc(stmt),
+        );
+        stmt = Stmt::Let(
+            dropped_sym,
+            dropped_expr,
+            usize_layout,
+            env.arena.alloc(stmt),
+        );
+        stmt = Stmt::Let(len_sym, len_expr, usize_layout, env.arena.alloc(stmt));
+        stmt = Stmt::Let(start_sym, start_expr, usize_layout, env.arena.alloc(stmt));
+
+        is_productive = true;
+    }
+
+    for (index, element) in elements.iter().enumerate().rev() {
+        let element_sym = match element {
+            Identifier(symbol) => {
+                // Pattern can define only one specialization
+                procs
+                    .symbol_specializations
+                    .remove_single(*symbol)
+                    .unwrap_or(*symbol)
+            }
+            Underscore
+            | IntLiteral(_, _)
+            | FloatLiteral(_, _)
+            | DecimalLiteral(_)
+            | EnumLiteral { .. }
+            | BitLiteral { .. }
+            | StrLiteral(_) => continue,
+            _ => {
+                // store the element in a symbol, and continue matching on it
+                let symbol = env.unique_symbol();
+
+                match store_pattern_help(env, procs, layout_cache, element, symbol, stmt) {
+                    StorePattern::Productive(new) => {
+                        stmt = new;
+                    }
+                    StorePattern::NotProductive(new) => {
+                        // only extract the element if we bind one of its (sub)patterns
+                        stmt = new;
+                        continue;
+                    }
+                }
+
+                symbol
+            }
+        };
+
+        is_productive = true;
+
+        let list_index = ListIndex::from_pattern_index(index, list_arity);
+        let (index_sym, index_stores) = build_list_index_probe(env, list_sym, &list_index);
+
+        let load = Expr::Call(Call {
+            call_type: CallType::LowLevel {
+                op: LowLevel::ListGetUnsafe,
+                update_mode: env.next_update_mode_id(),
+            },
+            arguments: env.arena.alloc([list_sym, index_sym]),
+        });
+
+        stmt = Stmt::Let(element_sym, load, element_layout, env.arena.alloc(stmt));
+
+        for (symbol, layout, expr) in index_stores.into_iter().rev() {
+            stmt = Stmt::Let(symbol, expr, layout, env.arena.alloc(stmt));
+        }
+    }
+
+    if is_productive {
+        StorePattern::Productive(stmt)
+    } else {
+        StorePattern::NotProductive(stmt)
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn store_tag_pattern<'a>(
     env: &mut Env<'a, '_>,
@@ -7413,6 +8193,72 @@ fn store_record_destruct<'a>(
     StorePattern::Productive(stmt)
 }
 
+#[allow(clippy::too_many_arguments)]
+fn store_tuple_destruct<'a>(
+    env: &mut Env<'a, '_>,
+    procs: &mut Procs<'a>,
+    layout_cache: &mut LayoutCache<'a>,
+    destruct: &TupleDestruct<'a>,
+    index: u64,
+    outer_symbol: Symbol,
+    sorted_elems: &'a [Layout<'a>],
+    mut stmt: Stmt<'a>,
+) -> StorePattern<'a> {
+    use Pattern::*;
+
+    let load = Expr::StructAtIndex {
+        index,
+        field_layouts: sorted_elems,
+        structure: outer_symbol,
+    };
+
+    match &destruct.pat {
+        Identifier(symbol) => {
+            // A destructure can define at most one specialization!
+            // Remove any requested specializations for this name now, since this is the definition site.
+            let specialization_symbol = procs
+                .symbol_specializations
+                .remove_single(*symbol)
+                // Can happen when the symbol was never used under this body, and hence has no
+                // requested specialization.
+                .unwrap_or(*symbol);
+
+            stmt = Stmt::Let(
+                specialization_symbol,
+                load,
+                destruct.layout,
+                env.arena.alloc(stmt),
+            );
+        }
+        Underscore => {
+            // an element that is not bound in the source code must not be loaded
+            return StorePattern::NotProductive(stmt);
+        }
+        IntLiteral(_, _)
+        | FloatLiteral(_, _)
+        | DecimalLiteral(_)
+        | EnumLiteral { .. }
+        | BitLiteral { .. }
+        | StrLiteral(_) => {
+            return StorePattern::NotProductive(stmt);
+        }
+
+        _ => {
+            let symbol = env.unique_symbol();
+
+            match store_pattern_help(env, procs, layout_cache, &destruct.pat, symbol, stmt) {
+                StorePattern::Productive(new) => {
+                    stmt = new;
+                    stmt = Stmt::Let(symbol, load, destruct.layout, env.arena.alloc(stmt));
+                }
+                StorePattern::NotProductive(stmt) => return StorePattern::NotProductive(stmt),
+            }
+        }
+    }
+
+    StorePattern::Productive(stmt)
+}
+
 /// We want to re-use symbols that are not function symbols
 /// for any other expression, we create a new symbol, and will
 /// later make sure it gets assigned the correct value.
@@ -7603,6 +8449,7 @@ fn force_thunk<'a>(
             ret_layout: env.arena.alloc(layout),
             arg_layouts: &[],
             specialization_id: env.next_call_specialization_id(),
+            region: Region::zero(),
         },
         arguments: &[],
     };
@@ -7952,6 +8799,7 @@ fn call_by_name<'a>(
     fn_var: Variable,
     proc_name: Symbol,
     loc_args: std::vec::Vec<(Variable, Loc<roc_can::expr::Expr>)>,
+    call_region: Region,
     layout_cache: &mut LayoutCache<'a>,
     assigned: Symbol,
     hole: &'a Stmt<'a>,
@@ -8025,6 +8873,7 @@ fn call_by_name<'a>(
                         arg_symbols,
                         arg_layouts,
                         ret_layout,
+                        call_region,
                         assigned,
                         hole,
                     );
@@ -8050,6 +8899,7 @@ fn call_by_name<'a>(
                     fn_var,
                     proc_name,
                     loc_args,
+                    call_region,
                     lambda_set,
                     arg_layouts,
                     ret_layout,
@@ -8089,6 +8939,7 @@ fn call_by_name_help<'a>(
     fn_var: Variable,
     proc_name: Symbol,
     loc_args: std::vec::Vec<(Variable, Loc<roc_can::expr::Expr>)>,
+    call_region: Region,
     lambda_set: LambdaSet<'a>,
     argument_layouts: &'a [Layout<'a>],
     ret_layout: &'a Layout<'a>,
@@ -8180,6 +9031,7 @@ fn call_by_name_help<'a>(
             top_level_layout,
             field_symbols.into_bump_slice(),
             loc_args,
+            call_region,
             layout_cache,
             assigned,
             hole,
@@ -8231,6 +9083,7 @@ fn call_by_name_help<'a>(
                     ret_layout,
                     arg_layouts: argument_layouts,
                     specialization_id: env.next_call_specialization_id(),
+                    region: call_region,
                 },
                 arguments: field_symbols,
             };
@@ -8287,6 +9140,7 @@ fn call_by_name_help<'a>(
                     top_level_layout,
                     field_symbols,
                     loc_args,
+                    call_region,
                     layout_cache,
                     assigned,
                     hole,
@@ -8339,6 +9193,7 @@ fn call_by_name_help<'a>(
                                     function_layout,
                                     field_symbols,
                                     loc_args,
+                                    call_region,
                                     layout_cache,
                                     assigned,
                                     hole,
@@ -8374,6 +9229,7 @@ fn call_by_name_help<'a>(
                                     function_layout,
                                     field_symbols,
                                     loc_args,
+                                    call_region,
                                     layout_cache,
                                     assigned,
                                     hole,
@@ -8532,6 +9388,7 @@ fn call_specialized_proc<'a>(
     function_layout: ProcLayout<'a>,
     field_symbols: &'a [Symbol],
     loc_args: std::vec::Vec<(Variable, Loc<roc_can::expr::Expr>)>,
+    call_region: Region,
     layout_cache: &mut LayoutCache<'a>,
     assigned: Symbol,
     hole: &'a Stmt<'a>,
@@ -8555,6 +9412,7 @@ fn call_specialized_proc<'a>(
                         ret_layout: env.arena.alloc(function_layout.result),
                         arg_layouts: function_layout.arguments,
                         specialization_id: env.next_call_specialization_id(),
+                        region: call_region,
                     },
                     arguments: field_symbols,
                 };
@@ -8598,6 +9456,7 @@ fn call_specialized_proc<'a>(
                     field_symbols,
                     argument_layouts.into_bump_slice(),
                     env.arena.alloc(function_layout.result),
+                    call_region,
                     assigned,
                     hole,
                 );
@@ -8631,6 +9490,7 @@ fn call_specialized_proc<'a>(
                         ret_layout: env.arena.alloc(function_layout.result),
                         arg_layouts: function_layout.arguments,
                         specialization_id: env.next_call_specialization_id(),
+                        region: call_region,
                     },
                     arguments: field_symbols,
                 };
@@ -8665,6 +9525,7 @@ pub enum Pattern<'a> {
     StrLiteral(Box<str>),
 
     RecordDestructure(Vec<'a, RecordDestruct<'a>>, &'a [Layout<'a>]),
+    TupleDestructure(Vec<'a, TupleDestruct<'a>>, &'a [Layout<'a>]),
     NewtypeDestructure {
         tag_name: TagName,
         arguments: Vec<'a, (Pattern<'a>, Layout<'a>)>,
@@ -8680,6 +9541,14 @@ pub enum Pattern<'a> {
         opaque: Symbol,
         argument: Box<(Pattern<'a>, Layout<'a>)>,
     },
+    List {
+        arity: ListArity,
+        list_layout: Layout<'a>,
+        element_layout: Layout<'a>,
+        elements: Vec<'a, Pattern<'a>>,
+        /// Index of the rest pattern in `elements`, and the symbol it binds to, if any
+        opt_rest: Option<(usize, Option<Symbol>)>,
+    },
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -8690,6 +9559,14 @@ pub struct RecordDestruct<'a> {
     pub typ: DestructType<'a>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct TupleDestruct<'a> {
+    pub index: usize,
+    pub variable: Variable,
+    pub layout: Layout<'a>,
+    pub pat: Pattern<'a>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum DestructType<'a> {
     Required(Symbol),
@@ -9372,6 +10249,96 @@ fn from_can_pattern_help<'a>(
                 field_layouts.into_bump_slice(),
             ))
         }
+
+        TupleDestructure {
+            whole_var,
+            destructs,
+            ..
+        } => {
+            // sorted elements based on the type
+            let sorted_elems = {
+                let mut layout_env = layout::Env::from_components(
+                    layout_cache,
+                    env.subs,
+                    env.arena,
+                    env.target_info,
+                );
+                crate::layout::sort_tuple_elems(&mut layout_env, *whole_var)
+                    .map_err(RuntimeError::from)?
+            };
+
+            let mut mono_destructs = Vec::with_capacity_in(sorted_elems.len(), env.arena);
+            let mut elem_layouts = Vec::with_capacity_in(sorted_elems.len(), env.arena);
+
+            for (index, variable, elem_layout) in sorted_elems.into_iter() {
+                let pat = match destructs
+                    .iter()
+                    .find(|destruct| destruct.value.destruct_index == index)
+                {
+                    Some(destruct) => {
+                        let (_, loc_pattern) = &destruct.value.typ;
+
+                        from_can_pattern_help(
+                            env,
+                            procs,
+                            layout_cache,
+                            &loc_pattern.value,
+                            assignments,
+                        )?
+                    }
+                    None => Pattern::Underscore,
+                };
+
+                mono_destructs.push(TupleDestruct {
+                    index,
+                    variable,
+                    layout: elem_layout,
+                    pat,
+                });
+
+                // the layout of this element is part of the layout of the tuple
+                elem_layouts.push(elem_layout);
+            }
+
+            Ok(Pattern::TupleDestructure(
+                mono_destructs,
+                elem_layouts.into_bump_slice(),
+            ))
+        }
+
+        List {
+            list_var,
+            elem_var,
+            patterns,
+        } => {
+            let list_layout = layout_cache
+                .from_var(env.arena, *list_var, env.subs)
+                .map_err(RuntimeError::from)?;
+
+            let element_layout = layout_cache
+                .from_var(env.arena, *elem_var, env.subs)
+                .map_err(RuntimeError::from)?;
+
+            let mut mono_patterns = Vec::with_capacity_in(patterns.patterns.len(), env.arena);
+            for loc_pat in patterns.patterns.iter() {
+                let mono_pat =
+                    from_can_pattern_help(env, procs, layout_cache, &loc_pat.value, assignments)?;
+                mono_patterns.push(mono_pat);
+            }
+
+            let opt_rest = patterns
+                .opt_rest
+                .as_ref()
+                .map(|(index, opt_symbol)| (*index, opt_symbol.as_ref().map(|s| s.value)));
+
+            Ok(Pattern::List {
+                arity: patterns.arity(),
+                list_layout,
+                element_layout,
+                elements: mono_patterns,
+                opt_rest,
+            })
+        }
     }
 }
 
@@ -9715,6 +10682,7 @@ fn match_on_lambda_set<'a>(
     argument_symbols: &'a [Symbol],
     argument_layouts: &'a [Layout<'a>],
     return_layout: &'a Layout<'a>,
+    call_region: Region,
     assigned: Symbol,
     hole: &'a Stmt<'a>,
 ) -> Stmt<'a> {
@@ -9733,6 +10701,7 @@ fn match_on_lambda_set<'a>(
                 argument_symbols,
                 argument_layouts,
                 return_layout,
+                call_region,
                 assigned,
                 hole,
             );
@@ -9792,6 +10761,7 @@ fn match_on_lambda_set<'a>(
                 argument_symbols,
                 argument_layouts,
                 return_layout,
+                call_region,
                 assigned,
                 hole,
             )
@@ -9815,6 +10785,7 @@ fn match_on_lambda_set<'a>(
                 argument_symbols,
                 argument_layouts,
                 return_layout,
+                call_region,
                 assigned,
                 hole,
             )
@@ -9831,6 +10802,7 @@ fn match_on_lambda_set<'a>(
                     argument_symbols,
                     argument_layouts,
                     return_layout,
+                    call_region,
                     assigned,
                     hole,
                 )
@@ -9846,6 +10818,7 @@ fn match_on_lambda_set<'a>(
                     argument_symbols,
                     argument_layouts,
                     return_layout,
+                    call_region,
                     assigned,
                     hole,
                 )
@@ -9865,6 +10838,7 @@ fn union_lambda_set_to_switch<'a>(
     argument_symbols: &'a [Symbol],
     argument_layouts: &'a [Layout<'a>],
     return_layout: &'a Layout<'a>,
+    call_region: Region,
     assigned: Symbol,
     hole: &'a Stmt<'a>,
 ) -> Stmt<'a> {
@@ -9899,6 +10873,7 @@ fn union_lambda_set_to_switch<'a>(
             argument_symbols,
             argument_layouts,
             return_layout,
+            call_region,
         );
         branches.push((i as u64, BranchInfo::None, stmt));
     }
@@ -9941,6 +10916,7 @@ fn union_lambda_set_branch<'a>(
     argument_symbols_slice: &'a [Symbol],
     argument_layouts_slice: &'a [Layout<'a>],
     return_layout: &'a Layout<'a>,
+    call_region: Region,
 ) -> Stmt<'a> {
     let result_symbol = env.unique_symbol();
 
@@ -9954,6 +10930,7 @@ fn union_lambda_set_branch<'a>(
         argument_symbols_slice,
         argument_layouts_slice,
         return_layout,
+        call_region,
         result_symbol,
         env.arena.alloc(hole),
     )
@@ -9978,6 +10955,7 @@ fn union_lambda_set_branch_help<'a>(
     argument_symbols_slice: &'a [Symbol],
     argument_layouts_slice: &'a [Layout<'a>],
     return_layout: &'a Layout<'a>,
+    call_region: Region,
     assigned: Symbol,
     hole: &'a Stmt<'a>,
 ) -> Stmt<'a> {
@@ -10019,6 +10997,7 @@ fn union_lambda_set_branch_help<'a>(
             ret_layout: return_layout,
             arg_layouts: argument_layouts,
             specialization_id: env.next_call_specialization_id(),
+            region: call_region,
         },
         arguments: argument_symbols,
     };
@@ -10037,6 +11016,7 @@ fn enum_lambda_set_to_switch<'a>(
     argument_symbols: &'a [Symbol],
     argument_layouts: &'a [Layout<'a>],
     return_layout: &'a Layout<'a>,
+    call_region: Region,
     assigned: Symbol,
     hole: &'a Stmt<'a>,
 ) -> Stmt<'a> {
@@ -10054,6 +11034,7 @@ fn enum_lambda_set_to_switch<'a>(
             argument_symbols,
             argument_layouts,
             return_layout,
+            call_region,
         );
         branches.push((i as u64, BranchInfo::None, stmt));
     }
@@ -10095,6 +11076,7 @@ fn enum_lambda_set_branch<'a>(
     argument_symbols: &'a [Symbol],
     argument_layouts: &'a [Layout<'a>],
     return_layout: &'a Layout<'a>,
+    call_region: Region,
 ) -> Stmt<'a> {
     let result_symbol = env.unique_symbol();
 
@@ -10108,6 +11090,7 @@ fn enum_lambda_set_branch<'a>(
             ret_layout: return_layout,
             arg_layouts: argument_layouts,
             specialization_id: env.next_call_specialization_id(),
+            region: call_region,
         },
         arguments: argument_symbols,
     };
@@ -241,7 +241,28 @@ fn function_s<'a, 'i>(
             }
         }
 
-        Ret(_) | Jump(_, _) | RuntimeError(_) => stmt,
+        Dbg {
+            symbol,
+            region,
+            remainder,
+        } => {
+            let continuation: &Stmt = *remainder;
+            let new_continuation = function_s(env, w, c, continuation);
+
+            if std::ptr::eq(continuation, new_continuation) || continuation == new_continuation {
+                stmt
+            } else {
+                let new_dbg = Dbg {
+                    symbol: *symbol,
+                    region: *region,
+                    remainder: new_continuation,
+                };
+
+                arena.alloc(new_dbg)
+            }
+        }
+
+        Ret(_) | Jump(_, _) | Crash { .. } | RuntimeError(_) => stmt,
     }
 }
 
@@ -504,6 +525,33 @@ fn function_d_main<'a, 'i>(
                 (arena.alloc(refcounting), found)
             }
         }
+        Dbg {
+            symbol,
+            region,
+            remainder,
+        } => {
+            let (b, found) = function_d_main(env, x, c, remainder);
+
+            if found || *symbol != x {
+                let dbg = Dbg {
+                    symbol: *symbol,
+                    region: *region,
+                    remainder: b,
+                };
+
+                (arena.alloc(dbg), found)
+            } else {
+                let b = try_function_s(env, x, c, b);
+
+                let dbg = Dbg {
+                    symbol: *symbol,
+                    region: *region,
+                    remainder: b,
+                };
+
+                (arena.alloc(dbg), found)
+            }
+        }
         Join {
             id,
             parameters,
@@ -535,7 +583,9 @@ fn function_d_main<'a, 'i>(
 
             (arena.alloc(new_join), found)
         }
-        Ret(_) | Jump(_, _) | RuntimeError(_) => (stmt, has_live_var(&env.jp_live_vars, stmt, x)),
+        Ret(_) | Jump(_, _) | Crash { .. } | RuntimeError(_) => {
+            (stmt, has_live_var(&env.jp_live_vars, stmt, x))
+        }
     }
 }
 
@@ -696,7 +746,23 @@ fn function_r<'a, 'i>(env: &mut Env<'a, 'i>, stmt: &'a Stmt<'a>) -> &'a Stmt<'a>
             arena.alloc(expect)
         }
 
-        Ret(_) | Jump(_, _) | RuntimeError(_) => {
+        Dbg {
+            symbol,
+            region,
+            remainder,
+        } => {
+            let b = function_r(env, remainder);
+
+            let dbg = Dbg {
+                symbol: *symbol,
+                region: *region,
+                remainder: b,
+            };
+
+            arena.alloc(dbg)
+        }
+
+        Ret(_) | Jump(_, _) | Crash { .. } | RuntimeError(_) => {
             // terminals
             stmt
         }
@@ -736,6 +802,10 @@ fn has_live_var<'a>(jp_live_vars: &JPLiveVarMap, stmt: &'a Stmt<'a>, needle: Sym
             remainder,
             ..
         } => *condition == needle || has_live_var(jp_live_vars, remainder, needle),
+        Dbg {
+            symbol, remainder, ..
+        } => *symbol == needle || has_live_var(jp_live_vars, remainder, needle),
+        Crash { msg, msg_bytes, .. } => *msg == needle || *msg_bytes == needle,
         Join {
             id,
             parameters,
@@ -163,9 +163,10 @@ pub enum Expr<'a> {
 
     // String Literals
     Str(StrLiteral<'a>), // string without escapes in it
-    /// Look up exactly one field on a record, e.g. (expr).foo.
+    /// Look up exactly one field on a record, e.g. (expr).foo,
+    /// or one element of a tuple, e.g. (expr).0.
     Access(&'a Expr<'a>, &'a str),
-    /// e.g. `.foo`
+    /// e.g. `.foo` or `.0`
     AccessorFunction(&'a str),
     /// eg 'b'
     SingleQuote(&'a str),
@@ -173,6 +174,8 @@ pub enum Expr<'a> {
     // Collection Literals
     List(Collection<'a, &'a Loc<Expr<'a>>>),
 
+    /// A record update, e.g. `{ model & count: 0 }`. A field label may be a
+    /// nested path like `settings.theme.color`, stored as `AssignedField::Nested`.
     RecordUpdate {
         update: &'a Loc<Expr<'a>>,
         fields: Collection<'a, Loc<AssignedField<'a, Expr<'a>>>>,
@@ -180,6 +183,9 @@ pub enum Expr<'a> {
 
     Record(Collection<'a, Loc<AssignedField<'a, Expr<'a>>>>),
 
+    /// A tuple with at least two elements, e.g. `(1, "a")`
+    Tuple(Collection<'a, &'a Loc<Expr<'a>>>),
+
     // Lookups
     Var {
         module_name: &'a str, // module_name will only be filled if the original Roc code stated something like `5 + SomeModule.myVar`, module_name will be blank if it was `5 + myVar`
@@ -188,6 +194,9 @@ pub enum Expr<'a> {
 
     Underscore(&'a str),
 
+    /// The `crash` keyword, which must be applied to a message, as in `crash "oops"`
+    Crash,
+
     // Tags
     Tag(&'a str),
 
@@ -200,6 +209,8 @@ pub enum Expr<'a> {
     Defs(&'a Defs<'a>, &'a Loc<Expr<'a>>),
     Backpassing(&'a [Loc<Pattern<'a>>], &'a Loc<Expr<'a>>, &'a Loc<Expr<'a>>),
     Expect(&'a Loc<Expr<'a>>, &'a Loc<Expr<'a>>),
+    /// `dbg expr`, which evaluates to `expr` and prints its value along the way
+    Dbg(&'a Loc<Expr<'a>>),
 
     // Application
     /// To apply by name, do Apply(Var(...), ...)
@@ -230,6 +241,8 @@ pub enum Expr<'a> {
     // Problems
     MalformedIdent(&'a str, crate::ident::BadIdent),
     MalformedClosure,
+    /// An expression with a syntax error, which the parser skipped over
+    Malformed(&'a str),
     // Both operators were non-associative, e.g. (True == False == False).
     // We should tell the author to disambiguate by grouping them with parens.
     PrecedenceConflict(&'a PrecedenceConflict<'a>),
@@ -340,6 +353,9 @@ pub enum ValueDef<'a> {
         condition: &'a Loc<Expr<'a>>,
         preceding_comment: Region,
     },
+
+    /// A top-level def with a syntax error, which the parser skipped over
+    Malformed(&'a str),
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -519,6 +535,14 @@ pub enum TypeAnnotation<'a> {
         ext: Option<&'a Loc<TypeAnnotation<'a>>>,
     },
 
+    /// A tuple with at least two elements, e.g. `(Str, I64)`
+    Tuple {
+        elems: Collection<'a, Loc<TypeAnnotation<'a>>>,
+        /// The row type variable in an open tuple, e.g. the `r` in `(Str, I64)r`.
+        /// This is None if it's a closed tuple annotation like `(Str, I64)`.
+        ext: Option<&'a Loc<TypeAnnotation<'a>>>,
+    },
+
     /// A tag union, e.g. `[
     TagUnion {
         /// The row type variable in an open tag union, e.g. the `a` in `[Foo, Bar]a`.
@@ -573,6 +597,10 @@ pub enum AssignedField<'a, Val> {
     // A label with no value, e.g. `{ name }` (this is sugar for { name: name })
     LabelOnly(Loc<&'a str>),
 
+    /// A field under another field, e.g. the `settings.` in the record update
+    /// `{ model & settings.theme: Dark }`
+    Nested(Loc<&'a str>, &'a AssignedField<'a, Val>),
+
     // We preserve this for the formatter; canonicalization ignores it.
     SpaceBefore(&'a AssignedField<'a, Val>, &'a [CommentOrNewline<'a>]),
     SpaceAfter(&'a AssignedField<'a, Val>, &'a [CommentOrNewline<'a>]),
@@ -641,6 +669,16 @@ pub enum Pattern<'a> {
     /// Can only occur inside of a RecordDestructure
     OptionalField(&'a str, &'a Loc<Expr<'a>>),
 
+    /// A tuple pattern with at least two elements, e.g. (x, Just 0) -> ...
+    Tuple(Collection<'a, Loc<Pattern<'a>>>),
+
+    /// A list pattern, e.g. [first, .., last] -> ...
+    List(Collection<'a, Loc<Pattern<'a>>>),
+
+    /// The rest of a list pattern, optionally named, e.g. the `..` in `[first, ..]` or
+    /// the `..rest` in `[first, ..rest]`. Can only occur inside of a List.
+    ListRest(Option<&'a str>),
+
     // Literal
     NumLiteral(&'a str),
     NonBase10Literal {
@@ -739,6 +777,13 @@ impl<'a> Pattern<'a> {
                 .iter()
                 .zip(fields_y.iter())
                 .all(|(p, q)| p.value.equivalent(&q.value)),
+            (Tuple(elems_x), Tuple(elems_y)) | (List(elems_x), List(elems_y)) => {
+                elems_x.len() == elems_y.len()
+                    && elems_x
+                        .iter()
+                        .zip(elems_y.iter())
+                        .all(|(p, q)| p.value.equivalent(&q.value))
+            }
             (RequiredField(x, inner_x), RequiredField(y, inner_y)) => {
                 x == y && inner_x.value.equivalent(&inner_y.value)
             }
@@ -767,6 +812,7 @@ impl<'a> Pattern<'a> {
             (FloatLiteral(x), FloatLiteral(y)) => x == y,
             (StrLiteral(x), StrLiteral(y)) => x == y,
             (Underscore(x), Underscore(y)) => x == y,
+            (ListRest(x), ListRest(y)) => x == y,
 
             // Space
             (SpaceBefore(x, _), SpaceBefore(y, _)) => x.equivalent(y),
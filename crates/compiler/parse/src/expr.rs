@@ -5,12 +5,12 @@ use crate::ast::{
 use crate::blankspace::{
     space0_after_e, space0_around_ee, space0_before_e, space0_before_optional_after, space0_e,
 };
-use crate::ident::{lowercase_ident, parse_ident, Ident};
+use crate::ident::{lowercase_ident, lowercase_ident_path, parse_ident, tuple_index, Ident};
 use crate::keyword;
 use crate::parser::{
     self, backtrackable, optional, sep_by1, sep_by1_e, specialize, specialize_ref, then,
     trailing_sep_by0, word1, word2, EExpect, EExpr, EIf, EInParens, ELambda, EList, ENumber,
-    EPattern, ERecord, EString, EType, EWhen, Either, ParseResult, Parser,
+    EPattern, ERecord, EString, EType, EWhen, Either, ParseResult, Parser, SyntaxError,
 };
 use crate::pattern::{loc_closure_param, loc_has_parser};
 use crate::state::State;
@@ -84,37 +84,85 @@ pub fn expr_help<'a>(min_indent: u32) -> impl Parser<'a, Expr<'a>, EExpr<'a>> {
 }
 
 fn loc_expr_in_parens_help<'a>(min_indent: u32) -> impl Parser<'a, Loc<Expr<'a>>, EInParens<'a>> {
-    move |arena, state| {
-        let (_, loc_expr, state) = loc_expr_in_parens_help_help(min_indent).parse(arena, state)?;
+    move |arena, state: State<'a>| {
+        let start = state.pos();
+        let (_, loc_expr, state) = skip_first!(
+            word1(b'(', EInParens::Open),
+            space0_around_ee(
+                specialize_ref(EInParens::Expr, move |arena, state: State<'a>| {
+                    // `(a, b <- task ...)` is a backpassing, but `(a, b)` is a tuple
+                    match parse_loc_expr(min_indent, arena, state.clone()) {
+                        Ok(good) => Ok(good),
+                        Err(fail) => parse_loc_expr_no_multi_backpassing(min_indent, arena, state)
+                            .map_err(|_| fail),
+                    }
+                }),
+                min_indent,
+                EInParens::IndentOpen,
+                EInParens::IndentEnd,
+            )
+        )
+        .parse(arena, state)?;
+
+        if let Ok((_, _, state)) = word1(b')', EInParens::End).parse(arena, state.clone()) {
+            return Ok((
+                MadeProgress,
+                Loc {
+                    region: loc_expr.region,
+                    value: Expr::ParensAround(arena.alloc(loc_expr.value)),
+                },
+                state,
+            ));
+        }
+
+        // more than one expression between the parentheses: this is a tuple
+        let (_, (rest, final_comments), state) = skip_first!(
+            word1(b',', EInParens::End),
+            and!(
+                trailing_sep_by0(
+                    word1(b',', EInParens::End),
+                    space0_before_optional_after(
+                        specialize_ref(EInParens::Expr, move |arena, state| {
+                            parse_loc_expr_no_multi_backpassing(min_indent, arena, state)
+                        }),
+                        min_indent,
+                        EInParens::IndentEnd,
+                        EInParens::IndentEnd,
+                    )
+                ),
+                // we use min_indent=0 because we want to parse incorrectly indented closing
+                // parentheses and later fix these up in the formatter.
+                space0_e(0, EInParens::IndentEnd)
+            )
+        )
+        .parse(arena, state)?;
+
+        if rest.is_empty() {
+            // `(a,)` is not a tuple
+            let fail = EInParens::Expr(arena.alloc(EExpr::Start(state.pos())), state.pos());
+            return Err((MadeProgress, fail, state));
+        }
+
+        let (_, _, state) = word1(b')', EInParens::End).parse(arena, state)?;
+
+        let mut elems = Vec::with_capacity_in(rest.len() + 1, arena);
+        elems.push(&*arena.alloc(loc_expr));
+        elems.extend(rest.into_iter().map(|elem| &*arena.alloc(elem)));
+
+        let tuple = Expr::Tuple(Collection::with_items_and_comments(
+            arena,
+            elems.into_bump_slice(),
+            final_comments,
+        ));
 
         Ok((
             MadeProgress,
-            Loc {
-                region: loc_expr.region,
-                value: Expr::ParensAround(arena.alloc(loc_expr.value)),
-            },
+            Loc::at(Region::new(start, state.pos()), tuple),
             state,
         ))
     }
 }
 
-fn loc_expr_in_parens_help_help<'a>(
-    min_indent: u32,
-) -> impl Parser<'a, Loc<Expr<'a>>, EInParens<'a>> {
-    between!(
-        word1(b'(', EInParens::Open),
-        space0_around_ee(
-            specialize_ref(EInParens::Expr, move |arena, state| parse_loc_expr(
-                min_indent, arena, state
-            )),
-            min_indent,
-            EInParens::IndentOpen,
-            EInParens::IndentEnd,
-        ),
-        word1(b')', EInParens::End)
-    )
-}
-
 fn loc_expr_in_parens_etc_help<'a>(min_indent: u32) -> impl Parser<'a, Loc<Expr<'a>>, EExpr<'a>> {
     move |arena, state: State<'a>| {
         let parser = loc!(and!(
@@ -183,7 +231,10 @@ fn record_field_access_chain<'a>() -> impl Parser<'a, Vec<'a, &'a str>, EExpr<'a
 fn record_field_access<'a>() -> impl Parser<'a, &'a str, EExpr<'a>> {
     skip_first!(
         word1(b'.', EExpr::Access),
-        specialize(|_, pos| EExpr::Access(pos), lowercase_ident())
+        specialize(
+            |_, pos| EExpr::Access(pos),
+            one_of!(lowercase_ident(), tuple_index())
+        )
     )
 }
 
@@ -209,6 +260,7 @@ fn parse_loc_term_or_underscore_or_conditional<'a>(
         loc!(underscore_expression()),
         loc!(record_literal_help(min_indent)),
         loc!(specialize(EExpr::List, list_literal_help(min_indent))),
+        loc!(crash_kw()),
         loc!(map_with_arena!(
             assign_or_destructure_identifier(),
             ident_to_expr
@@ -234,6 +286,7 @@ fn parse_loc_term_or_underscore<'a>(
         loc!(underscore_expression()),
         loc!(record_literal_help(min_indent)),
         loc!(specialize(EExpr::List, list_literal_help(min_indent))),
+        loc!(crash_kw()),
         loc!(map_with_arena!(
             assign_or_destructure_identifier(),
             ident_to_expr
@@ -256,6 +309,7 @@ fn parse_loc_term<'a>(
         loc!(specialize(EExpr::Lambda, closure_help(min_indent, options))),
         loc!(record_literal_help(min_indent)),
         loc!(specialize(EExpr::List, list_literal_help(min_indent))),
+        loc!(crash_kw()),
         loc!(map_with_arena!(
             assign_or_destructure_identifier(),
             ident_to_expr
@@ -264,6 +318,15 @@ fn parse_loc_term<'a>(
     .parse(arena, state)
 }
 
+fn crash_kw<'a>() -> impl Parser<'a, Expr<'a>, EExpr<'a>> {
+    move |arena: &'a Bump, state: State<'a>| {
+        let (_, _, next_state) =
+            parser::keyword_e(keyword::CRASH, EExpr::Crash).parse(arena, state)?;
+
+        Ok((MadeProgress, Expr::Crash, next_state))
+    }
+}
+
 fn underscore_expression<'a>() -> impl Parser<'a, Expr<'a>, EExpr<'a>> {
     move |arena: &'a Bump, state: State<'a>| {
         let start = state.pos();
@@ -355,6 +418,7 @@ fn parse_expr_start<'a>(
             when::expr_help(min_indent, options)
         )),
         loc!(specialize(EExpr::Expect, expect_help(min_indent, options))),
+        loc!(specialize(EExpr::Dbg, dbg_help(min_indent, options))),
         loc!(specialize(EExpr::Lambda, closure_help(min_indent, options))),
         loc!(move |a, s| parse_expr_operator_chain(min_indent, options, start_column, a, s)),
         fail_expr_start_e()
@@ -605,10 +669,10 @@ fn numeric_negate_expression<'a, T>(
 fn parse_defs_end<'a>(
     _options: ExprParseOptions,
     start_column: u32,
-    mut defs: Defs<'a>,
+    defs: &mut Defs<'a>,
     arena: &'a Bump,
     state: State<'a>,
-) -> ParseResult<'a, Defs<'a>, EExpr<'a>> {
+) -> ParseResult<'a, (), EExpr<'a>> {
     let min_indent = start_column;
 
     let mut global_state = state;
@@ -649,7 +713,7 @@ fn parse_defs_end<'a>(
                 match parse_expect.parse(arena, state) {
                     Err((_, _, _)) => {
                         // a hacky way to get expression-based error messages. TODO fix this
-                        return Ok((NoProgress, defs, initial));
+                        return Ok((NoProgress, (), initial));
                     }
 
                     Ok((_, expect_flavor, state)) => {
@@ -701,7 +765,7 @@ fn parse_defs_end<'a>(
             }
             Err((MadeProgress, _, _)) => {
                 // a hacky way to get expression-based error messages. TODO fix this
-                return Ok((NoProgress, defs, initial));
+                return Ok((NoProgress, (), initial));
             }
             Ok((_, loc_pattern, state)) => {
                 // First let's check whether this is an ability definition.
@@ -958,7 +1022,7 @@ fn parse_defs_end<'a>(
                         continue;
                     }
 
-                    _ => return Ok((MadeProgress, defs, initial)),
+                    _ => return Ok((MadeProgress, (), initial)),
                 }
             }
         }
@@ -968,15 +1032,15 @@ fn parse_defs_end<'a>(
 fn parse_defs_expr<'a>(
     options: ExprParseOptions,
     start_column: u32,
-    defs: Defs<'a>,
+    mut defs: Defs<'a>,
     arena: &'a Bump,
     state: State<'a>,
 ) -> ParseResult<'a, Expr<'a>, EExpr<'a>> {
     let min_indent = start_column;
 
-    match parse_defs_end(options, start_column, defs, arena, state) {
+    match parse_defs_end(options, start_column, &mut defs, arena, state) {
         Err(bad) => Err(bad),
-        Ok((_, def_state, state)) => {
+        Ok((_, (), state)) => {
             // this is no def, because there is no `=` or `:`; parse as an expr
             let parse_final_expr = space0_before_e(
                 move |a, s| parse_loc_expr(min_indent, a, s),
@@ -995,7 +1059,7 @@ fn parse_defs_expr<'a>(
                 Ok((_, loc_ret, state)) => {
                     return Ok((
                         MadeProgress,
-                        Expr::Defs(arena.alloc(def_state), arena.alloc(loc_ret)),
+                        Expr::Defs(arena.alloc(defs), arena.alloc(loc_ret)),
                         state,
                     ));
                 }
@@ -1842,6 +1906,15 @@ fn expr_to_pattern_help<'a>(arena: &'a Bump, expr: &Expr<'a>) -> Result<Pattern<
             Ok(Pattern::RecordDestructure(patterns))
         }
 
+        Expr::Tuple(elems) => {
+            let patterns = elems.map_items_result(arena, |loc_elem| {
+                let value = expr_to_pattern_help(arena, &loc_elem.value)?;
+                Ok(Loc::at(loc_elem.region, value))
+            })?;
+
+            Ok(Pattern::Tuple(patterns))
+        }
+
         &Expr::Float(string) => Ok(Pattern::FloatLiteral(string)),
         &Expr::Num(string) => Ok(Pattern::NumLiteral(string)),
         Expr::NonBase10Int {
@@ -1864,6 +1937,8 @@ fn expr_to_pattern_help<'a>(arena: &'a Bump, expr: &Expr<'a>) -> Result<Pattern<
         | Expr::If(_, _)
         | Expr::When(_, _)
         | Expr::Expect(_, _)
+        | Expr::Dbg(_)
+        | Expr::Crash
         | Expr::MalformedClosure
         | Expr::PrecedenceConflict { .. }
         | Expr::RecordUpdate { .. }
@@ -1871,7 +1946,7 @@ fn expr_to_pattern_help<'a>(arena: &'a Bump, expr: &Expr<'a>) -> Result<Pattern<
 
         Expr::Str(string) => Ok(Pattern::StrLiteral(*string)),
         Expr::SingleQuote(string) => Ok(Pattern::SingleQuote(*string)),
-        Expr::MalformedIdent(string, _problem) => Ok(Pattern::Malformed(string)),
+        Expr::MalformedIdent(string, _) | Expr::Malformed(string) => Ok(Pattern::Malformed(string)),
     }
 }
 
@@ -1919,6 +1994,8 @@ fn assigned_expr_field_to_pattern_help<'a>(
             arena.alloc(assigned_expr_field_to_pattern_help(arena, nested)?),
             spaces,
         ),
+        // only record updates have nested fields, and they are not patterns
+        AssignedField::Nested(_, _) => return Err(()),
         AssignedField::Malformed(string) => Pattern::Malformed(string),
     })
 }
@@ -1938,10 +2015,32 @@ pub fn toplevel_defs<'a>(min_indent: u32) -> impl Parser<'a, Defs<'a>, EExpr<'a>
         let mut output = Defs::default();
         let before = Slice::extend_new(&mut output.spaces, initial_space.iter().copied());
 
-        let (_, mut output, state) = parse_defs_end(options, start_column, output, arena, state)?;
+        let mut state = state;
+
+        let (final_space, state) = loop {
+            let chunk_start = state.clone();
 
-        let (_, final_space, state) =
-            space0_e(start_column, EExpr::IndentEnd).parse(arena, state)?;
+            let (error, error_state) =
+                match parse_defs_end(options, start_column, &mut output, arena, state) {
+                    Ok((_, (), state)) => {
+                        let (_, final_space, state) =
+                            space0_e(start_column, EExpr::IndentEnd).parse(arena, state)?;
+
+                        if !state.recovering || state.has_reached_end() {
+                            break (final_space, state);
+                        }
+
+                        (SyntaxError::NotEndOfFile(state.pos()), state)
+                    }
+                    Err((_, fail, state)) if state.recovering => {
+                        // the real position is filled in once we know where the def starts
+                        (SyntaxError::Expr(fail, Position::default()), state)
+                    }
+                    Err(fail) => return Err(fail),
+                };
+
+            state = recover_toplevel_def(arena, &mut output, &chunk_start, error, error_state);
+        };
 
         if !output.tags.is_empty() {
             // add surrounding whitespace
@@ -1959,6 +2058,184 @@ pub fn toplevel_defs<'a>(min_indent: u32) -> impl Parser<'a, Defs<'a>, EExpr<'a>
     }
 }
 
+/// Skip over a top-level def with a syntax error, leaving a malformed def in its place.
+/// The def is assumed to run until the next line that starts with a letter in the first
+/// column, which is where parsing resumes.
+fn recover_toplevel_def<'a>(
+    arena: &'a Bump,
+    defs: &mut Defs<'a>,
+    chunk_start: &State<'a>,
+    error: SyntaxError<'a>,
+    error_state: State<'a>,
+) -> State<'a> {
+    let bytes = chunk_start.original_bytes();
+    let starts_def = |offset: usize| {
+        (offset == 0 || bytes[offset - 1] == b'\n')
+            && matches!(bytes.get(offset), Some(byte) if byte.is_ascii_alphabetic())
+    };
+
+    // the broken def starts after the defs we did manage to parse
+    let mut lower = chunk_start.pos().offset as usize;
+
+    if let Some(region) = defs.regions.last() {
+        lower = lower.max(region.end().offset as usize);
+    }
+
+    let (error_pos, error) = match error {
+        SyntaxError::NotEndOfFile(pos) => {
+            lower = pos.offset as usize;
+            (lower, SyntaxError::NotEndOfFile(pos))
+        }
+        SyntaxError::Expr(fail, _) => {
+            let pos = (fail.position().offset as usize)
+                .max(error_state.pos().offset as usize)
+                .max(lower);
+
+            (pos, SyntaxError::Expr(fail, Position::default()))
+        }
+        other => (lower, other),
+    };
+
+    let def_start = match (lower..error_pos).rev().find(|offset| starts_def(*offset)) {
+        Some(offset) => offset,
+        // the error may point into the middle of a multi-byte character
+        None => char_start(bytes, skip_blank_lines(bytes, lower).min(error_pos)),
+    };
+
+    let resume = (error_pos.max(def_start + 1)..bytes.len())
+        .find(|offset| starts_def(*offset))
+        .unwrap_or(bytes.len());
+
+    let source = utf8_prefix(&bytes[def_start..resume]).trim_end();
+    let region = Region::new(
+        Position::new(def_start as u32),
+        Position::new((def_start + source.len()) as u32),
+    );
+
+    let error = match error {
+        SyntaxError::Expr(fail, _) => SyntaxError::Expr(fail, region.start()),
+        other => other,
+    };
+
+    let mut def_state = chunk_start.clone();
+    def_state.recovered = error_state.recovered;
+    let def_state = def_state.advance_lines(def_start - chunk_start.pos().offset as usize);
+
+    push_malformed_def(arena, defs, def_state.clone(), source, region);
+
+    def_state
+        .advance_lines(resume - def_start)
+        .recover_from(arena, error)
+}
+
+/// Push a def for the given broken source. If it starts out like `pattern =`, we keep
+/// the pattern, so that the rest of the module can still refer to what it defines.
+fn push_malformed_def<'a>(
+    arena: &'a Bump,
+    defs: &mut Defs<'a>,
+    state: State<'a>,
+    source: &'a str,
+    region: Region,
+) {
+    let min_indent = 0;
+    let end = region.end().offset;
+
+    let parse_pattern = space0_after_e(
+        crate::pattern::loc_pattern_help(min_indent),
+        min_indent,
+        EPattern::IndentEnd,
+    );
+
+    let opt_pattern = match parse_pattern.parse(arena, state) {
+        Ok((_, loc_pattern, state)) => match operator().parse(arena, state) {
+            Ok((_, BinOp::Assignment, state)) if state.pos().offset <= end => {
+                let body_start = match space0_e(min_indent, EExpr::IndentEnd).parse(arena, state) {
+                    Ok((_, _, state)) => state.pos().offset.min(end),
+                    Err(_) => end,
+                };
+
+                Some((loc_pattern, body_start))
+            }
+            _ => None,
+        },
+        Err(_) => None,
+    };
+
+    match opt_pattern {
+        Some((loc_pattern, body_start)) => {
+            let body_source = &source[(body_start - region.start().offset) as usize..];
+            let body_region = Region::new(Position::new(body_start), region.end());
+            let loc_body = Loc::at(body_region, Expr::Malformed(body_source));
+
+            match defs.last() {
+                // only join the body with an annotation of the same name; an annotation
+                // of `x` followed by a broken `y = …` are two separate defs
+                Some(Err(ValueDef::Annotation(ann_pattern, ann_type)))
+                    if ann_pattern
+                        .value
+                        .extract_spaces()
+                        .item
+                        .equivalent(&loc_pattern.value.extract_spaces().item) =>
+                {
+                    let value_def = ValueDef::AnnotatedBody {
+                        ann_pattern: arena.alloc(*ann_pattern),
+                        ann_type: arena.alloc(*ann_type),
+                        comment: None,
+                        body_pattern: arena.alloc(loc_pattern),
+                        body_expr: arena.alloc(loc_body),
+                    };
+
+                    let region = Region::span_across(&ann_pattern.region, &region);
+
+                    defs.replace_with_value_def(defs.tags.len() - 1, value_def, region)
+                }
+                _ => {
+                    let value_def = ValueDef::Body(arena.alloc(loc_pattern), arena.alloc(loc_body));
+
+                    defs.push_value_def(value_def, region, &[], &[])
+                }
+            }
+        }
+        None => defs.push_value_def(ValueDef::Malformed(source), region, &[], &[]),
+    }
+}
+
+/// The offset of the character that the byte at `offset` is part of
+fn char_start(bytes: &[u8], mut offset: usize) -> usize {
+    while offset > 0 && matches!(bytes.get(offset), Some(byte) if byte & 0b1100_0000 == 0b1000_0000)
+    {
+        offset -= 1;
+    }
+
+    offset
+}
+
+/// The longest prefix of `bytes` that is valid UTF-8. The parser works on bytes, so
+/// skipped source isn't guaranteed to be valid UTF-8 as a whole.
+fn utf8_prefix(bytes: &[u8]) -> &str {
+    match std::str::from_utf8(bytes) {
+        Ok(string) => string,
+        Err(error) => std::str::from_utf8(&bytes[..error.valid_up_to()]).unwrap_or_default(),
+    }
+}
+
+/// The offset of the first byte at or after `offset` that isn't whitespace or part of a comment.
+fn skip_blank_lines(bytes: &[u8], mut offset: usize) -> usize {
+    while let Some(byte) = bytes.get(offset) {
+        match byte {
+            b' ' | b'\t' | b'\r' | b'\n' => offset += 1,
+            b'#' => {
+                while !matches!(bytes.get(offset), None | Some(b'\n')) {
+                    offset += 1;
+                }
+            }
+            _ => break,
+        }
+    }
+
+    offset
+}
+
 // PARSER HELPERS
 
 fn closure_help<'a>(
@@ -2361,6 +2638,28 @@ fn expect_help<'a>(
     }
 }
 
+fn dbg_help<'a>(
+    min_indent: u32,
+    options: ExprParseOptions,
+) -> impl Parser<'a, Expr<'a>, EExpect<'a>> {
+    move |arena: &'a Bump, state: State<'a>| {
+        let (_, _, state) = parser::keyword_e(keyword::DBG, EExpect::Dbg).parse(arena, state)?;
+
+        // unlike `expect`, there is no continuation: `dbg x` evaluates to `x`
+        let (_, loc_expr, state) = space0_before_e(
+            specialize_ref(EExpect::Condition, move |arena, state| {
+                parse_loc_expr_with_options(min_indent, options, arena, state)
+            }),
+            min_indent,
+            EExpect::IndentCondition,
+        )
+        .parse(arena, state)
+        .map_err(|(_, f, s)| (MadeProgress, f, s))?;
+
+        Ok((MadeProgress, Expr::Dbg(arena.alloc(loc_expr)), state))
+    }
+}
+
 fn if_expr_help<'a>(
     min_indent: u32,
     options: ExprParseOptions,
@@ -2486,9 +2785,14 @@ fn list_literal_help<'a>(min_indent: u32) -> impl Parser<'a, Expr<'a>, EList<'a>
     move |arena, state| {
         let (_, elements, state) = collection_trailing_sep_e!(
             word1(b'[', EList::Open),
-            specialize_ref(
-                EList::Expr,
-                move |a, s| parse_loc_expr_no_multi_backpassing(min_indent, a, s)
+            recover_element(
+                specialize_ref(
+                    EList::Expr,
+                    move |a, s| parse_loc_expr_no_multi_backpassing(min_indent, a, s)
+                ),
+                b']',
+                |region, source| Loc::at(region, Expr::Malformed(source)),
+                EExpr::List,
             ),
             word1(b',', EList::End),
             word1(b']', EList::End),
@@ -2506,53 +2810,169 @@ fn list_literal_help<'a>(min_indent: u32) -> impl Parser<'a, Expr<'a>, EList<'a>
     }
 }
 
+/// When recovering from syntax errors, a collection element that fails to parse is
+/// skipped up to the next `,` or the closing bracket, and replaced by a malformed element.
+fn recover_element<'a, T, E: 'a>(
+    element: impl Parser<'a, T, E>,
+    closing: u8,
+    malformed: impl Fn(Region, &'a str) -> T,
+    to_expr_error: impl Fn(E, Position) -> EExpr<'a>,
+) -> impl Parser<'a, T, E> {
+    move |arena, state: State<'a>| {
+        let start = state.clone();
+
+        match element.parse(arena, state) {
+            Err((MadeProgress, fail, error_state)) if start.recovering => {
+                match skip_element(start.bytes(), closing) {
+                    Some(length) if length > 0 => {
+                        let bytes = &start.bytes()[..length];
+                        let source = utf8_prefix(bytes).trim_end();
+                        let region = start.len_region(source.len() as u32);
+                        let error =
+                            SyntaxError::Expr(to_expr_error(fail, start.pos()), start.pos());
+
+                        let mut state = start;
+                        state.recovered = error_state.recovered;
+                        let state = state.advance_lines(source.len()).recover_from(arena, error);
+
+                        Ok((MadeProgress, malformed(region, source), state))
+                    }
+                    _ => Err((MadeProgress, fail, error_state)),
+                }
+            }
+            result => result,
+        }
+    }
+}
+
+/// The length of a collection element: everything up to the next `,` or `closing`
+/// that isn't nested in brackets, a string, or a comment. Returns `None` if the
+/// collection is never closed, in which case there is nothing sensible to skip to.
+fn skip_element(bytes: &[u8], closing: u8) -> Option<usize> {
+    let mut nesting = std::vec::Vec::new();
+    let mut offset = 0;
+
+    while let Some(&byte) = bytes.get(offset) {
+        match byte {
+            b'(' => nesting.push(b')'),
+            b'[' => nesting.push(b']'),
+            b'{' => nesting.push(b'}'),
+            b')' | b']' | b'}' => match nesting.pop() {
+                Some(expected) if expected == byte => {}
+                None if byte == closing => return Some(offset),
+                _ => return None,
+            },
+            b',' if nesting.is_empty() => return Some(offset),
+            b'"' | b'\'' => {
+                // skip to the matching quote
+                offset += 1;
+
+                while let Some(&next) = bytes.get(offset) {
+                    match next {
+                        b'\\' => offset += 1,
+                        _ if next == byte => break,
+                        _ => {}
+                    }
+
+                    offset += 1;
+                }
+            }
+            b'#' => {
+                while !matches!(bytes.get(offset + 1), None | Some(b'\n')) {
+                    offset += 1;
+                }
+            }
+            _ => {}
+        }
+
+        offset += 1;
+    }
+
+    None
+}
+
 pub fn record_value_field<'a>(
     min_indent: u32,
+) -> impl Parser<'a, AssignedField<'a, Expr<'a>>, ERecord<'a>> {
+    record_field_help(min_indent, false)
+}
+
+/// A record field. When `allow_paths` is set (in record updates), the label may
+/// be a nested path like `settings.theme.color`.
+fn record_field_help<'a>(
+    min_indent: u32,
+    allow_paths: bool,
 ) -> impl Parser<'a, AssignedField<'a, Expr<'a>>, ERecord<'a>> {
     use AssignedField::*;
 
     move |arena, state: State<'a>| {
         // You must have a field name, e.g. "email"
-        let (progress, loc_label, state) =
-            specialize(|_, pos| ERecord::Field(pos), loc!(lowercase_ident()))
-                .parse(arena, state)?;
+        let (progress, path, state) = if allow_paths {
+            specialize(|_, pos| ERecord::Field(pos), lowercase_ident_path()).parse(arena, state)?
+        } else {
+            let (progress, loc_label, state) =
+                specialize(|_, pos| ERecord::Field(pos), loc!(lowercase_ident()))
+                    .parse(arena, state)?;
+
+            let path: &[_] = arena.alloc([loc_label]);
+
+            (progress, path, state)
+        };
         debug_assert_eq!(progress, MadeProgress);
 
+        // In a nested path like `settings.theme`, the value belongs to the last label
+        let (&loc_label, parents) = path.split_last().unwrap();
+        let nest = |field: AssignedField<'a, Expr<'a>>| {
+            parents
+                .iter()
+                .rev()
+                .fold(field, |field, &parent| Nested(parent, arena.alloc(field)))
+        };
+
         let (_, spaces, state) = space0_e(min_indent, ERecord::IndentColon).parse(arena, state)?;
 
         // Having a value is optional; both `{ email }` and `{ email: blah }` work.
         // (This is true in both literals and types.)
-        let (_, opt_loc_val, state) = optional(and!(
-            either!(
-                word1(b':', ERecord::Colon),
-                word1(b'?', ERecord::QuestionMark)
-            ),
-            space0_before_e(
-                specialize_ref(ERecord::Expr, move |a, s| {
-                    parse_loc_expr_no_multi_backpassing(min_indent, a, s)
-                }),
-                min_indent,
-                ERecord::IndentEnd,
-            )
+        let (_, opt_colon, state) = optional(either!(
+            word1(b':', ERecord::Colon),
+            word1(b'?', ERecord::QuestionMark)
         ))
         .parse(arena, state)?;
 
+        // Once we've seen the colon, the value is no longer optional.
+        let (opt_loc_val, state) = match opt_colon {
+            Some(colon) => {
+                let (_, loc_val, state) = space0_before_e(
+                    specialize_ref(ERecord::Expr, move |a, s| {
+                        parse_loc_expr_no_multi_backpassing(min_indent, a, s)
+                    }),
+                    min_indent,
+                    ERecord::IndentEnd,
+                )
+                .parse(arena, state)
+                .map_err(|(_, fail, state)| (MadeProgress, fail, state))?;
+
+                (Some((colon, loc_val)), state)
+            }
+            None => (None, state),
+        };
+
         let answer = match opt_loc_val {
             Some((Either::First(_), loc_val)) => {
-                RequiredValue(loc_label, spaces, arena.alloc(loc_val))
+                nest(RequiredValue(loc_label, spaces, arena.alloc(loc_val)))
             }
 
             Some((Either::Second(_), loc_val)) => {
-                OptionalValue(loc_label, spaces, arena.alloc(loc_val))
+                nest(OptionalValue(loc_label, spaces, arena.alloc(loc_val)))
             }
 
             // If no value was provided, record it as a Var.
             // Canonicalize will know what to do with a Var later.
             None => {
                 if !spaces.is_empty() {
-                    SpaceAfter(arena.alloc(LabelOnly(loc_label)), spaces)
+                    SpaceAfter(arena.alloc(nest(LabelOnly(loc_label))), spaces)
                 } else {
-                    LabelOnly(loc_label)
+                    nest(LabelOnly(loc_label))
                 }
             }
         };
@@ -2581,48 +3001,62 @@ fn record_help<'a>(
     ),
     ERecord<'a>,
 > {
-    skip_first!(
-        word1(b'{', ERecord::Open),
-        and!(
-            // You can optionally have an identifier followed by an '&' to
-            // make this a record update, e.g. { Foo.user & username: "blah" }.
-            optional(skip_second!(
-                space0_around_ee(
-                    // We wrap the ident in an Expr here,
-                    // so that we have a Spaceable value to work with,
-                    // and then in canonicalization verify that it's an Expr::Var
-                    // (and not e.g. an `Expr::Access`) and extract its string.
-                    loc!(record_updateable_identifier()),
-                    min_indent,
-                    ERecord::IndentEnd,
-                    ERecord::IndentAmpersand,
-                ),
-                word1(b'&', ERecord::Ampersand)
-            )),
-            loc!(skip_first!(
-                // We specifically allow space characters inside here, so that
-                // `{  }` can be successfully parsed as an empty record, and then
-                // changed by the formatter back into `{}`.
-                zero_or_more!(word1(b' ', ERecord::End)),
-                skip_second!(
-                    and!(
-                        trailing_sep_by0(
-                            word1(b',', ERecord::End),
-                            space0_before_optional_after(
-                                loc!(record_value_field(min_indent)),
-                                min_indent,
-                                ERecord::IndentEnd,
-                                ERecord::IndentEnd
-                            ),
+    move |arena, state: State<'a>| {
+        let original_state = state.clone();
+        let (_, _, state) = word1(b'{', ERecord::Open).parse(arena, state)?;
+
+        // You can optionally have an identifier followed by an '&' to
+        // make this a record update, e.g. { Foo.user & username: "blah" }.
+        let (_, opt_update, state) = optional(skip_second!(
+            space0_around_ee(
+                // We wrap the ident in an Expr here,
+                // so that we have a Spaceable value to work with,
+                // and then in canonicalization verify that it's an Expr::Var
+                // (and not e.g. an `Expr::Access`) and extract its string.
+                loc!(record_updateable_identifier()),
+                min_indent,
+                ERecord::IndentEnd,
+                ERecord::IndentAmpersand,
+            ),
+            word1(b'&', ERecord::Ampersand)
+        ))
+        .parse(arena, state)?;
+
+        // Only record updates may set nested fields, e.g. { model & settings.theme: Dark }
+        let allow_paths = opt_update.is_some();
+
+        let (_, loc_fields, state) = loc!(skip_first!(
+            // We specifically allow space characters inside here, so that
+            // `{  }` can be successfully parsed as an empty record, and then
+            // changed by the formatter back into `{}`.
+            zero_or_more!(word1(b' ', ERecord::End)),
+            skip_second!(
+                and!(
+                    trailing_sep_by0(
+                        word1(b',', ERecord::End),
+                        space0_before_optional_after(
+                            loc!(recover_element(
+                                record_field_help(min_indent, allow_paths),
+                                b'}',
+                                |_, source| AssignedField::Malformed(source),
+                                EExpr::Record,
+                            )),
+                            min_indent,
+                            ERecord::IndentEnd,
+                            ERecord::IndentEnd
                         ),
-                        // Allow outdented closing braces
-                        space0_e(0, ERecord::IndentEnd)
                     ),
-                    word1(b'}', ERecord::End)
-                )
-            ))
-        )
-    )
+                    // Allow outdented closing braces
+                    space0_e(0, ERecord::IndentEnd)
+                ),
+                word1(b'}', ERecord::End)
+            )
+        ))
+        .parse(arena, state)
+        .map_err(|(_, fail, _)| (MadeProgress, fail, original_state))?;
+
+        Ok((MadeProgress, (opt_update, loc_fields), state))
+    }
 }
 
 fn record_literal_help<'a>(min_indent: u32) -> impl Parser<'a, Expr<'a>, EExpr<'a>> {
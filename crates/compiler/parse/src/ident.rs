@@ -3,7 +3,7 @@ use crate::parser::{BadInputError, EExpr, ParseResult, Parser};
 use crate::state::State;
 use bumpalo::collections::vec::Vec;
 use bumpalo::Bump;
-use roc_region::all::Position;
+use roc_region::all::{Loc, Position};
 
 /// A tag, for example. Must start with an uppercase letter
 /// and then contain only letters and numbers afterwards - no dots allowed!
@@ -43,7 +43,7 @@ pub enum Ident<'a> {
         module_name: &'a str,
         parts: &'a [&'a str],
     },
-    /// .foo { foo: 42 }
+    /// .foo { foo: 42 } or .0 (42, "a")
     AccessorFunction(&'a str),
     /// .Foo or foo. or something like foo.Bar
     Malformed(&'a str, BadIdent),
@@ -97,6 +97,41 @@ pub fn lowercase_ident<'a>() -> impl Parser<'a, &'a str, ()> {
     }
 }
 
+/// A dot-separated path of lowercase identifiers, e.g. `settings.theme.color`
+/// in the nested record update `{ model & settings.theme.color: Red }`.
+///
+/// The path is returned as its segments, each with its own region.
+pub fn lowercase_ident_path<'a>() -> impl Parser<'a, &'a [Loc<&'a str>], ()> {
+    move |arena, state: State<'a>| {
+        let mut segments = Vec::with_capacity_in(1, arena);
+        let (_, segment, mut state) = loc!(lowercase_ident()).parse(arena, state)?;
+        segments.push(segment);
+
+        while state.bytes().first() == Some(&b'.') {
+            match loc!(lowercase_ident()).parse(arena, state.clone().advance(1)) {
+                Ok((_, segment, next_state)) => {
+                    segments.push(segment);
+                    state = next_state;
+                }
+                Err((_, (), _)) => return Err((MadeProgress, (), state.advance(1))),
+            }
+        }
+
+        Ok((MadeProgress, segments.into_bump_slice(), state))
+    }
+}
+
+/// A tuple index, e.g. the `0` in `(1, 2).0`
+pub fn tuple_index<'a>() -> impl Parser<'a, &'a str, ()> {
+    move |_, state: State<'a>| match chomp_tuple_index(state.bytes()) {
+        Err(progress) => Err((progress, (), state)),
+        Ok(index) => {
+            let width = index.len();
+            Ok((MadeProgress, index, state.advance(width)))
+        }
+    }
+}
+
 pub fn tag_name<'a>() -> impl Parser<'a, &'a str, ()> {
     move |arena, state: State<'a>| uppercase_ident().parse(arena, state)
 }
@@ -233,6 +268,28 @@ fn chomp_uppercase_part(buffer: &[u8]) -> Result<&str, Progress> {
     chomp_part(|c: char| c.is_uppercase(), buffer)
 }
 
+/// A tuple index, e.g. the `0` in `.0`
+fn chomp_tuple_index(buffer: &[u8]) -> Result<&str, Progress> {
+    use encode_unicode::CharExt;
+
+    let chomped = buffer.iter().take_while(|b| b.is_ascii_digit()).count();
+
+    if chomped == 0 {
+        return Err(NoProgress);
+    }
+
+    match char::from_utf8_slice_start(&buffer[chomped..]) {
+        // something like `.0a` or `.0_` is not a valid tuple index
+        Ok((ch, _)) if ch.is_alphabetic() || ch == '_' => Err(MadeProgress),
+        _ => Ok(unsafe { std::str::from_utf8_unchecked(&buffer[..chomped]) }),
+    }
+}
+
+/// Whether this field name is actually a tuple index, like the `0` in `t.0`
+pub fn is_tuple_index(name: &str) -> bool {
+    name.bytes().all(|b| b.is_ascii_digit())
+}
+
 #[inline(always)]
 fn chomp_part<F>(leading_is_good: F, buffer: &[u8]) -> Result<&str, Progress>
 where
@@ -268,12 +325,12 @@ where
     }
 }
 
-/// a `.foo` accessor function
+/// a `.foo` or `.0` accessor function
 fn chomp_accessor(buffer: &[u8], pos: Position) -> Result<&str, BadIdent> {
     // assumes the leading `.` has been chomped already
     use encode_unicode::CharExt;
 
-    match chomp_lowercase_part(buffer) {
+    match chomp_lowercase_part(buffer).or_else(|_| chomp_tuple_index(buffer)) {
         Ok(name) => {
             let chomped = name.len();
 
@@ -386,6 +443,11 @@ fn chomp_identifier_chain<'a>(
         }
 
         match chomp_access_chain(&buffer[chomped..], &mut parts) {
+            // A module can't contain a tuple index, e.g. `Foo.0`
+            Ok(_) if first_is_uppercase && is_tuple_index(parts[0]) => Err((
+                chomped as u32 + 1,
+                BadIdent::WeirdDotQualified(pos.bump_column(chomped as u32 + 1)),
+            )),
             Ok(width) => {
                 chomped += width as usize;
 
@@ -504,19 +566,21 @@ fn chomp_access_chain<'a>(buffer: &'a [u8], parts: &mut Vec<'a, &'a str>) -> Res
 
     while let Some(b'.') = buffer.get(chomped) {
         match &buffer.get(chomped + 1..) {
-            Some(slice) => match chomp_lowercase_part(slice) {
-                Ok(name) => {
-                    let value = unsafe {
-                        std::str::from_utf8_unchecked(
-                            &buffer[chomped + 1..chomped + 1 + name.len()],
-                        )
-                    };
-                    parts.push(value);
-
-                    chomped += name.len() + 1;
+            Some(slice) => {
+                match chomp_lowercase_part(slice).or_else(|_| chomp_tuple_index(slice)) {
+                    Ok(name) => {
+                        let value = unsafe {
+                            std::str::from_utf8_unchecked(
+                                &buffer[chomped + 1..chomped + 1 + name.len()],
+                            )
+                        };
+                        parts.push(value);
+
+                        chomped += name.len() + 1;
+                    }
+                    Err(_) => return Err(chomped as u32 + 1),
                 }
-                Err(_) => return Err(chomped as u32 + 1),
-            },
+            }
             None => return Err(chomped as u32 + 1),
         }
     }
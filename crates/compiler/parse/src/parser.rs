@@ -55,12 +55,12 @@ pub enum SyntaxError<'a> {
     ReservedKeyword(Region),
     ArgumentsBeforeEquals(Region),
     NotYetImplemented(String),
-    Todo,
+    Todo(Position),
     Type(EType<'a>),
     Pattern(EPattern<'a>),
     Expr(EExpr<'a>, Position),
     Header(EHeader<'a>),
-    Space(BadInputError),
+    Space(BadInputError, Position),
     NotEndOfFile(Position),
 }
 pub trait SpaceProblem {
@@ -107,6 +107,7 @@ impl_space_problem! {
     EWhen<'a>,
     EAbility<'a>,
     PInParens<'a>,
+    PList<'a>,
     PRecord<'a>
 }
 
@@ -128,6 +129,31 @@ pub enum EHeader<'a> {
     IndentStart(Position),
 }
 
+impl<'a> EHeader<'a> {
+    /// Roughly where this error is. For errors in the `exposes`, `imports` and `generates`
+    /// sections, that's where in the section it went wrong; for the other sections, the
+    /// error of that section may be more precise.
+    pub fn position(&self) -> Position {
+        use EHeader::*;
+
+        match self {
+            Exposes(exposes, _) => exposes.position(),
+            Imports(imports, _) => imports.position(),
+            Generates(generates, _) => generates.position(),
+            GeneratesWith(generates_with, _) => generates_with.position(),
+            Provides(_, pos)
+            | Requires(_, pos)
+            | Packages(_, pos)
+            | Space(_, pos)
+            | Start(pos)
+            | ModuleName(pos)
+            | AppName(_, pos)
+            | PlatformName(_, pos)
+            | IndentStart(pos) => *pos,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EProvides<'a> {
     Provides(Position),
@@ -158,6 +184,24 @@ pub enum EExposes {
     Space(BadInputError, Position),
 }
 
+impl EExposes {
+    pub fn position(&self) -> Position {
+        use EExposes::*;
+
+        match self {
+            Exposes(pos)
+            | Open(pos)
+            | IndentExposes(pos)
+            | IndentListStart(pos)
+            | IndentListEnd(pos)
+            | ListStart(pos)
+            | ListEnd(pos)
+            | Identifier(pos)
+            | Space(_, pos) => *pos,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ERequires<'a> {
     Requires(Position),
@@ -233,6 +277,32 @@ pub enum EImports {
     SetEnd(Position),
 }
 
+impl EImports {
+    pub fn position(&self) -> Position {
+        use EImports::*;
+
+        match self {
+            Open(pos)
+            | Imports(pos)
+            | IndentImports(pos)
+            | IndentListStart(pos)
+            | IndentListEnd(pos)
+            | ListStart(pos)
+            | ListEnd(pos)
+            | Identifier(pos)
+            | ExposingDot(pos)
+            | ShorthandDot(pos)
+            | Shorthand(pos)
+            | ModuleName(pos)
+            | Space(_, pos)
+            | IndentSetStart(pos)
+            | IndentSetEnd(pos)
+            | SetStart(pos)
+            | SetEnd(pos) => *pos,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EGenerates {
     Open(Position),
@@ -244,6 +314,22 @@ pub enum EGenerates {
     IndentTypeEnd(Position),
 }
 
+impl EGenerates {
+    pub fn position(&self) -> Position {
+        use EGenerates::*;
+
+        match self {
+            Open(pos)
+            | Generates(pos)
+            | IndentGenerates(pos)
+            | Identifier(pos)
+            | Space(_, pos)
+            | IndentTypeStart(pos)
+            | IndentTypeEnd(pos) => *pos,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EGeneratesWith {
     Open(Position),
@@ -257,6 +343,24 @@ pub enum EGeneratesWith {
     Space(BadInputError, Position),
 }
 
+impl EGeneratesWith {
+    pub fn position(&self) -> Position {
+        use EGeneratesWith::*;
+
+        match self {
+            Open(pos)
+            | With(pos)
+            | IndentWith(pos)
+            | IndentListStart(pos)
+            | IndentListEnd(pos)
+            | ListStart(pos)
+            | ListEnd(pos)
+            | Identifier(pos)
+            | Space(_, pos) => *pos,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BadInputError {
     HasTab,
@@ -314,6 +418,26 @@ impl<'a> SyntaxError<'a> {
     ) -> FileError<'a, SyntaxError<'a>> {
         self.into_source_error(state).into_file_error(filename)
     }
+
+    /// Roughly where the error occurred, if we know.
+    pub fn region(&self) -> Option<Region> {
+        use SyntaxError::*;
+
+        match self {
+            Unexpected(region)
+            | Eof(region)
+            | ReservedKeyword(region)
+            | ArgumentsBeforeEquals(region) => Some(*region),
+            Expr(expr, _) => Some(Region::from_pos(expr.position())),
+            Type(typ) => Some(Region::from_pos(typ.position())),
+            Pattern(pattern) => Some(Region::from_pos(pattern.position())),
+            Header(header) => Some(Region::from_pos(header.position())),
+            NotEndOfFile(pos) | Space(_, pos) | Todo(pos) => Some(Region::from_pos(*pos)),
+            OutdentedTooFar | TooManyLines | InvalidPattern | BadUtf8 | NotYetImplemented(_) => {
+                None
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -351,9 +475,11 @@ pub enum EExpr<'a> {
     If(EIf<'a>, Position),
 
     Expect(EExpect<'a>, Position),
+    Dbg(EExpect<'a>, Position),
 
     Lambda(ELambda<'a>, Position),
     Underscore(Position),
+    Crash(Position),
 
     InParens(EInParens<'a>, Position),
     Record(ERecord<'a>, Position),
@@ -366,6 +492,58 @@ pub enum EExpr<'a> {
     IndentEnd(Position),
 }
 
+impl<'a> EExpr<'a> {
+    /// The position of this error. For nested errors (e.g. in a `when`), this is
+    /// where the enclosing expression starts.
+    pub fn position(&self) -> Position {
+        use EExpr::*;
+
+        match self {
+            Start(pos)
+            | End(pos)
+            | BadExprEnd(pos)
+            | Space(_, pos)
+            | Dot(pos)
+            | Access(pos)
+            | UnaryNot(pos)
+            | UnaryNegate(pos)
+            | BadOperator(_, pos)
+            | DefMissingFinalExpr(pos)
+            | DefMissingFinalExpr2(_, pos)
+            | Type(_, pos)
+            | Pattern(_, pos)
+            | Ability(_, pos)
+            | IndentDefBody(pos)
+            | IndentEquals(pos)
+            | IndentAnnotation(pos)
+            | Equals(pos)
+            | Colon(pos)
+            | DoubleColon(pos)
+            | Ident(pos)
+            | ElmStyleFunction(_, pos)
+            | MalformedPattern(pos)
+            | QualifiedTag(pos)
+            | BackpassComma(pos)
+            | BackpassArrow(pos)
+            | When(_, pos)
+            | If(_, pos)
+            | Expect(_, pos)
+            | Dbg(_, pos)
+            | Lambda(_, pos)
+            | Underscore(pos)
+            | Crash(pos)
+            | InParens(_, pos)
+            | Record(_, pos)
+            | Str(_, pos)
+            | SingleQuote(_, pos)
+            | Number(_, pos)
+            | List(_, pos)
+            | IndentStart(pos)
+            | IndentEnd(pos) => *pos,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ENumber {
     End,
@@ -508,6 +686,7 @@ pub enum EIf<'a> {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EExpect<'a> {
     Space(BadInputError, Position),
+    Dbg(Position),
     Expect(Position),
     Condition(&'a EExpr<'a>, Position),
     Continuation(&'a EExpr<'a>, Position),
@@ -524,6 +703,7 @@ pub enum EPattern<'a> {
     Space(BadInputError, Position),
 
     PInParens(PInParens<'a>, Position),
+    List(PList<'a>, Position),
     NumLiteral(ENumber, Position),
 
     IndentStart(Position),
@@ -531,6 +711,28 @@ pub enum EPattern<'a> {
     AsIndentStart(Position),
 }
 
+impl<'a> EPattern<'a> {
+    /// Roughly where this error is. For nested errors (e.g. in a record pattern), the
+    /// inner error may be more precise.
+    pub fn position(&self) -> Position {
+        use EPattern::*;
+
+        match self {
+            Record(_, pos)
+            | Underscore(pos)
+            | Start(pos)
+            | End(pos)
+            | Space(_, pos)
+            | PInParens(_, pos)
+            | List(_, pos)
+            | NumLiteral(_, pos)
+            | IndentStart(pos)
+            | IndentEnd(pos)
+            | AsIndentStart(pos) => *pos,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PRecord<'a> {
     End(Position),
@@ -551,6 +753,19 @@ pub enum PRecord<'a> {
     IndentEnd(Position),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PList<'a> {
+    End(Position),
+    Open(Position),
+
+    Rest(Position),
+    Pattern(&'a EPattern<'a>, Position),
+
+    Space(BadInputError, Position),
+    IndentOpen(Position),
+    IndentEnd(Position),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PInParens<'a> {
     End(Position),
@@ -587,6 +802,35 @@ pub enum EType<'a> {
     TAsIndentStart(Position),
 }
 
+impl<'a> EType<'a> {
+    /// Roughly where this error is. For nested errors (e.g. in a record type), the
+    /// inner error may be more precise.
+    pub fn position(&self) -> Position {
+        use EType::*;
+
+        match self {
+            Space(_, pos)
+            | TRecord(_, pos)
+            | TTagUnion(_, pos)
+            | TInParens(_, pos)
+            | TApply(_, pos)
+            | TInlineAlias(_, pos)
+            | TBadTypeVariable(pos)
+            | TWildcard(pos)
+            | TInferred(pos)
+            | TStart(pos)
+            | TEnd(pos)
+            | TFunctionArgument(pos)
+            | TWhereBar(pos)
+            | THasClause(pos)
+            | TAbilityImpl(_, pos)
+            | TIndentStart(pos)
+            | TIndentEnd(pos)
+            | TAsIndentStart(pos) => *pos,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ETypeRecord<'a> {
     End(Position),
@@ -1,10 +1,12 @@
-use crate::ast::{Has, Pattern};
-use crate::blankspace::{space0_around_ee, space0_before_e, space0_e};
+use crate::ast::{Collection, Has, Pattern};
+use crate::blankspace::{
+    space0_around_ee, space0_before_e, space0_before_optional_after, space0_e,
+};
 use crate::ident::{lowercase_ident, parse_ident, Ident};
 use crate::parser::Progress::{self, *};
 use crate::parser::{
-    backtrackable, optional, specialize, specialize_ref, then, word1, EPattern, PInParens, PRecord,
-    ParseResult, Parser,
+    backtrackable, optional, specialize, specialize_ref, then, trailing_sep_by0, word1, word2,
+    EPattern, PInParens, PList, PRecord, ParseResult, Parser,
 };
 use crate::state::State;
 use bumpalo::collections::string::String;
@@ -59,6 +61,7 @@ pub fn loc_pattern_help<'a>(min_indent: u32) -> impl Parser<'a, Loc<Pattern<'a>>
             EPattern::Record,
             crate::pattern::record_pattern_help(min_indent)
         )),
+        loc!(specialize(EPattern::List, list_pattern_help(min_indent))),
         loc!(number_pattern_help()),
         loc!(string_pattern_help()),
         loc!(single_quote_pattern_help()),
@@ -139,6 +142,7 @@ fn loc_parse_tag_pattern_arg<'a>(
             EPattern::Record,
             crate::pattern::record_pattern_help(min_indent)
         )),
+        loc!(specialize(EPattern::List, list_pattern_help(min_indent))),
         loc!(string_pattern_help()),
         loc!(single_quote_pattern_help()),
         loc!(number_pattern_help())
@@ -149,19 +153,111 @@ fn loc_parse_tag_pattern_arg<'a>(
 fn loc_pattern_in_parens_help<'a>(
     min_indent: u32,
 ) -> impl Parser<'a, Loc<Pattern<'a>>, PInParens<'a>> {
-    between!(
-        word1(b'(', PInParens::Open),
-        space0_around_ee(
-            move |arena, state| specialize_ref(PInParens::Pattern, loc_pattern_help(min_indent))
-                .parse(arena, state),
+    move |arena, state: State<'a>| {
+        let start = state.pos();
+        let (_, first, state) = skip_first!(
+            word1(b'(', PInParens::Open),
+            space0_around_ee(
+                specialize_ref(PInParens::Pattern, loc_pattern_help(min_indent)),
+                min_indent,
+                PInParens::IndentOpen,
+                PInParens::IndentEnd,
+            )
+        )
+        .parse(arena, state)?;
+
+        if let Ok((_, _, state)) = word1(b')', PInParens::End).parse(arena, state.clone()) {
+            return Ok((MadeProgress, first, state));
+        }
+
+        // more than one pattern between the parentheses: this is a tuple pattern
+        let comma_pos = state.pos();
+        let (_, (rest, final_comments), state) = skip_first!(
+            word1(b',', PInParens::End),
+            and!(
+                trailing_sep_by0(
+                    word1(b',', PInParens::End),
+                    space0_before_optional_after(
+                        specialize_ref(PInParens::Pattern, loc_pattern_help(min_indent)),
+                        min_indent,
+                        PInParens::IndentEnd,
+                        PInParens::IndentEnd,
+                    )
+                ),
+                space0_e(0, PInParens::IndentEnd)
+            )
+        )
+        .parse(arena, state)
+        .map_err(|(progress, fail, state)| match fail {
+            // nothing but whitespace after the comma: the comma itself is the problem
+            PInParens::IndentEnd(_) => (progress, PInParens::End(comma_pos), state),
+            _ => (progress, fail, state),
+        })?;
+
+        if rest.is_empty() {
+            // `(a,)` is not a tuple pattern
+            return Err((MadeProgress, PInParens::End(comma_pos), state));
+        }
+
+        let (_, _, state) = word1(b')', PInParens::End).parse(arena, state)?;
+
+        let mut elems = Vec::with_capacity_in(rest.len() + 1, arena);
+        elems.push(first);
+        elems.extend(rest);
+
+        let tuple = Pattern::Tuple(Collection::with_items_and_comments(
+            arena,
+            elems.into_bump_slice(),
+            final_comments,
+        ));
+
+        Ok((
+            MadeProgress,
+            Loc::at(Region::new(start, state.pos()), tuple),
+            state,
+        ))
+    }
+}
+
+fn list_pattern_help<'a>(min_indent: u32) -> impl Parser<'a, Pattern<'a>, PList<'a>> {
+    move |arena, state| {
+        let (_, elems, state) = collection_trailing_sep_e!(
+            word1(b'[', PList::Open),
+            list_element_pattern(min_indent),
+            word1(b',', PList::End),
+            word1(b']', PList::End),
             min_indent,
-            PInParens::IndentOpen,
-            PInParens::IndentEnd,
-        ),
-        word1(b')', PInParens::End)
+            PList::Open,
+            PList::IndentEnd,
+            Pattern::SpaceBefore
+        )
+        .parse(arena, state)?;
+
+        Ok((MadeProgress, Pattern::List(elems), state))
+    }
+}
+
+fn list_element_pattern<'a>(min_indent: u32) -> impl Parser<'a, Loc<Pattern<'a>>, PList<'a>> {
+    one_of!(
+        loc!(list_rest_pattern()),
+        specialize_ref(PList::Pattern, loc_pattern_help(min_indent)),
     )
 }
 
+/// The rest of a list pattern, e.g. `..` or `..rest`
+fn list_rest_pattern<'a>() -> impl Parser<'a, Pattern<'a>, PList<'a>> {
+    move |arena: &'a Bump, state: State<'a>| {
+        let (_, _, state) = word2(b'.', b'.', PList::Open).parse(arena, state)?;
+
+        let pos = state.pos();
+        let (_, name, state) =
+            optional(specialize(move |_, _| PList::Rest(pos), lowercase_ident()))
+                .parse(arena, state)?;
+
+        Ok((MadeProgress, Pattern::ListRest(name), state))
+    }
+}
+
 fn number_pattern_help<'a>() -> impl Parser<'a, Pattern<'a>, EPattern<'a>> {
     specialize(
         EPattern::NumLiteral,
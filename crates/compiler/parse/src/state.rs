@@ -1,3 +1,5 @@
+use crate::parser::SyntaxError;
+use bumpalo::Bump;
 use roc_region::all::{Position, Region};
 use std::fmt;
 
@@ -14,6 +16,20 @@ pub struct State<'a> {
 
     /// Position of the start of the current line
     pub(crate) line_start: Position,
+
+    /// Whether to recover from syntax errors where possible (see [`State::with_error_recovery`])
+    pub(crate) recovering: bool,
+
+    /// The syntax errors recovered from so far, most recent first
+    pub(crate) recovered: Option<&'a Recovered<'a>>,
+}
+
+/// A syntax error the parser recovered from. These form a persistent list, so a
+/// parser that backtracks also forgets the errors recovered from along the way.
+#[derive(Debug)]
+pub(crate) struct Recovered<'a> {
+    error: SyntaxError<'a>,
+    previous: Option<&'a Recovered<'a>>,
 }
 
 impl<'a> State<'a> {
@@ -22,7 +38,40 @@ impl<'a> State<'a> {
             original_bytes: bytes,
             offset: 0,
             line_start: Position::zero(),
+            recovering: false,
+            recovered: None,
+        }
+    }
+
+    /// Instead of stopping at the first syntax error, skip over the broken top-level
+    /// def or collection element, leave a malformed node in its place, and continue.
+    /// The errors are available from [`State::recovered_errors`] once parsing is done.
+    pub fn with_error_recovery(mut self) -> Self {
+        self.recovering = true;
+        self
+    }
+
+    /// Remember a syntax error that the parser recovered from.
+    pub(crate) fn recover_from(mut self, arena: &'a Bump, error: SyntaxError<'a>) -> Self {
+        self.recovered = Some(arena.alloc(Recovered {
+            error,
+            previous: self.recovered,
+        }));
+        self
+    }
+
+    /// The syntax errors recovered from so far, in the order they were encountered.
+    pub fn recovered_errors(&self) -> std::vec::Vec<&'a SyntaxError<'a>> {
+        let mut errors = std::vec::Vec::new();
+        let mut next = self.recovered;
+
+        while let Some(recovered) = next {
+            errors.push(&recovered.error);
+            next = recovered.previous;
         }
+
+        errors.reverse();
+        errors
     }
 
     pub fn original_bytes(&self) -> &'a [u8] {
@@ -37,6 +86,19 @@ impl<'a> State<'a> {
         self.pos().offset - self.line_start.offset
     }
 
+    /// Advance the state by a given offset, keeping track of any newlines skipped over
+    pub(crate) fn advance_lines(mut self, offset: usize) -> State<'a> {
+        for _ in 0..offset {
+            if self.bytes()[0] == b'\n' {
+                self = self.advance_newline();
+            } else {
+                self.offset += 1;
+            }
+        }
+
+        self
+    }
+
     /// Mutably advance the state by a given offset
     #[inline(always)]
     pub(crate) fn advance_mut(&mut self, offset: usize) {
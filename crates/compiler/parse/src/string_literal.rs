@@ -123,7 +123,7 @@ pub fn parse_single_quote<'a>() -> impl Parser<'a, &'a str, EString<'a>> {
 fn consume_indent<'a>(
     mut state: State<'a>,
     mut indent: u32,
-) -> Result<State, (Progress, EString<'a>, State<'a>)> {
+) -> Result<State<'a>, (Progress, EString<'a>, State<'a>)> {
     while indent > 0 {
         match state.bytes().first() {
             Some(b' ') => {
@@ -146,6 +146,120 @@ fn consume_indent<'a>(
     Ok(state)
 }
 
+/// The number of spaces at the start of a line.
+fn leading_spaces(string: &str) -> usize {
+    string.bytes().take_while(|&byte| byte == b' ').count()
+}
+
+/// The contents of a block string. The parser keeps a block string the way it was written,
+/// so that it can be formatted back unchanged; only the indentation of the line it starts on
+/// is left out. The string itself doesn't have
+///
+/// - the indentation that all of its lines share, including the line of the closing `"""`
+/// - the line break right after the opening `"""`, and the one before the closing `"""`
+///
+/// So this is the string `"SELECT *\n  FROM users"`:
+///
+/// ```roc
+/// query =
+///     """
+///     SELECT *
+///       FROM users
+///     """
+/// ```
+pub fn block_string_segments<'a>(lines: &[&[StrSegment<'a>]]) -> std::vec::Vec<StrSegment<'a>> {
+    use StrSegment::*;
+
+    let mut split_lines = vec![std::vec::Vec::new()];
+
+    for segment in lines.iter().flat_map(|segments| segments.iter()) {
+        match segment {
+            Plaintext(string) => {
+                for (index, piece) in string.split('\n').enumerate() {
+                    if index > 0 {
+                        split_lines.push(std::vec::Vec::new());
+                    }
+
+                    if !piece.is_empty() {
+                        split_lines.last_mut().unwrap().push(Plaintext(piece));
+                    }
+                }
+            }
+            other => split_lines.last_mut().unwrap().push(*other),
+        }
+    }
+
+    let indent_of = |line: &[StrSegment]| match line.first() {
+        Some(Plaintext(string)) => leading_spaces(string),
+        _ => 0,
+    };
+    let is_blank = |line: &[StrSegment]| match line {
+        [] => true,
+        [Plaintext(string)] => leading_spaces(string) == string.len(),
+        _ => false,
+    };
+
+    let mut lines = split_lines.as_slice();
+    let mut starts_on_own_line = false;
+    let mut closing_indent = None;
+
+    if lines.len() > 1 {
+        if is_blank(&lines[0]) {
+            starts_on_own_line = true;
+            lines = &lines[1..];
+        }
+
+        if let [rest @ .., last] = lines {
+            if is_blank(last) {
+                closing_indent = Some(indent_of(last));
+                lines = rest;
+            }
+        }
+    }
+
+    // The text right after the opening `"""` has no indentation of its own
+    let indented_lines = if starts_on_own_line {
+        lines
+    } else {
+        lines.get(1..).unwrap_or_default()
+    };
+
+    let common_indent = indented_lines
+        .iter()
+        .filter(|line| !is_blank(line))
+        .map(|line| indent_of(line))
+        .chain(closing_indent)
+        .min()
+        .unwrap_or(0);
+
+    let mut segments = std::vec::Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        if index > 0 {
+            segments.push(Plaintext("\n"));
+        }
+
+        let strip = if index == 0 && !starts_on_own_line {
+            0
+        } else {
+            indent_of(line).min(common_indent)
+        };
+
+        for (position, segment) in line.iter().enumerate() {
+            match segment {
+                Plaintext(string) if position == 0 => {
+                    if string.len() > strip {
+                        segments.push(Plaintext(&string[strip..]));
+                    }
+                }
+                other => segments.push(*other),
+            }
+        }
+    }
+
+    segments
+}
+
 fn utf8<'a>(
     state: State<'a>,
     string_bytes: &'a [u8],
@@ -168,19 +282,21 @@ pub fn parse<'a>() -> impl Parser<'a, StrLiteral<'a>, EString<'a>> {
     move |arena: &'a Bump, mut state: State<'a>| {
         let is_multiline;
 
-        let indent = state.column();
+        // The lines of a block string must be indented at least as much as the line it starts
+        // on. That much indentation belongs to the code around the string, so we skip it.
+        let line = &state.original_bytes()[state.line_start.offset as usize..];
+        let indent = line.iter().take_while(|&&byte| byte == b' ').count() as u32;
 
         let start_state;
 
+        // Whether a block string has line breaks, and so must stay a block
+        let mut has_line_breaks = false;
+
         if state.consume_mut("\"\"\"") {
             start_state = state.clone();
 
             // we will be parsing a multi-line string
             is_multiline = true;
-
-            if state.consume_mut("\n") {
-                state = consume_indent(state, indent)?;
-            }
         } else if state.consume_mut("\"") {
             start_state = state.clone();
 
@@ -273,7 +389,7 @@ pub fn parse<'a>() -> impl Parser<'a, StrLiteral<'a>, EString<'a>> {
                             if bytes.as_slice().starts_with(b"\"\"") {
                                 end_segment!(StrSegment::Plaintext);
 
-                                let expr = if segments.len() == 1 {
+                                let expr = if segments.len() == 1 && !has_line_breaks {
                                     // We had exactly one segment, so this is a candidate
                                     // to be StrLiteral::Plaintext
                                     match segments.pop().unwrap() {
@@ -312,21 +428,28 @@ pub fn parse<'a>() -> impl Parser<'a, StrLiteral<'a>, EString<'a>> {
                 }
                 b'\n' => {
                     if is_multiline {
-                        let without_newline = &state.bytes()[0..(segment_parsed_bytes - 1)];
                         let with_newline = &state.bytes()[0..segment_parsed_bytes];
 
                         state.advance_mut(segment_parsed_bytes);
-                        state = consume_indent(state, indent)?;
+
+                        let rest = state.bytes();
+                        state = consume_indent(state, indent).map_err(|problem| {
+                            if rest.windows(3).any(|window| window == b"\"\"\"") {
+                                problem
+                            } else {
+                                // The string never ends, so the line that is not indented
+                                // enough is just the code after it.
+                                (
+                                    MadeProgress,
+                                    EString::EndlessMulti(start_state.pos()),
+                                    start_state.clone(),
+                                )
+                            }
+                        })?;
                         bytes = state.bytes().iter();
 
-                        if state.bytes().starts_with(b"\"\"\"") {
-                            // ending the string; don't use the last newline
-                            segments
-                                .push(StrSegment::Plaintext(utf8(state.clone(), without_newline)?));
-                        } else {
-                            segments
-                                .push(StrSegment::Plaintext(utf8(state.clone(), with_newline)?));
-                        }
+                        segments.push(StrSegment::Plaintext(utf8(state.clone(), with_newline)?));
+                        has_line_breaks = true;
 
                         segment_parsed_bytes = 0;
 
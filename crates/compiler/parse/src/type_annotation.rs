@@ -1,14 +1,17 @@
 use crate::ast::{
-    AssignedField, CommentOrNewline, HasAbilities, HasAbility, HasClause, HasImpls, Pattern,
-    Spaced, Tag, TypeAnnotation, TypeHeader,
+    AssignedField, Collection, CommentOrNewline, HasAbilities, HasAbility, HasClause, HasImpls,
+    Pattern, Spaced, Tag, TypeAnnotation, TypeHeader,
+};
+use crate::blankspace::{
+    space0_around_ee, space0_before_e, space0_before_optional_after, space0_e,
 };
-use crate::blankspace::{space0_around_ee, space0_before_e, space0_e};
 use crate::expr::record_value_field;
 use crate::ident::lowercase_ident;
 use crate::keyword;
 use crate::parser::{
-    allocated, backtrackable, optional, specialize, specialize_ref, word1, word2, word3, EType,
-    ETypeApply, ETypeInParens, ETypeInlineAlias, ETypeRecord, ETypeTagUnion, ParseResult, Parser,
+    allocated, backtrackable, optional, specialize, specialize_ref, trailing_sep_by0, word1, word2,
+    word3, EType, ETypeApply, ETypeInParens, ETypeInlineAlias, ETypeRecord, ETypeTagUnion,
+    ParseResult, Parser,
     Progress::{self, *},
 };
 use crate::parser::{then, ERecord, ETypeAbilityImpl};
@@ -223,20 +226,69 @@ fn loc_applied_arg<'a>(
 fn loc_type_in_parens<'a>(
     min_indent: u32,
 ) -> impl Parser<'a, Loc<TypeAnnotation<'a>>, ETypeInParens<'a>> {
-    between!(
-        word1(b'(', ETypeInParens::Open),
-        space0_around_ee(
-            move |arena, state| specialize_ref(
-                ETypeInParens::Type,
-                expression(min_indent, true, false)
+    (move |arena, state: State<'a>| {
+        let start = state.pos();
+        let (_, first, state) = skip_first!(
+            word1(b'(', ETypeInParens::Open),
+            space0_around_ee(
+                specialize_ref(ETypeInParens::Type, expression(min_indent, true, false)),
+                min_indent,
+                ETypeInParens::IndentOpen,
+                ETypeInParens::IndentEnd,
             )
-            .parse(arena, state),
-            min_indent,
-            ETypeInParens::IndentOpen,
-            ETypeInParens::IndentEnd,
-        ),
-        word1(b')', ETypeInParens::IndentEnd)
-    )
+        )
+        .parse(arena, state)?;
+
+        if let Ok((_, _, state)) = word1(b')', ETypeInParens::IndentEnd).parse(arena, state.clone())
+        {
+            return Ok((MadeProgress, first, state));
+        }
+
+        // more than one type between the parentheses: this is a tuple type
+        let (_, (rest, final_comments), state) = skip_first!(
+            word1(b',', ETypeInParens::End),
+            and!(
+                trailing_sep_by0(
+                    word1(b',', ETypeInParens::End),
+                    space0_before_optional_after(
+                        specialize_ref(ETypeInParens::Type, expression(min_indent, true, false)),
+                        min_indent,
+                        ETypeInParens::IndentEnd,
+                        ETypeInParens::IndentEnd,
+                    )
+                ),
+                space0_e(0, ETypeInParens::IndentEnd)
+            )
+        )
+        .parse(arena, state)?;
+
+        if rest.is_empty() {
+            // `(a,)` is not a tuple type
+            let fail = ETypeInParens::Type(arena.alloc(EType::TStart(state.pos())), state.pos());
+            return Err((MadeProgress, fail, state));
+        }
+
+        let (_, _, state) = word1(b')', ETypeInParens::End).parse(arena, state)?;
+
+        let mut elems = Vec::with_capacity_in(rest.len() + 1, arena);
+        elems.push(first);
+        elems.extend(rest);
+
+        let elems =
+            Collection::with_items_and_comments(arena, elems.into_bump_slice(), final_comments);
+
+        let ext_term = specialize_ref(ETypeInParens::Type, term(min_indent, false));
+        let (_, ext, state) = optional(allocated(ext_term)).parse(arena, state)?;
+
+        let tuple = TypeAnnotation::Tuple { elems, ext };
+
+        Ok((
+            MadeProgress,
+            Loc::at(Region::new(start, state.pos()), tuple),
+            state,
+        ))
+    })
+    .trace("type_annotation:loc_type_in_parens")
 }
 
 #[inline(always)]
@@ -0,0 +1 @@
+{ settings.theme: Dark }
\ No newline at end of file
@@ -0,0 +1,12 @@
+query =
+    """
+        SELECT name
+          FROM users
+         WHERE id = \(id)
+    """
+html = """
+  <p>
+    hello
+  </p>
+  """
+query
\ No newline at end of file
@@ -0,0 +1 @@
+{ model & settings.theme.color: Red, count: 0 }
\ No newline at end of file
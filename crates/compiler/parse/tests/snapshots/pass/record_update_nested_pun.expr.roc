@@ -0,0 +1 @@
+{ model & settings.theme }
\ No newline at end of file
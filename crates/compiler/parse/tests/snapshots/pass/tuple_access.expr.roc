@@ -0,0 +1 @@
+pair.0 + pair.1
@@ -0,0 +1,4 @@
+f : (Str, U8)a -> Str
+f = \t -> t.0
+
+f
@@ -0,0 +1,3 @@
+when pair is
+    (0, _) -> 1
+    (_, y) -> y
@@ -120,6 +120,8 @@ mod test_parse {
 
     // see tests/snapshots to see test input(.roc) and expected output(.result-ast)
     snapshot_tests! {
+        fail/record_literal_nested_field.expr,
+        fail/tuple_trailing_comma_single.expr,
         fail/type_argument_no_arrow.expr,
         fail/type_double_comma.expr,
         pass/ability_demand_signature_is_multiline.expr,
@@ -147,6 +149,8 @@ mod test_parse {
         pass/comment_before_op.expr,
         pass/comment_inside_empty_list.expr,
         pass/comment_with_non_ascii.expr,
+        pass/crash.expr,
+        pass/dbg.expr,
         pass/destructure_tag_assignment.expr,
         pass/empty_app_header.header,
         pass/empty_hosted_header.header,
@@ -205,6 +209,7 @@ mod test_parse {
         pass/number_literal_suffixes.expr,
         pass/one_backpassing.expr,
         pass/multiline_string.expr,
+        pass/multiline_string_common_indent.expr,
         pass/one_char_string.expr,
         pass/one_def.expr,
         pass/one_minus_two.expr,
@@ -243,6 +248,8 @@ mod test_parse {
         pass/record_func_type_decl.expr,
         pass/record_type_with_function.expr,
         pass/record_update.expr,
+        pass/record_update_nested.expr,
+        pass/record_update_nested_pun.expr,
         pass/record_with_if.expr,
         pass/requires_type.header,
         pass/single_arg_closure.expr,
@@ -258,6 +265,10 @@ mod test_parse {
         pass/tag_pattern.expr,
         pass/ten_times_eleven.expr,
         pass/three_arg_closure.expr,
+        pass/tuple_access.expr,
+        pass/tuple_expr.expr,
+        pass/tuple_type_and_pattern.expr,
+        pass/tuple_type_ext.expr,
         pass/two_arg_closure.expr,
         pass/two_backpassing.expr,
         pass/two_branch_when.expr,
@@ -287,6 +298,8 @@ mod test_parse {
         pass/when_with_negative_numbers.expr,
         pass/when_with_numbers.expr,
         pass/when_with_records.expr,
+        pass/when_tuple_pattern.expr,
+        pass/when_list_pattern.expr,
         pass/where_clause_function.expr,
         pass/where_clause_multiple_has_across_newlines.expr,
         pass/where_clause_multiple_has.expr,
@@ -833,6 +846,100 @@ mod test_parse {
         }
     }
 
+    #[test]
+    fn recover_from_multiple_syntax_errors() {
+        let arena = &Bump::new();
+
+        let src = indoc!(
+            r#"
+            xs = [1, if 2 then 3, 4]
+
+            point = { x: 1, y: }
+
+            broken ) = 5
+
+            main = xs
+            "#
+        );
+
+        let state = State::new(src.as_bytes()).with_error_recovery();
+        let (_, defs, state) = module_defs().parse(arena, state).unwrap();
+
+        assert_eq!(state.recovered_errors().len(), 3);
+
+        // the well-formed parts of every def survive, and the rest is malformed
+        let value_defs = format!("{:?}", defs.value_defs);
+        assert_eq!(defs.value_defs.len(), 4);
+        assert!(value_defs.contains(r#"Malformed("if 2 then 3")"#));
+        assert!(value_defs.contains(r#"Malformed("y:")"#));
+        assert!(value_defs.contains(r#"Malformed("broken ) = 5")"#));
+        assert!(value_defs.contains(r#"Var { module_name: "", ident: "xs" }"#));
+    }
+
+    #[test]
+    fn recovered_body_joins_only_its_own_annotation() {
+        let arena = &Bump::new();
+
+        let src = indoc!(
+            r#"
+            x : Str
+
+            y = )
+
+            z : Str
+            z = )
+            "#
+        );
+
+        let state = State::new(src.as_bytes()).with_error_recovery();
+        let (_, defs, state) = module_defs().parse(arena, state).unwrap();
+
+        assert_eq!(state.recovered_errors().len(), 2);
+
+        let value_defs: std::vec::Vec<_> = defs.defs().filter_map(Result::err).collect();
+
+        assert_eq!(value_defs.len(), 3);
+        assert!(matches!(value_defs[0], ast::ValueDef::Annotation(..)));
+        assert!(matches!(value_defs[1], ast::ValueDef::Body(..)));
+        assert!(matches!(value_defs[2], ast::ValueDef::AnnotatedBody { .. }));
+    }
+
+    #[test]
+    fn recover_from_syntax_error_after_multi_byte_characters() {
+        let arena = &Bump::new();
+
+        let src = indoc!(
+            r#"
+            greeting = "héllo" ) "wörld"
+
+            main = greeting
+            "#
+        );
+
+        let state = State::new(src.as_bytes()).with_error_recovery();
+        let (_, defs, state) = module_defs().parse(arena, state).unwrap();
+
+        assert_eq!(state.recovered_errors().len(), 1);
+
+        let value_defs = format!("{:?}", defs.value_defs);
+        assert_eq!(defs.value_defs.len(), 3);
+        assert!(value_defs.contains(r#"PlainLine("héllo")"#));
+        assert!(value_defs.contains(r#"Malformed(") \"wörld\"")"#));
+        assert!(value_defs.contains(r#"Var { module_name: "", ident: "greeting" }"#));
+    }
+
+    #[test]
+    fn header_syntax_error_region() {
+        let arena = &Bump::new();
+        let src = "interface Test exposes [x imports []";
+
+        let fail = roc_parse::module::parse_header(arena, State::new(src.as_bytes())).unwrap_err();
+        let region = SyntaxError::Header(fail.problem).region().unwrap();
+
+        // where the exposes list went wrong, not the start of the file
+        assert!(region.start().offset as usize >= "interface Test exposes ".len());
+    }
+
     #[test]
     fn parse_expr_size() {
         assert_eq!(std::mem::size_of::<roc_parse::ast::Expr>(), 40);
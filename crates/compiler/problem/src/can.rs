@@ -177,12 +177,106 @@ pub enum Problem {
         original_opaque: Symbol,
         ability_member: Symbol,
     },
+    MultipleListRestPattern {
+        region: Region,
+    },
+}
+
+impl Problem {
+    /// The region in the source that this problem is most closely associated with, if any.
+    pub fn region(&self) -> Option<Region> {
+        use Problem::*;
+
+        match self {
+            UnusedDef(_, region)
+            | UnusedImport(_, region)
+            | UnusedArgument(_, _, _, region)
+            | UnusedBranchDef(_, region)
+            | UnsupportedPattern(_, region)
+            | CyclicAlias(_, region, _, _)
+            | InvalidInterpolation(region)
+            | InvalidHexadecimal(region)
+            | InvalidUnicodeCodePt(region)
+            | AbilityUsedAsType(_, _, region)
+            | NestedSpecialization(_, region)
+            | IllegalDerivedAbility(region)
+            | NotAnAbility(region)
+            | NoIdentifiersIntroduced(region)
+            | PrecedenceProblem(self::PrecedenceProblem::BothNonAssociative(region, _, _))
+            | PhantomTypeArgument {
+                variable_region: region,
+                ..
+            }
+            | UnboundTypeVariable {
+                one_occurrence: region,
+                ..
+            }
+            | DuplicateRecordFieldValue {
+                field_region: region,
+                ..
+            }
+            | DuplicateRecordFieldType {
+                field_region: region,
+                ..
+            }
+            | InvalidOptionalValue {
+                field_region: region,
+                ..
+            }
+            | DuplicateTag {
+                tag_region: region, ..
+            }
+            | SignatureDefMismatch {
+                def_pattern: region,
+                ..
+            }
+            | InvalidAliasRigid { region, .. }
+            | NestedDatatype {
+                differing_recursion_region: region,
+                ..
+            }
+            | InvalidExtensionType { region, .. }
+            | AbilityHasTypeVariables {
+                variables_region: region,
+                ..
+            }
+            | HasClauseIsNotAbility { region }
+            | IllegalHasClause { region }
+            | AbilityMemberMissingHasClause { region, .. }
+            | AbilityMemberMultipleBoundVars {
+                span_has_clauses: region,
+                ..
+            }
+            | AbilityNotOnToplevel { region }
+            | ImplementationNotFound { region, .. }
+            | NotAnAbilityMember { region, .. }
+            | OptionalAbilityImpl { region, .. }
+            | QualifiedAbilityImpl { region }
+            | AbilityImplNotIdent { region }
+            | DuplicateImpl {
+                duplicate: region, ..
+            }
+            | ImplementsNonRequired { region, .. }
+            | DoesNotImplementAbility { region, .. }
+            | NotBoundInAllPatterns { region, .. }
+            | OverloadedSpecialization {
+                overload: region, ..
+            }
+            | MultipleListRestPattern { region } => Some(*region),
+            Shadowing { shadow, .. } => Some(shadow.region),
+            UnknownGeneratesWith(loc_ident) => Some(loc_ident.region),
+            BadRecursion(cycle) => cycle.first().map(|entry| entry.expr_region),
+            RuntimeError(runtime_error) => runtime_error.region(),
+            ExposedButNotDefined(_) => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum ExtensionTypeKind {
     Record,
     TagUnion,
+    Tuple,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -309,6 +403,8 @@ pub enum RuntimeError {
     MalformedIdentifier(Box<str>, roc_parse::ident::BadIdent, Region),
     MalformedTypeName(Box<str>, Region),
     MalformedClosure(Region),
+    /// Code the parser skipped over after a syntax error, which it has already reported
+    MalformedExpr(Region),
     InvalidRecordUpdate {
         region: Region,
     },
@@ -339,9 +435,61 @@ pub enum RuntimeError {
     MultipleCharsInSingleQuote(Region),
 
     DegenerateBranch(Region),
+
+    /// `crash` used on its own, rather than applied to a message
+    CrashNotApplied(Region),
+    /// `crash` applied to more than one argument
+    CrashAppliedToMultipleArgs(Region),
 }
 
 impl RuntimeError {
+    /// The region in the source that this error is most closely associated with, if any.
+    pub fn region(&self) -> Option<Region> {
+        use RuntimeError::*;
+
+        match self {
+            Shadowing { shadow, .. } => Some(shadow.region),
+            InvalidOptionalValue { field_region, .. } => Some(*field_region),
+            UnsupportedPattern(region)
+            | MalformedPattern(_, region)
+            | OpaqueAppliedToMultipleArgs(region)
+            | ValueNotExposed { region, .. }
+            | ModuleNotImported { region, .. }
+            | InvalidPrecedence(_, region)
+            | MalformedIdentifier(_, _, region)
+            | MalformedTypeName(_, region)
+            | MalformedClosure(region)
+            | MalformedExpr(region)
+            | InvalidRecordUpdate { region }
+            | InvalidFloat(_, region, _)
+            | InvalidInt(_, _, region, _)
+            | InvalidInterpolation(region)
+            | InvalidHexadecimal(region)
+            | InvalidUnicodeCodePt(region)
+            | EmptySingleQuote(region)
+            | MultipleCharsInSingleQuote(region)
+            | DegenerateBranch(region)
+            | CrashNotApplied(region)
+            | CrashAppliedToMultipleArgs(region) => Some(*region),
+            LookupNotInScope(loc_ident, _)
+            | OpaqueNotDefined {
+                usage: loc_ident, ..
+            }
+            | OpaqueNotApplied(loc_ident) => Some(loc_ident.region),
+            OpaqueOutsideScope {
+                referenced_region, ..
+            } => Some(*referenced_region),
+            CircularDef(cycle) => cycle.first().map(|entry| entry.expr_region),
+            UnresolvedTypeVar
+            | ErroneousType
+            | NonExhaustivePattern
+            | NoImplementationNamed { .. }
+            | NoImplementation
+            | VoidValue
+            | ExposedButNotDefined(_) => None,
+        }
+    }
+
     pub fn runtime_message(self) -> String {
         use RuntimeError::*;
 
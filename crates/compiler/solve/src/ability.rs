@@ -2,14 +2,15 @@ use roc_can::abilities::AbilitiesStore;
 use roc_can::expr::PendingDerives;
 use roc_collections::{VecMap, VecSet};
 use roc_error_macros::{internal_error, todo_abilities};
-use roc_module::symbol::Symbol;
+use roc_module::symbol::{ModuleId, Symbol};
 use roc_region::all::{Loc, Region};
 use roc_solve_problem::{
     NotDerivableContext, NotDerivableDecode, TypeError, UnderivableReason, Unfulfilled,
 };
 use roc_types::num::NumericRange;
 use roc_types::subs::{
-    instantiate_rigids, Content, FlatType, GetSubsSlice, Rank, RecordFields, Subs, Variable,
+    instantiate_rigids, Content, FlatType, GetSubsSlice, Rank, RecordFields, Subs, TupleElems,
+    Variable,
 };
 use roc_types::types::{AliasKind, Category, MemberImpl, PatternCategory};
 use roc_unify::unify::{Env, MustImplementConstraints};
@@ -272,6 +273,12 @@ impl ObligationCache {
                 var,
             )),
 
+            Symbol::HASH_HASH_ABILITY => {
+                Some(DeriveHash::is_derivable(self, abilities_store, subs, var))
+            }
+
+            Symbol::BOOL_EQ => Some(DeriveEq::is_derivable(self, abilities_store, subs, var)),
+
             _ => None,
         };
 
@@ -510,6 +517,18 @@ trait DerivableVisitor {
         })
     }
 
+    #[inline(always)]
+    fn visit_tuple(
+        _subs: &Subs,
+        var: Variable,
+        _elems: TupleElems,
+    ) -> Result<Descend, NotDerivable> {
+        Err(NotDerivable {
+            var,
+            context: NotDerivableContext::NoContext,
+        })
+    }
+
     #[inline(always)]
     fn visit_tag_union(var: Variable) -> Result<Descend, NotDerivable> {
         Err(NotDerivable {
@@ -542,6 +561,14 @@ trait DerivableVisitor {
         })
     }
 
+    #[inline(always)]
+    fn visit_empty_tuple(var: Variable) -> Result<(), NotDerivable> {
+        Err(NotDerivable {
+            var,
+            context: NotDerivableContext::NoContext,
+        })
+    }
+
     #[inline(always)]
     fn visit_empty_tag_union(var: Variable) -> Result<(), NotDerivable> {
         Err(NotDerivable {
@@ -566,6 +593,20 @@ trait DerivableVisitor {
         })
     }
 
+    #[inline(always)]
+    fn visit_floating_point_content(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    /// Visits a number whose range is an unbound rigid, like `Num a` in a signature.
+    #[inline(always)]
+    fn visit_rigid_number_range(var: Variable) -> Result<(), NotDerivable> {
+        Err(NotDerivable {
+            var,
+            context: NotDerivableContext::NoContext,
+        })
+    }
+
     #[inline(always)]
     fn is_derivable(
         obligation_cache: &mut ObligationCache,
@@ -643,6 +684,19 @@ trait DerivableVisitor {
                             }
                         }
                     }
+                    Tuple(elems, ext) => {
+                        let descend = Self::visit_tuple(subs, var, elems)?;
+                        if descend.0 {
+                            push_var_slice!(elems.variables());
+                            if !matches!(
+                                subs.get_content_without_compacting(ext),
+                                Content::FlexVar(_) | Content::RigidVar(_)
+                            ) {
+                                // As with records, a flex var in the extension is not constrained.
+                                stack.push(ext);
+                            }
+                        }
+                    }
                     TagUnion(tags, ext) => {
                         let descend = Self::visit_tag_union(var)?;
                         if descend.0 {
@@ -669,6 +723,7 @@ trait DerivableVisitor {
                         }
                     }
                     EmptyRecord => Self::visit_empty_record(var)?,
+                    EmptyTuple => Self::visit_empty_tuple(var)?,
                     EmptyTagUnion => Self::visit_empty_tag_union(var)?,
 
                     Erroneous(_) => {
@@ -679,13 +734,27 @@ trait DerivableVisitor {
                     }
                 },
                 Alias(
-                    Symbol::NUM_NUM | Symbol::NUM_INTEGER | Symbol::NUM_FLOATINGPOINT,
+                    Symbol::NUM_NUM | Symbol::NUM_INTEGER,
                     _alias_variables,
                     real_var,
                     AliasKind::Opaque,
                 ) => {
-                    // Numbers: always decay until a ground is hit.
-                    stack.push(real_var);
+                    if matches!(subs.get_content_without_compacting(real_var), RigidVar(_)) {
+                        Self::visit_rigid_number_range(var)?
+                    } else {
+                        // Numbers: always decay until a ground is hit.
+                        stack.push(real_var);
+                    }
+                }
+                Alias(Symbol::NUM_FLOATINGPOINT, _alias_variables, real_var, AliasKind::Opaque) => {
+                    let descend = Self::visit_floating_point_content(var)?;
+                    if descend.0 {
+                        if matches!(subs.get_content_without_compacting(real_var), RigidVar(_)) {
+                            Self::visit_rigid_number_range(var)?
+                        } else {
+                            stack.push(real_var);
+                        }
+                    }
                 }
                 Alias(opaque, _alias_variables, _real_var, AliasKind::Opaque) => {
                     if obligation_cache
@@ -764,6 +833,15 @@ impl DerivableVisitor for DeriveEncoding {
         Ok(Descend(true))
     }
 
+    #[inline(always)]
+    fn visit_tuple(
+        _subs: &Subs,
+        _var: Variable,
+        _elems: TupleElems,
+    ) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
     #[inline(always)]
     fn visit_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
         Ok(Descend(true))
@@ -784,6 +862,11 @@ impl DerivableVisitor for DeriveEncoding {
         Ok(())
     }
 
+    #[inline(always)]
+    fn visit_empty_tuple(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
     #[inline(always)]
     fn visit_empty_tag_union(_var: Variable) -> Result<(), NotDerivable> {
         Ok(())
@@ -853,6 +936,104 @@ impl DerivableVisitor for DeriveDecoding {
         Ok(Descend(true))
     }
 
+    #[inline(always)]
+    fn visit_tuple(
+        _subs: &Subs,
+        _var: Variable,
+        _elems: TupleElems,
+    ) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_recursive_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_function_or_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_empty_record(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_empty_tuple(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_empty_tag_union(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_alias(_var: Variable, symbol: Symbol) -> Result<Descend, NotDerivable> {
+        if is_builtin_number_alias(symbol) {
+            Ok(Descend(false))
+        } else {
+            Ok(Descend(true))
+        }
+    }
+
+    #[inline(always)]
+    fn visit_ranged_number(_var: Variable, _range: NumericRange) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+}
+
+struct DeriveHash;
+impl DerivableVisitor for DeriveHash {
+    const ABILITY: Symbol = Symbol::HASH_HASH_ABILITY;
+
+    #[inline(always)]
+    fn is_derivable_builtin_opaque(symbol: Symbol) -> bool {
+        is_builtin_number_alias(symbol)
+    }
+
+    #[inline(always)]
+    fn visit_recursion(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_apply(var: Variable, symbol: Symbol) -> Result<Descend, NotDerivable> {
+        if matches!(symbol, Symbol::LIST_LIST | Symbol::STR_STR) {
+            Ok(Descend(true))
+        } else {
+            Err(NotDerivable {
+                var,
+                context: NotDerivableContext::NoContext,
+            })
+        }
+    }
+
+    #[inline(always)]
+    fn visit_record(
+        _subs: &Subs,
+        _var: Variable,
+        _fields: RecordFields,
+    ) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_tuple(
+        _subs: &Subs,
+        _var: Variable,
+        _elems: TupleElems,
+    ) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
     #[inline(always)]
     fn visit_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
         Ok(Descend(true))
@@ -873,6 +1054,11 @@ impl DerivableVisitor for DeriveDecoding {
         Ok(())
     }
 
+    #[inline(always)]
+    fn visit_empty_tuple(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
     #[inline(always)]
     fn visit_empty_tag_union(_var: Variable) -> Result<(), NotDerivable> {
         Ok(())
@@ -889,10 +1075,191 @@ impl DerivableVisitor for DeriveDecoding {
 
     #[inline(always)]
     fn visit_ranged_number(_var: Variable, _range: NumericRange) -> Result<(), NotDerivable> {
+        // Number literals that are not otherwise constrained default to integers.
         Ok(())
     }
 }
 
+struct DeriveEq;
+impl DerivableVisitor for DeriveEq {
+    const ABILITY: Symbol = Symbol::BOOL_EQ;
+
+    #[inline(always)]
+    fn is_derivable_builtin_opaque(symbol: Symbol) -> bool {
+        is_builtin_number_alias(symbol)
+    }
+
+    #[inline(always)]
+    fn visit_flex_able(var: Variable, ability: Symbol) -> Result<(), NotDerivable> {
+        if AbilitiesStore::ability_implies(ability, Self::ABILITY) {
+            Ok(())
+        } else {
+            Err(NotDerivable {
+                var,
+                context: NotDerivableContext::NoContext,
+            })
+        }
+    }
+
+    #[inline(always)]
+    fn visit_rigid_able(var: Variable, ability: Symbol) -> Result<(), NotDerivable> {
+        Self::visit_flex_able(var, ability)
+    }
+
+    #[inline(always)]
+    fn visit_recursion(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_apply(_var: Variable, _symbol: Symbol) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_record(
+        _subs: &Subs,
+        _var: Variable,
+        _fields: RecordFields,
+    ) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_tuple(
+        _subs: &Subs,
+        _var: Variable,
+        _elems: TupleElems,
+    ) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_recursive_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_function_or_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_empty_record(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_empty_tuple(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_empty_tag_union(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_alias(_var: Variable, _symbol: Symbol) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_ranged_number(_var: Variable, _range: NumericRange) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_rigid_number_range(_var: Variable) -> Result<(), NotDerivable> {
+        // Every number can be compared, no matter its range.
+        Ok(())
+    }
+}
+
+/// Whether comparing values of a type with `isEq` may run a custom `isEq` implementation
+/// somewhere inside of them. If it can't, the values can be compared structurally.
+///
+/// Unbound type variables may later be bound to a type with a custom `isEq`, so they are
+/// assumed to reach one.
+pub fn may_reach_custom_is_eq(
+    subs: &Subs,
+    var: Variable,
+    get_implementation: impl Fn(roc_can::abilities::ImplKey) -> Option<MemberImpl>,
+) -> bool {
+    let mut stack = vec![var];
+    let mut seen = VecSet::default();
+
+    while let Some(var) = stack.pop() {
+        if seen.insert(subs.get_root_key_without_compacting(var)) {
+            continue;
+        }
+
+        use Content::*;
+        use FlatType::*;
+
+        // Like derived implementations, only look at the concrete part of records, tuples and tag
+        // unions.
+        let push_ext = |stack: &mut Vec<Variable>, ext: Variable| {
+            if !matches!(
+                subs.get_content_without_compacting(ext),
+                FlexVar(_) | RigidVar(_) | FlexAbleVar(..) | RigidAbleVar(..)
+            ) {
+                stack.push(ext);
+            }
+        };
+
+        match *subs.get_content_without_compacting(var) {
+            FlexVar(_) | RigidVar(_) | FlexAbleVar(..) | RigidAbleVar(..) => return true,
+            RecursionVar { structure, .. } => stack.push(structure),
+            Structure(flat_type) => match flat_type {
+                Apply(_, vars) => stack.extend(subs.get_subs_slice(vars)),
+                Record(fields, ext) => {
+                    stack.extend(subs.get_subs_slice(fields.variables()));
+                    push_ext(&mut stack, ext);
+                }
+                Tuple(elems, ext) => {
+                    stack.extend(subs.get_subs_slice(elems.variables()));
+                    push_ext(&mut stack, ext);
+                }
+                TagUnion(tags, ext) | RecursiveTagUnion(_, tags, ext) => {
+                    for i in tags.variables() {
+                        stack.extend(subs.get_subs_slice(subs[i]));
+                    }
+                    push_ext(&mut stack, ext);
+                }
+                FunctionOrTagUnion(_, _, ext) => push_ext(&mut stack, ext),
+                // Functions can't be compared at all, and an error will already be reported.
+                Func(..) | EmptyRecord | EmptyTuple | EmptyTagUnion | Erroneous(_) => {}
+            },
+            Alias(opaque, _, real_var, AliasKind::Opaque)
+                if opaque.module_id() != ModuleId::NUM =>
+            {
+                let impl_key = roc_can::abilities::ImplKey {
+                    opaque,
+                    ability_member: Symbol::BOOL_IS_EQ,
+                };
+                match get_implementation(impl_key) {
+                    Some(MemberImpl::Impl(_)) => return true,
+                    Some(MemberImpl::Derived) => stack.push(real_var),
+                    // Builtin opaques without an `Eq` implementation are compared structurally.
+                    None if opaque.module_id().is_builtin() => stack.push(real_var),
+                    // Doesn't implement `Eq`; an error will already be reported for this.
+                    None | Some(MemberImpl::Error) => {}
+                }
+            }
+            Alias(_, _, real_var, _) => stack.push(real_var),
+            RangedNumber(_) | LambdaSet(_) | Error => {}
+        }
+    }
+
+    false
+}
+
 /// Determines what type implements an ability member of a specialized signature, given the
 /// [MustImplementAbility] constraints of the signature.
 pub fn type_implementing_specialization(
@@ -1007,6 +1374,25 @@ pub fn resolve_ability_specialization<R: AbilityResolver>(
                 roc_types::types::MemberImpl::Impl(spec_symbol) => {
                     Resolved::Specialization(spec_symbol)
                 }
+                roc_types::types::MemberImpl::Derived if ability_member == Symbol::BOOL_IS_EQ => {
+                    // isEq : a, a -> Bool, so the opaque is the type of the first argument.
+                    let opaque_var = match subs.get_content_without_compacting(specialization_var) {
+                        Content::Structure(FlatType::Func(args, _, _)) => {
+                            subs.get_subs_slice(*args)[0]
+                        }
+                        _ => internal_error!("isEq specialization is not a function"),
+                    };
+
+                    // Derived equality is structural equality, unless the opaque holds a value
+                    // with a custom `isEq`.
+                    if may_reach_custom_is_eq(subs, opaque_var, |key| {
+                        resolver.get_implementation(key)
+                    }) {
+                        Resolved::NeedsGenerated(opaque_var)
+                    } else {
+                        Resolved::Specialization(Symbol::BOOL_STRUCTURAL_EQ)
+                    }
+                }
                 roc_types::types::MemberImpl::Derived => {
                     todo_abilities!("get type from obligated opaque")
                 }
@@ -1016,8 +1402,16 @@ pub fn resolve_ability_specialization<R: AbilityResolver>(
             }
         }
         Obligated::Adhoc(variable) => {
-            // TODO: more rules need to be validated here, like is this a builtin ability?
-            Resolved::NeedsGenerated(variable)
+            if ability_member == Symbol::BOOL_IS_EQ
+                && !may_reach_custom_is_eq(subs, variable, |key| resolver.get_implementation(key))
+            {
+                // Structural types are compared structurally, unless they hold a value with a
+                // custom `isEq`.
+                Resolved::Specialization(Symbol::BOOL_STRUCTURAL_EQ)
+            } else {
+                // TODO: more rules need to be validated here, like is this a builtin ability?
+                Resolved::NeedsGenerated(variable)
+            }
         }
     };
 
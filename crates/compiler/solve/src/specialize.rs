@@ -24,6 +24,7 @@ use roc_types::{
 };
 use roc_unify::unify::{unify, Env as UEnv, Mode, MustImplementConstraints};
 
+use crate::ability::may_reach_custom_is_eq;
 use crate::solve::{deep_copy_var_in, introduce, Pools};
 
 /// What phase in the compiler is reaching out to specialize lambda sets?
@@ -601,6 +602,7 @@ enum SpecializationTypeKey {
     Opaque(Symbol),
     Derived(DeriveKey),
     Immediate(Symbol),
+    SingleLambdaSetImmediate(Symbol),
 }
 
 enum SpecializeDecision {
@@ -622,7 +624,41 @@ fn make_specialization_decision<P: Phase>(
     use Content::*;
     use SpecializationTypeKey::*;
     match subs.get_content_without_compacting(var) {
-        Alias(opaque, _, _, AliasKind::Opaque) if opaque.module_id() != ModuleId::NUM => {
+        Alias(opaque, _, real_var, AliasKind::Opaque) if opaque.module_id() != ModuleId::NUM => {
+            let derives_impl = |abilities_store: &AbilitiesStore| {
+                let impl_key = ImplKey {
+                    opaque: *opaque,
+                    ability_member,
+                };
+                matches!(
+                    abilities_store.get_implementation(impl_key),
+                    Some(MemberImpl::Derived)
+                )
+            };
+
+            if ability_member == Symbol::BOOL_IS_EQ
+                && phase.with_module_abilities_store(opaque.module_id(), derives_impl)
+            {
+                // Derived equality is structural equality, unless the opaque holds a value with
+                // a custom `isEq`. Then we need a derived `isEq` that calls it.
+                if !reaches_custom_is_eq(subs, phase, *real_var) {
+                    return SpecializeDecision::Specialize(SingleLambdaSetImmediate(
+                        Symbol::BOOL_STRUCTURAL_EQ,
+                    ));
+                }
+
+                return match roc_derive_key::Derived::builtin(
+                    roc_derive_key::DeriveBuiltin::IsEq,
+                    subs,
+                    var,
+                ) {
+                    Ok(roc_derive_key::Derived::Key(derive_key)) => {
+                        SpecializeDecision::Specialize(Derived(derive_key))
+                    }
+                    _ => internal_error!("opaques with a derived Eq always have a derive key"),
+                };
+            }
+
             if P::IS_LATE {
                 SpecializeDecision::Specialize(Opaque(*opaque))
             } else {
@@ -660,12 +696,23 @@ fn make_specialization_decision<P: Phase>(
                 Err(_) => return SpecializeDecision::Drop,
             };
 
+            if ability_member == Symbol::BOOL_IS_EQ && !reaches_custom_is_eq(subs, phase, var) {
+                // Structural types are compared structurally, unless they hold a value with a
+                // custom `isEq`.
+                return SpecializeDecision::Specialize(SingleLambdaSetImmediate(
+                    Symbol::BOOL_STRUCTURAL_EQ,
+                ));
+            }
+
             // This is a structural type, find the derived ability function it should use.
             match roc_derive_key::Derived::builtin(builtin, subs, var) {
                 Ok(derived) => match derived {
                     roc_derive_key::Derived::Immediate(imm) => {
                         SpecializeDecision::Specialize(Immediate(imm))
                     }
+                    roc_derive_key::Derived::SingleLambdaSetImmediate(imm) => {
+                        SpecializeDecision::Specialize(SingleLambdaSetImmediate(imm))
+                    }
                     roc_derive_key::Derived::Key(derive_key) => {
                         SpecializeDecision::Specialize(Derived(derive_key))
                     }
@@ -695,6 +742,14 @@ fn make_specialization_decision<P: Phase>(
     }
 }
 
+fn reaches_custom_is_eq<P: Phase>(subs: &Subs, phase: &P, var: Variable) -> bool {
+    may_reach_custom_is_eq(subs, var, |impl_key| {
+        phase.with_module_abilities_store(impl_key.opaque.module_id(), |abilities_store| {
+            abilities_store.get_implementation(impl_key).copied()
+        })
+    })
+}
+
 #[allow(clippy::too_many_arguments)]
 fn get_specialization_lambda_set_ambient_function<P: Phase>(
     subs: &mut Subs,
@@ -785,5 +840,40 @@ fn get_specialization_lambda_set_ambient_function<P: Phase>(
 
             Ok(immediate_lambda_set_at_region)
         }
+
+        SpecializationTypeKey::SingleLambdaSetImmediate(imm) => {
+            let module_id = imm.module_id();
+            debug_assert!(module_id.is_builtin());
+
+            let module_types = &derived_env
+                .exposed_types
+                .get(&module_id)
+                .unwrap()
+                .exposed_types_storage_subs;
+
+            // Since this immediate has only one lambda set, the specialization must be the same
+            // as the immediate's signature.
+            let storage_var = module_types.stored_vars_by_symbol.get(&imm).unwrap();
+            let imported = module_types
+                .storage_subs
+                .export_variable_to(subs, *storage_var);
+
+            // The stored signature comes from the immediate's annotation, so its type variables
+            // are rigid. We are about to unify it with a concrete usage site, so instantiate them.
+            for var in imported.rigid {
+                if let &Content::RigidVar(name) = subs.get_content_without_compacting(var) {
+                    subs.set_content(var, Content::FlexVar(Some(name)));
+                }
+            }
+            for var in imported.rigid_able {
+                if let &Content::RigidAbleVar(name, ability) =
+                    subs.get_content_without_compacting(var)
+                {
+                    subs.set_content(var, Content::FlexAbleVar(Some(name), ability));
+                }
+            }
+
+            Ok(imported.variable)
+        }
     }
 }
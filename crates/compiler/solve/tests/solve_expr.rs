@@ -377,6 +377,9 @@ mod solve_expr {
 
         let known_specializations = abilities_store.iter_declared_implementations().filter_map(
             |(impl_key, member_impl)| match member_impl {
+                // Skip implementations of builtin types (e.g. the hasher `Dict` uses), since
+                // the member definitions of their abilities need not be imported here.
+                _ if impl_key.opaque.module_id() != home => None,
                 MemberImpl::Impl(impl_symbol) => {
                     let specialization = abilities_store.specialization_info(*impl_symbol).expect(
                         "declared implementations should be resolved conclusively after solving",
@@ -1431,6 +1434,146 @@ mod solve_expr {
         infer_eq("{ x: 5, y : 3.14 }.x", "Num *");
     }
 
+    #[test]
+    fn two_elem_tuple() {
+        infer_eq("(5, \"a\")", "(Num *, Str)");
+    }
+
+    #[test]
+    fn tuple_accessor() {
+        infer_eq("(5, 3.14, \"a\").2", "Str");
+    }
+
+    #[test]
+    fn dbg_has_type_of_its_expr() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                inc = \n -> dbg (n + 1)
+
+                dbg inc
+                "#
+            ),
+            "Num a -> Num a",
+        );
+    }
+
+    #[test]
+    fn crash_has_any_type() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                crash "oh no"
+                "#
+            ),
+            "*",
+        );
+    }
+
+    #[test]
+    fn crash_unifies_with_other_branches() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                f : I64 -> Str
+                f = \n ->
+                    if n > 0 then
+                        Num.toStr n
+                    else
+                        crash "n must be positive"
+
+                f
+                "#
+            ),
+            "I64 -> Str",
+        );
+    }
+
+    #[test]
+    fn nested_tuple_accessor() {
+        infer_eq(
+            indoc!(
+                r#"
+                t = (1, ("a", 3))
+
+                t.1.0
+                "#
+            ),
+            "Str",
+        );
+    }
+
+    #[test]
+    fn tuple_accessor_function() {
+        infer_eq(".1", "(*, a)* -> a");
+    }
+
+    #[test]
+    fn tuple_annotation_and_pattern() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                    swap : (a, b) -> (b, a)
+                    swap = \(x, y) -> (y, x)
+
+                    swap
+                "#
+            ),
+            "(a, b) -> (b, a)",
+        );
+    }
+
+    #[test]
+    fn tuple_pattern_is_closed() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                    \(x, _) -> x
+                "#
+            ),
+            "(a, *) -> a",
+        );
+    }
+
+    #[test]
+    fn tuple_with_ten_elems() {
+        infer_eq_without_problem(
+            "(1u8, 2u16, 3u32, 4u64, 5i8, 6i16, 7i32, 8i64, \"9\", 10dec)",
+            "(U8, U16, U32, U64, I8, I16, I32, I64, Str, Dec)",
+        );
+    }
+
+    #[test]
+    fn list_patterns() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                    \l ->
+                        when l is
+                            [] -> ""
+                            [x] -> x
+                            [first, .., _] -> first
+                "#
+            ),
+            "List Str -> Str",
+        );
+    }
+
+    #[test]
+    fn list_rest_pattern_is_a_list() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                    \l ->
+                        when l is
+                            [_, ..rest] -> rest
+                            [] -> []
+                "#
+            ),
+            "List a -> List a",
+        );
+    }
+
     #[test]
     fn record_arg() {
         infer_eq("\\rec -> rec.x", "{ x : a }* -> a");
@@ -1563,6 +1706,47 @@ mod solve_expr {
         );
     }
 
+    #[test]
+    fn nested_record_update() {
+        infer_eq(
+            indoc!(
+                r#"
+                    model = { count: 0, settings: { size: 12, theme: { color: Blue } } }
+
+                    { model & settings.theme.color: Red, settings.size: 14 }
+                "#
+            ),
+            "{ count : Num *, settings : { size : Num *, theme : { color : [Blue, Red]* } } }",
+        );
+    }
+
+    #[test]
+    fn nested_record_update_pun() {
+        infer_eq(
+            indoc!(
+                r#"
+                    model = { count: 0, settings: { size: 12, theme: Blue } }
+                    theme = Red
+
+                    { model & settings.theme }
+                "#
+            ),
+            "{ count : Num *, settings : { size : Num *, theme : [Blue, Red]* } }",
+        );
+    }
+
+    #[test]
+    fn nested_record_update_is_polymorphic() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                    \model -> { model & settings.theme: Dark }
+                "#
+            ),
+            "{ settings : { theme : [Dark]a }b }c -> { settings : { theme : [Dark]a }b }c",
+        );
+    }
+
     #[test]
     fn bare_tag() {
         infer_eq(
@@ -3469,7 +3653,7 @@ mod solve_expr {
                 Dict.insert
                 "#
             ),
-            "Dict k v, k, v -> Dict k v",
+            "Dict k v, k, v -> Dict k v | k has Hash",
         );
     }
 
@@ -3730,7 +3914,9 @@ mod solve_expr {
         infer_eq_without_problem(
             indoc!(
                 r#"
-                reconstructPath : Dict position position, position -> List position
+                app "test" imports [Hash.{ Hash }] provides [main] to "./platform"
+
+                reconstructPath : Dict position position, position -> List position | position has Hash
                 reconstructPath = \cameFrom, goal ->
                     when Dict.get cameFrom goal is
                         Err KeyNotFound ->
@@ -3739,10 +3925,10 @@ mod solve_expr {
                         Ok next ->
                             List.append (reconstructPath cameFrom next) goal
 
-                reconstructPath
+                main = reconstructPath
                 "#
             ),
-            "Dict position position, position -> List position",
+            "Dict position position, position -> List position | position has Hash",
         );
     }
 
@@ -3777,7 +3963,7 @@ mod solve_expr {
 
                 Model position : { openSet : Set position }
 
-                cheapestOpen : Model position -> Result position [KeyNotFound]*
+                cheapestOpen : Model position -> Result position [KeyNotFound]* | position has Eq
                 cheapestOpen = \model ->
 
                     folder = \resSmallestSoFar, position ->
@@ -3792,14 +3978,14 @@ mod solve_expr {
                     Set.walk model.openSet (Ok { position: boom {}, cost: 0.0 }) folder
                         |> Result.map (\x -> x.position)
 
-                astar : Model position -> Result position [KeyNotFound]*
+                astar : Model position -> Result position [KeyNotFound]* | position has Eq
                 astar = \model -> cheapestOpen model
 
                 main =
                     astar
                 "#
             ),
-            "Model position -> Result position [KeyNotFound]*",
+            "Model position -> Result position [KeyNotFound]* | position has Eq",
         );
     }
 
@@ -6149,6 +6335,73 @@ mod solve_expr {
         )
     }
 
+    #[test]
+    fn ability_constrained_in_non_member_check_same_var_name() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                app "test" provides [find] to "./platform"
+
+                Hash has
+                    hash : a -> U64 | a has Hash
+
+                home : k -> U64 | k has Hash
+                home = \key -> hash key
+
+                find : k -> U64 | k has Hash
+                find = \key -> home key
+                "#
+            ),
+            "k -> U64 | k has Hash",
+        )
+    }
+
+    #[test]
+    fn equality_infers_eq_ability() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = \a, b -> a == b
+                "#
+            ),
+            "a, a -> Bool | a has Eq",
+        )
+    }
+
+    #[test]
+    fn opaque_custom_eq_specialization() {
+        check_inferred_abilities(
+            indoc!(
+                r#"
+                app "test" provides [idIsEq] to "./platform"
+
+                Id := U64 has [Eq { isEq: idIsEq }]
+
+                idIsEq = \@Id m, @Id n -> m == n
+                "#
+            ),
+            [("Eq:isEq", "Id")],
+        )
+    }
+
+    #[test]
+    fn opaque_with_hash_implies_eq() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                app "test" imports [Hash.{ Hash }] provides [main] to "./platform"
+
+                Id := U64 has [Hash]
+
+                main = @Id 1 == @Id 2
+                "#
+            ),
+            "Bool",
+        )
+    }
+
     #[test]
     fn ability_constrained_in_non_member_infer() {
         infer_eq_without_problem(
@@ -6527,7 +6780,7 @@ mod solve_expr {
                      # ^^^^^^^^^
                 "#
             ),
-            @"Encoding#toEncoder(2) : { a : Str } -[[#Derived.toEncoder_{a}(0)]]-> Encoder fmt | fmt has EncoderFormatting"
+            @"Encoding#toEncoder(2) : { a : Str } -[[#Derived.toEncoder_{a}(3)]]-> Encoder fmt | fmt has EncoderFormatting"
         )
     }
 
@@ -6547,7 +6800,7 @@ mod solve_expr {
                      # ^^^^^^^^^
                 "#
             ),
-            @"Encoding#toEncoder(2) : { a : A } -[[#Derived.toEncoder_{a}(0)]]-> Encoder fmt | fmt has EncoderFormatting"
+            @"Encoding#toEncoder(2) : { a : A } -[[#Derived.toEncoder_{a}(3)]]-> Encoder fmt | fmt has EncoderFormatting"
         )
     }
 
@@ -31,6 +31,33 @@ pub enum TypeError {
     },
 }
 
+impl TypeError {
+    /// The region in the source that this error is most closely associated with, if any.
+    pub fn region(&self) -> Option<Region> {
+        use TypeError::*;
+
+        match self {
+            BadExpr(region, ..)
+            | BadPattern(region, ..)
+            | CircularType(region, ..)
+            | BadExprMissingAbility(region, ..)
+            | BadPatternMissingAbility(region, ..)
+            | StructuralSpecialization { region, .. }
+            | WrongSpecialization { region, .. } => Some(*region),
+            CircularDef(cycle) => cycle.first().map(|entry| entry.expr_region),
+            Exhaustive(roc_exhaustive::Error::Incomplete(region, ..)) => Some(*region),
+            Exhaustive(roc_exhaustive::Error::Redundant { branch_region, .. }) => {
+                Some(*branch_region)
+            }
+            BadType(problem) => problem.region(),
+            UnfulfilledAbility(Unfulfilled::OpaqueUnderivable { derive_region, .. }) => {
+                Some(*derive_region)
+            }
+            UnfulfilledAbility(_) | UnexposedLookup(_) => None,
+        }
+    }
+}
+
 #[derive(PartialEq, Debug, Clone)]
 pub enum Unfulfilled {
     /// No claimed implementation of an ability for an opaque type.
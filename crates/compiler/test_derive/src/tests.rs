@@ -2,6 +2,7 @@
 
 mod decoding;
 mod encoding;
+mod eq;
+mod hash;
 
-mod pretty_print;
 mod util;
@@ -3,10 +3,10 @@ use std::path::PathBuf;
 use bumpalo::Bump;
 use ven_pretty::DocAllocator;
 
-use crate::pretty_print::{pretty_print_def, Ctx};
 use roc_can::{
     abilities::{AbilitiesStore, SpecializationLambdaSets},
     constraint::Constraints,
+    debug::{pretty_print_def, Ctx},
     def::Def,
     expr::Declarations,
     module::{
@@ -50,12 +50,32 @@ fn module_source_and_path(builtin: DeriveBuiltin) -> (ModuleId, &'static str, Pa
             module_source(ModuleId::DECODE),
             builtins_path.join("Decode.roc"),
         ),
+        DeriveBuiltin::Hash => (
+            ModuleId::HASH,
+            module_source(ModuleId::HASH),
+            builtins_path.join("Hash.roc"),
+        ),
+        DeriveBuiltin::IsEq => (
+            ModuleId::BOOL,
+            module_source(ModuleId::BOOL),
+            builtins_path.join("Bool.roc"),
+        ),
     }
 }
 
 /// DSL for creating [`Content`][roc_types::subs::Content].
 #[macro_export]
 macro_rules! v {
+     (( $($make_v:expr,)+ )) => {{
+         use roc_types::subs::{Subs, TupleElems, Content, FlatType};
+         |subs: &mut Subs| {
+             let elems = [$($make_v(subs),)+];
+             let elems = TupleElems::insert_into_subs(subs, elems.into_iter().enumerate());
+             let ext = roc_derive::synth_var(subs, Content::Structure(FlatType::EmptyTuple));
+
+             roc_derive::synth_var(subs, Content::Structure(FlatType::Tuple(elems, ext)))
+         }
+     }};
      ({ $($field:ident: $make_v:expr,)* $(?$opt_field:ident : $make_opt_v:expr,)* }$( $($ext:tt)+ )?) => {{
          #[allow(unused)]
          use roc_types::types::RecordField;
@@ -247,6 +267,18 @@ where
     assert_eq!(key, Ok(Derived::Immediate(immediate)));
 }
 
+pub(crate) fn check_single_lset_immediate<S>(builtin: DeriveBuiltin, synth: S, immediate: Symbol)
+where
+    S: FnOnce(&mut Subs) -> Variable,
+{
+    let mut subs = Subs::new();
+    let var = synth(&mut subs);
+
+    let key = Derived::builtin(builtin, &subs, var);
+
+    assert_eq!(key, Ok(Derived::SingleLambdaSetImmediate(immediate)));
+}
+
 #[allow(clippy::too_many_arguments)]
 fn assemble_derived_golden(
     subs: &mut Subs,
@@ -62,6 +62,75 @@ fn hash_specialization_multiple_add() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn hash_fractions_by_their_bits() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Hash.{ Hasher }]
+                provides [main] to "./platform"
+
+            # Records every number it is given, truncated to a U64
+            Words := List U64 has [
+                     Hasher {
+                         addBytes: wordsAddBytes,
+                         addU8: wordsAddU8,
+                         addU16: wordsAddU16,
+                         addU32: wordsAddU32,
+                         addU64: wordsAddU64,
+                         addU128: wordsAddU128,
+                         addI8: wordsAddI8,
+                         addI16: wordsAddI16,
+                         addI32: wordsAddI32,
+                         addI64: wordsAddI64,
+                         addI128: wordsAddI128,
+                         complete: wordsComplete,
+                     },
+                 ]
+
+            record = \@Words ws, n -> @Words (List.append ws n)
+
+            wordsAddBytes = \words, _ -> words
+            wordsAddU8 = \words, n -> record words (Num.toU64 n)
+            wordsAddU16 = \words, n -> record words (Num.toU64 n)
+            wordsAddU32 = \words, n -> record words (Num.toU64 n)
+            wordsAddU64 = \words, n -> record words n
+            wordsAddU128 = \words, n -> record words (Num.toU64 n)
+            wordsAddI8 = \words, n -> record words (Num.toU64 n)
+            wordsAddI16 = \words, n -> record words (Num.toU64 n)
+            wordsAddI32 = \words, n -> record words (Num.toU64 n)
+            wordsAddI64 = \words, n -> record words (Num.toU64 n)
+            wordsAddI128 = \words, n -> record words (Num.toU64 n)
+            wordsComplete = \@Words ws -> Num.toU64 (List.len ws)
+
+            main =
+                hasher =
+                    @Words []
+                    |> Hash.hashF32 1.5f32
+                    |> Hash.hashF32 -0.0f32
+                    |> Hash.hashF64 1.5f64
+                    |> Hash.hashF64 0.0f64
+                    |> Hash.hashF64 -0.0f64
+                    |> Hash.hashDec 1.5dec
+
+                when hasher is
+                    @Words ws -> ws
+            "#
+        ),
+        RocList::from_slice(&[
+            0x3FC0_0000,
+            0,
+            0x3FF8_0000_0000_0000,
+            0,
+            0,
+            1_500_000_000_000_000_000,
+        ]),
+        RocList<u64>
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn alias_member_specialization() {
@@ -736,6 +805,29 @@ fn encode_derived_record_with_many_types() {
     )
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn encode_derived_tuple_two_elems() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Encode.{ toEncoder }, Json]
+                provides [main] to "./platform"
+
+            main =
+                tup = (1u8, "foo")
+                result = Str.fromUtf8 (Encode.toBytes tup Json.toUtf8)
+                when result is
+                    Ok s -> s
+                    _ -> "<bad>"
+            "#
+        ),
+        RocStr::from(r#"[1,"foo"]"#),
+        RocStr
+    )
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn decode_use_stdlib() {
@@ -1102,3 +1194,304 @@ fn decode_record_of_record() {
         RocStr
     )
 }
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_tag_two_payloads() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode, Decode, Json] provides [main] to "./platform"
+
+            main =
+                when Str.toUtf8 "{\"A\":[\"ab\",10]}" |> Decode.fromBytes Json.fromUtf8 is
+                    Ok (A "ab" 10u8) -> "ab10"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("ab10"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_tag_no_payload() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode, Decode, Json] provides [main] to "./platform"
+
+            main =
+                decoded : Result [A Str, B] _
+                decoded = Str.toUtf8 "{\"B\":[]}" |> Decode.fromBytes Json.fromUtf8
+
+                when decoded is
+                    Ok (A _) -> "A"
+                    Ok B -> "B"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("B"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_tag_unknown_tag() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode, Decode, Json] provides [main] to "./platform"
+
+            main =
+                decoded : Result [A Str, B] _
+                decoded = Str.toUtf8 "{\"C\":[]}" |> Decode.fromBytes Json.fromUtf8
+
+                when decoded is
+                    Ok (A _) -> "A"
+                    Ok B -> "B"
+                    Err _ -> "error"
+            "#
+        ),
+        RocStr::from("error"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_tag_round_trip_through_encode() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode, Decode, Json] provides [main] to "./platform"
+
+            Shape : [Circle U8, Rect U8 U8]
+
+            main =
+                shape : Shape
+                shape = Rect 3 4
+
+                decoded : Result Shape _
+                decoded = Encode.toBytes shape Json.toUtf8 |> Decode.fromBytes Json.fromUtf8
+
+                when decoded is
+                    Ok (Rect width height) -> width * height
+                    _ -> 0
+            "#
+        ),
+        12,
+        u8
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_tuple_two_elems() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode, Decode, Json] provides [main] to "./platform"
+
+            main =
+                when Str.toUtf8 "[\"ab\",10]" |> Decode.fromBytes Json.fromUtf8 is
+                    Ok ("ab", 10u8) -> "ab10"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("ab10"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_tuple_too_few_elems() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode, Decode, Json] provides [main] to "./platform"
+
+            main =
+                decoded : Result (Str, U8) _
+                decoded = Str.toUtf8 "[\"ab\"]" |> Decode.fromBytes Json.fromUtf8
+
+                when decoded is
+                    Ok _ -> "decoded"
+                    Err _ -> "error"
+            "#
+        ),
+        RocStr::from("error"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_tuple_round_trip_through_encode() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode, Decode, Json] provides [main] to "./platform"
+
+            main =
+                tup : (U8, Str, U8)
+                tup = (3, "by", 4)
+
+                decoded : Result (U8, Str, U8) _
+                decoded = Encode.toBytes tup Json.toUtf8 |> Decode.fromBytes Json.fromUtf8
+
+                when decoded is
+                    Ok (width, "by", height) -> width * height
+                    _ -> 0
+            "#
+        ),
+        12,
+        u8
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn eq_custom_implementation() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            LastDigit := U64 has [Eq { isEq: lastDigitIsEq }]
+
+            lastDigitIsEq = \@LastDigit m, @LastDigit n -> m % 10 == n % 10
+
+            same = \a, b -> a == b
+
+            main =
+                if @LastDigit 3 == @LastDigit 13 && same (@LastDigit 5) (@LastDigit 25) && @LastDigit 1 != @LastDigit 2 then
+                    1u8
+                else
+                    0u8
+            "#
+        ),
+        1,
+        u8
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn eq_derived_structural() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            same = \a, b -> a == b
+
+            main =
+                if same { a: "foo", b: [1u8, 2] } { a: "foo", b: [1, 2] } && !(same [A 1u8] [B]) then
+                    1u8
+                else
+                    0u8
+            "#
+        ),
+        1,
+        u8
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn eq_custom_implementation_in_record() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            LastDigit := U64 has [Eq { isEq: lastDigitIsEq }]
+
+            lastDigitIsEq = \@LastDigit m, @LastDigit n -> m % 10 == n % 10
+
+            main =
+                if { a: @LastDigit 3, b: "x" } == { a: @LastDigit 13, b: "x" } && { a: @LastDigit 3, b: "x" } != { a: @LastDigit 13, b: "y" } then
+                    1u8
+                else
+                    0u8
+            "#
+        ),
+        1,
+        u8
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn eq_custom_implementation_in_list() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            LastDigit := U64 has [Eq { isEq: lastDigitIsEq }]
+
+            lastDigitIsEq = \@LastDigit m, @LastDigit n -> m % 10 == n % 10
+
+            main =
+                if [@LastDigit 3, @LastDigit 4] == [@LastDigit 13, @LastDigit 24] && [@LastDigit 3] != [@LastDigit 4] && [@LastDigit 3] != [] then
+                    1u8
+                else
+                    0u8
+            "#
+        ),
+        1,
+        u8
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn eq_custom_implementation_in_derived_opaque() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            LastDigit := U64 has [Eq { isEq: lastDigitIsEq }]
+
+            lastDigitIsEq = \@LastDigit m, @LastDigit n -> m % 10 == n % 10
+
+            Reading := [Exact LastDigit, Unknown] has [Eq]
+
+            main =
+                if @Reading (Exact (@LastDigit 3)) == @Reading (Exact (@LastDigit 13)) && @Reading (Exact (@LastDigit 3)) != @Reading Unknown then
+                    1u8
+                else
+                    0u8
+            "#
+        ),
+        1,
+        u8
+    )
+}
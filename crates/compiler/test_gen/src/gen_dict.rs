@@ -10,7 +10,7 @@ use crate::helpers::llvm::assert_evals_to;
 use crate::helpers::wasm::assert_evals_to;
 
 use indoc::indoc;
-use roc_std::{RocList, RocStr};
+use roc_std::{RocDict, RocList, RocStr};
 
 #[test]
 #[cfg(any(feature = "gen-llvm"))]
@@ -549,3 +549,111 @@ fn walk_sum_keys() {
         i64
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn eq_ignores_insertion_order() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            dict1 : Dict.Dict I64 Str
+            dict1 =
+                Dict.empty
+                    |> Dict.insert 1 "one"
+                    |> Dict.insert 2 "two"
+                    |> Dict.insert 3 "three"
+
+            dict2 : Dict.Dict I64 Str
+            dict2 =
+                Dict.withCapacity 100
+                    |> Dict.insert 3 "three"
+                    |> Dict.insert 1 "one"
+                    |> Dict.insert 2 "two"
+
+            dict1 == dict2
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn eq_compares_values() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            dict1 : Dict.Dict I64 Str
+            dict1 =
+                Dict.empty
+                    |> Dict.insert 1 "one"
+                    |> Dict.insert 2 "two"
+
+            dict2 : Dict.Dict I64 Str
+            dict2 =
+                Dict.empty
+                    |> Dict.insert 2 "two"
+                    |> Dict.insert 1 "uno"
+
+            dict1 == dict2
+            "#
+        ),
+        false,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn float_keys() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            dict : Dict.Dict F64 Str
+            dict =
+                Dict.empty
+                    |> Dict.insert 1.5 "one and a half"
+                    |> Dict.insert 0.0 "zero"
+                    |> Dict.insert -0.0 "negative zero"
+
+            when Dict.get dict 0.0 is
+                Ok value -> value
+                Err KeyNotFound -> "not found"
+            "#
+        ),
+        RocStr::from("negative zero"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn dict_round_trips_through_rust() {
+    // Rust finds the keys of a dict that Roc built, and builds the same dict from its entries
+    let entries = || {
+        (0..50u64)
+            .map(|n| (RocStr::from(format!("key {}", n).as_str()), n))
+            .chain([(RocStr::from("key 7"), 700)])
+    };
+
+    assert_evals_to!(
+        indoc!(
+            r#"
+            List.range 0u64 50
+                |> List.walk Dict.empty (\dict, n -> Dict.insert dict "key \(Num.toStr n)" n)
+                |> Dict.insert "key 7" 700
+            "#
+        ),
+        entries().collect::<RocDict<RocStr, u64>>(),
+        RocDict<RocStr, u64>,
+        |dict: RocDict<RocStr, u64>| {
+            // the value of "key 7" was replaced
+            for (key, value) in entries().filter(|(_, value)| *value != 7) {
+                assert_eq!(dict.get(&key), Some(&value), "{:?}", key);
+            }
+
+            dict
+        }
+    );
+}
@@ -3394,3 +3394,52 @@ fn list_let_generalization() {
         usize
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn list_pattern_match_length() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            describe : List I64 -> I64
+            describe = \l ->
+                when l is
+                    [] -> 0
+                    [_] -> 1
+                    [_, _] -> 2
+                    _ -> 3
+
+            [describe [], describe [1], describe [1, 2], describe [1, 2, 3]]
+            "#
+        ),
+        RocList::from_slice(&[0, 1, 2, 3]),
+        RocList<i64>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn list_pattern_match_elements_and_rest() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            sumEnds : List I64 -> I64
+            sumEnds = \l ->
+                when l is
+                    [first, .., last] -> first + last
+                    [only] -> only
+                    [] -> 0
+
+            middle : List I64 -> List I64
+            middle = \l ->
+                when l is
+                    [_, ..rest, _] -> rest
+                    _ -> []
+
+            List.append (middle [1, 2, 3, 4]) (sumEnds [10, 20, 30])
+            "#
+        ),
+        RocList::from_slice(&[2, 3, 40]),
+        RocList<i64>
+    );
+}
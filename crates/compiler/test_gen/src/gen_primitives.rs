@@ -4065,3 +4065,73 @@ fn int_let_generalization() {
         RocStr
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[should_panic(expected = "hello crash")]
+fn crash_literal() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            x : U8
+            x = if True then crash "hello crash" else 1u8
+
+            x
+            "#
+        ),
+        1u8,
+        u8
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[should_panic(expected = "negative: -1")]
+fn crash_with_interpolated_message() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            checkPositive : I64 -> I64
+            checkPositive = \n ->
+                if n > 0 then
+                    n
+                else
+                    crash "negative: \(Num.toStr n)"
+
+            checkPositive -1
+            "#
+        ),
+        1,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn crash_in_untaken_branch() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            if False then crash "unreachable" else 42
+            "#
+        ),
+        42,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn dbg_runs_what_follows_it() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            x = dbg 6 * 7
+
+            x + 1
+            "#
+        ),
+        43,
+        i64
+    );
+}
@@ -1084,3 +1084,24 @@ fn update_record_that_is_a_thunk_single_field() {
         RocStr
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn update_nested_record_fields() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main =
+                updated = { model & settings.theme.size: 14, settings.debug: Bool.true }
+
+                updated.count + updated.settings.theme.size + updated.settings.theme.weight
+
+            model = { count: 1, settings: { debug: Bool.false, theme: { size: 12, weight: 400 } } }
+            "#
+        ),
+        415,
+        i64
+    );
+}
@@ -280,3 +280,17 @@ fn from_list_result() {
         i64
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn eq_ignores_insertion_order() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            Set.fromList [1, 2, 3] == Set.fromList [3, 1, 2]
+            "#
+        ),
+        true,
+        bool
+    );
+}
@@ -1930,3 +1930,25 @@ fn when_on_strings() {
         i64
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn block_string_strips_common_indent() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            table = "users"
+
+            query =
+                """
+                    SELECT *
+                      FROM \(table)
+                """
+
+            query
+            "#
+        ),
+        RocStr::from("    SELECT *\n      FROM users"),
+        RocStr
+    );
+}
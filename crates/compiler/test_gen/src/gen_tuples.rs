@@ -0,0 +1,164 @@
+#[cfg(feature = "gen-llvm")]
+use crate::helpers::llvm::assert_evals_to;
+
+#[cfg(feature = "gen-dev")]
+use crate::helpers::dev::assert_evals_to;
+
+#[cfg(feature = "gen-wasm")]
+use crate::helpers::wasm::assert_evals_to;
+
+use indoc::indoc;
+
+#[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
+use roc_std::RocStr;
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn basic_tuple() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                (17, 15, 19).0
+                "#
+        ),
+        17,
+        i64
+    );
+
+    assert_evals_to!(
+        indoc!(
+            r#"
+                (17, 15, 19).1
+                "#
+        ),
+        15,
+        i64
+    );
+
+    assert_evals_to!(
+        indoc!(
+            r#"
+                (17, 15, 19).2
+                "#
+        ),
+        19,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn return_tuple() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                x = 4
+                y = 3
+
+                (x, y)
+                "#
+        ),
+        (4, 3),
+        (i64, i64)
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn tuple_accessor_function() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                List.map [(1, 2), (3, 4)] .1
+                    |> List.sum
+                "#
+        ),
+        6,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn tuple_destructure_in_closure() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                swap : (I64, I64) -> (I64, I64)
+                swap = \(a, b) -> (b, a)
+
+                swap (1, 2)
+                "#
+        ),
+        (2, 1),
+        (i64, i64)
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn tuple_when_pattern() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                pair : (Bool, I64)
+                pair = (False, 42)
+
+                when pair is
+                    (True, n) -> n
+                    (False, n) -> n + 1
+                "#
+        ),
+        43,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn tuple_with_str() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                greeting = ("Hello", "World")
+
+                Str.concat greeting.0 greeting.1
+                "#
+        ),
+        RocStr::from("HelloWorld"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn nested_tuple() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                t = (1, (2, 3))
+
+                t.1.0 + t.1.1
+                "#
+        ),
+        5,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn tuple_with_more_than_ten_elems() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                t = (0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11)
+
+                when t is
+                    (_, _, two, _, _, _, _, _, _, _, ten, _) -> t.2 + t.10 + two * ten
+                "#
+        ),
+        32,
+        i64
+    );
+}
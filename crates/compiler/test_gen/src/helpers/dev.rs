@@ -51,10 +51,11 @@ pub fn helper(
     }
 
     let load_config = LoadConfig {
-        target_info: roc_target::TargetInfo::default_x86_64(),
+        target_info: roc_target::TargetInfo::from(&target_lexicon::Triple::host()),
         render: roc_reporting::report::RenderTarget::ColorTerminal,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
+        ..Default::default()
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -124,15 +125,18 @@ pub fn helper(
     for (home, (module_path, src)) in loaded.sources {
         use roc_reporting::report::{can_problem, type_problem, RocDocAllocator, DEFAULT_PALETTE};
 
+        let parse_problems = loaded.parse_problems.remove(&home).unwrap_or_default();
         let can_problems = loaded.can_problems.remove(&home).unwrap_or_default();
         let type_problems = loaded.type_problems.remove(&home).unwrap_or_default();
 
-        let error_count = can_problems.len() + type_problems.len();
+        let error_count = parse_problems.len() + can_problems.len() + type_problems.len();
 
         if error_count == 0 {
             continue;
         }
 
+        lines.extend(parse_problems);
+
         let line_info = LineInfo::new(&src);
         let src_lines: Vec<&str> = src.split('\n').collect();
         let palette = DEFAULT_PALETTE;
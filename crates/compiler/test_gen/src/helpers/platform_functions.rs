@@ -37,18 +37,24 @@ pub unsafe fn roc_dealloc(c_ptr: *mut c_void, _alignment: u32) {
 /// The Roc application needs this.
 #[no_mangle]
 pub unsafe fn roc_panic(c_ptr: *mut c_void, tag_id: u32) {
-    use roc_gen_llvm::llvm::build::PanicTagId;
+    use roc_mono::ir::CrashTag;
 
     use std::ffi::CStr;
     use std::os::raw::c_char;
 
-    match PanicTagId::try_from(tag_id) {
-        Ok(PanicTagId::NullTerminatedString) => {
+    match CrashTag::try_from(tag_id) {
+        Ok(CrashTag::Roc) => {
             let slice = CStr::from_ptr(c_ptr as *const c_char);
             let string = slice.to_str().unwrap();
             eprintln!("Roc hit a panic: {}", string);
             std::process::exit(1);
         }
+        Ok(CrashTag::User) => {
+            let slice = CStr::from_ptr(c_ptr as *const c_char);
+            let string = slice.to_str().unwrap();
+            eprintln!("Roc crashed with: {}", string);
+            std::process::exit(1);
+        }
         Err(_) => unreachable!(),
     }
 }
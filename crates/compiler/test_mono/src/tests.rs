@@ -99,6 +99,7 @@ fn compiles_to_ir(test_name: &str, src: &str) {
         threading: Threading::Single,
         render: roc_reporting::report::RenderTarget::Generic,
         exec_mode: ExecutionMode::Executable,
+        ..Default::default()
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -1936,3 +1937,69 @@ fn num_width_gt_u8_layout_as_float() {
         "#
     )
 }
+
+#[mono_test]
+fn dbg_borrows_its_value() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main =
+            s = Str.concat "a long string that is not small" "!"
+            t = dbg s
+            Str.concat t s
+        "#
+    )
+}
+
+#[mono_test]
+fn crash() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        getInfallible = \result ->
+            when result is
+                Ok x -> x
+                _ -> crash "turns out this was fallible"
+
+        main =
+            x : [Ok U64, Err {}]
+            x = Ok 78
+            getInfallible x
+        "#
+    )
+}
+
+#[mono_test]
+fn match_list() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main =
+            l : List Str
+            l = ["a", "b", "c"]
+
+            when l is
+                [] -> "empty"
+                [x] -> x
+                [first, .., last] if first == last -> first
+                [_, second, ..rest] -> Str.concat second (Str.joinWith rest "")
+        "#
+    )
+}
+
+#[mono_test]
+fn tuple_with_more_than_ten_elems() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main =
+            t = (0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11)
+
+            t.2 + t.10
+        "#
+    )
+}
@@ -10,6 +10,7 @@ use std::fmt::Write;
 
 pub static WILDCARD: &str = "*";
 static EMPTY_RECORD: &str = "{}";
+static EMPTY_TUPLE: &str = "()";
 static EMPTY_TAG_UNION: &str = "[]";
 
 /// Requirements for parentheses.
@@ -255,6 +256,28 @@ fn find_names_needed(
                 find_under_alias,
             );
         }
+        Structure(Tuple(elems, ext_var)) => {
+            for index in elems.iter_variables() {
+                let var = subs[index];
+                find_names_needed(
+                    var,
+                    subs,
+                    roots,
+                    root_appearances,
+                    names_taken,
+                    find_under_alias,
+                );
+            }
+
+            find_names_needed(
+                *ext_var,
+                subs,
+                roots,
+                root_appearances,
+                names_taken,
+                find_under_alias,
+            );
+        }
         Structure(TagUnion(tags, ext_var)) => {
             for slice_index in tags.variables() {
                 let slice = subs[slice_index];
@@ -402,7 +425,11 @@ fn find_names_needed(
                 find_under_alias,
             );
         }
-        Error | Structure(Erroneous(_)) | Structure(EmptyRecord) | Structure(EmptyTagUnion) => {
+        Error
+        | Structure(Erroneous(_))
+        | Structure(EmptyRecord)
+        | Structure(EmptyTuple)
+        | Structure(EmptyTagUnion) => {
             // Errors and empty records don't need names.
         }
     }
@@ -920,6 +947,7 @@ impl<'a> ExtContent<'a> {
         match content {
             Content::Structure(FlatType::EmptyTagUnion) => ExtContent::Empty,
             Content::Structure(FlatType::EmptyRecord) => ExtContent::Empty,
+            Content::Structure(FlatType::EmptyTuple) => ExtContent::Empty,
 
             Content::FlexVar(_)
             | Content::FlexAbleVar(..)
@@ -1062,6 +1090,7 @@ fn write_flat_type<'a>(
             parens,
         ),
         EmptyRecord => buf.push_str(EMPTY_RECORD),
+        EmptyTuple => buf.push_str(EMPTY_TUPLE),
         EmptyTagUnion => buf.push_str(EMPTY_TAG_UNION),
         Func(args, closure, ret) => write_fn(
             env,
@@ -1137,6 +1166,58 @@ fn write_flat_type<'a>(
                 }
             }
         }
+        Tuple(elems, ext_var) => {
+            use crate::types::{gather_tuple_elems, TupleStructure};
+
+            // If the `ext` has concrete elements, merge them
+            let TupleStructure { elems, ext } = gather_tuple_elems(subs, *elems, *ext_var)
+                .expect("Something ended up weird in this tuple type");
+
+            if elems.is_empty() {
+                buf.push_str(EMPTY_TUPLE)
+            } else {
+                buf.push('(');
+
+                // Elements this tuple type does not mention, because it is only known to have
+                // some element further along, are printed as a wildcard.
+                let mut next_index = 0;
+
+                for (index, var) in elems {
+                    while next_index <= index {
+                        if next_index > 0 {
+                            buf.push_str(", ");
+                        }
+
+                        if next_index < index {
+                            buf.push_str(WILDCARD);
+                        }
+
+                        next_index += 1;
+                    }
+
+                    write_content(
+                        env,
+                        ctx,
+                        subs.get_content_without_compacting(var),
+                        subs,
+                        buf,
+                        Parens::Unnecessary,
+                    );
+                }
+
+                buf.push(')');
+            }
+
+            match subs.get_content_without_compacting(ext) {
+                Content::Structure(EmptyTuple) => {
+                    // This is a closed tuple. We're done!
+                }
+                content => {
+                    // This is an open tuple, so print the variable right after the ')'
+                    write_content(env, ctx, content, subs, buf, parens)
+                }
+            }
+        }
         TagUnion(tags, ext_var) => {
             buf.push('[');
 
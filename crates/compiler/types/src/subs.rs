@@ -19,6 +19,7 @@ roc_error_macros::assert_sizeof_all!(Descriptor, 5 * 8 + 4);
 roc_error_macros::assert_sizeof_all!(FlatType, 3 * 8);
 roc_error_macros::assert_sizeof_all!(UnionTags, 12);
 roc_error_macros::assert_sizeof_all!(RecordFields, 2 * 8);
+roc_error_macros::assert_sizeof_all!(TupleElems, 12);
 
 roc_error_macros::assert_sizeof_aarch64!(Problem, 6 * 8);
 roc_error_macros::assert_sizeof_wasm!(Problem, 32);
@@ -75,6 +76,7 @@ struct SubsHeader {
     closure_names: u64,
     field_names: u64,
     record_fields: u64,
+    tuple_elem_indices: u64,
     variable_slices: u64,
     unspecialized_lambda_sets: u64,
     exposed_vars_by_symbol: u64,
@@ -93,6 +95,7 @@ impl SubsHeader {
             closure_names: subs.closure_names.len() as u64,
             field_names: subs.field_names.len() as u64,
             record_fields: subs.record_fields.len() as u64,
+            tuple_elem_indices: subs.tuple_elem_indices.len() as u64,
             variable_slices: subs.variable_slices.len() as u64,
             unspecialized_lambda_sets: subs.unspecialized_lambda_sets.len() as u64,
             exposed_vars_by_symbol: exposed_vars_by_symbol as u64,
@@ -142,6 +145,7 @@ impl Subs {
         written = Self::serialize_slice(&self.closure_names, writer, written)?;
         written = Self::serialize_field_names(&self.field_names, writer, written)?;
         written = Self::serialize_slice(&self.record_fields, writer, written)?;
+        written = Self::serialize_slice(&self.tuple_elem_indices, writer, written)?;
         written = Self::serialize_slice(&self.variable_slices, writer, written)?;
         written = Self::serialize_slice(&self.unspecialized_lambda_sets, writer, written)?;
         written = Self::serialize_slice(exposed_vars_by_symbol, writer, written)?;
@@ -225,6 +229,8 @@ impl Subs {
             Self::deserialize_field_names(bytes, header.field_names as usize, offset);
         let (record_fields, offset) =
             Self::deserialize_slice(bytes, header.record_fields as usize, offset);
+        let (tuple_elem_indices, offset) =
+            Self::deserialize_slice(bytes, header.tuple_elem_indices as usize, offset);
         let (variable_slices, offset) =
             Self::deserialize_slice(bytes, header.variable_slices as usize, offset);
         let (unspecialized_lambda_sets, offset) =
@@ -240,6 +246,7 @@ impl Subs {
                 closure_names: closure_names.to_vec(),
                 field_names,
                 record_fields: record_fields.to_vec(),
+                tuple_elem_indices: tuple_elem_indices.to_vec(),
                 variable_slices: variable_slices.to_vec(),
                 unspecialized_lambda_sets: unspecialized_lambda_sets.to_vec(),
                 tag_name_cache: Default::default(),
@@ -250,6 +257,77 @@ impl Subs {
         )
     }
 
+    /// Feeds the types stored in these subs into `state`, ignoring ranks, marks and copies.
+    ///
+    /// Unlike the bytes produced by [Subs::serialize], the result is stable across runs, so it
+    /// can be used to tell whether two subs describe the same types.
+    pub fn hash_structure<H: std::hash::Hasher>(&self, state: &mut H) {
+        use std::hash::Hash;
+
+        self.len().hash(state);
+
+        for index in 0..self.len() {
+            // Safety: we only visit variables that exist in this subs
+            let var = unsafe { Variable::from_index(index as u32) };
+
+            self.utable.root_key_without_compacting(var).hash(state);
+            self.get_content_without_compacting(var).hash(state);
+        }
+
+        self.variables.hash(state);
+        self.tag_names.hash(state);
+        self.closure_names.hash(state);
+        self.field_names.hash(state);
+        self.record_fields.hash(state);
+        self.tuple_elem_indices.hash(state);
+        self.variable_slices.hash(state);
+        self.unspecialized_lambda_sets.hash(state);
+        self.problems.hash(state);
+    }
+
+    /// Replaces every symbol these subs refer to with what `f` maps it to, giving up on the first
+    /// symbol that `f` has no replacement for.
+    pub fn map_symbols(&mut self, mut f: impl FnMut(Symbol) -> Option<Symbol>) -> Option<()> {
+        for index in 0..self.len() {
+            // Safety: we only visit variables that exist in this subs
+            let var = unsafe { Variable::from_index(index as u32) };
+
+            // only the content of a root is ever looked at
+            if self.utable.root_key_without_compacting(var) != var {
+                continue;
+            }
+
+            use Content::*;
+            use FlatType::*;
+
+            let content = match *self.get_content_without_compacting(var) {
+                FlexAbleVar(name, ability) => FlexAbleVar(name, f(ability)?),
+                RigidAbleVar(name, ability) => RigidAbleVar(name, f(ability)?),
+                Alias(symbol, arguments, real_var, kind) => {
+                    Alias(f(symbol)?, arguments, real_var, kind)
+                }
+                Structure(Apply(symbol, arguments)) => Structure(Apply(f(symbol)?, arguments)),
+                Structure(FunctionOrTagUnion(tag_name, symbol, ext)) => {
+                    Structure(FunctionOrTagUnion(tag_name, f(symbol)?, ext))
+                }
+                _ => continue,
+            };
+
+            self.set_content_unchecked(var, content);
+        }
+
+        // the lambdas of lambda sets
+        for symbol in self.closure_names.iter_mut() {
+            *symbol = f(*symbol)?;
+        }
+
+        for Uls(_, member, _) in self.unspecialized_lambda_sets.iter_mut() {
+            *member = f(*member)?;
+        }
+
+        Some(())
+    }
+
     fn deserialize_field_names(
         bytes: &[u8],
         length: usize,
@@ -389,6 +467,7 @@ pub struct Subs {
     pub closure_names: Vec<Symbol>,
     pub field_names: Vec<Lowercase>,
     pub record_fields: Vec<RecordField<()>>,
+    pub tuple_elem_indices: Vec<usize>,
     pub variable_slices: Vec<VariableSubsSlice>,
     pub unspecialized_lambda_sets: Vec<Uls>,
     pub tag_name_cache: TagNameCache,
@@ -525,6 +604,20 @@ impl std::ops::IndexMut<SubsIndex<RecordField<()>>> for Subs {
     }
 }
 
+impl std::ops::Index<SubsIndex<usize>> for Subs {
+    type Output = usize;
+
+    fn index(&self, index: SubsIndex<usize>) -> &Self::Output {
+        &self.tuple_elem_indices[index.index as usize]
+    }
+}
+
+impl std::ops::IndexMut<SubsIndex<usize>> for Subs {
+    fn index_mut(&mut self, index: SubsIndex<usize>) -> &mut Self::Output {
+        &mut self.tuple_elem_indices[index.index as usize]
+    }
+}
+
 impl std::ops::Index<SubsIndex<VariableSubsSlice>> for Subs {
     type Output = VariableSubsSlice;
 
@@ -587,6 +680,19 @@ impl<T> Clone for SubsSlice<T> {
     }
 }
 
+impl<T> std::hash::Hash for SubsIndex<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl<T> std::hash::Hash for SubsSlice<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.start.hash(state);
+        self.length.hash(state);
+    }
+}
+
 impl<T> Default for SubsSlice<T> {
     fn default() -> Self {
         Self {
@@ -736,6 +842,12 @@ impl GetSubsSlice<RecordField<()>> for Subs {
     }
 }
 
+impl GetSubsSlice<usize> for Subs {
+    fn get_subs_slice(&self, subs_slice: SubsSlice<usize>) -> &[usize] {
+        subs_slice.get_slice(&self.tuple_elem_indices)
+    }
+}
+
 impl GetSubsSlice<Lowercase> for Subs {
     fn get_subs_slice(&self, subs_slice: SubsSlice<Lowercase>) -> &[Lowercase] {
         subs_slice.get_slice(&self.field_names)
@@ -943,6 +1055,21 @@ fn subs_fmt_flat_type(this: &FlatType, subs: &Subs, f: &mut fmt::Formatter) -> f
 
             write!(f, "}}<{:?}>", new_ext)
         }
+        FlatType::Tuple(elems, ext) => {
+            write!(f, "( ")?;
+
+            let (it, new_ext) = elems.sorted_iterator_and_ext(subs, *ext);
+            for (index, var) in it {
+                write!(
+                    f,
+                    "{:?}: {:?}, ",
+                    index,
+                    SubsFmtContent(subs.get_content_without_compacting(var), subs)
+                )?;
+            }
+
+            write!(f, ")<{:?}>", new_ext)
+        }
         FlatType::TagUnion(tags, ext) => {
             write!(f, "[")?;
 
@@ -983,6 +1110,7 @@ fn subs_fmt_flat_type(this: &FlatType, subs: &Subs, f: &mut fmt::Formatter) -> f
         }
         FlatType::Erroneous(e) => write!(f, "Erroneous({:?})", e),
         FlatType::EmptyRecord => write!(f, "EmptyRecord"),
+        FlatType::EmptyTuple => write!(f, "EmptyTuple"),
         FlatType::EmptyTagUnion => write!(f, "EmptyTagUnion"),
     }
 }
@@ -1146,6 +1274,14 @@ impl ExhaustiveMark {
     pub fn is_non_exhaustive(&self, subs: &Subs) -> bool {
         matches!(subs.get_content_without_compacting(self.0), Content::Error)
     }
+
+    pub const fn from_variable(var: Variable) -> Self {
+        Self(var)
+    }
+
+    pub const fn variable(&self) -> Variable {
+        self.0
+    }
 }
 
 /// Marks whether a when branch is redundant using a variable.
@@ -1170,6 +1306,14 @@ impl RedundantMark {
     pub fn is_redundant(&self, subs: &Subs) -> bool {
         matches!(subs.get_content_without_compacting(self.0), Content::Error)
     }
+
+    pub const fn from_variable(var: Variable) -> Self {
+        Self(var)
+    }
+
+    pub const fn variable(&self) -> Variable {
+        self.0
+    }
 }
 
 pub fn new_marks(var_store: &mut VarStore) -> (RedundantMark, ExhaustiveMark) {
@@ -1207,6 +1351,14 @@ impl IllegalCycleMark {
             false
         }
     }
+
+    pub fn from_variable(var: Option<Variable>) -> Self {
+        Self(var.map_or(OptVariable::NONE, OptVariable::some))
+    }
+
+    pub const fn variable(&self) -> Option<Variable> {
+        self.0.into_variable()
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -1706,6 +1858,7 @@ impl Subs {
             closure_names: Vec::new(),
             field_names: Vec::new(),
             record_fields: Vec::new(),
+            tuple_elem_indices: Vec::new(),
             // store an empty slice at the first position
             // used for "TagOrFunction"
             variable_slices: vec![VariableSubsSlice::default()],
@@ -2234,7 +2387,7 @@ roc_error_macros::assert_sizeof_default!((Variable, Option<Lowercase>), 4 * 8);
 roc_error_macros::assert_copyable!(Content);
 roc_error_macros::assert_copyable!(Descriptor);
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Hash)]
 pub enum Content {
     /// A type variable which the user did not name in an annotation,
     ///
@@ -2268,7 +2421,7 @@ pub enum Content {
 ///   if b then f else g
 ///
 /// has the type {} -[f, g]-> {} where [f, g] is the solved lambda set.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Hash)]
 pub struct LambdaSet {
     /// The resolved lambda symbols we know.
     pub solved: UnionLambdas,
@@ -2307,7 +2460,7 @@ pub struct LambdaSet {
     pub ambient_function: Variable,
 }
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, Hash)]
 pub struct AliasVariables {
     pub variables_start: u32,
     pub all_variables_len: u16,
@@ -2413,16 +2566,18 @@ impl Content {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Hash)]
 pub enum FlatType {
     Apply(Symbol, VariableSubsSlice),
     Func(VariableSubsSlice, Variable, Variable),
     Record(RecordFields, Variable),
+    Tuple(TupleElems, Variable),
     TagUnion(UnionTags, Variable),
     FunctionOrTagUnion(SubsIndex<TagName>, Symbol, Variable),
     RecursiveTagUnion(Variable, UnionTags, Variable),
     Erroneous(SubsIndex<Problem>),
     EmptyRecord,
+    EmptyTuple,
     EmptyTagUnion,
 }
 
@@ -2559,7 +2714,7 @@ impl Label for Symbol {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Hash)]
 pub struct UnionLabels<L> {
     length: u16,
     labels_start: u32,
@@ -2907,7 +3062,7 @@ pub fn is_empty_tag_union(subs: &Subs, mut var: Variable) -> bool {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Hash)]
 pub struct RecordFields {
     pub length: u16,
     pub field_names_start: u32,
@@ -3097,6 +3252,142 @@ impl RecordFields {
     }
 }
 
+/// The elements of a tuple type, keyed by their position in the tuple.
+#[derive(Clone, Copy, Debug, Hash)]
+pub struct TupleElems {
+    pub length: u16,
+    pub elem_index_start: u32,
+    pub variables_start: u32,
+}
+
+pub type SortedElemsIterator<'a> = Box<dyn Iterator<Item = (usize, Variable)> + 'a>;
+
+impl TupleElems {
+    pub const fn len(&self) -> usize {
+        self.length as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            length: 0,
+            elem_index_start: 0,
+            variables_start: 0,
+        }
+    }
+
+    pub const fn variables(&self) -> SubsSlice<Variable> {
+        SubsSlice::new(self.variables_start, self.length)
+    }
+
+    pub const fn elem_indices(&self) -> SubsSlice<usize> {
+        SubsSlice::new(self.elem_index_start, self.length)
+    }
+
+    pub fn iter_variables(&self) -> impl Iterator<Item = SubsIndex<Variable>> {
+        self.variables().into_iter()
+    }
+
+    pub fn insert_into_subs<I>(subs: &mut Subs, input: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, Variable)>,
+    {
+        let elem_index_start = subs.tuple_elem_indices.len() as u32;
+        let variables_start = subs.variables.len() as u32;
+
+        let it = input.into_iter();
+        let size_hint = it.size_hint().0;
+
+        subs.variables.reserve(size_hint);
+        subs.tuple_elem_indices.reserve(size_hint);
+
+        let mut length = 0;
+        for (index, var) in it {
+            subs.tuple_elem_indices.push(index);
+            subs.variables.push(var);
+
+            length += 1;
+        }
+
+        TupleElems {
+            length,
+            elem_index_start,
+            variables_start,
+        }
+    }
+
+    #[inline(always)]
+    pub fn unsorted_iterator_and_ext<'a>(
+        &'a self,
+        subs: &'a Subs,
+        ext: Variable,
+    ) -> (impl Iterator<Item = (usize, Variable)> + 'a, Variable) {
+        let (it, ext) = crate::types::gather_tuple_elems_unsorted_iter(subs, *self, ext)
+            .expect("Something weird ended up in a tuple type");
+
+        (it, ext)
+    }
+
+    /// get a sorted iterator over the elements of this tuple type
+    ///
+    /// Like [RecordFields::sorted_iterator_and_ext], the elements are read directly from Subs
+    /// when the tuple is closed. Otherwise we chase the ext var and sort by position.
+    #[inline(always)]
+    pub fn sorted_iterator_and_ext<'a>(
+        &'_ self,
+        subs: &'a Subs,
+        ext: Variable,
+    ) -> (SortedElemsIterator<'a>, Variable) {
+        if is_empty_tuple(subs, ext) {
+            (
+                Box::new(self.iter_all().map(move |(i1, i2)| (subs[i1], subs[i2]))),
+                ext,
+            )
+        } else {
+            let tuple_structure = crate::types::gather_tuple_elems(subs, *self, ext)
+                .expect("Something ended up weird in this tuple type");
+
+            (
+                Box::new(tuple_structure.elems.into_iter()),
+                tuple_structure.ext,
+            )
+        }
+    }
+
+    pub fn iter_all(&self) -> impl Iterator<Item = (SubsIndex<usize>, SubsIndex<Variable>)> {
+        self.elem_indices()
+            .into_iter()
+            .zip(self.variables().into_iter())
+    }
+}
+
+fn is_empty_tuple(subs: &Subs, mut var: Variable) -> bool {
+    use crate::subs::Content::*;
+    use crate::subs::FlatType::*;
+
+    loop {
+        match subs.get_content_without_compacting(var) {
+            Structure(EmptyTuple) => return true,
+            Structure(Tuple(sub_elems, sub_ext)) => {
+                if !sub_elems.is_empty() {
+                    return false;
+                }
+
+                var = *sub_ext;
+            }
+
+            Alias(_, _, actual_var, _) => {
+                var = *actual_var;
+            }
+
+            _ => return false,
+        }
+    }
+}
+
 fn is_empty_record(subs: &Subs, mut var: Variable) -> bool {
     use crate::subs::Content::*;
     use crate::subs::FlatType::*;
@@ -3164,6 +3455,10 @@ fn occurs(
                         let it = once(ext_var).chain(subs.get_subs_slice(slice).iter());
                         short_circuit(subs, root_var, &new_seen, it)
                     }
+                    Tuple(elems, ext_var) => {
+                        let it = once(ext_var).chain(subs.get_subs_slice(elems.variables()).iter());
+                        short_circuit(subs, root_var, &new_seen, it)
+                    }
                     TagUnion(tags, ext_var) => {
                         occurs_union(subs, root_var, &new_seen, tags)?;
 
@@ -3178,7 +3473,7 @@ fn occurs(
 
                         short_circuit_help(subs, root_var, &new_seen, *ext_var)
                     }
-                    EmptyRecord | EmptyTagUnion | Erroneous(_) => Ok(()),
+                    EmptyRecord | EmptyTuple | EmptyTagUnion | Erroneous(_) => Ok(()),
                 }
             }
             Alias(_, args, _, _) => {
@@ -3348,8 +3643,19 @@ fn explicit_substitute(
 
                         subs.set_content(in_var, Structure(Record(vars_by_field, new_ext_var)));
                     }
+                    Tuple(elems, ext_var) => {
+                        let new_ext_var = explicit_substitute(subs, from, to, ext_var, seen);
+
+                        for index in elems.iter_variables() {
+                            let var = subs[index];
+                            let new_var = explicit_substitute(subs, from, to, var, seen);
+                            subs[index] = new_var;
+                        }
+
+                        subs.set_content(in_var, Structure(Tuple(elems, new_ext_var)));
+                    }
 
-                    EmptyRecord | EmptyTagUnion | Erroneous(_) => {}
+                    EmptyRecord | EmptyTuple | EmptyTagUnion | Erroneous(_) => {}
                 }
 
                 in_var
@@ -3532,9 +3838,10 @@ fn get_var_names(
                     accum
                 }
 
-                FlatType::EmptyRecord | FlatType::EmptyTagUnion | FlatType::Erroneous(_) => {
-                    taken_names
-                }
+                FlatType::EmptyRecord
+                | FlatType::EmptyTuple
+                | FlatType::EmptyTagUnion
+                | FlatType::Erroneous(_) => taken_names,
 
                 FlatType::Record(vars_by_field, ext_var) => {
                     let mut accum = get_var_names(subs, ext_var, taken_names);
@@ -3547,6 +3854,17 @@ fn get_var_names(
 
                     accum
                 }
+                FlatType::Tuple(elems, ext_var) => {
+                    let mut accum = get_var_names(subs, ext_var, taken_names);
+
+                    for var_index in elems.iter_variables() {
+                        let arg_var = subs[var_index];
+
+                        accum = get_var_names(subs, arg_var, accum)
+                    }
+
+                    accum
+                }
                 FlatType::TagUnion(tags, ext_var) => {
                     let taken_names = get_var_names(subs, ext_var, taken_names);
                     get_var_names_union(subs, tags, taken_names)
@@ -3811,6 +4129,7 @@ fn flat_type_to_err_type(
         }
 
         EmptyRecord => ErrorType::Record(SendMap::default(), TypeExt::Closed),
+        EmptyTuple => ErrorType::Tuple(Vec::new(), TypeExt::Closed),
         EmptyTagUnion => ErrorType::TagUnion(SendMap::default(), TypeExt::Closed),
 
         Record(vars_by_field, ext_var) => {
@@ -3852,6 +4171,41 @@ fn flat_type_to_err_type(
             }
         }
 
+        Tuple(elems, ext_var) => {
+            let mut err_elems = Vec::with_capacity(elems.len());
+
+            for (i1, i2) in elems.iter_all() {
+                let index = subs[i1];
+                let var = subs[i2];
+
+                err_elems.push((index, var_to_err_type(subs, state, var)));
+            }
+
+            match var_to_err_type(subs, state, ext_var).unwrap_structural_alias() {
+                ErrorType::Tuple(sub_elems, sub_ext) => {
+                    err_elems.extend(sub_elems);
+                    err_elems.sort_by_key(|(index, _)| *index);
+
+                    ErrorType::Tuple(err_elems, sub_ext)
+                }
+
+                ErrorType::FlexVar(var) => {
+                    err_elems.sort_by_key(|(index, _)| *index);
+
+                    ErrorType::Tuple(err_elems, TypeExt::FlexOpen(var))
+                }
+
+                ErrorType::RigidVar(var) => {
+                    err_elems.sort_by_key(|(index, _)| *index);
+
+                    ErrorType::Tuple(err_elems, TypeExt::RigidOpen(var))
+                }
+
+                other =>
+                    panic!("Tried to convert a tuple extension to an error, but the tuple extension had the ErrorType of {:?}", other)
+            }
+        }
+
         TagUnion(tags, ext_var) => {
             let err_tags = union_tags_to_err_tags(subs, state, tags);
 
@@ -4009,6 +4363,7 @@ struct StorageSubsOffsets {
     closure_names: u32,
     field_names: u32,
     record_fields: u32,
+    tuple_elem_indices: u32,
     variable_slices: u32,
     unspecialized_lambda_sets: u32,
     problems: u32,
@@ -4093,6 +4448,7 @@ impl StorageSubs {
             closure_names: self.subs.closure_names.len() as u32,
             field_names: self.subs.field_names.len() as u32,
             record_fields: self.subs.record_fields.len() as u32,
+            tuple_elem_indices: self.subs.tuple_elem_indices.len() as u32,
             variable_slices: self.subs.variable_slices.len() as u32,
             unspecialized_lambda_sets: self.subs.unspecialized_lambda_sets.len() as u32,
             problems: self.subs.problems.len() as u32,
@@ -4105,6 +4461,7 @@ impl StorageSubs {
             closure_names: target.closure_names.len() as u32,
             field_names: target.field_names.len() as u32,
             record_fields: target.record_fields.len() as u32,
+            tuple_elem_indices: target.tuple_elem_indices.len() as u32,
             variable_slices: target.variable_slices.len() as u32,
             unspecialized_lambda_sets: target.unspecialized_lambda_sets.len() as u32,
             problems: target.problems.len() as u32,
@@ -4153,6 +4510,9 @@ impl StorageSubs {
         target.closure_names.extend(self.subs.closure_names);
         target.field_names.extend(self.subs.field_names);
         target.record_fields.extend(self.subs.record_fields);
+        target
+            .tuple_elem_indices
+            .extend(self.subs.tuple_elem_indices);
         target
             .unspecialized_lambda_sets
             .extend(self.subs.unspecialized_lambda_sets);
@@ -4193,6 +4553,10 @@ impl StorageSubs {
                 Self::offset_record_fields(offsets, *record_fields),
                 Self::offset_variable(offsets, *ext),
             ),
+            FlatType::Tuple(elems, ext) => FlatType::Tuple(
+                Self::offset_tuple_elems(offsets, *elems),
+                Self::offset_variable(offsets, *ext),
+            ),
             FlatType::TagUnion(union_tags, ext) => FlatType::TagUnion(
                 Self::offset_tag_union(offsets, *union_tags),
                 Self::offset_variable(offsets, *ext),
@@ -4211,6 +4575,7 @@ impl StorageSubs {
                 FlatType::Erroneous(Self::offset_problem(offsets, *problem))
             }
             FlatType::EmptyRecord => FlatType::EmptyRecord,
+            FlatType::EmptyTuple => FlatType::EmptyTuple,
             FlatType::EmptyTagUnion => FlatType::EmptyTagUnion,
         }
     }
@@ -4290,6 +4655,13 @@ impl StorageSubs {
         record_fields
     }
 
+    fn offset_tuple_elems(offsets: &StorageSubsOffsets, mut elems: TupleElems) -> TupleElems {
+        elems.elem_index_start += offsets.tuple_elem_indices;
+        elems.variables_start += offsets.variables;
+
+        elems
+    }
+
     fn offset_tag_name_index(
         offsets: &StorageSubsOffsets,
         mut tag_name: SubsIndex<TagName>,
@@ -4493,7 +4865,10 @@ fn storage_copy_var_to_help(env: &mut StorageCopyVarToEnv<'_>, var: Variable) ->
                     Func(new_arguments, new_closure_var, new_ret_var)
                 }
 
-                same @ EmptyRecord | same @ EmptyTagUnion | same @ Erroneous(_) => same,
+                same @ EmptyRecord
+                | same @ EmptyTuple
+                | same @ EmptyTagUnion
+                | same @ Erroneous(_) => same,
 
                 Record(fields, ext_var) => {
                     let record_fields = {
@@ -4530,6 +4905,36 @@ fn storage_copy_var_to_help(env: &mut StorageCopyVarToEnv<'_>, var: Variable) ->
                     Record(record_fields, storage_copy_var_to_help(env, ext_var))
                 }
 
+                Tuple(elems, ext_var) => {
+                    let tuple_elems = {
+                        let new_variables =
+                            VariableSubsSlice::reserve_into_subs(env.target, elems.len());
+
+                        let it = (new_variables.indices()).zip(elems.iter_variables());
+                        for (target_index, var_index) in it {
+                            let var = env.source[var_index];
+                            let copy_var = storage_copy_var_to_help(env, var);
+                            env.target.variables[target_index] = copy_var;
+                        }
+
+                        let elem_index_start = env.target.tuple_elem_indices.len() as u32;
+
+                        let elem_indices =
+                            &env.source.tuple_elem_indices[elems.elem_indices().indices()];
+                        env.target
+                            .tuple_elem_indices
+                            .extend(elem_indices.iter().copied());
+
+                        TupleElems {
+                            length: elems.len() as _,
+                            elem_index_start,
+                            variables_start: new_variables.start,
+                        }
+                    };
+
+                    Tuple(tuple_elems, storage_copy_var_to_help(env, ext_var))
+                }
+
                 TagUnion(tags, ext_var) => {
                     let new_ext = storage_copy_var_to_help(env, ext_var);
                     let union_tags = storage_copy_union(env, tags);
@@ -4931,7 +5336,7 @@ fn copy_import_to_help(env: &mut CopyImportEnv<'_>, max_rank: Rank, var: Variabl
 
                 Erroneous(_) => internal_error!("I thought this was handled above"),
 
-                same @ EmptyRecord | same @ EmptyTagUnion => same,
+                same @ EmptyRecord | same @ EmptyTuple | same @ EmptyTagUnion => same,
 
                 Record(fields, ext_var) => {
                     let record_fields = {
@@ -4968,6 +5373,36 @@ fn copy_import_to_help(env: &mut CopyImportEnv<'_>, max_rank: Rank, var: Variabl
                     Record(record_fields, copy_import_to_help(env, max_rank, ext_var))
                 }
 
+                Tuple(elems, ext_var) => {
+                    let tuple_elems = {
+                        let new_variables =
+                            VariableSubsSlice::reserve_into_subs(env.target, elems.len());
+
+                        let it = (new_variables.indices()).zip(elems.iter_variables());
+                        for (target_index, var_index) in it {
+                            let var = env.source[var_index];
+                            let copy_var = copy_import_to_help(env, max_rank, var);
+                            env.target.variables[target_index] = copy_var;
+                        }
+
+                        let elem_index_start = env.target.tuple_elem_indices.len() as u32;
+
+                        let elem_indices =
+                            &env.source.tuple_elem_indices[elems.elem_indices().indices()];
+                        env.target
+                            .tuple_elem_indices
+                            .extend(elem_indices.iter().copied());
+
+                        TupleElems {
+                            length: elems.len() as _,
+                            elem_index_start,
+                            variables_start: new_variables.start,
+                        }
+                    };
+
+                    Tuple(tuple_elems, copy_import_to_help(env, max_rank, ext_var))
+                }
+
                 TagUnion(tags, ext_var) => {
                     let new_ext = copy_import_to_help(env, max_rank, ext_var);
 
@@ -5245,6 +5680,7 @@ fn instantiate_rigids_help(subs: &mut Subs, max_rank: Rank, initial: Variable) {
                 }
 
                 EmptyRecord => (),
+                EmptyTuple => (),
                 EmptyTagUnion => (),
 
                 Record(fields, ext_var) => {
@@ -5254,6 +5690,13 @@ fn instantiate_rigids_help(subs: &mut Subs, max_rank: Rank, initial: Variable) {
 
                     stack.push(ext_var);
                 }
+                Tuple(elems, ext_var) => {
+                    let elems = *elems;
+                    let ext_var = *ext_var;
+                    stack.extend(var_slice!(elems.variables()));
+
+                    stack.push(ext_var);
+                }
                 TagUnion(tags, ext_var) => {
                     let tags = *tags;
                     let ext_var = *ext_var;
@@ -5366,6 +5809,10 @@ pub fn get_member_lambda_sets_at_region(subs: &Subs, var: Variable, target_regio
                     stack.extend(subs.get_subs_slice(fields.variables()));
                     stack.push(*ext);
                 }
+                FlatType::Tuple(elems, ext) => {
+                    stack.extend(subs.get_subs_slice(elems.variables()));
+                    stack.push(*ext);
+                }
                 FlatType::TagUnion(tags, ext) => {
                     stack.extend(
                         subs.get_subs_slice(tags.variables())
@@ -5386,7 +5833,10 @@ pub fn get_member_lambda_sets_at_region(subs: &Subs, var: Variable, target_regio
                     );
                     stack.push(*ext);
                 }
-                FlatType::Erroneous(_) | FlatType::EmptyRecord | FlatType::EmptyTagUnion => {}
+                FlatType::Erroneous(_)
+                | FlatType::EmptyRecord
+                | FlatType::EmptyTuple
+                | FlatType::EmptyTagUnion => {}
             },
             Content::Alias(_, _, real_var, _) => {
                 stack.push(*real_var);
@@ -450,6 +450,9 @@ pub fn load_modules_for_files(filenames: Vec<PathBuf>) -> Vec<LoadedModule> {
             render: roc_reporting::report::RenderTarget::ColorTerminal,
             threading: Threading::AllAvailable,
             exec_mode: ExecutionMode::Check,
+            // Modules restored from the cache come without their docs
+            cache_dir: None,
+            ..Default::default()
         };
         match roc_load::load_and_typecheck(&arena, filename, Default::default(), load_config) {
             Ok(loaded) => modules.push(loaded),
@@ -630,6 +633,21 @@ fn type_annotation_to_html(
 
             type_annotation_to_html(indent_level, buf, extension, true);
         }
+        TypeAnnotation::Tuple { elems, extension } => {
+            buf.push('(');
+
+            for (index, elem) in elems.iter().enumerate() {
+                if index > 0 {
+                    buf.push_str(", ");
+                }
+
+                type_annotation_to_html(indent_level, buf, elem, false);
+            }
+
+            buf.push(')');
+
+            type_annotation_to_html(indent_level, buf, extension, true);
+        }
         TypeAnnotation::Function { args, output } => {
             let mut peekable_args = args.iter().peekable();
             while let Some(arg) = peekable_args.next() {
@@ -741,6 +759,9 @@ fn should_be_multiline(type_ann: &TypeAnnotation) -> bool {
 
             is_multiline
         }
+        TypeAnnotation::Tuple { elems, extension } => {
+            should_be_multiline(extension) || elems.iter().any(should_be_multiline)
+        }
         TypeAnnotation::Ability { .. } => true,
         TypeAnnotation::Wildcard => false,
         TypeAnnotation::NoTypeAnn => false,
@@ -74,6 +74,7 @@ pub fn load_types(
     let subs_by_module = Default::default();
     let LoadedModule {
         module_id: home,
+        mut parse_problems,
         mut can_problems,
         mut type_problems,
         mut declarations_by_id,
@@ -89,6 +90,7 @@ pub fn load_types(
             render: RenderTarget::Generic,
             threading,
             exec_mode: ExecutionMode::Check,
+            ..Default::default()
         },
     )
     .unwrap_or_else(|problem| match problem {
@@ -105,6 +107,14 @@ pub fn load_types(
     let decls = declarations_by_id.remove(&home).unwrap();
     let subs = solved.inner_mut();
 
+    if let Some(problems) = parse_problems.remove(&home) {
+        for report in problems {
+            eprintln!("{}", report);
+        }
+
+        process::exit(1);
+    }
+
     let can_problems = can_problems.remove(&home).unwrap_or_default();
     let type_problems = type_problems.remove(&home).unwrap_or_default();
 
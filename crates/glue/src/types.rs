@@ -18,7 +18,7 @@ use roc_mono::layout::{
 use roc_target::TargetInfo;
 use roc_types::{
     subs::{Content, FlatType, GetSubsSlice, Subs, UnionLabels, UnionTags, Variable},
-    types::{AliasKind, RecordField},
+    types::RecordField,
 };
 use std::fmt::Display;
 
@@ -787,6 +787,19 @@ fn add_type_help<'a>(
                 RocType::Struct { name, fields }
             })
         }
+        Content::Structure(FlatType::Tuple(elems, ext)) => {
+            let (it, _) = elems.unsorted_iterator_and_ext(subs, *ext);
+
+            let name = match opt_name {
+                Some(sym) => sym.as_str(env.interns).to_string(),
+                None => env.struct_names.get_name(var),
+            };
+
+            // like a tag payload, the elements of a tuple are only known by their position
+            add_struct(env, name, it, types, layout, |name, fields| {
+                RocType::TagUnionPayload { name, fields }
+            })
+        }
         Content::Structure(FlatType::TagUnion(tags, ext_var)) => {
             debug_assert!(ext_var_is_empty_tag_union(subs, *ext_var));
 
@@ -865,7 +878,7 @@ fn add_type_help<'a>(
             todo!()
         }
         Content::Structure(FlatType::Erroneous(_)) => todo!(),
-        Content::Structure(FlatType::EmptyRecord) => {
+        Content::Structure(FlatType::EmptyRecord | FlatType::EmptyTuple) => {
             types.add_anonymous(&env.layout_cache.interner, RocType::Unit, layout)
         }
         Content::Structure(FlatType::EmptyTagUnion) => {
@@ -932,6 +945,54 @@ fn add_type_help<'a>(
                             }
                         }
                     }
+                    Layout::Struct { .. } if *name == Symbol::DICT_DICT => {
+                        let type_vars = env.subs.get_subs_slice(alias_vars.type_variables());
+
+                        debug_assert_eq!(type_vars.len(), 2);
+
+                        let key_var = type_vars[0];
+                        let key_layout =
+                            env.layout_cache.from_var(env.arena, key_var, subs).unwrap();
+                        let key_id = add_type_help(env, key_layout, key_var, None, types);
+
+                        let val_var = type_vars[1];
+                        let val_layout =
+                            env.layout_cache.from_var(env.arena, val_var, subs).unwrap();
+                        let val_id = add_type_help(env, val_layout, val_var, None, types);
+
+                        let dict_id = types.add_anonymous(
+                            &env.layout_cache.interner,
+                            RocType::RocDict(key_id, val_id),
+                            layout,
+                        );
+
+                        types.depends(dict_id, key_id);
+                        types.depends(dict_id, val_id);
+
+                        dict_id
+                    }
+                    Layout::Struct { .. } if *name == Symbol::SET_SET => {
+                        let type_vars = env.subs.get_subs_slice(alias_vars.type_variables());
+
+                        debug_assert_eq!(type_vars.len(), 1);
+
+                        let elem_var = type_vars[0];
+                        let elem_layout = env
+                            .layout_cache
+                            .from_var(env.arena, elem_var, subs)
+                            .unwrap();
+                        let elem_id = add_type_help(env, elem_layout, elem_var, None, types);
+
+                        let set_id = types.add_anonymous(
+                            &env.layout_cache.interner,
+                            RocType::RocSet(elem_id),
+                            layout,
+                        );
+
+                        types.depends(set_id, elem_id);
+
+                        set_id
+                    }
                     _ => {
                         unreachable!()
                     }
@@ -1070,91 +1131,6 @@ fn add_builtin_type<'a>(
 
             list_id
         }
-        (
-            Builtin::List(elem_layout),
-            Alias(Symbol::DICT_DICT, _alias_variables, alias_var, AliasKind::Opaque),
-        ) => {
-            match (
-                elem_layout,
-                env.subs.get_content_without_compacting(*alias_var),
-            ) {
-                (
-                    Layout::Struct { field_layouts, .. },
-                    Content::Structure(FlatType::Apply(Symbol::LIST_LIST, args_subs_slice)),
-                ) => {
-                    let (key_var, val_var) = {
-                        let args_tuple = env.subs.get_subs_slice(*args_subs_slice);
-
-                        debug_assert_eq!(args_tuple.len(), 1);
-
-                        match env.subs.get_content_without_compacting(args_tuple[0]) {
-                            Content::Structure(FlatType::TagUnion(union_tags, ext_var)) => {
-                                let (mut iter, _) = union_tags.sorted_iterator_and_ext(env.subs, *ext_var);
-                                let payloads = iter.next().unwrap().1;
-
-                                debug_assert_eq!(iter.next(), None);
-
-                                (payloads[0], payloads[1])
-                            }
-                            _ => {
-                                unreachable!()
-                            }
-                        }
-                    };
-
-                    debug_assert_eq!(field_layouts.len(), 2);
-
-                    let key_id = add_type_help(env, field_layouts[0], key_var, opt_name, types);
-                    let val_id = add_type_help(env, field_layouts[1], val_var, opt_name, types);
-                    let dict_id = types.add_anonymous(&env.layout_cache.interner,RocType::RocDict(key_id, val_id), layout);
-
-                    types.depends(dict_id, key_id);
-                    types.depends(dict_id, val_id);
-
-                    dict_id
-                }
-                (elem_layout, alias_content) => unreachable!(
-                    "Unrecognized List element for Dict. Layout was: {:?} and alias_content was: {:?}",
-                    elem_layout,
-                    alias_content
-                ),
-            }
-        }
-        (
-            Builtin::List(elem_layout),
-            Alias(Symbol::SET_SET, _alias_vars, alias_var, AliasKind::Opaque),
-        ) => {
-            match (
-                elem_layout,
-                env.subs.get_content_without_compacting(*alias_var),
-            ) {
-                (
-                    Layout::Struct { field_layouts, .. },
-                    Alias(Symbol::DICT_DICT, alias_args, _alias_var, AliasKind::Opaque),
-                ) => {
-                    let dict_type_vars = env.subs.get_subs_slice(alias_args.type_variables());
-
-                    debug_assert_eq!(dict_type_vars.len(), 2);
-
-                    // Sets only use the key of the Dict they wrap, not the value
-                    let elem_var = dict_type_vars[0];
-
-                    debug_assert_eq!(field_layouts.len(), 2);
-
-                    let elem_id = add_type_help(env, field_layouts[0], elem_var, opt_name, types);
-                    let set_id = types.add_anonymous(&env.layout_cache.interner,RocType::RocSet(elem_id), layout);
-
-                    types.depends(set_id, elem_id);
-
-                    set_id
-                }
-                (elem_layout, alias_content) => unreachable!(
-                    "Unrecognized List element for Set. Layout was: {:?} and alias_content was: {:?}",
-                    elem_layout,
-                    alias_content
-                ),
-            }
-        }
         (Builtin::List(elem_layout), alias) => {
             unreachable!(
                 "The type alias {:?} was not an Apply(Symbol::LIST_LIST) as expected, given that its builtin was Builtin::List({:?})",
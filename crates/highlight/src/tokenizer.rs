@@ -33,6 +33,8 @@ pub enum Token {
     KeywordEffects = 0b_0011_0000,
     KeywordPlatform = 0b_0011_0001,
     KeywordRequires = 0b_0011_0010,
+    KeywordDbg = 0b_0011_0100,
+    KeywordCrash = 0b_0011_0101,
 
     Comma = 0b_0100_0000,
     Colon = 0b_0100_0001,
@@ -428,6 +430,8 @@ fn lex_ident(uppercase: bool, bytes: &[u8]) -> (Token, usize) {
         b"effects" => Token::KeywordEffects,
         b"platform" => Token::KeywordPlatform,
         b"requires" => Token::KeywordRequires,
+        b"dbg" => Token::KeywordDbg,
+        b"crash" => Token::KeywordCrash,
         ident => {
             if ident.contains(&b'_') {
                 Token::MalformedIdent
@@ -34,6 +34,8 @@ mod test_peg_grammar {
           rule common_expr() =
               closure()
               / expect()
+              / dbg()
+              / crash()
               / if_expr()
               / when()
               / backpass()
@@ -129,6 +131,10 @@ mod test_peg_grammar {
 
             rule expect() = [T::KeywordExpect] expr()
 
+            rule dbg() = [T::KeywordDbg] expr()
+
+            rule crash() = [T::KeywordCrash] expr()
+
             pub rule backpass() =
             single_backpass() ([T::SameIndent] single_backpass())* [T::SameIndent] full_expr()
 
@@ -527,6 +533,8 @@ mod test_peg_grammar {
         );
 
         assert_eq!(tokenparser::expr(&[T::KeywordExpect, T::Number]), Ok(()));
+        assert_eq!(tokenparser::expr(&[T::KeywordDbg, T::Number]), Ok(()));
+        assert_eq!(tokenparser::expr(&[T::KeywordCrash, T::String]), Ok(()));
     }
 
     #[test]
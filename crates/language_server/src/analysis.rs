@@ -0,0 +1,337 @@
+//! Type checking of open documents, and the queries we answer from the results.
+
+use crate::protocol::{
+    self, Diagnostic, DiagnosticSeverity, Hover, Location, MarkupContent, Range,
+};
+use bumpalo::Bump;
+use roc_can::traverse::{
+    find_ability_member_and_owning_type_at, find_closest_type_at, find_declaration, find_symbol_at,
+};
+use roc_load::{LoadedModule, LoadingProblem};
+use roc_module::symbol::{Interns, ModuleIds};
+use roc_parse::parser::{FileError, SyntaxError};
+use roc_region::all::{LineColumn, LineColumnRegion, LineInfo, Position, Region};
+use roc_reporting::report::{
+    self as report, can_problem, parse_problem, type_problem, RenderTarget, Report,
+    RocDocAllocator, Severity,
+};
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
+use std::path::{Path, PathBuf};
+use url::Url;
+
+const DIAGNOSTIC_SOURCE: &str = "roc";
+
+/// The result of type checking a single document.
+pub struct AnalyzedDocument {
+    source: String,
+    line_info: LineInfo,
+    module: Option<LoadedModule>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl AnalyzedDocument {
+    /// Load and type check `source` as if it were the contents of the file at `path`.
+    /// Imports are resolved relative to the directory of `path`.
+    pub fn new(path: &Path, source: String) -> Self {
+        let arena = Bump::new();
+        let src_dir = path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        let loaded = roc_load::load_and_typecheck_str(
+            &arena,
+            path.to_path_buf(),
+            arena.alloc_str(&source),
+            src_dir,
+            Default::default(),
+            roc_target::TargetInfo::from(&target_lexicon::Triple::host()),
+            RenderTarget::Generic,
+        );
+
+        let line_info = LineInfo::new(&source);
+
+        let (module, diagnostics) = match loaded {
+            Ok(module) => {
+                let diagnostics = module_diagnostics(&module, &source, &line_info);
+
+                (Some(module), diagnostics)
+            }
+            Err(LoadingProblem::ParsingFailed(file_error)) => {
+                // The header of this document didn't parse, so nothing else was checked.
+                let diagnostic = syntax_diagnostic(file_error, &source, &line_info);
+
+                (None, vec![diagnostic])
+            }
+            Err(problem) => {
+                // Anything else (say, a broken import) goes at the start of the file.
+                let message = match problem {
+                    LoadingProblem::FormattedReport(report) => report,
+                    other => format!("{:?}", other),
+                };
+
+                let diagnostic = Diagnostic {
+                    range: start_of_file_range(),
+                    severity: DiagnosticSeverity::Error,
+                    source: DIAGNOSTIC_SOURCE.to_string(),
+                    message,
+                };
+
+                (None, vec![diagnostic])
+            }
+        };
+
+        AnalyzedDocument {
+            source,
+            line_info,
+            module,
+            diagnostics,
+        }
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// The type of the innermost expression or pattern at `position`.
+    pub fn hover(&mut self, position: protocol::Position) -> Option<Hover> {
+        let position = to_roc_position(&self.source, &self.line_info, position)?;
+
+        let LoadedModule {
+            module_id: home,
+            interns,
+            solved,
+            declarations_by_id,
+            abilities_store,
+            ..
+        } = self.module.as_mut()?;
+
+        let decls = declarations_by_id.get(home)?;
+        let (region, var) = find_closest_type_at(position, decls)?;
+
+        let subs = solved.inner_mut();
+        let snapshot = subs.snapshot();
+        let type_str = name_and_print_var(var, subs, *home, interns, DebugPrint::NOTHING);
+        subs.rollback_to(snapshot);
+
+        let signature = match find_ability_member_and_owning_type_at(region, decls, abilities_store)
+        {
+            Some((owning_type, member)) => format!(
+                "{}#{} : {}",
+                owning_type.as_str(interns),
+                member.as_str(interns),
+                type_str
+            ),
+            None => type_str,
+        };
+
+        Some(Hover {
+            contents: MarkupContent {
+                kind: "markdown".to_string(),
+                value: format!("```roc\n{}\n```", signature),
+            },
+            range: to_lsp_range(&self.source, &self.line_info, region),
+        })
+    }
+
+    /// Where the symbol at `position` is defined. Ability members resolve to the
+    /// specialization being used, when it is known.
+    pub fn definition(&self, position: protocol::Position) -> Option<Location> {
+        let position = to_roc_position(&self.source, &self.line_info, position)?;
+
+        let LoadedModule {
+            module_id: home,
+            declarations_by_id,
+            abilities_store,
+            sources,
+            ..
+        } = self.module.as_ref()?;
+
+        let decls = declarations_by_id.get(home)?;
+        let loc_symbol = find_symbol_at(position, decls)?;
+
+        let symbol =
+            match find_ability_member_and_owning_type_at(loc_symbol.region, decls, abilities_store)
+            {
+                Some((_, specialization)) => specialization,
+                None => loc_symbol.value,
+            };
+
+        let module_id = symbol.module_id();
+
+        if module_id.is_builtin() {
+            return None;
+        }
+
+        let region = find_declaration(symbol, declarations_by_id.get(&module_id)?)?;
+        let (path, source) = sources.get(&module_id)?;
+
+        Some(Location {
+            uri: path_to_uri(path)?,
+            range: to_lsp_range(source, &LineInfo::new(source), region),
+        })
+    }
+}
+
+/// The report for a syntax error that stopped the loader before it got to the module's defs.
+fn syntax_diagnostic(
+    file_error: FileError<SyntaxError>,
+    source: &str,
+    line_info: &LineInfo,
+) -> Diagnostic {
+    // Syntax errors never mention a symbol, so they don't need the module's real interns.
+    let mut module_ids = ModuleIds::default();
+    let home = module_ids.get_or_insert(&"".into());
+    let interns = Interns {
+        module_ids,
+        ..Interns::default()
+    };
+
+    let src_lines: Vec<&str> = source.split('\n').collect();
+    let alloc = RocDocAllocator::new(&src_lines, home, &interns);
+
+    let region = file_error.problem.problem.region();
+    let filename = file_error.filename.clone();
+    let report = parse_problem(&alloc, line_info, filename, 0, file_error);
+
+    to_diagnostic(report, region, source, line_info)
+}
+
+fn module_diagnostics(
+    module: &LoadedModule,
+    source: &str,
+    line_info: &LineInfo,
+) -> Vec<Diagnostic> {
+    let home = module.module_id;
+    let filename = match module.sources.get(&home) {
+        Some((path, _)) => path.clone(),
+        None => PathBuf::new(),
+    };
+
+    let src_lines: Vec<&str> = source.split('\n').collect();
+    let alloc = RocDocAllocator::new(&src_lines, home, &module.interns);
+
+    // Syntax errors the parser recovered from come to us already reported.
+    let mut diagnostics: Vec<Diagnostic> = module
+        .parse_diagnostics
+        .get(&home)
+        .into_iter()
+        .flatten()
+        .map(|diagnostic| to_lsp_diagnostic(diagnostic.clone(), source))
+        .collect();
+
+    let can_problems = module.can_problems.get(&home).into_iter().flatten();
+
+    for problem in can_problems {
+        let region = problem.region();
+        let report = can_problem(&alloc, line_info, filename.clone(), problem.clone());
+
+        diagnostics.push(to_diagnostic(report, region, source, line_info));
+    }
+
+    let type_problems = module.type_problems.get(&home).into_iter().flatten();
+
+    for problem in type_problems {
+        let region = problem.region();
+
+        if let Some(report) = type_problem(&alloc, line_info, filename.clone(), problem.clone()) {
+            diagnostics.push(to_diagnostic(report, region, source, line_info));
+        }
+    }
+
+    diagnostics
+}
+
+fn start_of_file_range() -> Range {
+    let start = protocol::Position {
+        line: 0,
+        character: 0,
+    };
+
+    Range { start, end: start }
+}
+
+fn to_diagnostic(
+    report: Report,
+    region: Option<Region>,
+    source: &str,
+    line_info: &LineInfo,
+) -> Diagnostic {
+    let region = region.map(|region| line_info.convert_region(region));
+
+    to_lsp_diagnostic(report.into_diagnostic(region), source)
+}
+
+fn to_lsp_diagnostic(diagnostic: report::Diagnostic, source: &str) -> Diagnostic {
+    let severity = match diagnostic.severity {
+        Severity::RuntimeError => DiagnosticSeverity::Error,
+        Severity::Warning => DiagnosticSeverity::Warning,
+    };
+
+    let region = diagnostic.region.unwrap_or_else(LineColumnRegion::zero);
+
+    Diagnostic {
+        range: Range {
+            start: to_lsp_position(source, region.start),
+            end: to_lsp_position(source, region.end),
+        },
+        severity,
+        source: DIAGNOSTIC_SOURCE.to_string(),
+        // The title goes on its own line; the editor already shows which file we're talking about.
+        message: format!("{}\n\n{}", diagnostic.title, diagnostic.message),
+    }
+}
+
+fn to_lsp_range(source: &str, line_info: &LineInfo, region: Region) -> Range {
+    Range {
+        start: to_lsp_position(source, line_info.convert_pos(region.start())),
+        end: to_lsp_position(source, line_info.convert_pos(region.end())),
+    }
+}
+
+/// Roc columns are byte offsets, LSP positions count UTF-16 code units within a line.
+fn to_lsp_position(source: &str, line_column: LineColumn) -> protocol::Position {
+    let LineColumn { line, column } = line_column;
+    let line_text = source.split('\n').nth(line as usize).unwrap_or("");
+    let prefix = line_text.get(..column as usize).unwrap_or(line_text);
+
+    protocol::Position {
+        line,
+        character: prefix.encode_utf16().count() as u32,
+    }
+}
+
+fn to_roc_position(
+    source: &str,
+    line_info: &LineInfo,
+    position: protocol::Position,
+) -> Option<Position> {
+    let line_text = source.split('\n').nth(position.line as usize)?;
+
+    let mut utf16_column = 0;
+    let mut column = line_text.len();
+
+    for (byte_index, c) in line_text.char_indices() {
+        if utf16_column >= position.character as usize {
+            column = byte_index;
+            break;
+        }
+
+        utf16_column += c.len_utf16();
+    }
+
+    Some(line_info.convert_line_column(LineColumn {
+        line: position.line,
+        column: column as u32,
+    }))
+}
+
+/// The path a `file://` URI points to.
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    Url::parse(uri).ok()?.to_file_path().ok()
+}
+
+/// The `file://` URI of an absolute path.
+pub fn path_to_uri(path: &Path) -> Option<String> {
+    Url::from_file_path(path).ok().map(String::from)
+}
@@ -0,0 +1,21 @@
+//! A language server for Roc. It speaks the Language Server Protocol over stdio and offers
+//! diagnostics, hover types and go-to-definition for the documents an editor has open.
+mod analysis;
+pub mod protocol;
+pub mod rpc;
+mod server;
+
+pub use analysis::{path_to_uri, uri_to_path};
+pub use server::Server;
+
+use std::io;
+
+/// Serve requests from stdin until the client asks us to exit, returning the exit code.
+pub fn run_stdio() -> io::Result<i32> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+
+    let mut server = Server::new(stdout.lock());
+
+    server.run(&mut stdin.lock())
+}
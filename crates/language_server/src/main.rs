@@ -0,0 +1,7 @@
+use std::io;
+
+fn main() -> io::Result<()> {
+    let exit_code = roc_language_server::run_stdio()?;
+
+    std::process::exit(exit_code)
+}
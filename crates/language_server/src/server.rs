@@ -0,0 +1,216 @@
+use crate::analysis::{uri_to_path, AnalyzedDocument};
+use crate::protocol::{
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    PublishDiagnosticsParams, TextDocumentPositionParams,
+};
+use crate::rpc::{self, error_code, Incoming, Notification, Response};
+use roc_collections::all::MutMap;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Waiting for the `initialize` request
+    Uninitialized,
+    Running,
+    /// We received `shutdown` and only wait for `exit`
+    ShuttingDown,
+}
+
+/// A language server that answers one message at a time. Every open document is
+/// type checked again whenever it changes, and queries are answered from the latest result.
+pub struct Server<W: Write> {
+    output: W,
+    state: State,
+    documents: MutMap<String, AnalyzedDocument>,
+}
+
+impl<W: Write> Server<W> {
+    pub fn new(output: W) -> Self {
+        Server {
+            output,
+            state: State::Uninitialized,
+            documents: MutMap::default(),
+        }
+    }
+
+    pub fn into_output(self) -> W {
+        self.output
+    }
+
+    /// Handle messages from `input` until the client asks us to exit, or closes the stream.
+    /// Returns the process exit code the protocol asks for.
+    pub fn run<R: BufRead>(&mut self, input: &mut R) -> io::Result<i32> {
+        while let Some(content) = rpc::read_message(input)? {
+            match serde_json::from_str::<Incoming>(&content) {
+                Ok(message) => {
+                    if message.method.as_deref() == Some("exit") {
+                        // exiting without a shutdown request first is an error
+                        return Ok((self.state != State::ShuttingDown) as i32);
+                    }
+
+                    self.handle(message)?;
+                }
+                Err(err) => {
+                    let response =
+                        Response::error(Value::Null, error_code::PARSE_ERROR, err.to_string());
+                    rpc::write_message(&mut self.output, &response)?;
+                }
+            }
+        }
+
+        Ok(1)
+    }
+
+    fn handle(&mut self, message: Incoming) -> io::Result<()> {
+        let Incoming { id, method, params } = message;
+
+        let method = match method {
+            Some(method) => method,
+            None => {
+                // we never send requests, so we do not expect responses
+                if let Some(id) = id {
+                    let response =
+                        Response::error(id, error_code::INVALID_REQUEST, "expected a method");
+                    rpc::write_message(&mut self.output, &response)?;
+                }
+
+                return Ok(());
+            }
+        };
+
+        match id {
+            Some(id) => {
+                let response = match self.handle_request(&method, params) {
+                    Ok(result) => Response::ok(id, result),
+                    Err((code, message)) => Response::error(id, code, message),
+                };
+
+                rpc::write_message(&mut self.output, &response)
+            }
+            None => self.handle_notification(&method, params),
+        }
+    }
+
+    fn handle_request(&mut self, method: &str, params: Value) -> Result<Value, (i64, String)> {
+        match (self.state, method) {
+            (State::Uninitialized, "initialize") => {
+                self.state = State::Running;
+
+                Ok(json!({
+                    "capabilities": {
+                        // full document sync
+                        "textDocumentSync": 1,
+                        "hoverProvider": true,
+                        "definitionProvider": true,
+                    },
+                    "serverInfo": {
+                        "name": "roc_language_server",
+                    },
+                }))
+            }
+            (State::Uninitialized, _) => Err((
+                error_code::SERVER_NOT_INITIALIZED,
+                "the server has not been initialized".to_string(),
+            )),
+            (_, "shutdown") => {
+                self.state = State::ShuttingDown;
+
+                Ok(Value::Null)
+            }
+            (State::ShuttingDown, _) => Err((
+                error_code::INVALID_REQUEST,
+                "the server is shutting down".to_string(),
+            )),
+            (State::Running, "textDocument/hover") => {
+                let params: TextDocumentPositionParams = parse_params(params)?;
+
+                let hover = self
+                    .documents
+                    .get_mut(&params.text_document.uri)
+                    .and_then(|document| document.hover(params.position));
+
+                Ok(json!(hover))
+            }
+            (State::Running, "textDocument/definition") => {
+                let params: TextDocumentPositionParams = parse_params(params)?;
+
+                let location = self
+                    .documents
+                    .get(&params.text_document.uri)
+                    .and_then(|document| document.definition(params.position));
+
+                Ok(json!(location))
+            }
+            (State::Running, _) => Err((
+                error_code::METHOD_NOT_FOUND,
+                format!("unsupported method {:?}", method),
+            )),
+        }
+    }
+
+    fn handle_notification(&mut self, method: &str, params: Value) -> io::Result<()> {
+        if self.state != State::Running {
+            return Ok(());
+        }
+
+        // Notifications can't be answered, so malformed ones are dropped.
+        match method {
+            "textDocument/didOpen" => {
+                if let Ok(params) = parse_params::<DidOpenTextDocumentParams>(params) {
+                    let document = params.text_document;
+
+                    self.update_document(document.uri, document.text)?;
+                }
+            }
+            "textDocument/didChange" => {
+                if let Ok(mut params) = parse_params::<DidChangeTextDocumentParams>(params) {
+                    if let Some(change) = params.content_changes.pop() {
+                        self.update_document(params.text_document.uri, change.text)?;
+                    }
+                }
+            }
+            "textDocument/didClose" => {
+                if let Ok(params) = parse_params::<DidCloseTextDocumentParams>(params) {
+                    let uri = params.text_document.uri;
+
+                    self.documents.remove(&uri);
+                    self.publish_diagnostics(uri, Vec::new())?;
+                }
+            }
+            _ => { /* ignore */ }
+        }
+
+        Ok(())
+    }
+
+    fn update_document(&mut self, uri: String, text: String) -> io::Result<()> {
+        let path = match uri_to_path(&uri) {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        let document = AnalyzedDocument::new(&path, text);
+        let diagnostics = document.diagnostics().to_vec();
+
+        self.documents.insert(uri.clone(), document);
+
+        self.publish_diagnostics(uri, diagnostics)
+    }
+
+    fn publish_diagnostics(
+        &mut self,
+        uri: String,
+        diagnostics: Vec<crate::protocol::Diagnostic>,
+    ) -> io::Result<()> {
+        let params = PublishDiagnosticsParams { uri, diagnostics };
+        let notification = Notification::new("textDocument/publishDiagnostics", json!(params));
+
+        rpc::write_message(&mut self.output, &notification)
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, (i64, String)> {
+    serde_json::from_value(params).map_err(|err| (error_code::INVALID_PARAMS, err.to_string()))
+}
@@ -0,0 +1,435 @@
+#[macro_use]
+extern crate pretty_assertions;
+#[macro_use]
+extern crate indoc;
+
+#[cfg(test)]
+mod test_language_server {
+    use roc_language_server::{path_to_uri, rpc, Server};
+    use serde_json::{json, Value};
+    use std::io::Cursor;
+    use std::path::Path;
+
+    /// Runs the server over a script of messages, returning the exit code and
+    /// every message the server sent back.
+    fn run_script(messages: &[Value]) -> (i32, Vec<Value>) {
+        let mut input = Vec::new();
+
+        for message in messages {
+            rpc::write_message(&mut input, message).unwrap();
+        }
+
+        let mut server = Server::new(Vec::new());
+        let exit_code = server.run(&mut Cursor::new(input)).unwrap();
+        let output = server.into_output();
+
+        let mut reader = Cursor::new(output);
+        let mut replies = Vec::new();
+
+        while let Some(content) = rpc::read_message(&mut reader).unwrap() {
+            replies.push(serde_json::from_str(&content).unwrap());
+        }
+
+        (exit_code, replies)
+    }
+
+    fn initialize() -> Value {
+        json!({ "jsonrpc": "2.0", "id": 0, "method": "initialize", "params": {} })
+    }
+
+    fn shutdown_and_exit() -> [Value; 2] {
+        [
+            json!({ "jsonrpc": "2.0", "id": 999, "method": "shutdown" }),
+            json!({ "jsonrpc": "2.0", "method": "exit" }),
+        ]
+    }
+
+    fn did_open(uri: &str, text: &str) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {
+                "textDocument": { "uri": uri, "languageId": "roc", "version": 1, "text": text }
+            }
+        })
+    }
+
+    fn position_request(id: u32, method: &str, uri: &str, line: u32, character: u32) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": {
+                "textDocument": { "uri": uri },
+                "position": { "line": line, "character": character }
+            }
+        })
+    }
+
+    fn response(replies: &[Value], id: u32) -> &Value {
+        replies
+            .iter()
+            .find(|reply| reply["id"] == json!(id))
+            .unwrap_or_else(|| panic!("no response for request {}", id))
+    }
+
+    fn diagnostics(replies: &[Value]) -> &Vec<Value> {
+        replies
+            .iter()
+            .rev()
+            .find(|reply| reply["method"] == "textDocument/publishDiagnostics")
+            .expect("no diagnostics were published")["params"]["diagnostics"]
+            .as_array()
+            .unwrap()
+    }
+
+    /// Runs a session against a module written to a temporary directory, so that it
+    /// can be loaded from disk like a real project.
+    fn with_module<T>(filename: &str, source: &str, f: impl FnOnce(&str) -> T) -> T {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(filename);
+        std::fs::write(&path, source).unwrap();
+
+        let result = f(&path_to_uri(&path).unwrap());
+
+        dir.close().unwrap();
+
+        result
+    }
+
+    #[test]
+    fn initialize_and_exit() {
+        let (exit_code, replies) = run_script(&[
+            initialize(),
+            json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+            shutdown_and_exit()[0].clone(),
+            shutdown_and_exit()[1].clone(),
+        ]);
+
+        assert_eq!(exit_code, 0);
+        assert_eq!(replies.len(), 2);
+
+        let capabilities = &response(&replies, 0)["result"]["capabilities"];
+        assert_eq!(capabilities["hoverProvider"], json!(true));
+        assert_eq!(capabilities["definitionProvider"], json!(true));
+        assert_eq!(response(&replies, 999)["result"], Value::Null);
+    }
+
+    #[test]
+    fn exit_without_shutdown_is_an_error() {
+        let (exit_code, _) = run_script(&[initialize(), shutdown_and_exit()[1].clone()]);
+
+        assert_eq!(exit_code, 1);
+    }
+
+    #[test]
+    fn requests_before_initialize_are_rejected() {
+        let (_, replies) = run_script(&[position_request(
+            1,
+            "textDocument/hover",
+            "file:///Test.roc",
+            0,
+            0,
+        )]);
+
+        assert_eq!(response(&replies, 1)["error"]["code"], json!(-32002));
+    }
+
+    #[test]
+    fn publishes_type_errors() {
+        let source = indoc!(
+            r#"
+            interface Test exposes [x] imports []
+
+            x : Str
+            x = 1
+            "#
+        );
+
+        with_module("Test.roc", source, |uri| {
+            let (_, replies) = run_script(&[initialize(), did_open(uri, source)]);
+            let diagnostics = diagnostics(&replies);
+
+            assert_eq!(diagnostics.len(), 1);
+            assert_eq!(diagnostics[0]["severity"], json!(1));
+            assert_eq!(
+                diagnostics[0]["range"],
+                json!({
+                    "start": { "line": 3, "character": 4 },
+                    "end": { "line": 3, "character": 5 }
+                })
+            );
+
+            let message = diagnostics[0]["message"].as_str().unwrap();
+            assert!(message.starts_with("TYPE MISMATCH"), "{}", message);
+        })
+    }
+
+    #[test]
+    fn publishes_warnings() {
+        let source = indoc!(
+            r#"
+            interface Test exposes [x] imports []
+
+            x = \y -> 1
+            "#
+        );
+
+        with_module("Test.roc", source, |uri| {
+            let (_, replies) = run_script(&[initialize(), did_open(uri, source)]);
+            let diagnostics = diagnostics(&replies);
+
+            assert_eq!(diagnostics.len(), 1);
+            assert_eq!(diagnostics[0]["severity"], json!(2));
+            assert_eq!(
+                diagnostics[0]["range"]["start"],
+                json!({ "line": 2, "character": 5 })
+            );
+        })
+    }
+
+    #[test]
+    fn publishes_syntax_errors() {
+        let source = indoc!(
+            r#"
+            interface Test exposes [x] imports []
+
+            x = [1, 2,
+            "#
+        );
+
+        with_module("Test.roc", source, |uri| {
+            let (_, replies) = run_script(&[initialize(), did_open(uri, source)]);
+            let diagnostics = diagnostics(&replies);
+
+            assert_eq!(diagnostics.len(), 1);
+            assert_eq!(diagnostics[0]["severity"], json!(1));
+
+            // Where the list stops, not the start of the file
+            let line = diagnostics[0]["range"]["start"]["line"].as_u64().unwrap();
+            assert!(line >= 2, "{}", diagnostics[0]);
+
+            let message = diagnostics[0]["message"].as_str().unwrap();
+            assert!(!message.starts_with('{'), "{}", message);
+        })
+    }
+
+    #[test]
+    fn publishes_syntax_errors_in_the_header() {
+        let source = "interface Test exposes [x] imports [Dict.{ get }\n\nx = 1\n";
+
+        with_module("Test.roc", source, |uri| {
+            let (_, replies) = run_script(&[initialize(), did_open(uri, source)]);
+            let diagnostics = diagnostics(&replies);
+
+            assert_eq!(diagnostics.len(), 1);
+
+            // Where the imports should have stopped, not the start of the file
+            let start = &diagnostics[0]["range"]["start"];
+            assert_ne!(
+                start,
+                &json!({ "line": 0, "character": 0 }),
+                "{}",
+                diagnostics[0]
+            );
+
+            let message = diagnostics[0]["message"].as_str().unwrap();
+            assert!(message.starts_with("WEIRD IMPORTS"), "{}", message);
+        })
+    }
+
+    #[test]
+    fn change_clears_diagnostics() {
+        let broken = "interface Test exposes [x] imports []\n\nx : Str\nx = 1\n";
+        let fixed = "interface Test exposes [x] imports []\n\nx : Str\nx = \"1\"\n";
+
+        with_module("Test.roc", broken, |uri| {
+            let (_, replies) = run_script(&[
+                initialize(),
+                did_open(uri, broken),
+                json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/didChange",
+                    "params": {
+                        "textDocument": { "uri": uri, "version": 2 },
+                        "contentChanges": [{ "text": fixed }]
+                    }
+                }),
+            ]);
+
+            assert!(diagnostics(&replies).is_empty());
+        })
+    }
+
+    #[test]
+    fn hover_shows_type() {
+        let source = indoc!(
+            r#"
+            interface Test exposes [main] imports []
+
+            main =
+                numbers = [1, 2, 3]
+                List.map numbers \n -> n + 1
+            "#
+        );
+
+        with_module("Test.roc", source, |uri| {
+            let (_, replies) = run_script(&[
+                initialize(),
+                did_open(uri, source),
+                // `numbers` in `List.map numbers`
+                position_request(1, "textDocument/hover", uri, 4, 15),
+                // the `n` argument of the lambda
+                position_request(2, "textDocument/hover", uri, 4, 22),
+                // whitespace outside of any def
+                position_request(3, "textDocument/hover", uri, 1, 0),
+            ]);
+
+            let hover = &response(&replies, 1)["result"];
+            assert_eq!(hover["contents"]["value"], "```roc\nList (Num *)\n```");
+            assert_eq!(
+                hover["range"],
+                json!({
+                    "start": { "line": 4, "character": 13 },
+                    "end": { "line": 4, "character": 20 }
+                })
+            );
+
+            let hover = &response(&replies, 2)["result"];
+            assert_eq!(hover["contents"]["value"], "```roc\nNum *\n```");
+
+            assert_eq!(response(&replies, 3)["result"], Value::Null);
+        })
+    }
+
+    #[test]
+    fn hover_shows_ability_specialization() {
+        let source = indoc!(
+            r#"
+            interface Test exposes [main] imports []
+
+            MHash has hash : a -> U64 | a has MHash
+
+            Id := U64 has [MHash {hash}]
+
+            hash = \@Id n -> n
+
+            main = hash (@Id 1)
+            "#
+        );
+
+        with_module("Test.roc", source, |uri| {
+            let (_, replies) = run_script(&[
+                initialize(),
+                did_open(uri, source),
+                position_request(1, "textDocument/hover", uri, 8, 8),
+            ]);
+
+            let hover = &response(&replies, 1)["result"];
+            assert_eq!(
+                hover["contents"]["value"],
+                "```roc\nId#hash : Id -> U64\n```"
+            );
+        })
+    }
+
+    #[test]
+    fn goto_local_definition() {
+        let source = indoc!(
+            r#"
+            interface Test exposes [main] imports []
+
+            main =
+                numbers = [1, 2, 3]
+                List.map numbers \n -> n + 1
+            "#
+        );
+
+        with_module("Test.roc", source, |uri| {
+            let (_, replies) = run_script(&[
+                initialize(),
+                did_open(uri, source),
+                // `numbers` in `List.map numbers`
+                position_request(1, "textDocument/definition", uri, 4, 15),
+                // `n` in `n + 1`
+                position_request(2, "textDocument/definition", uri, 4, 27),
+                // `List.map` is a builtin, so we can't jump to it
+                position_request(3, "textDocument/definition", uri, 4, 6),
+            ]);
+
+            assert_eq!(
+                response(&replies, 1)["result"],
+                json!({
+                    "uri": uri,
+                    "range": {
+                        "start": { "line": 3, "character": 4 },
+                        "end": { "line": 3, "character": 11 }
+                    }
+                })
+            );
+
+            assert_eq!(
+                response(&replies, 2)["result"]["range"],
+                json!({
+                    "start": { "line": 4, "character": 22 },
+                    "end": { "line": 4, "character": 23 }
+                })
+            );
+
+            assert_eq!(response(&replies, 3)["result"], Value::Null);
+        })
+    }
+
+    #[test]
+    fn goto_definition_in_imported_module() {
+        let dep = indoc!(
+            r#"
+            interface Dep exposes [greeting] imports []
+
+            greeting = "Hello"
+            "#
+        );
+
+        let source = indoc!(
+            r#"
+            interface Test exposes [main] imports [Dep]
+
+            main = Dep.greeting
+            "#
+        );
+
+        with_module("Dep.roc", dep, |dep_uri| {
+            let dep_path = roc_language_server::uri_to_path(dep_uri).unwrap();
+            let path = dep_path.parent().unwrap().join("Test.roc");
+            std::fs::write(&path, source).unwrap();
+            let uri = path_to_uri(Path::new(&path)).unwrap();
+
+            let (_, replies) = run_script(&[
+                initialize(),
+                did_open(&uri, source),
+                position_request(1, "textDocument/definition", &uri, 2, 12),
+            ]);
+
+            assert!(diagnostics(&replies).is_empty());
+            assert_eq!(
+                response(&replies, 1)["result"],
+                json!({
+                    "uri": dep_uri,
+                    "range": {
+                        "start": { "line": 2, "character": 0 },
+                        "end": { "line": 2, "character": 8 }
+                    }
+                })
+            );
+        })
+    }
+
+    #[test]
+    fn uri_round_trip() {
+        let path = Path::new("/home/user/my project/Main.roc");
+        let uri = path_to_uri(path).unwrap();
+
+        assert_eq!(uri, "file:///home/user/my%20project/Main.roc");
+        assert_eq!(roc_language_server::uri_to_path(&uri).unwrap(), path);
+    }
+}
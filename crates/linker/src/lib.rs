@@ -267,6 +267,27 @@ fn collect_roc_definitions<'a>(object: &object::File<'a, &'a [u8]>) -> MutMap<St
     vaddresses
 }
 
+/// The app refers to a symbol that neither the app nor the host defines. When that is one of
+/// the `roc_` functions, such as `roc_dbg`, the host is missing a function that the app needs
+/// from it.
+fn report_undefined_symbol(
+    app_obj: &object::File,
+    index: SymbolIndex,
+    rel: &(u64, object::Relocation),
+) -> ! {
+    match app_obj.symbol_by_index(index) {
+        Ok(sym) if is_roc_symbol(&sym) => {
+            user_error!(
+                "The app calls `{}`, but the host does not define it. Add it to the host, and rebuild the host.",
+                sym.name().unwrap_or_default().trim_start_matches('_')
+            );
+        }
+        sym => {
+            internal_error!("Undefined Symbol in relocation, {:+x?}: {:+x?}", rel, sym);
+        }
+    }
+}
+
 fn collect_roc_undefined_symbols<'file, 'data>(
     object: &'file object::File<'data, &'data [u8]>,
     target: &Triple,
@@ -2302,11 +2323,7 @@ pub fn surgery_macho(
                         // Explicitly ignore some symbols that are currently always linked.
                         continue;
                     } else {
-                        internal_error!(
-                            "Undefined Symbol in relocation, {:+x?}: {:+x?}",
-                            rel,
-                            app_obj.symbol_by_index(index)
-                        );
+                        report_undefined_symbol(&app_obj, index, &rel);
                     }
                 }
 
@@ -2746,11 +2763,7 @@ pub fn surgery_elf(
                         // Explicitly ignore some symbols that are currently always linked.
                         continue;
                     } else {
-                        internal_error!(
-                            "Undefined Symbol in relocation, {:+x?}: {:+x?}",
-                            rel,
-                            app_obj.symbol_by_index(index)
-                        );
+                        report_undefined_symbol(&app_obj, index, &rel);
                     }
                 }
 
@@ -0,0 +1,218 @@
+use bumpalo::Bump;
+use roc_module::symbol::{IdentIds, ModuleIds};
+use roc_parse::ast::{Module, Spaced};
+use roc_parse::header::ExposedName;
+use roc_parse::keyword::KEYWORDS;
+use roc_parse::module::parse_header;
+use roc_parse::state::State;
+use roc_repl_eval::state::ReplState;
+
+/// The names of the builtin modules, and of the members of each
+pub(crate) struct Builtins {
+    modules: Vec<(String, Vec<String>)>,
+}
+
+impl Builtins {
+    pub(crate) fn new() -> Self {
+        let module_ids = ModuleIds::default();
+        let arena = Bump::new();
+
+        let mut modules = Vec::new();
+
+        for module_id in IdentIds::exposed_builtins(0).keys() {
+            let module_name = match module_ids.get_name(*module_id) {
+                Some(module_name) => module_name.as_str(),
+                None => continue,
+            };
+
+            // Names with a # in them are for compiler use only
+            if is_internal(module_name) {
+                continue;
+            }
+
+            // A builtin module has private helpers too, like `List.sublistLowlevel`,
+            // so we only suggest what its header exposes.
+            let src = roc_builtins::roc::module_source(*module_id);
+
+            let mut members = match parse_header(&arena, State::new(src.as_bytes())) {
+                Ok((Module::Interface { header }, _)) => header
+                    .exposes
+                    .iter()
+                    .map(|exposed| exposed_name(&exposed.value).to_string())
+                    .collect(),
+                _ => Vec::new(),
+            };
+
+            members.sort();
+
+            modules.push((module_name.to_string(), members));
+        }
+
+        modules.sort();
+
+        Builtins { modules }
+    }
+
+    /// Completions for the word that ends at `pos`, and the offset where that word begins.
+    pub(crate) fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        state: &ReplState,
+    ) -> (usize, Vec<String>) {
+        let start = line[..pos]
+            .char_indices()
+            .rev()
+            .find(|(_, c)| !(c.is_alphanumeric() || *c == '_' || *c == '.'))
+            .map_or(0, |(index, c)| index + c.len_utf8());
+        let word = &line[start..pos];
+
+        // the builtins, and the modules brought in with `:load`
+        let modules: Vec<(&str, &[String])> = self
+            .modules
+            .iter()
+            .map(|(name, members)| (name.as_str(), members.as_slice()))
+            .chain(state.loaded_modules())
+            .collect();
+
+        let mut candidates: Vec<String> = match word.rsplit_once('.') {
+            // After `List.` we only suggest what `List` exposes
+            Some((module_name, prefix)) => modules
+                .iter()
+                .filter(|(name, _)| *name == module_name)
+                .flat_map(|(_, members)| members.iter())
+                .filter(|member| member.starts_with(prefix))
+                .map(|member| format!("{}.{}", module_name, member))
+                .collect(),
+            None => {
+                let modules = modules.iter().map(|(name, _)| format!("{}.", name));
+                let keywords = KEYWORDS.iter().map(|keyword| keyword.to_string());
+                let session = state.names().map(|name| name.to_string());
+
+                modules
+                    .chain(keywords)
+                    .chain(session)
+                    .filter(|candidate| candidate.starts_with(word))
+                    .collect()
+            }
+        };
+
+        candidates.sort();
+        candidates.dedup();
+
+        (start, candidates)
+    }
+}
+
+fn is_internal(name: &str) -> bool {
+    name.contains('#')
+}
+
+fn exposed_name<'a>(spaced: &Spaced<'a, ExposedName<'a>>) -> &'a str {
+    match spaced {
+        Spaced::Item(name) => name.as_str(),
+        Spaced::SpaceBefore(inner, _) | Spaced::SpaceAfter(inner, _) => exposed_name(inner),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use roc_repl_eval::state::ReplInput;
+    use std::path::Path;
+
+    fn complete(line: &str, state: &ReplState) -> (usize, Vec<String>) {
+        Builtins::new().complete(line, line.len(), state)
+    }
+
+    #[test]
+    fn str_members() {
+        let (start, candidates) = complete("Str.conc", &ReplState::new());
+
+        assert_eq!(start, 0);
+        assert_eq!(candidates, vec!["Str.concat".to_string()]);
+
+        let (_, candidates) = complete("Str.", &ReplState::new());
+
+        assert!(candidates.contains(&"Str.fromUtf8".to_string()));
+        assert!(!candidates.contains(&"Str.fromUtf8RangeLowlevel".to_string()));
+    }
+
+    #[test]
+    fn list_members_leave_out_private_helpers() {
+        let (start, candidates) = complete("List.map List.sub", &ReplState::new());
+
+        assert_eq!(start, 9);
+        assert_eq!(candidates, vec!["List.sublist".to_string()]);
+
+        let (_, candidates) = complete("List.", &ReplState::new());
+
+        assert!(candidates.contains(&"List.map".to_string()));
+        assert!(candidates.iter().all(|name| !name.ends_with("Lowlevel")));
+    }
+
+    #[test]
+    fn bool_members_leave_out_compiler_internals() {
+        let (_, candidates) = complete("Bool.", &ReplState::new());
+
+        assert!(candidates.contains(&"Bool.isEq".to_string()));
+        assert!(!candidates.contains(&"Bool.structuralEq".to_string()));
+        assert!(!candidates.contains(&"Bool.structuralNotEq".to_string()));
+    }
+
+    #[test]
+    fn members_of_loaded_modules() {
+        let shout = "\
+interface Shout
+    exposes [shout, shoutTwice]
+    imports []
+
+shout = \\name -> Str.concat name \"!\"
+
+shoutTwice = \\name -> shout (shout name)
+";
+
+        let state = ReplState::new()
+            .load(Path::new("/fixtures/Shout.roc"), shout)
+            .unwrap();
+
+        let (_, candidates) = complete("Sh", &state);
+
+        assert_eq!(candidates, vec!["Shout.".to_string()]);
+
+        // a def that takes over `shout` leaves `Shout.shout` as it was
+        let state = match state.parse_input("shout = 1") {
+            ReplInput::Defs(state) => state,
+            ReplInput::Expr => panic!("this is a def"),
+        };
+
+        let (start, candidates) = complete("1 + Shout.sh", &state);
+
+        assert_eq!(start, 4);
+        assert_eq!(
+            candidates,
+            vec!["Shout.shout".to_string(), "Shout.shoutTwice".to_string()]
+        );
+    }
+
+    #[test]
+    fn modules_keywords_and_local_defs() {
+        let state = match ReplState::new().parse_input("score = 5\n\nscoreOf = \\x -> x") {
+            ReplInput::Defs(state) => state,
+            ReplInput::Expr => panic!("these are defs"),
+        };
+
+        let (start, candidates) = complete("1 + sco", &state);
+
+        assert_eq!(start, 4);
+        assert_eq!(candidates, vec!["score".to_string(), "scoreOf".to_string()]);
+
+        let (_, candidates) = complete("S", &state);
+
+        assert_eq!(candidates, vec!["Set.".to_string(), "Str.".to_string()]);
+
+        let (_, candidates) = complete("whe", &state);
+
+        assert_eq!(candidates, vec!["when".to_string()]);
+    }
+}
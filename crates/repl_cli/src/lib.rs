@@ -1,3 +1,5 @@
+mod completion;
+
 use bumpalo::Bump;
 use const_format::concatcp;
 use inkwell::context::Context;
@@ -6,11 +8,13 @@ use roc_gen_llvm::llvm::build::LlvmBackendMode;
 use roc_intern::SingleThreadedInterner;
 use roc_mono::layout::Layout;
 use roc_types::subs::Subs;
+use rustyline::completion::Completer;
 use rustyline::highlight::{Highlighter, PromptInfo};
 use rustyline::validate::{self, ValidationContext, ValidationResult, Validator};
-use rustyline_derive::{Completer, Helper, Hinter};
+use rustyline_derive::{Helper, Hinter};
 use std::borrow::Cow;
 use std::io;
+use std::time::{Duration, Instant};
 use target_lexicon::Triple;
 
 use roc_build::link::llvm_module_to_dylib;
@@ -21,14 +25,20 @@ use roc_load::{EntryPoint, MonomorphizedModule};
 use roc_mono::ir::OptLevel;
 use roc_parse::ast::Expr;
 use roc_parse::parser::{EExpr, ELambda, SyntaxError};
+use roc_repl_eval::command::{ReplCommand, HELP};
 use roc_repl_eval::eval::jit_to_ast;
-use roc_repl_eval::gen::{compile_to_mono, format_answer, ReplOutput};
+use roc_repl_eval::gen::{
+    compile_to_mono, format_answer, type_of, update_state, Compiled, ReplOutput,
+};
+use roc_repl_eval::state::{is_defs, ReplState};
 use roc_repl_eval::{ReplApp, ReplAppMemory};
 use roc_reporting::report::DEFAULT_PALETTE;
 use roc_std::RocStr;
 use roc_target::TargetInfo;
 use roc_types::pretty_print::{name_and_print_var, DebugPrint};
 
+use crate::completion::Builtins;
+
 const BLUE: &str = "\u{001b}[36m";
 const PINK: &str = "\u{001b}[35m";
 const END_COL: &str = "\u{001b}[0m";
@@ -48,21 +58,38 @@ pub const INSTRUCTIONS: &str = "Enter an expression, or :help, or :q to quit.\n"
 pub const PROMPT: &str = concatcp!("\n", BLUE, "»", END_COL, " ");
 pub const CONT_PROMPT: &str = concatcp!(BLUE, "…", END_COL, " ");
 
-#[derive(Completer, Helper, Hinter)]
+#[derive(Helper, Hinter)]
 struct ReplHelper {
     validator: InputValidator,
+    builtins: Builtins,
     pending_src: String,
+    state: ReplState,
 }
 
 impl ReplHelper {
     pub(crate) fn new() -> ReplHelper {
         ReplHelper {
             validator: InputValidator::new(),
+            builtins: Builtins::new(),
             pending_src: String::new(),
+            state: ReplState::new(),
         }
     }
 }
 
+impl Completer for ReplHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &rustyline::Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.builtins.complete(line, pos, &self.state))
+    }
+}
+
 impl Highlighter for ReplHelper {
     fn has_continuation_prompt(&self) -> bool {
         true
@@ -108,15 +135,30 @@ impl Validator for InputValidator {
             Ok(ValidationResult::Incomplete)
         } else {
             let arena = bumpalo::Bump::new();
-            let state = roc_parse::state::State::new(ctx.input().trim().as_bytes());
+            let src = ctx.input().trim();
+            let state = roc_parse::state::State::new(src.as_bytes());
 
             match roc_parse::expr::parse_loc_expr(0, &arena, state) {
+                // Defs on their own are added to the session once they're followed by a
+                // blank line. Until then, we don't know whether an expression is coming.
+                Err((_, EExpr::DefMissingFinalExpr(_), _))
+                | Err((_, EExpr::DefMissingFinalExpr2(_, _), _))
+                    if ctx.input().ends_with('\n') && is_defs(src) =>
+                {
+                    Ok(ValidationResult::Valid(None))
+                }
                 // Special case some syntax errors to allow for multi-line inputs
                 Err((_, EExpr::DefMissingFinalExpr(_), _))
                 | Err((_, EExpr::DefMissingFinalExpr2(_, _), _))
                 | Err((_, EExpr::Lambda(ELambda::Body(_, _), _), _)) => {
                     Ok(ValidationResult::Incomplete)
                 }
+                // e.g. `when x is` with the branches still to come
+                Err((_, EExpr::Start(position), _))
+                    if position.offset as usize == src.len() && !ctx.input().ends_with('\n') =>
+                {
+                    Ok(ValidationResult::Incomplete)
+                }
                 _ => Ok(ValidationResult::Valid(None)),
             }
         }
@@ -247,6 +289,7 @@ pub fn mono_module_to_dylib<'a>(
         builder: &builder,
         dibuilder: &dibuilder,
         compile_unit: &compile_unit,
+        debug_sources: Default::default(),
         context: &context,
         interns,
         module,
@@ -308,15 +351,23 @@ pub fn mono_module_to_dylib<'a>(
 }
 
 fn gen_and_eval_llvm<'a>(
+    state: &mut ReplState,
     src: &str,
     target: Triple,
     opt_level: OptLevel,
+    eval_time: &mut Duration,
 ) -> Result<ReplOutput, SyntaxError<'a>> {
     let arena = Bump::new();
     let target_info = TargetInfo::from(&target);
 
-    let mut loaded = match compile_to_mono(&arena, src, target_info, DEFAULT_PALETTE) {
-        Ok(x) => x,
+    let mut loaded = match compile_to_mono(&arena, state, src, target_info, DEFAULT_PALETTE) {
+        Ok(Compiled::Expr(x)) => x,
+        Ok(Compiled::Defs) => {
+            return Ok(ReplOutput::Empty);
+        }
+        Ok(Compiled::UndefinedNames { names, reports }) => {
+            return Ok(ReplOutput::UndefinedNames { names, reports });
+        }
         Err(prob_strings) => {
             return Ok(ReplOutput::Problems(prob_strings));
         }
@@ -354,6 +405,8 @@ fn gen_and_eval_llvm<'a>(
 
     let mut app = CliApp { lib };
 
+    let eval_start = Instant::now();
+
     let res_answer = jit_to_ast(
         &arena,
         &mut app,
@@ -366,19 +419,142 @@ fn gen_and_eval_llvm<'a>(
         target_info,
     );
 
+    *eval_time = eval_start.elapsed();
+
     let formatted = format_answer(&arena, res_answer, expr_type_str);
     Ok(formatted)
 }
 
-fn eval_and_format<'a>(src: &str) -> Result<String, SyntaxError<'a>> {
+/// What evaluating an input gave
+enum Evaluated {
+    /// The output to print, after which the input is done with
+    Done(String),
+    /// A note that the input is made of defs which refer to names that aren't defined yet.
+    /// The input is kept, and tried again together with the next one.
+    Waiting(String),
+}
+
+/// Defs that refer to names which aren't defined yet wait for the input that follows,
+/// unless `complete` says that no more input is coming that could define them.
+fn eval_and_format<'a>(
+    state: &mut ReplState,
+    src: &str,
+    show_timings: bool,
+    complete: bool,
+) -> Result<Evaluated, SyntaxError<'a>> {
     let format_output = |output| match output {
         ReplOutput::NoProblems { expr, expr_type } => {
             format!("\n{} {}:{} {}", expr, PINK, END_COL, expr_type)
         }
-        ReplOutput::Problems(lines) => format!("\n{}\n", lines.join("\n\n")),
+        ReplOutput::Problems(lines) | ReplOutput::UndefinedNames { reports: lines, .. } => {
+            format!("\n{}\n", lines.join("\n\n"))
+        }
+        ReplOutput::Empty => String::new(),
+    };
+
+    let start = Instant::now();
+    let mut eval_time = Duration::ZERO;
+    let output = gen_and_eval_llvm(state, src, Triple::host(), OptLevel::Normal, &mut eval_time)?;
+
+    if let (ReplOutput::UndefinedNames { names, .. }, false) = (&output, complete) {
+        let names: Vec<_> = names.iter().map(|name| format!("`{}`", name)).collect();
+
+        return Ok(Evaluated::Waiting(format!(
+            "\n{}The defs so far will be added once {} {} defined. Enter a blank line twice to see the problems instead.{}\n",
+            PINK,
+            names.join(", "),
+            if names.len() == 1 { "is" } else { "are" },
+            END_COL
+        )));
+    }
+
+    let compile_time = start.elapsed() - eval_time;
+    let mut formatted = format_output(output);
+
+    if show_timings {
+        formatted.push_str(&format!(
+            "\n{}compiled in {:.2?}, evaluated in {:.2?}{}",
+            PINK, compile_time, eval_time, END_COL
+        ));
+    }
+
+    Ok(Evaluated::Done(formatted))
+}
+
+fn run_command(state: &mut ReplState, show_timings: &mut bool, command: ReplCommand) -> String {
+    let target = Triple::host();
+    let target_info = TargetInfo::from(&target);
+
+    match command {
+        ReplCommand::Help => format!("\n{}", HELP),
+        ReplCommand::Exit => unreachable!("the REPL exits before running :exit"),
+        ReplCommand::Reset => {
+            *state = ReplState::new();
+
+            String::new()
+        }
+        ReplCommand::Time => {
+            *show_timings = !*show_timings;
+
+            let status = if *show_timings { "on" } else { "off" };
+
+            format!("\nPrinting timings is now {}.", status)
+        }
+        ReplCommand::Type(src) => {
+            let arena = Bump::new();
+
+            match type_of(&arena, state, src, target_info, DEFAULT_PALETTE) {
+                Ok(expr_type) => format!("\n{} {}:{} {}", src, PINK, END_COL, expr_type),
+                Err(lines) => format!("\n{}\n", lines.join("\n\n")),
+            }
+        }
+        ReplCommand::Load(path) => {
+            let src = match std::fs::read_to_string(path) {
+                Ok(src) => src,
+                Err(error) => return format!("\nI could not read {}: {}", path, error),
+            };
+
+            // so modules loaded by relative paths end up in the same source directory
+            let full_path = std::fs::canonicalize(path).unwrap_or_else(|_| path.into());
+
+            let new_state = match state.load(&full_path, &src) {
+                Ok(new_state) => new_state,
+                Err(message) => return format!("\nI could not load {}. {}", path, message),
+            };
+
+            let arena = Bump::new();
+
+            match update_state(&arena, state, new_state, target_info, DEFAULT_PALETTE) {
+                Ok(()) => String::new(),
+                Err(lines) => format!("\n{}\n", lines.join("\n\n")),
+            }
+        }
+    }
+}
+
+fn print_output(output: &str) {
+    // Defs added to the session have nothing to show, not even a blank line
+    if !output.is_empty() {
+        println!("{}", output);
+    }
+}
+
+/// Whether more input is already there to be read, like the rest of a pasted input.
+/// Input that isn't typed into a terminal is all there from the start.
+#[cfg(unix)]
+fn input_follows() -> bool {
+    let mut stdin = libc::pollfd {
+        fd: libc::STDIN_FILENO,
+        events: libc::POLLIN,
+        revents: 0,
     };
 
-    gen_and_eval_llvm(src, Triple::host(), OptLevel::Normal).map(format_output)
+    unsafe { libc::isatty(libc::STDIN_FILENO) == 0 || libc::poll(&mut stdin, 1, 0) > 0 }
+}
+
+#[cfg(not(unix))]
+fn input_follows() -> bool {
+    false
 }
 
 fn report_parse_error(fail: SyntaxError) {
@@ -395,6 +571,7 @@ pub fn main() -> io::Result<()> {
     print!("{}{}", WELCOME_MESSAGE, INSTRUCTIONS);
 
     let mut prev_line_blank = false;
+    let mut show_timings = false;
     let mut editor = Editor::<ReplHelper>::new();
     let repl_helper = ReplHelper::new();
     editor.set_helper(Some(repl_helper));
@@ -407,21 +584,20 @@ pub fn main() -> io::Result<()> {
                 let trim_line = line.trim();
                 editor.add_history_entry(trim_line);
 
-                let pending_src = &mut editor
-                    .helper_mut()
-                    .expect("Editor helper was not set")
-                    .pending_src;
+                let helper = editor.helper_mut().expect("Editor helper was not set");
+                let pending_src = &mut helper.pending_src;
+                let state = &mut helper.state;
 
-                match trim_line.to_lowercase().as_str() {
+                match trim_line {
                     "" => {
                         if pending_src.is_empty() {
                             print!("\n{}", INSTRUCTIONS);
                         } else if prev_line_blank {
                             // After two blank lines in a row, give up and try parsing it
                             // even though it's going to fail. This way you don't get stuck.
-                            match eval_and_format(pending_src.as_str()) {
-                                Ok(output) => {
-                                    println!("{}", output);
+                            match eval_and_format(state, pending_src.as_str(), show_timings, true) {
+                                Ok(Evaluated::Done(output) | Evaluated::Waiting(output)) => {
+                                    print_output(&output);
                                 }
                                 Err(fail) => {
                                     report_parse_error(fail);
@@ -436,37 +612,49 @@ pub fn main() -> io::Result<()> {
                             continue; // Skip the part where we reset prev_line_blank to false
                         }
                     }
-                    ":help" => {
-                        println!("Use :exit or :quit or :q to exit.");
-                    }
-                    ":exit" | ":quit" | ":q" => {
-                        break;
-                    }
-                    _ => {
-                        let result = if pending_src.is_empty() {
-                            eval_and_format(trim_line)
-                        } else {
-                            pending_src.push('\n');
-                            pending_src.push_str(trim_line);
+                    _ => match ReplCommand::parse(trim_line) {
+                        Some(Ok(ReplCommand::Exit)) => {
+                            break;
+                        }
+                        Some(Ok(command)) => {
+                            print_output(&run_command(state, &mut show_timings, command));
+                        }
+                        Some(Err(message)) => {
+                            println!("\n{}", message);
+                        }
+                        None => {
+                            if !pending_src.is_empty() {
+                                pending_src.push('\n');
+                            }
 
-                            eval_and_format(pending_src.as_str())
-                        };
+                            pending_src.push_str(trim_line);
 
-                        match result {
-                            Ok(output) => {
-                                println!("{}", output);
-                                pending_src.clear();
-                            }
-                            //                            Err(Fail {
-                            //                                reason: FailReason::Eof(_),
-                            //                                ..
-                            //                            }) => {}
-                            Err(fail) => {
-                                report_parse_error(fail);
-                                pending_src.clear();
+                            match eval_and_format(state, pending_src.as_str(), show_timings, false)
+                            {
+                                Ok(Evaluated::Done(output)) => {
+                                    print_output(&output);
+                                    pending_src.clear();
+                                }
+                                // These defs may well refer to defs that come next, like
+                                // `Command : [Command Tool]` followed by `Tool : [...]`,
+                                // so keep them until those arrive. If they are in the rest
+                                // of a pasted input, there's no need to say so.
+                                Ok(Evaluated::Waiting(note)) => {
+                                    if !input_follows() {
+                                        print_output(&note);
+                                    }
+                                }
+                                //                            Err(Fail {
+                                //                                reason: FailReason::Eof(_),
+                                //                                ..
+                                //                            }) => {}
+                                Err(fail) => {
+                                    report_parse_error(fail);
+                                    pending_src.clear();
+                                }
                             }
                         }
-                    }
+                    },
                 }
             }
             Err(ReadlineError::Interrupted) => {
@@ -0,0 +1,161 @@
+//! Meta-commands like `:type`, which the REPL handles itself rather than evaluating.
+//!
+//! Parsing them here lets the CLI and the web REPL agree on what they mean,
+//! even though not every command makes sense in both.
+
+/// The help of `roc repl`, which knows every command
+pub const HELP: &str = "\
+Enter an expression to evaluate it, or a definition to add it to the session.
+
+Commands:
+  :type <expr>      Print the type of an expression without evaluating it
+  :load <file.roc>  Import what an interface module exposes into the session
+  :reset            Forget every definition in the session
+  :time             Toggle printing how long compiling and evaluating took
+  :help             Print this message
+  :exit, :quit, :q  Leave the REPL";
+
+/// The help of the web REPL, which has no files to load, no timings, and no way to leave
+pub const WEB_HELP: &str = "\
+Enter an expression to evaluate it, or a definition to add it to the session.
+
+Commands:
+  :type <expr>      Print the type of an expression without evaluating it
+  :reset            Forget every definition in the session
+  :help             Print this message";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplCommand<'a> {
+    Help,
+    Exit,
+    Reset,
+    Time,
+    /// `:type <expr>`
+    Type(&'a str),
+    /// `:load <file.roc>`
+    Load(&'a str),
+}
+
+impl<'a> ReplCommand<'a> {
+    /// Returns `None` if the input is not a command at all, which is to say it doesn't
+    /// start with a `:`, and an error message if it's a command we can't make sense of.
+    pub fn parse(input: &'a str) -> Option<Result<Self, String>> {
+        let input = input.trim().strip_prefix(':')?;
+
+        let (name, argument) = match input.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, argument.trim()),
+            None => (input, ""),
+        };
+
+        let command = match name.to_lowercase().as_str() {
+            "help" => Self::without_argument(name, argument, ReplCommand::Help),
+            "exit" | "quit" | "q" => Self::without_argument(name, argument, ReplCommand::Exit),
+            "reset" => Self::without_argument(name, argument, ReplCommand::Reset),
+            "time" => Self::without_argument(name, argument, ReplCommand::Time),
+            "type" if argument.is_empty() => Err(
+                "`:type` needs an expression, like `:type List.map`. Use :help for more.".into(),
+            ),
+            "type" => Ok(ReplCommand::Type(argument)),
+            "load" if argument.is_empty() => {
+                Err("`:load` needs a file, like `:load Utils.roc`. Use :help for more.".into())
+            }
+            "load" => Ok(ReplCommand::Load(argument)),
+            _ => Err(format!(
+                "I don't know the command `:{}`. Use :help to see the ones I know.",
+                name
+            )),
+        };
+
+        Some(command)
+    }
+
+    fn without_argument(name: &str, argument: &str, command: Self) -> Result<Self, String> {
+        if argument.is_empty() {
+            Ok(command)
+        } else {
+            Err(format!("`:{}` doesn't take an argument.", name))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn not_a_command() {
+        assert_eq!(ReplCommand::parse("1 + 1"), None);
+        assert_eq!(ReplCommand::parse("x = 5"), None);
+    }
+
+    #[test]
+    fn commands_without_argument() {
+        assert_eq!(ReplCommand::parse(":help"), Some(Ok(ReplCommand::Help)));
+        assert_eq!(ReplCommand::parse(":reset"), Some(Ok(ReplCommand::Reset)));
+        assert_eq!(ReplCommand::parse(":time"), Some(Ok(ReplCommand::Time)));
+
+        for exit in [":exit", ":quit", ":q"] {
+            assert_eq!(ReplCommand::parse(exit), Some(Ok(ReplCommand::Exit)));
+        }
+    }
+
+    #[test]
+    fn surrounding_whitespace_and_case() {
+        assert_eq!(
+            ReplCommand::parse("  :Help \n"),
+            Some(Ok(ReplCommand::Help))
+        );
+        assert_eq!(
+            ReplCommand::parse(":TYPE   Str.concat  "),
+            Some(Ok(ReplCommand::Type("Str.concat")))
+        );
+    }
+
+    #[test]
+    fn commands_with_argument() {
+        assert_eq!(
+            ReplCommand::parse(":type List.map [1, 2] Num.toStr"),
+            Some(Ok(ReplCommand::Type("List.map [1, 2] Num.toStr")))
+        );
+        assert_eq!(
+            ReplCommand::parse(":load Utils.roc"),
+            Some(Ok(ReplCommand::Load("Utils.roc")))
+        );
+    }
+
+    #[test]
+    fn missing_argument() {
+        assert!(matches!(ReplCommand::parse(":type"), Some(Err(_))));
+        assert!(matches!(ReplCommand::parse(":load  "), Some(Err(_))));
+    }
+
+    #[test]
+    fn unexpected_argument() {
+        assert_eq!(
+            ReplCommand::parse(":reset now"),
+            Some(Err("`:reset` doesn't take an argument.".to_string()))
+        );
+    }
+
+    #[test]
+    fn web_help_is_the_help_without_cli_only_commands() {
+        for line in WEB_HELP.lines() {
+            assert!(HELP.lines().any(|help_line| help_line == line), "{}", line);
+        }
+
+        for cli_only in [":load", ":time", ":exit"] {
+            assert!(!WEB_HELP.contains(cli_only), "{}", cli_only);
+        }
+    }
+
+    #[test]
+    fn unknown_command() {
+        assert_eq!(
+            ReplCommand::parse(":frobnicate"),
+            Some(Err(
+                "I don't know the command `:frobnicate`. Use :help to see the ones I know."
+                    .to_string()
+            ))
+        );
+    }
+}
@@ -17,7 +17,9 @@ use roc_parse::ast::{AssignedField, Collection, Expr, StrLiteral};
 use roc_region::all::{Loc, Region};
 use roc_std::RocDec;
 use roc_target::TargetInfo;
-use roc_types::subs::{Content, FlatType, GetSubsSlice, RecordFields, Subs, UnionTags, Variable};
+use roc_types::subs::{
+    Content, FlatType, GetSubsSlice, RecordFields, Subs, TupleElems, UnionTags, Variable,
+};
 
 use crate::{ReplApp, ReplAppMemory};
 
@@ -376,6 +378,9 @@ fn jit_to_ast_help<'a, A: ReplApp<'a>>(
                 Content::Structure(FlatType::EmptyRecord) => {
                     Ok(struct_to_ast(env, mem, addr, RecordFields::empty()))
                 }
+                Content::Structure(FlatType::Tuple(elems, ext)) => {
+                    Ok(struct_to_ast_tuple(env, mem, addr, *elems, *ext))
+                }
                 Content::Structure(FlatType::TagUnion(tags, _)) => {
                     debug_assert_eq!(tags.len(), 1);
 
@@ -578,6 +583,9 @@ fn addr_to_ast<'a, M: ReplAppMemory>(
             Content::Structure(FlatType::EmptyRecord) => {
                 struct_to_ast(env, mem, addr, RecordFields::empty())
             }
+            Content::Structure(FlatType::Tuple(elems, ext)) => {
+                struct_to_ast_tuple(env, mem, addr, *elems, *ext)
+            }
             other => {
                 unreachable!(
                     "Something had a Struct layout, but instead of a Record type, it had: {:?}",
@@ -1058,6 +1066,85 @@ fn struct_to_ast<'a, 'env, M: ReplAppMemory>(
     }
 }
 
+fn struct_to_ast_tuple<'a, 'env, M: ReplAppMemory>(
+    env: &mut Env<'a, 'env>,
+    mem: &'a M,
+    addr: usize,
+    tuple_elems: TupleElems,
+    ext: Variable,
+) -> Expr<'a> {
+    let arena = env.arena;
+    let subs = env.subs;
+
+    let mut elems = std::vec::Vec::with_capacity(tuple_elems.len());
+
+    for (index, elem_var) in tuple_elems.sorted_iterator_and_ext(subs, ext).0 {
+        let elem_layout = env.layout_cache.from_var(arena, elem_var, subs).unwrap();
+
+        elems.push((index, elem_var, elem_layout));
+    }
+
+    if let [(_, elem_var, elem_layout)] = elems.as_slice() {
+        // this is a 1-element wrapper tuple around another value
+        let inner_content = env.subs.get_content_without_compacting(*elem_var);
+        let inner_layouts = arena.alloc([*elem_layout]);
+
+        let loc_expr = &*arena.alloc(Loc {
+            value: addr_to_ast(
+                env,
+                mem,
+                addr,
+                &Layout::struct_no_name_order(inner_layouts),
+                WhenRecursive::Unreachable,
+                inner_content,
+            ),
+            region: Region::zero(),
+        });
+
+        return Expr::Tuple(Collection::with_items(arena.alloc([loc_expr])));
+    }
+
+    // The elements are stored sorted by descending alignment, and then by position (the sort is
+    // stable), but we want to render them in the order of the type.
+    elems.sort_by(|(_, _, layout1), (_, _, layout2)| {
+        let align1 = layout1.alignment_bytes(&env.layout_cache.interner, env.target_info);
+        let align2 = layout2.alignment_bytes(&env.layout_cache.interner, env.target_info);
+
+        align2.cmp(&align1)
+    });
+
+    // We'll advance this as we iterate through the elements
+    let mut elem_addr = addr;
+    let mut output = std::vec::Vec::with_capacity(elems.len());
+
+    for (index, elem_var, elem_layout) in elems {
+        let content = subs.get_content_without_compacting(elem_var);
+
+        let loc_expr = &*arena.alloc(Loc {
+            value: addr_to_ast(
+                env,
+                mem,
+                elem_addr,
+                &elem_layout,
+                WhenRecursive::Unreachable,
+                content,
+            ),
+            region: Region::zero(),
+        });
+
+        output.push((index, loc_expr));
+
+        // Advance the element pointer to the next element.
+        elem_addr += elem_layout.stack_size(&env.layout_cache.interner, env.target_info) as usize;
+    }
+
+    output.sort_by_key(|(index, _)| *index);
+
+    let output = Vec::from_iter_in(output.into_iter().map(|(_, loc_expr)| loc_expr), arena);
+
+    Expr::Tuple(Collection::with_items(output.into_bump_slice()))
+}
+
 fn unpack_single_element_tag_union(subs: &Subs, tags: UnionTags) -> (&TagName, &[Variable]) {
     let (tag_name_index, payload_vars_index) = tags.iter_all().next().unwrap();
 
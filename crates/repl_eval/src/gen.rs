@@ -7,15 +7,54 @@ use roc_fmt::annotation::Formattable;
 use roc_fmt::annotation::{Newlines, Parens};
 use roc_load::{LoadingProblem, MonomorphizedModule};
 use roc_parse::ast::Expr;
+use roc_problem::can::{Problem, RuntimeError};
 use roc_region::all::LineInfo;
 use roc_reporting::report::{can_problem, type_problem, RocDocAllocator};
 use roc_target::TargetInfo;
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
 
 use crate::eval::ToAstProblem;
+use crate::state::{ReplInput, ReplState};
 
 pub enum ReplOutput {
     Problems(Vec<String>),
-    NoProblems { expr: String, expr_type: String },
+    NoProblems {
+        expr: String,
+        expr_type: String,
+    },
+    /// The input only added definitions to the session, so there is nothing to print
+    Empty,
+    /// The input is made of definitions that refer to names which aren't defined yet.
+    /// Whoever reads the input can wait for the definitions that follow before reporting these.
+    UndefinedNames {
+        names: Vec<String>,
+        reports: Vec<String>,
+    },
+}
+
+/// What compiling an input in the session led to
+#[allow(clippy::large_enum_variant)]
+pub enum Compiled<'a> {
+    /// An expression, ready to evaluate
+    Expr(MonomorphizedModule<'a>),
+    /// Definitions, which are now part of the session
+    Defs,
+    /// Definitions that only failed to compile because they refer to names which aren't
+    /// defined yet, e.g. the first of two type aliases that refer to each other. The session
+    /// is unchanged.
+    UndefinedNames {
+        names: Vec<String>,
+        reports: Vec<String>,
+    },
+}
+
+/// The problems of an input that did not compile
+struct Problems {
+    lines: Vec<String>,
+    /// Whether every problem is the use of a name that isn't defined
+    only_undefined_names: bool,
+    /// The names that aren't defined, in the order they were first used
+    undefined_names: Vec<String>,
 }
 
 pub fn format_answer(
@@ -42,16 +81,91 @@ pub fn format_answer(
     }
 }
 
+/// Compile `src` in a module with every def in the session so far. If `src` itself
+/// is made of defs, they are added to the session once they compile without problems,
+/// and there is nothing to evaluate.
 pub fn compile_to_mono<'a>(
     arena: &'a Bump,
+    state: &mut ReplState,
     src: &str,
     target_info: TargetInfo,
     palette: Palette,
-) -> Result<MonomorphizedModule<'a>, Vec<String>> {
-    let filename = PathBuf::from("");
-    let src_dir = PathBuf::from("fake/test/path");
+) -> Result<Compiled<'a>, Vec<String>> {
+    match state.parse_input(src) {
+        ReplInput::Expr => match compile_in_session(arena, state, src, target_info, palette) {
+            Ok(loaded) => Ok(Compiled::Expr(loaded)),
+            Err(problems) => Err(problems.lines),
+        },
+        ReplInput::Defs(new_state) => {
+            match compile_in_session(arena, &new_state, "{}", target_info, palette) {
+                Ok(_) => {
+                    *state = new_state;
+
+                    Ok(Compiled::Defs)
+                }
+                Err(problems) if problems.only_undefined_names => Ok(Compiled::UndefinedNames {
+                    names: problems.undefined_names,
+                    reports: problems.lines,
+                }),
+                Err(problems) => Err(problems.lines),
+            }
+        }
+    }
+}
+
+/// Replace the session with `new_state`, but only if all of its defs compile.
+pub fn update_state(
+    arena: &Bump,
+    state: &mut ReplState,
+    new_state: ReplState,
+    target_info: TargetInfo,
+    palette: Palette,
+) -> Result<(), Vec<String>> {
+    // There is no value to print, but `{}` still makes sure the new defs compile.
+    compile_in_session(arena, &new_state, "{}", target_info, palette)
+        .map_err(|problems| problems.lines)?;
+
+    *state = new_state;
+
+    Ok(())
+}
+
+/// The type of the expression `src`, which we compile but don't evaluate.
+pub fn type_of(
+    arena: &Bump,
+    state: &ReplState,
+    src: &str,
+    target_info: TargetInfo,
+    palette: Palette,
+) -> Result<String, Vec<String>> {
+    let mut loaded = compile_in_session(arena, state, src, target_info, palette)
+        .map_err(|problems| problems.lines)?;
+
+    debug_assert_eq!(loaded.exposed_to_host.values.len(), 1);
+    let main_fn_var = *loaded.exposed_to_host.values.values().next().unwrap();
 
-    let module_src = arena.alloc(promote_expr_to_module(src));
+    Ok(name_and_print_var(
+        main_fn_var,
+        &mut loaded.subs,
+        loaded.module_id,
+        &loaded.interns,
+        DebugPrint::NOTHING,
+    ))
+}
+
+fn compile_in_session<'a>(
+    arena: &'a Bump,
+    session: &ReplState,
+    expr: &str,
+    target_info: TargetInfo,
+    palette: Palette,
+) -> Result<MonomorphizedModule<'a>, Problems> {
+    let filename = PathBuf::from("");
+    let src_dir = match session.src_dir() {
+        Some(src_dir) => src_dir.to_path_buf(),
+        None => PathBuf::from("fake/test/path"),
+    };
+    let module_src = arena.alloc(session.module_src(expr));
 
     let exposed_types = Default::default();
     let loaded = roc_load::load_and_monomorphize_from_str(
@@ -65,13 +179,18 @@ pub fn compile_to_mono<'a>(
             render: roc_reporting::report::RenderTarget::ColorTerminal,
             threading: Threading::Single,
             exec_mode: ExecutionMode::Executable,
+            ..Default::default()
         },
     );
 
     let mut loaded = match loaded {
         Ok(v) => v,
         Err(LoadingProblem::FormattedReport(report)) => {
-            return Err(vec![report]);
+            return Err(Problems {
+                lines: vec![report],
+                only_undefined_names: false,
+                undefined_names: Vec::new(),
+            });
         }
         Err(e) => {
             panic!("error while loading module: {:?}", e)
@@ -81,24 +200,55 @@ pub fn compile_to_mono<'a>(
     let MonomorphizedModule {
         interns,
         sources,
+        parse_problems,
         can_problems,
         type_problems,
         ..
     } = &mut loaded;
 
     let mut lines = Vec::new();
+    let mut only_undefined_names = true;
+    let mut undefined_names = Vec::new();
 
     for (home, (module_path, src)) in sources.iter() {
-        let can_probs = can_problems.remove(home).unwrap_or_default();
+        let module_parse_problems = parse_problems.remove(home).unwrap_or_default();
+        only_undefined_names &= module_parse_problems.is_empty();
+        lines.extend(module_parse_problems);
+
+        let mut can_probs = can_problems.remove(home).unwrap_or_default();
         let type_probs = type_problems.remove(home).unwrap_or_default();
 
+        // Defs from earlier inputs and loaded modules usually aren't used by this one,
+        // and that's fine.
+        can_probs.retain(|problem| match problem {
+            Problem::UnusedDef(symbol, _) => !session.defines(symbol.as_str(interns)),
+            Problem::UnusedImport(module_id, _) => match interns.module_ids.get_name(*module_id) {
+                Some(module_name) => !session.imports(module_name),
+                None => true,
+            },
+            _ => true,
+        });
+
+        for problem in can_probs.iter() {
+            match problem {
+                Problem::RuntimeError(RuntimeError::LookupNotInScope(loc_ident, _)) => {
+                    let name = loc_ident.value.as_str().to_string();
+
+                    if !undefined_names.contains(&name) {
+                        undefined_names.push(name);
+                    }
+                }
+                _ => only_undefined_names = false,
+            }
+        }
+
         let error_count = can_probs.len() + type_probs.len();
 
         if error_count == 0 {
             continue;
         }
 
-        let line_info = LineInfo::new(module_src);
+        let line_info = LineInfo::new(src);
         let src_lines: Vec<&str> = src.split('\n').collect();
 
         // Report parsing and canonicalization problems
@@ -120,27 +270,18 @@ pub fn compile_to_mono<'a>(
                 report.render_color_terminal(&mut buf, &alloc, &palette);
 
                 lines.push(buf);
+                only_undefined_names = false;
             }
         }
     }
 
     if !lines.is_empty() {
-        Err(lines)
+        Err(Problems {
+            lines,
+            only_undefined_names,
+            undefined_names,
+        })
     } else {
         Ok(loaded)
     }
 }
-
-fn promote_expr_to_module(src: &str) -> String {
-    let mut buffer =
-        String::from("app \"app\" provides [replOutput] to \"./platform\"\n\nreplOutput =\n");
-
-    for line in src.lines() {
-        // indent the body!
-        buffer.push_str("    ");
-        buffer.push_str(line);
-        buffer.push('\n');
-    }
-
-    buffer
-}
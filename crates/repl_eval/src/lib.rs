@@ -2,8 +2,11 @@ use roc_parse::ast::Expr;
 use roc_std::RocDec;
 use roc_target::TargetInfo;
 
+pub mod command;
 pub mod eval;
 pub mod gen;
+mod shadow;
+pub mod state;
 
 pub trait ReplApp<'a> {
     type Memory: 'a + ReplAppMemory;
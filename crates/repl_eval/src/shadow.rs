@@ -0,0 +1,536 @@
+//! Renaming the defs of a REPL session that later input shadows.
+//!
+//! The REPL emits every def of the session into one module, and Roc doesn't allow shadowing
+//! within a module. So when a name is defined again while earlier defs still use it, the
+//! earlier def gets a fresh name, and so does every mention of it in the defs that use it.
+//! Those defs keep seeing the def they were entered against; later input sees the new one.
+
+use bumpalo::Bump;
+use roc_collections::all::MutSet;
+use roc_parse::ast::{
+    AssignedField, Collection, Expr, HasAbility, HasImpls, Pattern, Spaced, StrLiteral, StrSegment,
+    Tag, TypeAnnotation, TypeDef, TypeHeader, ValueDef,
+};
+use roc_parse::module::module_defs;
+use roc_parse::parser::Parser;
+use roc_parse::state::State;
+use roc_region::all::{Loc, Region};
+
+/// The top-level names the defs in `src` mention, including the names they define.
+pub(crate) fn mentioned_names(src: &str) -> MutSet<String> {
+    let mut walker = Walker {
+        src,
+        goal: Goal::Collect(MutSet::default()),
+    };
+
+    walker.walk();
+
+    match walker.goal {
+        Goal::Collect(names) => names,
+        Goal::Rename { .. } => unreachable!(),
+    }
+}
+
+/// Replaces every mention of the top-level name `old` in the defs of `src` with `new`.
+pub(crate) fn rename(src: &str, old: &str, new: &str) -> String {
+    let mut walker = Walker {
+        src,
+        goal: Goal::Rename {
+            old,
+            new,
+            edits: Vec::new(),
+        },
+    };
+
+    walker.walk();
+
+    let mut edits = match walker.goal {
+        Goal::Rename { edits, .. } => edits,
+        Goal::Collect(_) => unreachable!(),
+    };
+
+    edits.sort_by_key(|(start, _, _)| *start);
+    edits.dedup_by_key(|(start, _, _)| *start);
+
+    let mut renamed = src.to_string();
+
+    // back to front, so the offsets of the edits still to come stay valid
+    for (start, end, replacement) in edits.into_iter().rev() {
+        renamed.replace_range(start..end, &replacement);
+    }
+
+    renamed
+}
+
+enum Goal<'s> {
+    /// Every name mentioned
+    Collect(MutSet<String>),
+    /// The byte ranges of `src` to replace to rename `old` to `new`
+    Rename {
+        old: &'s str,
+        new: &'s str,
+        edits: Vec<(usize, usize, String)>,
+    },
+}
+
+struct Walker<'s> {
+    src: &'s str,
+    goal: Goal<'s>,
+}
+
+impl Walker<'_> {
+    fn walk(&mut self) {
+        let arena = Bump::new();
+
+        // every def in the session parsed before, so it will again
+        let defs = match module_defs().parse(&arena, State::new(self.src.as_bytes())) {
+            Ok((_, defs, _)) => defs,
+            Err(_) => return,
+        };
+
+        for def in defs.defs() {
+            match def {
+                Ok(type_def) => self.type_def(type_def),
+                Err(value_def) => self.value_def(value_def),
+            }
+        }
+    }
+
+    /// A mention of `name`, written as `token` at the start of `region`. When renaming,
+    /// `replacement` turns the new name into what replaces `token`.
+    fn name(
+        &mut self,
+        region: Region,
+        name: &str,
+        token: &str,
+        replacement: impl FnOnce(&str) -> String,
+    ) {
+        match &mut self.goal {
+            Goal::Collect(names) => {
+                names.insert(name.to_string());
+            }
+            Goal::Rename { old, new, .. } if *old == name => {
+                let replacement = replacement(*new);
+
+                self.mention(region, token, replacement);
+            }
+            Goal::Rename { .. } => {}
+        }
+    }
+
+    /// Replaces `token`, which is the first thing in `region` apart from spaces, comments and
+    /// parentheses. Nodes like `SpaceBefore` and `ParensAround` share the region of what
+    /// they wrap, so this is how we find the name itself.
+    fn mention(&mut self, region: Region, token: &str, replacement: String) {
+        let end = region.end().offset as usize;
+        let mut start = region.start().offset as usize;
+
+        loop {
+            let rest = &self.src[start..end];
+            let trimmed = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '(');
+            start += rest.len() - trimmed.len();
+
+            if trimmed.starts_with('#') {
+                start += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else {
+                break;
+            }
+        }
+
+        let is_whole_token = self.src[start..end].starts_with(token)
+            && !self.src[start + token.len()..]
+                .starts_with(|c: char| c.is_alphanumeric() || c == '_');
+
+        if let (true, Goal::Rename { edits, .. }) = (is_whole_token, &mut self.goal) {
+            edits.push((start, start + token.len(), replacement));
+        }
+    }
+
+    fn type_def(&mut self, type_def: &TypeDef) {
+        match type_def {
+            TypeDef::Alias { header, ann } => {
+                self.header(header);
+                self.annotation(ann);
+            }
+            TypeDef::Opaque {
+                header,
+                typ,
+                derived,
+            } => {
+                self.header(header);
+                self.annotation(typ);
+
+                if let Some(derived) = derived {
+                    for ability in derived.value.collection().iter() {
+                        self.has_ability(&ability.value);
+                    }
+                }
+            }
+            TypeDef::Ability {
+                header, members, ..
+            } => {
+                self.header(header);
+
+                for member in members.iter() {
+                    let name = spaced_item(&member.name.value);
+                    self.name(member.name.region, name, name, str::to_string);
+
+                    self.annotation(&member.typ);
+                }
+            }
+        }
+    }
+
+    fn header(&mut self, header: &TypeHeader) {
+        let name = header.name.value;
+        self.name(header.name.region, name, name, str::to_string);
+    }
+
+    fn has_ability(&mut self, has_ability: &HasAbility) {
+        match has_ability {
+            HasAbility::HasAbility { ability, impls } => {
+                self.annotation(ability);
+
+                if let Some(impls) = impls {
+                    self.has_impls(&impls.value);
+                }
+            }
+            HasAbility::SpaceBefore(inner, _) | HasAbility::SpaceAfter(inner, _) => {
+                self.has_ability(inner)
+            }
+        }
+    }
+
+    fn has_impls(&mut self, has_impls: &HasImpls) {
+        match has_impls {
+            HasImpls::HasImpls(fields) => self.fields(fields),
+            HasImpls::SpaceBefore(inner, _) | HasImpls::SpaceAfter(inner, _) => {
+                self.has_impls(inner)
+            }
+        }
+    }
+
+    fn value_def(&mut self, value_def: &ValueDef) {
+        match value_def {
+            ValueDef::Annotation(pattern, ann) => {
+                self.pattern(pattern);
+                self.annotation(ann);
+            }
+            ValueDef::Body(pattern, expr) => {
+                self.pattern(pattern);
+                self.expr(expr);
+            }
+            ValueDef::AnnotatedBody {
+                ann_pattern,
+                ann_type,
+                body_pattern,
+                body_expr,
+                ..
+            } => {
+                self.pattern(ann_pattern);
+                self.annotation(ann_type);
+                self.pattern(body_pattern);
+                self.expr(body_expr);
+            }
+            ValueDef::Expect { condition, .. } | ValueDef::ExpectFx { condition, .. } => {
+                self.expr(condition)
+            }
+            ValueDef::Malformed(_) => {}
+        }
+    }
+
+    fn expr(&mut self, loc_expr: &Loc<Expr>) {
+        self.expr_at(loc_expr.region, &loc_expr.value)
+    }
+
+    fn expr_at(&mut self, region: Region, expr: &Expr) {
+        match expr {
+            Expr::Var { module_name, ident } if module_name.is_empty() => {
+                self.name(region, ident, ident, str::to_string)
+            }
+            Expr::OpaqueRef(name) => self.name(region, &name[1..], name, |new| format!("@{}", new)),
+            Expr::Access(inner, _)
+            | Expr::SpaceBefore(inner, _)
+            | Expr::SpaceAfter(inner, _)
+            | Expr::ParensAround(inner) => self.expr_at(region, inner),
+            Expr::Str(literal) => self.str_literal(literal),
+            Expr::List(items) | Expr::Tuple(items) => {
+                for item in items.iter() {
+                    self.expr(item);
+                }
+            }
+            Expr::RecordUpdate { update, fields } => {
+                self.expr(update);
+                self.fields(fields);
+            }
+            Expr::Record(fields) => self.fields(fields),
+            Expr::Closure(patterns, body) => {
+                for pattern in patterns.iter() {
+                    self.pattern(pattern);
+                }
+
+                self.expr(body);
+            }
+            Expr::Defs(defs, final_expr) => {
+                for def in defs.defs() {
+                    match def {
+                        Ok(type_def) => self.type_def(type_def),
+                        Err(value_def) => self.value_def(value_def),
+                    }
+                }
+
+                self.expr(final_expr);
+            }
+            Expr::Backpassing(patterns, argument, continuation) => {
+                for pattern in patterns.iter() {
+                    self.pattern(pattern);
+                }
+
+                self.expr(argument);
+                self.expr(continuation);
+            }
+            Expr::Expect(condition, continuation) => {
+                self.expr(condition);
+                self.expr(continuation);
+            }
+            Expr::Dbg(inner) | Expr::UnaryOp(inner, _) => self.expr(inner),
+            Expr::Apply(function, arguments, _) => {
+                self.expr(function);
+
+                for argument in arguments.iter() {
+                    self.expr(argument);
+                }
+            }
+            Expr::BinOps(operands, last) => {
+                for (operand, _) in operands.iter() {
+                    self.expr(operand);
+                }
+
+                self.expr(last);
+            }
+            Expr::If(branches, final_else) => {
+                for (condition, then) in branches.iter() {
+                    self.expr(condition);
+                    self.expr(then);
+                }
+
+                self.expr(final_else);
+            }
+            Expr::When(condition, branches) => {
+                self.expr(condition);
+
+                for branch in branches.iter() {
+                    for pattern in branch.patterns.iter() {
+                        self.pattern(pattern);
+                    }
+
+                    if let Some(guard) = &branch.guard {
+                        self.expr(guard);
+                    }
+
+                    self.expr(&branch.value);
+                }
+            }
+            Expr::Float(_)
+            | Expr::Num(_)
+            | Expr::NonBase10Int { .. }
+            | Expr::AccessorFunction(_)
+            | Expr::SingleQuote(_)
+            | Expr::Var { .. }
+            | Expr::Underscore(_)
+            | Expr::Crash
+            | Expr::Tag(_)
+            | Expr::MalformedIdent(_, _)
+            | Expr::MalformedClosure
+            | Expr::Malformed(_)
+            | Expr::PrecedenceConflict(_) => {}
+        }
+    }
+
+    fn fields(&mut self, fields: &Collection<Loc<AssignedField<Expr>>>) {
+        for field in fields.iter() {
+            self.field(&field.value);
+        }
+    }
+
+    fn field(&mut self, field: &AssignedField<Expr>) {
+        match field {
+            AssignedField::RequiredValue(_, _, value)
+            | AssignedField::OptionalValue(_, _, value) => self.expr(value),
+            // `{ x }` is short for `{ x: x }`, and the label must stay as it is
+            AssignedField::LabelOnly(label) => {
+                self.name(label.region, label.value, label.value, |new| {
+                    format!("{}: {}", label.value, new)
+                })
+            }
+            AssignedField::SpaceBefore(inner, _)
+            | AssignedField::SpaceAfter(inner, _)
+            | AssignedField::Nested(_, inner) => self.field(inner),
+            AssignedField::Malformed(_) => {}
+        }
+    }
+
+    fn str_literal(&mut self, literal: &StrLiteral) {
+        match literal {
+            StrLiteral::PlainLine(_) => {}
+            StrLiteral::Line(segments) => self.str_segments(segments),
+            StrLiteral::Block(lines) => {
+                for segments in lines.iter() {
+                    self.str_segments(segments);
+                }
+            }
+        }
+    }
+
+    fn str_segments(&mut self, segments: &[StrSegment]) {
+        for segment in segments.iter() {
+            if let StrSegment::Interpolated(loc_expr) = segment {
+                self.expr_at(loc_expr.region, loc_expr.value);
+            }
+        }
+    }
+
+    fn pattern(&mut self, loc_pattern: &Loc<Pattern>) {
+        self.pattern_at(loc_pattern.region, &loc_pattern.value)
+    }
+
+    fn pattern_at(&mut self, region: Region, pattern: &Pattern) {
+        match pattern {
+            Pattern::Identifier(name) => self.name(region, name, name, str::to_string),
+            Pattern::OpaqueRef(name) => {
+                self.name(region, &name[1..], name, |new| format!("@{}", new))
+            }
+            Pattern::Apply(tag, arguments) => {
+                self.pattern(tag);
+
+                for argument in arguments.iter() {
+                    self.pattern(argument);
+                }
+            }
+            Pattern::RecordDestructure(patterns)
+            | Pattern::Tuple(patterns)
+            | Pattern::List(patterns) => {
+                for pattern in patterns.iter() {
+                    self.pattern(pattern);
+                }
+            }
+            Pattern::ListRest(Some(name)) => {
+                let token = format!("..{}", name);
+                self.name(region, name, &token, |new| format!("..{}", new))
+            }
+            Pattern::RequiredField(_, pattern) => self.pattern(pattern),
+            Pattern::OptionalField(_, default) => self.expr(default),
+            Pattern::SpaceBefore(inner, _) | Pattern::SpaceAfter(inner, _) => {
+                self.pattern_at(region, inner)
+            }
+            Pattern::Tag(_)
+            | Pattern::ListRest(None)
+            | Pattern::NumLiteral(_)
+            | Pattern::NonBase10Literal { .. }
+            | Pattern::FloatLiteral(_)
+            | Pattern::StrLiteral(_)
+            | Pattern::Underscore(_)
+            | Pattern::SingleQuote(_)
+            | Pattern::Malformed(_)
+            | Pattern::MalformedIdent(_, _)
+            | Pattern::QualifiedIdentifier { .. } => {}
+        }
+    }
+
+    fn annotation(&mut self, loc_ann: &Loc<TypeAnnotation>) {
+        self.annotation_at(loc_ann.region, &loc_ann.value)
+    }
+
+    fn annotation_at(&mut self, region: Region, ann: &TypeAnnotation) {
+        match ann {
+            TypeAnnotation::Function(arguments, result) => {
+                for argument in arguments.iter() {
+                    self.annotation(argument);
+                }
+
+                self.annotation(result);
+            }
+            TypeAnnotation::Apply(module_name, name, arguments) => {
+                if module_name.is_empty() {
+                    self.name(region, name, name, str::to_string);
+                }
+
+                for argument in arguments.iter() {
+                    self.annotation(argument);
+                }
+            }
+            TypeAnnotation::As(inner, _, _) => self.annotation(inner),
+            TypeAnnotation::Record { fields, ext } => {
+                for field in fields.iter() {
+                    self.field_type(&field.value);
+                }
+
+                if let Some(ext) = ext {
+                    self.annotation(ext);
+                }
+            }
+            TypeAnnotation::Tuple { elems, ext } => {
+                for elem in elems.iter() {
+                    self.annotation(elem);
+                }
+
+                if let Some(ext) = ext {
+                    self.annotation(ext);
+                }
+            }
+            TypeAnnotation::TagUnion { ext, tags } => {
+                for tag in tags.iter() {
+                    self.tag(&tag.value);
+                }
+
+                if let Some(ext) = ext {
+                    self.annotation(ext);
+                }
+            }
+            TypeAnnotation::Where(inner, clauses) => {
+                self.annotation(inner);
+
+                for clause in clauses.iter() {
+                    self.annotation(&clause.value.ability);
+                }
+            }
+            TypeAnnotation::SpaceBefore(inner, _) | TypeAnnotation::SpaceAfter(inner, _) => {
+                self.annotation_at(region, inner)
+            }
+            TypeAnnotation::BoundVariable(_)
+            | TypeAnnotation::Inferred
+            | TypeAnnotation::Wildcard
+            | TypeAnnotation::Malformed(_) => {}
+        }
+    }
+
+    fn field_type(&mut self, field: &AssignedField<TypeAnnotation>) {
+        match field {
+            AssignedField::RequiredValue(_, _, value)
+            | AssignedField::OptionalValue(_, _, value) => self.annotation(value),
+            AssignedField::SpaceBefore(inner, _)
+            | AssignedField::SpaceAfter(inner, _)
+            | AssignedField::Nested(_, inner) => self.field_type(inner),
+            AssignedField::LabelOnly(_) | AssignedField::Malformed(_) => {}
+        }
+    }
+
+    fn tag(&mut self, tag: &Tag) {
+        match tag {
+            Tag::Apply { args, .. } => {
+                for arg in args.iter() {
+                    self.annotation(arg);
+                }
+            }
+            Tag::SpaceBefore(inner, _) | Tag::SpaceAfter(inner, _) => self.tag(inner),
+            Tag::Malformed(_) => {}
+        }
+    }
+}
+
+fn spaced_item<'a>(spaced: &Spaced<'a, &'a str>) -> &'a str {
+    match spaced {
+        Spaced::Item(item) => *item,
+        Spaced::SpaceBefore(inner, _) | Spaced::SpaceAfter(inner, _) => spaced_item(inner),
+    }
+}
@@ -0,0 +1,636 @@
+//! The definitions a REPL session has accumulated so far.
+//!
+//! Every input is compiled as part of a fresh module, so to make `x = 5` on one line
+//! available on the next, we remember the source of each top-level def and emit all
+//! of them again into every module we synthesize. Modules brought in with `:load` are
+//! imported by that module instead, so the loader resolves their own imports.
+
+use std::path::{Path, PathBuf};
+
+use bumpalo::Bump;
+use roc_collections::all::MutSet;
+use roc_parse::ast::{Module, Pattern, Spaced, TypeDef, TypeHeader, ValueDef};
+use roc_parse::header::ExposedName;
+use roc_parse::module::{module_defs, parse_header};
+use roc_parse::parser::Parser;
+use roc_parse::state::State;
+use roc_region::all::Region;
+
+use crate::shadow::{mentioned_names, rename};
+
+/// The name of the def that holds the expression being evaluated
+pub const REPL_OUTPUT: &str = "replOutput";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DefKind {
+    /// A type annotation whose body hasn't been entered yet, e.g. `x : Str`
+    Annotation,
+    /// A value, with or without an annotation
+    Value,
+    /// A type alias, opaque type, or ability
+    Type,
+}
+
+#[derive(Debug, Clone)]
+struct PastDef {
+    kind: DefKind,
+    /// The names this def introduces. Entering a new def with any of these names
+    /// shadows this one.
+    names: Vec<String>,
+    /// The input this def came from. The defs of one input are compiled together,
+    /// so they refer to each other rather than to the defs they shadow.
+    input: usize,
+    /// Whether a later def shadowed this one. It is only kept, under a fresh name,
+    /// for the earlier defs that use it.
+    shadowed: bool,
+    src: String,
+    /// The top-level names `src` mentions, so telling which defs use which doesn't take
+    /// parsing every def of the session again.
+    mentions: MutSet<String>,
+}
+
+impl PastDef {
+    /// Replaces the mentions of `old` in this def with `new`
+    fn rename(&mut self, old: &str, new: &str) {
+        if self.mentions.remove(old) {
+            self.src = rename(&self.src, old, new);
+            self.mentions.insert(new.to_string());
+        }
+    }
+}
+
+/// An interface module loaded into the session
+#[derive(Debug, Clone)]
+struct LoadedModule {
+    name: String,
+    /// The exposed names the session imports unqualified. A def entered later with one
+    /// of these names takes it over; the module's value stays available qualified.
+    exposes: Vec<String>,
+    /// Every name the module exposes, which the session can always use qualified
+    exposed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReplState {
+    past_defs: Vec<PastDef>,
+    /// How many inputs have added defs so far
+    inputs: usize,
+    loaded_modules: Vec<LoadedModule>,
+    /// The directory the loaded modules, and the modules they import, are found in
+    src_dir: Option<PathBuf>,
+}
+
+/// An input, after we've decided whether it adds to the session.
+pub enum ReplInput {
+    /// An expression, which can refer to the defs we have so far
+    Expr,
+    /// One or more top-level defs. This state only replaces the current one once they
+    /// compile, so a mistake doesn't get stuck in the session.
+    Defs(ReplState),
+}
+
+impl ReplState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inputs that parse as top-level defs are added to (a copy of) the session.
+    /// Anything else is an expression.
+    pub fn parse_input(&self, src: &str) -> ReplInput {
+        let arena = Bump::new();
+        let src = src.trim();
+
+        let defs = match module_defs().parse(&arena, State::new(src.as_bytes())) {
+            Ok((_, defs, _)) if !defs.is_empty() => defs,
+            _ => return ReplInput::Expr,
+        };
+
+        let mut state = self.clone();
+        state.inputs += 1;
+
+        let input_names = mentioned_names(src);
+
+        for (index, def) in defs.defs().enumerate() {
+            if !state.add_def(def, region_src(src, defs.regions[index]), &input_names) {
+                // e.g. an `expect`, or a destructure; as an expression,
+                // these get a proper error message
+                return ReplInput::Expr;
+            }
+        }
+
+        ReplInput::Defs(state)
+    }
+
+    /// Imports the interface module at `path` into the session, bringing the names it
+    /// exposes into scope. Its private defs stay private, and its own imports are
+    /// resolved from its directory when the session is compiled.
+    pub fn load(&self, path: &Path, src: &str) -> Result<ReplState, String> {
+        let arena = Bump::new();
+
+        let (module, _) = parse_header(&arena, State::new(src.as_bytes()))
+            .map_err(|_| "I could not parse the header of this module.".to_string())?;
+
+        let header = match module {
+            Module::Interface { header } => header,
+            _ => return Err("I can only load interface modules.".into()),
+        };
+
+        let name = header.name.value.as_str();
+
+        // The loader finds `Foo.Bar` at `Foo/Bar.roc` in the source directory
+        let mut module_path: PathBuf = name.split('.').collect();
+        module_path.set_extension("roc");
+
+        let src_dir = match path.ancestors().nth(name.split('.').count()) {
+            Some(src_dir) if path.ends_with(&module_path) => src_dir.to_path_buf(),
+            _ => {
+                return Err(format!(
+                    "Its header names it {}, so I expected it to be at {}.",
+                    name,
+                    module_path.display()
+                ))
+            }
+        };
+
+        if matches!(&self.src_dir, Some(dir) if *dir != src_dir) {
+            return Err(format!(
+                "I can only load modules from {}, where the modules loaded earlier are.",
+                self.src_dir.as_ref().unwrap().display()
+            ));
+        }
+
+        let exposes: Vec<String> = header
+            .exposes
+            .iter()
+            .map(|exposed| exposed_name(&exposed.value))
+            .collect();
+
+        let mut state = self.clone();
+        state.inputs += 1;
+        state.src_dir = Some(src_dir);
+
+        // loading a module again picks up what it exposes now
+        state.loaded_modules.retain(|module| module.name != name);
+
+        state.shadow(&exposes, &exposes.iter().cloned().collect());
+        state.drop_unused_shadowed();
+
+        state.loaded_modules.push(LoadedModule {
+            name: name.to_string(),
+            exposed: exposes.clone(),
+            exposes,
+        });
+
+        Ok(state)
+    }
+
+    /// The names of everything defined in the session so far
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        let imported = self
+            .loaded_modules
+            .iter()
+            .flat_map(|module| module.exposes.iter().map(String::as_str));
+
+        self.past_defs
+            .iter()
+            .filter(|def| !def.shadowed)
+            .flat_map(|def| def.names.iter().map(String::as_str))
+            .chain(imported)
+    }
+
+    /// The modules loaded into the session, along with everything each of them exposes
+    pub fn loaded_modules(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.loaded_modules
+            .iter()
+            .map(|module| (module.name.as_str(), module.exposed.as_slice()))
+    }
+
+    /// Whether the session imports the module of this name
+    pub(crate) fn imports(&self, module_name: &str) -> bool {
+        self.loaded_modules
+            .iter()
+            .any(|module| module.name == module_name)
+    }
+
+    /// The directory to resolve imports from, if any modules were loaded
+    pub fn src_dir(&self) -> Option<&Path> {
+        self.src_dir.as_deref()
+    }
+
+    /// Whether the session has a def of this name, including the fresh names of shadowed defs
+    pub(crate) fn defines(&self, name: &str) -> bool {
+        self.past_defs
+            .iter()
+            .any(|def| def.names.iter().any(|def_name| def_name == name))
+    }
+
+    /// The source of a module containing every def so far, which evaluates `expr`.
+    pub fn module_src(&self, expr: &str) -> String {
+        let imports = if self.loaded_modules.is_empty() {
+            String::new()
+        } else {
+            let modules: Vec<String> = self
+                .loaded_modules
+                .iter()
+                .map(|module| match module.exposes.as_slice() {
+                    [] => module.name.clone(),
+                    exposes => format!("{}.{{ {} }}", module.name, exposes.join(", ")),
+                })
+                .collect();
+
+            format!("imports [{}] ", modules.join(", "))
+        };
+
+        let mut buffer = format!(
+            "app \"app\" {}provides [{}] to \"./platform\"\n\n",
+            imports, REPL_OUTPUT
+        );
+
+        for def in self.past_defs.iter() {
+            // An annotation on its own is not a valid def; it will be emitted
+            // together with its body once that's entered.
+            if def.kind != DefKind::Annotation {
+                buffer.push_str(&def.src);
+                buffer.push_str("\n\n");
+            }
+        }
+
+        buffer.push_str(REPL_OUTPUT);
+        buffer.push_str(" =\n");
+
+        for line in expr.lines() {
+            // indent the body!
+            buffer.push_str("    ");
+            buffer.push_str(line);
+            buffer.push('\n');
+        }
+
+        buffer
+    }
+
+    /// Returns `false` if this is not something we can keep in the session. `input_names` are
+    /// the names mentioned anywhere in the input the def is part of.
+    fn add_def(
+        &mut self,
+        def: Result<&TypeDef, &ValueDef>,
+        src: &str,
+        input_names: &MutSet<String>,
+    ) -> bool {
+        match def {
+            Ok(type_def) => {
+                self.add_type_def(type_def, src, input_names);
+
+                true
+            }
+            Err(value_def) => self.add_value_def(value_def, src, input_names),
+        }
+    }
+
+    fn add_type_def(&mut self, type_def: &TypeDef, src: &str, input_names: &MutSet<String>) {
+        let mut names = Vec::new();
+
+        match type_def {
+            TypeDef::Alias { header, .. } | TypeDef::Opaque { header, .. } => {
+                names.push(header_name(header));
+            }
+            TypeDef::Ability {
+                header, members, ..
+            } => {
+                names.push(header_name(header));
+                names.extend(members.iter().map(|member| spaced_name(&member.name.value)));
+            }
+        }
+
+        self.push(DefKind::Type, names, src.to_string(), input_names);
+    }
+
+    fn add_value_def(
+        &mut self,
+        value_def: &ValueDef,
+        src: &str,
+        input_names: &MutSet<String>,
+    ) -> bool {
+        let (kind, name, src) = match value_def {
+            ValueDef::Annotation(pattern, _) => match identifier(&pattern.value) {
+                Some(name) => (DefKind::Annotation, name, src.to_string()),
+                None => return false,
+            },
+            ValueDef::Body(pattern, _) => match identifier(&pattern.value) {
+                Some(name) => {
+                    // `x : Str` on one line and `x = "hi"` on the next belong together
+                    let annotation = self.past_defs.iter().rposition(|def| {
+                        def.kind == DefKind::Annotation && def.names.contains(&name)
+                    });
+
+                    let src = match annotation {
+                        Some(index) => format!("{}\n{}", self.past_defs[index].src, src),
+                        None => src.to_string(),
+                    };
+
+                    (DefKind::Value, name, src)
+                }
+                None => return false,
+            },
+            ValueDef::AnnotatedBody { body_pattern, .. } => match identifier(&body_pattern.value) {
+                Some(name) => (DefKind::Value, name, src.to_string()),
+                None => return false,
+            },
+            ValueDef::Expect { .. } | ValueDef::ExpectFx { .. } | ValueDef::Malformed(_) => {
+                return false
+            }
+        };
+
+        self.push(kind, vec![name], src, input_names);
+
+        true
+    }
+
+    /// Adds a def, which shadows any earlier defs of the same names. A shadowed def that
+    /// defs of earlier inputs use is renamed, along with their references to it, so they
+    /// keep their meaning. Otherwise it is dropped.
+    fn push(
+        &mut self,
+        kind: DefKind,
+        names: Vec<String>,
+        src: String,
+        input_names: &MutSet<String>,
+    ) {
+        self.shadow(&names, input_names);
+
+        self.past_defs.push(PastDef {
+            kind,
+            names,
+            input: self.inputs,
+            shadowed: false,
+            mentions: mentioned_names(&src),
+            src,
+        });
+
+        self.drop_unused_shadowed();
+    }
+
+    /// Makes way for new defs or imports of `names`. Earlier defs of these names are renamed
+    /// or dropped, and names imported from loaded modules are qualified in the defs of
+    /// earlier inputs instead. The fresh names avoid `input_names`, which the input that
+    /// brings in `names` mentions.
+    fn shadow(&mut self, names: &[String], input_names: &MutSet<String>) {
+        for module in self.loaded_modules.iter_mut() {
+            let (shadowed, kept): (Vec<String>, Vec<String>) = module
+                .exposes
+                .drain(..)
+                .partition(|name| names.contains(name));
+
+            module.exposes = kept;
+
+            for name in shadowed {
+                let qualified = format!("{}.{}", module.name, name);
+
+                for def in self.past_defs.iter_mut() {
+                    if def.input != self.inputs {
+                        def.rename(&name, &qualified);
+                    }
+                }
+            }
+        }
+
+        let mut index = 0;
+
+        while index < self.past_defs.len() {
+            let def = &self.past_defs[index];
+
+            if !def.names.iter().any(|name| names.contains(name)) {
+                index += 1;
+            } else if def.kind != DefKind::Annotation
+                && def.input != self.inputs
+                && self.users(index).any(|user| user.input != self.inputs)
+            {
+                self.rename_shadowed(index, input_names);
+                index += 1;
+            } else {
+                // an annotation is taken along by its body, which is this def
+                self.past_defs.remove(index);
+            }
+        }
+    }
+
+    /// Whether nothing in the session, nor the input being added, mentions `name`. Roc doesn't
+    /// allow shadowing, so that includes the names of arguments and local defs.
+    fn is_unused(&self, name: &str, input_names: &MutSet<String>) -> bool {
+        !input_names.contains(name)
+            && !self.defines(name)
+            && !self.past_defs.iter().any(|def| def.mentions.contains(name))
+            && !self
+                .loaded_modules
+                .iter()
+                .any(|module| module.exposes.iter().any(|exposed| exposed == name))
+    }
+
+    /// Shadowed defs are only kept for as long as something uses them
+    fn drop_unused_shadowed(&mut self) {
+        while let Some(index) = (0..self.past_defs.len())
+            .find(|&index| self.past_defs[index].shadowed && self.users(index).next().is_none())
+        {
+            self.past_defs.remove(index);
+        }
+    }
+
+    /// The other defs that mention a name of the def at `index`
+    fn users(&self, index: usize) -> impl Iterator<Item = &PastDef> {
+        let names = &self.past_defs[index].names;
+
+        self.past_defs
+            .iter()
+            .enumerate()
+            .filter(move |(other, def)| {
+                *other != index && names.iter().any(|name| def.mentions.contains(name))
+            })
+            .map(|(_, def)| def)
+    }
+
+    /// Gives the def at `index` fresh names, and updates the mentions of its names
+    /// in the defs of earlier inputs.
+    fn rename_shadowed(&mut self, index: usize, input_names: &MutSet<String>) {
+        for name in self.past_defs[index].names.clone() {
+            let fresh = (1..)
+                .map(|n| format!("{}Shadowed{}", name, n))
+                .find(|fresh| self.is_unused(fresh, input_names))
+                .unwrap();
+
+            let inputs = self.inputs;
+
+            for (other, def) in self.past_defs.iter_mut().enumerate() {
+                if other == index || def.input != inputs {
+                    def.rename(&name, &fresh);
+                }
+            }
+
+            for def_name in self.past_defs[index].names.iter_mut() {
+                if *def_name == name {
+                    *def_name = fresh.clone();
+                }
+            }
+        }
+
+        self.past_defs[index].shadowed = true;
+    }
+}
+
+/// Whether `src` is made of top-level defs that a session would keep,
+/// as opposed to an expression to evaluate.
+pub fn is_defs(src: &str) -> bool {
+    matches!(ReplState::new().parse_input(src), ReplInput::Defs(_))
+}
+
+fn region_src(src: &str, region: Region) -> &str {
+    &src[region.start().offset as usize..region.end().offset as usize]
+}
+
+fn header_name(header: &TypeHeader) -> String {
+    header.name.value.to_string()
+}
+
+fn exposed_name(spaced: &Spaced<ExposedName>) -> String {
+    match spaced {
+        Spaced::Item(name) => name.as_str().to_string(),
+        Spaced::SpaceBefore(inner, _) | Spaced::SpaceAfter(inner, _) => exposed_name(inner),
+    }
+}
+
+fn spaced_name(spaced: &Spaced<&str>) -> String {
+    match spaced {
+        Spaced::Item(name) => name.to_string(),
+        Spaced::SpaceBefore(inner, _) | Spaced::SpaceAfter(inner, _) => spaced_name(inner),
+    }
+}
+
+/// Top-level destructures aren't supported by the compiler yet,
+/// so only plain identifiers can be added to the session.
+fn identifier(pattern: &Pattern) -> Option<String> {
+    match pattern {
+        Pattern::Identifier(name) => Some(name.to_string()),
+        Pattern::SpaceBefore(inner, _) | Pattern::SpaceAfter(inner, _) => identifier(inner),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SHOUT: &str = "\
+interface Shout
+    exposes [shout]
+    imports [Greeting.{ greet }]
+
+shout = \\name -> Str.concat (greet name) exclamation
+
+exclamation = \"!!\"
+";
+
+    #[test]
+    fn load_imports_exposed_names_only() {
+        let state = ReplState::new()
+            .load(Path::new("/fixtures/Shout.roc"), SHOUT)
+            .unwrap();
+
+        assert_eq!(state.src_dir(), Some(Path::new("/fixtures")));
+        assert_eq!(state.names().collect::<Vec<_>>(), vec!["shout"]);
+
+        let src = state.module_src("shout \"Roc\"");
+
+        assert!(src.starts_with("app \"app\" imports [Shout.{ shout }] provides"));
+        assert!(!src.contains("exclamation"));
+    }
+
+    #[test]
+    fn load_checks_the_module_name() {
+        assert!(ReplState::new()
+            .load(Path::new("/fixtures/Yell.roc"), SHOUT)
+            .is_err());
+    }
+
+    #[test]
+    fn defs_shadow_imported_names() {
+        let state = ReplState::new()
+            .load(Path::new("/fixtures/Shout.roc"), SHOUT)
+            .unwrap();
+
+        let state = match state.parse_input("loud = shout \"Roc\"") {
+            ReplInput::Defs(state) => state,
+            ReplInput::Expr => panic!("expected defs"),
+        };
+
+        let state = match state.parse_input("shout = \\name -> name") {
+            ReplInput::Defs(state) => state,
+            ReplInput::Expr => panic!("expected defs"),
+        };
+
+        let src = state.module_src("loud");
+
+        assert!(src.starts_with("app \"app\" imports [Shout] provides"));
+        assert!(src.contains("loud = Shout.shout \"Roc\""));
+    }
+
+    #[test]
+    fn shadowed_defs_stay_while_used() {
+        let mut state = ReplState::new();
+
+        for src in ["x = 1", "y = x + 1", "x = 2"] {
+            state = match state.parse_input(src) {
+                ReplInput::Defs(state) => state,
+                ReplInput::Expr => panic!("expected defs"),
+            };
+        }
+
+        let src = state.module_src("y");
+
+        assert!(src.contains("xShadowed1 = 1"));
+        assert!(src.contains("y = xShadowed1 + 1"));
+        assert!(src.contains("x = 2"));
+
+        let state = match state.parse_input("y = 3") {
+            ReplInput::Defs(state) => state,
+            ReplInput::Expr => panic!("expected defs"),
+        };
+
+        assert!(!state.module_src("y").contains("xShadowed1"));
+    }
+
+    #[test]
+    fn fresh_names_avoid_every_name_in_the_input() {
+        let mut state = ReplState::new();
+
+        for src in ["x = 1", "y = x + 1", "x = \\xShadowed1 -> xShadowed1"] {
+            state = match state.parse_input(src) {
+                ReplInput::Defs(state) => state,
+                ReplInput::Expr => panic!("expected defs"),
+            };
+        }
+
+        let src = state.module_src("y");
+
+        assert!(src.contains("xShadowed2 = 1"));
+        assert!(src.contains("y = xShadowed2 + 1"));
+        assert!(src.contains("x = \\xShadowed1 -> xShadowed1"));
+    }
+
+    #[test]
+    fn renaming_covers_list_rest_bindings() {
+        let mut state = ReplState::new();
+
+        for src in [
+            "x = 1",
+            "f = \\l ->\n    when l is\n        [..x] -> List.len x",
+            "x = 2",
+        ] {
+            state = match state.parse_input(src) {
+                ReplInput::Defs(state) => state,
+                ReplInput::Expr => panic!("expected defs"),
+            };
+        }
+
+        assert!(state
+            .module_src("f")
+            .contains("[..xShadowed1] -> List.len xShadowed1"));
+    }
+}
@@ -0,0 +1,106 @@
+//! Timing the benchmarks of `roc bench`.
+//!
+//! The loader generates a 0-arity thunk for each benchmark that runs it once, exposed from the
+//! dylib just like a top-level expect. A benchmark first runs for a warm-up period, in batches
+//! that double in size, which also estimates how long one run takes. That estimate sets how many
+//! runs each sample times, so that all the samples together take about as long as asked for.
+
+use roc_gen_llvm::run_roc::RocCallResult;
+use roc_module::symbol::Symbol;
+use roc_region::all::Region;
+use std::time::{Duration, Instant};
+
+use crate::run::ExpectMemory;
+
+/// A benchmark as exposed from the dylib.
+#[derive(Debug, Clone, Copy)]
+pub struct ToplevelBench<'a> {
+    /// The name of the thunk that runs the benchmark once
+    pub name: &'a str,
+    /// The benchmark function
+    pub symbol: Symbol,
+    pub region: Region,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BenchConfig {
+    /// How long to run a benchmark before timing it
+    pub warm_up: Duration,
+    /// About how long to spend on the samples of a benchmark
+    pub measure: Duration,
+    /// How many times to time a batch of runs
+    pub samples: usize,
+}
+
+/// The timings of one benchmark.
+#[derive(Debug, Clone)]
+pub struct BenchSamples {
+    /// How many runs each sample timed
+    pub iterations_per_sample: u64,
+    /// The nanoseconds that one run took on average, in each sample
+    pub nanos_per_iteration: Vec<f64>,
+}
+
+type BenchThunk = unsafe extern "C" fn(*mut RocCallResult<()>);
+
+/// Warm up and then time the benchmark. Returns the message of the crash if the benchmark
+/// crashed.
+pub fn run_bench(
+    lib: &libloading::Library,
+    bench: &ToplevelBench,
+    config: &BenchConfig,
+) -> Result<BenchSamples, String> {
+    let thunk: libloading::Symbol<BenchThunk> = unsafe {
+        lib.get(bench.name.as_bytes())
+            .unwrap_or_else(|_| panic!("the dylib exposes the benchmark `{}`", bench.name))
+    };
+
+    // a benchmark runs like an expect, which could report to the shared buffer
+    let mut buffer = vec![0; ExpectMemory::BUFFER_SIZE];
+    let mut memory = ExpectMemory::from_slice(&mut buffer);
+    memory.set_shared_buffer(lib);
+
+    let run_batch = |iterations: u64| -> Result<Duration, String> {
+        let start = Instant::now();
+
+        for _ in 0..iterations {
+            let mut result = RocCallResult::default();
+            unsafe { thunk(&mut result) };
+            Result::<(), String>::from(result)?;
+        }
+
+        Ok(start.elapsed())
+    };
+
+    let warm_up_start = Instant::now();
+    let mut batch = 1;
+    let mut warm_up_iterations = 0;
+
+    loop {
+        run_batch(batch)?;
+        warm_up_iterations += batch;
+
+        if warm_up_start.elapsed() >= config.warm_up {
+            break;
+        }
+
+        batch *= 2;
+    }
+
+    let estimate = warm_up_start.elapsed().as_nanos() as f64 / warm_up_iterations as f64;
+    let per_sample = config.measure.as_nanos() as f64 / config.samples.max(1) as f64;
+    let iterations_per_sample = (per_sample / estimate.max(1.0)).max(1.0) as u64;
+
+    let mut nanos_per_iteration = Vec::with_capacity(config.samples);
+
+    for _ in 0..config.samples {
+        let elapsed = run_batch(iterations_per_sample)?;
+
+        nanos_per_iteration.push(elapsed.as_nanos() as f64 / iterations_per_sample as f64);
+    }
+
+    Ok(BenchSamples {
+        iterations_per_sample,
+        nanos_per_iteration,
+    })
+}
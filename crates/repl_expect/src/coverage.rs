@@ -0,0 +1,134 @@
+//! Counting the runs of functions and branches for `roc test --coverage`.
+//!
+//! The code generated for each function body and branch calls `roc_coverage_hit` in the builtins
+//! with its module and region, and the builtins pass that on to the handler here. The counters
+//! live in memory shared with the workers that run the expects, so the runs in those count too.
+
+use roc_can::coverage::CoveragePoint;
+use roc_collections::{MutMap, VecMap};
+use roc_module::symbol::ModuleId;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+type CoverageHandler = extern "C" fn(u32, u64);
+
+struct Counters {
+    /// The index of the counter of each (module, region) that is counted
+    indices: MutMap<(u32, u64), usize>,
+    counters: *const AtomicU64,
+}
+
+thread_local! {
+    static COUNTERS: RefCell<Option<Counters>> = RefCell::new(None);
+}
+
+extern "C" fn count_hit(module_id: u32, region: u64) {
+    COUNTERS.with(|counters| {
+        if let Some(Counters { indices, counters }) = &*counters.borrow() {
+            // code that the coverage points do not list, like the body of a top-level value,
+            // is not counted
+            if let Some(index) = indices.get(&(module_id, region)) {
+                unsafe { (*counters.add(*index)).fetch_add(1, Ordering::Relaxed) };
+            }
+        }
+    })
+}
+
+/// How often each coverage point ran, as counted since [`install`].
+pub struct CoverageCounts {
+    ptr: *mut libc::c_void,
+    length: usize,
+    /// Each module with the index of its first counter and its number of points
+    modules: Vec<(ModuleId, usize, usize)>,
+}
+
+impl CoverageCounts {
+    /// The counts of each module's coverage points, in the order of its points.
+    pub fn counts(&self) -> VecMap<ModuleId, Vec<u64>> {
+        let counters = self.ptr as *const AtomicU64;
+
+        self.modules
+            .iter()
+            .map(|(module_id, start, len)| {
+                let counts = (*start..start + len)
+                    .map(|index| unsafe { (*counters.add(index)).load(Ordering::Relaxed) })
+                    .collect();
+
+                (*module_id, counts)
+            })
+            .collect()
+    }
+}
+
+impl Drop for CoverageCounts {
+    fn drop(&mut self) {
+        COUNTERS.with(|counters| counters.borrow_mut().take());
+
+        unsafe { libc::munmap(self.ptr, self.length) };
+    }
+}
+
+/// Make the builtins in `lib` count the runs of these coverage points.
+pub fn install(
+    lib: &libloading::Library,
+    coverage: &VecMap<ModuleId, Vec<CoveragePoint>>,
+) -> std::io::Result<CoverageCounts> {
+    type SetCoverageHandler = unsafe extern "C" fn(CoverageHandler) -> usize;
+
+    let mut indices = MutMap::default();
+    let mut modules = Vec::with_capacity(coverage.len());
+    let mut total = 0;
+
+    for (module_id, points) in coverage.iter() {
+        // the generated code reports the module id as its raw u32
+        let raw_module_id = module_id.to_u32();
+
+        modules.push((*module_id, total, points.len()));
+
+        for point in points {
+            let region = point.region();
+            let key = (region.start().offset as u64) << 32 | region.end().offset as u64;
+
+            indices.insert((raw_module_id, key), total);
+            total += 1;
+        }
+    }
+
+    // workers are forked from this process, so they write to these same counters
+    let length = total.max(1) * std::mem::size_of::<AtomicU64>();
+    let ptr = unsafe {
+        libc::mmap(
+            std::ptr::null_mut(),
+            length,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED | libc::MAP_ANONYMOUS,
+            -1,
+            0,
+        )
+    };
+
+    if ptr == libc::MAP_FAILED {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    COUNTERS.with(|counters| {
+        *counters.borrow_mut() = Some(Counters {
+            indices,
+            counters: ptr as *const AtomicU64,
+        })
+    });
+
+    unsafe {
+        let set_coverage_handler: libloading::Symbol<SetCoverageHandler> = lib
+            .get(b"set_coverage_handler")
+            .expect("the builtins of a test build define set_coverage_handler");
+
+        set_coverage_handler(count_hit);
+    }
+
+    Ok(CoverageCounts {
+        ptr,
+        length,
+        modules,
+    })
+}
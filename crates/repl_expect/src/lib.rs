@@ -19,6 +19,12 @@ use {
 #[cfg(not(windows))]
 mod app;
 #[cfg(not(windows))]
+pub mod bench;
+#[cfg(not(windows))]
+pub mod coverage;
+#[cfg(not(windows))]
+mod mock;
+#[cfg(not(windows))]
 pub mod run;
 
 #[cfg(not(windows))]
@@ -120,6 +126,7 @@ mod test {
             render: RenderTarget::ColorTerminal,
             threading: Threading::Single,
             exec_mode: ExecutionMode::Test,
+            ..Default::default()
         };
         let loaded = roc_load::load_and_monomorphize_from_str(
             arena,
@@ -160,7 +167,7 @@ mod test {
         unsafe { set_shared_buffer((shared_buffer.as_mut_ptr(), BUFFER_SIZE), &mut result) };
 
         let mut writer = Vec::with_capacity(1024);
-        let (_failed, _passed) = crate::run::run_expects_with_memory(
+        let _results = crate::run::run_expects_with_memory(
             &mut writer,
             RenderTarget::ColorTerminal,
             arena,
@@ -169,6 +176,7 @@ mod test {
             &lib,
             &mut expectations,
             expects,
+            None,
             &mut memory,
         )
         .unwrap();
@@ -934,4 +942,56 @@ mod test {
             ),
         );
     }
+
+    #[test]
+    fn dbg_prints_value_and_location() {
+        run_expect_test(
+            indoc!(
+                r#"
+                interface Test exposes [] imports []
+
+                expect 1 == 2
+
+                expect
+                    x = dbg 1 + 1
+
+                    x == 2
+                "#
+            ),
+            indoc!(
+                r#"
+                This expectation failed:
+
+                3│  expect 1 == 2
+                    ^^^^^^^^^^^^^
+                [Test.roc:6:13] 2
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn crash_reports_location_and_message() {
+        run_expect_test(
+            indoc!(
+                r#"
+                interface Test exposes [] imports []
+
+                expect crash "oh no"
+                "#
+            ),
+            indoc!(
+                r#"
+                This expectation crashed at this crash:
+
+                3│  expect crash "oh no"
+                    ^^^^^^^^^^^^^^^^^^^^
+
+                The crash reported this message:
+
+                    oh no
+                "#
+            ),
+        );
+    }
 }
@@ -0,0 +1,140 @@
+//! The host that `expect-fx` run against with `roc test --mock-effects`.
+//!
+//! Hosted functions canonicalized with a script ask `roc_mock_next_call` in the builtins which
+//! scripted call they are, and the builtins ask the handler here. It answers with the key of
+//! the next call of the script, if that is a call of the same effect. When that call expects
+//! other arguments, the hosted function says so through `roc_mock_unexpected_arguments`.
+
+use roc_can::mock_effects::MockedCall;
+use roc_std::RocStr;
+use std::cell::RefCell;
+
+/// Returned for a call the script did not expect; the builtins panic on it.
+const UNEXPECTED_CALL: u64 = u64::MAX;
+
+type MockEffectHandler = extern "C" fn(&RocStr) -> u64;
+type MockArgumentsHandler = extern "C" fn(&RocStr, u64);
+
+#[derive(Default)]
+struct MockHost {
+    /// The key of each call the running expect should make, and the call itself
+    script: Vec<(u64, MockedCall)>,
+    next: usize,
+    unexpected: Option<UnexpectedEffect>,
+}
+
+thread_local! {
+    static MOCK_HOST: RefCell<MockHost> = RefCell::new(MockHost::default());
+}
+
+#[derive(Debug)]
+pub(crate) enum UnexpectedEffect {
+    Call {
+        called: String,
+        /// The effect the script expected instead, or `None` if the script was done
+        expected: Option<String>,
+    },
+    /// The effect the script expected was called, but not with the arguments it expected
+    Arguments { call: MockedCall },
+}
+
+#[derive(Debug)]
+pub(crate) struct MockOutcome {
+    pub unexpected: Option<UnexpectedEffect>,
+    /// The effects of the scripted calls that were never made
+    pub missing: Vec<String>,
+}
+
+impl MockOutcome {
+    pub fn is_ok(&self) -> bool {
+        self.unexpected.is_none() && self.missing.is_empty()
+    }
+}
+
+extern "C" fn next_mocked_call(effect: &RocStr) -> u64 {
+    MOCK_HOST.with(|host| {
+        let mut host = host.borrow_mut();
+        let host = &mut *host;
+
+        match host.script.get(host.next) {
+            Some((key, expected)) if expected.effect == effect.as_str() => {
+                host.next += 1;
+
+                *key
+            }
+            expected => {
+                host.unexpected = Some(UnexpectedEffect::Call {
+                    called: effect.as_str().to_string(),
+                    expected: expected.map(|(_, expected)| expected.effect.clone()),
+                });
+
+                UNEXPECTED_CALL
+            }
+        }
+    })
+}
+
+extern "C" fn unexpected_arguments(_effect: &RocStr, key: u64) {
+    MOCK_HOST.with(|host| {
+        let mut host = host.borrow_mut();
+
+        // the hosted function only knows the key of the call it was answered with
+        let call = host
+            .script
+            .iter()
+            .find(|(scripted, _)| *scripted == key)
+            .map(|(_, call)| call.clone())
+            .expect("the arguments were compared with a call of the running script");
+
+        host.unexpected = Some(UnexpectedEffect::Arguments { call });
+    })
+}
+
+/// Make the builtins in `lib` call into this host for mocked effects.
+pub(crate) fn install(lib: &libloading::Library) {
+    type SetMockEffectHandler =
+        unsafe extern "C" fn(MockEffectHandler, MockArgumentsHandler) -> usize;
+
+    unsafe {
+        let set_mock_effect_handler: libloading::Symbol<SetMockEffectHandler> = lib
+            .get(b"set_mock_effect_handler")
+            .expect("the builtins of a test build define set_mock_effect_handler");
+
+        set_mock_effect_handler(next_mocked_call, unexpected_arguments);
+    }
+}
+
+/// Expect these calls, in order, until [`finish`].
+pub(crate) fn start(script: Vec<(u64, MockedCall)>) {
+    MOCK_HOST.with(|host| {
+        *host.borrow_mut() = MockHost {
+            script,
+            ..MockHost::default()
+        }
+    })
+}
+
+pub(crate) fn finish() -> MockOutcome {
+    MOCK_HOST.with(|host| {
+        let MockHost {
+            script,
+            next,
+            unexpected,
+        } = host.take();
+
+        let missing = match unexpected {
+            // the expect stopped at the unexpected call, so it never got to the rest
+            Some(_) => Vec::new(),
+            None => script
+                .into_iter()
+                .skip(next)
+                .map(|(_, call)| call.effect)
+                .collect(),
+        };
+
+        MockOutcome {
+            unexpected,
+            missing,
+        }
+    })
+}
@@ -1,9 +1,20 @@
-use std::{os::unix::process::parent_id, sync::Arc};
+use std::{
+    collections::VecDeque,
+    ffi::CString,
+    fs::File,
+    io::Read,
+    os::unix::io::{AsRawFd, FromRawFd},
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use bumpalo::collections::Vec as BumpVec;
 use bumpalo::Bump;
 use inkwell::context::Context;
 use roc_build::link::llvm_module_to_dylib;
+use roc_can::mock_effects::MockEffects;
 use roc_collections::{MutSet, VecMap};
 use roc_gen_llvm::{
     llvm::{build::LlvmBackendMode, externs::add_default_roc_externs},
@@ -17,71 +28,198 @@ use roc_mono::{ir::OptLevel, layout::Layout};
 use roc_region::all::Region;
 use roc_reporting::{error::expect::Renderer, report::RenderTarget};
 use roc_target::TargetInfo;
+use roc_types::subs::Variable;
 use target_lexicon::Triple;
 
+use crate::bench::ToplevelBench;
+use crate::mock::UnexpectedEffect;
+
 pub(crate) struct ExpectMemory<'a> {
     ptr: *mut u8,
     length: usize,
-    shm_name: Option<std::ffi::CString>,
     _marker: std::marker::PhantomData<&'a ()>,
 }
 
 impl<'a> ExpectMemory<'a> {
-    const SHM_SIZE: usize = 1024;
+    pub(crate) const BUFFER_SIZE: usize = 1024;
 
-    #[cfg(test)]
     pub(crate) fn from_slice(slice: &mut [u8]) -> Self {
         Self {
             ptr: slice.as_mut_ptr(),
             length: slice.len(),
-            shm_name: None,
             _marker: std::marker::PhantomData,
         }
     }
 
-    fn create_or_reuse_mmap(shm_name: &str) -> Self {
-        let cstring = std::ffi::CString::new(shm_name).unwrap();
-        Self::mmap_help(cstring, libc::O_RDWR | libc::O_CREAT)
+    pub(crate) fn set_shared_buffer(&mut self, lib: &libloading::Library) {
+        let set_shared_buffer = run_roc_dylib!(lib, "set_shared_buffer", (*mut u8, usize), ());
+        let mut result = RocCallResult::default();
+        unsafe { set_shared_buffer((self.ptr, self.length), &mut result) };
     }
+}
 
-    fn reuse_mmap(&mut self) -> Option<Self> {
-        let shm_name = self.shm_name.as_ref()?.clone();
-        Some(Self::mmap_help(shm_name, libc::O_RDWR))
+/// The outcome of running one top-level expect.
+#[derive(Debug)]
+pub struct ExpectResult<'a> {
+    pub expect: ToplevelExpect<'a>,
+    pub passed: bool,
+    pub duration: Duration,
+    /// Everything the expect reported: its failures along with the values they looked up,
+    /// the values of `dbg`s, and crashes. This is also written to the writer as it is rendered.
+    pub output: String,
+}
+
+/// How [`run_expects`] runs the expects in worker processes.
+#[derive(Debug, Clone, Copy)]
+pub struct WorkerConfig {
+    /// Stop an expect that runs for longer than this, and report it as failed.
+    pub timeout: Option<Duration>,
+    /// How many modules can have their expects running at the same time.
+    pub jobs: usize,
+}
+
+/// Run all expects, writing what they report to `writer`. Returns the result of every expect,
+/// in the order they were given.
+///
+/// Every expect runs in a worker process of its own, so that an expect that crashes or times out
+/// fails without taking the rest of the run with it. The expects of a module run one after the
+/// other, and with more than one job, the expects of several modules run at the same time. What
+/// they report is still written in the order the expects were given.
+#[allow(clippy::too_many_arguments)]
+pub fn run_expects<'a, 'b, W: std::io::Write>(
+    writer: &mut W,
+    render_target: RenderTarget,
+    arena: &'a Bump,
+    interns: &'a Interns,
+    layout_interner: &Arc<GlobalInterner<'a, Layout<'a>>>,
+    lib: &libloading::Library,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    expects: ExpectFunctions<'b>,
+    mock_effects: Option<&MockEffects>,
+    config: WorkerConfig,
+) -> std::io::Result<Vec<ExpectResult<'b>>> {
+    let all: Vec<(ToplevelExpect<'b>, bool)> = expects
+        .fx
+        .iter()
+        .map(|expect| (*expect, true))
+        .chain(expects.pure.iter().map(|expect| (*expect, false)))
+        .collect();
+
+    // the indices of the expects of each module, in the order they run
+    let mut modules: Vec<(ModuleId, VecDeque<usize>)> = Vec::new();
+
+    for (index, (expect, _)) in all.iter().enumerate() {
+        let module_id = expect.symbol.module_id();
+
+        match modules.iter_mut().find(|(id, _)| *id == module_id) {
+            Some((_, queue)) => queue.push_back(index),
+            None => modules.push((module_id, VecDeque::from([index]))),
+        }
     }
 
-    fn mmap_help(cstring: std::ffi::CString, shm_flags: i32) -> Self {
-        let ptr = unsafe {
-            let shared_fd = libc::shm_open(cstring.as_ptr().cast(), shm_flags, 0o666);
+    let mut modules = modules.into_iter().map(|(_, queue)| queue);
+
+    let mut running: Vec<(Worker, VecDeque<usize>)> = Vec::new();
+    let mut finished: Vec<Option<ExpectResult<'b>>> = all.iter().map(|_| None).collect();
+    let mut written = 0;
+
+    loop {
+        while running.len() < config.jobs.max(1) {
+            let mut queue = match modules.next() {
+                Some(queue) => queue,
+                None => break,
+            };
+
+            let index = queue.pop_front().unwrap();
+            let (expect, is_fx) = all[index];
+
+            let worker = spawn_worker(
+                render_target,
+                arena,
+                interns,
+                layout_interner,
+                lib,
+                expectations,
+                mock_effects,
+                index,
+                expect,
+                is_fx,
+            )?;
+
+            running.push((worker, queue));
+        }
 
-            libc::ftruncate(shared_fd, Self::SHM_SIZE as _);
+        if running.is_empty() {
+            break;
+        }
 
-            libc::mmap(
-                std::ptr::null_mut(),
-                Self::SHM_SIZE,
-                libc::PROT_WRITE | libc::PROT_READ,
-                libc::MAP_SHARED,
-                shared_fd,
-                0,
-            )
+        let (slot, outcome) = wait_for_worker(&mut running, config.timeout)?;
+        let worker = &running[slot].0;
+
+        let (expect, _) = all[worker.index];
+        let duration = worker.start.elapsed();
+
+        let (passed, output) = match outcome {
+            WorkerOutcome::Finished { passed, output } => (passed, output),
+            outcome => {
+                let mut output = Vec::new();
+
+                render_worker_failure(
+                    &mut output,
+                    render_target,
+                    arena,
+                    interns,
+                    expectations,
+                    expect,
+                    outcome,
+                )?;
+
+                (false, output)
+            }
         };
 
-        Self {
-            ptr: ptr.cast(),
-            length: Self::SHM_SIZE,
-            shm_name: Some(cstring),
-            _marker: std::marker::PhantomData,
+        finished[worker.index] = Some(ExpectResult {
+            expect,
+            passed,
+            duration,
+            output: String::from_utf8_lossy(&output).into_owned(),
+        });
+
+        // the next expect of this module takes the place of the one that finished
+        match running[slot].1.pop_front() {
+            Some(index) => {
+                let (expect, is_fx) = all[index];
+
+                running[slot].0 = spawn_worker(
+                    render_target,
+                    arena,
+                    interns,
+                    layout_interner,
+                    lib,
+                    expectations,
+                    mock_effects,
+                    index,
+                    expect,
+                    is_fx,
+                )?;
+            }
+            None => {
+                running.swap_remove(slot);
+            }
         }
-    }
 
-    fn set_shared_buffer(&mut self, lib: &libloading::Library) {
-        let set_shared_buffer = run_roc_dylib!(lib, "set_shared_buffer", (*mut u8, usize), ());
-        let mut result = RocCallResult::default();
-        unsafe { set_shared_buffer((self.ptr, self.length), &mut result) };
+        while let Some(Some(result)) = finished.get(written) {
+            writer.write_all(result.output.as_bytes())?;
+            written += 1;
+        }
     }
+
+    Ok(finished.into_iter().flatten().collect())
 }
 
+/// Run all expects in this process, one after the other.
 #[allow(clippy::too_many_arguments)]
-pub fn run_expects<'a, W: std::io::Write>(
+pub(crate) fn run_expects_with_memory<'a, 'b, W: std::io::Write>(
     writer: &mut W,
     render_target: RenderTarget,
     arena: &'a Bump,
@@ -89,26 +227,49 @@ pub fn run_expects<'a, W: std::io::Write>(
     layout_interner: &Arc<GlobalInterner<'a, Layout<'a>>>,
     lib: &libloading::Library,
     expectations: &mut VecMap<ModuleId, Expectations>,
-    expects: ExpectFunctions<'_>,
-) -> std::io::Result<(usize, usize)> {
-    let shm_name = format!("/roc_expect_buffer_{}", std::process::id());
-    let mut memory = ExpectMemory::create_or_reuse_mmap(&shm_name);
+    expects: ExpectFunctions<'b>,
+    mock_effects: Option<&MockEffects>,
+    memory: &mut ExpectMemory,
+) -> std::io::Result<Vec<ExpectResult<'b>>> {
+    let mut results = Vec::with_capacity(expects.fx.len() + expects.pure.len());
 
-    run_expects_with_memory(
-        writer,
-        render_target,
-        arena,
-        interns,
-        layout_interner,
-        lib,
-        expectations,
-        expects,
-        &mut memory,
-    )
+    memory.set_shared_buffer(lib);
+
+    if mock_effects.is_some() {
+        crate::mock::install(lib);
+    }
+
+    let fx = expects.fx.iter().map(|expect| (*expect, true));
+    let pure = expects.pure.iter().map(|expect| (*expect, false));
+
+    for (expect, is_fx) in fx.chain(pure) {
+        let start = Instant::now();
+        let mut output = Vec::new();
+
+        let passed = run_expect(
+            &mut output,
+            render_target,
+            arena,
+            interns,
+            layout_interner,
+            lib,
+            expectations,
+            memory,
+            expect,
+            is_fx,
+            mock_effects,
+        )?;
+
+        results.push(expect_result(writer, expect, passed, start, output)?);
+    }
+
+    Ok(results)
 }
 
+/// Run one expect in this process. The shared buffer must already be set, and with mock effects,
+/// so must the mock host.
 #[allow(clippy::too_many_arguments)]
-pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
+fn run_expect<'a, W: std::io::Write>(
     writer: &mut W,
     render_target: RenderTarget,
     arena: &'a Bump,
@@ -116,14 +277,13 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
     layout_interner: &Arc<GlobalInterner<'a, Layout<'a>>>,
     lib: &libloading::Library,
     expectations: &mut VecMap<ModuleId, Expectations>,
-    expects: ExpectFunctions<'_>,
     memory: &mut ExpectMemory,
-) -> std::io::Result<(usize, usize)> {
-    let mut failed = 0;
-    let mut passed = 0;
-
-    for expect in expects.fx {
-        let result = run_expect_fx(
+    expect: ToplevelExpect<'_>,
+    is_fx: bool,
+    mock_effects: Option<&MockEffects>,
+) -> std::io::Result<bool> {
+    match mock_effects {
+        Some(mock_effects) if is_fx => run_expect_mocked(
             writer,
             render_target,
             arena,
@@ -133,18 +293,11 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
             expectations,
             memory,
             expect,
-        )?;
-
-        match result {
-            true => passed += 1,
-            false => failed += 1,
-        }
-    }
-
-    memory.set_shared_buffer(lib);
-
-    for expect in expects.pure {
-        let result = run_expect_pure(
+            mock_effects,
+        ),
+        // without a mock host, the effects of an expect-fx run against whatever the build
+        // linked in, just like the expect itself
+        _ => run_expect_pure(
             writer,
             render_target,
             arena,
@@ -154,15 +307,299 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
             expectations,
             memory,
             expect,
-        )?;
+        ),
+    }
+}
+
+struct Worker {
+    pid: libc::pid_t,
+    /// The read end of the pipe the worker reports through
+    pipe: File,
+    /// What the worker reported so far
+    report: Vec<u8>,
+    /// The index of the expect the worker runs
+    index: usize,
+    start: Instant,
+}
 
-        match result {
-            true => passed += 1,
-            false => failed += 1,
+enum WorkerOutcome {
+    Finished {
+        passed: bool,
+        output: Vec<u8>,
+    },
+    TimedOut(Duration),
+    /// How the worker died, e.g. `was killed by SIGSEGV`
+    Crashed(String),
+}
+
+/// Fork a worker that runs this expect. It reports whether the expect passed (one byte), followed
+/// by what the expect reported, then exits.
+#[allow(clippy::too_many_arguments)]
+fn spawn_worker<'a>(
+    render_target: RenderTarget,
+    arena: &'a Bump,
+    interns: &'a Interns,
+    layout_interner: &Arc<GlobalInterner<'a, Layout<'a>>>,
+    lib: &libloading::Library,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    mock_effects: Option<&MockEffects>,
+    index: usize,
+    expect: ToplevelExpect<'_>,
+    is_fx: bool,
+) -> std::io::Result<Worker> {
+    let mut fds = [0; 2];
+
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    let [read_fd, write_fd] = fds;
+
+    match unsafe { libc::fork() } {
+        0 => {
+            // we are the worker
+            unsafe { libc::close(read_fd) };
+
+            let mut pipe = unsafe { File::from_raw_fd(write_fd) };
+
+            let run = || {
+                let mut buffer = vec![0; ExpectMemory::BUFFER_SIZE];
+                let mut memory = ExpectMemory::from_slice(&mut buffer);
+
+                memory.set_shared_buffer(lib);
+
+                if mock_effects.is_some() {
+                    crate::mock::install(lib);
+                }
+
+                let mut output = vec![0];
+
+                let passed = run_expect(
+                    &mut output,
+                    render_target,
+                    arena,
+                    interns,
+                    layout_interner,
+                    lib,
+                    expectations,
+                    &mut memory,
+                    expect,
+                    is_fx,
+                    mock_effects,
+                )?;
+
+                output[0] = passed as u8;
+
+                std::io::Write::write_all(&mut pipe, &output)
+            };
+
+            // a panic must not unwind into the code of the parent
+            let code = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(run)) {
+                Ok(Ok(())) => 0,
+                Ok(Err(_)) | Err(_) => 1,
+            };
+
+            // skip flushing the stdout buffers this process copied from the parent
+            unsafe { libc::_exit(code) }
+        }
+        -1 => {
+            let error = std::io::Error::last_os_error();
+
+            unsafe {
+                libc::close(read_fd);
+                libc::close(write_fd);
+            }
+
+            Err(error)
+        }
+        pid => {
+            unsafe { libc::close(write_fd) };
+
+            Ok(Worker {
+                pid,
+                pipe: unsafe { File::from_raw_fd(read_fd) },
+                report: Vec::new(),
+                index,
+                start: Instant::now(),
+            })
+        }
+    }
+}
+
+/// Wait until one of the workers is done, and return its position along with how it went.
+/// A worker that runs past the timeout is killed.
+fn wait_for_worker<T>(
+    workers: &mut [(Worker, T)],
+    timeout: Option<Duration>,
+) -> std::io::Result<(usize, WorkerOutcome)> {
+    loop {
+        let now = Instant::now();
+
+        if let Some(timeout) = timeout {
+            let timed_out = workers
+                .iter()
+                .position(|(worker, _)| now.duration_since(worker.start) >= timeout);
+
+            if let Some(slot) = timed_out {
+                let worker = &workers[slot].0;
+
+                unsafe { libc::kill(worker.pid, libc::SIGKILL) };
+                wait_for_exit(worker.pid)?;
+
+                return Ok((slot, WorkerOutcome::TimedOut(timeout)));
+            }
+        }
+
+        // wake up in time to stop the worker that will time out first
+        let poll_timeout = match timeout {
+            Some(timeout) => workers
+                .iter()
+                .map(|(worker, _)| (worker.start + timeout).saturating_duration_since(now))
+                .min()
+                .map_or(-1, |remaining| remaining.as_millis() as i32 + 1),
+            None => -1,
+        };
+
+        let mut fds: Vec<_> = workers
+            .iter()
+            .map(|(worker, _)| libc::pollfd {
+                fd: worker.pipe.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            })
+            .collect();
+
+        if unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as _, poll_timeout) } < 0 {
+            let error = std::io::Error::last_os_error();
+
+            if error.kind() == std::io::ErrorKind::Interrupted {
+                continue;
+            }
+
+            return Err(error);
+        }
+
+        for (slot, fd) in fds.iter().enumerate() {
+            if fd.revents == 0 {
+                continue;
+            }
+
+            let worker = &mut workers[slot].0;
+            let mut chunk = [0; 4096];
+            let read = worker.pipe.read(&mut chunk)?;
+
+            if read > 0 {
+                worker.report.extend_from_slice(&chunk[..read]);
+                continue;
+            }
+
+            // the pipe closed, so the worker is exiting
+            let status = wait_for_exit(worker.pid)?;
+            let report = std::mem::take(&mut worker.report);
+
+            return Ok((slot, worker_outcome(report, status)));
+        }
+    }
+}
+
+fn wait_for_exit(pid: libc::pid_t) -> std::io::Result<libc::c_int> {
+    let mut status = 0;
+
+    loop {
+        if unsafe { libc::waitpid(pid, &mut status, 0) } >= 0 {
+            return Ok(status);
+        }
+
+        let error = std::io::Error::last_os_error();
+
+        if error.kind() != std::io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
+}
+
+fn worker_outcome(report: Vec<u8>, status: libc::c_int) -> WorkerOutcome {
+    let exited = libc::WIFEXITED(status);
+
+    match report.split_first() {
+        Some((passed, output)) if exited && libc::WEXITSTATUS(status) == 0 => {
+            WorkerOutcome::Finished {
+                passed: *passed != 0,
+                output: output.to_vec(),
+            }
+        }
+        _ if libc::WIFSIGNALED(status) => WorkerOutcome::Crashed(format!(
+            "was killed by {}",
+            signal_name(libc::WTERMSIG(status))
+        )),
+        _ => WorkerOutcome::Crashed(format!("exited with code {}", libc::WEXITSTATUS(status))),
+    }
+}
+
+fn signal_name(signal: libc::c_int) -> String {
+    let name = match signal {
+        libc::SIGSEGV => "SIGSEGV",
+        libc::SIGBUS => "SIGBUS",
+        libc::SIGILL => "SIGILL",
+        libc::SIGFPE => "SIGFPE",
+        libc::SIGABRT => "SIGABRT",
+        libc::SIGTRAP => "SIGTRAP",
+        libc::SIGSYS => "SIGSYS",
+        libc::SIGPIPE => "SIGPIPE",
+        libc::SIGKILL => "SIGKILL",
+        libc::SIGTERM => "SIGTERM",
+        libc::SIGINT => "SIGINT",
+        _ => return format!("signal {}", signal),
+    };
+
+    name.to_string()
+}
+
+fn render_worker_failure<W: std::io::Write>(
+    writer: &mut W,
+    render_target: RenderTarget,
+    arena: &Bump,
+    interns: &Interns,
+    expectations: &VecMap<ModuleId, Expectations>,
+    expect: ToplevelExpect<'_>,
+    outcome: WorkerOutcome,
+) -> std::io::Result<()> {
+    let module_id = expect.symbol.module_id();
+    let path = expectations.get(&module_id).unwrap().path.clone();
+    let source = std::fs::read_to_string(&path).unwrap();
+
+    let renderer = Renderer::new(arena, interns, render_target, module_id, path, &source);
+
+    match outcome {
+        WorkerOutcome::Finished { .. } => {}
+        WorkerOutcome::TimedOut(timeout) => {
+            renderer.render_timeout(writer, timeout, expect.region)?
+        }
+        WorkerOutcome::Crashed(cause) => {
+            renderer.render_worker_crash(writer, &cause, expect.region)?
         }
     }
 
-    Ok((failed, passed))
+    writeln!(writer)
+}
+
+fn expect_result<'b>(
+    writer: &mut impl std::io::Write,
+    expect: ToplevelExpect<'b>,
+    passed: bool,
+    start: Instant,
+    output: Vec<u8>,
+) -> std::io::Result<ExpectResult<'b>> {
+    let duration = start.elapsed();
+
+    writer.write_all(&output)?;
+
+    Ok(ExpectResult {
+        expect,
+        passed,
+        duration,
+        output: String::from_utf8_lossy(&output).into_owned(),
+    })
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -183,9 +620,156 @@ fn run_expect_pure<'a, W: std::io::Write>(
 
     let result: Result<(), String> = try_run_jit_function!(lib, expect.name, (), |v: ()| v);
 
+    render_expect_outcome(
+        writer,
+        render_target,
+        arena,
+        interns,
+        layout_interner,
+        expectations,
+        shared_memory,
+        &sequence,
+        expect,
+        result,
+    )
+}
+
+/// Run an expect-fx in this process, with its effects answered from the mock effects script.
+#[allow(clippy::too_many_arguments)]
+fn run_expect_mocked<'a, W: std::io::Write>(
+    writer: &mut W,
+    render_target: RenderTarget,
+    arena: &'a Bump,
+    interns: &'a Interns,
+    layout_interner: &Arc<GlobalInterner<'a, Layout<'a>>>,
+    lib: &libloading::Library,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    shared_memory: &mut ExpectMemory,
+    expect: ToplevelExpect<'_>,
+    mock_effects: &MockEffects,
+) -> std::io::Result<bool> {
+    use roc_gen_llvm::try_run_jit_function;
+
+    let module_id = expect.symbol.module_id();
+    let path = expectations.get(&module_id).unwrap().path.clone();
+
+    let name = toplevel_expect_name(interns, &path, expect.symbol, expect.enclosing_def);
+    let script = mock_effects
+        .script_for(&name)
+        .into_iter()
+        .map(|(key, call)| (key, call.clone()))
+        .collect();
+
+    crate::mock::start(script);
+
+    let sequence = ExpectSequence::new(shared_memory.ptr.cast());
+
+    let result: Result<(), String> = try_run_jit_function!(lib, expect.name, (), |v: ()| v);
+
+    let outcome = crate::mock::finish();
+
+    // an unexpected call panics in the builtins; it is reported below instead
+    let result = match (&outcome.unexpected, result) {
+        (Some(_), Err(_)) => Ok(()),
+        (_, result) => result,
+    };
+
+    let passed = render_expect_outcome(
+        writer,
+        render_target,
+        arena,
+        interns,
+        layout_interner,
+        expectations,
+        shared_memory,
+        &sequence,
+        expect,
+        result,
+    )?;
+
+    if outcome.is_ok() {
+        return Ok(passed);
+    }
+
+    let source = std::fs::read_to_string(&path).unwrap();
+    let renderer = Renderer::new(arena, interns, render_target, module_id, path, &source);
+
+    match &outcome.unexpected {
+        Some(UnexpectedEffect::Call { called, expected }) => {
+            renderer.render_unexpected_effect(
+                writer,
+                called,
+                expected.as_deref(),
+                expect.region,
+            )?;
+        }
+        Some(UnexpectedEffect::Arguments { call }) => {
+            let arguments: Vec<_> = call.arguments.iter().map(String::as_str).collect();
+
+            renderer.render_unexpected_arguments(
+                writer,
+                &call.effect,
+                &arguments,
+                call.line,
+                expect.region,
+            )?;
+        }
+        None => {}
+    }
+
+    if !outcome.missing.is_empty() {
+        let missing: Vec<_> = outcome.missing.iter().map(String::as_str).collect();
+
+        renderer.render_missing_effects(writer, &missing, expect.region)?;
+    }
+
+    writeln!(writer)?;
+
+    Ok(false)
+}
+
+/// Report the frames the expect wrote to shared memory, and the panic it ended with, if any.
+/// Returns whether the expect passed.
+#[allow(clippy::too_many_arguments)]
+fn render_expect_outcome<'a, W: std::io::Write>(
+    writer: &mut W,
+    render_target: RenderTarget,
+    arena: &'a Bump,
+    interns: &'a Interns,
+    layout_interner: &Arc<GlobalInterner<'a, Layout<'a>>>,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    shared_memory: &ExpectMemory,
+    sequence: &ExpectSequence,
+    expect: ToplevelExpect<'_>,
+    result: Result<(), String>,
+) -> std::io::Result<bool> {
     let shared_memory_ptr: *const u8 = shared_memory.ptr.cast();
 
-    if result.is_err() || sequence.count_failures() > 0 {
+    // frames are written by failed expects, by `dbg`s and by `crash`es
+    let mut failures = 0;
+    let mut crashed = false;
+    let mut offset = ExpectSequence::START_OFFSET;
+
+    for _ in 0..sequence.count_frames() {
+        let (next_offset, kind) = render_expect_frame(
+            writer,
+            render_target,
+            arena,
+            Some(expect),
+            expectations,
+            interns,
+            layout_interner,
+            shared_memory_ptr,
+            offset,
+        )?;
+
+        offset = next_offset;
+        failures += (kind != FrameKind::Dbg) as usize;
+        crashed |= kind == FrameKind::Crash;
+    }
+
+    // a `crash` has already been reported, along with its message
+    if let (Err(roc_panic_message), false) = (result, crashed) {
         let module_id = expect.symbol.module_id();
         let data = expectations.get_mut(&module_id).unwrap();
 
@@ -195,26 +779,12 @@ fn run_expect_pure<'a, W: std::io::Write>(
 
         let renderer = Renderer::new(arena, interns, render_target, module_id, filename, &source);
 
-        if let Err(roc_panic_message) = result {
-            renderer.render_panic(writer, &roc_panic_message, expect.region)?;
-        } else {
-            let mut offset = ExpectSequence::START_OFFSET;
+        renderer.render_panic(writer, &roc_panic_message, expect.region)?;
 
-            for _ in 0..sequence.count_failures() {
-                offset += render_expect_failure(
-                    writer,
-                    &renderer,
-                    arena,
-                    Some(expect),
-                    expectations,
-                    interns,
-                    layout_interner,
-                    shared_memory_ptr,
-                    offset,
-                )?;
-            }
-        }
+        failures += 1;
+    }
 
+    if failures > 0 {
         writeln!(writer)?;
 
         Ok(false)
@@ -223,147 +793,250 @@ fn run_expect_pure<'a, W: std::io::Write>(
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-fn run_expect_fx<'a, W: std::io::Write>(
-    writer: &mut W,
-    render_target: RenderTarget,
+pub fn roc_dev_expect<'a>(
+    writer: &mut impl std::io::Write,
     arena: &'a Bump,
+    expectations: &mut VecMap<ModuleId, Expectations>,
     interns: &'a Interns,
     layout_interner: &Arc<GlobalInterner<'a, Layout<'a>>>,
-    lib: &libloading::Library,
-    expectations: &mut VecMap<ModuleId, Expectations>,
-    parent_memory: &mut ExpectMemory,
-    expect: ToplevelExpect<'_>,
-) -> std::io::Result<bool> {
-    use signal_hook::{consts::signal::SIGCHLD, consts::signal::SIGUSR1, iterator::Signals};
+    shared_ptr: *mut u8,
+) -> std::io::Result<usize> {
+    let (offset, _) = render_expect_frame(
+        writer,
+        RenderTarget::ColorTerminal,
+        arena,
+        None,
+        expectations,
+        interns,
+        layout_interner,
+        shared_ptr,
+        ExpectSequence::START_OFFSET,
+    )?;
 
-    let mut signals = Signals::new(&[SIGCHLD, SIGUSR1]).unwrap();
+    Ok(offset)
+}
 
-    match unsafe { libc::fork() } {
-        0 => unsafe {
-            // we are the child
+/// The memory that `roc dev` shares with the program it runs. The program writes the frame of
+/// every `dbg`, failed `expect` and `crash` to it, and signals `roc dev` to report the frame.
+pub struct DevExpectMemory {
+    name: CString,
+    ptr: *mut u8,
+}
 
-            use roc_gen_llvm::try_run_jit_function;
+impl DevExpectMemory {
+    /// Create the memory for a program that this process runs, which finds it by our process id.
+    pub fn create() -> std::io::Result<Self> {
+        // IMPORTANT: shared memory object names must begin with / and contain no other slashes!
+        let name = CString::new(format!("/roc_expect_buffer_{}", std::process::id())).unwrap();
+        let length = ExpectMemory::BUFFER_SIZE;
 
-            let mut child_memory = parent_memory.reuse_mmap().unwrap();
+        let ptr: *mut u8 = unsafe {
+            let fd = libc::shm_open(name.as_ptr(), libc::O_RDWR | libc::O_CREAT, 0o600);
 
-            let sequence = ExpectSequence::new(child_memory.ptr);
+            if fd < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
 
-            child_memory.set_shared_buffer(lib);
+            let ptr = if libc::ftruncate(fd, length as libc::off_t) == 0 {
+                libc::mmap(
+                    std::ptr::null_mut(),
+                    length,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_SHARED,
+                    fd,
+                    0,
+                )
+            } else {
+                libc::MAP_FAILED
+            };
+
+            let error = std::io::Error::last_os_error();
+            libc::close(fd);
+
+            if ptr == libc::MAP_FAILED {
+                libc::shm_unlink(name.as_ptr());
+
+                return Err(error);
+            }
 
-            let result: Result<(), String> = try_run_jit_function!(lib, expect.name, (), |v: ()| v);
+            ptr.cast()
+        };
 
-            if let Err(msg) = result {
-                panic!("roc panic {}", msg);
-            }
+        ExpectSequence::new(ptr);
 
-            if sequence.count_failures() > 0 {
-                libc::kill(parent_id() as _, SIGUSR1);
-            }
+        Ok(Self { name, ptr })
+    }
 
-            std::process::exit(0)
-        },
-        -1 => {
-            // something failed
+    /// Report the frame that the program wrote, if there is one, and let the program continue.
+    pub fn report_frame<'a>(
+        &self,
+        writer: &mut impl std::io::Write,
+        arena: &'a Bump,
+        expectations: &mut VecMap<ModuleId, Expectations>,
+        interns: &'a Interns,
+    ) -> std::io::Result<()> {
+        if ExpectSequence::count_frames_at(self.ptr) == 0 {
+            return Ok(());
+        }
+
+        // only the modules of the app's own package have their expectations loaded
+        let frame = ExpectFrame::at_offset(self.ptr, ExpectSequence::START_OFFSET);
+        let is_dbg = expectations
+            .get(&frame.module_id)
+            .map_or(false, |data| data.dbgs.contains_key(&frame.region));
+
+        let mut next_offset = ExpectSequence::START_OFFSET;
+
+        if is_dbg {
+            // the program's host prints the `dbg`, once we have rendered it
+            let layout_interner = GlobalInterner::with_capacity(32);
+
+            let (location, value) = render_dbg_frame(
+                arena,
+                expectations,
+                interns,
+                &layout_interner,
+                self.ptr,
+                ExpectSequence::START_OFFSET,
+            );
+
+            next_offset = unsafe { self.write_rendered_dbg(&location, &value) };
+        } else if expectations.contains_key(&frame.module_id) {
+            // the layouts of the values are computed from their types again
+            let layout_interner = GlobalInterner::with_capacity(32);
+
+            roc_dev_expect(
+                writer,
+                arena,
+                expectations,
+                interns,
+                &layout_interner,
+                self.ptr,
+            )?;
+
+            writer.flush()?;
+        }
 
-            // Display a human-friendly error message
-            println!("Error {:?}", std::io::Error::last_os_error());
+        // the program waits until its frame is cleared
+        unsafe {
+            let state = self.ptr as *mut usize;
+            *state.add(ExpectSequence::OFFSET_INDEX) = next_offset;
 
-            std::process::exit(1)
+            let count = &*(state.add(ExpectSequence::COUNT_INDEX) as *const AtomicUsize);
+            count.store(0, Ordering::SeqCst);
         }
-        1.. => {
-            let mut has_succeeded = true;
-
-            for sig in &mut signals {
-                match sig {
-                    SIGCHLD => {
-                        // done!
-                        return Ok(has_succeeded);
-                    }
-                    SIGUSR1 => {
-                        // this is the signal we use for an expect failure. Let's see what the child told us
-                        has_succeeded = false;
-
-                        let frame =
-                            ExpectFrame::at_offset(parent_memory.ptr, ExpectSequence::START_OFFSET);
-                        let module_id = frame.module_id;
-
-                        let data = expectations.get_mut(&module_id).unwrap();
-                        let filename = data.path.to_owned();
-                        let source = std::fs::read_to_string(&data.path).unwrap();
-
-                        let renderer = Renderer::new(
-                            arena,
-                            interns,
-                            render_target,
-                            module_id,
-                            filename,
-                            &source,
-                        );
-
-                        render_expect_failure(
-                            writer,
-                            &renderer,
-                            arena,
-                            None,
-                            expectations,
-                            interns,
-                            layout_interner,
-                            parent_memory.ptr,
-                            ExpectSequence::START_OFFSET,
-                        )?;
-                    }
-                    _ => println!("received signal {}", sig),
-                }
-            }
 
-            Ok(true)
+        Ok(())
+    }
+
+    /// Write the rendered `dbg` over its frame: the lengths of its location and of its value,
+    /// followed by their bytes. Returns where they end, which tells the program that the `dbg`
+    /// was rendered. Both are cut short to fit in the buffer.
+    unsafe fn write_rendered_dbg(&self, location: &str, value: &str) -> usize {
+        let header_size = 2 * std::mem::size_of::<usize>();
+        let mut space = ExpectMemory::BUFFER_SIZE - ExpectSequence::START_OFFSET - header_size;
+
+        let location = truncate_to_fit(location, space);
+        space -= location.len();
+        let value = truncate_to_fit(value, space);
+
+        let header = self.ptr.add(ExpectSequence::START_OFFSET) as *mut usize;
+        std::ptr::write_unaligned(header, location.len());
+        std::ptr::write_unaligned(header.add(1), value.len());
+
+        let text = self.ptr.add(ExpectSequence::START_OFFSET + header_size);
+        std::ptr::copy_nonoverlapping(location.as_ptr(), text, location.len());
+        std::ptr::copy_nonoverlapping(value.as_ptr(), text.add(location.len()), value.len());
+
+        ExpectSequence::START_OFFSET + header_size + location.len() + value.len()
+    }
+}
+
+/// The longest prefix of `text` that is at most `max_len` bytes, cut on a character boundary
+fn truncate_to_fit(text: &str, max_len: usize) -> &str {
+    let mut end = text.len().min(max_len);
+
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    &text[..end]
+}
+
+impl Drop for DevExpectMemory {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr.cast(), ExpectMemory::BUFFER_SIZE);
+            libc::shm_unlink(self.name.as_ptr());
         }
-        _ => unreachable!(),
     }
 }
 
-pub fn roc_dev_expect<'a>(
-    writer: &mut impl std::io::Write,
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FrameKind {
+    Failure,
+    Dbg,
+    Crash,
+}
+
+/// The location and the value of the `dbg` whose frame is at `offset`, for the host to print.
+fn render_dbg_frame<'a>(
     arena: &'a Bump,
     expectations: &mut VecMap<ModuleId, Expectations>,
     interns: &'a Interns,
     layout_interner: &Arc<GlobalInterner<'a, Layout<'a>>>,
-    shared_ptr: *mut u8,
-) -> std::io::Result<usize> {
-    let frame = ExpectFrame::at_offset(shared_ptr, ExpectSequence::START_OFFSET);
+    start: *const u8,
+    offset: usize,
+) -> (String, String) {
+    // we always run programs as the host
+    let target_info = (&target_lexicon::Triple::host()).into();
+
+    let frame = ExpectFrame::at_offset(start, offset);
     let module_id = frame.module_id;
 
     let data = expectations.get_mut(&module_id).unwrap();
+    let (_, variable) = data.dbgs.get(&frame.region).unwrap();
+    let variable = *variable;
+
     let filename = data.path.to_owned();
     let source = std::fs::read_to_string(&data.path).unwrap();
 
+    // the host prints the text as it is, so leave out colors
     let renderer = Renderer::new(
         arena,
         interns,
-        RenderTarget::ColorTerminal,
+        RenderTarget::Generic,
         module_id,
         filename,
         &source,
     );
 
-    render_expect_failure(
-        writer,
-        &renderer,
+    let subs = arena.alloc(&mut data.subs);
+
+    let (_, expressions) = crate::get_values(
+        target_info,
         arena,
-        None,
-        expectations,
+        subs,
         interns,
         layout_interner,
-        shared_ptr,
-        ExpectSequence::START_OFFSET,
+        start,
+        frame.start_offset,
+        &[variable],
+    )
+    .unwrap();
+
+    (
+        renderer.dbg_location(frame.region),
+        renderer.dbg_value(&expressions[0]),
     )
 }
 
+/// Render the frame at `offset`, which is a failed expectation, the value of a `dbg`,
+/// or the message of a `crash`. Returns the offset of the next frame, and what kind of frame it was.
 #[allow(clippy::too_many_arguments)]
-fn render_expect_failure<'a>(
+fn render_expect_frame<'a>(
     writer: &mut impl std::io::Write,
-    renderer: &Renderer,
+    render_target: RenderTarget,
     arena: &'a Bump,
     expect: Option<ToplevelExpect>,
     expectations: &mut VecMap<ModuleId, Expectations>,
@@ -371,7 +1044,7 @@ fn render_expect_failure<'a>(
     layout_interner: &Arc<GlobalInterner<'a, Layout<'a>>>,
     start: *const u8,
     offset: usize,
-) -> std::io::Result<usize> {
+) -> std::io::Result<(usize, FrameKind)> {
     // we always run programs as the host
     let target_info = (&target_lexicon::Triple::host()).into();
 
@@ -383,6 +1056,57 @@ fn render_expect_failure<'a>(
 
     let data = expectations.get_mut(&module_id).unwrap();
 
+    let filename = data.path.to_owned();
+    let source = std::fs::read_to_string(&data.path).unwrap();
+
+    let renderer = Renderer::new(arena, interns, render_target, module_id, filename, &source);
+
+    if data.crashes.contains(&failure_region) {
+        let subs = arena.alloc(&mut data.subs);
+
+        let (offset, expressions) = crate::get_values(
+            target_info,
+            arena,
+            subs,
+            interns,
+            layout_interner,
+            start,
+            frame.start_offset,
+            &[Variable::STR],
+        )
+        .unwrap();
+
+        let message = match expressions[0] {
+            roc_parse::ast::Expr::Str(roc_parse::ast::StrLiteral::PlainLine(message)) => message,
+            _ => panic!("the message of a crash is always a plain string"),
+        };
+
+        renderer.render_crash(writer, message, expect_region, failure_region)?;
+
+        return Ok((offset, FrameKind::Crash));
+    }
+
+    if let Some((_, variable)) = data.dbgs.get(&failure_region) {
+        let variable = *variable;
+        let subs = arena.alloc(&mut data.subs);
+
+        let (offset, expressions) = crate::get_values(
+            target_info,
+            arena,
+            subs,
+            interns,
+            layout_interner,
+            start,
+            frame.start_offset,
+            &[variable],
+        )
+        .unwrap();
+
+        renderer.render_dbg(writer, &expressions[0], failure_region)?;
+
+        return Ok((offset, FrameKind::Dbg));
+    }
+
     let current = match data.expectations.get(&failure_region) {
         None => panic!("region {failure_region:?} not in list of expects"),
         Some(current) => current,
@@ -413,7 +1137,7 @@ fn render_expect_failure<'a>(
         failure_region,
     )?;
 
-    Ok(offset)
+    Ok((offset, FrameKind::Failure))
 }
 
 struct ExpectSequence {
@@ -438,8 +1162,12 @@ impl ExpectSequence {
         }
     }
 
-    fn count_failures(&self) -> usize {
-        unsafe { *(self.ptr as *const usize).add(Self::COUNT_INDEX) }
+    fn count_frames(&self) -> usize {
+        Self::count_frames_at(self.ptr)
+    }
+
+    fn count_frames_at(ptr: *const u8) -> usize {
+        unsafe { *(ptr as *const usize).add(Self::COUNT_INDEX) }
     }
 }
 
@@ -473,12 +1201,46 @@ pub struct ToplevelExpect<'a> {
     pub name: &'a str,
     pub symbol: Symbol,
     pub region: Region,
+    /// The top-level def this expect is written beneath, if there is one
+    pub enclosing_def: Option<Symbol>,
+}
+
+/// The name `roc test` shows for a top-level expect: its module name followed by the name of
+/// the def the expect is written beneath, e.g. `Parser.parseInt`, or just the module name if it
+/// comes before every def. `--filter` and mock effects scripts refer to expects by this name.
+pub fn toplevel_expect_name(
+    interns: &Interns,
+    path: &Path,
+    symbol: Symbol,
+    enclosing_def: Option<Symbol>,
+) -> String {
+    let module_name = expect_module_name(interns, path, symbol.module_id());
+
+    match enclosing_def {
+        Some(def) => format!("{}.{}", module_name, def.as_str(interns)),
+        None => module_name,
+    }
+}
+
+/// The name of the module at `path`, as `roc test` shows it.
+pub fn expect_module_name(interns: &Interns, path: &Path, module_id: ModuleId) -> String {
+    let module_name = interns.module_name(module_id).as_str();
+
+    if module_name.is_empty() {
+        // the app module has no name of its own
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    } else {
+        module_name.to_string()
+    }
 }
 
 #[derive(Debug)]
 pub struct ExpectFunctions<'a> {
     pub pure: BumpVec<'a, ToplevelExpect<'a>>,
     pub fx: BumpVec<'a, ToplevelExpect<'a>>,
+    pub benches: BumpVec<'a, ToplevelBench<'a>>,
 }
 
 pub fn expect_mono_module_to_dylib<'a>(
@@ -499,6 +1261,7 @@ pub fn expect_mono_module_to_dylib<'a>(
 
     let MonomorphizedModule {
         toplevel_expects,
+        toplevel_benches,
         procedures,
         entry_point,
         interns,
@@ -525,6 +1288,7 @@ pub fn expect_mono_module_to_dylib<'a>(
         builder: &builder,
         dibuilder: &dibuilder,
         compile_unit: &compile_unit,
+        debug_sources: Default::default(),
         context: &context,
         interns,
         module,
@@ -545,11 +1309,15 @@ pub fn expect_mono_module_to_dylib<'a>(
         EntryPoint::Test => None,
     };
 
-    let capacity = toplevel_expects.pure.len() + toplevel_expects.fx.len();
+    let capacity =
+        toplevel_expects.pure.len() + toplevel_expects.fx.len() + toplevel_benches.thunks.len();
     let mut expect_symbols = BumpVec::with_capacity_in(capacity, env.arena);
 
     expect_symbols.extend(toplevel_expects.pure.keys().copied());
     expect_symbols.extend(toplevel_expects.fx.keys().copied());
+    // benchmarks run from thunks that are exposed just like expects
+    let bench_count = toplevel_benches.thunks.len();
+    expect_symbols.extend(toplevel_benches.thunks.keys().copied());
 
     let expect_names = roc_gen_llvm::llvm::build::build_procedures_expose_expects(
         &env,
@@ -568,6 +1336,7 @@ pub fn expect_mono_module_to_dylib<'a>(
                 symbol,
                 region,
                 name,
+                enclosing_def: toplevel_expects.enclosing_defs.get(&symbol).copied(),
             }),
         env.arena,
     );
@@ -581,6 +1350,20 @@ pub fn expect_mono_module_to_dylib<'a>(
                 symbol,
                 region,
                 name,
+                enclosing_def: toplevel_expects.enclosing_defs.get(&symbol).copied(),
+            }),
+        env.arena,
+    );
+
+    let benches = bumpalo::collections::Vec::from_iter_in(
+        toplevel_benches
+            .thunks
+            .into_iter()
+            .zip(expect_names.iter().skip(expect_symbols.len() - bench_count))
+            .map(|((_, bench), name)| ToplevelBench {
+                name,
+                symbol: bench.value,
+                region: bench.region,
             }),
         env.arena,
     );
@@ -588,6 +1371,7 @@ pub fn expect_mono_module_to_dylib<'a>(
     let expects = ExpectFunctions {
         pure: expects_pure,
         fx: expects_fx,
+        benches,
     };
 
     env.dibuilder.finalize();
@@ -0,0 +1,6 @@
+interface Greeting
+    exposes [greet]
+    imports []
+
+greet : Str -> Str
+greet = \name -> Str.concat (Str.concat "Hello, " name) "!"
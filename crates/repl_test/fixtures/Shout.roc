@@ -0,0 +1,8 @@
+interface Shout
+    exposes [shout]
+    imports [Greeting.{ greet }]
+
+shout : Str -> Str
+shout = \name -> Str.concat (greet name) exclamation
+
+exclamation = "!!"
@@ -131,6 +131,11 @@ pub fn expect_success(input: &str, expected: &str) {
     assert!(out.status.success());
 }
 
+/// Enters `inputs` one after the other, expecting the last one to evaluate to `expected`
+pub fn expect_session(inputs: &[&str], expected: &str) {
+    expect_success(&inputs.join("\n\n"), expected);
+}
+
 pub fn expect_failure(input: &str, expected: &str) {
     let out = repl_eval(input);
 
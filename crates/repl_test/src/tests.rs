@@ -2,11 +2,11 @@
 use indoc::indoc;
 
 #[cfg(not(feature = "wasm"))]
-use crate::cli::{expect_failure, expect_success};
+use crate::cli::{expect_failure, expect_session, expect_success};
 
 #[cfg(feature = "wasm")]
 #[allow(unused_imports)]
-use crate::wasm::{expect_failure, expect_success};
+use crate::wasm::{expect_failure, expect_session, expect_success};
 
 #[test]
 fn literal_0() {
@@ -1239,3 +1239,121 @@ fn record_of_poly_function_and_string() {
         r#"{ a: <function>, b: "b" } : { a : * -> Str, b : Str }"#,
     );
 }
+
+#[test]
+fn defs_persist_across_inputs() {
+    expect_session(&["x = 5", "y = x + 1", "y * 2"], "12 : Num *");
+}
+
+#[test]
+fn annotation_persists_until_its_body() {
+    expect_session(
+        &["name : Str", r#"name = "roc""#, r#"Str.concat name "!""#],
+        r#""roc!" : Str"#,
+    );
+}
+
+#[test]
+fn opaque_type_persists_across_inputs() {
+    expect_session(
+        &["Age := U32", r#"getAge = \@Age n -> n"#, "getAge (@Age 21)"],
+        "21 : U32",
+    );
+}
+
+#[test]
+fn redefinition_shadows_earlier_def() {
+    expect_session(&["x = 5", r#"x = "five""#, "x"], r#""five" : Str"#);
+}
+
+#[test]
+fn redefinition_keeps_earlier_uses() {
+    expect_session(&["x = 5", "y = x + 1", "x = 10", "y + x"], "16 : Num *");
+}
+
+#[test]
+fn redefined_type_keeps_earlier_uses() {
+    expect_session(
+        &[
+            "Name : Str",
+            indoc!(
+                r#"
+                greet : Name -> Str
+                greet = \name -> Str.concat "Hi, " name
+                "#
+            ),
+            "Name : U64",
+            r#"greet "Roc""#,
+        ],
+        r#""Hi, Roc" : Str"#,
+    );
+}
+
+#[test]
+fn type_command() {
+    expect_success(":type List.map", "List.map : List a, (a -> b) -> List b");
+}
+
+#[test]
+fn reset_command_forgets_defs() {
+    expect_session(&["x : Str", ":reset", "x = 5", "x"], "5 : Num *");
+}
+
+#[cfg(not(feature = "wasm"))]
+#[test]
+fn load_command_adds_module_defs() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/Greeting.roc");
+
+    expect_success(
+        &format!(":load {}\ngreet \"Roc\"", path.display()),
+        r#""Hello, Roc!" : Str"#,
+    );
+}
+
+#[cfg(not(feature = "wasm"))]
+#[test]
+fn load_command_resolves_module_imports() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/Shout.roc");
+
+    expect_success(
+        &format!(":load {}\nshout \"Roc\"", path.display()),
+        r#""Hello, Roc!!!" : Str"#,
+    );
+}
+
+#[cfg(not(feature = "wasm"))]
+#[test]
+fn load_command_leaves_private_defs_out() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/Shout.roc");
+
+    // `exclamation` is private to Shout, so this defines a new one rather than shadowing it
+    expect_success(
+        &format!(
+            ":load {}\nexclamation = \"?\"\nStr.concat (shout \"Roc\") exclamation",
+            path.display()
+        ),
+        r#""Hello, Roc!!!?" : Str"#,
+    );
+}
+
+#[cfg(not(feature = "wasm"))]
+#[test]
+fn time_command_toggles_timings() {
+    expect_success(
+        indoc!(
+            r#"
+            :time
+            :time
+            1
+            "#
+        ),
+        indoc!(
+            r#"
+            Printing timings is now on.
+
+            Printing timings is now off.
+
+            1 : Num *"#
+        ),
+    );
+}
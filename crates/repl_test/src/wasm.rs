@@ -273,16 +273,42 @@ fn run(src: &'static str) -> (bool, String) {
 
 #[allow(dead_code)]
 pub fn expect_success(input: &'static str, expected: &str) {
-    let (ok, output) = run(input);
+    let (ok, output) = run(*input);
     if !ok {
         panic!("\n{}\n", output);
     }
     assert_eq!(output, expected);
 }
 
+/// Enters `inputs` one after the other, expecting the last one to evaluate to `expected`.
+/// The REPL keeps its defs between runs, so the session starts and ends with a reset.
+#[allow(dead_code)]
+pub fn expect_session(inputs: &[&'static str], expected: &str) {
+    let (last, defs) = inputs.split_last().unwrap();
+
+    for input in std::iter::once(&":reset").chain(defs) {
+        let (ok, output) = run(*input);
+
+        if !ok {
+            panic!("\n{}\n", output);
+        }
+
+        assert_eq!(output, "", "entering {:?}", input);
+    }
+
+    let (ok, output) = run(*last);
+    run(":reset");
+
+    if !ok {
+        panic!("\n{}\n", output);
+    }
+
+    assert_eq!(output, expected);
+}
+
 #[allow(dead_code)]
 pub fn expect_failure(input: &'static str, expected: &str) {
-    let (ok, output) = run(input);
+    let (ok, output) = run(*input);
     assert_eq!(ok, false);
     assert_eq!(output, expected);
 }
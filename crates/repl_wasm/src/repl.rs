@@ -1,4 +1,5 @@
 use bumpalo::{collections::vec::Vec, Bump};
+use std::cell::RefCell;
 use std::mem::size_of;
 
 use roc_collections::all::MutSet;
@@ -6,8 +7,10 @@ use roc_gen_wasm::wasm32_result;
 use roc_load::MonomorphizedModule;
 use roc_parse::ast::Expr;
 use roc_repl_eval::{
+    command::{ReplCommand, WEB_HELP},
     eval::jit_to_ast,
-    gen::{compile_to_mono, format_answer, ReplOutput},
+    gen::{compile_to_mono, format_answer, type_of, Compiled, ReplOutput},
+    state::ReplState,
     ReplApp, ReplAppMemory,
 };
 use roc_reporting::report::DEFAULT_PALETTE_HTML;
@@ -18,6 +21,11 @@ use crate::{js_create_app, js_get_result_and_memory, js_run_app};
 
 const WRAPPER_NAME: &str = "wrapper";
 
+thread_local! {
+    /// The defs entered so far, which every later input can use
+    static REPL_STATE: RefCell<ReplState> = RefCell::new(ReplState::new());
+}
+
 pub struct WasmReplApp<'a> {
     arena: &'a Bump,
 }
@@ -172,12 +180,27 @@ pub async fn entrypoint_from_js(src: String) -> Result<String, String> {
     console_error_panic_hook::set_once();
 
     let arena = &Bump::new();
+    let target_info = TargetInfo::default_wasm32();
+
+    if let Some(command) = ReplCommand::parse(&src) {
+        return run_command(arena, command?, target_info);
+    }
 
     // Compile the app
-    let target_info = TargetInfo::default_wasm32();
-    let mono = match compile_to_mono(arena, &src, target_info, DEFAULT_PALETTE_HTML) {
-        Ok(m) => m,
-        Err(messages) => return Err(messages.join("\n\n")),
+    let compiled = REPL_STATE.with(|state| {
+        let state = &mut state.borrow_mut();
+
+        compile_to_mono(arena, state, &src, target_info, DEFAULT_PALETTE_HTML)
+    });
+
+    // Each input is complete in the web REPL, so names that aren't defined yet never will be
+    let mono = match compiled {
+        Ok(Compiled::Expr(m)) => m,
+        Ok(Compiled::Defs) => return Ok(String::new()),
+        Ok(Compiled::UndefinedNames {
+            reports: messages, ..
+        })
+        | Err(messages) => return Err(messages.join("\n\n")),
     };
 
     let MonomorphizedModule {
@@ -275,6 +298,39 @@ pub async fn entrypoint_from_js(src: String) -> Result<String, String> {
     // `Result::Err` becomes a JS exception that will be caught and displayed
     match format_answer(arena, res_answer, expr_type_str) {
         ReplOutput::NoProblems { expr, expr_type } => Ok(format!("{} : {}", expr, expr_type)),
-        ReplOutput::Problems(lines) => Err(format!("\n{}\n", lines.join("\n\n"))),
+        ReplOutput::Problems(lines) | ReplOutput::UndefinedNames { reports: lines, .. } => {
+            Err(format!("\n{}\n", lines.join("\n\n")))
+        }
+        ReplOutput::Empty => Ok(String::new()),
+    }
+}
+
+fn run_command(
+    arena: &Bump,
+    command: ReplCommand,
+    target_info: TargetInfo,
+) -> Result<String, String> {
+    match command {
+        ReplCommand::Help => Ok(WEB_HELP.to_string()),
+        ReplCommand::Reset => {
+            REPL_STATE.with(|state| *state.borrow_mut() = ReplState::new());
+
+            Ok(String::new())
+        }
+        ReplCommand::Type(src) => REPL_STATE.with(|state| {
+            match type_of(
+                arena,
+                &state.borrow(),
+                src,
+                target_info,
+                DEFAULT_PALETTE_HTML,
+            ) {
+                Ok(expr_type) => Ok(format!("{} : {}", src, expr_type)),
+                Err(messages) => Err(messages.join("\n\n")),
+            }
+        }),
+        ReplCommand::Exit | ReplCommand::Time | ReplCommand::Load(_) => Err(
+            "This command is only available in the REPL you get from running `roc repl`.".into(),
+        ),
     }
 }
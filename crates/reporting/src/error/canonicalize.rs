@@ -38,6 +38,8 @@ const OPAQUE_NOT_DEFINED: &str = "OPAQUE TYPE NOT DEFINED";
 const OPAQUE_DECLARED_OUTSIDE_SCOPE: &str = "OPAQUE TYPE DECLARED OUTSIDE SCOPE";
 const OPAQUE_NOT_APPLIED: &str = "OPAQUE TYPE NOT APPLIED";
 const OPAQUE_OVER_APPLIED: &str = "OPAQUE TYPE APPLIED TO TOO MANY ARGS";
+const CRASH_NOT_APPLIED: &str = "UNAPPLIED CRASH";
+const CRASH_OVER_APPLIED: &str = "OVERAPPLIED CRASH";
 const INVALID_EXTENSION_TYPE: &str = "INVALID_EXTENSION_TYPE";
 const ABILITY_HAS_TYPE_VARIABLES: &str = "ABILITY HAS TYPE VARIABLES";
 const HAS_CLAUSE_IS_NOT_AN_ABILITY: &str = "HAS CLAUSE IS NOT AN ABILITY";
@@ -583,6 +585,7 @@ pub fn can_problem<'b>(
         Problem::InvalidExtensionType { region, kind } => {
             let (kind_str, can_only_contain) = match kind {
                 ExtensionTypeKind::Record => ("record", "a type variable or another record"),
+                ExtensionTypeKind::Tuple => ("tuple", "a type variable or another tuple"),
                 ExtensionTypeKind::TagUnion => {
                     ("tag union", "a type variable or another tag union")
                 }
@@ -977,6 +980,19 @@ pub fn can_problem<'b>(
             title = "OVERLOADED SPECIALIZATION".to_string();
             severity = Severity::Warning;
         }
+        Problem::MultipleListRestPattern { region } => {
+            doc = alloc.stack([
+                alloc.reflow("This list pattern match has multiple rest patterns:"),
+                alloc.region(lines.convert_region(region)),
+                alloc.concat([
+                    alloc.reflow("I only support compiling list patterns with one "),
+                    alloc.parser_suggestion(".."),
+                    alloc.reflow(" pattern! Can you remove this additional one?"),
+                ]),
+            ]);
+            title = "MULTIPLE LIST REST PATTERNS".to_string();
+            severity = Severity::RuntimeError;
+        }
     };
 
     Report {
@@ -1555,6 +1571,10 @@ fn pretty_runtime_error<'b>(
         RuntimeError::MalformedClosure(_) => {
             todo!("");
         }
+        RuntimeError::MalformedExpr(_) => {
+            // do nothing, reported as a parse problem
+            unreachable!();
+        }
         RuntimeError::InvalidFloat(sign @ FloatErrorKind::PositiveInfinity, region, _raw_str)
         | RuntimeError::InvalidFloat(sign @ FloatErrorKind::NegativeInfinity, region, _raw_str) => {
             let tip = alloc
@@ -1962,6 +1982,40 @@ fn pretty_runtime_error<'b>(
 
             title = "DEGENERATE BRANCH";
         }
+        RuntimeError::CrashNotApplied(region) => {
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("This "),
+                    alloc.keyword("crash"),
+                    alloc.reflow(" doesn't have a message given to it:"),
+                ]),
+                alloc.region(lines.convert_region(region)),
+                alloc.concat([
+                    alloc.keyword("crash"),
+                    alloc.reflow(" must be passed a message to crash with at the exact place it's used. "),
+                    alloc.keyword("crash"),
+                    alloc.reflow(" can't be used as a value that's passed around, like functions can be - it must be applied immediately!"),
+                ]),
+            ]);
+
+            title = CRASH_NOT_APPLIED;
+        }
+        RuntimeError::CrashAppliedToMultipleArgs(region) => {
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("This "),
+                    alloc.keyword("crash"),
+                    alloc.reflow(" has too many values given to it:"),
+                ]),
+                alloc.region(lines.convert_region(region)),
+                alloc.concat([
+                    alloc.keyword("crash"),
+                    alloc.reflow(" must be given exactly one message to crash with."),
+                ]),
+            ]);
+
+            title = CRASH_OVER_APPLIED;
+        }
     }
 
     (doc, title)
@@ -152,8 +152,9 @@ impl<'a> Renderer<'a> {
 
         let mut buf = String::new();
 
-        report.render(
+        report.render_at(
             self.render_target,
+            Some(self.line_info.convert_region(failure_region)),
             &mut buf,
             &self.alloc,
             &crate::report::DEFAULT_PALETTE,
@@ -162,6 +163,100 @@ impl<'a> Renderer<'a> {
         write!(writer, "{}", buf)
     }
 
+    /// Report a `crash` that happened while running an expectation
+    pub fn render_crash<W>(
+        &self,
+        writer: &mut W,
+        message: &str,
+        expect_region: Option<Region>,
+        crash_region: Region,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use crate::report::Report;
+        use ven_pretty::DocAllocator;
+
+        let line_col_region = self.to_line_col_region(expect_region, crash_region);
+
+        // without an expect, the program that `roc dev` runs crashed
+        let (title, intro) = match expect_region {
+            Some(_) => ("EXPECT CRASHED", "This expectation crashed at this "),
+            None => ("CRASHED", "The program crashed at this "),
+        };
+
+        let doc = self.alloc.stack([
+            self.alloc.concat([
+                self.alloc.reflow(intro),
+                self.alloc.keyword("crash"),
+                self.alloc.reflow(":"),
+            ]),
+            self.alloc.region(line_col_region),
+            self.alloc.text("The crash reported this message:"),
+            self.alloc.text(message).indent(4),
+        ]);
+
+        let report = Report {
+            title: title.into(),
+            doc,
+            filename: self.filename.clone(),
+            severity: crate::report::Severity::RuntimeError,
+        };
+
+        let mut buf = String::new();
+
+        report.render_at(
+            self.render_target,
+            Some(self.line_info.convert_region(crash_region)),
+            &mut buf,
+            &self.alloc,
+            &crate::report::DEFAULT_PALETTE,
+        );
+
+        write!(writer, "{}", buf)
+    }
+
+    /// Print the value of a `dbg`, prefixed with the location of the printed expression
+    pub fn render_dbg<W>(
+        &self,
+        writer: &mut W,
+        expr: &Expr<'_>,
+        dbg_region: Region,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        writeln!(
+            writer,
+            "[{}] {}",
+            self.dbg_location(dbg_region),
+            self.dbg_value(expr)
+        )
+    }
+
+    /// Where a `dbg` is, as `file.roc:line:column`
+    pub fn dbg_location(&self, dbg_region: Region) -> String {
+        let line_col = self.line_info.convert_pos(dbg_region.start());
+        let filename = self.filename.file_name().unwrap_or_default();
+
+        format!(
+            "{}:{}:{}",
+            filename.to_string_lossy(),
+            line_col.line + 1,
+            line_col.column + 1
+        )
+    }
+
+    /// The value of a `dbg`, as Roc code
+    pub fn dbg_value(&self, expr: &Expr<'_>) -> String {
+        use roc_fmt::annotation::Formattable;
+
+        let mut buf = roc_fmt::Buf::new_in(self.arena);
+        expr.format(&mut buf, 0);
+
+        buf.as_str().to_string()
+    }
+
     pub fn render_panic<W>(
         &self,
         writer: &mut W,
@@ -192,8 +287,220 @@ impl<'a> Renderer<'a> {
 
         let mut buf = String::new();
 
-        report.render(
+        report.render_at(
+            self.render_target,
+            Some(line_col_region),
+            &mut buf,
+            &self.alloc,
+            &crate::report::DEFAULT_PALETTE,
+        );
+
+        write!(writer, "{}", buf)
+    }
+
+    /// For `roc test --mock-effects`: the expectation called an effect other than the one
+    /// the script expected next, or called one after the script ran out.
+    pub fn render_unexpected_effect<W>(
+        &self,
+        writer: &mut W,
+        called: &str,
+        expected: Option<&str>,
+        expect_region: Region,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use ven_pretty::DocAllocator;
+
+        let line_col_region = self.line_info.convert_region(expect_region);
+
+        let expected = match expected {
+            Some(expected) => self.alloc.concat([
+                self.alloc
+                    .reflow("but the mock effects script expected a call of "),
+                self.alloc.ident(expected.into()),
+                self.alloc.reflow(" next."),
+            ]),
+            None => self
+                .alloc
+                .reflow("but the mock effects script expected no more effect calls."),
+        };
+
+        let doc = self.alloc.stack([
+            self.alloc
+                .text("This expectation called an effect the script did not expect:"),
+            self.alloc.region(line_col_region),
+            self.alloc.concat([
+                self.alloc.reflow("It called "),
+                self.alloc.ident(called.into()),
+                self.alloc.reflow(", "),
+                expected,
+            ]),
+        ]);
+
+        self.render_runtime_report(writer, "UNEXPECTED EFFECT", doc, line_col_region)
+    }
+
+    /// For `roc test --mock-effects`: the expectation called the effect the script expected
+    /// next, but with other arguments than the ones on that line of the script.
+    pub fn render_unexpected_arguments<W>(
+        &self,
+        writer: &mut W,
+        effect: &str,
+        expected: &[&str],
+        script_line: u32,
+        expect_region: Region,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use ven_pretty::DocAllocator;
+
+        let line_col_region = self.line_info.convert_region(expect_region);
+
+        let doc = self.alloc.stack([
+            self.alloc.concat([
+                self.alloc.reflow("This expectation called "),
+                self.alloc.ident(effect.into()),
+                self.alloc
+                    .reflow(" with arguments the script did not expect:"),
+            ]),
+            self.alloc.region(line_col_region),
+            self.alloc.concat([
+                self.alloc.reflow("Line "),
+                self.alloc.text(script_line.to_string()),
+                self.alloc
+                    .reflow(" of the mock effects script expected it to be called with:"),
+            ]),
+            self.alloc
+                .stack(
+                    expected
+                        .iter()
+                        .map(|argument| self.alloc.text((*argument).to_string())),
+                )
+                .indent(4),
+        ]);
+
+        self.render_runtime_report(writer, "UNEXPECTED EFFECT ARGUMENTS", doc, line_col_region)
+    }
+
+    /// For `roc test --mock-effects`: the expectation finished without making all the effect
+    /// calls the script expected.
+    pub fn render_missing_effects<W>(
+        &self,
+        writer: &mut W,
+        missing: &[&str],
+        expect_region: Region,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use ven_pretty::DocAllocator;
+
+        let line_col_region = self.line_info.convert_region(expect_region);
+
+        let doc = self.alloc.stack([
+            self.alloc.text(
+                "This expectation finished before making every effect call the script expected:",
+            ),
+            self.alloc.region(line_col_region),
+            self.alloc.reflow("These calls were never made:"),
+            self.alloc
+                .stack(
+                    missing
+                        .iter()
+                        .map(|effect| self.alloc.ident((*effect).into())),
+                )
+                .indent(4),
+        ]);
+
+        self.render_runtime_report(writer, "MISSING EFFECTS", doc, line_col_region)
+    }
+
+    /// For `roc test --timeout`: the expectation was stopped because it ran for too long.
+    pub fn render_timeout<W>(
+        &self,
+        writer: &mut W,
+        timeout: std::time::Duration,
+        expect_region: Region,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use ven_pretty::DocAllocator;
+
+        let line_col_region = self.line_info.convert_region(expect_region);
+
+        let doc = self.alloc.stack([
+            self.alloc.text(format!(
+                "This expectation was still running after {} ms:",
+                timeout.as_millis()
+            )),
+            self.alloc.region(line_col_region),
+            self.alloc.concat([
+                self.alloc
+                    .reflow("It was stopped so the other expectations could run. A longer "),
+                self.alloc.keyword("--timeout"),
+                self.alloc.reflow(" gives it more time."),
+            ]),
+        ]);
+
+        self.render_runtime_report(writer, "EXPECT TIMED OUT", doc, line_col_region)
+    }
+
+    /// The process running the expectation died without reporting how the expectation went,
+    /// e.g. because of a segmentation fault. `cause` says how it died, e.g. `was killed by SIGSEGV`.
+    pub fn render_worker_crash<W>(
+        &self,
+        writer: &mut W,
+        cause: &str,
+        expect_region: Region,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use ven_pretty::DocAllocator;
+
+        let line_col_region = self.line_info.convert_region(expect_region);
+
+        let doc = self.alloc.stack([
+            self.alloc
+                .text("This expectation crashed the process that was running it:"),
+            self.alloc.region(line_col_region),
+            self.alloc.concat([
+                self.alloc.reflow("The process "),
+                self.alloc.text(cause.to_string()),
+                self.alloc.reflow(" before the expectation finished."),
+            ]),
+        ]);
+
+        self.render_runtime_report(writer, "EXPECT CRASHED", doc, line_col_region)
+    }
+
+    fn render_runtime_report<'r, W>(
+        &'r self,
+        writer: &mut W,
+        title: &str,
+        doc: RocDocBuilder<'r>,
+        line_col_region: LineColumnRegion,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use crate::report::Report;
+
+        let report = Report {
+            title: title.into(),
+            doc,
+            filename: self.filename.clone(),
+            severity: crate::report::Severity::RuntimeError,
+        };
+
+        let mut buf = String::new();
+
+        report.render_at(
             self.render_target,
+            Some(line_col_region),
             &mut buf,
             &self.alloc,
             &crate::report::DEFAULT_PALETTE,
@@ -906,11 +906,22 @@ fn to_str_report<'a>(
                 alloc.reflow(r"I cannot find the end of this block string:"),
                 alloc.region_with_subregion(lines.convert_region(surroundings), region),
                 alloc.concat([
-                    alloc.reflow(r"You could change it to something like "),
-                    alloc.parser_suggestion("\"\"\"to be or not to be\"\"\""),
-                    alloc.reflow(" or even just "),
-                    alloc.parser_suggestion("\"\"\"\"\"\""),
-                    alloc.reflow("."),
+                    alloc.reflow(r"A block string ends with another "),
+                    alloc.parser_suggestion("\"\"\""),
+                    alloc.reflow(r", which can go on its own line after the last line of text:"),
+                ]),
+                alloc
+                    .vcat(vec![
+                        alloc.text("\"\"\""),
+                        alloc.text("to be"),
+                        alloc.text("or not to be"),
+                        alloc.text("\"\"\""),
+                    ])
+                    .indent(4),
+                alloc.concat([
+                    alloc.reflow(r"The indentation shared by its lines and the closing "),
+                    alloc.parser_suggestion("\"\"\""),
+                    alloc.reflow(r" is not part of the string."),
                 ]),
             ]);
 
@@ -930,7 +941,7 @@ fn to_str_report<'a>(
                 alloc.region_with_subregion(lines.convert_region(surroundings), region),
                 alloc.concat([
                     alloc.reflow(r"Lines in a multi-line string must be indented at least as "),
-                    alloc.reflow("much as the beginning \"\"\". This extra indentation is automatically removed "),
+                    alloc.reflow("much as the line with the beginning \"\"\". The indentation they all share is automatically removed "),
                     alloc.reflow("from the string during compilation."),
                 ]),
             ]);
@@ -3,9 +3,9 @@ use crate::report::{Annotation, Report, RocDocAllocator, RocDocBuilder, Severity
 use roc_can::expected::{Expected, PExpected};
 use roc_collections::all::{HumanIndex, MutSet, SendMap};
 use roc_error_macros::internal_error;
-use roc_exhaustive::CtorName;
+use roc_exhaustive::{CtorName, ListArity};
 use roc_module::called_via::{BinOp, CalledVia};
-use roc_module::ident::{Ident, IdentStr, Lowercase, TagName};
+use roc_module::ident::{Ident, IdentStr, IndexOrField, Lowercase, TagName};
 use roc_module::symbol::Symbol;
 use roc_region::all::{LineInfo, Loc, Region};
 use roc_solve_problem::{
@@ -848,6 +848,35 @@ fn to_expr_report<'b>(
                     // they don't know. ("Wait, what's truthiness?")
                 )
             }
+            Reason::CrashArg => {
+                let problem = alloc.concat([
+                    alloc.text("This "),
+                    alloc.keyword("crash"),
+                    alloc.text(" message needs to be a "),
+                    alloc.type_str("Str"),
+                    alloc.text(":"),
+                ]);
+
+                report_bad_type(
+                    alloc,
+                    lines,
+                    filename,
+                    &category,
+                    found,
+                    expected_type,
+                    region,
+                    Some(expr_region),
+                    problem,
+                    alloc.text("Right now it’s"),
+                    alloc.concat([
+                        alloc.reflow("But I can only "),
+                        alloc.keyword("crash"),
+                        alloc.reflow(" with messages of type "),
+                        alloc.type_str("Str"),
+                        alloc.reflow("."),
+                    ]),
+                )
+            }
             Reason::IfCondition => {
                 let problem = alloc.concat([
                     alloc.text("This "),
@@ -1216,12 +1245,20 @@ fn to_expr_report<'b>(
                     }
                 }
             },
-            Reason::FnArg { name, arg_index } => {
+            Reason::FnArg {
+                name,
+                arg_index,
+                called_via,
+            } => {
                 let ith = arg_index.ordinal();
 
-                let this_function = match name {
-                    None => alloc.text("this function"),
-                    Some(symbol) => alloc.symbol_unqualified(symbol),
+                // `==` and `!=` call `isEq` and `isNotEq`, which the code doesn't mention
+                let this_function = match (called_via, name) {
+                    (CalledVia::BinOp(op @ (BinOp::Equals | BinOp::NotEquals)), _) => {
+                        alloc.binop(op)
+                    }
+                    (_, None) => alloc.text("this function"),
+                    (_, Some(symbol)) => alloc.symbol_unqualified(symbol),
                 };
 
                 report_mismatch(
@@ -1690,7 +1727,12 @@ fn format_category<'b>(
             alloc.text(" of type:"),
         ),
 
-        Accessor(field) => (
+        Tuple => (
+            alloc.concat([this_is, alloc.text(" a tuple")]),
+            alloc.text(" of type:"),
+        ),
+
+        Accessor(IndexOrField::Field(field)) => (
             alloc.concat([
                 alloc.text(format!("{}his ", t)),
                 alloc.record_field(field.to_owned()),
@@ -1698,6 +1740,14 @@ fn format_category<'b>(
             ]),
             alloc.text(" is a:"),
         ),
+        Accessor(IndexOrField::Index(index)) => (
+            alloc.concat([
+                alloc.text(format!("{}his ", t)),
+                alloc.tuple_elem(*index),
+                alloc.text(" value"),
+            ]),
+            alloc.text(" is a:"),
+        ),
         Access(field) => (
             alloc.concat([
                 alloc.text(format!("{}he value at ", t)),
@@ -1705,6 +1755,13 @@ fn format_category<'b>(
             ]),
             alloc.text(" is a:"),
         ),
+        TupleAccess(index) => (
+            alloc.concat([
+                alloc.text(format!("{}he value at ", t)),
+                alloc.tuple_elem(*index),
+            ]),
+            alloc.text(" is a:"),
+        ),
         CallResult(
             Some(_),
             CalledVia::BinOp(
@@ -1930,6 +1987,27 @@ fn to_pattern_report<'b>(
                     severity: Severity::RuntimeError,
                 }
             }
+            PReason::ListElem => {
+                let doc = alloc.stack([
+                    alloc.reflow("This list element doesn't match the types of other elements in the pattern:"),
+                    alloc.region(lines.convert_region(region)),
+                    pattern_type_comparison(
+                        alloc,
+                        found,
+                        expected_type,
+                        add_pattern_category(alloc, alloc.text("It matches"), &category),
+                        alloc.text("But the other elements in this list pattern match"),
+                        vec![],
+                    ),
+                ]);
+
+                Report {
+                    filename,
+                    title: "TYPE MISMATCH".to_string(),
+                    doc,
+                    severity: Severity::RuntimeError,
+                }
+            }
             PReason::TagArg { .. } | PReason::PatternGuard => {
                 unreachable!("I didn't think this could trigger. Please tell Folkert about it!")
             }
@@ -1973,11 +2051,13 @@ fn add_pattern_category<'b>(
 
     let rest = match category {
         Record => alloc.reflow(" record values of type:"),
+        Tuple => alloc.reflow(" tuple values of type:"),
         EmptyRecord => alloc.reflow(" an empty record:"),
         PatternGuard => alloc.reflow(" a pattern guard of type:"),
         PatternDefault => alloc.reflow(" an optional field of type:"),
         Set => alloc.reflow(" sets of type:"),
         Map => alloc.reflow(" maps of type:"),
+        List => alloc.reflow(" lists of type:"),
         Ctor(tag_name) => alloc.concat([
             alloc.reflow(" a "),
             alloc.tag_name(tag_name.clone()),
@@ -2033,6 +2113,7 @@ fn to_circular_report<'b>(
 pub enum Problem {
     IntFloat,
     ArityMismatch(usize, usize),
+    TupleArityMismatch(usize, usize),
     FieldTypo(Lowercase, Vec<Lowercase>),
     FieldsMissing(Vec<Lowercase>),
     TagTypo(TagName, Vec<TagName>),
@@ -2194,6 +2275,25 @@ fn ext_to_doc<'b>(alloc: &'b RocDocAllocator<'b>, ext: TypeExt) -> Option<RocDoc
     }
 }
 
+/// Tuple elements are shown positionally, so any index the type does not (yet) know about is
+/// shown as a wildcard, like `( *, Str )*` for a value that was only accessed with `.1`.
+fn fill_tuple_gaps<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    sorted_elems: impl IntoIterator<Item = (usize, RocDocBuilder<'b>)>,
+) -> Vec<RocDocBuilder<'b>> {
+    let mut docs = Vec::new();
+
+    for (index, doc) in sorted_elems {
+        while docs.len() < index {
+            docs.push(alloc.text("*"));
+        }
+
+        docs.push(doc);
+    }
+
+    docs
+}
+
 type AbleVariables = Vec<(Lowercase, Symbol)>;
 
 #[derive(Default)]
@@ -2280,6 +2380,21 @@ fn to_doc_help<'b>(
                 .collect(),
         ),
 
+        Tuple(mut elems, ext) => {
+            elems.sort_by_key(|(index, _)| *index);
+
+            report_text::tuple(
+                alloc,
+                fill_tuple_gaps(
+                    alloc,
+                    elems.into_iter().map(|(index, elem)| {
+                        (index, to_doc_help(ctx, alloc, Parens::Unnecessary, elem))
+                    }),
+                ),
+                ext_to_doc(alloc, ext),
+            )
+        }
+
         Record(fields_map, ext) => {
             let mut fields = fields_map.into_iter().collect::<Vec<_>>();
             fields.sort_by(|(a, _), (b, _)| a.cmp(b));
@@ -2407,8 +2522,17 @@ fn type_with_able_vars<'b>(
     let mut doc = Vec::with_capacity(1 + 6 * able.len());
     doc.push(typ);
 
-    for (i, (var, ability)) in able.into_iter().enumerate() {
-        doc.push(alloc.string(if i == 0 { " | " } else { ", " }.to_string()));
+    // a variable is collected every time it occurs, like twice in `a -> a`
+    let mut printed: AbleVariables = Vec::with_capacity(able.len());
+
+    for (var, ability) in able.into_iter() {
+        if printed.contains(&(var.clone(), ability)) {
+            continue;
+        }
+
+        doc.push(alloc.string(if printed.is_empty() { " | " } else { ", " }.to_string()));
+        printed.push((var.clone(), ability));
+
         doc.push(alloc.type_variable(var));
         doc.push(alloc.space());
         doc.push(alloc.keyword("has"));
@@ -2616,6 +2740,10 @@ fn to_diff<'b>(
             diff_record(alloc, fields1, ext1, fields2, ext2)
         }
 
+        (Tuple(elems1, ext1), Tuple(elems2, ext2)) => {
+            diff_tuple(alloc, elems1, ext1, elems2, ext2)
+        }
+
         (TagUnion(tags1, ext1), TagUnion(tags2, ext2)) => {
             diff_tag_union(alloc, &tags1, ext1, &tags2, ext2)
         }
@@ -2927,6 +3055,88 @@ fn diff_record<'b>(
     }
 }
 
+fn diff_tuple<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    mut elems1: Vec<(usize, ErrorType)>,
+    ext1: TypeExt,
+    mut elems2: Vec<(usize, ErrorType)>,
+    ext2: TypeExt,
+) -> Diff<RocDocBuilder<'b>> {
+    elems1.sort_by_key(|(index, _)| *index);
+    elems2.sort_by_key(|(index, _)| *index);
+
+    let (found, expected) = (elems1.len(), elems2.len());
+    let status = if found != expected && ext_has_fixed_fields(&ext1) && ext_has_fixed_fields(&ext2)
+    {
+        // a field typo tip makes no sense for tuples; explain the size difference instead
+        Status::Different(vec![Problem::TupleArityMismatch(found, expected)])
+    } else {
+        Status::Similar
+    };
+
+    let mut elems_diff: Diff<Vec<(usize, RocDocBuilder<'b>)>> = Diff {
+        left: vec![],
+        right: vec![],
+        status,
+        left_able: vec![],
+        right_able: vec![],
+    };
+
+    let mut right = elems2.into_iter().peekable();
+
+    for (index, t1) in elems1 {
+        while let Some((i2, t2)) = right.next_if(|(i2, _)| *i2 < index) {
+            let (doc, able) = to_doc(alloc, Parens::Unnecessary, t2);
+            elems_diff.right.push((i2, doc));
+            elems_diff.right_able.extend(able);
+            elems_diff.status.merge(Status::Different(vec![]));
+        }
+
+        match right.next_if(|(i2, _)| *i2 == index) {
+            Some((_, t2)) => {
+                let diff = to_diff(alloc, Parens::Unnecessary, t1, t2);
+
+                elems_diff.left.push((index, diff.left));
+                elems_diff.right.push((index, diff.right));
+                elems_diff.status.merge(diff.status);
+                elems_diff.left_able.extend(diff.left_able);
+                elems_diff.right_able.extend(diff.right_able);
+            }
+            None => {
+                let (doc, able) = to_doc(alloc, Parens::Unnecessary, t1);
+                elems_diff.left.push((index, doc));
+                elems_diff.left_able.extend(able);
+                elems_diff.status.merge(Status::Different(vec![]));
+            }
+        }
+    }
+
+    for (i2, t2) in right {
+        let (doc, able) = to_doc(alloc, Parens::Unnecessary, t2);
+        elems_diff.right.push((i2, doc));
+        elems_diff.right_able.extend(able);
+        elems_diff.status.merge(Status::Different(vec![]));
+    }
+
+    let ext_diff = ext_to_diff(alloc, ext1, ext2);
+
+    Diff {
+        left: report_text::tuple(
+            alloc,
+            fill_tuple_gaps(alloc, elems_diff.left),
+            ext_diff.left,
+        ),
+        right: report_text::tuple(
+            alloc,
+            fill_tuple_gaps(alloc, elems_diff.right),
+            ext_diff.right,
+        ),
+        status: elems_diff.status,
+        left_able: elems_diff.left_able,
+        right_able: elems_diff.right_able,
+    }
+}
+
 fn same_tag_name_overlap_diff<'b>(
     alloc: &'b RocDocAllocator<'b>,
     field: TagName,
@@ -3249,6 +3459,24 @@ mod report_text {
         }
     }
 
+    pub fn tuple<'b>(
+        alloc: &'b RocDocAllocator<'b>,
+        elems: Vec<RocDocBuilder<'b>>,
+        opt_ext: Option<RocDocBuilder<'b>>,
+    ) -> RocDocBuilder<'b> {
+        let ext_doc = if let Some(t) = opt_ext {
+            t
+        } else {
+            alloc.nil()
+        };
+
+        alloc
+            .text("(")
+            .append(alloc.intersperse(elems, alloc.text(", ")))
+            .append(alloc.text(")"))
+            .append(ext_doc)
+    }
+
     pub fn to_suggestion_record<'b>(
         alloc: &'b RocDocAllocator<'b>,
         f: (Lowercase, RecordField<ErrorType>),
@@ -3523,6 +3751,23 @@ fn type_problem_to_pretty<'b>(
 
             alloc.tip().append(line)
         }
+        (TupleArityMismatch(found, expected), _) => {
+            let (line, difference) = if found < expected {
+                (
+                    "It looks like the tuple has too few elements. I was expecting ",
+                    alloc.text(format!("{} more.", expected - found)),
+                )
+            } else {
+                (
+                    "It looks like the tuple has too many elements. I'm seeing ",
+                    alloc.text(format!("{} extra.", found - expected)),
+                )
+            };
+
+            alloc
+                .tip()
+                .append(alloc.concat([alloc.reflow(line), difference]))
+        }
 
         (BadRigidVar(x, tipe, opt_ability), expectation) => {
             use ErrorType::*;
@@ -3594,6 +3839,7 @@ fn type_problem_to_pretty<'b>(
                 RigidVar(y) | RigidAbleVar(y, _) => bad_double_rigid(x, y),
                 Function(_, _, _) => bad_rigid_var(x, alloc.reflow("a function value")),
                 Record(_, _) => bad_rigid_var(x, alloc.reflow("a record value")),
+                Tuple(_, _) => bad_rigid_var(x, alloc.reflow("a tuple value")),
                 TagUnion(_, _) | RecursiveTagUnion(_, _, _) => {
                     bad_rigid_var(x, alloc.reflow("a tag value"))
                 }
@@ -3978,6 +4224,16 @@ fn pattern_to_doc_help<'b>(
                         alloc.text(" clause)"),
                     ])
                 }
+                RenderAs::Tuple => {
+                    let arg_docs = args
+                        .into_iter()
+                        .map(|v| pattern_to_doc_help(alloc, v, false));
+
+                    alloc
+                        .text("(")
+                        .append(alloc.intersperse(arg_docs, alloc.reflow(", ")))
+                        .append(")")
+                }
                 RenderAs::Record(field_names) => {
                     let mut arg_docs = Vec::with_capacity(args.len());
 
@@ -3986,7 +4242,7 @@ fn pattern_to_doc_help<'b>(
                             Anything => {
                                 arg_docs.push(alloc.text(label.to_string()));
                             }
-                            Literal(_) | Ctor(_, _, _) => {
+                            Literal(_) | Ctor(_, _, _) | List(_, _) => {
                                 arg_docs.push(
                                     alloc
                                         .text(label.to_string())
@@ -4044,5 +4300,20 @@ fn pattern_to_doc_help<'b>(
                 }
             }
         }
+        List(arity, patterns) => {
+            let mut docs: Vec<_> = patterns
+                .into_iter()
+                .map(|v| pattern_to_doc_help(alloc, v, false))
+                .collect();
+
+            if let ListArity::Slice(before, _) = arity {
+                docs.insert(before, alloc.text(".."));
+            }
+
+            alloc
+                .text("[")
+                .append(alloc.intersperse(docs, alloc.reflow(", ")))
+                .append("]")
+        }
     }
 }
@@ -1,7 +1,8 @@
 use roc_module::ident::Ident;
 use roc_module::ident::{Lowercase, ModuleName, TagName, Uppercase};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
-use roc_region::all::LineColumnRegion;
+use roc_region::all::{LineColumn, LineColumnRegion};
+use serde_json::json;
 use std::fmt;
 use std::path::{Path, PathBuf};
 use ven_pretty::{BoxAllocator, DocAllocator, DocBuilder, Render, RenderAnnotated};
@@ -115,6 +116,8 @@ pub enum Severity {
 pub enum RenderTarget {
     ColorTerminal,
     Generic,
+    /// One JSON object per report, on its own line. See [`Diagnostic::to_json`].
+    Json,
 }
 
 /// A textual report.
@@ -129,18 +132,57 @@ impl<'b> Report<'b> {
     pub fn render(
         self,
         target: RenderTarget,
-        buf: &'b mut String,
+        buf: &mut String,
+        alloc: &'b RocDocAllocator<'b>,
+        palette: &'b Palette,
+    ) {
+        self.render_at(target, None, buf, alloc, palette)
+    }
+
+    /// Like `render`, but JSON output will also say where in the file the problem is.
+    pub fn render_at(
+        self,
+        target: RenderTarget,
+        region: Option<LineColumnRegion>,
+        buf: &mut String,
         alloc: &'b RocDocAllocator<'b>,
         palette: &'b Palette,
     ) {
         match target {
             RenderTarget::Generic => self.render_ci(buf, alloc),
             RenderTarget::ColorTerminal => self.render_color_terminal(buf, alloc, palette),
+            RenderTarget::Json => self.render_json(region, buf),
+        }
+    }
+
+    /// Render as a single line of JSON, for tools that consume our diagnostics.
+    pub fn render_json(self, region: Option<LineColumnRegion>, buf: &mut String) {
+        buf.push_str(&self.into_diagnostic(region).to_json().to_string());
+        buf.push('\n');
+    }
+
+    /// The structured form of this report. The document is rendered without
+    /// colors or a header; the title and filename are kept separately.
+    pub fn into_diagnostic(self, region: Option<LineColumnRegion>) -> Diagnostic {
+        let err_msg = "<buffer is not a utf-8 encoded string>";
+        let mut message = String::new();
+
+        self.doc
+            .1
+            .render_raw(70, &mut CiWrite::new(&mut message))
+            .expect(err_msg);
+
+        Diagnostic {
+            severity: self.severity,
+            title: self.title,
+            filename: self.filename,
+            region,
+            message: message.trim().to_string(),
         }
     }
 
     /// Render to CI console output, where no colors are available.
-    pub fn render_ci(self, buf: &'b mut String, alloc: &'b RocDocAllocator<'b>) {
+    pub fn render_ci(self, buf: &mut String, alloc: &'b RocDocAllocator<'b>) {
         let err_msg = "<buffer is not a utf-8 encoded string>";
 
         self.pretty(alloc)
@@ -183,6 +225,49 @@ impl<'b> Report<'b> {
     }
 }
 
+/// A report in structured form, for editors, CI bots and other tools.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub title: String,
+    pub filename: PathBuf,
+    /// Where the problem is, if the report is about a specific part of the file
+    pub region: Option<LineColumnRegion>,
+    /// The body of the report, rendered as plain text
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Lines and columns are 1-based here, like in the reports we print, e.g.
+    ///
+    /// ```json
+    /// {"severity":"error","title":"TYPE MISMATCH","path":"main.roc",
+    ///  "region":{"start":{"line":3,"column":5},"end":{"line":3,"column":6}},"message":"..."}
+    /// ```
+    pub fn to_json(&self) -> serde_json::Value {
+        fn position(line_column: LineColumn) -> serde_json::Value {
+            json!({ "line": line_column.line + 1, "column": line_column.column + 1 })
+        }
+
+        let severity = match self.severity {
+            Severity::RuntimeError => "error",
+            Severity::Warning => "warning",
+        };
+
+        let region = self
+            .region
+            .map(|region| json!({ "start": position(region.start), "end": position(region.end) }));
+
+        json!({
+            "severity": severity,
+            "title": self.title,
+            "path": self.filename.to_string_lossy(),
+            "region": region,
+            "message": self.message,
+        })
+    }
+}
+
 /// This struct is a combination of several things
 /// 1. A set of StyleCodes suitable for the environment we're running in (web or terminal)
 /// 2. A set of colors we decided to use
@@ -446,6 +531,11 @@ impl<'a> RocDocAllocator<'a> {
             .annotate(Annotation::RecordField)
     }
 
+    pub fn tuple_elem(&'a self, index: usize) -> DocBuilder<'a, Self, Annotation> {
+        self.text(format!(".{}", index))
+            .annotate(Annotation::RecordField)
+    }
+
     pub fn module(&'a self, module_id: ModuleId) -> DocBuilder<'a, Self, Annotation> {
         let name = self.interns.module_name(module_id);
         let name = if name.is_empty() {
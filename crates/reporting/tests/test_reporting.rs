@@ -88,6 +88,7 @@ mod test_reporting {
                 render: RenderTarget::Generic,
                 threading: Threading::Single,
                 exec_mode: ExecutionMode::Check,
+                ..Default::default()
             };
             let result =
                 roc_load::load_and_typecheck(arena, full_file_path, exposed_types, load_config);
@@ -107,6 +108,7 @@ mod test_reporting {
     ) -> Result<
         (
             String,
+            Vec<String>,
             Vec<TypeError>,
             Vec<roc_problem::can::Problem>,
             ModuleId,
@@ -117,16 +119,25 @@ mod test_reporting {
         let (module_src, result) = run_load_and_infer(subdir, arena, expr_src);
         let LoadedModule {
             module_id: home,
+            mut parse_problems,
             mut can_problems,
             mut type_problems,
             interns,
             ..
         } = result?;
 
+        let parse_problems = parse_problems.remove(&home).unwrap_or_default();
         let can_problems = can_problems.remove(&home).unwrap_or_default();
         let type_problems = type_problems.remove(&home).unwrap_or_default();
 
-        Ok((module_src, type_problems, can_problems, home, interns))
+        Ok((
+            module_src,
+            parse_problems,
+            type_problems,
+            can_problems,
+            home,
+            interns,
+        ))
     }
 
     fn list_reports_new<F>(subdir: &str, arena: &Bump, src: &str, finalize_render: F) -> String
@@ -141,7 +152,7 @@ mod test_reporting {
 
         match infer_expr_help_new(subdir, arena, src) {
             Err(LoadingProblem::FormattedReport(fail)) => fail,
-            Ok((module_src, type_problems, can_problems, home, interns)) => {
+            Ok((module_src, parse_problems, type_problems, can_problems, home, interns)) => {
                 let lines = LineInfo::new(&module_src);
                 let src_lines: Vec<&str> = module_src.split('\n').collect();
                 let mut reports = Vec::new();
@@ -163,6 +174,13 @@ mod test_reporting {
 
                 let has_reports = !reports.is_empty();
 
+                // syntax errors the parser recovered from come to us already rendered
+                buf.push_str(&parse_problems.join("\n\n"));
+
+                if !parse_problems.is_empty() && has_reports {
+                    buf.push_str("\n\n");
+                }
+
                 let doc = alloc
                     .stack(reports.into_iter().map(|v| v.pretty(&alloc)))
                     .append(if has_reports {
@@ -2098,7 +2116,7 @@ mod test_reporting {
         Ok
         U8
         Box
-        f
+        Eq
     "###
     );
 
@@ -3616,7 +3634,7 @@ mod test_reporting {
         Set
         List
         Dict
-        Result
+        Hash
 
     ── SYNTAX PROBLEM ──────────────────────────────────────── /code/proj/Main.roc ─
 
@@ -4934,6 +4952,96 @@ mod test_reporting {
 
     6│       2 -> 2
              ^
+
+    ── UNSAFE PATTERN ──────────────────────────────────────── /code/proj/Main.roc ─
+
+    This `when` does not cover all the possibilities:
+
+    4│>      when 4 is
+    5│>          5 -> 2
+
+    Other possibilities include:
+
+        _
+
+    I would have to crash if I saw one of those! Add branches for them!
+    "###
+    );
+
+    test_report!(
+        multiple_syntax_errors,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            xs = [1, if 2 then 3, 4]
+
+            point = { x: 1, y: }
+
+            n : Str
+            n = 42
+
+            main = xs
+            "#
+        ),
+        @r###"
+    ── BAD BACKPASSING ARROW ───────────────── tmp/multiple_syntax_errors/Test.roc ─
+
+    I am partway through parsing an expression, but I got stuck here:
+
+    3│  xs = [1, if 2 then 3, 4]
+                               ^
+
+    Looks like you are trying to define a function. 
+
+    ── RECORD PARSE PROBLEM ────────────────── tmp/multiple_syntax_errors/Test.roc ─
+
+    I am partway through parsing an record, but I got stuck here:
+
+    1│  app "test" provides [main] to "./platform"
+    2│
+    3│  xs = [1, if 2 then 3, 4]
+    4│
+    5│  point = { x: 1, y: }
+                        ^
+
+    TODO provide more context.
+
+    ── UNUSED DEFINITION ───────────────────────────────────── /code/proj/Main.roc ─
+
+    `point` is not used anywhere in your code.
+
+    5│  point = { x: 1, y: }
+        ^^^^^
+
+    If you didn't intend on using `point` then remove it so future readers
+    of your code don't wonder why it is there.
+
+    ── UNUSED DEFINITION ───────────────────────────────────── /code/proj/Main.roc ─
+
+    `n` is not used anywhere in your code.
+
+    8│  n = 42
+        ^
+
+    If you didn't intend on using `n` then remove it so future readers of
+    your code don't wonder why it is there.
+
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    Something is off with the body of the `n` definition:
+
+    7│  n : Str
+    8│  n = 42
+            ^^
+
+    The body is a number of type:
+
+        Num a
+
+    But the type annotation on `n` says it should be:
+
+        Str
     "###
     );
 
@@ -5223,8 +5331,16 @@ mod test_reporting {
     4│      """there is no end
                ^
 
-    You could change it to something like """to be or not to be""" or even
-    just """""".
+    A block string ends with another """, which can go on its own line
+    after the last line of text:
+
+        """
+        to be
+        or not to be
+        """
+
+    The indentation shared by its lines and the closing """ is not part of
+    the string.
     "###
     );
 
@@ -5240,8 +5356,41 @@ mod test_reporting {
               ^
 
     Lines in a multi-line string must be indented at least as much as the
-    beginning """. This extra indentation is automatically removed from
-    the string during compilation.
+    line with the beginning """. The indentation they all share is
+    automatically removed from the string during compilation.
+    "###
+    );
+
+    test_report!(
+        multi_no_end_multiline,
+        indoc!(
+            r#"
+            query =
+                """
+                SELECT *
+                FROM users
+
+            query
+            "#
+        ),
+        @r###"
+    ── ENDLESS STRING ──────────────────────── tmp/multi_no_end_multiline/Test.roc ─
+
+    I cannot find the end of this block string:
+
+    5│          """
+                   ^
+
+    A block string ends with another """, which can go on its own line
+    after the last line of text:
+
+        """
+        to be
+        or not to be
+        """
+
+    The indentation shared by its lines and the closing """ is not part of
+    the string.
     "###
     );
 
@@ -5423,7 +5572,7 @@ All branches in an `if` must have the same type!
         part_starts_with_number,
         indoc!(
             r#"
-            foo.100
+            foo.1a
             "#
         ),
         @r###"
@@ -5431,7 +5580,7 @@ All branches in an `if` must have the same type!
 
     I trying to parse a record field access here:
 
-    4│      foo.100
+    4│      foo.1a
                 ^
 
     So I expect to see a lowercase letter next, like .name or .height.
@@ -6068,6 +6217,71 @@ All branches in an `if` must have the same type!
     "###
     );
 
+    test_report!(
+        crash_given_non_string,
+        indoc!(
+            r#"
+            crash {}
+            "#
+        ),
+        @r###"
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    This `crash` message needs to be a Str:
+
+    4│      crash {}
+                  ^^
+
+    Right now it’s a record of type:
+
+        {}
+
+    But I can only `crash` with messages of type Str.
+    "###
+    );
+
+    test_report!(
+        crash_unapplied,
+        indoc!(
+            r#"
+            f = crash
+
+            f
+            "#
+        ),
+        @r###"
+    ── UNAPPLIED CRASH ─────────────────────────────────────── /code/proj/Main.roc ─
+
+    This `crash` doesn't have a message given to it:
+
+    4│      f = crash
+                ^^^^^
+
+    `crash` must be passed a message to crash with at the exact place it's
+    used. `crash` can't be used as a value that's passed around, like
+    functions can be - it must be applied immediately!
+    "###
+    );
+
+    test_report!(
+        crash_overapplied,
+        indoc!(
+            r#"
+            crash "" ""
+            "#
+        ),
+        @r###"
+    ── OVERAPPLIED CRASH ───────────────────────────────────── /code/proj/Main.roc ─
+
+    This `crash` has too many values given to it:
+
+    4│      crash "" ""
+            ^^^^^^^^^^^
+
+    `crash` must be given exactly one message to crash with.
+    "###
+    );
+
     test_report!(
         num_too_general_wildcard,
         indoc!(
@@ -6255,21 +6469,55 @@ All branches in an `if` must have the same type!
         @r###"
     ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
 
+    This 1st argument to == has an unexpected type:
+
+    5│      f = \x -> \y -> if x == y then x else y
+                               ^
+
+    This `x` value is a:
+
+        a
+
+    But == needs its 1st argument to be:
+
+        a | a has Eq
+
+    Tip: The type annotation uses the type variable `a` to say that this
+    definition can produce any type of value. But in the body I see that
+    it will only produce an instance of the ability `Eq` of a single
+    specific type. Maybe change the type annotation to be more specific?
+    Maybe change the code to be more general?
+    "###
+    );
+
+    test_report!(
+        inference_var_conflict_in_rigid_links_with_ability,
+        indoc!(
+            r#"
+            f : a -> (_ -> b) | a has Eq
+            f = \x -> \y -> if x == y then x else y
+            f
+            "#
+        ),
+        // TODO: We should tell the user that we inferred `_` as `a`
+        @r###"
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
     Something is off with the body of the `f` definition:
 
-    4│      f : a -> (_ -> b)
+    4│      f : a -> (_ -> b) | a has Eq
     5│      f = \x -> \y -> if x == y then x else y
                       ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
 
     The body is an anonymous function of type:
 
-        a -> a
+        a -> a | a has Eq
 
     But the type annotation on `f` says it should be:
 
-        a -> b
+        a -> b | a has Eq
 
-    Tip: Your type annotation uses `a` and `b` as separate type variables.
+    Tip: Your type annotation uses `b` and `a` as separate type variables.
     Your code seems to be saying they are the same though. Maybe they
     should be the same in your type annotation? Maybe your code uses them
     in a weird way?
@@ -7966,8 +8214,8 @@ All branches in an `if` must have the same type!
         ability_first_demand_not_indented_enough,
         indoc!(
             r#"
-            Eq has
-            eq : a, a -> U64 | a has Eq
+            MEq has
+            eq : a, a -> U64 | a has MEq
 
             1
             "#
@@ -7978,8 +8226,8 @@ All branches in an `if` must have the same type!
     I was partway through parsing an ability definition, but I got stuck
     here:
 
-    4│      Eq has
-    5│      eq : a, a -> U64 | a has Eq
+    4│      MEq has
+    5│      eq : a, a -> U64 | a has MEq
             ^
 
     I suspect this line is not indented enough (by 1 spaces)
@@ -7990,9 +8238,9 @@ All branches in an `if` must have the same type!
         ability_demands_not_indented_with_first,
         indoc!(
             r#"
-            Eq has
-                eq : a, a -> U64 | a has Eq
-                    neq : a, a -> U64 | a has Eq
+            MEq has
+                eq : a, a -> U64 | a has MEq
+                    neq : a, a -> U64 | a has MEq
 
             1
             "#
@@ -8003,8 +8251,8 @@ All branches in an `if` must have the same type!
         I was partway through parsing an ability definition, but I got stuck
         here:
 
-        5│          eq : a, a -> U64 | a has Eq
-        6│              neq : a, a -> U64 | a has Eq
+        5│          eq : a, a -> U64 | a has MEq
+        6│              neq : a, a -> U64 | a has MEq
                         ^
 
         I suspect this line is indented too much (by 4 spaces)"#
@@ -8014,8 +8262,8 @@ All branches in an `if` must have the same type!
         ability_demand_value_has_args,
         indoc!(
             r#"
-                Eq has
-                    eq b c : a, a -> U64 | a has Eq
+                MEq has
+                    eq b c : a, a -> U64 | a has MEq
 
                 1
                 "#
@@ -8026,7 +8274,7 @@ All branches in an `if` must have the same type!
         I was partway through parsing an ability definition, but I got stuck
         here:
 
-        5│          eq b c : a, a -> U64 | a has Eq
+        5│          eq b c : a, a -> U64 | a has MEq
                        ^
 
         I was expecting to see a : annotating the signature of this value
@@ -8037,7 +8285,7 @@ All branches in an `if` must have the same type!
         ability_non_signature_expression,
         indoc!(
             r#"
-            Eq has
+            MEq has
                 123
 
             1
@@ -8049,7 +8297,7 @@ All branches in an `if` must have the same type!
     I was partway through parsing an ability definition, but I got stuck
     here:
 
-    4│      Eq has
+    4│      MEq has
     5│          123
                 ^
 
@@ -8320,23 +8568,23 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [] to "./platform"
 
-            Eq has eq : a, b -> Bool.Bool | a has Eq, b has Eq
+            MEq has eq : a, b -> Bool.Bool | a has MEq, b has MEq
             "#
         ),
         @r#"
         ── ABILITY MEMBER BINDS MULTIPLE VARIABLES ─────────────── /code/proj/Main.roc ─
 
         The definition of the ability member `eq` includes multiple variables
-        bound to the `Eq`` ability:`
+        bound to the `MEq`` ability:`
 
-        3│  Eq has eq : a, b -> Bool.Bool | a has Eq, b has Eq
-                                            ^^^^^^^^^^^^^^^^^^
+        3│  MEq has eq : a, b -> Bool.Bool | a has MEq, b has MEq
+                                             ^^^^^^^^^^^^^^^^^^^^
 
         Ability members can only bind one type variable to their parent
         ability. Otherwise, I wouldn't know what type implements an ability by
         looking at specializations!
 
-        Hint: Did you mean to only bind `a` to `Eq`?
+        Hint: Did you mean to only bind `a` to `MEq`?
         "#
     );
 
@@ -8415,11 +8663,11 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [eq, le] to "./platform"
 
-            Eq has
-                eq : a, a -> Bool | a has Eq
-                le : a, a -> Bool | a has Eq
+            MEq has
+                eq : a, a -> Bool | a has MEq
+                le : a, a -> Bool | a has MEq
 
-            Id := U64 has [Eq {eq}]
+            Id := U64 has [MEq {eq}]
 
             eq = \@Id m, @Id n -> m == n
             "#
@@ -8427,10 +8675,10 @@ All branches in an `if` must have the same type!
         @r###"
     ── INCOMPLETE ABILITY IMPLEMENTATION ───────────────────── /code/proj/Main.roc ─
 
-    This type does not fully implement the `Eq` ability:
+    This type does not fully implement the `MEq` ability:
 
-    7│  Id := U64 has [Eq {eq}]
-                       ^^^^^^^
+    7│  Id := U64 has [MEq {eq}]
+                       ^^^^^^^^
 
     The following necessary members are missing implementations:
 
@@ -8554,10 +8802,10 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [eq] to "./platform"
 
-            Eq has
-                eq : a, a -> Bool | a has Eq
+            MEq has
+                eq : a, a -> Bool | a has MEq
 
-            You := {} has [Eq {eq}]
+            You := {} has [MEq {eq}]
             AndI := {}
 
             eq = \@You {}, @AndI {} -> False
@@ -8962,7 +9210,7 @@ All branches in an `if` must have the same type!
         @r###"
     ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
 
-    This 2nd argument to `isEq` has an unexpected type:
+    This 2nd argument to == has an unexpected type:
 
     9│          Job lst -> lst == ""
                                   ^^
@@ -8971,7 +9219,7 @@ All branches in an `if` must have the same type!
 
         Str
 
-    But `isEq` needs its 2nd argument to be:
+    But == needs its 2nd argument to be:
 
         List [Job ∞] as ∞
     "###
@@ -9179,9 +9427,9 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [A] to "./platform"
 
-            Eq has eq : a, a -> U64 | a has Eq
+            MEq has eq : a, a -> U64 | a has MEq
 
-            A := U8 has [Eq {eq}]
+            A := U8 has [MEq {eq}]
             "#
         ),
         @r###"
@@ -9189,8 +9437,8 @@ All branches in an `if` must have the same type!
 
     An implementation of `eq` could not be found in this scope:
 
-    5│  A := U8 has [Eq {eq}]
-                         ^^
+    5│  A := U8 has [MEq {eq}]
+                          ^^
 
     Tip: consider adding a value of name `eq` in this scope, or using
     another variable that implements this ability member, like
@@ -9198,10 +9446,10 @@ All branches in an `if` must have the same type!
 
     ── INCOMPLETE ABILITY IMPLEMENTATION ───────────────────── /code/proj/Main.roc ─
 
-    This type does not fully implement the `Eq` ability:
+    This type does not fully implement the `MEq` ability:
 
-    5│  A := U8 has [Eq {eq}]
-                     ^^^^^^^
+    5│  A := U8 has [MEq {eq}]
+                     ^^^^^^^^
 
     The following necessary members are missing implementations:
 
@@ -9215,9 +9463,9 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [A, myEq] to "./platform"
 
-            Eq has eq : a, a -> Bool | a has Eq
+            MEq has eq : a, a -> Bool | a has MEq
 
-            A := U8 has [ Eq {eq: aEq} ]
+            A := U8 has [ MEq {eq: aEq} ]
 
             myEq = \m, n -> m == n
             "#
@@ -9227,22 +9475,22 @@ All branches in an `if` must have the same type!
 
     Nothing is named `aEq` in this scope.
 
-    5│  A := U8 has [ Eq {eq: aEq} ]
-                              ^^^
+    5│  A := U8 has [ MEq {eq: aEq} ]
+                               ^^^
 
     Did you mean one of these?
 
         Eq
+        MEq
         myEq
         eq
-        U8
 
     ── INCOMPLETE ABILITY IMPLEMENTATION ───────────────────── /code/proj/Main.roc ─
 
-    This type does not fully implement the `Eq` ability:
+    This type does not fully implement the `MEq` ability:
 
-    5│  A := U8 has [ Eq {eq: aEq} ]
-                      ^^^^^^^^^^^^
+    5│  A := U8 has [ MEq {eq: aEq} ]
+                      ^^^^^^^^^^^^^
 
     The following necessary members are missing implementations:
 
@@ -9256,9 +9504,9 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [A, myEq] to "./platform"
 
-            Eq has eq : a, a -> Bool | a has Eq
+            MEq has eq : a, a -> Bool | a has MEq
 
-            A := U8 has [ Eq {eq ? aEq} ]
+            A := U8 has [ MEq {eq ? aEq} ]
 
             myEq = \m, n -> m == n
             "#
@@ -9268,8 +9516,8 @@ All branches in an `if` must have the same type!
 
     Ability implementations cannot be optional:
 
-    5│  A := U8 has [ Eq {eq ? aEq} ]
-                          ^^^^^^^^
+    5│  A := U8 has [ MEq {eq ? aEq} ]
+                           ^^^^^^^^
 
     Custom implementations must be supplied fully.
 
@@ -9277,10 +9525,10 @@ All branches in an `if` must have the same type!
 
     ── INCOMPLETE ABILITY IMPLEMENTATION ───────────────────── /code/proj/Main.roc ─
 
-    This type does not fully implement the `Eq` ability:
+    This type does not fully implement the `MEq` ability:
 
-    5│  A := U8 has [ Eq {eq ? aEq} ]
-                      ^^^^^^^^^^^^^
+    5│  A := U8 has [ MEq {eq ? aEq} ]
+                      ^^^^^^^^^^^^^^
 
     The following necessary members are missing implementations:
 
@@ -9334,9 +9582,9 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [A] to "./platform"
 
-            Eq has eq : a, a -> Bool | a has Eq
+            MEq has eq : a, a -> Bool | a has MEq
 
-            A := U8 has [ Eq {eq : Bool.eq} ]
+            A := U8 has [ MEq {eq : Bool.eq} ]
             "#
         ),
         @r###"
@@ -9344,18 +9592,18 @@ All branches in an `if` must have the same type!
 
     This ability implementation is qualified:
 
-    5│  A := U8 has [ Eq {eq : Bool.eq} ]
-                               ^^^^^^^
+    5│  A := U8 has [ MEq {eq : Bool.eq} ]
+                                ^^^^^^^
 
     Custom implementations must be defined in the local scope, and
     unqualified.
 
     ── INCOMPLETE ABILITY IMPLEMENTATION ───────────────────── /code/proj/Main.roc ─
 
-    This type does not fully implement the `Eq` ability:
+    This type does not fully implement the `MEq` ability:
 
-    5│  A := U8 has [ Eq {eq : Bool.eq} ]
-                      ^^^^^^^^^^^^^^^^^
+    5│  A := U8 has [ MEq {eq : Bool.eq} ]
+                      ^^^^^^^^^^^^^^^^^^
 
     The following necessary members are missing implementations:
 
@@ -9369,9 +9617,9 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [A] to "./platform"
 
-            Eq has eq : a, a -> Bool | a has Eq
+            MEq has eq : a, a -> Bool | a has MEq
 
-            A := U8 has [ Eq {eq : \m, n -> m == n} ]
+            A := U8 has [ MEq {eq : \m, n -> m == n} ]
             "#
         ),
         @r###"
@@ -9379,8 +9627,8 @@ All branches in an `if` must have the same type!
 
     This ability implementation is not an identifier:
 
-    5│  A := U8 has [ Eq {eq : \m, n -> m == n} ]
-                               ^^^^^^^^^^^^^^^
+    5│  A := U8 has [ MEq {eq : \m, n -> m == n} ]
+                                ^^^^^^^^^^^^^^^
 
     Custom ability implementations defined in this position can only be
     unqualified identifiers, not arbitrary expressions.
@@ -9389,10 +9637,10 @@ All branches in an `if` must have the same type!
 
     ── INCOMPLETE ABILITY IMPLEMENTATION ───────────────────── /code/proj/Main.roc ─
 
-    This type does not fully implement the `Eq` ability:
+    This type does not fully implement the `MEq` ability:
 
-    5│  A := U8 has [ Eq {eq : \m, n -> m == n} ]
-                      ^^^^^^^^^^^^^^^^^^^^^^^^^
+    5│  A := U8 has [ MEq {eq : \m, n -> m == n} ]
+                      ^^^^^^^^^^^^^^^^^^^^^^^^^^
 
     The following necessary members are missing implementations:
 
@@ -9406,9 +9654,9 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [A] to "./platform"
 
-            Eq has eq : a, a -> Bool | a has Eq
+            MEq has eq : a, a -> Bool | a has MEq
 
-            A := U8 has [ Eq {eq: eqA, eq: eqA} ]
+            A := U8 has [ MEq {eq: eqA, eq: eqA} ]
 
             eqA = \@A m, @A n -> m == n
             "#
@@ -9418,13 +9666,13 @@ All branches in an `if` must have the same type!
 
     This ability member implementation is duplicate:
 
-    5│  A := U8 has [ Eq {eq: eqA, eq: eqA} ]
-                                   ^^^^^^^
+    5│  A := U8 has [ MEq {eq: eqA, eq: eqA} ]
+                                    ^^^^^^^
 
     The first implementation was defined here:
 
-    5│  A := U8 has [ Eq {eq: eqA, eq: eqA} ]
-                          ^^^^^^^
+    5│  A := U8 has [ MEq {eq: eqA, eq: eqA} ]
+                           ^^^^^^^
 
     Only one custom implementation can be defined for an ability member.
     "###
@@ -9743,7 +9991,7 @@ All branches in an `if` must have the same type!
         @r###"
     ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
 
-    This 2nd argument to `isEq` has an unexpected type:
+    This 2nd argument to == has an unexpected type:
 
     4│      0x80000000000000000000000000000000 == -0x80000000000000000000000000000000
                                                   ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
@@ -9752,7 +10000,7 @@ All branches in an `if` must have the same type!
 
         I128
 
-    But `isEq` needs its 2nd argument to be:
+    But == needs its 2nd argument to be:
 
         U128
     "###
@@ -9768,7 +10016,7 @@ All branches in an `if` must have the same type!
         @r###"
     ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
 
-    This 2nd argument to `isEq` has an unexpected type:
+    This 2nd argument to == has an unexpected type:
 
     4│      170141183460469231731687303715884105728 == -170141183460469231731687303715884105728
                                                        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
@@ -9777,7 +10025,7 @@ All branches in an `if` must have the same type!
 
         I128 or Dec
 
-    But `isEq` needs its 2nd argument to be:
+    But == needs its 2nd argument to be:
 
         U128
     "###
@@ -10326,6 +10574,54 @@ All branches in an `if` must have the same type!
     "###
     );
 
+    test_report!(
+        function_cannot_derive_eq,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main = (\x -> x + 1) == (\x -> x + 1)
+            "#
+        ),
+        @r###"
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    This expression has a type that does not implement the abilities it's expected to:
+
+    3│  main = (\x -> x + 1) == (\x -> x + 1)
+                ^^^^^^^^^^^
+
+    Roc can't generate an implementation of the `Bool.Eq` ability for
+
+        Num a -> Num a
+
+    Note: `Eq` cannot be generated for functions.
+    "###
+    );
+
+    test_report!(
+        opaque_without_eq_cannot_be_compared,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            A := {}
+
+            main = @A {} == @A {}
+            "#
+        ),
+        @r###"
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    This expression has a type that does not implement the abilities it's expected to:
+
+    5│  main = @A {} == @A {}
+               ^^^^^
+
+    The type `A` does not fully implement the ability `Eq`.
+    "###
+    );
+
     test_report!(
         anonymous_function_does_not_use_param,
         indoc!(
@@ -10534,4 +10830,341 @@ All branches in an `if` must have the same type!
      Maybe you wanted to use a `Result`?
      "###
     );
+
+    test_report!(
+        tuple_arity_mismatch,
+        indoc!(
+            r#"
+            pair : (Str, U8)
+            pair = ("a", 1, 2)
+
+            pair
+            "#
+        ),
+        @r###"
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    Something is off with the body of the `pair` definition:
+
+    4│      pair : (Str, U8)
+    5│      pair = ("a", 1, 2)
+                   ^^^^^^^^^^^
+
+    The body is a tuple of type:
+
+        (Str, Num a, Num b)
+
+    But the type annotation on `pair` says it should be:
+
+        (Str, U8)
+
+    Tip: It looks like the tuple has too many elements. I'm seeing
+    1 extra.
+    "###
+    );
+
+    test_report!(
+        tuple_pattern_not_exhaustive,
+        indoc!(
+            r#"
+            x : (Bool, Bool)
+            x = (True, False)
+
+            when x is
+                (True, _) -> 1
+                (False, True) -> 2
+            "#
+        ),
+        @r###"
+    ── UNSAFE PATTERN ──────────────────────────────────────── /code/proj/Main.roc ─
+
+    This `when` does not cover all the possibilities:
+
+    7│>      when x is
+    8│>          (True, _) -> 1
+    9│>          (False, True) -> 2
+
+    Other possibilities include:
+
+        (False, False)
+
+    I would have to crash if I saw one of those! Add branches for them!
+    "###
+    );
+
+    test_report!(
+        list_match_non_exhaustive,
+        indoc!(
+            r#"
+            l : List Str
+            l = []
+
+            when l is
+                [] -> 1
+                [_, _, ..] -> 2
+            "#
+        ),
+        @r###"
+    ── UNSAFE PATTERN ──────────────────────────────────────── /code/proj/Main.roc ─
+
+    This `when` does not cover all the possibilities:
+
+    7│>      when l is
+    8│>          [] -> 1
+    9│>          [_, _, ..] -> 2
+
+    Other possibilities include:
+
+        [_]
+
+    I would have to crash if I saw one of those! Add branches for them!
+    "###
+    );
+
+    test_report!(
+        list_match_redundant,
+        indoc!(
+            r#"
+            l : List Str
+            l = []
+
+            when l is
+                [_, ..] -> 1
+                [] -> 2
+                [_, _] -> 3
+            "#
+        ),
+        @r###"
+    ── REDUNDANT PATTERN ───────────────────────────────────── /code/proj/Main.roc ─
+
+    The 3rd pattern is redundant:
+
+     7│      when l is
+     8│          [_, ..] -> 1
+     9│          [] -> 2
+    10│          [_, _] -> 3
+                 ^^^^^^
+
+    Any value of this shape will be handled by a previous pattern, so this
+    one should be removed.
+    "###
+    );
+
+    test_report!(
+        list_match_multiple_rest,
+        indoc!(
+            r#"
+            l : List Str
+            l = []
+
+            when l is
+                [.., _, ..] -> ""
+            "#
+        ),
+        @r###"
+    ── MULTIPLE LIST REST PATTERNS ─────────────────────────── /code/proj/Main.roc ─
+
+    This list pattern match has multiple rest patterns:
+
+    8│          [.., _, ..] -> ""
+                        ^^
+
+    I only support compiling list patterns with one .. pattern! Can you
+    remove this additional one?
+    "###
+    );
+
+    test_report!(
+        list_match_element_mismatch,
+        indoc!(
+            r#"
+            l : List Str
+            l = []
+
+            when l is
+                [x, 1] -> x
+                _ -> ""
+            "#
+        ),
+        @r###"
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    The branches of this `when` expression don't match the condition:
+
+    7│>      when l is
+    8│           [x, 1] -> x
+    9│           _ -> ""
+
+    This `l` value is a:
+
+        List Str
+
+    But the branch patterns have type:
+
+        List (Num a)
+
+    The branches must be cases of the `when` condition's type!
+
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    The 2nd branch of this `when` does not match all the previous branches:
+
+    7│      when l is
+    8│          [x, 1] -> x
+    9│          _ -> ""
+                     ^^
+
+    The 2nd branch is a string of type:
+
+        Str
+
+    But all the previous branches have type:
+
+        Num a
+
+    All branches of a `when` must have the same type!
+    "###
+    );
+
+    test_report!(
+        nested_record_update_not_a_record,
+        indoc!(
+            r#"
+            model = { settings: "dark" }
+
+            { model & settings.theme: Dark }
+            "#
+        ),
+        @r###"
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    This is not a record, so it has no fields to update!
+
+    6│      { model & settings.theme: Dark }
+                      ^^^^^^^^^^^^^^^^^^^^
+
+    This `model.settings` value is a:
+
+        Str
+
+    But I need a record!
+    "###
+    );
+
+    test_report!(
+        nested_record_update_missing_field,
+        indoc!(
+            r#"
+            model = { settings: { theme: Light } }
+
+            { model & settings.colour: Dark }
+            "#
+        ),
+        @r###"
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    This `model.settings` record doesn’t have a `colour` field:
+
+    6│      { model & settings.colour: Dark }
+                      ^^^^^^^^^^^^^^^^^^^^^
+
+    There may be a typo. These `model.settings` fields are the most similar:
+
+        {
+            theme : [Light]a,
+        }
+
+    Maybe `colour:` should be `theme:` instead?
+    "###
+    );
+
+    test_report!(
+        nested_record_update_duplicate_field,
+        indoc!(
+            r#"
+            \model -> { model & settings: {}, settings.theme: Dark }
+            "#
+        ),
+        @r###"
+    ── DUPLICATE FIELD NAME ────────────────────────────────── /code/proj/Main.roc ─
+
+    This record defines the `.settings` field twice!
+
+    4│      \model -> { model & settings: {}, settings.theme: Dark }
+                                ^^^^^^^^^^^^  ^^^^^^^^^^^^^^^^^^^^
+
+    In the rest of the program, I will only use the latter definition:
+
+    4│      \model -> { model & settings: {}, settings.theme: Dark }
+                                              ^^^^^^^^^^^^^^^^^^^^
+
+    For clarity, remove the previous `.settings` definitions from this
+    record.
+    "###
+    );
+
+    #[test]
+    fn json_report() {
+        let arena = Bump::new();
+        let (module_src, _, type_problems, _, home, interns) = infer_expr_help_new(
+            "json_report",
+            &arena,
+            indoc!(
+                r#"
+                x : Str
+                x = 1
+
+                x
+                "#
+            ),
+        )
+        .unwrap();
+
+        let lines = LineInfo::new(&module_src);
+        let src_lines: Vec<&str> = module_src.split('\n').collect();
+        let alloc = RocDocAllocator::new(&src_lines, home, &interns);
+
+        let problem = type_problems.into_iter().next().unwrap();
+        let region = problem.region().map(|region| lines.convert_region(region));
+        let report = type_problem(
+            &alloc,
+            &lines,
+            filename_from_string(r"/code/proj/Main.roc"),
+            problem,
+        )
+        .unwrap();
+
+        let mut buf = String::new();
+        report.render_at(
+            RenderTarget::Json,
+            region,
+            &mut buf,
+            &alloc,
+            &DEFAULT_PALETTE,
+        );
+
+        assert!(buf.ends_with('\n'));
+        assert_eq!(buf.lines().count(), 1);
+
+        let json: serde_json::Value = serde_json::from_str(&buf).unwrap();
+
+        assert_eq!(json["severity"], "error");
+        assert_eq!(json["title"], "TYPE MISMATCH");
+        assert_eq!(json["path"], "/code/proj/Main.roc");
+        assert_eq!(
+            json["region"],
+            serde_json::json!({
+                "start": { "line": 5, "column": 9 },
+                "end": { "line": 5, "column": 10 }
+            })
+        );
+
+        let message = json["message"].as_str().unwrap();
+        assert!(
+            message.starts_with("Something is off with the body"),
+            "{}",
+            message
+        );
+        assert!(!message.contains('\u{1b}'));
+    }
 }
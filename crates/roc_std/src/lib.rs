@@ -18,7 +18,7 @@ mod roc_str;
 mod storage;
 
 pub use roc_box::RocBox;
-pub use roc_dict::RocDict;
+pub use roc_dict::{DictHasher, RocDict, RocHash};
 pub use roc_list::RocList;
 pub use roc_set::RocSet;
 pub use roc_str::{InteriorNulError, RocStr};
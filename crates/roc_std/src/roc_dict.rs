@@ -1,55 +1,398 @@
 use crate::roc_list::RocList;
+use crate::roc_str::RocStr;
 use core::{
+    cmp::Ordering,
     fmt::{self, Debug},
-    hash::Hash,
+    hash::{Hash, Hasher},
+    iter::FromIterator,
     mem::{align_of, ManuallyDrop},
 };
 
-#[derive(Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct RocDict<K, V>(RocList<RocDictItem<K, V>>);
+/// A Roc `Dict`. This mirrors the layout of the `Dict` opaque type in `Dict.roc`: a list of
+/// entries in insertion order, plus the hash table's slots, which index into those entries.
+///
+/// Like in Roc, two dicts are equal when they have the same entries, no matter the order they
+/// were inserted in.
+#[derive(Default, Clone)]
+#[repr(C)]
+pub struct RocDict<K, V> {
+    data: RocList<RocDictItem<K, V>>,
+    slots: RocList<usize>,
+}
 
 impl<K, V> RocDict<K, V> {
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.data.len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.data.is_empty()
     }
 
     pub fn with_capacity(capacity: usize) -> Self {
-        Self(RocList::with_capacity(capacity))
+        Self {
+            data: RocList::with_capacity(capacity),
+            slots: RocList::empty(),
+        }
     }
 
     pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
-        self.0.iter().map(|item| (item.key(), item.value()))
+        self.data.iter().map(|item| (item.key(), item.value()))
     }
 
     pub fn iter_keys(&self) -> impl Iterator<Item = &K> {
-        self.0.iter().map(|item| item.key())
+        self.data.iter().map(|item| item.key())
     }
 
     pub fn iter_values(&self) -> impl Iterator<Item = &V> {
-        self.0.iter().map(|item| item.value())
+        self.data.iter().map(|item| item.value())
+    }
+
+    /// The entry with the smallest key that is greater than `after` (or the smallest key of all),
+    /// or `None` if some of the keys can't be compared, like *NaN*.
+    fn next_in_key_order(&self, after: Option<&K>) -> Option<Option<(&K, &V)>>
+    where
+        K: PartialOrd,
+    {
+        let mut next: Option<(&K, &V)> = None;
+
+        for (key, value) in self.iter() {
+            if let Some(after) = after {
+                if key.partial_cmp(after)? != Ordering::Greater {
+                    continue;
+                }
+            }
+
+            match next {
+                Some((next_key, _)) if key.partial_cmp(next_key)? != Ordering::Less => {}
+                _ => next = Some((key, value)),
+            }
+        }
+
+        Some(next)
     }
 }
 
-impl<K: Hash, V> RocDict<K, V> {
-    #[allow(unused)]
-    pub fn from_iter<I: Iterator<Item = (K, V)>>(src: I) -> Self {
-        let mut ret = Self::with_capacity(src.size_hint().0);
+impl<K: PartialEq, V: PartialEq> PartialEq for RocDict<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        // Keys are unique, so the dicts are equal if every entry of one is in the other
+        self.len() == other.len()
+            && self.iter().all(|(key, value)| {
+                other
+                    .iter()
+                    .find(|(other_key, _)| key == *other_key)
+                    .map_or(false, |(_, other_value)| value == other_value)
+            })
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for RocDict<K, V> {}
+
+/// Compares the entries in the order of their keys.
+impl<K: PartialOrd, V: PartialOrd> PartialOrd for RocDict<K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let mut self_after = None;
+        let mut other_after = None;
+
+        loop {
+            let entries = (
+                self.next_in_key_order(self_after)?,
+                other.next_in_key_order(other_after)?,
+            );
+
+            match entries {
+                (None, None) => return Some(Ordering::Equal),
+                (None, Some(_)) => return Some(Ordering::Less),
+                (Some(_), None) => return Some(Ordering::Greater),
+                (Some((key, value)), Some((other_key, other_value))) => {
+                    match key.partial_cmp(other_key)? {
+                        Ordering::Equal => {}
+                        ordering => return Some(ordering),
+                    }
+
+                    match value.partial_cmp(other_value)? {
+                        Ordering::Equal => {}
+                        ordering => return Some(ordering),
+                    }
+
+                    self_after = Some(key);
+                    other_after = Some(other_key);
+                }
+            }
+        }
+    }
+}
+
+impl<K: Ord, V: Ord> Ord for RocDict<K, V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.partial_cmp(other)
+            .expect("keys and values with a total order can always be compared")
+    }
+}
+
+impl<K: Hash, V: Hash> Hash for RocDict<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Hash every entry on its own, and add up the hashes so that their order doesn't matter
+        let mut entries_hash: u64 = 0;
+
+        for item in self.data.iter() {
+            let mut hasher = EntryHasher::default();
+            item.hash(&mut hasher);
+
+            entries_hash = entries_hash.wrapping_add(hasher.finish());
+        }
+
+        self.len().hash(state);
+        entries_hash.hash(state);
+    }
+}
+
+/// FNV-1a, which hashes the entries of a dict one by one (see the [Hash] impl of [RocDict]).
+struct EntryHasher(u64);
+
+impl Default for EntryHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for EntryHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl<K: RocHash + PartialEq, V> RocDict<K, V> {
+    pub fn get(&self, key: &K) -> Option<&V> {
+        match self.find(key) {
+            Slot::Found(index) => self.data.get(index).map(|item| item.value()),
+            Slot::Vacant(_) => None,
+        }
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        matches!(self.find(key), Slot::Found(_))
+    }
 
-        for (key, val) in src {
-            unsafe {
-                ret.insert_unchecked(key, val);
+    /// Like `find` in Dict.roc: probes the slots from the key's home slot, until it reaches the
+    /// key's entry or a vacant slot.
+    fn find(&self, key: &K) -> Slot {
+        let slot_count = self.slots.len();
+
+        if slot_count == 0 {
+            return Slot::Vacant(0);
+        }
+
+        let mut slot_index = home_slot(key, slot_count);
+
+        loop {
+            match self.slots.get(slot_index) {
+                Some(&entry) if entry != 0 => {
+                    if self.data.get(entry - 1).map(|item| item.key()) == Some(key) {
+                        return Slot::Found(entry - 1);
+                    }
+
+                    slot_index = next_slot(slot_index, slot_count);
+                }
+                _ => return Slot::Vacant(slot_index),
             }
         }
+    }
+}
+
+impl<K: RocHash + PartialEq + Clone, V: Clone> RocDict<K, V> {
+    /// Inserts the entry the way `Dict.insert` does, so that Roc can look it up.
+    pub fn insert(&mut self, key: K, value: V) {
+        match self.find(&key) {
+            Slot::Found(index) => {
+                self.data.as_mut_slice()[index] = RocDictItem::new(key, value);
+            }
+            Slot::Vacant(slot_index) => {
+                let new_len = self.data.len() + 1;
+
+                self.data.push(RocDictItem::new(key, value));
+
+                if new_len * 4 > self.slots.len() * 3 {
+                    self.slots = rebuild_slots(&self.data, slot_count_for(new_len));
+                } else {
+                    self.slots.as_mut_slice()[slot_index] = new_len;
+                }
+            }
+        }
+    }
+}
+
+impl<K: RocHash + PartialEq + Clone, V: Clone> FromIterator<(K, V)> for RocDict<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(into: I) -> Self {
+        let src = into.into_iter();
+        let mut ret = Self::with_capacity(src.size_hint().0);
+
+        for (key, value) in src {
+            ret.insert(key, value);
+        }
 
         ret
     }
+}
+
+enum Slot {
+    /// The index of the key's entry in `data`
+    Found(usize),
+    /// The slot the key would be inserted into
+    Vacant(usize),
+}
+
+/// Like `rebuildSlots` in Dict.roc
+fn rebuild_slots<K: RocHash, V>(
+    data: &RocList<RocDictItem<K, V>>,
+    slot_count: usize,
+) -> RocList<usize> {
+    let mut slots: RocList<usize> = core::iter::repeat(0).take(slot_count).collect();
+    let slots_mut = slots.as_mut_slice();
+
+    for (index, item) in data.iter().enumerate() {
+        let mut slot_index = home_slot(item.key(), slot_count);
+
+        while slots_mut[slot_index] != 0 {
+            slot_index = next_slot(slot_index, slot_count);
+        }
+
+        slots_mut[slot_index] = index + 1;
+    }
+
+    slots
+}
+
+/// Like `slotCountFor` in Dict.roc
+fn slot_count_for(len: usize) -> usize {
+    let mut slot_count = 8;
+
+    while len * 4 > slot_count * 3 {
+        slot_count *= 2;
+    }
+
+    slot_count
+}
+
+fn home_slot<K: RocHash>(key: &K, slot_count: usize) -> usize {
+    let mut hasher = DictHasher::default();
+    key.roc_hash(&mut hasher);
+
+    (hasher.finish() & (slot_count as u64 - 1)) as usize
+}
+
+fn next_slot(slot_index: usize, slot_count: usize) -> usize {
+    (slot_index + 1) & (slot_count - 1)
+}
+
+/// A key that hashes the way its Roc counterpart does with the `Hash` ability. [RocDict] hashes
+/// keys with this, rather than with [Hash], to put them in the same slots as Roc would.
+pub trait RocHash {
+    fn roc_hash(&self, hasher: &mut DictHasher);
+}
+
+/// The `LowLevelHasher` that Dict.roc hashes keys with.
+pub struct DictHasher(u64);
+
+impl Default for DictHasher {
+    fn default() -> Self {
+        Self(0x243F_6A88_85A3_08D3)
+    }
+}
+
+impl DictHasher {
+    pub fn add_bytes(&mut self, bytes: &[u8]) {
+        let mut word: u64 = 0;
+
+        for chunk in bytes.chunks(8) {
+            word = chunk
+                .iter()
+                .fold(0, |word, byte| (word << 8) | *byte as u64);
+
+            if chunk.len() == 8 {
+                self.combine(word);
+                word = 0;
+            }
+        }
+
+        // the length goes in the top byte of the trailing word, like in Roc
+        let length_byte = (bytes.len() as u64).wrapping_shl(56);
+
+        self.combine(word | length_byte);
+    }
+
+    pub fn add_u64(&mut self, n: u64) {
+        self.combine(n);
+    }
+
+    pub fn add_u128(&mut self, n: u128) {
+        self.add_u64(n as u64);
+        self.add_u64((n >> 64) as u64);
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn combine(&mut self, word: u64) {
+        let x = (self.0 ^ word).wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        // the splitmix64 finalizer
+        let a = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        let b = (a ^ (a >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+        self.0 = b ^ (b >> 31);
+    }
+}
+
+macro_rules! roc_hash_as_u64 {
+    ($($int:ty => $unsigned:ty),*) => {
+        $(
+            impl RocHash for $int {
+                fn roc_hash(&self, hasher: &mut DictHasher) {
+                    hasher.add_u64(*self as $unsigned as u64);
+                }
+            }
+        )*
+    };
+}
+
+// Roc widens every integer up to 64 bits to a U64 (and hashes a Nat as a U64)
+roc_hash_as_u64!(
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64, usize => usize,
+    i8 => u8, i16 => u16, i32 => u32, i64 => u64
+);
+
+impl RocHash for u128 {
+    fn roc_hash(&self, hasher: &mut DictHasher) {
+        hasher.add_u128(*self);
+    }
+}
+
+impl RocHash for i128 {
+    fn roc_hash(&self, hasher: &mut DictHasher) {
+        hasher.add_u128(*self as u128);
+    }
+}
 
-    unsafe fn insert_unchecked(&mut self, _key: K, _val: V) {
-        todo!();
+impl RocHash for RocStr {
+    fn roc_hash(&self, hasher: &mut DictHasher) {
+        hasher.add_bytes(self.as_str().as_bytes());
+    }
+}
+
+impl<T: RocHash> RocHash for RocList<T> {
+    fn roc_hash(&self, hasher: &mut DictHasher) {
+        for elem in self.iter() {
+            elem.roc_hash(hasher);
+        }
     }
 }
 
@@ -76,7 +419,7 @@ impl<'a, K, V> Iterator for IntoIter<'a, K, V> {
     fn next(&mut self) -> Option<Self::Item> {
         let item = self
             .items
-            .0
+            .data
             .get(self.index)
             .map(|item| (item.key(), item.value()));
 
@@ -86,7 +429,7 @@ impl<'a, K, V> Iterator for IntoIter<'a, K, V> {
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = self.items.0.len() - self.index;
+        let remaining = self.items.data.len() - self.index;
 
         (remaining, Some(remaining))
     }
@@ -123,6 +466,18 @@ union RocDictItem<K, V> {
 }
 
 impl<K, V> RocDictItem<K, V> {
+    fn new(key: K, value: V) -> Self {
+        if align_of::<K>() >= align_of::<V>() {
+            Self {
+                key_first: ManuallyDrop::new(KeyFirst { key, value }),
+            }
+        } else {
+            Self {
+                value_first: ManuallyDrop::new(ValueFirst { value, key }),
+            }
+        }
+    }
+
     fn key(&self) -> &K {
         if align_of::<K>() >= align_of::<V>() {
             unsafe { &self.key_first.key }
@@ -150,33 +505,20 @@ impl<K, V> Drop for RocDictItem<K, V> {
     }
 }
 
-impl<K: PartialEq, V: PartialEq> PartialEq for RocDictItem<K, V> {
-    fn eq(&self, other: &Self) -> bool {
-        self.key() == other.key() && self.value() == other.value()
-    }
-}
-
-impl<K: PartialOrd, V: PartialOrd> PartialOrd for RocDictItem<K, V> {
-    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
-        self.key().partial_cmp(other.key()).map(|key_cmp| {
-            match self.value().partial_cmp(other.value()) {
-                Some(value_cmp) => key_cmp.then(value_cmp),
-                None => key_cmp,
-            }
-        })
+impl<K: Clone, V: Clone> Clone for RocDictItem<K, V> {
+    fn clone(&self) -> Self {
+        Self::new(self.key().clone(), self.value().clone())
     }
 }
 
-impl<K: Ord, V: Ord> Ord for RocDictItem<K, V> {
-    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
-        self.key()
-            .cmp(other.key())
-            .then(self.value().cmp(other.value()))
+impl<K: PartialEq, V: PartialEq> PartialEq for RocDictItem<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key() && self.value() == other.value()
     }
 }
 
 impl<K: Hash, V: Hash> Hash for RocDictItem<K, V> {
-    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    fn hash<H: Hasher>(&self, state: &mut H) {
         self.key().hash(state);
         self.value().hash(state);
     }
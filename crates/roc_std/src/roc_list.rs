@@ -104,15 +104,7 @@ impl<T> RocList<T> {
     }
 
     pub fn get(&self, index: usize) -> Option<&T> {
-        if self.len() <= index {
-            return None;
-        }
-
-        let elements = self.elements?;
-        let element_ptr = unsafe { elements.as_ptr().add(index) };
-
-        // Return the element.
-        Some(unsafe { ManuallyDrop::into_inner(element_ptr.cast::<ManuallyDrop<&T>>().read()) })
+        self.as_slice().get(index)
     }
 
     /// Note that there is no way to convert directly to a Vec.
@@ -251,6 +243,44 @@ where
 
         self.capacity = self.length
     }
+
+    /// The elements as a mutable slice. If other lists share them, they are copied first.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.make_unique();
+
+        match self.elements {
+            Some(elements) => unsafe {
+                &mut *ptr::slice_from_raw_parts_mut(elements.as_ptr().cast::<T>(), self.length)
+            },
+            None => &mut [],
+        }
+    }
+
+    /// Appends an element. If other lists share the elements, they are copied first.
+    pub fn push(&mut self, value: T) {
+        self.make_unique();
+
+        if self.length == self.capacity {
+            self.reserve(self.capacity.max(1));
+        }
+
+        let elements = self.elements.unwrap().as_ptr();
+
+        unsafe {
+            elements.add(self.length).write(ManuallyDrop::new(value));
+        }
+
+        self.length += 1;
+    }
+
+    fn make_unique(&mut self) {
+        let is_shared = self.storage().map_or(false, |storage| !storage.is_unique());
+
+        if is_shared {
+            // dropping the shared list decrements its reference count
+            *self = self.iter().cloned().collect();
+        }
+    }
 }
 
 impl<T> RocList<T> {
@@ -278,6 +308,8 @@ impl<T> RocList<T> {
 
                         if new_alloc == old_alloc {
                             // We successfully reallocated in-place; we're done!
+                            self.capacity = new_len;
+
                             return;
                         } else {
                             // We got back a different allocation; copy the existing elements
@@ -1,7 +1,7 @@
-use crate::roc_dict::RocDict;
+use crate::roc_dict::{RocDict, RocHash};
 use core::{
     fmt::{self, Debug},
-    hash::Hash,
+    iter::FromIterator,
 };
 
 #[derive(Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -27,10 +27,22 @@ impl<T> RocSet<T> {
     }
 }
 
-impl<T: Hash> RocSet<T> {
-    #[allow(unused)]
-    pub fn from_iter<I: Iterator<Item = T>>(src: I) -> Self {
-        Self(RocDict::from_iter(src.map(|elem| (elem, ()))))
+impl<T: RocHash + PartialEq> RocSet<T> {
+    pub fn contains(&self, elem: &T) -> bool {
+        self.0.contains_key(elem)
+    }
+}
+
+impl<T: RocHash + PartialEq + Clone> RocSet<T> {
+    /// Inserts the element the way `Set.insert` does, so that Roc can look it up.
+    pub fn insert(&mut self, elem: T) {
+        self.0.insert(elem, ());
+    }
+}
+
+impl<T: RocHash + PartialEq + Clone> FromIterator<T> for RocSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(into: I) -> Self {
+        Self(into.into_iter().map(|elem| (elem, ())).collect())
     }
 }
 
@@ -59,7 +59,7 @@ pub unsafe extern "C" fn roc_memset(dst: *mut c_void, c: i32, n: usize) -> *mut
 
 #[cfg(test)]
 mod test_roc_std {
-    use roc_std::{RocBox, RocDec, RocList, RocResult, RocStr};
+    use roc_std::{RocBox, RocDec, RocDict, RocList, RocResult, RocStr};
 
     fn roc_str_byte_representation(string: &RocStr) -> [u8; RocStr::SIZE] {
         unsafe { core::mem::transmute_copy(string) }
@@ -296,6 +296,64 @@ mod test_roc_std {
         let example = RocDec::from_str("1234.5678").unwrap();
         assert_eq!(format!("{}", example), "1234.5678");
     }
+
+    #[test]
+    fn roc_dict_with_capacity_eq_default() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let hash = |dict: &RocDict<u8, u8>| {
+            let mut hasher = DefaultHasher::new();
+            dict.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let with_capacity = RocDict::with_capacity(4);
+        let empty = RocDict::default();
+
+        assert_eq!(with_capacity, empty);
+        assert_eq!(hash(&with_capacity), hash(&empty));
+    }
+
+    #[test]
+    fn roc_dict_insert_and_get() {
+        let mut dict = RocDict::default();
+
+        // enough entries to grow the slots a few times
+        for n in 0..100u64 {
+            dict.insert(RocStr::from(format!("key {}", n).as_str()), n);
+        }
+
+        dict.insert(RocStr::from("key 7"), 700);
+
+        assert_eq!(dict.len(), 100);
+        assert_eq!(dict.get(&RocStr::from("key 7")), Some(&700));
+        assert_eq!(dict.get(&RocStr::from("key 99")), Some(&99));
+        assert_eq!(dict.get(&RocStr::from("key 100")), None);
+
+        // like in Roc, replacing a value keeps the entry where it was
+        let keys: Vec<_> = dict.iter_keys().take(9).map(RocStr::as_str).collect();
+        assert_eq!(
+            keys,
+            ["key 0", "key 1", "key 2", "key 3", "key 4", "key 5", "key 6", "key 7", "key 8"]
+        );
+    }
+
+    #[test]
+    fn roc_dict_from_iter_keeps_the_last_value() {
+        let dict: RocDict<u8, RocList<u8>> = [(1, RocList::from([1])), (2, RocList::empty())]
+            .into_iter()
+            .chain([(1, RocList::from([1, 1]))])
+            .collect();
+
+        let shared = dict.clone();
+        let mut updated = dict;
+        updated.insert(2, RocList::from([2]));
+
+        assert_eq!(shared.get(&1), Some(&RocList::from([1, 1])));
+        assert_eq!(shared.get(&2), Some(&RocList::empty()));
+        assert_eq!(updated.get(&2), Some(&RocList::from([2])));
+    }
 }
 
 #[cfg(test)]
@@ -59,15 +59,16 @@ pub unsafe extern "C" fn roc_dealloc(c_ptr: *mut c_void, _alignment: u32) {
 
 #[no_mangle]
 pub unsafe extern "C" fn roc_panic(c_ptr: *mut c_void, tag_id: u32) {
+    let slice = CStr::from_ptr(c_ptr as *const c_char);
+    let string = slice.to_str().unwrap();
+
     match tag_id {
-        0 => {
-            let slice = CStr::from_ptr(c_ptr as *const c_char);
-            let string = slice.to_str().unwrap();
-            eprintln!("Roc hit a panic: {}", string);
-            std::process::exit(1);
-        }
-        _ => todo!(),
+        0 => eprintln!("Roc hit a panic: {}", string),
+        1 => eprintln!("Roc crashed with: {}", string),
+        _ => unreachable!("unknown panic tag {}", tag_id),
     }
+
+    std::process::exit(1);
 }
 
 #[no_mangle]
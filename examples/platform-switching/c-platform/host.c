@@ -1,8 +1,10 @@
 #include <errno.h>
+#include <signal.h>
 #include <stdbool.h>
 #include <stdio.h>
 #include <stdlib.h>
 #include <string.h>
+#include <sys/mman.h>
 #include <unistd.h>
 
 void* roc_alloc(size_t size, unsigned int alignment) { return malloc(size); }
@@ -27,6 +29,19 @@ void* roc_memcpy(void* dest, const void* src, size_t n) {
 
 void* roc_memset(void* str, int c, size_t n) { return memset(str, c, n); }
 
+int roc_getppid() { return getppid(); }
+
+int roc_shm_open(const char* name, int oflag, unsigned int mode) {
+  return shm_open(name, oflag, mode);
+}
+
+void* roc_mmap(void* addr, size_t length, int prot, int flags, int fd,
+               off_t offset) {
+  return mmap(addr, length, prot, flags, fd, offset);
+}
+
+int roc_send_signal(int pid, int sig) { return kill(pid, sig); }
+
 struct RocStr {
   char* bytes;
   size_t len;
@@ -54,6 +69,19 @@ size_t roc_str_len(struct RocStr str) {
   }
 }
 
+// `roc dev` has rendered the location and the value of a `dbg` as text
+void roc_dbg(struct RocStr* location, struct RocStr* value) {
+  char* location_bytes =
+      is_small_str(*location) ? (char*)location : location->bytes;
+  char* value_bytes = is_small_str(*value) ? (char*)value : value->bytes;
+
+  // print what came before the `dbg` first
+  fflush(stdout);
+
+  fprintf(stderr, "[%.*s] %.*s\n", (int)roc_str_len(*location), location_bytes,
+          (int)roc_str_len(*value), value_bytes);
+}
+
 extern void roc__mainForHost_1_exposed_generic(struct RocStr *string);
 
 int main() {
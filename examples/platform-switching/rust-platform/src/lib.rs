@@ -33,15 +33,16 @@ pub unsafe extern "C" fn roc_dealloc(c_ptr: *mut c_void, _alignment: u32) {
 
 #[no_mangle]
 pub unsafe extern "C" fn roc_panic(c_ptr: *mut c_void, tag_id: u32) {
+    let slice = CStr::from_ptr(c_ptr as *const c_char);
+    let string = slice.to_str().unwrap();
+
     match tag_id {
-        0 => {
-            let slice = CStr::from_ptr(c_ptr as *const c_char);
-            let string = slice.to_str().unwrap();
-            eprintln!("Roc hit a panic: {}", string);
-            std::process::exit(1);
-        }
-        _ => todo!(),
+        0 => eprintln!("Roc hit a panic: {}", string),
+        1 => eprintln!("Roc crashed with: {}", string),
+        _ => unreachable!("unknown panic tag {}", tag_id),
     }
+
+    std::process::exit(1);
 }
 
 #[no_mangle]
@@ -54,6 +55,52 @@ pub unsafe extern "C" fn roc_memset(dst: *mut c_void, c: i32, n: usize) -> *mut
     libc::memset(dst, c, n)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn roc_dbg(location: &RocStr, value: &RocStr) {
+    use std::io::Write;
+
+    // `roc dev` has rendered the location and the value of a `dbg` as text;
+    // print what came before the `dbg` first
+    let _ = std::io::stdout().flush();
+
+    eprintln!("[{}] {}", location.as_str(), value.as_str());
+}
+
+#[cfg(unix)]
+#[no_mangle]
+pub unsafe extern "C" fn roc_getppid() -> libc::pid_t {
+    libc::getppid()
+}
+
+#[cfg(unix)]
+#[no_mangle]
+pub unsafe extern "C" fn roc_shm_open(
+    name: *const libc::c_char,
+    oflag: libc::c_int,
+    mode: libc::c_uint,
+) -> libc::c_int {
+    libc::shm_open(name, oflag, mode)
+}
+
+#[cfg(unix)]
+#[no_mangle]
+pub unsafe extern "C" fn roc_mmap(
+    addr: *mut c_void,
+    len: libc::size_t,
+    prot: libc::c_int,
+    flags: libc::c_int,
+    fd: libc::c_int,
+    offset: libc::off_t,
+) -> *mut c_void {
+    libc::mmap(addr, len, prot, flags, fd, offset)
+}
+
+#[cfg(unix)]
+#[no_mangle]
+pub unsafe extern "C" fn roc_send_signal(pid: libc::pid_t, sig: libc::c_int) -> libc::c_int {
+    libc::kill(pid, sig)
+}
+
 #[no_mangle]
 pub extern "C" fn rust_main() -> i32 {
     unsafe {
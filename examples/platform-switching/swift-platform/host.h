@@ -1,4 +1,10 @@
 #include <stdlib.h>
+#include <sys/mman.h>
+
+// shm_open takes a variable number of arguments, so Swift cannot call it directly
+static inline int shmOpen(const char* name, int oflag, unsigned int mode) {
+    return shm_open(name, oflag, mode);
+}
 
 struct RocStr {
     char* bytes;
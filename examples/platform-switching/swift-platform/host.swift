@@ -21,6 +21,36 @@ func rocRealloc(ptr: UInt, _oldSize: Int, newSize: Int, _alignment: UInt) -> UIn
     return UInt(bitPattern: ptr)
 }
 
+@_cdecl("roc_dbg")
+func rocDbg(location: UnsafePointer<RocStr>, value: UnsafePointer<RocStr>) {
+    // `roc dev` has rendered the location and the value of a `dbg` as text;
+    // print what came before the `dbg` first
+    fflush(stdout)
+
+    let line = "[\(getSwiftString(rocStr: location.pointee))] \(getSwiftString(rocStr: value.pointee))\n"
+    FileHandle.standardError.write(line.data(using: .utf8)!)
+}
+
+@_cdecl("roc_getppid")
+func rocGetppid() -> Int32 {
+    return getppid()
+}
+
+@_cdecl("roc_shm_open")
+func rocShmOpen(name: UnsafePointer<CChar>, oflag: Int32, mode: UInt32) -> Int32 {
+    return shmOpen(name, oflag, mode)
+}
+
+@_cdecl("roc_mmap")
+func rocMmap(addr: UnsafeMutableRawPointer?, length: Int, prot: Int32, flags: Int32, fd: Int32, offset: Int64) -> UnsafeMutableRawPointer? {
+    return mmap(addr, length, prot, flags, fd, off_t(offset))
+}
+
+@_cdecl("roc_send_signal")
+func rocSendSignal(pid: Int32, sig: Int32) -> Int32 {
+    return kill(pid, sig)
+}
+
 func isSmallString(rocStr: RocStr) -> Bool {
     return rocStr.capacity < 0
 }
@@ -75,6 +75,43 @@ export fn roc_memset(dst: [*]u8, value: i32, size: usize) callconv(.C) void {
     return memset(dst, value, size);
 }
 
+export fn roc_dbg(location: *const RocStr, value: *const RocStr) callconv(.C) void {
+    // stdout is not buffered, so everything that came before the `dbg` has been printed already
+    const stderr = std.io.getStdErr().writer();
+
+    stderr.print("[{s}] {s}\n", .{ location.asSlice(), value.asSlice() }) catch unreachable;
+}
+
+extern fn getppid() c_int;
+extern fn shm_open(name: [*:0]const u8, oflag: c_int, ...) c_int;
+extern fn mmap(addr: ?*anyopaque, length: usize, prot: c_int, flags: c_int, fd: c_int, offset: i64) *anyopaque;
+extern fn kill(pid: c_int, sig: c_int) c_int;
+
+comptime {
+    if (builtin.os.tag == .macos or builtin.os.tag == .linux) {
+        @export(roc_getppid, .{ .name = "roc_getppid", .linkage = .Strong });
+        @export(roc_shm_open, .{ .name = "roc_shm_open", .linkage = .Strong });
+        @export(roc_mmap, .{ .name = "roc_mmap", .linkage = .Strong });
+        @export(roc_send_signal, .{ .name = "roc_send_signal", .linkage = .Strong });
+    }
+}
+
+fn roc_getppid() callconv(.C) c_int {
+    return getppid();
+}
+
+fn roc_shm_open(name: [*:0]const u8, oflag: c_int, mode: c_uint) callconv(.C) c_int {
+    return shm_open(name, oflag, mode);
+}
+
+fn roc_mmap(addr: ?*anyopaque, length: usize, prot: c_int, flags: c_int, fd: c_int, offset: i64) callconv(.C) *anyopaque {
+    return mmap(addr, length, prot, flags, fd, offset);
+}
+
+fn roc_send_signal(pid: c_int, sig: c_int) callconv(.C) c_int {
+    return kill(pid, sig);
+}
+
 const mem = std.mem;
 const Allocator = mem.Allocator;
 